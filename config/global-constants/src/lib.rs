@@ -10,6 +10,7 @@
 
 /// Definitions of global cryptographic keys (e.g., as held in secure storage)
 pub const CONSENSUS_KEY: &str = "consensus";
+pub const BLS_CONSENSUS_KEY: &str = "bls_consensus";
 pub const EXECUTION_KEY: &str = "execution";
 pub const FULLNODE_NETWORK_KEY: &str = "fullnode_network";
 pub const MANGO_ROOT_KEY: &str = "mango_root";
@@ -8,7 +8,7 @@ use diem_global_constants::{
 };
 use diem_secure_storage::{KVStorage, Namespaced};
 use mango_crypto::ed25519::Ed25519PublicKey;
-use mango_management::constants::{self, VALIDATOR_CONFIG, VALIDATOR_OPERATOR};
+use mango_management::constants::{self, VALIDATOR_BLS_KEY, VALIDATOR_CONFIG, VALIDATOR_OPERATOR};
 use mango_types::{
     chain_id::ChainId,
     on_chain_config::VMPublishingOption,
@@ -147,11 +147,21 @@ impl<S: KVStorage> GenesisBuilder<S> {
             let consensus_pubkey = bcs::from_bytes(&validator_config.args()[1])?;
             let network_address = bcs::from_bytes(&validator_config.args()[2])?;
             let full_node_network_address = bcs::from_bytes(&validator_config.args()[3])?;
+            let (bls_consensus_pubkey, bls_proof_of_possession) =
+                match self.validator_bls_key(&operator)? {
+                    Some(bls_key) => (
+                        bcs::from_bytes(&bls_key.args()[1])?,
+                        bcs::from_bytes(&bls_key.args()[2])?,
+                    ),
+                    None => (vec![], vec![]),
+                };
             validators.push(Validator {
                 address,
                 name,
                 auth_key,
                 consensus_pubkey,
+                bls_consensus_pubkey,
+                bls_proof_of_possession,
                 operator_address,
                 operator_name,
                 operator_auth_key,
@@ -194,6 +204,36 @@ impl<S: KVStorage> GenesisBuilder<S> {
             .with_namespace(operator)
             .get::<Transaction>(VALIDATOR_CONFIG)
             .map(|r| r.value)?;
+        Self::script_function(txn).ok_or_else(|| anyhow::anyhow!("Invalid Validator Config"))
+    }
+
+    pub fn set_validator_bls_key(
+        &mut self,
+        operator: &str,
+        validator_bls_key_transaction: &Transaction,
+    ) -> Result<()> {
+        self.with_namespace_mut(operator)
+            .set(VALIDATOR_BLS_KEY, validator_bls_key_transaction)
+            .map_err(Into::into)
+    }
+
+    /// Returns the operator's BLS key registration, if one was uploaded. Validators without one
+    /// only sign with their Ed25519 key until they register a BLS key on-chain.
+    pub fn validator_bls_key(&self, operator: &str) -> Result<Option<ScriptFunction>> {
+        let txn = match self
+            .with_namespace(operator)
+            .get::<Transaction>(VALIDATOR_BLS_KEY)
+        {
+            Ok(response) => response.value,
+            Err(diem_secure_storage::Error::KeyNotSet(_)) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        Self::script_function(txn)
+            .map(Some)
+            .ok_or_else(|| anyhow::anyhow!("Invalid Validator BLS Key"))
+    }
+
+    fn script_function(txn: Transaction) -> Option<ScriptFunction> {
         if let Transaction::UserTransaction(txn) = txn {
             Some(txn)
         } else {
@@ -208,7 +248,6 @@ impl<S: KVStorage> GenesisBuilder<S> {
                 None
             }
         })
-        .ok_or_else(|| anyhow::anyhow!("Invalid Validator Config"))
     }
 
     pub fn build(
@@ -133,7 +133,8 @@ impl FullnodeConfig {
             .find(|config| config.network_id == NetworkId::Public)
             .expect("VFN should have a public network");
         fullnode_public_network.identity = public_network.identity;
-        fullnode_public_network.listen_address = public_network.listen_address;
+        fullnode_public_network.listen_addresses = public_network.listen_addresses;
+        fullnode_public_network.advertised_addresses = public_network.advertised_addresses;
 
         // Grab the validator's vfn network information and configure it as a seed for the VFN's
         // vfn network
@@ -8,8 +8,9 @@ use crate::{
 use anyhow::Result;
 use consensus_types::safety_data::SafetyData;
 use diem_global_constants::{
-    CONSENSUS_KEY, EXECUTION_KEY, FULLNODE_NETWORK_KEY, GENESIS_WAYPOINT, OPERATOR_ACCOUNT,
-    OPERATOR_KEY, OWNER_ACCOUNT, OWNER_KEY, SAFETY_DATA, VALIDATOR_NETWORK_KEY, WAYPOINT,
+    BLS_CONSENSUS_KEY, CONSENSUS_KEY, EXECUTION_KEY, FULLNODE_NETWORK_KEY, GENESIS_WAYPOINT,
+    OPERATOR_ACCOUNT, OPERATOR_KEY, OWNER_ACCOUNT, OWNER_KEY, SAFETY_DATA, VALIDATOR_NETWORK_KEY,
+    WAYPOINT,
 };
 use diem_secure_storage::{CryptoStorage, KVStorage, OnDiskStorage, Storage};
 use mango_config::{
@@ -20,11 +21,13 @@ use mango_config::{
     network_id::NetworkId,
 };
 use mango_crypto::{
+    bls12381::Bls12381PrivateKey,
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
     Uniform,
 };
 use mango_management::{
-    storage::StorageWrapper, validator_config::build_validator_config_transaction,
+    storage::StorageWrapper,
+    validator_config::{build_validator_bls_key_transaction, build_validator_config_transaction},
 };
 use mango_types::{
     chain_id::ChainId,
@@ -310,14 +313,14 @@ impl ValidatorBuilder {
         // By default we don't start a swarm with VFNs, so make sure the public fullnode endpoint
         // really is publicly accessable
 
-        let fullnode_network_listen_address =
+        let fullnode_network_listen_addresses =
             if let Some(template_fullnode_config) = config.full_node_networks.first() {
-                template_fullnode_config.listen_address.clone()
+                template_fullnode_config.listen_addresses.clone()
             } else {
-                mango_config::utils::get_available_port_in_multiaddr(true)
+                vec![mango_config::utils::get_available_port_in_multiaddr(true)]
             };
         let fullnode_network = NetworkConfig {
-            listen_address: fullnode_network_listen_address,
+            listen_addresses: fullnode_network_listen_addresses,
             network_id: NetworkId::Public,
             max_outbound_connections: 0,
             discovery_method: DiscoveryMethod::Onchain,
@@ -331,7 +334,7 @@ impl ValidatorBuilder {
         };
 
         let vfn_network = NetworkConfig {
-            listen_address: mango_config::utils::get_available_port_in_multiaddr(true),
+            listen_addresses: vec![mango_config::utils::get_available_port_in_multiaddr(true)],
             network_id: NetworkId::Private("vfn".to_owned()),
             max_outbound_connections: 0,
             identity: Identity::from_storage(
@@ -390,6 +393,7 @@ impl ValidatorBuilder {
         storage.import_private_key(OPERATOR_KEY, operator_key)?;
 
         storage.import_private_key(CONSENSUS_KEY, Ed25519PrivateKey::generate(&mut rng))?;
+        storage.set(BLS_CONSENSUS_KEY, Bls12381PrivateKey::generate(&mut rng))?;
         storage.import_private_key(EXECUTION_KEY, Ed25519PrivateKey::generate(&mut rng))?;
         storage.import_private_key(FULLNODE_NETWORK_KEY, Ed25519PrivateKey::generate(&mut rng))?;
         storage.import_private_key(VALIDATOR_NETWORK_KEY, Ed25519PrivateKey::generate(&mut rng))?;
@@ -448,18 +452,23 @@ impl ValidatorBuilder {
                 ChainId::test(),
                 0, // sequence_number
                 validator.config.full_node_networks[0]
-                    .listen_address
-                    .clone(),
+                    .advertised_addresses()
+                    .to_vec(),
                 validator
                     .config
                     .validator_network
                     .as_ref()
-                    .map(|a| a.listen_address.clone())
+                    .map(|a| a.advertised_addresses().to_vec())
                     .unwrap(),
                 false, // This isn't a reconfiguration
                 false, // Don't disable address validation
             )?;
             genesis_builder.set_validator_config(&validator.operator(), &validator_config)?;
+
+            // Register the BLS consensus key alongside the validator config
+            let validator_bls_key =
+                build_validator_bls_key_transaction(validator.storage(), ChainId::test(), 0)?;
+            genesis_builder.set_validator_bls_key(&validator.operator(), &validator_bls_key)?;
         }
 
         // Create Genesis and Genesis Waypoint
@@ -1,9 +1,11 @@
 // Copyright (c) The Mango Blockchain Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use diem_global_constants::OWNER_ACCOUNT;
+use diem_global_constants::{BLS_CONSENSUS_KEY, OWNER_ACCOUNT};
+use mango_crypto::{bls12381::Bls12381PrivateKey, Uniform};
 use mango_management::{constants, error::Error, secure_backend::SharedBackend};
 use mango_types::{network_address::NetworkAddress, transaction::Transaction};
+use rand::rngs::OsRng;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -12,10 +14,12 @@ pub struct ValidatorConfig {
     owner_name: String,
     #[structopt(flatten)]
     validator_config: mango_management::validator_config::ValidatorConfig,
-    #[structopt(long)]
-    validator_address: NetworkAddress,
-    #[structopt(long)]
-    fullnode_address: NetworkAddress,
+    /// Validator Network Address, may be repeated to advertise several addresses
+    #[structopt(long = "validator-address", number_of_values = 1, required = true)]
+    validator_addresses: Vec<NetworkAddress>,
+    /// Full Node Network Address, may be repeated to advertise several addresses
+    #[structopt(long = "fullnode-address", number_of_values = 1, required = true)]
+    fullnode_addresses: Vec<NetworkAddress>,
     #[structopt(flatten)]
     shared_backend: SharedBackend,
     #[structopt(long, help = "Disables network address validation")]
@@ -35,17 +39,28 @@ impl ValidatorConfig {
         let mut validator_storage = config.validator_backend();
         validator_storage.set(OWNER_ACCOUNT, owner_account)?;
 
+        // Secure storage cannot generate BLS keys, so the BLS consensus key is created here, once,
+        // and kept as a value that safety rules reads at startup.
+        if !validator_storage.exists::<Bls12381PrivateKey>(BLS_CONSENSUS_KEY)? {
+            validator_storage.set(BLS_CONSENSUS_KEY, Bls12381PrivateKey::generate(&mut OsRng))?;
+        }
+
         let txn = self.validator_config.build_transaction(
             0,
-            self.fullnode_address,
-            self.validator_address,
+            self.fullnode_addresses,
+            self.validator_addresses,
             false,
             self.disable_address_validation,
         )?;
 
-        // Upload the validator config to shared storage
+        // The BLS key is registered by a separate script, run by genesis after the validator
+        // config is published
+        let bls_key_txn = self.validator_config.build_bls_key_transaction(0)?;
+
+        // Upload the validator config and BLS key registration to shared storage
         let mut shared_storage = config.shared_backend();
         shared_storage.set(constants::VALIDATOR_CONFIG, txn.clone())?;
+        shared_storage.set(constants::VALIDATOR_BLS_KEY, bls_key_txn)?;
 
         Ok(txn)
     }
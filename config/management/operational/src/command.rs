@@ -6,7 +6,7 @@ use crate::{
     validator_set::DecryptedValidatorInfo, TransactionContext,
 };
 use mango_config::config::Peer;
-use mango_crypto::{ed25519::Ed25519PublicKey, x25519};
+use mango_crypto::{bls12381::Bls12381PublicKey, ed25519::Ed25519PublicKey, x25519};
 use mango_management::{error::Error, execute_command};
 use mango_types::{account_address::AccountAddress, waypoint::Waypoint, PeerId};
 use serde::Serialize;
@@ -54,6 +54,8 @@ pub enum Command {
     PrintWaypoint(crate::print::PrintWaypoint),
     #[structopt(about = "Remove a validator from ValidatorSet")]
     RemoveValidator(crate::governance::RemoveValidator),
+    #[structopt(about = "Rotates the BLS consensus key for a validator")]
+    RotateBlsConsensusKey(crate::validator_config::RotateBlsConsensusKey),
     #[structopt(about = "Rotates the consensus key for a validator")]
     RotateConsensusKey(crate::validator_config::RotateConsensusKey),
     #[structopt(about = "Rotates a full node network key")]
@@ -94,6 +96,7 @@ pub enum CommandName {
     PrintXKey,
     PrintWaypoint,
     RemoveValidator,
+    RotateBlsConsensusKey,
     RotateConsensusKey,
     RotateOperatorKey,
     RotateFullNodeNetworkKey,
@@ -126,6 +129,7 @@ impl From<&Command> for CommandName {
             Command::PrintXKey(_) => CommandName::PrintXKey,
             Command::PrintWaypoint(_) => CommandName::PrintWaypoint,
             Command::RemoveValidator(_) => CommandName::RemoveValidator,
+            Command::RotateBlsConsensusKey(_) => CommandName::RotateBlsConsensusKey,
             Command::RotateConsensusKey(_) => CommandName::RotateConsensusKey,
             Command::RotateOperatorKey(_) => CommandName::RotateOperatorKey,
             Command::RotateFullNodeNetworkKey(_) => CommandName::RotateFullNodeNetworkKey,
@@ -160,6 +164,7 @@ impl std::fmt::Display for CommandName {
             CommandName::PrintXKey => "print-x-key",
             CommandName::PrintWaypoint => "print-waypoint",
             CommandName::RemoveValidator => "remove-validator",
+            CommandName::RotateBlsConsensusKey => "rotate-bls-consensus-key",
             CommandName::RotateConsensusKey => "rotate-consensus-key",
             CommandName::RotateOperatorKey => "rotate-operator-key",
             CommandName::RotateFullNodeNetworkKey => "rotate-full-node-network-key",
@@ -199,6 +204,9 @@ impl Command {
             Command::PrintXKey(cmd) => Self::pretty_print(cmd.execute()),
             Command::PrintWaypoint(cmd) => Self::pretty_print(cmd.execute()),
             Command::RemoveValidator(cmd) => Self::print_transaction_context(cmd.execute()),
+            Command::RotateBlsConsensusKey(cmd) => {
+                Self::print_transaction_context(cmd.execute().map(|(txn_ctx, _)| txn_ctx))
+            }
             Command::RotateConsensusKey(cmd) => {
                 Self::print_transaction_context(cmd.execute().map(|(txn_ctx, _)| txn_ctx))
             }
@@ -353,6 +361,16 @@ impl Command {
         execute_command!(self, Command::RemoveValidator, CommandName::RemoveValidator)
     }
 
+    pub fn rotate_bls_consensus_key(
+        self,
+    ) -> Result<(TransactionContext, Bls12381PublicKey), Error> {
+        execute_command!(
+            self,
+            Command::RotateBlsConsensusKey,
+            CommandName::RotateBlsConsensusKey
+        )
+    }
+
     pub fn rotate_consensus_key(self) -> Result<(TransactionContext, Ed25519PublicKey), Error> {
         execute_command!(
             self,
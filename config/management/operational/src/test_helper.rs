@@ -11,7 +11,10 @@ use crate::{
 };
 use itertools::Itertools;
 use mango_config::{config, config::Peer, network_id::NetworkId};
-use mango_crypto::{ed25519::Ed25519PublicKey, traits::ValidCryptoMaterialStringExt, x25519};
+use mango_crypto::{
+    bls12381::Bls12381PublicKey, ed25519::Ed25519PublicKey, traits::ValidCryptoMaterialStringExt,
+    x25519,
+};
 use mango_management::{error::Error, secure_backend::DISK};
 use mango_types::{
     account_address::AccountAddress, chain_id::ChainId, network_address::NetworkAddress,
@@ -462,6 +465,19 @@ impl OperationalTool {
         execute(command)
     }
 
+    pub fn rotate_bls_consensus_key(
+        &self,
+        backend: &config::SecureBackend,
+        disable_validate: bool,
+    ) -> Result<(TransactionContext, Bls12381PublicKey), Error> {
+        self.rotate_key(
+            backend,
+            disable_validate,
+            CommandName::RotateBlsConsensusKey,
+            |cmd| cmd.rotate_bls_consensus_key(),
+        )
+    }
+
     pub fn rotate_consensus_key(
         &self,
         backend: &config::SecureBackend,
@@ -3,16 +3,22 @@
 
 use crate::{auto_validate::AutoValidate, json_rpc::JsonRpcClientWrapper, TransactionContext};
 use diem_global_constants::{
-    CONSENSUS_KEY, FULLNODE_NETWORK_KEY, OPERATOR_ACCOUNT, OWNER_ACCOUNT, VALIDATOR_NETWORK_KEY,
+    BLS_CONSENSUS_KEY, CONSENSUS_KEY, FULLNODE_NETWORK_KEY, OPERATOR_ACCOUNT, OWNER_ACCOUNT,
+    VALIDATOR_NETWORK_KEY,
 };
 use diem_network_address_encryption::Encryptor;
 use diem_secure_storage::Storage;
-use mango_crypto::{ed25519::Ed25519PublicKey, x25519};
+use mango_crypto::{
+    bls12381::{Bls12381PrivateKey, Bls12381PublicKey},
+    ed25519::Ed25519PublicKey,
+    x25519, PrivateKey, Uniform,
+};
 use mango_management::{error::Error, secure_backend::ValidatorBackend, storage::to_x25519};
 use mango_types::{
     account_address::AccountAddress,
     network_address::{NetworkAddress, Protocol},
 };
+use rand::rngs::OsRng;
 use serde::Serialize;
 use std::{convert::TryFrom, str::FromStr};
 use structopt::StructOpt;
@@ -26,17 +32,19 @@ pub struct SetValidatorConfig {
     #[structopt(flatten)]
     validator_config: mango_management::validator_config::ValidatorConfig,
     #[structopt(
-        long,
-        required_unless = "fullnode-address",
-        help = "Validator Network Address"
+        long = "validator-address",
+        number_of_values = 1,
+        required_unless = "fullnode-addresses",
+        help = "Validator Network Address, may be repeated to advertise several addresses"
     )]
-    validator_address: Option<NetworkAddress>,
+    validator_addresses: Vec<NetworkAddress>,
     #[structopt(
-        long,
-        required_unless = "validator-address",
-        help = "Full Node Network Address"
+        long = "fullnode-address",
+        number_of_values = 1,
+        required_unless = "validator-addresses",
+        help = "Full Node Network Address, may be repeated to advertise several addresses"
     )]
-    fullnode_address: Option<NetworkAddress>,
+    fullnode_addresses: Vec<NetworkAddress>,
     #[structopt(flatten)]
     auto_validate: AutoValidate,
     #[structopt(long, help = "Disables network address validation")]
@@ -80,20 +88,20 @@ impl SetValidatorConfig {
             None
         };
 
-        let validator_address = if let Some(validator_address) = &self.validator_address {
-            validator_address.clone()
+        let validator_addresses = if !self.validator_addresses.is_empty() {
+            self.validator_addresses.clone()
         } else if let Some(vc) = &validator_config {
-            strip_address(&vc.validator_network_address)
+            strip_addresses(&vc.validator_network_addresses)
         } else {
             return Err(Error::UnexpectedError(
                 "Missing validator-network-address".to_string(),
             ));
         };
 
-        let fullnode_address = if let Some(fullnode_address) = &self.fullnode_address {
-            fullnode_address.clone()
+        let fullnode_addresses = if !self.fullnode_addresses.is_empty() {
+            self.fullnode_addresses.clone()
         } else if let Some(vc) = &validator_config {
-            strip_address(&vc.fullnode_network_address)
+            strip_addresses(&vc.fullnode_network_addresses)
         } else {
             return Err(Error::UnexpectedError(
                 "Missing fullnode-network-address".to_string(),
@@ -102,8 +110,8 @@ impl SetValidatorConfig {
 
         let txn = self.validator_config.build_transaction(
             sequence_number,
-            fullnode_address,
-            validator_address,
+            fullnode_addresses,
+            validator_addresses,
             validator_config.is_some(),
             self.disable_address_validation,
         )?;
@@ -157,16 +165,14 @@ impl RotateKey {
         let mut storage_key = storage.ed25519_public_from_private(key_name)?;
         let keys_match = match key_name {
             CONSENSUS_KEY => storage_key == validator_config.consensus_public_key,
-            VALIDATOR_NETWORK_KEY => {
-                Some(to_x25519(storage_key.clone())?)
-                    == validator_config
-                        .validator_network_address
-                        .find_noise_proto()
-            }
-            FULLNODE_NETWORK_KEY => {
-                Some(to_x25519(storage_key.clone())?)
-                    == validator_config.fullnode_network_address.find_noise_proto()
-            }
+            VALIDATOR_NETWORK_KEY => noise_keys_match(
+                to_x25519(storage_key.clone())?,
+                &validator_config.validator_network_addresses,
+            ),
+            FULLNODE_NETWORK_KEY => noise_keys_match(
+                to_x25519(storage_key.clone())?,
+                &validator_config.fullnode_network_addresses,
+            ),
             _ => {
                 return Err(Error::UnexpectedError(
                     "Rotate key was called with an unknown key name!".into(),
@@ -181,8 +187,8 @@ impl RotateKey {
         let set_validator_config = SetValidatorConfig {
             json_server: self.json_server.clone(),
             validator_config: self.validator_config.clone(),
-            validator_address: None,
-            fullnode_address: None,
+            validator_addresses: Vec::new(),
+            fullnode_addresses: Vec::new(),
             auto_validate: self.auto_validate.clone(),
             disable_address_validation: true,
         };
@@ -209,6 +215,52 @@ impl RotateConsensusKey {
     }
 }
 
+/// Generates a new BLS12-381 consensus key, stores it in the validator storage and registers it
+/// on-chain. Unlike the Ed25519 keys, secure storage cannot generate or rotate BLS keys, so the
+/// key is created here and kept as a value under `BLS_CONSENSUS_KEY`.
+#[derive(Debug, StructOpt)]
+pub struct RotateBlsConsensusKey {
+    /// JSON-RPC Endpoint (e.g. http://localhost:8080)
+    #[structopt(long, required_unless = "config")]
+    json_server: Option<String>,
+    #[structopt(flatten)]
+    validator_config: mango_management::validator_config::ValidatorConfig,
+    #[structopt(flatten)]
+    auto_validate: AutoValidate,
+}
+
+impl RotateBlsConsensusKey {
+    pub fn execute(self) -> Result<(TransactionContext, Bls12381PublicKey), Error> {
+        let config = self
+            .validator_config
+            .config()?
+            .override_json_server(&self.json_server);
+        let mut storage = config.validator_backend();
+        let client = JsonRpcClientWrapper::new(config.json_server.clone());
+
+        // The on-chain key is replaced wholesale, so there is nothing to resynchronize: a rotation
+        // that failed to land is simply superseded by this one.
+        let bls_key = Bls12381PrivateKey::generate(&mut OsRng);
+        let bls_public_key = bls_key.public_key();
+        storage.set(BLS_CONSENSUS_KEY, bls_key)?;
+
+        let operator_account = storage.account_address(OPERATOR_ACCOUNT)?;
+        let sequence_number = client.sequence_number(operator_account)?;
+        let txn = self
+            .validator_config
+            .build_bls_key_transaction(sequence_number)?;
+        let mut transaction_context =
+            client.submit_transaction(txn.as_signed_user_txn().unwrap().clone())?;
+
+        // Perform auto validation if required
+        transaction_context = self
+            .auto_validate
+            .execute(config.json_server, transaction_context)?;
+
+        Ok((transaction_context, bls_public_key))
+    }
+}
+
 #[derive(Debug, StructOpt)]
 pub struct RotateValidatorNetworkKey {
     #[structopt(flatten)]
@@ -235,6 +287,19 @@ impl RotateFullNodeNetworkKey {
     }
 }
 
+/// Returns true if every address is registered with the given noise public key
+fn noise_keys_match(key: x25519::PublicKey, addresses: &[NetworkAddress]) -> bool {
+    !addresses.is_empty()
+        && addresses
+            .iter()
+            .all(|address| address.find_noise_proto() == Some(key))
+}
+
+/// Returns only the IP/DNS + Port portion of each NetworkAddress
+pub fn strip_addresses(addresses: &[NetworkAddress]) -> Vec<NetworkAddress> {
+    addresses.iter().map(strip_address).collect()
+}
+
 /// Returns only the IP/DNS + Port portion of the NetworkAddress
 pub fn strip_address(address: &NetworkAddress) -> NetworkAddress {
     let protocols = address
@@ -301,8 +366,8 @@ impl ValidatorConfig {
 pub struct DecryptedValidatorConfig {
     pub name: String,
     pub consensus_public_key: Ed25519PublicKey,
-    pub validator_network_address: NetworkAddress,
-    pub fullnode_network_address: NetworkAddress,
+    pub validator_network_addresses: Vec<NetworkAddress>,
+    pub fullnode_network_addresses: Vec<NetworkAddress>,
 }
 
 impl DecryptedValidatorConfig {
@@ -335,8 +400,8 @@ impl DecryptedValidatorConfig {
         Ok(DecryptedValidatorConfig {
             name: "".to_string(),
             consensus_public_key: config.consensus_public_key.clone(),
-            fullnode_network_address: fullnode_network_addresses[0].clone(),
-            validator_network_address: validator_network_addresses[0].clone(),
+            fullnode_network_addresses,
+            validator_network_addresses,
         })
     }
 
@@ -70,8 +70,8 @@ pub fn decode_validator_set(
             name,
             account_address: *info.account_address(),
             consensus_public_key: config.consensus_public_key,
-            fullnode_network_address: config.fullnode_network_address,
-            validator_network_address: config.validator_network_address,
+            fullnode_network_addresses: config.fullnode_network_addresses,
+            validator_network_addresses: config.validator_network_addresses,
         };
         decoded_set.push(info);
     }
@@ -120,6 +120,6 @@ pub struct DecryptedValidatorInfo {
     pub name: String,
     pub account_address: AccountAddress,
     pub consensus_public_key: Ed25519PublicKey,
-    pub fullnode_network_address: NetworkAddress,
-    pub validator_network_address: NetworkAddress,
+    pub fullnode_network_addresses: Vec<NetworkAddress>,
+    pub validator_network_addresses: Vec<NetworkAddress>,
 }
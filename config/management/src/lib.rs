@@ -15,6 +15,7 @@ pub mod constants {
     use mango_types::account_config::XUS_NAME;
     pub const COMMON_NS: &str = "common";
     pub const LAYOUT: &str = "layout";
+    pub const VALIDATOR_BLS_KEY: &str = "validator_bls_key";
     pub const VALIDATOR_CONFIG: &str = "validator_config";
     pub const VALIDATOR_OPERATOR: &str = "validator_operator";
 
@@ -40,6 +40,20 @@ impl StorageWrapper {
             .map_err(|e| Error::StorageReadError(self.storage_name, name, e.to_string()))
     }
 
+    /// Returns whether a value is set under `name`, distinguishing a missing value from a failure
+    /// to read the storage.
+    pub fn exists<T: DeserializeOwned>(&self, name: &'static str) -> Result<bool, Error> {
+        match self.storage.get::<T>(name) {
+            Ok(_) => Ok(true),
+            Err(diem_secure_storage::Error::KeyNotSet(_)) => Ok(false),
+            Err(e) => Err(Error::StorageReadError(
+                self.storage_name,
+                name,
+                e.to_string(),
+            )),
+        }
+    }
+
     pub fn account_address(&self, name: &'static str) -> Result<AccountAddress, Error> {
         self.value(name)
     }
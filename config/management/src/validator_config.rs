@@ -10,13 +10,17 @@ use crate::{
 };
 use core::str::FromStr;
 use diem_global_constants::{
-    CONSENSUS_KEY, FULLNODE_NETWORK_KEY, OPERATOR_ACCOUNT, OPERATOR_KEY, OWNER_ACCOUNT,
-    VALIDATOR_NETWORK_KEY,
+    BLS_CONSENSUS_KEY, CONSENSUS_KEY, FULLNODE_NETWORK_KEY, OPERATOR_ACCOUNT, OPERATOR_KEY,
+    OWNER_ACCOUNT, VALIDATOR_NETWORK_KEY,
 };
 use diem_network_address_encryption::Encryptor;
 use diem_secure_storage::{CryptoStorage, KVStorage, Storage};
 use diem_transaction_builder::stdlib as transaction_builder;
 use mango_config::config::HANDSHAKE_VERSION;
+use mango_crypto::{
+    bls12381::{Bls12381PrivateKey, Bls12381ProofOfPossession},
+    PrivateKey,
+};
 use mango_types::{
     account_address::AccountAddress,
     chain_id::ChainId,
@@ -47,8 +51,8 @@ impl ValidatorConfig {
     pub fn build_transaction(
         &self,
         sequence_number: u64,
-        fullnode_address: NetworkAddress,
-        validator_address: NetworkAddress,
+        fullnode_addresses: Vec<NetworkAddress>,
+        validator_addresses: Vec<NetworkAddress>,
         reconfigure: bool,
         disable_address_validation: bool,
     ) -> Result<Transaction, Error> {
@@ -60,8 +64,8 @@ impl ValidatorConfig {
             storage,
             chain_id,
             sequence_number,
-            fullnode_address,
-            validator_address,
+            fullnode_addresses,
+            validator_addresses,
             reconfigure,
             disable_address_validation,
         )
@@ -72,6 +76,20 @@ impl ValidatorConfig {
             ))
         })
     }
+
+    pub fn build_bls_key_transaction(&self, sequence_number: u64) -> Result<Transaction, Error> {
+        let config = self.config()?;
+        let storage = Storage::from(&config.validator_backend);
+
+        build_validator_bls_key_transaction(storage, config.chain_id, sequence_number).map_err(
+            |e| {
+                Error::UnexpectedError(format!(
+                    "Error building validator BLS key transaction: {}",
+                    e
+                ))
+            },
+        )
+    }
 }
 
 /// Requires that the validator storage has the following keys set:
@@ -86,15 +104,15 @@ pub fn build_validator_config_transaction<S: KVStorage + CryptoStorage>(
     mut validator_storage: S,
     chain_id: ChainId,
     sequence_number: u64,
-    fullnode_address: NetworkAddress,
-    validator_address: NetworkAddress,
+    fullnode_addresses: Vec<NetworkAddress>,
+    validator_addresses: Vec<NetworkAddress>,
     reconfigure: bool,
     disable_address_validation: bool,
 ) -> anyhow::Result<Transaction> {
     if !disable_address_validation {
         // Verify addresses
-        validate_address("validator address", &validator_address)?;
-        validate_address("fullnode address", &fullnode_address)?;
+        validate_addresses("validator address", &validator_addresses)?;
+        validate_addresses("fullnode address", &fullnode_addresses)?;
     }
 
     let owner_account = validator_storage
@@ -118,15 +136,17 @@ pub fn build_validator_config_transaction<S: KVStorage + CryptoStorage>(
         .map_err(|e| Error::UnexpectedError(e.to_string()))
         .and_then(to_x25519)?;
 
-    // Build Validator address including protocols and encryption
+    // Build Validator addresses including protocols and encryption
     // Append ln-noise-ik and ln-handshake protocols to base network addresses
-    // and encrypt the validator address.
-    let validator_address =
-        validator_address.append_prod_protos(validator_network_key, HANDSHAKE_VERSION);
+    // and encrypt the validator addresses.
+    let validator_addresses: Vec<_> = validator_addresses
+        .into_iter()
+        .map(|address| address.append_prod_protos(validator_network_key, HANDSHAKE_VERSION))
+        .collect();
     let encryptor = Encryptor::new(&mut validator_storage);
     let validator_addresses = encryptor
         .encrypt(
-            &[validator_address],
+            &validator_addresses,
             owner_account,
             sequence_number + if reconfigure { 1 } else { 0 },
         )
@@ -134,9 +154,11 @@ pub fn build_validator_config_transaction<S: KVStorage + CryptoStorage>(
             Error::UnexpectedError(format!("Error encrypting validator address: {}", e))
         })?;
 
-    // Build Fullnode address including protocols
-    let fullnode_address =
-        fullnode_address.append_prod_protos(fullnode_network_key, HANDSHAKE_VERSION);
+    // Build Fullnode addresses including protocols
+    let fullnode_addresses: Vec<_> = fullnode_addresses
+        .into_iter()
+        .map(|address| address.append_prod_protos(fullnode_network_key, HANDSHAKE_VERSION))
+        .collect();
 
     // Generate the validator config script
     let transaction_callback = if reconfigure {
@@ -148,7 +170,7 @@ pub fn build_validator_config_transaction<S: KVStorage + CryptoStorage>(
         owner_account,
         consensus_key.to_bytes().to_vec(),
         validator_addresses,
-        bcs::to_bytes(&vec![fullnode_address]).unwrap(),
+        bcs::to_bytes(&fullnode_addresses).unwrap(),
     )
     .into_script_function();
 
@@ -169,6 +191,71 @@ pub fn build_validator_config_transaction<S: KVStorage + CryptoStorage>(
     Ok(txn)
 }
 
+/// Builds the transaction registering the BLS12-381 consensus key held in storage on-chain. It
+/// triggers a reconfiguration once the chain is running; in genesis the key is simply recorded.
+///
+/// Requires that the validator storage has the following keys set:
+/// * OWNER_ACCOUNT
+/// * BLS_CONSENSUS_KEY
+/// * OPERATOR_ACCOUNT
+/// * OPERATOR_KEY
+pub fn build_validator_bls_key_transaction<S: KVStorage + CryptoStorage>(
+    validator_storage: S,
+    chain_id: ChainId,
+    sequence_number: u64,
+) -> anyhow::Result<Transaction> {
+    let owner_account = validator_storage
+        .get::<AccountAddress>(OWNER_ACCOUNT)
+        .map(|v| v.value)?;
+    let operator_account = validator_storage
+        .get::<AccountAddress>(OPERATOR_ACCOUNT)
+        .map(|v| v.value)?;
+    let bls_key = validator_storage
+        .get::<Bls12381PrivateKey>(BLS_CONSENSUS_KEY)
+        .map(|v| v.value)?;
+
+    let set_bls_key_script =
+        transaction_builder::encode_set_validator_bls_key_and_reconfigure_script_function(
+            owner_account,
+            bls_key.public_key().to_bytes().to_vec(),
+            Bls12381ProofOfPossession::create(&bls_key)
+                .to_bytes()
+                .to_vec(),
+        )
+        .into_script_function();
+
+    let raw_txn = build_raw_transaction(
+        chain_id,
+        operator_account,
+        sequence_number,
+        set_bls_key_script,
+    );
+    let public_key = validator_storage
+        .get_public_key(OPERATOR_KEY)
+        .map(|v| v.public_key)?;
+    let signature = validator_storage.sign(OPERATOR_KEY, &raw_txn)?;
+    let signed_txn = SignedTransaction::new(raw_txn, public_key, signature);
+    Ok(Transaction::UserTransaction(signed_txn))
+}
+
+/// Validates that at least one address is given, and that each of them is valid
+pub fn validate_addresses(
+    address_name: &'static str,
+    network_addresses: &[NetworkAddress],
+) -> Result<(), Error> {
+    if network_addresses.is_empty() {
+        return Err(Error::CommandArgumentError(format!(
+            "{}: At least one address is required",
+            address_name
+        )));
+    }
+
+    for network_address in network_addresses {
+        validate_address(address_name, network_address)?;
+    }
+    Ok(())
+}
+
 /// Validates an address to have a DNS/IP and a port, as well as to be resolvable
 pub fn validate_address(
     address_name: &'static str,
@@ -260,5 +347,16 @@ mod tests {
 
         validate_address("ip", &ip).expect("IP failed to validate");
         validate_address("dns", &dns).expect("DNS failed to validate");
+        validate_addresses("ip and dns", &[ip, dns]).expect("Addresses failed to validate");
+    }
+
+    #[test]
+    fn test_invalid_address_lists() {
+        let ip = NetworkAddress::from_str("/ip4/127.0.0.1/tcp/1234").unwrap();
+        let no_port = NetworkAddress::from_str("/ip4/127.0.0.1").unwrap();
+
+        validate_addresses("empty", &[]).expect_err("Failed to check for no addresses");
+        validate_addresses("ip and no_port", &[ip, no_port])
+            .expect_err("Failed to check every address");
     }
 }
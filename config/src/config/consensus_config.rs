@@ -29,6 +29,8 @@ pub struct ConsensusConfig {
     pub decoupled_execution: bool,
     pub channel_size: usize,
     pub back_pressure_limit: u64,
+    // batch dissemination settings, used when the quorum store is enabled on-chain
+    pub quorum_store: QuorumStoreConfig,
 }
 
 impl Default for ConsensusConfig {
@@ -50,6 +52,7 @@ impl Default for ConsensusConfig {
             decoupled_execution: false, // by default, we turn of the decoupling execution feature
             channel_size: 30,           // hard-coded
             back_pressure_limit: 1,
+            quorum_store: QuorumStoreConfig::default(),
         }
     }
 }
@@ -60,6 +63,34 @@ impl ConsensusConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuorumStoreConfig {
+    // Maximum number of transactions in a batch
+    pub max_batch_size: u64,
+    // Maximum size of the serialized transactions of a batch (in bytes)
+    pub max_batch_bytes: u64,
+    // Maximum number of uncommitted batches stored per author, batches beyond it are not signed
+    pub max_batches_per_author: usize,
+    // How often transactions are pulled from mempool to form a batch (in milliseconds)
+    pub batch_interval_ms: u64,
+    // Timeout for fetching a missing batch from another validator (in milliseconds)
+    pub batch_request_timeout_ms: u64,
+}
+
+impl Default for QuorumStoreConfig {
+    fn default() -> QuorumStoreConfig {
+        QuorumStoreConfig {
+            max_batch_size: 250,
+            max_batch_bytes: 4 * 1024 * 1024,
+            // 30s worth of batches at the default interval
+            max_batches_per_author: 300,
+            batch_interval_ms: 100,
+            batch_request_timeout_ms: 1000,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum ConsensusProposerType {
@@ -69,6 +100,8 @@ pub enum ConsensusProposerType {
     RotatingProposer,
     // Committed history based proposer election
     LeaderReputation(LeaderReputationConfig),
+    // Committed history based proposer election, penalising the validators failing to propose
+    ProposerAndVoterReputation(ProposerAndVoterConfig),
     // Pre-specified proposers for each round,
     // or default proposer if round proposer not
     // specified
@@ -81,3 +114,17 @@ pub struct LeaderReputationConfig {
     pub active_weights: u64,
     pub inactive_weights: u64,
 }
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProposerAndVoterConfig {
+    pub active_weights: u64,
+    pub inactive_weights: u64,
+    // Weight of the validators whose failed proposer rounds exceed the threshold
+    pub failed_weights: u64,
+    // Percentage of failed rounds among the proposer rounds of a validator in the window
+    pub failure_threshold_percent: u64,
+    // Percentage of the blocks in the window a validator that didn't propose has to vote for to
+    // be considered active
+    pub voter_threshold_percent: u64,
+}
@@ -1,9 +1,9 @@
 // Copyright (c) The Mango Blockchain Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::utils;
+use crate::{config::Error, utils};
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::{collections::BTreeMap, net::SocketAddr};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -16,6 +16,8 @@ pub struct JsonRpcConfig {
     pub tls_key_path: Option<String>,
     #[serde(default)]
     pub stream_rpc: StreamConfig,
+    #[serde(default)]
+    pub rate_limit: JsonRpcRateLimitConfig,
 }
 
 pub const DEFAULT_JSON_RPC_ADDRESS: &str = "127.0.0.1";
@@ -36,6 +38,7 @@ impl Default for JsonRpcConfig {
             tls_cert_path: None,
             tls_key_path: None,
             stream_rpc: StreamConfig::default(),
+            rate_limit: JsonRpcRateLimitConfig::default(),
         }
     }
 }
@@ -74,3 +77,147 @@ impl Default for StreamConfig {
         }
     }
 }
+
+/// Token bucket limits on the method calls of each caller. Callers passing a configured API key
+/// in the `X-mango-Api-Key` header are limited by the quota of their key, other callers by their
+/// IP. A call takes as many tokens as the cost of its method, in a batch or not.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JsonRpcRateLimitConfig {
+    pub enabled: bool,
+    /// Maximum burst of tokens for an IP
+    pub ip_bucket_size: usize,
+    /// Tokens/s refilled for an IP
+    pub ip_bucket_rate: usize,
+    /// Quotas by API key
+    pub api_keys: BTreeMap<String, ApiKeyQuota>,
+    /// Cost of a call by method name, methods not listed cost `default_method_cost`
+    pub method_costs: BTreeMap<String, usize>,
+    pub default_method_cost: usize,
+}
+
+pub const DEFAULT_RATE_LIMIT_IP_BUCKET_SIZE: usize = 200;
+pub const DEFAULT_RATE_LIMIT_IP_BUCKET_RATE: usize = 100;
+pub const DEFAULT_RATE_LIMIT_METHOD_COST: usize = 1;
+pub const DEFAULT_RATE_LIMIT_EXPENSIVE_METHOD_COST: usize = 10;
+
+impl Default for JsonRpcRateLimitConfig {
+    fn default() -> JsonRpcRateLimitConfig {
+        JsonRpcRateLimitConfig {
+            enabled: false,
+            ip_bucket_size: DEFAULT_RATE_LIMIT_IP_BUCKET_SIZE,
+            ip_bucket_rate: DEFAULT_RATE_LIMIT_IP_BUCKET_RATE,
+            api_keys: BTreeMap::new(),
+            method_costs: [
+                "get_account_transactions",
+                "get_account_transactions_with_proofs",
+                "get_events_with_proofs",
+                "get_transactions_with_proofs",
+            ]
+            .iter()
+            .map(|method| (method.to_string(), DEFAULT_RATE_LIMIT_EXPENSIVE_METHOD_COST))
+            .collect(),
+            default_method_cost: DEFAULT_RATE_LIMIT_METHOD_COST,
+        }
+    }
+}
+
+impl JsonRpcRateLimitConfig {
+    /// Checks that the buckets can be built and that every method fits in each of them, since a
+    /// call costing more than a bucket holds would be rejected forever
+    pub fn verify(&self) -> Result<(), Error> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let max_method_cost = self
+            .method_costs
+            .values()
+            .copied()
+            .fold(self.default_method_cost, usize::max);
+        verify_bucket(
+            "the IP bucket",
+            self.ip_bucket_size,
+            self.ip_bucket_rate,
+            max_method_cost,
+        )?;
+        // API keys are secrets, so their buckets are reported by index rather than by key.
+        for (index, quota) in self.api_keys.values().enumerate() {
+            verify_bucket(
+                &format!("the bucket of API key #{}", index),
+                quota.bucket_size,
+                quota.bucket_rate,
+                max_method_cost,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn verify_bucket(
+    name: &str,
+    size: usize,
+    rate: usize,
+    max_method_cost: usize,
+) -> Result<(), Error> {
+    crate::config::invariant(
+        rate > 0 && size >= rate,
+        format!(
+            "JSON-RPC rate limit: {} needs 0 < rate <= size, got size {} and rate {}",
+            name, size, rate
+        ),
+    )?;
+    crate::config::invariant(
+        max_method_cost <= size,
+        format!(
+            "JSON-RPC rate limit: a method costs {} tokens, more than the {} of {}",
+            max_method_cost, size, name
+        ),
+    )
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyQuota {
+    /// Maximum burst of tokens for the key
+    pub bucket_size: usize,
+    /// Tokens/s refilled for the key
+    pub bucket_rate: usize,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_verify_rate_limit() {
+        let mut config = JsonRpcRateLimitConfig {
+            enabled: true,
+            ..JsonRpcRateLimitConfig::default()
+        };
+        config.verify().unwrap();
+
+        config.api_keys.insert(
+            "test-key".to_string(),
+            ApiKeyQuota {
+                bucket_size: DEFAULT_RATE_LIMIT_EXPENSIVE_METHOD_COST - 1,
+                bucket_rate: 1,
+            },
+        );
+        config.verify().unwrap_err();
+        // disabled limits are not checked
+        config.enabled = false;
+        config.verify().unwrap();
+
+        let mut config = JsonRpcRateLimitConfig {
+            enabled: true,
+            method_costs: BTreeMap::new(),
+            ..JsonRpcRateLimitConfig::default()
+        };
+        config.default_method_cost = config.ip_bucket_size + 1;
+        config.verify().unwrap_err();
+        config.default_method_cost = config.ip_bucket_size;
+        config.verify().unwrap();
+        config.ip_bucket_rate = 0;
+        config.verify().unwrap_err();
+    }
+}
@@ -10,6 +10,8 @@ pub struct MempoolConfig {
     pub capacity_per_user: usize,
     // number of failovers to broadcast to when the primary network is alive
     pub default_failovers: usize,
+    // optional fee market used when consensus pulls blocks
+    pub fee_market: FeeMarketConfig,
     pub max_broadcasts_per_peer: usize,
     pub mempool_snapshot_interval_secs: u64,
     pub shared_mempool_ack_timeout_ms: u64,
@@ -34,8 +36,38 @@ impl Default for MempoolConfig {
             capacity: 1_000_000,
             capacity_per_user: 100,
             default_failovers: 3,
+            fee_market: FeeMarketConfig::default(),
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
         }
     }
 }
+
+/// Configuration of the mempool fee market. When enabled, consensus pulls are ordered by the
+/// ranking score of whole sender chains, and transactions priced below a dynamic floor are left
+/// in mempool. The floor follows the fullness of recently pulled blocks.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeeMarketConfig {
+    pub enabled: bool,
+    // lowest value the dynamic floor can take
+    pub min_gas_price: u64,
+    // block fullness (in percent) at which the floor stays unchanged
+    pub target_block_fullness_pct: u64,
+    // the floor moves by at most 1/max_change_denominator per block
+    pub max_change_denominator: u64,
+    // number of recent blocks kept in the fee market history
+    pub history_size: usize,
+}
+
+impl Default for FeeMarketConfig {
+    fn default() -> FeeMarketConfig {
+        FeeMarketConfig {
+            enabled: false,
+            min_gas_price: 0,
+            target_block_fullness_pct: 50,
+            max_change_denominator: 8,
+            history_size: 32,
+        }
+    }
+}
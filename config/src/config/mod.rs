@@ -231,6 +231,7 @@ impl NodeConfig {
 
         let input_dir = RootPath::new(input_path);
         config.execution.load(&input_dir)?;
+        config.json_rpc.rate_limit.verify()?;
 
         let mut config = config.validate_network_configs()?;
         config.set_data_dir(config.data_dir().to_path_buf());
@@ -296,11 +297,11 @@ impl NodeConfig {
         self.storage.randomize_ports();
 
         if let Some(network) = self.validator_network.as_mut() {
-            network.listen_address = crate::utils::get_available_port_in_multiaddr(true);
+            network.listen_addresses = vec![crate::utils::get_available_port_in_multiaddr(true)];
         }
 
         for network in self.full_node_networks.iter_mut() {
-            network.listen_address = crate::utils::get_available_port_in_multiaddr(true);
+            network.listen_addresses = vec![crate::utils::get_available_port_in_multiaddr(true)];
         }
     }
 
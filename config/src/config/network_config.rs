@@ -11,13 +11,15 @@ use diem_network_address_encryption::Encryptor;
 use diem_secure_storage::{CryptoStorage, KVStorage, Storage};
 use mango_crypto::{x25519, Uniform};
 use mango_types::{
-    network_address::NetworkAddress, transaction::authenticator::AuthenticationKey, PeerId,
+    network_address::{NetworkAddress, Protocol},
+    transaction::authenticator::AuthenticationKey,
+    PeerId,
 };
 use rand::{
     rngs::{OsRng, StdRng},
     Rng, SeedableRng,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use short_hex_str::AsShortHexStr;
 use std::{
     collections::{HashMap, HashSet},
@@ -65,9 +67,13 @@ pub struct NetworkConfig {
     pub discovery_method: DiscoveryMethod,
     pub discovery_methods: Vec<DiscoveryMethod>,
     pub identity: Identity,
-    // TODO: Add support for multiple listen/advertised addresses in config.
-    // The address that this node is listening on for new connections.
-    pub listen_address: NetworkAddress,
+    // The addresses that this node is listening on for new connections, e.g. on both IPv4 and
+    // IPv6. A single `listen_address` is accepted for backwards compatibility.
+    #[serde(alias = "listen_address", deserialize_with = "deserialize_addresses")]
+    pub listen_addresses: Vec<NetworkAddress>,
+    // The addresses other peers should dial to reach this node, e.g. in the on-chain validator
+    // config. The listen addresses are advertised if empty.
+    pub advertised_addresses: Vec<NetworkAddress>,
     // Select this to enforce that both peers should authenticate each other, otherwise
     // authentication only occurs for outgoing connections.
     pub mutual_authentication: bool,
@@ -112,7 +118,8 @@ impl NetworkConfig {
             discovery_method: DiscoveryMethod::None,
             discovery_methods: Vec::new(),
             identity: Identity::None,
-            listen_address: "/ip4/0.0.0.0/tcp/6180".parse().unwrap(),
+            listen_addresses: vec!["/ip4/0.0.0.0/tcp/6180".parse().unwrap()],
+            advertised_addresses: Vec::new(),
             mutual_authentication: false,
             network_address_key_backend: None,
             network_id,
@@ -204,15 +211,82 @@ impl NetworkConfig {
     }
 
     fn load(&mut self) -> Result<(), Error> {
-        if self.listen_address.to_string().is_empty() {
-            self.listen_address = utils::get_local_ip()
-                .ok_or_else(|| Error::InvariantViolation("No local IP".to_string()))?;
+        if self
+            .listen_addresses
+            .iter()
+            .all(|addr| addr.to_string().is_empty())
+        {
+            self.listen_addresses = vec![utils::get_local_ip()
+                .ok_or_else(|| Error::InvariantViolation("No local IP".to_string()))?];
         }
+        self.verify_addresses()?;
 
         self.prepare_identity();
         Ok(())
     }
 
+    /// Addresses to advertise to other peers, the listen addresses unless `advertised_addresses`
+    /// are configured
+    pub fn advertised_addresses(&self) -> &[NetworkAddress] {
+        if self.advertised_addresses.is_empty() {
+            &self.listen_addresses
+        } else {
+            &self.advertised_addresses
+        }
+    }
+
+    /// Verifies the listen and advertised addresses, which the network can't reject later on
+    pub fn verify_addresses(&self) -> Result<(), Error> {
+        for (kind, addrs) in [
+            ("listen", &self.listen_addresses),
+            ("advertised", &self.advertised_addresses),
+        ] {
+            crate::config::invariant(
+                addrs.iter().all(|addr| !addr.as_slice().is_empty()),
+                format!("{} network has an empty {} address", self.network_id, kind),
+            )?;
+        }
+        let is_memory = |addr: &NetworkAddress| matches!(addr.as_slice(), [Protocol::Memory(_)]);
+        crate::config::invariant(
+            self.listen_addresses.iter().all(is_memory)
+                || !self.listen_addresses.iter().any(is_memory),
+            format!(
+                "{} network can't listen on both memory and socket addresses",
+                self.network_id
+            ),
+        )?;
+        Self::verify_unique_addresses(&self.network_id, "listen", &self.listen_addresses)?;
+
+        Self::verify_unique_addresses(&self.network_id, "advertised", &self.advertised_addresses)?;
+        for addr in &self.advertised_addresses {
+            let is_unspecified = addr.as_slice().iter().any(|protocol| match protocol {
+                Protocol::Ip4(ip) => ip.is_unspecified(),
+                Protocol::Ip6(ip) => ip.is_unspecified(),
+                _ => false,
+            });
+            crate::config::invariant(
+                !is_unspecified,
+                format!(
+                    "{} network advertises the unspecified address '{}', which peers can't dial",
+                    self.network_id, addr
+                ),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn verify_unique_addresses(
+        network_id: &NetworkId,
+        kind: &str,
+        addrs: &[NetworkAddress],
+    ) -> Result<(), Error> {
+        let unique_addrs: HashSet<_> = addrs.iter().collect();
+        crate::config::invariant(
+            unique_addrs.len() == addrs.len(),
+            format!("{} network has duplicate {} addresses", network_id, kind),
+        )
+    }
+
     pub fn peer_id(&self) -> PeerId {
         match &self.identity {
             Identity::FromConfig(config) => Some(config.peer_id),
@@ -299,6 +373,25 @@ impl NetworkConfig {
     }
 }
 
+/// Deserializes a list of addresses, or a single one as configs did before multiple addresses
+/// were supported
+fn deserialize_addresses<'de, D>(deserializer: D) -> Result<Vec<NetworkAddress>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Addresses {
+        One(NetworkAddress),
+        Many(Vec<NetworkAddress>),
+    }
+
+    Ok(match Addresses::deserialize(deserializer)? {
+        Addresses::One(addr) => vec![addr],
+        Addresses::Many(addrs) => addrs,
+    })
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DiscoveryMethod {
@@ -454,3 +547,56 @@ impl Peer {
         Peer::new(addresses, keys, role)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_listen_address_compatibility() {
+        let config: NetworkConfig =
+            serde_yaml::from_str("listen_address: \"/ip4/0.0.0.0/tcp/6180\"").unwrap();
+        assert_eq!(
+            config.listen_addresses,
+            vec!["/ip4/0.0.0.0/tcp/6180".parse::<NetworkAddress>().unwrap()]
+        );
+
+        let config: NetworkConfig = serde_yaml::from_str(
+            "listen_addresses: [\"/ip4/0.0.0.0/tcp/6180\", \"/ip6/::/tcp/6181\"]",
+        )
+        .unwrap();
+        assert_eq!(config.listen_addresses.len(), 2);
+        assert_eq!(config.advertised_addresses(), &config.listen_addresses[..]);
+    }
+
+    #[test]
+    fn test_verify_addresses() {
+        let addr = |addr: &str| addr.parse::<NetworkAddress>().unwrap();
+        let mut config = NetworkConfig::default();
+        config.listen_addresses = vec![addr("/ip4/0.0.0.0/tcp/6180"), addr("/ip6/::/tcp/6181")];
+        config.advertised_addresses = vec![addr("/dns4/example.com/tcp/6180")];
+        config.verify_addresses().unwrap();
+        assert_eq!(
+            config.advertised_addresses(),
+            &config.advertised_addresses[..]
+        );
+
+        let mut duplicate_listen_addresses = config.clone();
+        duplicate_listen_addresses
+            .listen_addresses
+            .push(addr("/ip4/0.0.0.0/tcp/6180"));
+        duplicate_listen_addresses.verify_addresses().unwrap_err();
+
+        let mut mixed_transports = config.clone();
+        mixed_transports.listen_addresses.push(addr("/memory/1234"));
+        mixed_transports.verify_addresses().unwrap_err();
+
+        let mut unspecified_advertised_address = config;
+        unspecified_advertised_address
+            .advertised_addresses
+            .push(addr("/ip4/0.0.0.0/tcp/6180"));
+        unspecified_advertised_address
+            .verify_addresses()
+            .unwrap_err();
+    }
+}
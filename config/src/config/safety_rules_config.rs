@@ -27,6 +27,8 @@ pub struct SafetyRulesConfig {
     pub network_timeout_ms: u64,
     pub enable_cached_safety_data: bool,
     pub decoupled_execution: bool,
+    // If set, every signing decision is appended to a hash-chained audit log at this path.
+    pub audit_log_path: Option<PathBuf>,
 }
 
 impl Default for SafetyRulesConfig {
@@ -42,6 +44,7 @@ impl Default for SafetyRulesConfig {
             network_timeout_ms: 30_000,
             enable_cached_safety_data: true,
             decoupled_execution: false,
+            audit_log_path: None,
         }
     }
 }
@@ -6,8 +6,12 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StateSyncConfig {
+    // How a full node with nothing but genesis in storage catches up with the network
+    pub bootstrapping_mode: BootstrappingMode,
     // Size of chunk to request for state synchronization
     pub chunk_limit: u64,
+    // How a node syncs the chunks of transactions that follow its waypoint
+    pub continuous_syncing_mode: ContinuousSyncingMode,
     // The timeout of the state sync client to process a commit notification (in milliseconds)
     pub client_commit_timeout_ms: u64,
     // default timeout used for long polling to remote peer
@@ -18,10 +22,17 @@ pub struct StateSyncConfig {
     pub max_timeout_ms: u64,
     // The timeout of the state sync coordinator to receive a commit ack from mempool (in milliseconds)
     pub mempool_commit_timeout_ms: u64,
+    // valid maximum number of accounts in a state snapshot chunk for sanity check
+    pub max_state_snapshot_chunk_limit: u64,
     // default timeout to make state sync progress by sending chunk requests to a certain number of networks
     // if no progress is made by sending chunk requests to a number of networks,
     // the next sync request will be multicasted, i.e. sent to more networks
     pub multicast_timeout_ms: u64,
+    // Number of accounts to request per state snapshot chunk when fast syncing
+    pub state_snapshot_chunk_limit: u64,
+    // The timeout after which an unanswered epoch ending ledger info or state snapshot request is
+    // sent again (in milliseconds)
+    pub state_snapshot_request_timeout_ms: u64,
     // The timeout for ensuring sync requests are making progress (i.e., the maximum time between
     // commits when processing a sync request).
     pub sync_request_timeout_ms: u64,
@@ -32,15 +43,42 @@ pub struct StateSyncConfig {
 impl Default for StateSyncConfig {
     fn default() -> Self {
         Self {
+            bootstrapping_mode: BootstrappingMode::ExecuteTransactions,
             chunk_limit: 1000,
+            continuous_syncing_mode: ContinuousSyncingMode::ExecuteTransactions,
             client_commit_timeout_ms: 5_000,
             long_poll_timeout_ms: 10_000,
             max_chunk_limit: 1000,
             max_timeout_ms: 120_000,
             mempool_commit_timeout_ms: 5_000,
+            max_state_snapshot_chunk_limit: 10_000,
             multicast_timeout_ms: 30_000,
+            state_snapshot_chunk_limit: 5_000,
+            state_snapshot_request_timeout_ms: 10_000,
             sync_request_timeout_ms: 60_000,
             tick_interval_ms: 100,
         }
     }
 }
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BootstrappingMode {
+    /// Fetch and execute every transaction since genesis.
+    ExecuteTransactions,
+    /// Restore the account state at the latest epoch ending version from a state snapshot served
+    /// by peers, then fetch and execute the transactions after it.
+    FastSyncStateSnapshot,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContinuousSyncingMode {
+    /// Fetch and execute the transactions.
+    ExecuteTransactions,
+    /// Fetch the transaction outputs and apply them directly, without re-executing the
+    /// transactions. The outputs are still verified against the proven transaction infos.
+    /// Peers only serve outputs they have write sets for (see
+    /// `StorageConfig::enable_write_set_storage`); otherwise the transactions are executed.
+    ApplyTransactionOutputs,
+}
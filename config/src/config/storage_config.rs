@@ -4,6 +4,7 @@
 use crate::utils;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
 };
@@ -11,11 +12,21 @@ use std::{
 /// Port selected RocksDB options for tuning underlying rocksdb instance of DiemDB.
 /// see https://github.com/facebook/rocksdb/blob/master/include/rocksdb/options.h
 /// for detailed explanations.
-#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct RocksdbConfig {
     pub max_open_files: i32,
     pub max_total_wal_size: u64,
+    /// Total size of the block caches, split among column families according to their
+    /// `block_cache_share`. None gives each column family the RocksDB default block cache.
+    pub block_cache_size: Option<u64>,
+    /// Collect RocksDB statistics, which block cache hit and miss metrics are reported from.
+    /// Off by default, since collecting them slows down every DB operation.
+    pub enable_statistics: bool,
+    /// Options of the column families not listed in `column_families`.
+    pub default_cf_config: RocksdbCfConfig,
+    /// Options by column family name, replacing `default_cf_config` for the listed families.
+    pub column_families: BTreeMap<String, RocksdbCfConfig>,
 }
 
 impl Default for RocksdbConfig {
@@ -28,10 +39,59 @@ impl Default for RocksdbConfig {
             // families are updated at non-uniform frequencies.
             #[allow(clippy::integer_arithmetic)] // TODO: remove once clippy lint fixed
             max_total_wal_size: 1u64 << 30,
+            block_cache_size: None,
+            enable_statistics: false,
+            default_cf_config: RocksdbCfConfig::default(),
+            column_families: BTreeMap::new(),
         }
     }
 }
 
+impl RocksdbConfig {
+    /// Options of the column family named `cf_name`.
+    pub fn cf_config(&self, cf_name: &str) -> &RocksdbCfConfig {
+        self.column_families
+            .get(cf_name)
+            .unwrap_or(&self.default_cf_config)
+    }
+}
+
+/// RocksDB options of a column family.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RocksdbCfConfig {
+    pub compression: RocksdbCompression,
+    /// Weight of the column family when splitting `RocksdbConfig::block_cache_size`.
+    pub block_cache_share: u32,
+    /// Bits per key of the bloom filters of the column family's SST files. None disables bloom
+    /// filters.
+    pub bloom_filter_bits_per_key: Option<u32>,
+    /// Size of a memtable of the column family, in bytes.
+    pub write_buffer_size: u64,
+}
+
+impl Default for RocksdbCfConfig {
+    fn default() -> Self {
+        Self {
+            compression: RocksdbCompression::Lz4,
+            block_cache_share: 1,
+            bloom_filter_bits_per_key: None,
+            // RocksDB default.
+            #[allow(clippy::integer_arithmetic)] // TODO: remove once clippy lint fixed
+            write_buffer_size: 64u64 << 20,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbCompression {
+    None,
+    Snappy,
+    Lz4,
+    Zstd,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StorageConfig {
@@ -48,6 +108,16 @@ pub struct StorageConfig {
     pub timeout_ms: u64,
     /// Rocksdb-specific configurations
     pub rocksdb_config: RocksdbConfig,
+    /// Index committed events by their type, which the `get_events_by_type` JSON-RPC method
+    /// requires. Only events committed while enabled are indexed.
+    pub enable_event_by_type_index: bool,
+    /// Store the write sets of committed transactions, which serving transaction outputs to
+    /// peers syncing with `ContinuousSyncingMode::ApplyTransactionOutputs` requires. Write sets
+    /// are pruned along with the state (see `prune_window`).
+    pub enable_write_set_storage: bool,
+    /// Absolute dir under which the `checkpoint` endpoint of the backup service creates DB
+    /// checkpoints. None disables the endpoint.
+    pub checkpoint_root: Option<PathBuf>,
 }
 
 impl Default for StorageConfig {
@@ -68,6 +138,9 @@ impl Default for StorageConfig {
             // Default read/write/connection timeout, in milliseconds
             timeout_ms: 30_000,
             rocksdb_config: RocksdbConfig::default(),
+            enable_event_by_type_index: false,
+            enable_write_set_storage: false,
+            checkpoint_root: None,
         }
     }
 }
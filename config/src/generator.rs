@@ -66,17 +66,21 @@ pub fn validator_swarm_for_testing(nodes: usize) -> ValidatorSwarm {
 /// and handshake protocol version.
 pub fn build_seed_for_network(seed_config: &NetworkConfig, seed_role: PeerRole) -> PeerSet {
     let seed_pubkey = mango_crypto::PrivateKey::public_key(&seed_config.identity_key());
-    let seed_addr = seed_config
-        .listen_address
-        .clone()
-        .append_prod_protos(seed_pubkey, HANDSHAKE_VERSION);
+    let seed_addrs = seed_config
+        .advertised_addresses()
+        .iter()
+        .map(|addr| {
+            addr.clone()
+                .append_prod_protos(seed_pubkey, HANDSHAKE_VERSION)
+        })
+        .collect();
 
     let mut keys = HashSet::new();
     keys.insert(seed_pubkey);
     let mut seeds = HashMap::default();
     seeds.insert(
         seed_config.peer_id(),
-        Peer::new(vec![seed_addr], keys, seed_role),
+        Peer::new(seed_addrs, keys, seed_role),
     );
     seeds
 }
@@ -12,10 +12,10 @@ use mango_infallible::duration_since_epoch;
 use mango_types::{
     account_address::AccountAddress,
     block_info::BlockInfo,
-    block_metadata::BlockMetadata,
+    block_metadata::{BlockMetadata, BlockMetadataExt},
     epoch_state::EpochState,
     ledger_info::LedgerInfo,
-    transaction::{Transaction, Version},
+    transaction::{SignedTransaction, Transaction, Version},
     validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
 };
@@ -93,6 +93,10 @@ impl Block {
         self.block_data.payload()
     }
 
+    pub fn failed_authors(&self) -> Option<&Vec<(Round, Author)>> {
+        self.block_data.failed_authors()
+    }
+
     pub fn quorum_cert(&self) -> &QuorumCert {
         self.block_data.quorum_cert()
     }
@@ -190,6 +194,7 @@ impl Block {
         let block_data = BlockData::new_proposal(
             payload,
             validator_signer.author(),
+            vec![],
             round,
             timestamp_usecs,
             quorum_cert,
@@ -217,23 +222,52 @@ impl Block {
         }
     }
 
-    /// Verifies that the proposal and the QC are correctly signed.
-    /// If this is the genesis block, we skip these checks.
+    /// Verifies that the proposal, the QC and the proofs of store of the payload are correctly
+    /// signed. If this is the genesis block, we skip these checks.
     pub fn validate_signature(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
         match self.block_data.block_type() {
             BlockType::Genesis => bail!("We should not accept genesis from others"),
             BlockType::NilBlock => self.quorum_cert().verify(validator),
-            BlockType::Proposal { author, .. } => {
+            BlockType::Proposal { .. } | BlockType::ProposalExt(_) => {
+                let author = self.author().expect("Proposals have an author");
                 let signature = self
                     .signature
                     .as_ref()
                     .ok_or_else(|| format_err!("Missing signature in Proposal"))?;
-                validator.verify(*author, &self.block_data, signature)?;
+                validator.verify(author, &self.block_data, signature)?;
+                if let Some(Payload::InQuorumStore(proofs)) = self.payload() {
+                    for proof in proofs {
+                        ensure!(
+                            proof.info().epoch() == self.epoch(),
+                            "{} is not from the block's epoch",
+                            proof
+                        );
+                        proof.verify(validator)?;
+                    }
+                }
                 self.quorum_cert().verify(validator)
             }
         }
     }
 
+    /// Makes sure the payload is of the kind the epoch's on-chain consensus config asks for:
+    /// proofs of store if the quorum store is enabled, inline transactions otherwise. Empty
+    /// payloads, such as the ones of reconfiguration suffixes, are accepted either way.
+    pub fn verify_payload_type(&self, quorum_store_enabled: bool) -> anyhow::Result<()> {
+        match self.payload() {
+            Some(payload) if payload.is_empty() => Ok(()),
+            Some(Payload::DirectMempool(_)) if quorum_store_enabled => bail!(
+                "Block {} carries transactions inline while the quorum store is enabled",
+                self.id()
+            ),
+            Some(Payload::InQuorumStore(_)) if !quorum_store_enabled => bail!(
+                "Block {} references batches while the quorum store is disabled",
+                self.id()
+            ),
+            _ => Ok(()),
+        }
+    }
+
     /// Makes sure that the proposal makes sense, independently of the current state.
     /// If this is the genesis block, we skip these checks.
     pub fn verify_well_formed(&self) -> anyhow::Result<()> {
@@ -250,6 +284,16 @@ impl Block {
             parent.epoch() == self.epoch(),
             "block's parent should be in the same epoch"
         );
+        if let Some(failed_authors) = self.failed_authors() {
+            let mut last_round = parent.round();
+            for (round, _) in failed_authors {
+                ensure!(
+                    last_round < *round && *round < self.round(),
+                    "Failed authors must be in increasing rounds between the parent and the block"
+                );
+                last_round = *round;
+            }
+        }
         if parent.has_reconfiguration() {
             ensure!(
                 self.payload().map_or(true, |p| p.is_empty()),
@@ -288,15 +332,40 @@ impl Block {
         Ok(())
     }
 
-    pub fn transactions_to_execute(&self) -> Vec<Transaction> {
-        std::iter::once(Transaction::BlockMetadata(self.into()))
-            .chain(
-                self.payload()
-                    .unwrap_or(&Vec::new())
-                    .iter()
-                    .cloned()
-                    .map(Transaction::UserTransaction),
-            )
+    /// `validators` are the ordered account addresses of the validators of the block's epoch,
+    /// used to resolve the voters of an aggregate signature.
+    pub fn new_block_metadata(&self, validators: &[AccountAddress]) -> BlockMetadata {
+        BlockMetadata::new(
+            self.id(),
+            self.round(),
+            self.timestamp_usecs(),
+            // an ordered vector of voters' account address
+            self.quorum_cert().ledger_info().get_voters(validators),
+            // For nil block, we use 0x0 which is convention for nil address in move.
+            self.author().unwrap_or(AccountAddress::ZERO),
+        )
+    }
+
+    /// `txns` are the user transactions of the payload: the inline ones for a direct mempool
+    /// payload, the ones of the referenced batches, in order, for a quorum store payload.
+    /// The block metadata only takes the extended layout when the block records failed authors.
+    pub fn transactions_to_execute(
+        &self,
+        validators: &[AccountAddress],
+        txns: Vec<SignedTransaction>,
+    ) -> Vec<Transaction> {
+        let block_metadata = self.new_block_metadata(validators);
+        let block_metadata_txn = match self.failed_authors() {
+            Some(failed_authors) if !failed_authors.is_empty() => {
+                Transaction::BlockMetadataExt(BlockMetadataExt::new(
+                    block_metadata,
+                    failed_authors.iter().map(|(_, author)| *author).collect(),
+                ))
+            }
+            _ => Transaction::BlockMetadata(block_metadata),
+        };
+        std::iter::once(block_metadata_txn)
+            .chain(txns.into_iter().map(Transaction::UserTransaction))
             .collect()
     }
 }
@@ -325,23 +394,3 @@ impl<'de> Deserialize<'de> for Block {
         })
     }
 }
-
-impl From<&Block> for BlockMetadata {
-    fn from(block: &Block) -> Self {
-        Self::new(
-            block.id(),
-            block.round(),
-            block.timestamp_usecs(),
-            // an ordered vector of voters' account address
-            block
-                .quorum_cert()
-                .ledger_info()
-                .signatures()
-                .keys()
-                .cloned()
-                .collect(),
-            // For nil block, we use 0x0 which is convention for nil address in move.
-            block.author().unwrap_or(AccountAddress::ZERO),
-        )
-    }
-}
@@ -16,10 +16,14 @@ use mirai_annotations::*;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+/// Variants are only ever appended: blocks are persisted in consensusdb and their id is the hash
+/// of their BCS serialization, so the layout of an existing variant must not change.
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum BlockType {
+    /// A proposal carrying its transactions inline, without failed authors.
     Proposal {
-        /// T of the block (e.g. one or more transaction(s)
+        /// T of the block (e.g. one or more transaction(s), always `Payload::DirectMempool`
+        #[serde(with = "direct_mempool_payload")]
         payload: Payload,
         /// Author of the block that can be validated by the author's public key and the signature
         author: Author,
@@ -32,8 +36,67 @@ pub enum BlockType {
     /// from the previous epoch.  The genesis block is used as the the first root block of the
     /// BlockTree for all epochs.
     Genesis,
+    /// A proposal whose payload may reference quorum store batches, or that reports failed
+    /// authors.
+    ProposalExt(ProposalExt),
 }
 
+/// The versioned content of `BlockType::ProposalExt`.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum ProposalExt {
+    V0 {
+        payload: Payload,
+        author: Author,
+        /// Proposers of the rounds between the parent block and this one that failed to produce
+        /// a certified block, in increasing round order.
+        failed_authors: Vec<(Round, Author)>,
+    },
+}
+
+impl ProposalExt {
+    pub fn payload(&self) -> &Payload {
+        match self {
+            ProposalExt::V0 { payload, .. } => payload,
+        }
+    }
+
+    pub fn author(&self) -> Author {
+        match self {
+            ProposalExt::V0 { author, .. } => *author,
+        }
+    }
+
+    pub fn failed_authors(&self) -> &Vec<(Round, Author)> {
+        match self {
+            ProposalExt::V0 { failed_authors, .. } => failed_authors,
+        }
+    }
+}
+
+/// Serializes a `Payload::DirectMempool` as the bare transaction vector, the layout proposals had
+/// before payloads could reference quorum store batches.
+mod direct_mempool_payload {
+    use crate::common::Payload;
+    use mango_types::transaction::SignedTransaction;
+    use serde::{ser::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(payload: &Payload, serializer: S) -> Result<S::Ok, S::Error> {
+        match payload {
+            Payload::DirectMempool(txns) => txns.serialize(serializer),
+            Payload::InQuorumStore(_) => Err(S::Error::custom(
+                "Quorum store payloads require BlockType::ProposalExt",
+            )),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Payload, D::Error> {
+        Vec::<SignedTransaction>::deserialize(deserializer).map(Payload::DirectMempool)
+    }
+}
+
+/// Failed authors of the proposals that can't report any.
+const NO_FAILED_AUTHORS: &Vec<(Round, Author)> = &Vec::new();
+
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, CryptoHasher, BCSCryptoHash)]
 /// Block has the core data of a consensus block that should be persistent when necessary.
 /// Each block must know the id of its parent and keep the QuorurmCertificate to that parent.
@@ -69,10 +132,10 @@ pub struct BlockData {
 
 impl BlockData {
     pub fn author(&self) -> Option<Author> {
-        if let BlockType::Proposal { author, .. } = self.block_type {
-            Some(author)
-        } else {
-            None
+        match &self.block_type {
+            BlockType::Proposal { author, .. } => Some(*author),
+            BlockType::ProposalExt(ext) => Some(ext.author()),
+            BlockType::NilBlock | BlockType::Genesis => None,
         }
     }
 
@@ -89,10 +152,18 @@ impl BlockData {
     }
 
     pub fn payload(&self) -> Option<&Payload> {
-        if let BlockType::Proposal { payload, .. } = &self.block_type {
-            Some(payload)
-        } else {
-            None
+        match &self.block_type {
+            BlockType::Proposal { payload, .. } => Some(payload),
+            BlockType::ProposalExt(ext) => Some(ext.payload()),
+            BlockType::NilBlock | BlockType::Genesis => None,
+        }
+    }
+
+    pub fn failed_authors(&self) -> Option<&Vec<(Round, Author)>> {
+        match &self.block_type {
+            BlockType::Proposal { .. } => Some(NO_FAILED_AUTHORS),
+            BlockType::ProposalExt(ext) => Some(ext.failed_authors()),
+            BlockType::NilBlock | BlockType::Genesis => None,
         }
     }
 
@@ -185,19 +256,32 @@ impl BlockData {
         }
     }
 
+    /// Uses the original proposal layout whenever it can express the proposal, so that blocks
+    /// without quorum store batches or failed authors keep their encoding.
     pub fn new_proposal(
         payload: Payload,
         author: Author,
+        failed_authors: Vec<(Round, Author)>,
         round: Round,
         timestamp_usecs: u64,
         quorum_cert: QuorumCert,
     ) -> Self {
+        let block_type = match payload {
+            Payload::DirectMempool(_) if failed_authors.is_empty() => {
+                BlockType::Proposal { payload, author }
+            }
+            _ => BlockType::ProposalExt(ProposalExt::V0 {
+                payload,
+                author,
+                failed_authors,
+            }),
+        };
         Self {
             epoch: quorum_cert.certified_block().epoch(),
             round,
             timestamp_usecs,
             quorum_cert,
-            block_type: BlockType::Proposal { payload, author },
+            block_type,
         }
     }
 
@@ -232,7 +316,13 @@ fn test_reconfiguration_suffix() {
             BTreeMap::new(),
         ),
     );
-    let reconfig_suffix_block =
-        BlockData::new_proposal(vec![], AccountAddress::random(), 2, 2, quorum_cert);
+    let reconfig_suffix_block = BlockData::new_proposal(
+        Payload::empty(),
+        AccountAddress::random(),
+        vec![],
+        2,
+        2,
+        quorum_cert,
+    );
     assert!(reconfig_suffix_block.is_reconfiguration_suffix());
 }
@@ -6,10 +6,17 @@ use crate::{
         block_test_utils::{certificate_for_genesis, *},
         Block,
     },
+    block_data::{BlockData, BlockType, ProposalExt},
+    common::{Author, Payload},
+    proof_of_store::{BatchInfo, ProofOfStore},
     quorum_cert::QuorumCert,
 };
 use mango_crypto::hash::HashValue;
-use mango_types::{validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier};
+use mango_types::{
+    ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction,
+    validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier,
+};
+use serde::Serialize;
 use std::{collections::BTreeMap, sync::Arc};
 
 #[test]
@@ -43,7 +50,7 @@ fn test_nil_block() {
     assert!(nil_block.verify_well_formed().is_ok());
 
     let signer = ValidatorSigner::random(None);
-    let payload = vec![];
+    let payload = Payload::empty();
     let parent_block_info = nil_block.quorum_cert().certified_block();
     let nil_block_qc = gen_test_certificate(
         vec![&signer],
@@ -78,7 +85,7 @@ fn test_block_relation() {
     // Test genesis and the next block
     let genesis_block = Block::make_genesis_block();
     let quorum_cert = certificate_for_genesis();
-    let payload = vec![];
+    let payload = Payload::empty();
     let next_block = Block::new_proposal(
         payload.clone(),
         1,
@@ -105,7 +112,7 @@ fn test_same_qc_different_authors() {
     let signer = ValidatorSigner::random(None);
     let genesis_qc = certificate_for_genesis();
     let round = 1;
-    let payload = vec![];
+    let payload = Payload::empty();
     let current_timestamp = mango_infallible::duration_since_epoch().as_micros() as u64;
     let block_round_1 = Block::new_proposal(
         payload.clone(),
@@ -116,8 +123,10 @@ fn test_same_qc_different_authors() {
     );
 
     let signature = signer.sign(genesis_qc.ledger_info().ledger_info());
-    let mut ledger_info_altered = genesis_qc.ledger_info().clone();
-    ledger_info_altered.add_signature(signer.author(), signature);
+    let ledger_info_altered = LedgerInfoWithSignatures::new(
+        genesis_qc.ledger_info().ledger_info().clone(),
+        std::iter::once((signer.author(), signature)).collect(),
+    );
     let genesis_qc_altered = QuorumCert::new(genesis_qc.vote_data().clone(), ledger_info_altered);
 
     let block_round_1_altered = Block::new_proposal(
@@ -134,3 +143,72 @@ fn test_same_qc_different_authors() {
     assert!(block_round_1.id() != block_round_1_altered.id());
     assert_eq!(block_round_1.id(), block_round_1_same.id());
 }
+
+#[test]
+fn test_proposal_layouts() {
+    // The block type as persisted before quorum store payloads and failed authors.
+    #[derive(Serialize)]
+    enum LegacyBlockType {
+        Proposal {
+            payload: Vec<SignedTransaction>,
+            author: Author,
+        },
+    }
+
+    let signer = ValidatorSigner::random(None);
+    let author = signer.author();
+    let legacy = bcs::to_bytes(&LegacyBlockType::Proposal {
+        payload: vec![],
+        author,
+    })
+    .unwrap();
+    let block_type: BlockType = bcs::from_bytes(&legacy).unwrap();
+    assert_eq!(
+        block_type,
+        BlockType::Proposal {
+            payload: Payload::empty(),
+            author
+        }
+    );
+    assert_eq!(bcs::to_bytes(&block_type).unwrap(), legacy);
+
+    let qc = certificate_for_genesis();
+    let timestamp = mango_infallible::duration_since_epoch().as_micros() as u64;
+    let direct =
+        BlockData::new_proposal(Payload::empty(), author, vec![], 1, timestamp, qc.clone());
+    assert!(matches!(direct.block_type(), BlockType::Proposal { .. }));
+    assert_eq!(direct.failed_authors(), Some(&vec![]));
+
+    let failed_authors = vec![(1, author)];
+    let with_failed_authors = BlockData::new_proposal(
+        Payload::empty(),
+        author,
+        failed_authors.clone(),
+        2,
+        timestamp,
+        qc.clone(),
+    );
+    assert!(matches!(
+        with_failed_authors.block_type(),
+        BlockType::ProposalExt(ProposalExt::V0 { .. })
+    ));
+    assert_eq!(with_failed_authors.failed_authors(), Some(&failed_authors));
+
+    // A quorum store payload can't be expressed with the original layout.
+    let info = BatchInfo::new(author, 1, 0, HashValue::random(), 1);
+    let payload = Payload::InQuorumStore(vec![ProofOfStore::new(info, BTreeMap::new())]);
+    let in_quorum_store = Block::new_proposal(payload.clone(), 1, timestamp, qc.clone(), &signer);
+    assert!(matches!(
+        in_quorum_store.block_data().block_type(),
+        BlockType::ProposalExt(_)
+    ));
+    let decoded: Block = bcs::from_bytes(&bcs::to_bytes(&in_quorum_store).unwrap()).unwrap();
+    assert_eq!(decoded.payload(), Some(&payload));
+
+    // The payload must match the on-chain quorum store flag, empty payloads match both.
+    assert!(in_quorum_store.verify_payload_type(true).is_ok());
+    assert!(in_quorum_store.verify_payload_type(false).is_err());
+    let empty = Block::new_proposal(Payload::empty(), 1, timestamp, qc, &signer);
+    assert!(empty.verify_payload_type(true).is_ok());
+    assert!(empty.verify_payload_type(false).is_ok());
+}
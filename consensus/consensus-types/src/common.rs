@@ -1,7 +1,11 @@
 // Copyright (c) The Mango Blockchain Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::proof_of_store::ProofOfStore;
+use mango_crypto::HashValue;
 use mango_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
 
 /// The round of a block is a consensus-internal counter, which starts with 0 and increases
 /// monotonically. It is used for the protocol safety and liveness (please see the detailed
@@ -11,4 +15,67 @@ pub type Round = u64;
 pub type Author = AccountAddress;
 
 /// The payload in block.
-pub type Payload = Vec<SignedTransaction>;
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Payload {
+    /// The transactions themselves, pulled from mempool by the proposer.
+    DirectMempool(Vec<SignedTransaction>),
+    /// Proofs of store of batches disseminated ahead of the proposal, the transactions are
+    /// fetched from the quorum store before execution.
+    InQuorumStore(Vec<ProofOfStore>),
+}
+
+impl Payload {
+    pub fn empty() -> Self {
+        Payload::DirectMempool(vec![])
+    }
+
+    /// Number of transactions carried or referenced by the payload.
+    pub fn len(&self) -> usize {
+        match self {
+            Payload::DirectMempool(txns) => txns.len(),
+            Payload::InQuorumStore(proofs) => proofs
+                .iter()
+                .map(|proof| proof.info().num_txns() as usize)
+                .sum(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Payload::DirectMempool(txns) => txns.is_empty(),
+            Payload::InQuorumStore(proofs) => proofs.is_empty(),
+        }
+    }
+
+    /// The transactions carried inline, empty for payloads referencing batches.
+    pub fn inline_transactions(&self) -> &[SignedTransaction] {
+        match self {
+            Payload::DirectMempool(txns) => txns,
+            Payload::InQuorumStore(_) => &[],
+        }
+    }
+
+    /// The digests of the batches referenced by the payload.
+    pub fn batch_digests(&self) -> Vec<HashValue> {
+        match self {
+            Payload::DirectMempool(_) => vec![],
+            Payload::InQuorumStore(proofs) => proofs.iter().map(|proof| proof.digest()).collect(),
+        }
+    }
+}
+
+impl Display for Payload {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Payload::DirectMempool(txns) => write!(f, "DirectMempool: [txns: {}]", txns.len()),
+            Payload::InQuorumStore(proofs) => {
+                write!(
+                    f,
+                    "InQuorumStore: [batches: {}, txns: {}]",
+                    proofs.len(),
+                    self.len()
+                )
+            }
+        }
+    }
+}
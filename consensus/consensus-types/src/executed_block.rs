@@ -10,9 +10,10 @@ use crate::{
 use executor_types::StateComputeResult;
 use mango_crypto::hash::HashValue;
 use mango_types::{
+    account_address::AccountAddress,
     block_info::BlockInfo,
     contract_event::ContractEvent,
-    transaction::{Transaction, TransactionStatus},
+    transaction::{SignedTransaction, Transaction, TransactionStatus},
 };
 use std::fmt::{Debug, Display, Formatter};
 
@@ -104,13 +105,17 @@ impl ExecutedBlock {
         }
     }
 
-    pub fn transactions_to_commit(&self) -> Vec<Transaction> {
+    pub fn transactions_to_commit(
+        &self,
+        validators: &[AccountAddress],
+        txns: Vec<SignedTransaction>,
+    ) -> Vec<Transaction> {
         // reconfiguration suffix don't execute
         if self.block.block_data().is_reconfiguration_suffix() {
             return vec![];
         }
         itertools::zip_eq(
-            self.block.transactions_to_execute(),
+            self.block.transactions_to_execute(validators, txns),
             self.state_compute_result.compute_status(),
         )
         .filter_map(|(txn, status)| match status {
@@ -0,0 +1,304 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::Author;
+use anyhow::{ensure, Context};
+use mango_crypto::{ed25519::Ed25519Signature, hash::DefaultHasher, HashValue};
+use mango_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use mango_types::{
+    transaction::SignedTransaction, validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+/// BatchInfo identifies a batch of transactions disseminated by `author` ahead of the proposals
+/// referencing it. This is what validators sign to certify they store the batch.
+#[derive(
+    Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize, CryptoHasher, BCSCryptoHash,
+)]
+pub struct BatchInfo {
+    author: Author,
+    epoch: u64,
+    /// Increases monotonically per author and epoch.
+    batch_id: u64,
+    digest: HashValue,
+    num_txns: u64,
+}
+
+impl BatchInfo {
+    pub fn new(
+        author: Author,
+        epoch: u64,
+        batch_id: u64,
+        digest: HashValue,
+        num_txns: u64,
+    ) -> Self {
+        Self {
+            author,
+            epoch,
+            batch_id,
+            digest,
+            num_txns,
+        }
+    }
+
+    pub fn author(&self) -> Author {
+        self.author
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn batch_id(&self) -> u64 {
+        self.batch_id
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.digest
+    }
+
+    pub fn num_txns(&self) -> u64 {
+        self.num_txns
+    }
+}
+
+impl Display for BatchInfo {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "BatchInfo: [author: {}, epoch: {}, batch_id: {}, digest: {}, num_txns: {}]",
+            self.author.short_str(),
+            self.epoch,
+            self.batch_id,
+            self.digest,
+            self.num_txns
+        )
+    }
+}
+
+/// A batch of transactions broadcast by its author.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Batch {
+    info: BatchInfo,
+    payload: Vec<SignedTransaction>,
+}
+
+impl Batch {
+    pub fn new(author: Author, epoch: u64, batch_id: u64, payload: Vec<SignedTransaction>) -> Self {
+        let info = BatchInfo::new(
+            author,
+            epoch,
+            batch_id,
+            Self::compute_digest(&payload),
+            payload.len() as u64,
+        );
+        Self { info, payload }
+    }
+
+    /// The digest of a batch is the hash of its serialized transactions.
+    pub fn compute_digest(payload: &[SignedTransaction]) -> HashValue {
+        let mut hasher = DefaultHasher::new(b"BatchPayload");
+        hasher.update(&bcs::to_bytes(payload).expect("Batch serialization can't fail"));
+        hasher.finish()
+    }
+
+    pub fn info(&self) -> &BatchInfo {
+        &self.info
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.info.digest()
+    }
+
+    pub fn payload(&self) -> &[SignedTransaction] {
+        &self.payload
+    }
+
+    pub fn into_payload(self) -> Vec<SignedTransaction> {
+        self.payload
+    }
+
+    /// Makes sure the batch info matches the transactions it carries.
+    pub fn verify(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.info.num_txns() == self.payload.len() as u64,
+            "Batch {} carries {} transactions",
+            self.info,
+            self.payload.len()
+        );
+        ensure!(
+            self.info.digest() == Self::compute_digest(&self.payload),
+            "Batch {} digest mismatch",
+            self.info
+        );
+        Ok(())
+    }
+}
+
+/// A validator's signature certifying it stores the batch.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SignedBatchInfo {
+    info: BatchInfo,
+    signer: Author,
+    signature: Ed25519Signature,
+}
+
+impl SignedBatchInfo {
+    pub fn new(info: BatchInfo, signer: Author, signature: Ed25519Signature) -> Self {
+        Self {
+            info,
+            signer,
+            signature,
+        }
+    }
+
+    pub fn sign(info: BatchInfo, validator_signer: &ValidatorSigner) -> Self {
+        let signature = validator_signer.sign(&info);
+        Self::new(info, validator_signer.author(), signature)
+    }
+
+    pub fn info(&self) -> &BatchInfo {
+        &self.info
+    }
+
+    pub fn signer(&self) -> Author {
+        self.signer
+    }
+
+    pub fn signature(&self) -> &Ed25519Signature {
+        &self.signature
+    }
+
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        validator
+            .verify(self.signer, &self.info, &self.signature)
+            .context("Failed to verify SignedBatchInfo")?;
+        Ok(())
+    }
+}
+
+/// A quorum of signatures over a batch info: the batch is available from at least one honest
+/// validator, so proposals can carry its digest instead of the transactions.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProofOfStore {
+    info: BatchInfo,
+    signatures: BTreeMap<Author, Ed25519Signature>,
+}
+
+impl ProofOfStore {
+    pub fn new(info: BatchInfo, signatures: BTreeMap<Author, Ed25519Signature>) -> Self {
+        Self { info, signatures }
+    }
+
+    pub fn info(&self) -> &BatchInfo {
+        &self.info
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.info.digest()
+    }
+
+    pub fn signatures(&self) -> &BTreeMap<Author, Ed25519Signature> {
+        &self.signatures
+    }
+
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        validator
+            .verify_aggregated_struct_signature(&self.info, &self.signatures)
+            .context("Failed to verify ProofOfStore")?;
+        Ok(())
+    }
+}
+
+impl Display for ProofOfStore {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "ProofOfStore: [{}, signers: {}]",
+            self.info,
+            self.signatures.len()
+        )
+    }
+}
+
+/// RPC request to fetch a batch the requester does not store.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BatchRequest {
+    digest: HashValue,
+}
+
+impl BatchRequest {
+    pub fn new(digest: HashValue) -> Self {
+        Self { digest }
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.digest
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum BatchResponse {
+    Batch(Batch),
+    NotFound(HashValue),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mango_types::validator_verifier::ValidatorConsensusInfo;
+
+    fn validators(count: usize) -> (Vec<ValidatorSigner>, ValidatorVerifier) {
+        let signers: Vec<_> = (0..count)
+            .map(|i| ValidatorSigner::random([i as u8; 32]))
+            .collect();
+        let infos = signers
+            .iter()
+            .map(|s| (s.author(), ValidatorConsensusInfo::new(s.public_key(), 1)))
+            .collect();
+        (signers, ValidatorVerifier::new(infos))
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let batch = Batch::new(Author::random(), 1, 0, vec![]);
+        assert!(batch.verify().is_ok());
+        assert_eq!(batch.info().num_txns(), 0);
+
+        let mut tampered = batch.clone();
+        tampered.info.num_txns = 1;
+        assert!(tampered.verify().is_err());
+    }
+
+    #[test]
+    fn test_proof_of_store_verify() {
+        let (signers, validators) = validators(4);
+        let batch = Batch::new(signers[0].author(), 1, 0, vec![]);
+
+        let signed = SignedBatchInfo::sign(batch.info().clone(), &signers[1]);
+        assert!(signed.verify(&validators).is_ok());
+        let forged = SignedBatchInfo::new(
+            batch.info().clone(),
+            signers[2].author(),
+            signed.signature().clone(),
+        );
+        assert!(forged.verify(&validators).is_err());
+
+        let mut signatures = BTreeMap::new();
+        for signer in &signers[..2] {
+            signatures.insert(signer.author(), signer.sign(batch.info()));
+        }
+        assert!(ProofOfStore::new(batch.info().clone(), signatures.clone())
+            .verify(&validators)
+            .is_err());
+        signatures.insert(signers[2].author(), signers[2].sign(batch.info()));
+        assert!(ProofOfStore::new(batch.info().clone(), signatures)
+            .verify(&validators)
+            .is_ok());
+    }
+}
@@ -115,7 +115,7 @@ impl QuorumCert {
                 "Genesis QC has inconsistent commit block with certified block"
             );
             ensure!(
-                self.ledger_info().signatures().is_empty(),
+                self.ledger_info().num_signers() == 0,
                 "Genesis QC should not carry signatures"
             );
             return Ok(());
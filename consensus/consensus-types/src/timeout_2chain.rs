@@ -205,15 +205,15 @@ fn test_2chain_timeout_certificate() {
     let quorum_size = validators.quorum_voting_power() as usize;
     let generate_quorum = |round, num_of_signature| {
         let vote_data = VoteData::new(BlockInfo::random(round), BlockInfo::random(0));
-        let mut ledger_info = LedgerInfoWithSignatures::new(
-            LedgerInfo::new(BlockInfo::empty(), vote_data.hash()),
-            BTreeMap::new(),
-        );
-        for signer in &signers[0..num_of_signature] {
-            let signature = signer.sign(ledger_info.ledger_info());
-            ledger_info.add_signature(signer.author(), signature);
-        }
-        QuorumCert::new(vote_data, ledger_info)
+        let ledger_info = LedgerInfo::new(BlockInfo::empty(), vote_data.hash());
+        let signatures: BTreeMap<_, _> = signers[0..num_of_signature]
+            .iter()
+            .map(|signer| (signer.author(), signer.sign(&ledger_info)))
+            .collect();
+        QuorumCert::new(
+            vote_data,
+            LedgerInfoWithSignatures::new(ledger_info, signatures),
+        )
     };
     let generate_timeout =
         |round, qc_round| TwoChainTimeout::new(1, round, generate_quorum(qc_round, quorum_size));
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{sync_info::SyncInfo, vote::Vote};
-use anyhow::ensure;
-use mango_crypto::HashValue;
+use anyhow::{ensure, Context};
+use mango_crypto::{bls12381::Bls12381Signature, HashValue};
 use mango_types::validator_verifier::ValidatorVerifier;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
@@ -18,6 +18,10 @@ pub struct VoteMsg {
     vote: Vote,
     /// Sync info carries information about highest QC, TC and LedgerInfo
     sync_info: SyncInfo,
+    /// The BLS12-381 share of the vote's LedgerInfo, only sent once aggregated signatures are
+    /// enabled in the on-chain consensus config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bls_signature: Option<Bls12381Signature>,
 }
 
 impl Display for VoteMsg {
@@ -28,7 +32,16 @@ impl Display for VoteMsg {
 
 impl VoteMsg {
     pub fn new(vote: Vote, sync_info: SyncInfo) -> Self {
-        Self { vote, sync_info }
+        Self {
+            vote,
+            sync_info,
+            bls_signature: None,
+        }
+    }
+
+    pub fn with_bls_signature(mut self, bls_signature: Bls12381Signature) -> Self {
+        self.bls_signature = Some(bls_signature);
+        self
     }
 
     /// Container for actual voting material
@@ -41,6 +54,11 @@ impl VoteMsg {
         &self.sync_info
     }
 
+    /// BLS12-381 share of the vote, if any
+    pub fn bls_signature(&self) -> Option<&Bls12381Signature> {
+        self.bls_signature.as_ref()
+    }
+
     pub fn epoch(&self) -> u64 {
         self.vote.epoch()
     }
@@ -63,6 +81,12 @@ impl VoteMsg {
         // We're not verifying SyncInfo here yet: we are going to verify it only in case we need
         // it. This way we avoid verifying O(n) SyncInfo messages while aggregating the votes
         // (O(n^2) signature verifications).
-        self.vote().verify(validator)
+        self.vote().verify(validator)?;
+        if let Some(bls_signature) = &self.bls_signature {
+            validator
+                .verify_bls(self.vote.author(), self.vote.ledger_info(), bls_signature)
+                .context("Failed to verify BLS signature of the vote")?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,696 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An append-only, hash-chained log of the signing decisions of SafetyRules. Every record carries
+//! the inputs the voting rules were checked against, the SafetyData persisted by the decision and
+//! the signature it released, so that the log can be replayed offline to show that no voting rule
+//! was violated. Every record is signed with the consensus key, which anchors the hash chain: the
+//! log can't be rewritten without the key.
+
+use crate::Error;
+use consensus_types::{
+    block::Block,
+    block_data::BlockData,
+    common::Round,
+    safety_data::SafetyData,
+    timeout::Timeout,
+    timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
+};
+use mango_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    hash::{CryptoHash, HashValue},
+    Signature,
+};
+use mango_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use mango_logger::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::{max, min},
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+/// The size of the chunks the tail of the log is read in when it's opened.
+const READ_CHUNK_SIZE: u64 = 4096;
+
+/// A signing decision along with the inputs the voting rules were checked against.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AuditDecision {
+    /// A vote for a proposed block.
+    Vote {
+        epoch: u64,
+        round: Round,
+        block_id: HashValue,
+        /// Round of the block certified by the QC of the proposal.
+        qc_round: Round,
+        /// Round of the parent of the certified block.
+        qc_parent_round: Round,
+        /// Round and highest QC round of the timeout certificate, 2-chain only.
+        timeout_cert: Option<(Round, Round)>,
+        two_chain: bool,
+    },
+    /// A signature of a proposal authored by this validator.
+    Proposal {
+        epoch: u64,
+        round: Round,
+        block_id: HashValue,
+        qc_round: Round,
+        qc_parent_round: Round,
+    },
+    /// A timeout of the round.
+    Timeout {
+        epoch: u64,
+        round: Round,
+        /// Round of the highest QC carried by the timeout, 2-chain only.
+        hqc_round: Option<Round>,
+        /// Round of the timeout certificate, 2-chain only.
+        timeout_cert_round: Option<Round>,
+    },
+}
+
+impl AuditDecision {
+    pub fn vote(
+        block: &Block,
+        timeout_cert: Option<&TwoChainTimeoutCertificate>,
+        two_chain: bool,
+    ) -> Self {
+        AuditDecision::Vote {
+            epoch: block.epoch(),
+            round: block.round(),
+            block_id: block.id(),
+            qc_round: block.quorum_cert().certified_block().round(),
+            qc_parent_round: block.quorum_cert().parent_block().round(),
+            timeout_cert: timeout_cert.map(|tc| (tc.round(), tc.highest_hqc_round())),
+            two_chain,
+        }
+    }
+
+    pub fn proposal(block_data: &BlockData) -> Self {
+        AuditDecision::Proposal {
+            epoch: block_data.epoch(),
+            round: block_data.round(),
+            block_id: block_data.hash(),
+            qc_round: block_data.quorum_cert().certified_block().round(),
+            qc_parent_round: block_data.quorum_cert().parent_block().round(),
+        }
+    }
+
+    pub fn timeout(timeout: &Timeout) -> Self {
+        AuditDecision::Timeout {
+            epoch: timeout.epoch(),
+            round: timeout.round(),
+            hqc_round: None,
+            timeout_cert_round: None,
+        }
+    }
+
+    pub fn timeout_2chain(
+        timeout: &TwoChainTimeout,
+        timeout_cert: Option<&TwoChainTimeoutCertificate>,
+    ) -> Self {
+        AuditDecision::Timeout {
+            epoch: timeout.epoch(),
+            round: timeout.round(),
+            hqc_round: Some(timeout.hqc_round()),
+            timeout_cert_round: timeout_cert.map(|tc| tc.round()),
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        match self {
+            AuditDecision::Vote { epoch, .. }
+            | AuditDecision::Proposal { epoch, .. }
+            | AuditDecision::Timeout { epoch, .. } => *epoch,
+        }
+    }
+
+    pub fn round(&self) -> Round {
+        match self {
+            AuditDecision::Vote { round, .. }
+            | AuditDecision::Proposal { round, .. }
+            | AuditDecision::Timeout { round, .. } => *round,
+        }
+    }
+}
+
+/// A record of the audit log, chained to the previous one through its hash.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, CryptoHasher, BCSCryptoHash)]
+pub struct AuditRecord {
+    index: u64,
+    prev_hash: HashValue,
+    decision: AuditDecision,
+    /// The SafetyData persisted once the decision was made.
+    safety_data: SafetyData,
+    /// The signature released for the decision.
+    signature: Ed25519Signature,
+}
+
+impl AuditRecord {
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    pub fn decision(&self) -> &AuditDecision {
+        &self.decision
+    }
+
+    pub fn safety_data(&self) -> &SafetyData {
+        &self.safety_data
+    }
+
+    pub fn signature(&self) -> &Ed25519Signature {
+        &self.signature
+    }
+}
+
+/// A line of the audit log file.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct AuditLogEntry {
+    record: AuditRecord,
+    hash: HashValue,
+    /// The consensus key the record is signed with.
+    public_key: Ed25519PublicKey,
+    record_signature: Ed25519Signature,
+}
+
+/// AuditLog appends the records as JSON lines and syncs them to disk before the signatures they
+/// record are released.
+pub struct AuditLog {
+    file: File,
+    next_index: u64,
+    last_hash: HashValue,
+}
+
+impl AuditLog {
+    /// Opens the log at the given path, resuming the hash chain of an existing log. Only the tail
+    /// of the log is read. A torn last line, left by a crash in the middle of an append, is
+    /// truncated: the signature it recorded was never released.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| Error::AuditLogError(e.to_string()))?;
+        let len = file
+            .metadata()
+            .map_err(|e| Error::AuditLogError(e.to_string()))?
+            .len();
+        let (end, last_line) =
+            read_last_line(&mut file).map_err(|e| Error::AuditLogError(e.to_string()))?;
+        if end < len {
+            warn!(
+                "Truncating a torn record of {} bytes at the end of the safety rules audit log {:?}",
+                len - end,
+                path
+            );
+            file.set_len(end)
+                .and_then(|_| file.sync_data())
+                .map_err(|e| Error::AuditLogError(e.to_string()))?;
+        }
+        let (next_index, last_hash) = match last_line {
+            Some(line) => {
+                let entry: AuditLogEntry = serde_json::from_slice(&line)?;
+                (entry.record.index + 1, entry.hash)
+            }
+            None => (0, HashValue::zero()),
+        };
+        Ok(Self {
+            file,
+            next_index,
+            last_hash,
+        })
+    }
+
+    /// The record of a decision, chained to the last record of the log.
+    pub fn next_record(
+        &self,
+        decision: AuditDecision,
+        safety_data: SafetyData,
+        signature: Ed25519Signature,
+    ) -> AuditRecord {
+        AuditRecord {
+            index: self.next_index,
+            prev_hash: self.last_hash,
+            decision,
+            safety_data,
+            signature,
+        }
+    }
+
+    /// Appends the next record, signed with the consensus key `public_key`.
+    pub fn append(
+        &mut self,
+        record: AuditRecord,
+        public_key: Ed25519PublicKey,
+        record_signature: Ed25519Signature,
+    ) -> Result<(), Error> {
+        if record.index != self.next_index || record.prev_hash != self.last_hash {
+            return Err(Error::AuditLogError(format!(
+                "Record {} doesn't follow the last record of the log",
+                record.index
+            )));
+        }
+        let hash = record.hash();
+        let mut line = serde_json::to_vec(&AuditLogEntry {
+            record,
+            hash,
+            public_key,
+            record_signature,
+        })?;
+        line.push(b'\n');
+        self.file
+            .write_all(&line)
+            .and_then(|_| self.file.sync_data())
+            .map_err(|e| Error::AuditLogError(e.to_string()))?;
+        self.next_index += 1;
+        self.last_hash = hash;
+        Ok(())
+    }
+}
+
+/// Reads the file backwards to find its last complete line, returning the length of the file up
+/// to the end of that line along with the line.
+fn read_last_line(file: &mut File) -> io::Result<(u64, Option<Vec<u8>>)> {
+    let mut offset = file.metadata()?.len();
+    let mut tail = vec![];
+    let mut end = None;
+    loop {
+        if end.is_none() {
+            end = tail
+                .iter()
+                .rposition(|b| *b == b'\n')
+                .map(|i| offset + i as u64 + 1);
+        }
+        match end {
+            Some(end) => {
+                let line_end = (end - 1 - offset) as usize;
+                if let Some(start) = tail[..line_end].iter().rposition(|b| *b == b'\n') {
+                    return Ok((end, Some(tail[start + 1..line_end].to_vec())));
+                }
+                if offset == 0 {
+                    return Ok((end, Some(tail[..line_end].to_vec())));
+                }
+            }
+            None if offset == 0 => return Ok((0, None)),
+            None => (),
+        }
+        let chunk_size = min(offset, READ_CHUNK_SIZE);
+        offset -= chunk_size;
+        let mut chunk = vec![0; chunk_size as usize];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&tail);
+        tail = chunk;
+    }
+}
+
+fn read_entries(path: &Path) -> Result<Vec<AuditLogEntry>, Error> {
+    let file = File::open(path).map_err(|e| Error::AuditLogError(e.to_string()))?;
+    BufReader::new(file)
+        .lines()
+        .map(|line| {
+            let line = line.map_err(|e| Error::AuditLogError(e.to_string()))?;
+            Ok(serde_json::from_str(&line)?)
+        })
+        .collect()
+}
+
+/// A voting rule violated by a record of the audit log.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Violation {
+    pub index: u64,
+    pub reason: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Record {}: {}", self.index, self.reason)
+    }
+}
+
+/// The outcome of replaying an audit log.
+#[derive(Debug)]
+pub struct AuditReport {
+    pub num_records: u64,
+    /// Hash of the last record, which commits to the whole log.
+    pub last_hash: HashValue,
+    /// The consensus keys the records are signed with, in order. They should be checked against
+    /// the keys the validator registered on-chain.
+    pub public_keys: Vec<Ed25519PublicKey>,
+    pub violations: Vec<Violation>,
+}
+
+/// The voting state rebuilt from the decisions of an epoch.
+#[derive(Default)]
+struct ReplayState {
+    epoch: u64,
+    last_voted_round: Round,
+    preferred_round: Round,
+    one_chain_round: Round,
+}
+
+impl ReplayState {
+    /// Checks the decision against the voting rules and applies it, returning the violated rule
+    /// if any.
+    fn apply(&mut self, decision: &AuditDecision) -> Option<String> {
+        match decision {
+            AuditDecision::Vote {
+                round,
+                qc_round,
+                qc_parent_round,
+                timeout_cert,
+                two_chain,
+                ..
+            } => {
+                let violation = if *round <= self.last_voted_round {
+                    Some(format!(
+                        "Voted in round {} after voting or timing out in round {}",
+                        round, self.last_voted_round
+                    ))
+                } else if !*two_chain && *qc_round < self.preferred_round {
+                    Some(format!(
+                        "Voted for a block certified in round {} below the preferred round {}",
+                        qc_round, self.preferred_round
+                    ))
+                } else if *two_chain
+                    && *round != qc_round + 1
+                    && !timeout_cert.map_or(false, |(tc_round, hqc_round)| {
+                        *round == tc_round + 1 && *qc_round >= hqc_round
+                    })
+                {
+                    Some(format!(
+                        "Voted in round {} for a block certified in round {} with timeout certificate {:?}",
+                        round, qc_round, timeout_cert
+                    ))
+                } else {
+                    None
+                };
+                self.last_voted_round = max(self.last_voted_round, *round);
+                self.one_chain_round = max(self.one_chain_round, *qc_round);
+                self.preferred_round = max(self.preferred_round, *qc_parent_round);
+                violation
+            }
+            AuditDecision::Proposal {
+                round, qc_round, ..
+            } => {
+                if *round <= self.last_voted_round {
+                    Some(format!(
+                        "Proposed in round {} after voting or timing out in round {}",
+                        round, self.last_voted_round
+                    ))
+                } else if *qc_round < self.preferred_round {
+                    Some(format!(
+                        "Proposed a block certified in round {} below the preferred round {}",
+                        qc_round, self.preferred_round
+                    ))
+                } else {
+                    None
+                }
+            }
+            AuditDecision::Timeout {
+                round,
+                hqc_round,
+                timeout_cert_round,
+                ..
+            } => {
+                let violation = if *round < self.last_voted_round {
+                    Some(format!(
+                        "Timed out in round {} after voting or timing out in round {}",
+                        round, self.last_voted_round
+                    ))
+                } else {
+                    match hqc_round {
+                        None if *round <= self.preferred_round => Some(format!(
+                            "Timed out in round {} not above the preferred round {}",
+                            round, self.preferred_round
+                        )),
+                        Some(hqc_round)
+                            if (*round != hqc_round + 1
+                                && Some(*round) != timeout_cert_round.map(|r| r + 1))
+                                || *hqc_round < self.one_chain_round =>
+                        {
+                            Some(format!(
+                                "Timed out in round {} with highest QC round {} and timeout certificate round {:?}, one-chain round {}",
+                                round, hqc_round, timeout_cert_round, self.one_chain_round
+                            ))
+                        }
+                        _ => None,
+                    }
+                };
+                self.last_voted_round = max(self.last_voted_round, *round);
+                violation
+            }
+        }
+    }
+
+    /// The persisted SafetyData can only make the rules stricter, e.g. when the log was enabled
+    /// in the middle of an epoch.
+    fn observe(&mut self, safety_data: &SafetyData) -> Option<String> {
+        let violation = if safety_data.epoch != self.epoch {
+            Some(format!(
+                "Persisted epoch {} doesn't match the decision epoch {}",
+                safety_data.epoch, self.epoch
+            ))
+        } else if safety_data.last_voted_round < self.last_voted_round {
+            Some(format!(
+                "Persisted last voted round {} is below the last voted round {}",
+                safety_data.last_voted_round, self.last_voted_round
+            ))
+        } else {
+            None
+        };
+        self.last_voted_round = max(self.last_voted_round, safety_data.last_voted_round);
+        self.preferred_round = max(self.preferred_round, safety_data.preferred_round);
+        self.one_chain_round = max(self.one_chain_round, safety_data.one_chain_round);
+        violation
+    }
+}
+
+/// Replays the audit log at the given path, checking its hash chain, the signatures of its
+/// records and that no decision violated the voting rules. A broken hash chain or an invalid
+/// signature is returned as an error: the records can't be trusted.
+pub fn verify_audit_log(path: &Path) -> Result<AuditReport, Error> {
+    let mut state = ReplayState::default();
+    let mut violations = vec![];
+    let mut public_keys: Vec<Ed25519PublicKey> = vec![];
+    let mut prev_hash = HashValue::zero();
+    let mut num_records = 0;
+
+    for entry in read_entries(path)? {
+        let record = &entry.record;
+        if record.index != num_records
+            || record.prev_hash != prev_hash
+            || record.hash() != entry.hash
+        {
+            return Err(Error::AuditLogError(format!(
+                "Hash chain broken at record {}",
+                num_records
+            )));
+        }
+        entry
+            .record_signature
+            .verify(record, &entry.public_key)
+            .map_err(|e| {
+                Error::AuditLogError(format!(
+                    "Invalid signature of record {}: {}",
+                    num_records, e
+                ))
+            })?;
+        if public_keys.last() != Some(&entry.public_key) {
+            public_keys.push(entry.public_key.clone());
+        }
+
+        let epoch = record.decision.epoch();
+        if epoch < state.epoch {
+            violations.push(Violation {
+                index: record.index,
+                reason: format!("Signed in epoch {} after epoch {}", epoch, state.epoch),
+            });
+        } else {
+            if epoch > state.epoch {
+                state = ReplayState {
+                    epoch,
+                    ..ReplayState::default()
+                };
+            }
+            for reason in state
+                .apply(&record.decision)
+                .into_iter()
+                .chain(state.observe(&record.safety_data))
+            {
+                violations.push(Violation {
+                    index: record.index,
+                    reason,
+                });
+            }
+        }
+
+        prev_hash = entry.hash;
+        num_records += 1;
+    }
+
+    Ok(AuditReport {
+        num_records,
+        last_hash: prev_hash,
+        public_keys,
+        violations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mango_temppath::TempPath;
+    use mango_types::validator_signer::ValidatorSigner;
+
+    fn vote(epoch: u64, round: Round, qc_round: Round) -> AuditDecision {
+        AuditDecision::Vote {
+            epoch,
+            round,
+            block_id: HashValue::random(),
+            qc_round,
+            qc_parent_round: qc_round.saturating_sub(1),
+            timeout_cert: None,
+            two_chain: true,
+        }
+    }
+
+    fn safety_data(epoch: u64, last_voted_round: Round) -> SafetyData {
+        SafetyData::new(epoch, last_voted_round, 0, 0, None)
+    }
+
+    fn append(
+        audit_log: &mut AuditLog,
+        signer: &ValidatorSigner,
+        decision: AuditDecision,
+        safety_data: SafetyData,
+    ) {
+        let signature = signer.sign(&HashValue::random());
+        let record = audit_log.next_record(decision, safety_data, signature);
+        let record_signature = signer.sign(&record);
+        audit_log
+            .append(record, signer.public_key(), record_signature)
+            .unwrap();
+    }
+
+    fn write_entries(path: &Path, entries: &[AuditLogEntry]) {
+        let lines: Vec<_> = entries
+            .iter()
+            .map(|entry| serde_json::to_string(entry).unwrap() + "\n")
+            .collect();
+        std::fs::write(path, lines.concat()).unwrap();
+    }
+
+    #[test]
+    fn test_audit_log_chain() {
+        let path = TempPath::new();
+        let signer = ValidatorSigner::from_int(0);
+        let mut audit_log = AuditLog::open(path.path()).unwrap();
+        append(&mut audit_log, &signer, vote(1, 1, 0), safety_data(1, 1));
+        append(&mut audit_log, &signer, vote(1, 2, 1), safety_data(1, 2));
+        drop(audit_log);
+
+        // the hash chain is resumed after a restart
+        let mut audit_log = AuditLog::open(path.path()).unwrap();
+        append(
+            &mut audit_log,
+            &signer,
+            AuditDecision::Timeout {
+                epoch: 1,
+                round: 3,
+                hqc_round: Some(2),
+                timeout_cert_round: None,
+            },
+            safety_data(1, 3),
+        );
+        append(&mut audit_log, &signer, vote(2, 1, 0), safety_data(2, 1));
+
+        let report = verify_audit_log(path.path()).unwrap();
+        assert_eq!(report.num_records, 4);
+        assert_eq!(report.public_keys, vec![signer.public_key()]);
+        assert!(report.violations.is_empty());
+    }
+
+    #[test]
+    fn test_audit_log_torn_tail() {
+        let path = TempPath::new();
+        let signer = ValidatorSigner::from_int(0);
+        let mut audit_log = AuditLog::open(path.path()).unwrap();
+        for round in 1..=3 {
+            append(
+                &mut audit_log,
+                &signer,
+                vote(1, round, round - 1),
+                safety_data(1, round),
+            );
+        }
+        drop(audit_log);
+        let len = std::fs::metadata(path.path()).unwrap().len();
+
+        // a crash in the middle of an append
+        let mut file = OpenOptions::new().append(true).open(path.path()).unwrap();
+        file.write_all(b"{\"record\":{\"index\":3").unwrap();
+        drop(file);
+
+        let mut audit_log = AuditLog::open(path.path()).unwrap();
+        assert_eq!(std::fs::metadata(path.path()).unwrap().len(), len);
+        append(&mut audit_log, &signer, vote(1, 4, 3), safety_data(1, 4));
+
+        let report = verify_audit_log(path.path()).unwrap();
+        assert_eq!(report.num_records, 4);
+        assert!(report.violations.is_empty());
+
+        // a crash before the first record was complete
+        let path = TempPath::new();
+        std::fs::write(path.path(), b"{\"record\"").unwrap();
+        let mut audit_log = AuditLog::open(path.path()).unwrap();
+        append(&mut audit_log, &signer, vote(1, 1, 0), safety_data(1, 1));
+        assert_eq!(verify_audit_log(path.path()).unwrap().num_records, 1);
+    }
+
+    #[test]
+    fn test_audit_log_tampered() {
+        let path = TempPath::new();
+        let signer = ValidatorSigner::from_int(0);
+        let mut audit_log = AuditLog::open(path.path()).unwrap();
+        append(&mut audit_log, &signer, vote(1, 1, 0), safety_data(1, 1));
+        append(&mut audit_log, &signer, vote(1, 2, 1), safety_data(1, 2));
+
+        let mut entries = read_entries(path.path()).unwrap();
+        entries[0].record.safety_data.last_voted_round = 5;
+        write_entries(path.path(), &entries);
+        assert!(verify_audit_log(path.path()).is_err());
+
+        // recomputing the hash chain requires the consensus key to sign the records again
+        let mut prev_hash = HashValue::zero();
+        for entry in &mut entries {
+            entry.record.prev_hash = prev_hash;
+            entry.hash = entry.record.hash();
+            prev_hash = entry.hash;
+        }
+        write_entries(path.path(), &entries);
+        assert!(verify_audit_log(path.path()).is_err());
+    }
+
+    #[test]
+    fn test_audit_log_violations() {
+        let path = TempPath::new();
+        let signer = ValidatorSigner::from_int(0);
+        let mut audit_log = AuditLog::open(path.path()).unwrap();
+        append(&mut audit_log, &signer, vote(1, 1, 0), safety_data(1, 1));
+        // equivocation
+        append(&mut audit_log, &signer, vote(1, 1, 0), safety_data(1, 1));
+        // the QC doesn't certify the previous round and there is no timeout certificate
+        append(&mut audit_log, &signer, vote(1, 3, 1), safety_data(1, 3));
+        // the persisted last voted round went backwards
+        append(&mut audit_log, &signer, vote(1, 4, 3), safety_data(1, 2));
+
+        let report = verify_audit_log(path.path()).unwrap();
+        assert_eq!(report.num_records, 4);
+        let indices: Vec<_> = report.violations.iter().map(|v| v.index).collect();
+        assert_eq!(indices, vec![1, 2, 3]);
+    }
+}
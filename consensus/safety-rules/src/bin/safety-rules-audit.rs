@@ -0,0 +1,40 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use anyhow::{ensure, Result};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "safety-rules-audit",
+    about = "Replays a safety rules audit log and checks that no voting rule was violated."
+)]
+struct Opt {
+    #[structopt(parse(from_os_str), help = "Path to the safety rules audit log.")]
+    audit_log: PathBuf,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+
+    let report = safety_rules::verify_audit_log(&opt.audit_log)?;
+    println!("Records: {}", report.num_records);
+    println!("Last hash: {}", report.last_hash);
+    for public_key in &report.public_keys {
+        println!("Signed by: {}", public_key);
+    }
+    for violation in &report.violations {
+        println!("Violation: {}", violation);
+    }
+
+    ensure!(
+        report.violations.is_empty(),
+        "Found {} voting rule violation(s).",
+        report.violations.len()
+    );
+    println!("No voting rule violations found.");
+    Ok(())
+}
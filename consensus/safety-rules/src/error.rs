@@ -51,6 +51,8 @@ pub enum Error {
     InconsistentExecutionResult(String, String),
     #[error("Invalid Ordered LedgerInfoWithSignatures: Empty or at least one of executed_state_id, version, or epoch_state are not dummy value: {0}")]
     InvalidOrderedLedgerInfo(String),
+    #[error("Audit log error: {0}")]
+    AuditLogError(String),
 }
 
 impl From<serde_json::Error> for Error {
@@ -6,6 +6,7 @@ use crate::serializer::SafetyRulesInput;
 use consensus_types::block::Block;
 use consensus_types::{
     block_data::{BlockData, BlockType},
+    common::Payload,
     quorum_cert::QuorumCert,
     timeout::Timeout,
     vote_data::VoteData,
@@ -96,8 +97,8 @@ prop_compose! {
         payload in prop::collection::vec(any::<SignedTransaction>(), 0..MAX_PROPOSAL_TRANSACTIONS),
     ) -> BlockType {
         BlockType::Proposal{
-            payload,
-            author
+            payload: Payload::DirectMempool(payload),
+            author,
         }
     }
 }
@@ -3,6 +3,7 @@
 
 #![forbid(unsafe_code)]
 
+mod audit_log;
 mod configurable_validator_signer;
 mod consensus_state;
 mod counters;
@@ -20,9 +21,13 @@ mod t_safety_rules;
 mod thread;
 
 pub use crate::{
-    consensus_state::ConsensusState, error::Error,
-    persistent_safety_storage::PersistentSafetyStorage, process::Process,
-    safety_rules::SafetyRules, safety_rules_manager::SafetyRulesManager,
+    audit_log::{verify_audit_log, AuditDecision, AuditLog, AuditRecord, AuditReport, Violation},
+    consensus_state::ConsensusState,
+    error::Error,
+    persistent_safety_storage::PersistentSafetyStorage,
+    process::Process,
+    safety_rules::SafetyRules,
+    safety_rules_manager::SafetyRulesManager,
     t_safety_rules::TSafetyRules,
 };
 
@@ -55,6 +55,8 @@ pub enum LogEntry {
     State,
     Waypoint,
     SignCommitVote,
+    SignVoteBls,
+    SignBatchInfo,
 }
 
 impl LogEntry {
@@ -75,6 +77,8 @@ impl LogEntry {
             LogEntry::State => "state",
             LogEntry::Waypoint => "waypoint",
             LogEntry::SignCommitVote => "sign_commit_vote",
+            LogEntry::SignVoteBls => "sign_vote_bls",
+            LogEntry::SignBatchInfo => "sign_batch_info",
         }
     }
 }
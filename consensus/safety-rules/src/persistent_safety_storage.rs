@@ -2,14 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit_log::{AuditDecision, AuditLog},
+    configurable_validator_signer::ConfigurableValidatorSigner,
     counters,
     logging::{self, LogEntry, LogEvent},
     Error,
 };
 use consensus_types::{common::Author, safety_data::SafetyData};
-use diem_global_constants::{CONSENSUS_KEY, EXECUTION_KEY, OWNER_ACCOUNT, SAFETY_DATA, WAYPOINT};
+use diem_global_constants::{
+    BLS_CONSENSUS_KEY, CONSENSUS_KEY, EXECUTION_KEY, OWNER_ACCOUNT, SAFETY_DATA, WAYPOINT,
+};
 use diem_secure_storage::{CryptoStorage, KVStorage, Storage};
 use mango_crypto::{
+    bls12381::Bls12381PrivateKey,
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
     hash::CryptoHash,
 };
@@ -25,10 +30,14 @@ use serde::Serialize;
 /// only ever be used by safety rules, we maintain an in-memory copy to avoid issuing reads
 /// to the internal storage if the SafetyData hasn't changed. On writes, we update the
 /// cache and internal storage.
+///
+/// When an audit log is attached, the signing decisions are appended to it along with the
+/// SafetyData they persisted.
 pub struct PersistentSafetyStorage {
     enable_cached_safety_data: bool,
     cached_safety_data: Option<SafetyData>,
     internal_store: Storage,
+    audit_log: Option<AuditLog>,
 }
 
 impl PersistentSafetyStorage {
@@ -56,6 +65,7 @@ impl PersistentSafetyStorage {
             enable_cached_safety_data,
             cached_safety_data: Some(safety_data),
             internal_store,
+            audit_log: None,
         }
     }
 
@@ -92,9 +102,15 @@ impl PersistentSafetyStorage {
             enable_cached_safety_data,
             cached_safety_data: None,
             internal_store,
+            audit_log: None,
         }
     }
 
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
     pub fn author(&self) -> Result<Author, Error> {
         let _timer = counters::start_timer("get", OWNER_ACCOUNT);
         Ok(self.internal_store.get(OWNER_ACCOUNT).map(|v| v.value)?)
@@ -110,6 +126,16 @@ impl PersistentSafetyStorage {
             .export_private_key_for_version(CONSENSUS_KEY, version)?)
     }
 
+    /// Returns the BLS12-381 consensus key used to sign vote shares for aggregated signatures.
+    /// Unlike the Ed25519 consensus key it is not versioned: operators rotate it on-chain.
+    pub fn bls_consensus_key(&self) -> Result<Bls12381PrivateKey, Error> {
+        let _timer = counters::start_timer("get", BLS_CONSENSUS_KEY);
+        Ok(self
+            .internal_store
+            .get(BLS_CONSENSUS_KEY)
+            .map(|v| v.value)?)
+    }
+
     pub fn execution_public_key(&self) -> Result<Ed25519PublicKey, Error> {
         let _timer = counters::start_timer("get", EXECUTION_KEY);
         Ok(self
@@ -163,6 +189,27 @@ impl PersistentSafetyStorage {
         }
     }
 
+    /// Records a signing decision along with the signature it produced, the signature must not
+    /// be released if this fails. The record is signed by `signer`.
+    pub fn record_decision(
+        &mut self,
+        decision: AuditDecision,
+        safety_data: SafetyData,
+        signature: &Ed25519Signature,
+        signer: &ConfigurableValidatorSigner,
+    ) -> Result<(), Error> {
+        let record = match &self.audit_log {
+            Some(audit_log) => audit_log.next_record(decision, safety_data, signature.clone()),
+            None => return Ok(()),
+        };
+        let _timer = counters::start_timer("set", "audit_log");
+        let record_signature = signer.sign(&record, self)?;
+        match &mut self.audit_log {
+            Some(audit_log) => audit_log.append(record, signer.public_key(), record_signature),
+            None => Ok(()),
+        }
+    }
+
     pub fn waypoint(&self) -> Result<Waypoint, Error> {
         let _timer = counters::start_timer("get", WAYPOINT);
         Ok(self.internal_store.get(WAYPOINT).map(|v| v.value)?)
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit_log::AuditDecision,
     configurable_validator_signer::ConfigurableValidatorSigner,
     consensus_state::ConsensusState,
     counters,
@@ -14,6 +15,7 @@ use consensus_types::{
     block::Block,
     block_data::BlockData,
     common::{Author, Round},
+    proof_of_store::BatchInfo,
     quorum_cert::QuorumCert,
     safety_data::SafetyData,
     timeout::Timeout,
@@ -23,6 +25,7 @@ use consensus_types::{
     vote_proposal::{MaybeSignedVoteProposal, VoteProposal},
 };
 use mango_crypto::{
+    bls12381::{Bls12381PrivateKey, Bls12381Signature},
     ed25519::{Ed25519PublicKey, Ed25519Signature},
     hash::{CryptoHash, HashValue},
     traits::Signature,
@@ -48,6 +51,7 @@ pub struct SafetyRules {
     pub(crate) execution_public_key: Option<Ed25519PublicKey>,
     pub(crate) export_consensus_key: bool,
     pub(crate) validator_signer: Option<ConfigurableValidatorSigner>,
+    pub(crate) bls_private_key: Option<Bls12381PrivateKey>,
     pub(crate) epoch_state: Option<EpochState>,
     pub(crate) decoupled_execution: bool,
 }
@@ -75,6 +79,7 @@ impl SafetyRules {
             execution_public_key,
             export_consensus_key,
             validator_signer: None,
+            bls_private_key: None,
             epoch_state: None,
             decoupled_execution,
         }
@@ -123,6 +128,21 @@ impl SafetyRules {
         signer.sign(message, &self.persistent_storage)
     }
 
+    /// Records a signing decision in the audit log, if any, along with the signature it produced.
+    pub(crate) fn record_decision(
+        &mut self,
+        decision: AuditDecision,
+        safety_data: SafetyData,
+        signature: &Ed25519Signature,
+    ) -> Result<(), Error> {
+        let signer = self
+            .validator_signer
+            .as_ref()
+            .ok_or_else(|| Error::NotInitialized("validator_signer".into()))?;
+        self.persistent_storage
+            .record_decision(decision, safety_data, signature, signer)
+    }
+
     pub(crate) fn signer(&self) -> Result<&ConfigurableValidatorSigner, Error> {
         self.validator_signer
             .as_ref()
@@ -337,6 +357,22 @@ impl SafetyRules {
         self.epoch_state = Some(epoch_state.clone());
 
         let author = self.persistent_storage.author()?;
+
+        // The BLS key is optional: without it this validator only contributes Ed25519 votes.
+        self.bls_private_key = match epoch_state.verifier.get_bls_public_key(&author) {
+            Some(expected_bls_key) => match self.persistent_storage.bls_consensus_key() {
+                Ok(bls_key) if bls_key.public_key() == expected_bls_key => Some(bls_key),
+                _ => {
+                    warn!(
+                        SafetyLogSchema::new(LogEntry::KeyReconciliation, LogEvent::Error),
+                        "registered BLS consensus key not found in storage",
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
         let expected_key = epoch_state.verifier.get_public_key(&author);
         let initialize_result = match expected_key {
             None => Err(Error::ValidatorNotInSet(author.to_string())),
@@ -418,14 +454,40 @@ impl SafetyRules {
         let author = self.signer()?.author();
         let ledger_info = self.construct_ledger_info(proposed_block, vote_data.hash())?;
         let signature = self.sign(&ledger_info)?;
-        let vote = Vote::new_with_signature(vote_data, author, ledger_info, signature);
+        let vote = Vote::new_with_signature(vote_data, author, ledger_info, signature.clone());
 
         safety_data.last_vote = Some(vote.clone());
-        self.persistent_storage.set_safety_data(safety_data)?;
+        self.persistent_storage
+            .set_safety_data(safety_data.clone())?;
+        self.record_decision(
+            AuditDecision::vote(proposed_block, None, false),
+            safety_data,
+            &signature,
+        )?;
 
         Ok(vote)
     }
 
+    fn guarded_sign_vote_bls(&mut self, vote: &Vote) -> Result<Bls12381Signature, Error> {
+        self.signer()?;
+
+        let safety_data = self.persistent_storage.safety_data()?;
+        self.verify_epoch(vote.epoch(), &safety_data)?;
+
+        // Only the last vote is signed, so the BLS share follows the same voting rules as the
+        // Ed25519 vote it accompanies.
+        match safety_data.last_vote {
+            Some(last_vote) if last_vote.ledger_info() == vote.ledger_info() => self
+                .bls_private_key
+                .as_ref()
+                .map(|bls_private_key| bls_private_key.sign(vote.ledger_info()))
+                .ok_or_else(|| Error::NotInitialized("bls_private_key".into())),
+            _ => Err(Error::InvalidProposal(
+                "Vote to sign with BLS is not the last vote".into(),
+            )),
+        }
+    }
+
     fn guarded_sign_proposal(&mut self, block_data: &BlockData) -> Result<Ed25519Signature, Error> {
         self.signer()?;
         self.verify_author(block_data.author())?;
@@ -446,6 +508,12 @@ impl SafetyRules {
         // we don't persist the updated preferred round to save latency (it'd be updated upon voting)
 
         let signature = self.sign(block_data)?;
+        let persisted_safety_data = self.persistent_storage.safety_data()?;
+        self.record_decision(
+            AuditDecision::proposal(block_data),
+            persisted_safety_data,
+            &signature,
+        )?;
         Ok(signature)
     }
 
@@ -469,13 +537,27 @@ impl SafetyRules {
         }
         if timeout.round() > safety_data.last_voted_round {
             self.verify_and_update_last_vote_round(timeout.round(), &mut safety_data)?;
-            self.persistent_storage.set_safety_data(safety_data)?;
+            self.persistent_storage
+                .set_safety_data(safety_data.clone())?;
         }
 
         let signature = self.sign(timeout)?;
+        self.record_decision(AuditDecision::timeout(timeout), safety_data, &signature)?;
         Ok(signature)
     }
 
+    fn guarded_sign_batch_info(
+        &mut self,
+        batch_info: &BatchInfo,
+    ) -> Result<Ed25519Signature, Error> {
+        self.signer()?;
+
+        let safety_data = self.persistent_storage.safety_data()?;
+        self.verify_epoch(batch_info.epoch(), &safety_data)?;
+
+        self.sign(batch_info)
+    }
+
     fn guarded_sign_commit_vote(
         &mut self,
         ledger_info: LedgerInfoWithSignatures,
@@ -533,6 +615,12 @@ impl TSafetyRules for SafetyRules {
         run_and_log(cb, |log| log.round(round), LogEntry::ConstructAndSignVote)
     }
 
+    fn sign_vote_bls(&mut self, vote: &Vote) -> Result<Bls12381Signature, Error> {
+        let round = vote.vote_data().proposed().round();
+        let cb = || self.guarded_sign_vote_bls(vote);
+        run_and_log(cb, |log| log.round(round), LogEntry::SignVoteBls)
+    }
+
     fn sign_proposal(&mut self, block_data: &BlockData) -> Result<Ed25519Signature, Error> {
         let round = block_data.round();
         let cb = || self.guarded_sign_proposal(block_data);
@@ -544,6 +632,11 @@ impl TSafetyRules for SafetyRules {
         run_and_log(cb, |log| log.round(timeout.round()), LogEntry::SignTimeout)
     }
 
+    fn sign_batch_info(&mut self, batch_info: &BatchInfo) -> Result<Ed25519Signature, Error> {
+        let cb = || self.guarded_sign_batch_info(batch_info);
+        run_and_log(cb, |log| log, LogEntry::SignBatchInfo)
+    }
+
     fn sign_timeout_with_qc(
         &mut self,
         timeout: &TwoChainTimeout,
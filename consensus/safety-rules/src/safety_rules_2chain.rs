@@ -1,7 +1,7 @@
 // Copyright (c) The Mango Blockchain Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{error::Error, safety_rules::next_round, SafetyRules};
+use crate::{audit_log::AuditDecision, error::Error, safety_rules::next_round, SafetyRules};
 use consensus_types::{
     block::Block,
     safety_data::SafetyData,
@@ -36,10 +36,16 @@ impl SafetyRules {
         }
         if timeout.round() > safety_data.last_voted_round {
             self.verify_and_update_last_vote_round(timeout.round(), &mut safety_data)?;
-            self.persistent_storage.set_safety_data(safety_data)?;
+            self.persistent_storage
+                .set_safety_data(safety_data.clone())?;
         }
 
         let signature = self.sign(&timeout.signing_format())?;
+        self.record_decision(
+            AuditDecision::timeout_2chain(timeout, timeout_cert),
+            safety_data,
+            &signature,
+        )?;
         Ok(signature)
     }
 
@@ -79,10 +85,16 @@ impl SafetyRules {
         let author = self.signer()?.author();
         let ledger_info = self.construct_ledger_info_2chain(proposed_block, vote_data.hash())?;
         let signature = self.sign(&ledger_info)?;
-        let vote = Vote::new_with_signature(vote_data, author, ledger_info, signature);
+        let vote = Vote::new_with_signature(vote_data, author, ledger_info, signature.clone());
 
         safety_data.last_vote = Some(vote.clone());
-        self.persistent_storage.set_safety_data(safety_data)?;
+        self.persistent_storage
+            .set_safety_data(safety_data.clone())?;
+        self.record_decision(
+            AuditDecision::vote(proposed_block, timeout_cert, true),
+            safety_data,
+            &signature,
+        )?;
 
         Ok(vote)
     }
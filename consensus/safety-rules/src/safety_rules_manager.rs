@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit_log::AuditLog,
     local_client::LocalClient,
     persistent_safety_storage::PersistentSafetyStorage,
     process::ProcessService,
@@ -22,7 +23,7 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
         panic!("Storage is not available: {:?}", error);
     }
 
-    if let Some(test_config) = &config.test {
+    let storage = if let Some(test_config) = &config.test {
         let author = test_config.author;
         let consensus_private_key = test_config
             .consensus_key
@@ -46,6 +47,13 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
         )
     } else {
         PersistentSafetyStorage::new(internal_storage, config.enable_cached_safety_data)
+    };
+
+    match &config.audit_log_path {
+        Some(path) => storage.with_audit_log(
+            AuditLog::open(path).expect("Unable to open the safety rules audit log"),
+        ),
+        None => storage,
     }
 }
 
@@ -4,12 +4,13 @@
 use crate::{counters, logging::LogEntry, ConsensusState, Error, SafetyRules, TSafetyRules};
 use consensus_types::{
     block_data::BlockData,
+    proof_of_store::BatchInfo,
     timeout::Timeout,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
     vote::Vote,
     vote_proposal::MaybeSignedVoteProposal,
 };
-use mango_crypto::ed25519::Ed25519Signature;
+use mango_crypto::{bls12381::Bls12381Signature, ed25519::Ed25519Signature};
 use mango_infallible::RwLock;
 use mango_types::{
     epoch_change::EpochChangeProof,
@@ -34,6 +35,8 @@ pub enum SafetyRulesInput {
         Box<Option<TwoChainTimeoutCertificate>>,
     ),
     SignCommitVote(Box<LedgerInfoWithSignatures>, Box<LedgerInfo>),
+    SignVoteBls(Box<Vote>),
+    SignBatchInfo(Box<BatchInfo>),
 }
 
 pub struct SerializerService {
@@ -80,6 +83,12 @@ impl SerializerService {
                     .internal
                     .sign_commit_vote(*ledger_info, *new_ledger_info),
             ),
+            SafetyRulesInput::SignVoteBls(vote) => {
+                serde_json::to_vec(&self.internal.sign_vote_bls(&vote))
+            }
+            SafetyRulesInput::SignBatchInfo(batch_info) => {
+                serde_json::to_vec(&self.internal.sign_batch_info(&batch_info))
+            }
         };
 
         Ok(output?)
@@ -129,6 +138,12 @@ impl TSafetyRules for SerializerClient {
         serde_json::from_slice(&response)?
     }
 
+    fn sign_vote_bls(&mut self, vote: &Vote) -> Result<Bls12381Signature, Error> {
+        let _timer = counters::start_timer("external", LogEntry::SignVoteBls.as_str());
+        let response = self.request(SafetyRulesInput::SignVoteBls(Box::new(vote.clone())))?;
+        serde_json::from_slice(&response)?
+    }
+
     fn sign_proposal(&mut self, block_data: &BlockData) -> Result<Ed25519Signature, Error> {
         let _timer = counters::start_timer("external", LogEntry::SignProposal.as_str());
         let response =
@@ -142,6 +157,14 @@ impl TSafetyRules for SerializerClient {
         serde_json::from_slice(&response)?
     }
 
+    fn sign_batch_info(&mut self, batch_info: &BatchInfo) -> Result<Ed25519Signature, Error> {
+        let _timer = counters::start_timer("external", LogEntry::SignBatchInfo.as_str());
+        let response = self.request(SafetyRulesInput::SignBatchInfo(Box::new(
+            batch_info.clone(),
+        )))?;
+        serde_json::from_slice(&response)?
+    }
+
     fn sign_timeout_with_qc(
         &mut self,
         timeout: &TwoChainTimeout,
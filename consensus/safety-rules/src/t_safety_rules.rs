@@ -4,12 +4,13 @@
 use crate::{ConsensusState, Error};
 use consensus_types::{
     block_data::BlockData,
+    proof_of_store::BatchInfo,
     timeout::Timeout,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
     vote::Vote,
     vote_proposal::MaybeSignedVoteProposal,
 };
-use mango_crypto::ed25519::Ed25519Signature;
+use mango_crypto::{bls12381::Bls12381Signature, ed25519::Ed25519Signature};
 use mango_types::{
     epoch_change::EpochChangeProof,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
@@ -33,6 +34,11 @@ pub trait TSafetyRules {
         vote_proposal: &MaybeSignedVoteProposal,
     ) -> Result<Vote, Error>;
 
+    /// As the holder of the BLS private key, SafetyRules signs the ledger info of its last vote.
+    /// The resulting share is aggregated into quorum certificates when aggregated signatures are
+    /// enabled on-chain.
+    fn sign_vote_bls(&mut self, vote: &Vote) -> Result<Bls12381Signature, Error>;
+
     /// As the holder of the private key, SafetyRules also signs proposals or blocks.
     /// A Block is a signed BlockData along with some additional metadata.
     fn sign_proposal(&mut self, block_data: &BlockData) -> Result<Ed25519Signature, Error>;
@@ -41,6 +47,10 @@ pub trait TSafetyRules {
     /// timeout message. This returns the signature for that timeout message.
     fn sign_timeout(&mut self, timeout: &Timeout) -> Result<Ed25519Signature, Error>;
 
+    /// As the holder of the private key, SafetyRules signs batch infos of the quorum store to
+    /// certify this validator stores the batch.
+    fn sign_batch_info(&mut self, batch_info: &BatchInfo) -> Result<Ed25519Signature, Error>;
+
     /// Sign the timeout together with highest qc for 2-chain protocol.
     fn sign_timeout_with_qc(
         &mut self,
@@ -85,7 +85,14 @@ pub fn make_proposal_with_qc(
     validator_signer: &ValidatorSigner,
     exec_key: Option<&Ed25519PrivateKey>,
 ) -> MaybeSignedVoteProposal {
-    make_proposal_with_qc_and_proof(vec![], round, empty_proof(), qc, validator_signer, exec_key)
+    make_proposal_with_qc_and_proof(
+        Payload::empty(),
+        round,
+        empty_proof(),
+        qc,
+        validator_signer,
+        exec_key,
+    )
 }
 
 pub fn make_proposal_with_parent_and_overrides(
@@ -168,10 +175,10 @@ pub fn make_proposal_with_parent_and_overrides(
         validator_signer,
     );
 
-    let mut ledger_info_with_signatures =
-        LedgerInfoWithSignatures::new(vote.ledger_info().clone(), BTreeMap::new());
-
-    ledger_info_with_signatures.add_signature(vote.author(), vote.signature().clone());
+    let ledger_info_with_signatures = LedgerInfoWithSignatures::new(
+        vote.ledger_info().clone(),
+        std::iter::once((vote.author(), vote.signature().clone())).collect(),
+    );
 
     let qc = QuorumCert::new(vote_data, ledger_info_with_signatures);
 
@@ -4,7 +4,8 @@
 use crate::{test_utils, test_utils::make_timeout_cert, Error, SafetyRules, TSafetyRules};
 use consensus_types::{
     block::block_test_utils::random_payload,
-    common::Round,
+    common::{Payload, Round},
+    proof_of_store::{BatchInfo, SignedBatchInfo},
     quorum_cert::QuorumCert,
     timeout::Timeout,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
@@ -35,7 +36,14 @@ fn make_proposal_with_qc_and_proof(
     signer: &ValidatorSigner,
     exec_key: Option<&Ed25519PrivateKey>,
 ) -> MaybeSignedVoteProposal {
-    test_utils::make_proposal_with_qc_and_proof(vec![], round, proof, qc, signer, exec_key)
+    test_utils::make_proposal_with_qc_and_proof(
+        Payload::empty(),
+        round,
+        proof,
+        qc,
+        signer,
+        exec_key,
+    )
 }
 
 fn make_proposal_with_parent(
@@ -45,7 +53,14 @@ fn make_proposal_with_parent(
     signer: &ValidatorSigner,
     exec_key: Option<&Ed25519PrivateKey>,
 ) -> MaybeSignedVoteProposal {
-    test_utils::make_proposal_with_parent(vec![], round, parent, committed, signer, exec_key)
+    test_utils::make_proposal_with_parent(
+        Payload::empty(),
+        round,
+        parent,
+        committed,
+        signer,
+        exec_key,
+    )
 }
 
 pub type Callback = Box<
@@ -70,6 +85,8 @@ pub fn run_test_suite(safety_rules: &Callback, decoupled_execution: bool) {
     test_sign_proposal_with_invalid_qc(safety_rules);
     test_sign_proposal_with_early_preferred_round(safety_rules);
     test_uninitialized_signer(safety_rules);
+    test_sign_vote_bls(safety_rules);
+    test_sign_batch_info(safety_rules);
     test_reconcile_key(safety_rules);
     test_validator_not_in_set(safety_rules);
     test_key_not_in_store(safety_rules);
@@ -452,7 +469,7 @@ fn test_voting_bad_epoch(safety_rules: &Callback) {
 
     let a1 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &signer, key.as_ref());
     let a2 = test_utils::make_proposal_with_parent_and_overrides(
-        vec![],
+        Payload::empty(),
         round + 3,
         &a1,
         None,
@@ -639,6 +656,57 @@ fn test_uninitialized_signer(safety_rules: &Callback) {
     safety_rules.construct_and_sign_vote(&a1).unwrap();
 }
 
+fn test_sign_vote_bls(safety_rules: &Callback) {
+    // The epoch state carries no BLS keys, so only the last vote check can be exercised here.
+    let (mut safety_rules, signer, key) = safety_rules();
+
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+
+    let a1 =
+        test_utils::make_proposal_with_qc(round + 1, genesis_qc.clone(), &signer, key.as_ref());
+    let b1 = test_utils::make_proposal_with_qc(round + 2, genesis_qc, &signer, key.as_ref());
+
+    safety_rules.initialize(&proof).unwrap();
+
+    let vote_a1 = safety_rules.construct_and_sign_vote(&a1).unwrap();
+    assert_eq!(
+        safety_rules.sign_vote_bls(&vote_a1),
+        Err(Error::NotInitialized("bls_private_key".into()))
+    );
+
+    safety_rules.construct_and_sign_vote(&b1).unwrap();
+    assert!(matches!(
+        safety_rules.sign_vote_bls(&vote_a1),
+        Err(Error::InvalidProposal(_))
+    ));
+}
+
+fn test_sign_batch_info(safety_rules: &Callback) {
+    let (mut safety_rules, signer, _key) = safety_rules();
+
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let epoch = genesis_qc.certified_block().epoch();
+    let batch_info = BatchInfo::new(signer.author(), epoch, 0, HashValue::random(), 0);
+
+    let err = safety_rules.sign_batch_info(&batch_info).unwrap_err();
+    assert_eq!(err, Error::NotInitialized("validator_signer".into()));
+
+    safety_rules.initialize(&proof).unwrap();
+    let signature = safety_rules.sign_batch_info(&batch_info).unwrap();
+    let signed = SignedBatchInfo::new(batch_info, signer.author(), signature);
+    assert!(signed
+        .verify(&ValidatorVerifier::new_single(
+            signer.author(),
+            signer.public_key()
+        ))
+        .is_ok());
+
+    let next_batch_info = BatchInfo::new(signer.author(), epoch + 1, 0, HashValue::random(), 0);
+    let err = safety_rules.sign_batch_info(&next_batch_info).unwrap_err();
+    assert_eq!(err, Error::IncorrectEpoch(epoch + 1, epoch));
+}
+
 fn test_validator_not_in_set(safety_rules: &Callback) {
     // Testing for a validator missing from the validator set
     // It does so by updating the safey rule to an epoch state, which does not contain the
@@ -664,7 +732,7 @@ fn test_validator_not_in_set(safety_rules: &Callback) {
     next_epoch_state.verifier =
         ValidatorVerifier::new_single(rand_signer.author(), rand_signer.public_key());
     let a2 = test_utils::make_proposal_with_parent_and_overrides(
-        vec![],
+        Payload::empty(),
         round + 2,
         &a1,
         Some(&a1),
@@ -711,7 +779,7 @@ fn test_reconcile_key(_safety_rules: &Callback) {
     next_epoch_state.epoch = 2;
     next_epoch_state.verifier = ValidatorVerifier::new_single(signer.author(), new_pub_key);
     let a2 = test_utils::make_proposal_with_parent_and_overrides(
-        vec![],
+        Payload::empty(),
         round + 2,
         &a1,
         Some(&a1),
@@ -728,7 +796,7 @@ fn test_reconcile_key(_safety_rules: &Callback) {
     // Verification fails for proposal signed by the outdated key
     let outdated_signer = &signer;
     let a3 = test_utils::make_proposal_with_parent_and_overrides(
-        vec![],
+        Payload::empty(),
         round + 3,
         &a2,
         Some(&a2),
@@ -762,7 +830,7 @@ fn test_key_not_in_store(safety_rules: &Callback) {
     next_epoch_state.verifier =
         ValidatorVerifier::new_single(signer.author(), rand_signer.public_key());
     let a2 = test_utils::make_proposal_with_parent_and_overrides(
-        vec![],
+        Payload::empty(),
         round + 2,
         &a1,
         Some(&a1),
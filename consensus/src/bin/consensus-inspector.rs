@@ -0,0 +1,112 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Context, Result};
+use consensus::inspector::ConsensusDbDump;
+use diemdb::DiemDB;
+use mango_config::config::RocksdbConfig;
+use serde_json::json;
+use std::{fs::File, path::PathBuf};
+use structopt::StructOpt;
+
+/// Built with the `inspector` feature:
+/// cargo run -p consensus --features inspector --bin consensus-inspector -- <db_dir>
+#[derive(StructOpt)]
+#[structopt(
+    name = "consensus-inspector",
+    about = "Inspect the blocks and quorum certificates persisted in ConsensusDB."
+)]
+struct Opt {
+    /// The storage directory of the node, containing both consensusdb and diemdb.
+    #[structopt(parse(from_os_str))]
+    db_dir: PathBuf,
+
+    /// Skip rebuilding the block tree from the latest ledger info in diemdb.
+    #[structopt(long)]
+    skip_recovery: bool,
+
+    /// Export the decoded ConsensusDB and the recovered block tree as JSON to this file.
+    #[structopt(long, parse(from_os_str))]
+    export_json: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+
+    let dump = ConsensusDbDump::load(&opt.db_dir)
+        .with_context(|| format_err!("Failed to read ConsensusDB."))?;
+
+    println!("Blocks ({}):", dump.blocks.len());
+    for block in dump.block_summaries() {
+        println!(
+            "\t[id: {}, epoch: {}, round: {:02}, parent_id: {}, author: {}, txns: {}, payload bytes: {}]",
+            block.id,
+            block.epoch,
+            block.round,
+            block.parent_id,
+            block
+                .author
+                .map_or_else(|| "None".to_string(), |author| author.to_string()),
+            block.payload_txns,
+            block.payload_bytes,
+        );
+    }
+    println!("Quorum certs: {}", dump.quorum_certs.len());
+    println!(
+        "Highest QC: {}",
+        dump.highest_quorum_cert()
+            .map_or_else(|| "None".to_string(), |qc| qc.to_string())
+    );
+    println!(
+        "Highest TC: {}",
+        dump.highest_timeout_certificate
+            .as_ref()
+            .map_or_else(|| "None".to_string(), |tc| tc.to_string())
+    );
+    println!(
+        "Highest 2-chain TC: {}",
+        dump.highest_2chain_timeout_certificate
+            .as_ref()
+            .map_or_else(|| "None".to_string(), |tc| tc.to_string())
+    );
+    println!(
+        "Last vote: {}",
+        dump.last_vote
+            .as_ref()
+            .map_or_else(|| "None".to_string(), |vote| vote.to_string())
+    );
+
+    let recovered_tree = if opt.skip_recovery {
+        None
+    } else {
+        let diem_db = DiemDB::open(&opt.db_dir, true, None, RocksdbConfig::default())
+            .with_context(|| format_err!("Failed to open DiemDB."))?;
+        match dump.recover(&diem_db) {
+            Ok(tree) => {
+                println!("Recovered block tree:\n{}", tree.render());
+                println!("Blocks to prune: {:?}", tree.blocks_to_prune);
+                Some(tree)
+            }
+            Err(e) => {
+                println!("Failed to recover the block tree: {:?}", e);
+                None
+            }
+        }
+    };
+
+    if let Some(path) = opt.export_json {
+        let file = File::create(&path)
+            .with_context(|| format_err!("Failed to create {}.", path.display()))?;
+        serde_json::to_writer_pretty(
+            file,
+            &json!({
+                "consensusdb": dump,
+                "blocks": dump.block_summaries(),
+                "recovered_tree": recovered_tree,
+            }),
+        )?;
+        println!("Exported to {}", path.display());
+    }
+
+    Ok(())
+}
@@ -366,6 +366,15 @@ impl BlockStore {
             .into_inner();
         self.try_commit().await;
     }
+    /// Makes sure the payload of the block is available locally, it must be called before the
+    /// block is executed.
+    pub async fn fetch_payload(&self, block: &Block) -> anyhow::Result<()> {
+        self.state_computer
+            .fetch_payload(block)
+            .await
+            .context("Failed to fetch the payload of the block")
+    }
+
     /// Execute and insert a block if it passes all validation tests.
     /// Returns the Arc to the block kept in the block store after persisting it to storage
     ///
@@ -4,6 +4,7 @@
 use crate::{
     block_storage::{block_store::BlockStore, BlockReader},
     persistent_liveness_storage::{LedgerRecoveryData, RecoveryData, RootMetadata},
+    quorum_store::batch_store::BatchStore,
     state_computer::ExecutionProxy,
     test_utils::{EmptyStorage, TreeInserter},
     util::mock_time_service::SimulatedTimeService,
@@ -66,18 +67,18 @@ fn build_inserter(
     let (consensus_notifier, _consensus_listener) =
         consensus_notifications::new_consensus_notifier_listener_pair(client_commit_timeout_ms);
 
+    let author = config.validator_network.as_ref().unwrap().peer_id();
+    let storage = Arc::new(EmptyStorage::new());
     let state_computer = Arc::new(ExecutionProxy::new(
         lec_client,
         Box::new(consensus_notifier),
+        Arc::new(BatchStore::new(author, storage.clone(), 1000)),
     ));
 
     TreeInserter::new_with_store(
-        ValidatorSigner::new(
-            config.validator_network.as_ref().unwrap().peer_id(),
-            Ed25519PrivateKey::generate_for_testing(),
-        ),
+        ValidatorSigner::new(author, Ed25519PrivateKey::generate_for_testing()),
         Arc::new(BlockStore::new(
-            Arc::new(EmptyStorage::new()),
+            storage,
             initial_data,
             state_computer,
             10, // max pruned blocks in mem
@@ -14,7 +14,7 @@ use consensus_types::{
         },
         Block,
     },
-    common::Author,
+    common::{Author, Payload},
     vote::Vote,
     vote_data::VoteData,
 };
@@ -331,7 +331,7 @@ fn test_illegal_timestamp() {
     let block_store = build_empty_tree();
     let genesis = block_store.ordered_root();
     let block_with_illegal_timestamp = Block::new_proposal(
-        vec![],
+        Payload::empty(),
         0,
         // This timestamp is illegal, it is the same as genesis
         genesis.timestamp_usecs(),
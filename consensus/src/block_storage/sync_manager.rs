@@ -159,6 +159,7 @@ impl BlockStore {
         while let Some(block) = pending.pop() {
             let block_qc = block.quorum_cert().clone();
             self.insert_single_quorum_cert(block_qc)?;
+            self.fetch_payload(&block).await?;
             self.execute_and_insert_block(block)?;
         }
         self.insert_single_quorum_cert(qc)
@@ -255,6 +256,11 @@ impl BlockStore {
             assert_eq!(block.id(), quorum_certs[i].certified_block().id());
         }
 
+        // The blocks above the committed one are going to be executed once the tree is rebuilt.
+        for block in blocks.iter().take(num_blocks as usize - 1) {
+            state_computer.fetch_payload(block).await?;
+        }
+
         // If a node restarts in the middle of state synchronization, it is going to try to catch up
         // to the stored quorum certs as the new root.
         storage.save_tree(blocks.clone(), quorum_certs.clone())?;
@@ -377,11 +383,13 @@ impl BlockRetriever {
         qc: &'a QuorumCert,
         num_blocks: u64,
     ) -> anyhow::Result<Vec<Block>> {
-        let mut peers = qc
-            .ledger_info()
-            .signatures()
-            .keys()
-            .collect::<Vec<&AccountAddress>>();
+        let validators: Vec<_> = self
+            .network
+            .validators()
+            .get_ordered_account_addresses_iter()
+            .collect();
+        let voters = qc.ledger_info().get_voters(&validators);
+        let mut peers = voters.iter().collect::<Vec<&AccountAddress>>();
         self.retrieve_block_for_id(qc.certified_block().id(), &mut peers, num_blocks)
             .await
     }
@@ -7,6 +7,7 @@ use crate::{
     network::NetworkTask,
     network_interface::{ConsensusNetworkEvents, ConsensusNetworkSender},
     persistent_liveness_storage::StorageWriteProxy,
+    quorum_store::batch_store::BatchStore,
     state_computer::ExecutionProxy,
     txn_manager::MempoolProxy,
     util::time_service::ClockTimeService,
@@ -19,26 +20,56 @@ use mango_config::config::NodeConfig;
 use mango_infallible::RwLock;
 use mango_logger::prelude::*;
 use mango_mempool::ConsensusRequest;
+use mango_time_service::TimeService;
 use mango_types::on_chain_config::OnChainConfigPayload;
 use std::{collections::HashMap, sync::Arc};
 use storage_interface::DbReader;
-use tokio::runtime::{self, Runtime};
+use tokio::runtime::{self, Handle, Runtime};
 
-/// Helper function to start consensus based on configuration and return the runtime
+/// Helper function to start consensus based on configuration and return the runtime. Round
+/// timeouts and block timestamps follow `clock`.
 pub fn start_consensus(
     node_config: &NodeConfig,
-    mut network_sender: ConsensusNetworkSender,
+    network_sender: ConsensusNetworkSender,
     network_events: ConsensusNetworkEvents,
     state_sync_notifier: Box<dyn ConsensusNotificationSender>,
     consensus_to_mempool_sender: mpsc::Sender<ConsensusRequest>,
     diem_db: Arc<dyn DbReader>,
     reconfig_events: diem_channel::Receiver<(), OnChainConfigPayload>,
+    clock: TimeService,
 ) -> Runtime {
     let runtime = runtime::Builder::new_multi_thread()
         .thread_name("consensus")
         .enable_all()
         .build()
         .expect("Failed to create Tokio runtime!");
+    start_consensus_on(
+        runtime.handle(),
+        node_config,
+        network_sender,
+        network_events,
+        state_sync_notifier,
+        consensus_to_mempool_sender,
+        diem_db,
+        reconfig_events,
+        clock,
+    );
+    runtime
+}
+
+/// Same as `start_consensus`, but consensus is spawned on `executor` instead of a runtime of its
+/// own.
+pub fn start_consensus_on(
+    executor: &Handle,
+    node_config: &NodeConfig,
+    mut network_sender: ConsensusNetworkSender,
+    network_events: ConsensusNetworkEvents,
+    state_sync_notifier: Box<dyn ConsensusNotificationSender>,
+    consensus_to_mempool_sender: mpsc::Sender<ConsensusRequest>,
+    diem_db: Arc<dyn DbReader>,
+    reconfig_events: diem_channel::Receiver<(), OnChainConfigPayload>,
+    clock: TimeService,
+) {
     let storage = Arc::new(StorageWriteProxy::new(node_config, diem_db));
     let txn_manager = Arc::new(MempoolProxy::new(
         consensus_to_mempool_sender,
@@ -47,13 +78,19 @@ pub fn start_consensus(
         node_config.consensus.mempool_executed_txn_timeout_ms,
     ));
     let execution_correctness_manager = ExecutionCorrectnessManager::new(node_config);
+    let batch_store = Arc::new(BatchStore::new(
+        node_config.validator_network.as_ref().unwrap().peer_id(),
+        storage.clone(),
+        node_config.consensus.quorum_store.batch_request_timeout_ms,
+    ));
 
     let state_computer = Arc::new(ExecutionProxy::new(
         execution_correctness_manager.client(),
         state_sync_notifier,
+        batch_store.clone(),
     ));
 
-    let time_service = Arc::new(ClockTimeService::new(runtime.handle().clone()));
+    let time_service = Arc::new(ClockTimeService::new_with_clock(executor.clone(), clock));
 
     let (timeout_sender, timeout_receiver) = channel::new(1_024, &counters::PENDING_ROUND_TIMEOUTS);
     let (self_sender, self_receiver) = channel::new(1_024, &counters::PENDING_SELF_MESSAGES);
@@ -70,14 +107,14 @@ pub fn start_consensus(
         state_computer,
         storage,
         reconfig_events,
+        batch_store,
     );
 
     let (network_task, network_receiver) =
         NetworkTask::new(network_events, self_receiver, shared_connections);
 
-    runtime.spawn(network_task.start());
-    runtime.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
+    executor.spawn(network_task.start());
+    executor.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
 
     debug!("Consensus started.");
-    runtime
 }
@@ -2,13 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use consensus_types::block::block_test_utils::certificate_for_genesis;
-use mango_temppath::TempPath;
+use consensus_types::block::block_test_utils::{certificate_for_genesis, random_payload};
+use mango_types::account_address::AccountAddress;
 
 #[test]
 fn test_put_get() {
-    let tmp_dir = TempPath::new();
-    let db = ConsensusDB::new(&tmp_dir);
+    let db = ConsensusDB::new_in_memory();
 
     let block = Block::make_genesis_block();
     let blocks = vec![block];
@@ -51,8 +50,7 @@ fn test_put_get() {
 
 #[test]
 fn test_delete_block_and_qc() {
-    let tmp_dir = TempPath::new();
-    let db = ConsensusDB::new(&tmp_dir);
+    let db = ConsensusDB::new_in_memory();
 
     assert_eq!(db.get_blocks().unwrap().len(), 0);
     assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
@@ -73,3 +71,57 @@ fn test_delete_block_and_qc() {
     assert_eq!(db.get_blocks().unwrap().len(), 0);
     assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
 }
+
+#[test]
+fn test_put_get_delete_batches() {
+    let db = ConsensusDB::new_in_memory();
+
+    assert_eq!(db.get_batches().unwrap().len(), 0);
+
+    let batches: Vec<_> = (0..2)
+        .map(|batch_id| {
+            Batch::new(
+                AccountAddress::random(),
+                1,
+                batch_id,
+                random_payload(3).inline_transactions().to_vec(),
+            )
+        })
+        .collect();
+    db.save_batches(batches.clone()).unwrap();
+    let stored = db.get_batches().unwrap();
+    assert_eq!(stored.len(), 2);
+    assert_eq!(stored.get(&batches[0].digest()), Some(&batches[0]));
+
+    db.delete_batches(vec![batches[0].digest()]).unwrap();
+    let stored = db.get_batches().unwrap();
+    assert_eq!(stored.len(), 1);
+    assert!(stored.contains_key(&batches[1].digest()));
+}
+
+#[test]
+fn test_readonly_without_batches() {
+    // A ConsensusDB written before quorum store batches were persisted
+    let tmp_dir = mango_temppath::TempPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let column_families = ConsensusDB::column_families()
+        .into_iter()
+        .filter(|cf_name| *cf_name != BATCH_CF_NAME)
+        .collect();
+    let db = DB::open(
+        tmp_dir.path().join("consensusdb"),
+        "consensus",
+        column_families,
+        &opts,
+    )
+    .unwrap();
+    let block = Block::make_genesis_block();
+    db.put::<BlockSchema>(&block.id(), &block).unwrap();
+    drop(db);
+
+    let db = ConsensusDB::new_readonly(tmp_dir.path()).unwrap();
+    assert_eq!(db.get_blocks().unwrap().len(), 1);
+    assert!(db.get_batches().unwrap().is_empty());
+}
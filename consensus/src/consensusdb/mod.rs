@@ -7,6 +7,7 @@ mod schema;
 
 use crate::{
     consensusdb::schema::{
+        batch::BatchSchema,
         block::BlockSchema,
         quorum_certificate::QCSchema,
         single_entry::{SingleEntryKey, SingleEntrySchema},
@@ -14,11 +15,11 @@ use crate::{
     error::DbError,
 };
 use anyhow::Result;
-use consensus_types::{block::Block, quorum_cert::QuorumCert};
+use consensus_types::{block::Block, proof_of_store::Batch, quorum_cert::QuorumCert};
 use mango_crypto::HashValue;
 use mango_logger::prelude::*;
-use schema::{BLOCK_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
-use schemadb::{Options, ReadOptions, SchemaBatch, DB, DEFAULT_CF_NAME};
+use schema::{BATCH_CF_NAME, BLOCK_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
+use schemadb::{ColumnFamilyName, Options, ReadOptions, SchemaBatch, DB, DEFAULT_CF_NAME};
 use std::{collections::HashMap, iter::Iterator, path::Path, time::Instant};
 
 pub struct ConsensusDB {
@@ -26,20 +27,23 @@ pub struct ConsensusDB {
 }
 
 impl ConsensusDB {
-    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        let column_families = vec![
+    fn column_families() -> Vec<ColumnFamilyName> {
+        vec![
             /* UNUSED CF = */ DEFAULT_CF_NAME,
             BLOCK_CF_NAME,
             QC_CF_NAME,
             SINGLE_ENTRY_CF_NAME,
-        ];
+            BATCH_CF_NAME,
+        ]
+    }
 
+    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
         let path = db_root_path.as_ref().join("consensusdb");
         let instant = Instant::now();
         let mut opts = Options::default();
         opts.create_if_missing(true);
         opts.create_missing_column_families(true);
-        let db = DB::open(path.clone(), "consensus", column_families, &opts)
+        let db = DB::open(path.clone(), "consensus", Self::column_families(), &opts)
             .expect("ConsensusDB open failed; unable to continue");
 
         info!(
@@ -51,6 +55,34 @@ impl ConsensusDB {
         Self { db }
     }
 
+    /// Creates an empty ConsensusDB that only lives in memory, for tests that don't need to touch
+    /// disk.
+    #[cfg(test)]
+    pub fn new_in_memory() -> Self {
+        let db = DB::open_in_memory("consensus_in_memory", Self::column_families())
+            .expect("ConsensusDB open failed; unable to continue");
+        Self { db }
+    }
+
+    /// Opens an existing ConsensusDB in readonly mode, used by offline tooling. Nothing is written
+    /// to the db, including the pruning normally done on recovery. Column families added by
+    /// later versions may be missing, and read as empty.
+    pub fn new_readonly<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let path = db_root_path.as_ref().join("consensusdb");
+        let existing_cfs = DB::list_cf(&path)?;
+        let column_families = Self::column_families()
+            .into_iter()
+            .filter(|cf_name| existing_cfs.iter().any(|existing| existing == cf_name))
+            .collect();
+        let db = DB::open_readonly(
+            path,
+            "consensus_readonly",
+            column_families,
+            &Options::default(),
+        )?;
+        Ok(Self { db })
+    }
+
     pub fn get_data(
         &self,
     ) -> Result<(
@@ -141,6 +173,22 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    pub fn save_batches(&self, batches: Vec<Batch>) -> Result<(), DbError> {
+        let mut batch = SchemaBatch::new();
+        batches
+            .iter()
+            .try_for_each(|b| batch.put::<BatchSchema>(&b.digest(), b))?;
+        self.commit(batch)
+    }
+
+    pub fn delete_batches(&self, digests: Vec<HashValue>) -> Result<(), DbError> {
+        let mut batch = SchemaBatch::new();
+        digests
+            .iter()
+            .try_for_each(|digest| batch.delete::<BatchSchema>(digest))?;
+        self.commit(batch)
+    }
+
     /// Write the whole schema batch including all data necessary to mutate the ledger
     /// state of some transaction by leveraging rocksdb atomicity support.
     fn commit(&self, batch: SchemaBatch) -> Result<(), DbError> {
@@ -201,4 +249,15 @@ impl ConsensusDB {
         iter.seek_to_first();
         Ok(iter.collect::<Result<HashMap<HashValue, QuorumCert>>>()?)
     }
+
+    /// Get all quorum store batches.
+    pub fn get_batches(&self) -> Result<HashMap<HashValue, Batch>, DbError> {
+        // A db written before batches were persisted, opened in readonly mode
+        if !self.db.has_cf(BATCH_CF_NAME) {
+            return Ok(HashMap::new());
+        }
+        let mut iter = self.db.iter::<BatchSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        Ok(iter.collect::<Result<HashMap<HashValue, Batch>>>()?)
+    }
 }
@@ -0,0 +1,18 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use consensus_types::block::block_test_utils::random_payload;
+use mango_types::account_address::AccountAddress;
+use schemadb::schema::assert_encode_decode;
+
+#[test]
+fn test_encode_decode() {
+    let batch = Batch::new(
+        AccountAddress::random(),
+        1,
+        0,
+        random_payload(2).inline_transactions().to_vec(),
+    );
+    assert_encode_decode::<BatchSchema>(&batch.digest(), &batch);
+}
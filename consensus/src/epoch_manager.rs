@@ -6,7 +6,9 @@ use crate::{
     counters,
     error::{error_kind, DbError},
     liveness::{
-        leader_reputation::{ActiveInactiveHeuristic, DiemDBBackend, LeaderReputation},
+        leader_reputation::{
+            ActiveInactiveHeuristic, DiemDBBackend, LeaderReputation, ProposerAndVoterHeuristic,
+        },
         proposal_generator::ProposalGenerator,
         proposer_election::ProposerElection,
         rotating_proposer_election::{choose_leader, RotatingProposer},
@@ -15,9 +17,16 @@ use crate::{
     },
     logging::{LogEvent, LogSchema},
     metrics_safety_rules::MetricsSafetyRules,
-    network::{IncomingBlockRetrievalRequest, NetworkReceivers, NetworkSender},
+    network::{
+        IncomingBatchRequest, IncomingBlockRetrievalRequest, NetworkReceivers, NetworkSender,
+    },
     network_interface::{ConsensusMsg, ConsensusNetworkSender},
     persistent_liveness_storage::{LedgerRecoveryData, PersistentLivenessStorage, RecoveryData},
+    quorum_store::{
+        batch_coordinator::BatchCoordinator,
+        batch_store::BatchStore,
+        proof_queue::{ProofQueue, QuorumStoreTxnManager},
+    },
     round_manager::{RecoveryManager, RoundManager, UnverifiedEvent, VerifiedEvent},
     state_replication::{StateComputer, TxnManager},
     util::time_service::TimeService,
@@ -29,7 +38,10 @@ use consensus_types::{
     epoch_retrieval::EpochRetrievalRequest,
 };
 use futures::{select, SinkExt, StreamExt};
-use mango_config::config::{ConsensusConfig, ConsensusProposerType, NodeConfig};
+use mango_config::config::{
+    ConsensusConfig, ConsensusProposerType, LeaderReputationConfig, NodeConfig,
+    ProposerAndVoterConfig,
+};
 use mango_infallible::{duration_since_epoch, Mutex};
 use mango_logger::prelude::*;
 use mango_metrics::monitor;
@@ -37,7 +49,11 @@ use mango_types::{
     account_address::AccountAddress,
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
-    on_chain_config::{OnChainConfigPayload, OnChainConsensusConfig, ValidatorSet},
+    on_chain_config::{
+        OnChainConfigPayload, OnChainConsensusConfig, ProposerElectionType, ValidatorBlsKeys,
+        ValidatorSet,
+    },
+    validator_verifier::ValidatorVerifier,
 };
 use network::protocols::network::Event;
 use safety_rules::SafetyRulesManager;
@@ -88,6 +104,9 @@ pub struct EpochManager {
     reconfig_events: diem_channel::Receiver<(), OnChainConfigPayload>,
     commit_msg_tx: Option<Sender<VerifiedEvent>>,
     back_pressure: Arc<AtomicU64>,
+    batch_store: Arc<BatchStore>,
+    // Dispatches the quorum store messages to the BatchCoordinator of the epoch, if enabled.
+    quorum_store_msg_tx: Option<Sender<(Author, VerifiedEvent)>>,
 }
 
 impl EpochManager {
@@ -101,6 +120,7 @@ impl EpochManager {
         commit_state_computer: Arc<dyn StateComputer>,
         storage: Arc<dyn PersistentLivenessStorage>,
         reconfig_events: diem_channel::Receiver<(), OnChainConfigPayload>,
+        batch_store: Arc<BatchStore>,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
@@ -125,6 +145,8 @@ impl EpochManager {
             reconfig_events,
             commit_msg_tx: None,
             back_pressure,
+            batch_store,
+            quorum_store_msg_tx: None,
         }
     }
 
@@ -147,38 +169,81 @@ impl EpochManager {
         &self,
         time_service: Arc<dyn TimeService>,
         timeout_sender: channel::Sender<Round>,
+        onchain_config: &OnChainConsensusConfig,
     ) -> RoundState {
+        let round_initial_timeout_ms = onchain_config
+            .round_initial_timeout_ms()
+            .unwrap_or(self.config.round_initial_timeout_ms);
         // 1.5^6 ~= 11
         // Timeout goes from initial_timeout to initial_timeout*11 in 6 steps
         let time_interval = Box::new(ExponentialTimeInterval::new(
-            Duration::from_millis(self.config.round_initial_timeout_ms),
+            Duration::from_millis(round_initial_timeout_ms),
             1.2,
             6,
         ));
         RoundState::new(time_interval, time_service, timeout_sender)
     }
 
+    /// The proposer type and contiguous rounds governed on-chain, falling back to the local
+    /// config when the on-chain config doesn't specify them.
+    fn proposer_type(
+        &self,
+        onchain_config: &OnChainConsensusConfig,
+    ) -> (ConsensusProposerType, u32) {
+        match onchain_config.proposer_election_type() {
+            Some(ProposerElectionType::FixedProposer(contiguous_rounds)) => {
+                (ConsensusProposerType::FixedProposer, *contiguous_rounds)
+            }
+            Some(ProposerElectionType::RotatingProposer(contiguous_rounds)) => {
+                (ConsensusProposerType::RotatingProposer, *contiguous_rounds)
+            }
+            Some(ProposerElectionType::LeaderReputation(weights)) => (
+                ConsensusProposerType::LeaderReputation(LeaderReputationConfig {
+                    active_weights: weights.active_weights,
+                    inactive_weights: weights.inactive_weights,
+                }),
+                self.config.contiguous_rounds,
+            ),
+            Some(ProposerElectionType::ProposerAndVoter(weights)) => (
+                ConsensusProposerType::ProposerAndVoterReputation(ProposerAndVoterConfig {
+                    active_weights: weights.active_weights,
+                    inactive_weights: weights.inactive_weights,
+                    failed_weights: weights.failed_weights,
+                    failure_threshold_percent: weights.failure_threshold_percent,
+                    voter_threshold_percent: weights.voter_threshold_percent,
+                }),
+                self.config.contiguous_rounds,
+            ),
+            Some(ProposerElectionType::RoundProposer(round_proposers)) => (
+                ConsensusProposerType::RoundProposer(round_proposers.clone().into_iter().collect()),
+                self.config.contiguous_rounds,
+            ),
+            None => (
+                self.config.proposer_type.clone(),
+                self.config.contiguous_rounds,
+            ),
+        }
+    }
+
     /// Create a proposer election handler based on proposers
     fn create_proposer_election(
         &self,
         epoch_state: &EpochState,
+        onchain_config: &OnChainConsensusConfig,
     ) -> Box<dyn ProposerElection + Send + Sync> {
         let proposers = epoch_state
             .verifier
             .get_ordered_account_addresses_iter()
             .collect::<Vec<_>>();
-        match &self.config.proposer_type {
-            ConsensusProposerType::RotatingProposer => Box::new(RotatingProposer::new(
-                proposers,
-                self.config.contiguous_rounds,
-            )),
+        let (proposer_type, contiguous_rounds) = self.proposer_type(onchain_config);
+        match &proposer_type {
+            ConsensusProposerType::RotatingProposer => {
+                Box::new(RotatingProposer::new(proposers, contiguous_rounds))
+            }
             // We don't really have a fixed proposer!
             ConsensusProposerType::FixedProposer => {
                 let proposer = choose_leader(proposers);
-                Box::new(RotatingProposer::new(
-                    vec![proposer],
-                    self.config.contiguous_rounds,
-                ))
+                Box::new(RotatingProposer::new(vec![proposer], contiguous_rounds))
             }
             ConsensusProposerType::LeaderReputation(heuristic_config) => {
                 let backend = Box::new(DiemDBBackend::new(proposers.len(), self.storage.diem_db()));
@@ -189,6 +254,18 @@ impl EpochManager {
                 ));
                 Box::new(LeaderReputation::new(proposers, backend, heuristic))
             }
+            ConsensusProposerType::ProposerAndVoterReputation(heuristic_config) => {
+                let backend = Box::new(DiemDBBackend::new(proposers.len(), self.storage.diem_db()));
+                let heuristic = Box::new(ProposerAndVoterHeuristic::new(
+                    self.author,
+                    heuristic_config.active_weights,
+                    heuristic_config.inactive_weights,
+                    heuristic_config.failed_weights,
+                    heuristic_config.failure_threshold_percent,
+                    heuristic_config.voter_threshold_percent,
+                ));
+                Box::new(LeaderReputation::new(proposers, backend, heuristic))
+            }
             ConsensusProposerType::RoundProposer(round_proposers) => {
                 // Hardcoded to the first proposer
                 let default_proposer = proposers.get(0).unwrap();
@@ -296,6 +373,8 @@ impl EpochManager {
     ) {
         // Release the previous RoundManager, especially the SafetyRule client
         self.processor = None;
+        // Stop the BatchCoordinator of the previous epoch
+        self.quorum_store_msg_tx = None;
         let epoch = epoch_state.epoch;
         counters::EPOCH.set(epoch_state.epoch as i64);
         counters::CURRENT_EPOCH_VALIDATORS.set(epoch_state.verifier.len() as i64);
@@ -320,11 +399,14 @@ impl EpochManager {
         }
 
         info!(epoch = epoch, "Create RoundState");
-        let round_state =
-            self.create_round_state(self.time_service.clone(), self.timeout_sender.clone());
+        let round_state = self.create_round_state(
+            self.time_service.clone(),
+            self.timeout_sender.clone(),
+            &onchain_config,
+        );
 
         info!(epoch = epoch, "Create ProposerElection");
-        let proposer_election = self.create_proposer_election(&epoch_state);
+        let proposer_election = self.create_proposer_election(&epoch_state, &onchain_config);
         let network_sender = NetworkSender::new(
             self.author,
             self.network_sender.clone(),
@@ -334,6 +416,31 @@ impl EpochManager {
 
         let safety_rules_container = Arc::new(Mutex::new(safety_rules));
 
+        let txn_manager = if onchain_config.quorum_store() {
+            info!(epoch = epoch, "Start BatchCoordinator");
+            let proof_queue = Arc::new(ProofQueue::new(self.batch_store.clone()));
+            let (quorum_store_msg_tx, quorum_store_msg_rx) =
+                channel::new(1_024, &counters::PENDING_QUORUM_STORE_EVENTS);
+            let coordinator = BatchCoordinator::new(
+                self.author,
+                epoch,
+                self.config.quorum_store.clone(),
+                network_sender.clone(),
+                self.txn_manager.clone(),
+                self.batch_store.clone(),
+                proof_queue.clone(),
+                safety_rules_container.clone(),
+            );
+            tokio::spawn(coordinator.start(quorum_store_msg_rx));
+            self.quorum_store_msg_tx = Some(quorum_store_msg_tx);
+            Arc::new(QuorumStoreTxnManager::new(
+                proof_queue,
+                self.txn_manager.clone(),
+            )) as Arc<dyn TxnManager>
+        } else {
+            self.txn_manager.clone()
+        };
+
         // TODO: prepare decoupled execution
         let mut processor = {
             info!(epoch = epoch, "Create BlockStore");
@@ -351,9 +458,17 @@ impl EpochManager {
             let proposal_generator = ProposalGenerator::new(
                 self.author,
                 block_store.clone(),
-                self.txn_manager.clone(),
+                txn_manager.clone(),
                 self.time_service.clone(),
-                self.config.max_block_size,
+                onchain_config
+                    .max_block_size()
+                    .unwrap_or(self.config.max_block_size),
+                // Only the proposer and voter heuristic reads the failed authors, and recording
+                // them requires `DiemBlock::block_prologue_ext` on-chain.
+                matches!(
+                    self.proposer_type(&onchain_config).0,
+                    ConsensusProposerType::ProposerAndVoterReputation(_)
+                ),
             );
 
             RoundManager::new(
@@ -364,7 +479,7 @@ impl EpochManager {
                 proposal_generator,
                 safety_rules_container,
                 network_sender,
-                self.txn_manager.clone(),
+                txn_manager,
                 self.storage.clone(),
                 self.config.sync_only,
                 onchain_config,
@@ -387,6 +502,7 @@ impl EpochManager {
         onchain_config: OnChainConsensusConfig,
     ) {
         let epoch = epoch_state.epoch;
+        self.quorum_store_msg_tx = None;
         let network_sender = NetworkSender::new(
             self.author,
             self.network_sender.clone(),
@@ -411,11 +527,23 @@ impl EpochManager {
         let validator_set: ValidatorSet = payload
             .get()
             .expect("failed to get ValidatorSet from payload");
+        let bls_keys: ValidatorBlsKeys = payload.get().unwrap_or_default();
         let epoch_state = EpochState {
             epoch: payload.epoch(),
-            verifier: (&validator_set).into(),
+            verifier: ValidatorVerifier::from(&validator_set)
+                .with_bls_public_keys(&bls_keys.verified_public_keys()),
         };
         let onchain_config: OnChainConsensusConfig = payload.get().unwrap_or_default();
+        self.commit_state_computer.new_epoch(&epoch_state);
+        self.batch_store.new_epoch(
+            epoch_state.epoch,
+            NetworkSender::new(
+                self.author,
+                self.network_sender.clone(),
+                self.self_sender.clone(),
+                epoch_state.verifier.clone(),
+            ),
+        );
 
         match self.storage.start() {
             LivenessStorageData::RecoveryData(initial_data) => {
@@ -469,7 +597,10 @@ impl EpochManager {
             | ConsensusMsg::SyncInfo(_)
             | ConsensusMsg::VoteMsg(_)
             | ConsensusMsg::CommitVoteMsg(_)
-            | ConsensusMsg::CommitDecisionMsg(_) => {
+            | ConsensusMsg::CommitDecisionMsg(_)
+            | ConsensusMsg::BatchMsg(_)
+            | ConsensusMsg::SignedBatchInfoMsg(_)
+            | ConsensusMsg::ProofOfStoreMsg(_) => {
                 let event: UnverifiedEvent = msg.into();
                 if event.epoch() == self.epoch() {
                     return Ok(Some(event));
@@ -531,6 +662,11 @@ impl EpochManager {
                             "Ignoring commit vote/decision message during recovery"
                         ));
                     }
+                    VerifiedEvent::Batch(_)
+                    | VerifiedEvent::SignedBatchInfo(_)
+                    | VerifiedEvent::ProofOfStore(_) => {
+                        return Err(anyhow!("Ignoring quorum store message during recovery"));
+                    }
                 }?;
                 let epoch_state = p.epoch_state().clone();
                 let onchain_config = p.onchain_config().clone();
@@ -563,6 +699,21 @@ impl EpochManager {
                         bail!("Commit Phase not started but received Commit Message (CommitVote/CommitDecision)");
                     }
                 }
+                verified_event @ VerifiedEvent::Batch(_)
+                | verified_event @ VerifiedEvent::SignedBatchInfo(_)
+                | verified_event @ VerifiedEvent::ProofOfStore(_) => {
+                    if let Some(sender) = &self.quorum_store_msg_tx {
+                        sender
+                            .clone()
+                            .send((peer_id, verified_event))
+                            .await
+                            .map_err(|err| {
+                                anyhow!("Error in passing quorum store message: {}", err)
+                            })
+                    } else {
+                        bail!("Quorum store not enabled but received quorum store message");
+                    }
+                }
             },
         }
     }
@@ -583,6 +734,10 @@ impl EpochManager {
         }
     }
 
+    fn process_batch_request(&self, request: IncomingBatchRequest) -> anyhow::Result<()> {
+        self.batch_store.process_batch_request(request)
+    }
+
     async fn process_local_timeout(&mut self, round: u64) -> anyhow::Result<()> {
         match self.processor_mut() {
             RoundProcessor::Normal(p) => p.process_local_timeout(round).await,
@@ -616,6 +771,13 @@ impl EpochManager {
                     block_retrieval = network_receivers.block_retrieval.select_next_some() => {
                         monitor!("process_block_retrieval", self.process_block_retrieval(block_retrieval).await)
                     }
+                    msg = network_receivers.quorum_store_messages.select_next_some() => {
+                        let (peer, msg) = (msg.0, msg.1);
+                        monitor!("process_quorum_store_message", self.process_message(peer, msg).await.with_context(|| format!("from peer: {}", peer)))
+                    }
+                    batch_request = network_receivers.batch_retrieval.select_next_some() => {
+                        monitor!("process_batch_request", self.process_batch_request(batch_request))
+                    }
                     round = round_timeout_sender_rx.select_next_some() => {
                         monitor!("process_local_timeout", self.process_local_timeout(round).await)
                     }
@@ -241,10 +241,14 @@ impl CommitPhase {
                 )); // ignore the message
             }
 
-            // add the signature into the signature tree
-            pending_blocks
-                .ledger_info_sig_mut()
-                .add_signature(commit_vote.author(), commit_vote.signature().clone());
+            // add the signature into the signature tree, commit votes are only signed with
+            // Ed25519 so the pending ledger info is always a V0 one
+            if let LedgerInfoWithSignatures::V0(ledger_info_sig) =
+                pending_blocks.ledger_info_sig_mut()
+            {
+                ledger_info_sig
+                    .add_signature(commit_vote.author(), commit_vote.signature().clone());
+            }
         } else {
             info!("Ignore the commit vote message because the commit phase does not have a pending block.")
         }
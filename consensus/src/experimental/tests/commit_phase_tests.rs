@@ -17,7 +17,10 @@ use mango_infallible::Mutex;
 use crate::{
     experimental::ordering_state_computer::OrderingStateComputer, state_replication::StateComputer,
 };
-use consensus_types::block::{block_test_utils::certificate_for_genesis, Block};
+use consensus_types::{
+    block::{block_test_utils::certificate_for_genesis, Block},
+    common::Payload,
+};
 use mango_crypto::{ed25519::Ed25519Signature, hash::ACCUMULATOR_PLACEHOLDER_HASH};
 
 use futures::future::FutureExt;
@@ -88,13 +91,11 @@ fn generate_random_commit_vote(signer: &ValidatorSigner) -> CommitVote {
 fn generate_random_commit_decision(signer: &ValidatorSigner) -> CommitDecision {
     let dummy_ledger_info = LedgerInfo::new(BlockInfo::random(0), *ACCUMULATOR_PLACEHOLDER_HASH);
 
-    let mut dummy_ledger_info_with_sig = LedgerInfoWithSignatures::new(
+    let dummy_ledger_info_with_sig = LedgerInfoWithSignatures::new(
         dummy_ledger_info.clone(),
-        BTreeMap::<AccountAddress, Ed25519Signature>::new(),
+        std::iter::once((signer.author(), signer.sign(&dummy_ledger_info))).collect(),
     );
 
-    dummy_ledger_info_with_sig.add_signature(signer.author(), signer.sign(&dummy_ledger_info));
-
     CommitDecision::new(dummy_ledger_info_with_sig)
 }
 
@@ -231,7 +232,8 @@ mod commit_phase_e2e_tests {
         ) = prepare_commit_phase(&runtime);
 
         let genesis_qc = certificate_for_genesis();
-        let block = Block::new_proposal(vec![], 1, 1, genesis_qc, signers.first().unwrap());
+        let block =
+            Block::new_proposal(Payload::empty(), 1, 1, genesis_qc, signers.first().unwrap());
         let compute_result = state_computer
             .compute(&block, *ACCUMULATOR_PLACEHOLDER_HASH)
             .unwrap();
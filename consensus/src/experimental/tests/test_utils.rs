@@ -20,6 +20,7 @@ use crate::{
 use channel::{diem_channel, message_queues::QueueStyle, Receiver, Sender};
 use consensus_types::{
     block::{block_test_utils::certificate_for_genesis, Block},
+    common::Payload,
     executed_block::ExecutedBlock,
 };
 use diem_secure_storage::Storage;
@@ -177,7 +178,7 @@ pub fn prepare_executed_blocks_with_ledger_info(
     consensus_hash: HashValue,
 ) -> (Vec<ExecutedBlock>, LedgerInfoWithSignatures) {
     let genesis_qc = certificate_for_genesis();
-    let block = Block::new_proposal(vec![], 1, 1, genesis_qc, signer);
+    let block = Block::new_proposal(Payload::empty(), 1, 1, genesis_qc, signer);
     let compute_result = StateComputeResult::new(
         executed_hash,
         vec![], // dummy subtree
@@ -199,13 +200,11 @@ pub fn prepare_executed_blocks_with_ledger_info(
         consensus_hash,
     );
 
-    let mut li_sig = LedgerInfoWithSignatures::new(
+    let li_sig = LedgerInfoWithSignatures::new(
         li.clone(),
-        BTreeMap::<AccountAddress, Ed25519Signature>::new(),
+        std::iter::once((signer.author(), signer.sign(&li))).collect(),
     );
 
-    li_sig.add_signature(signer.author(), signer.sign(&li));
-
     let executed_block = ExecutedBlock::new(block, compute_result);
 
     (vec![executed_block], li_sig)
@@ -0,0 +1,285 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline inspection of the blocks and quorum certificates persisted in ConsensusDB, used to
+//! debug a validator that fails to recover after a crash.
+
+use crate::{
+    consensusdb::ConsensusDB,
+    persistent_liveness_storage::{
+        deserialize_last_vote, LedgerRecoveryData, RecoveryData, RootMetadata,
+    },
+};
+use anyhow::{format_err, Result};
+use consensus_types::{
+    block::Block, common::Author, quorum_cert::QuorumCert,
+    timeout_2chain::TwoChainTimeoutCertificate, timeout_certificate::TimeoutCertificate,
+    vote::Vote,
+};
+use executor_types::ExecutedTrees;
+use mango_crypto::HashValue;
+use mango_types::block_info::Round;
+use serde::Serialize;
+use std::{collections::HashMap, fmt::Write, path::Path};
+use storage_interface::DbReader;
+
+/// Everything persisted in ConsensusDB, decoded.
+#[derive(Serialize)]
+pub struct ConsensusDbDump {
+    /// The last vote message sent by this validator.
+    pub last_vote: Option<Vote>,
+    /// The highest (3-chain) timeout certificate.
+    pub highest_timeout_certificate: Option<TimeoutCertificate>,
+    /// The highest 2-chain timeout certificate.
+    pub highest_2chain_timeout_certificate: Option<TwoChainTimeoutCertificate>,
+    /// The blocks, sorted by (epoch, round).
+    pub blocks: Vec<Block>,
+    /// The quorum certificates, sorted by the (epoch, round) of the certified block.
+    pub quorum_certs: Vec<QuorumCert>,
+}
+
+impl ConsensusDbDump {
+    /// Reads the ConsensusDB under `db_root_path` in readonly mode.
+    pub fn load<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let db = ConsensusDB::new_readonly(db_root_path)?;
+        let (last_vote, highest_tc, highest_2chain_tc, mut blocks, mut quorum_certs) =
+            db.get_data()?;
+        blocks.sort_by_key(|b| (b.epoch(), b.round()));
+        quorum_certs.sort_by_key(|qc| (qc.certified_block().epoch(), qc.certified_block().round()));
+
+        Ok(Self {
+            last_vote: last_vote
+                .map(|bytes| deserialize_last_vote(&bytes))
+                .transpose()?,
+            highest_timeout_certificate: highest_tc
+                .map(|bytes| bcs::from_bytes(&bytes))
+                .transpose()?,
+            highest_2chain_timeout_certificate: highest_2chain_tc
+                .map(|bytes| bcs::from_bytes(&bytes))
+                .transpose()?,
+            blocks,
+            quorum_certs,
+        })
+    }
+
+    /// Summaries of the persisted blocks, in (epoch, round) order.
+    pub fn block_summaries(&self) -> Vec<BlockSummary> {
+        self.blocks.iter().map(BlockSummary::new).collect()
+    }
+
+    /// The quorum certificate certifying the highest block.
+    pub fn highest_quorum_cert(&self) -> Option<&QuorumCert> {
+        self.quorum_certs.last()
+    }
+
+    /// Rebuilds the block tree the way `RecoveryData` would on startup, rooted at the block of
+    /// the latest ledger info in `diem_db`. Nothing is pruned from ConsensusDB.
+    pub fn recover(&self, diem_db: &dyn DbReader) -> Result<RecoveredTree> {
+        let startup_info = diem_db
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("startup info is None"))?;
+        let ledger_recovery_data = LedgerRecoveryData::new(startup_info.latest_ledger_info.clone());
+        let frozen_root_hashes = startup_info
+            .committed_tree_state
+            .ledger_frozen_subtree_hashes
+            .clone();
+        let root_executed_trees = ExecutedTrees::from(startup_info.committed_tree_state);
+        let mut recovery_data = RecoveryData::new(
+            self.last_vote.clone(),
+            ledger_recovery_data,
+            self.blocks.clone(),
+            RootMetadata::new(
+                root_executed_trees.txn_accumulator().num_leaves(),
+                root_executed_trees.state_id(),
+                frozen_root_hashes,
+            ),
+            self.quorum_certs.clone(),
+            self.highest_timeout_certificate.clone(),
+            self.highest_2chain_timeout_certificate.clone(),
+        )?;
+
+        let blocks_to_prune = recovery_data.take_blocks_to_prune();
+        let last_vote_round = recovery_data
+            .last_vote()
+            .map(|vote| vote.vote_data().proposed().round());
+        let highest_timeout_round = recovery_data
+            .highest_2chain_timeout_certificate()
+            .map(|tc| tc.round())
+            .or_else(|| {
+                recovery_data
+                    .highest_timeout_certificate()
+                    .map(|tc| tc.round())
+            });
+        let (root, _, blocks, _) = recovery_data.take();
+
+        Ok(RecoveredTree {
+            root: BlockSummary::new(&root.0),
+            blocks: blocks.iter().map(BlockSummary::new).collect(),
+            blocks_to_prune,
+            last_vote_round,
+            highest_timeout_round,
+        })
+    }
+}
+
+/// A short description of a persisted block.
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockSummary {
+    /// Id of the block.
+    pub id: HashValue,
+    /// Epoch of the block.
+    pub epoch: u64,
+    /// Round of the block.
+    pub round: Round,
+    /// Id of the parent block, as certified by the QC of the block.
+    pub parent_id: HashValue,
+    /// Author of the block, None for NIL and genesis blocks.
+    pub author: Option<Author>,
+    /// Number of transactions carried or referenced by the payload.
+    pub payload_txns: usize,
+    /// Size of the BCS serialized payload in bytes.
+    pub payload_bytes: usize,
+}
+
+impl BlockSummary {
+    /// Summarizes the given block.
+    pub fn new(block: &Block) -> Self {
+        let (payload_txns, payload_bytes) = block.payload().map_or((0, 0), |payload| {
+            (
+                payload.len(),
+                bcs::serialized_size(payload).expect("payload serialization must not fail"),
+            )
+        });
+        Self {
+            id: block.id(),
+            epoch: block.epoch(),
+            round: block.round(),
+            parent_id: block.parent_id(),
+            author: block.author(),
+            payload_txns,
+            payload_bytes,
+        }
+    }
+}
+
+/// The block tree rebuilt from ConsensusDB as `RecoveryData` would on startup.
+#[derive(Debug, Serialize)]
+pub struct RecoveredTree {
+    /// The root of the tree, i.e. the block of the latest ledger info.
+    pub root: BlockSummary,
+    /// The descendants of the root in topological order.
+    pub blocks: Vec<BlockSummary>,
+    /// The blocks that are not descendants of the root and would be pruned on startup.
+    pub blocks_to_prune: Vec<HashValue>,
+    /// Round of the last vote, if it is kept by recovery.
+    pub last_vote_round: Option<Round>,
+    /// Round of the highest timeout certificate, if it is kept by recovery.
+    pub highest_timeout_round: Option<Round>,
+}
+
+impl RecoveredTree {
+    /// Renders the tree with one block per line, children indented below their parent.
+    pub fn render(&self) -> String {
+        let mut children: HashMap<HashValue, Vec<&BlockSummary>> = HashMap::new();
+        for block in &self.blocks {
+            children.entry(block.parent_id).or_default().push(block);
+        }
+
+        let mut output = String::new();
+        let mut stack = vec![(&self.root, 0)];
+        while let Some((block, depth)) = stack.pop() {
+            writeln!(
+                output,
+                "{:indent$}[id: {}, epoch: {}, round: {:02}, txns: {}]",
+                "",
+                block.id,
+                block.epoch,
+                block.round,
+                block.payload_txns,
+                indent = depth * 2
+            )
+            .expect("writing to a string must not fail");
+            if let Some(blocks) = children.get(&block.id) {
+                // push in reverse so that lower rounds are printed first
+                stack.extend(blocks.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_types::block::block_test_utils::{
+        certificate_for_genesis, placeholder_certificate_for_block, random_payload,
+    };
+    use mango_temppath::TempPath;
+    use mango_types::validator_signer::ValidatorSigner;
+
+    // genesis <- a1 <- a2
+    //               <- b3
+    fn make_blocks() -> (Vec<Block>, Vec<QuorumCert>) {
+        let signer = ValidatorSigner::random(None);
+        let genesis = Block::make_genesis_block();
+        let genesis_qc = certificate_for_genesis();
+        let a1 = Block::new_proposal(random_payload(2), 1, 1, genesis_qc.clone(), &signer);
+        let a1_qc = placeholder_certificate_for_block(
+            vec![&signer],
+            a1.id(),
+            a1.round(),
+            genesis.id(),
+            genesis.round(),
+        );
+        let a2 = Block::new_proposal(random_payload(3), 2, 2, a1_qc.clone(), &signer);
+        let b3 = Block::new_proposal(random_payload(0), 3, 3, a1_qc.clone(), &signer);
+        (vec![b3, a2, a1, genesis], vec![a1_qc, genesis_qc])
+    }
+
+    #[test]
+    fn test_load_dump() {
+        let tmp_dir = TempPath::new();
+        let (blocks, quorum_certs) = make_blocks();
+        ConsensusDB::new(&tmp_dir)
+            .save_blocks_and_quorum_certificates(blocks.clone(), quorum_certs.clone())
+            .unwrap();
+
+        let dump = ConsensusDbDump::load(&tmp_dir).unwrap();
+        assert!(dump.last_vote.is_none());
+        assert!(dump.highest_timeout_certificate.is_none());
+        assert!(dump.highest_2chain_timeout_certificate.is_none());
+        let rounds: Vec<_> = dump.blocks.iter().map(|b| b.round()).collect();
+        assert_eq!(rounds, vec![0, 1, 2, 3]);
+        assert_eq!(dump.highest_quorum_cert(), Some(&quorum_certs[0]));
+
+        let summaries = dump.block_summaries();
+        assert_eq!(summaries[1].id, blocks[2].id());
+        assert_eq!(summaries[1].parent_id, blocks[3].id());
+        assert_eq!(summaries[1].payload_txns, 2);
+        assert!(summaries[1].payload_bytes > 0);
+        assert_eq!(summaries[2].payload_txns, 3);
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let (blocks, _) = make_blocks();
+        let tree = RecoveredTree {
+            root: BlockSummary::new(&blocks[3]),
+            blocks: vec![
+                BlockSummary::new(&blocks[2]),
+                BlockSummary::new(&blocks[1]),
+                BlockSummary::new(&blocks[0]),
+            ],
+            blocks_to_prune: vec![],
+            last_vote_round: None,
+            highest_timeout_round: None,
+        };
+
+        let lines: Vec<_> = tree.render().lines().map(str::to_string).collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with(&format!("[id: {}", blocks[3].id())));
+        assert!(lines[1].starts_with(&format!("  [id: {}", blocks[2].id())));
+        assert!(lines[2].starts_with(&format!("    [id: {}", blocks[1].id())));
+        assert!(lines[3].starts_with(&format!("    [id: {}", blocks[0].id())));
+    }
+}
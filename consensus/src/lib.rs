@@ -26,6 +26,7 @@ mod network;
 mod network_tests;
 mod pending_votes;
 mod persistent_liveness_storage;
+mod quorum_store;
 mod round_manager;
 mod state_computer;
 mod state_replication;
@@ -38,6 +39,8 @@ mod util;
 
 /// DiemBFT implementation
 pub mod consensus_provider;
+/// Offline ConsensusDB inspection.
+pub mod inspector;
 /// DiemNet interface.
 pub mod network_interface;
 
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    counters::{COMMITTED_PROPOSALS_IN_WINDOW, COMMITTED_VOTES_IN_WINDOW},
+    counters::{
+        COMMITTED_PROPOSALS_IN_WINDOW, COMMITTED_VOTES_IN_WINDOW, FAILED_PROPOSALS_IN_WINDOW,
+        LEADER_REPUTATION_SCORES,
+    },
     liveness::proposer_election::{next, ProposerElection},
 };
 use consensus_types::{
@@ -53,7 +56,7 @@ impl DiemDBBackend {
         )?;
         let mut result = vec![];
         for (v, e) in events {
-            let e = bcs::from_bytes::<NewBlockEvent>(e.event_data())?;
+            let e = NewBlockEvent::try_from_bytes(e.event_data())?;
             if e.round() <= target_round && result.len() < self.window_size {
                 result.push((v, e));
             }
@@ -153,6 +156,105 @@ impl ReputationHeuristic for ActiveInactiveHeuristic {
     }
 }
 
+/// Besides the committed proposals and votes, takes into account the rounds the candidates failed
+/// to propose in: a candidate that failed more than `failure_threshold_percent` of its proposer
+/// rounds in the history is assigned failed_weight. Otherwise it's assigned active_weight if it
+/// proposed in the history or voted in at least `voter_threshold_percent` of its blocks, and
+/// inactive_weight if it did neither.
+pub struct ProposerAndVoterHeuristic {
+    author: Author,
+    active_weight: u64,
+    inactive_weight: u64,
+    failed_weight: u64,
+    failure_threshold_percent: u64,
+    voter_threshold_percent: u64,
+}
+
+impl ProposerAndVoterHeuristic {
+    pub fn new(
+        author: Author,
+        active_weight: u64,
+        inactive_weight: u64,
+        failed_weight: u64,
+        failure_threshold_percent: u64,
+        voter_threshold_percent: u64,
+    ) -> Self {
+        Self {
+            author,
+            active_weight,
+            inactive_weight,
+            failed_weight,
+            failure_threshold_percent,
+            voter_threshold_percent,
+        }
+    }
+}
+
+impl ReputationHeuristic for ProposerAndVoterHeuristic {
+    fn get_weights(&self, candidates: &[Author], history: &[NewBlockEvent]) -> Vec<u64> {
+        let mut proposals = HashMap::new();
+        let mut failed_proposals = HashMap::new();
+        let mut votes = HashMap::new();
+        for meta in history {
+            *proposals.entry(meta.proposer()).or_insert(0u64) += 1;
+            for failed_proposer in meta.failed_proposers() {
+                *failed_proposals.entry(*failed_proposer).or_insert(0u64) += 1;
+            }
+            for vote in meta.votes() {
+                *votes.entry(vote).or_insert(0u64) += 1;
+            }
+        }
+        let count = |counts: &HashMap<Author, u64>, author: &Author| {
+            counts.get(author).copied().unwrap_or(0)
+        };
+
+        COMMITTED_PROPOSALS_IN_WINDOW.set(count(&proposals, &self.author) as i64);
+        FAILED_PROPOSALS_IN_WINDOW.set(count(&failed_proposals, &self.author) as i64);
+        COMMITTED_VOTES_IN_WINDOW.set(count(&votes, &self.author) as i64);
+
+        candidates
+            .iter()
+            .map(|author| {
+                let committed = count(&proposals, author);
+                let failed = count(&failed_proposals, author);
+                let voted = count(&votes, author);
+                let failed_percent = if committed + failed > 0 {
+                    failed * 100 / (committed + failed)
+                } else {
+                    0
+                };
+                let vote_participation_percent = if history.is_empty() {
+                    0
+                } else {
+                    voted * 100 / history.len() as u64
+                };
+
+                let weight = if failed_percent > self.failure_threshold_percent {
+                    self.failed_weight
+                } else if committed > 0
+                    || (voted > 0 && vote_participation_percent >= self.voter_threshold_percent)
+                {
+                    self.active_weight
+                } else {
+                    self.inactive_weight
+                };
+
+                let validator = author.short_str();
+                for (score, value) in [
+                    ("weight", weight),
+                    ("failed_proposals_percent", failed_percent),
+                    ("vote_participation_percent", vote_participation_percent),
+                ] {
+                    LEADER_REPUTATION_SCORES
+                        .with_label_values(&[validator.as_str(), score])
+                        .set(value as i64);
+                }
+                weight
+            })
+            .collect()
+    }
+}
+
 /// Committed history based proposer election implementation that could help bias towards
 /// successful leaders to help improve performance.
 pub struct LeaderReputation {
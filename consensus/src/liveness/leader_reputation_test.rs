@@ -3,13 +3,14 @@
 
 use crate::liveness::{
     leader_reputation::{
-        ActiveInactiveHeuristic, LeaderReputation, MetadataBackend, ReputationHeuristic,
+        ActiveInactiveHeuristic, LeaderReputation, MetadataBackend, ProposerAndVoterHeuristic,
+        ReputationHeuristic,
     },
     proposer_election::{next, ProposerElection},
 };
 use consensus_types::{
     block::{block_test_utils::certificate_for_genesis, Block},
-    common::{Author, Round},
+    common::{Author, Payload, Round},
 };
 use mango_types::{block_metadata::NewBlockEvent, validator_signer::ValidatorSigner};
 
@@ -36,7 +37,21 @@ impl MetadataBackend for MockHistory {
 }
 
 fn create_block(proposer: Author, voters: Vec<&ValidatorSigner>) -> NewBlockEvent {
-    NewBlockEvent::new(0, proposer, voters.iter().map(|v| v.author()).collect(), 0)
+    create_block_with_failed_proposers(proposer, voters, vec![])
+}
+
+fn create_block_with_failed_proposers(
+    proposer: Author,
+    voters: Vec<&ValidatorSigner>,
+    failed_proposers: Vec<Author>,
+) -> NewBlockEvent {
+    NewBlockEvent::new(
+        0,
+        proposer,
+        voters.iter().map(|v| v.author()).collect(),
+        0,
+        failed_proposers,
+    )
 }
 
 #[test]
@@ -76,6 +91,65 @@ fn test_simple_heuristic() {
     }
 }
 
+#[test]
+fn test_proposer_and_voter_heuristic() {
+    let active_weight = 9;
+    let inactive_weight = 2;
+    let failed_weight = 1;
+    let mut proposers = vec![];
+    let mut signers = vec![];
+    for i in 0..8 {
+        let signer = ValidatorSigner::random([i; 32]);
+        proposers.push(signer.author());
+        signers.push(signer);
+    }
+    let heuristic = ProposerAndVoterHeuristic::new(
+        proposers[0],
+        active_weight,
+        inactive_weight,
+        failed_weight,
+        50,
+        50,
+    );
+    // 1. Window size not enough
+    let weights = heuristic.get_weights(&proposers, &[]);
+    assert_eq!(weights, vec![inactive_weight; proposers.len()]);
+    // 2. Sliding window with [proposer 0, voters 1, 2, failed 4],
+    // [proposer 4, voters 3, failed 5, 6], [proposer 0, voters 1, failed 6]
+    let weights = heuristic.get_weights(
+        &proposers,
+        &[
+            create_block_with_failed_proposers(
+                proposers[0],
+                vec![&signers[1], &signers[2]],
+                vec![proposers[4]],
+            ),
+            create_block_with_failed_proposers(
+                proposers[4],
+                vec![&signers[3]],
+                vec![proposers[5], proposers[6]],
+            ),
+            create_block_with_failed_proposers(proposers[0], vec![&signers[1]], vec![proposers[6]]),
+        ],
+    );
+    // Validator 4 failed half of its proposer rounds, which is not above the threshold, while
+    // validators 5 and 6 failed all of theirs. Validators 2 and 3 only voted in a third of the
+    // blocks, below the voter threshold.
+    assert_eq!(
+        weights,
+        vec![
+            active_weight,
+            active_weight,
+            inactive_weight,
+            inactive_weight,
+            active_weight,
+            failed_weight,
+            failed_weight,
+            inactive_weight,
+        ]
+    );
+}
+
 #[test]
 fn test_api() {
     let active_weight = 9;
@@ -130,7 +204,7 @@ fn test_api() {
     assert!(proposer_election.is_valid_proposer(proposers[expected_index], 42));
     assert!(!proposer_election.is_valid_proposer(proposers[unexpected_index], 42));
     let good_proposal = Block::new_proposal(
-        vec![],
+        Payload::empty(),
         round,
         1,
         certificate_for_genesis(),
@@ -138,7 +212,7 @@ fn test_api() {
     );
     assert!(proposer_election.is_valid_proposal(&good_proposal));
     let bad_proposal = Block::new_proposal(
-        vec![],
+        Payload::empty(),
         round,
         1,
         certificate_for_genesis(),
@@ -146,7 +220,7 @@ fn test_api() {
     );
     assert!(!proposer_election.is_valid_proposal(&bad_proposal));
     let bad_proposal_2 = Block::new_proposal(
-        vec![],
+        Payload::empty(),
         round,
         2,
         certificate_for_genesis(),
@@ -3,7 +3,10 @@
 
 use crate::{
     block_storage::BlockReader,
-    liveness::proposal_generator::ProposalGenerator,
+    liveness::{
+        proposal_generator::{ProposalGenerator, MAX_FAILED_AUTHORS_TO_STORE},
+        rotating_proposer_election::RotatingProposer,
+    },
     test_utils::{build_empty_tree, MockTransactionManager, TreeInserter},
     util::mock_time_service::SimulatedTimeService,
 };
@@ -21,18 +24,27 @@ async fn test_proposal_generation_empty_tree() {
         Arc::new(MockTransactionManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        false,
     );
+    let proposer_election = RotatingProposer::new(vec![signer.author()], 1);
     let genesis = block_store.ordered_root();
 
     // Generate proposals for an empty tree.
-    let proposal_data = proposal_generator.generate_proposal(1).await.unwrap();
+    let proposal_data = proposal_generator
+        .generate_proposal(1, &proposer_election)
+        .await
+        .unwrap();
     let proposal = Block::new_proposal_from_block_data(proposal_data, &signer);
     assert_eq!(proposal.parent_id(), genesis.id());
     assert_eq!(proposal.round(), 1);
     assert_eq!(proposal.quorum_cert().certified_block().id(), genesis.id());
+    assert_eq!(proposal.failed_authors(), Some(&vec![]));
 
     // Duplicate proposals on the same round are not allowed
-    let proposal_err = proposal_generator.generate_proposal(1).await.err();
+    let proposal_err = proposal_generator
+        .generate_proposal(1, &proposer_election)
+        .await
+        .err();
     assert!(proposal_err.is_some());
 }
 
@@ -46,35 +58,65 @@ async fn test_proposal_generation_parent() {
         Arc::new(MockTransactionManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        true,
     );
+    let proposer_election = RotatingProposer::new(vec![inserter.signer().author()], 1);
     let genesis = block_store.ordered_root();
     let a1 = inserter.insert_block_with_qc(certificate_for_genesis(), &genesis, 1);
     let b1 = inserter.insert_block_with_qc(certificate_for_genesis(), &genesis, 2);
 
     // With no certifications the parent is genesis
     // generate proposals for an empty tree.
+    let genesis_child_res = proposal_generator
+        .generate_proposal(10, &proposer_election)
+        .await
+        .unwrap();
+    assert_eq!(genesis_child_res.parent_id(), genesis.id());
+    // The proposers of the rounds 1 to 9 failed
+    let expected_failed_authors: Vec<_> = (1..10)
+        .map(|round| (round, inserter.signer().author()))
+        .collect();
     assert_eq!(
-        proposal_generator
-            .generate_proposal(10)
-            .await
-            .unwrap()
-            .parent_id(),
-        genesis.id()
+        genesis_child_res.failed_authors(),
+        Some(&expected_failed_authors)
     );
 
     // Once a1 is certified, it should be the one to choose from
     inserter.insert_qc_for_block(a1.as_ref(), None);
-    let a1_child_res = proposal_generator.generate_proposal(11).await.unwrap();
+    let a1_child_res = proposal_generator
+        .generate_proposal(11, &proposer_election)
+        .await
+        .unwrap();
     assert_eq!(a1_child_res.parent_id(), a1.id());
     assert_eq!(a1_child_res.round(), 11);
     assert_eq!(a1_child_res.quorum_cert().certified_block().id(), a1.id());
+    assert_eq!(a1_child_res.failed_authors().unwrap().len(), 9);
 
     // Once b1 is certified, it should be the one to choose from
     inserter.insert_qc_for_block(b1.as_ref(), None);
-    let b1_child_res = proposal_generator.generate_proposal(12).await.unwrap();
+    let b1_child_res = proposal_generator
+        .generate_proposal(12, &proposer_election)
+        .await
+        .unwrap();
     assert_eq!(b1_child_res.parent_id(), b1.id());
     assert_eq!(b1_child_res.round(), 12);
     assert_eq!(b1_child_res.quorum_cert().certified_block().id(), b1.id());
+
+    // Without recording, the proposals don't carry failed authors
+    let mut proposal_generator = ProposalGenerator::new(
+        inserter.signer().author(),
+        block_store.clone(),
+        Arc::new(MockTransactionManager::new(None)),
+        Arc::new(SimulatedTimeService::new()),
+        1,
+        false,
+    );
+    let b1_child_res = proposal_generator
+        .generate_proposal(20, &proposer_election)
+        .await
+        .unwrap();
+    assert_eq!(b1_child_res.parent_id(), b1.id());
+    assert_eq!(b1_child_res.failed_authors(), Some(&vec![]));
 }
 
 #[tokio::test]
@@ -87,11 +129,36 @@ async fn test_old_proposal_generation() {
         Arc::new(MockTransactionManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        false,
     );
+    let proposer_election = RotatingProposer::new(vec![inserter.signer().author()], 1);
     let genesis = block_store.ordered_root();
     let a1 = inserter.insert_block_with_qc(certificate_for_genesis(), &genesis, 1);
     inserter.insert_qc_for_block(a1.as_ref(), None);
 
-    let proposal_err = proposal_generator.generate_proposal(1).await.err();
+    let proposal_err = proposal_generator
+        .generate_proposal(1, &proposer_election)
+        .await
+        .err();
     assert!(proposal_err.is_some());
 }
+
+#[test]
+fn test_compute_failed_authors() {
+    let author = ValidatorSigner::random(None).author();
+    let proposer_election = RotatingProposer::new(vec![author], 1);
+
+    assert!(ProposalGenerator::compute_failed_authors(5, 4, &proposer_election).is_empty());
+    assert_eq!(
+        ProposalGenerator::compute_failed_authors(5, 2, &proposer_election),
+        vec![(3, author), (4, author)]
+    );
+    // Only the most recent failed rounds are kept
+    let failed_authors = ProposalGenerator::compute_failed_authors(
+        MAX_FAILED_AUTHORS_TO_STORE as u64 + 20,
+        0,
+        &proposer_election,
+    );
+    assert_eq!(failed_authors.len(), MAX_FAILED_AUTHORS_TO_STORE);
+    assert_eq!(failed_authors[0].0, 20);
+}
@@ -7,6 +7,7 @@ use crate::{
     util::time_service::{SendTask, TimeService},
 };
 use consensus_types::{common::Round, sync_info::SyncInfo, vote::Vote};
+use mango_crypto::bls12381::Bls12381Signature;
 use mango_logger::{prelude::*, Schema};
 use mango_types::validator_verifier::ValidatorVerifier;
 use serde::Serialize;
@@ -255,10 +256,12 @@ impl RoundState {
     pub fn insert_vote(
         &mut self,
         vote: &Vote,
+        bls_signature: Option<&Bls12381Signature>,
         verifier: &ValidatorVerifier,
     ) -> VoteReceptionResult {
         if vote.vote_data().proposed().round() == self.current_round {
-            self.pending_votes.insert_vote(vote, verifier)
+            self.pending_votes
+                .insert_vote_with_bls_signature(vote, bls_signature, verifier)
         } else {
             VoteReceptionResult::UnexpectedRound(
                 vote.vote_data().proposed().round(),
@@ -4,12 +4,13 @@
 use crate::persistent_liveness_storage::PersistentLivenessStorage;
 use consensus_types::{
     block_data::BlockData,
+    proof_of_store::BatchInfo,
     timeout::Timeout,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
     vote::Vote,
     vote_proposal::MaybeSignedVoteProposal,
 };
-use mango_crypto::ed25519::Ed25519Signature;
+use mango_crypto::{bls12381::Bls12381Signature, ed25519::Ed25519Signature};
 use mango_metrics::monitor;
 use mango_types::{
     epoch_change::EpochChangeProof,
@@ -78,6 +79,10 @@ impl TSafetyRules for MetricsSafetyRules {
         self.retry(|inner| monitor!("safety_rules", inner.construct_and_sign_vote(vote_proposal)))
     }
 
+    fn sign_vote_bls(&mut self, vote: &Vote) -> Result<Bls12381Signature, Error> {
+        self.retry(|inner| monitor!("safety_rules", inner.sign_vote_bls(vote)))
+    }
+
     fn sign_proposal(&mut self, block_data: &BlockData) -> Result<Ed25519Signature, Error> {
         self.retry(|inner| monitor!("safety_rules", inner.sign_proposal(block_data)))
     }
@@ -86,6 +91,10 @@ impl TSafetyRules for MetricsSafetyRules {
         self.retry(|inner| monitor!("safety_rules", inner.sign_timeout(timeout)))
     }
 
+    fn sign_batch_info(&mut self, batch_info: &BatchInfo) -> Result<Ed25519Signature, Error> {
+        self.retry(|inner| monitor!("safety_rules", inner.sign_batch_info(batch_info)))
+    }
+
     fn sign_timeout_with_qc(
         &mut self,
         timeout: &TwoChainTimeout,
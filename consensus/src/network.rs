@@ -12,6 +12,7 @@ use channel::{self, diem_channel, message_queues::QueueStyle};
 use consensus_types::{
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse, MAX_BLOCKS_PER_REQUEST},
     common::Author,
+    proof_of_store::{Batch, BatchRequest, BatchResponse},
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
 };
@@ -33,6 +34,9 @@ use std::{
     time::Duration,
 };
 
+/// Number of quorum store messages and batch requests buffered per peer.
+const QUORUM_STORE_CHANNEL_SIZE: usize = 100;
+
 /// The block retrieval request is used internally for implementing RPC: the callback is executed
 /// for carrying the response
 #[derive(Debug)]
@@ -41,6 +45,14 @@ pub struct IncomingBlockRetrievalRequest {
     pub response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
 }
 
+/// The batch request is used internally for implementing the batch RPC: the callback is executed
+/// for carrying the response
+#[derive(Debug)]
+pub struct IncomingBatchRequest {
+    pub req: BatchRequest,
+    pub response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
+}
+
 /// Just a convenience struct to keep all the network proxy receiving queues in one place.
 /// Will be returned by the NetworkTask upon startup.
 pub struct NetworkReceivers {
@@ -50,6 +62,11 @@ pub struct NetworkReceivers {
         (AccountAddress, ConsensusMsg),
     >,
     pub block_retrieval: diem_channel::Receiver<AccountAddress, IncomingBlockRetrievalRequest>,
+    /// Provide a FIFO buffer per author for the quorum store messages, none of which can be
+    /// superseded by a later one.
+    pub quorum_store_messages:
+        diem_channel::Receiver<AccountAddress, (AccountAddress, ConsensusMsg)>,
+    pub batch_retrieval: diem_channel::Receiver<AccountAddress, IncomingBatchRequest>,
 }
 
 /// Implements the actual networking support for all consensus messaging.
@@ -79,6 +96,11 @@ impl NetworkSender {
         }
     }
 
+    /// The validator verifier of the epoch this sender was created for.
+    pub fn validators(&self) -> &ValidatorVerifier {
+        &self.validators
+    }
+
     /// Tries to retrieve num of blocks backwards starting from id from the given peer: the function
     /// returns a future that is fulfilled with BlockRetrievalResponse.
     pub async fn request_block(
@@ -115,6 +137,42 @@ impl NetworkSender {
         Ok(response)
     }
 
+    /// Tries to fetch the batch with the given digest from the given peer: the function returns a
+    /// future that is fulfilled with the verified batch.
+    pub async fn request_batch(
+        &self,
+        request: BatchRequest,
+        from: Author,
+        timeout: Duration,
+    ) -> anyhow::Result<Batch> {
+        ensure!(from != self.author, "Retrieve batch from self");
+        let msg = ConsensusMsg::BatchRequestMsg(Box::new(request.clone()));
+        let response_msg = monitor!(
+            "batch_retrieval",
+            self.network_sender
+                .clone()
+                .send_rpc(from, msg, timeout)
+                .await?
+        );
+        let batch = match response_msg {
+            ConsensusMsg::BatchResponseMsg(resp) => match *resp {
+                BatchResponse::Batch(batch) => batch,
+                BatchResponse::NotFound(digest) => {
+                    return Err(anyhow!("Batch {} not found on {}", digest, from))
+                }
+            },
+            _ => return Err(anyhow!("Invalid response to request")),
+        };
+        ensure!(
+            batch.digest() == request.digest(),
+            "Received batch {} instead of {}",
+            batch.digest(),
+            request.digest()
+        );
+        batch.verify()?;
+        Ok(batch)
+    }
+
     /// Tries to send the given msg to all the participants.
     ///
     /// The future is fulfilled as soon as the message put into the mpsc channel to network
@@ -196,6 +254,8 @@ pub struct NetworkTask {
         (AccountAddress, ConsensusMsg),
     >,
     block_retrieval_tx: diem_channel::Sender<AccountAddress, IncomingBlockRetrievalRequest>,
+    quorum_store_messages_tx: diem_channel::Sender<AccountAddress, (AccountAddress, ConsensusMsg)>,
+    batch_retrieval_tx: diem_channel::Sender<AccountAddress, IncomingBatchRequest>,
     all_events: Box<dyn Stream<Item = Event<ConsensusMsg>> + Send + Unpin>,
     connections: Arc<RwLock<HashMap<PeerId, SupportedProtocols>>>,
 }
@@ -214,17 +274,31 @@ impl NetworkTask {
             1,
             Some(&counters::BLOCK_RETRIEVAL_CHANNEL_MSGS),
         );
+        let (quorum_store_messages_tx, quorum_store_messages) = diem_channel::new(
+            QueueStyle::FIFO,
+            QUORUM_STORE_CHANNEL_SIZE,
+            Some(&counters::QUORUM_STORE_CHANNEL_MSGS),
+        );
+        let (batch_retrieval_tx, batch_retrieval) = diem_channel::new(
+            QueueStyle::LIFO,
+            QUORUM_STORE_CHANNEL_SIZE,
+            Some(&counters::BATCH_RETRIEVAL_CHANNEL_MSGS),
+        );
         let all_events = Box::new(select(network_events, self_receiver));
         (
             NetworkTask {
                 consensus_messages_tx,
                 block_retrieval_tx,
+                quorum_store_messages_tx,
+                batch_retrieval_tx,
                 all_events,
                 connections,
             },
             NetworkReceivers {
                 consensus_messages,
                 block_retrieval,
+                quorum_store_messages,
+                batch_retrieval,
             },
         )
     }
@@ -232,6 +306,19 @@ impl NetworkTask {
     pub async fn start(mut self) {
         while let Some(message) = self.all_events.next().await {
             match message {
+                Event::Message(
+                    peer_id,
+                    msg @ (ConsensusMsg::BatchMsg(_)
+                    | ConsensusMsg::SignedBatchInfoMsg(_)
+                    | ConsensusMsg::ProofOfStoreMsg(_)),
+                ) => {
+                    if let Err(e) = self.quorum_store_messages_tx.push(peer_id, (peer_id, msg)) {
+                        warn!(
+                            remote_peer = peer_id,
+                            error = ?e, "Error pushing quorum store msg",
+                        );
+                    }
+                }
                 Event::Message(peer_id, msg) => {
                     if let Err(e) = self
                         .consensus_messages_tx
@@ -267,6 +354,15 @@ impl NetworkTask {
                             warn!(error = ?e, "mango channel closed");
                         }
                     }
+                    ConsensusMsg::BatchRequestMsg(request) => {
+                        let req_with_callback = IncomingBatchRequest {
+                            req: *request,
+                            response_sender: callback,
+                        };
+                        if let Err(e) = self.batch_retrieval_tx.push(peer_id, req_with_callback) {
+                            warn!(error = ?e, "mango channel closed");
+                        }
+                    }
                     _ => {
                         warn!(remote_peer = peer_id, "Unexpected msg: {:?}", msg);
                         continue;
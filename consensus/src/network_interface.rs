@@ -10,6 +10,7 @@ use consensus_types::{
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse},
     epoch_retrieval::EpochRetrievalRequest,
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
+    proof_of_store::{Batch, BatchRequest, BatchResponse, ProofOfStore, SignedBatchInfo},
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
@@ -59,6 +60,16 @@ pub enum ConsensusMsg {
     /// than 2f + 1 signatures on the commit proposal. This part is not on the critical path, but
     /// it can save slow machines to quickly confirm the execution result.
     CommitDecisionMsg(Box<CommitDecision>),
+    /// Batch of transactions broadcast by its author ahead of the proposals referencing it.
+    BatchMsg(Box<Batch>),
+    /// Signature of a validator certifying it stores the batch, sent back to the batch author.
+    SignedBatchInfoMsg(Box<SignedBatchInfo>),
+    /// Quorum of signatures over a batch, broadcast by the batch author.
+    ProofOfStoreMsg(Box<ProofOfStore>),
+    /// RPC to fetch a batch referenced by a proposal that is not available locally.
+    BatchRequestMsg(Box<BatchRequest>),
+    /// Carries the requested batch if it is stored by the responder.
+    BatchResponseMsg(Box<BatchResponse>),
 }
 
 /// The interface from Network to Consensus layer.
@@ -9,7 +9,7 @@ use crate::{
 use channel::{self, diem_channel, message_queues::QueueStyle};
 use consensus_types::{
     block::{block_test_utils::certificate_for_genesis, Block},
-    common::Author,
+    common::{Author, Payload},
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     vote::Vote,
@@ -596,7 +596,7 @@ mod tests {
         );
         let previous_qc = certificate_for_genesis();
         let proposal = ProposalMsg::new(
-            Block::new_proposal(vec![], 1, 1, previous_qc.clone(), &signers[0]),
+            Block::new_proposal(Payload::empty(), 1, 1, previous_qc.clone(), &signers[0]),
             SyncInfo::new(previous_qc.clone(), previous_qc, None, None),
         );
         timed_block_on(&mut runtime, async {
@@ -11,10 +11,10 @@ use consensus_types::{
     common::Author, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate,
     timeout_certificate::TimeoutCertificate, vote::Vote,
 };
-use mango_crypto::{hash::CryptoHash, HashValue};
+use mango_crypto::{bls12381::Bls12381Signature, hash::CryptoHash, HashValue};
 use mango_logger::prelude::*;
 use mango_types::{
-    ledger_info::LedgerInfoWithSignatures,
+    ledger_info::{LedgerInfoWithSignatures, LedgerInfoWithV0},
     validator_verifier::{ValidatorVerifier, VerifyError},
 };
 use std::{
@@ -51,7 +51,11 @@ pub struct PendingVotes {
     /// Maps LedgerInfo digest to associated signatures (contained in a partial LedgerInfoWithSignatures).
     /// This might keep multiple LedgerInfos for the current round: either due to different proposals (byzantine behavior)
     /// or due to different NIL proposals (clients can have a different view of what block to extend).
-    li_digest_to_votes: HashMap<HashValue /* LedgerInfo digest */, LedgerInfoWithSignatures>,
+    li_digest_to_votes: HashMap<HashValue /* LedgerInfo digest */, LedgerInfoWithV0>,
+    /// Maps LedgerInfo digest to the BLS12-381 shares of the votes. Once a quorum of shares is
+    /// collected they are aggregated into the QC instead of the individual Ed25519 signatures.
+    li_digest_to_bls_signatures:
+        HashMap<HashValue /* LedgerInfo digest */, BTreeMap<Author, Bls12381Signature>>,
     /// Tracks all the signatures of the votes for the given round. In case we succeed to
     /// aggregate 2f+1 signatures a TimeoutCertificate is formed.
     maybe_partial_tc: Option<TimeoutCertificate>,
@@ -66,6 +70,7 @@ impl PendingVotes {
     pub fn new() -> Self {
         PendingVotes {
             li_digest_to_votes: HashMap::new(),
+            li_digest_to_bls_signatures: HashMap::new(),
             maybe_partial_tc: None,
             maybe_partial_2chain_tc: None,
             author_to_vote: HashMap::new(),
@@ -78,6 +83,17 @@ impl PendingVotes {
         &mut self,
         vote: &Vote,
         validator_verifier: &ValidatorVerifier,
+    ) -> VoteReceptionResult {
+        self.insert_vote_with_bls_signature(vote, None, validator_verifier)
+    }
+
+    /// Same as `insert_vote`, additionally collecting the (verified) BLS12-381 share of the vote.
+    /// The QC carries an aggregate signature if a quorum of shares is available when it's formed.
+    pub fn insert_vote_with_bls_signature(
+        &mut self,
+        vote: &Vote,
+        bls_signature: Option<&Bls12381Signature>,
+        validator_verifier: &ValidatorVerifier,
     ) -> VoteReceptionResult {
         // derive data from vote
         let li_digest = vote.ledger_info().hash();
@@ -121,20 +137,41 @@ impl PendingVotes {
         // obtain the ledger info with signatures associated to the vote's ledger info
         let li_with_sig = self.li_digest_to_votes.entry(li_digest).or_insert_with(|| {
             // if the ledger info with signatures doesn't exist yet, create it
-            LedgerInfoWithSignatures::new(vote.ledger_info().clone(), BTreeMap::new())
+            LedgerInfoWithV0::new(vote.ledger_info().clone(), BTreeMap::new())
         });
 
         // add this vote to the ledger info with signatures
         li_with_sig.add_signature(vote.author(), vote.signature().clone());
 
+        // add the BLS share of this vote, if any
+        let bls_signatures = self
+            .li_digest_to_bls_signatures
+            .entry(li_digest)
+            .or_insert_with(BTreeMap::new);
+        if let Some(bls_signature) = bls_signature {
+            bls_signatures.insert(vote.author(), bls_signature.clone());
+        }
+
         // check if we have enough signatures to create a QC
         let voting_power =
             match validator_verifier.check_voting_power(li_with_sig.signatures().keys()) {
                 // a quorum of signature was reached, a new QC is formed
                 Ok(_) => {
+                    let li_with_sig = match validator_verifier
+                        .check_voting_power(bls_signatures.keys())
+                        .and_then(|_| validator_verifier.aggregate_signatures(bls_signatures))
+                    {
+                        Ok(aggregate_signature) => {
+                            LedgerInfoWithSignatures::new_with_aggregate_signature(
+                                li_with_sig.ledger_info().clone(),
+                                aggregate_signature,
+                            )
+                        }
+                        Err(_) => LedgerInfoWithSignatures::V0(li_with_sig.clone()),
+                    };
                     return VoteReceptionResult::NewQuorumCertificate(Arc::new(QuorumCert::new(
                         vote.vote_data().clone(),
-                        li_with_sig.clone(),
+                        li_with_sig,
                     )));
                 }
 
@@ -267,7 +304,8 @@ mod tests {
     };
     use mango_crypto::HashValue;
     use mango_types::{
-        block_info::BlockInfo, ledger_info::LedgerInfo,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
         validator_verifier::random_validator_verifier,
     };
 
@@ -341,9 +379,7 @@ mod tests {
         let vote_data_2_author_2 = Vote::new(vote_data_2, signers[2].author(), li2, &signers[2]);
         match pending_votes.insert_vote(&vote_data_2_author_2, &validator) {
             VoteReceptionResult::NewQuorumCertificate(qc) => {
-                assert!(validator
-                    .check_voting_power(qc.ledger_info().signatures().keys())
-                    .is_ok());
+                assert!(qc.ledger_info().check_voting_power(&validator).is_ok());
             }
             _ => {
                 panic!("No QC formed.");
@@ -453,4 +489,70 @@ mod tests {
             }
         };
     }
+
+    #[test]
+    /// Verify that a QC carries an aggregate signature once a quorum of BLS shares is collected
+    fn test_qc_aggregation_with_bls_signatures() {
+        ::mango_logger::Logger::init_for_testing();
+
+        // set up 4 validators
+        let (signers, validator) = random_validator_verifier(4, Some(2), false);
+        let mut pending_votes = PendingVotes::new();
+
+        let li = random_ledger_info();
+        let vote_data = random_vote_data();
+        let votes: Vec<_> = signers
+            .iter()
+            .map(|signer| Vote::new(vote_data.clone(), signer.author(), li.clone(), signer))
+            .collect();
+
+        // a vote without a BLS share -> VoteAdded
+        assert_eq!(
+            pending_votes.insert_vote(&votes[0], &validator),
+            VoteReceptionResult::VoteAdded(1)
+        );
+
+        // the Ed25519 quorum is reached before a quorum of BLS shares -> Ed25519 QC
+        let bls_signature = signers[1].sign_bls(&li).unwrap();
+        match pending_votes.insert_vote_with_bls_signature(
+            &votes[1],
+            Some(&bls_signature),
+            &validator,
+        ) {
+            VoteReceptionResult::NewQuorumCertificate(qc) => {
+                assert!(matches!(qc.ledger_info(), LedgerInfoWithSignatures::V0(_)));
+                assert!(qc.ledger_info().verify_signatures(&validator).is_ok());
+            }
+            _ => {
+                panic!("No QC formed.");
+            }
+        };
+
+        // with a share in every vote the QC carries an aggregate signature
+        let mut pending_votes = PendingVotes::new();
+        let bls_signature = signers[0].sign_bls(&li).unwrap();
+        assert_eq!(
+            pending_votes.insert_vote_with_bls_signature(
+                &votes[0],
+                Some(&bls_signature),
+                &validator
+            ),
+            VoteReceptionResult::VoteAdded(1)
+        );
+        let bls_signature = signers[1].sign_bls(&li).unwrap();
+        match pending_votes.insert_vote_with_bls_signature(
+            &votes[1],
+            Some(&bls_signature),
+            &validator,
+        ) {
+            VoteReceptionResult::NewQuorumCertificate(qc) => {
+                assert!(matches!(qc.ledger_info(), LedgerInfoWithSignatures::V1(_)));
+                assert_eq!(qc.ledger_info().num_signers(), 2);
+                assert!(qc.ledger_info().verify_signatures(&validator).is_ok());
+            }
+            _ => {
+                panic!("No QC formed.");
+            }
+        };
+    }
 }
@@ -4,7 +4,7 @@
 use crate::{consensusdb::ConsensusDB, epoch_manager::LivenessStorageData, error::DbError};
 use anyhow::{format_err, Context, Result};
 use consensus_types::{
-    block::Block, common::Author, quorum_cert::QuorumCert,
+    block::Block, common::Author, proof_of_store::Batch, quorum_cert::QuorumCert,
     timeout_2chain::TwoChainTimeoutCertificate, timeout_certificate::TimeoutCertificate,
     vote::Vote, vote_data::VoteData,
 };
@@ -54,6 +54,15 @@ pub trait PersistentLivenessStorage: Send + Sync {
         highest_timeout_cert: &TwoChainTimeoutCertificate,
     ) -> Result<()>;
 
+    /// Persist quorum store batches, they're kept until the blocks referencing them are committed.
+    fn save_batches(&self, batches: Vec<Batch>) -> Result<()>;
+
+    /// Delete the corresponding quorum store batches.
+    fn prune_batches(&self, digests: Vec<HashValue>) -> Result<()>;
+
+    /// Retrieve the persisted quorum store batches, used to warm up the batch store on restart.
+    fn get_batches(&self) -> Result<Vec<Batch>>;
+
     /// Retrieve a epoch change proof for SafetyRules so it can instantiate its
     /// ValidatorVerifier.
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof>;
@@ -309,6 +318,36 @@ impl RecoveryData {
     }
 }
 
+/// Deserializes the last vote persisted in ConsensusDB.
+pub(crate) fn deserialize_last_vote(bytes: &[u8]) -> Result<Vote> {
+    // backward compatible for the 2-chain struct change
+    #[derive(Deserialize)]
+    struct OldVote {
+        pub vote_data: VoteData,
+        pub author: Author,
+        pub ledger_info: LedgerInfo,
+        pub signature: Ed25519Signature,
+        pub timeout_signature: Option<Ed25519Signature>,
+    }
+    match bcs::from_bytes(bytes) {
+        Ok(v) => Ok(v),
+        Err(_) => {
+            let OldVote {
+                vote_data,
+                author,
+                ledger_info,
+                signature,
+                timeout_signature,
+            } = bcs::from_bytes(bytes)?;
+            let mut vote = Vote::new_with_signature(vote_data, author, ledger_info, signature);
+            if let Some(sig) = timeout_signature {
+                vote.add_timeout_signature(sig);
+            }
+            Ok(vote)
+        }
+    }
+}
+
 /// The proxy we use to persist data in mango db storage service via grpc.
 pub struct StorageWriteProxy {
     db: Arc<ConsensusDB>,
@@ -358,35 +397,9 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             .get_data()
             .expect("unable to recover consensus data");
 
-        let last_vote = raw_data.0.map(|bytes| {
-            // backward compatible for the 2-chain struct change
-            #[derive(Deserialize)]
-            struct OldVote {
-                pub vote_data: VoteData,
-                pub author: Author,
-                pub ledger_info: LedgerInfo,
-                pub signature: Ed25519Signature,
-                pub timeout_signature: Option<Ed25519Signature>,
-            }
-            match bcs::from_bytes(&bytes[..]) {
-                Ok(v) => v,
-                Err(_) => {
-                    let OldVote {
-                        vote_data,
-                        author,
-                        ledger_info,
-                        signature,
-                        timeout_signature,
-                    } = bcs::from_bytes(&bytes).expect("unable to deserialize last vote");
-                    let mut vote =
-                        Vote::new_with_signature(vote_data, author, ledger_info, signature);
-                    if let Some(sig) = timeout_signature {
-                        vote.add_timeout_signature(sig);
-                    }
-                    vote
-                }
-            }
-        });
+        let last_vote = raw_data
+            .0
+            .map(|bytes| deserialize_last_vote(&bytes).expect("unable to deserialize last vote"));
 
         let highest_timeout_certificate = raw_data.1.map(|ts| {
             bcs::from_bytes(&ts[..]).expect("unable to deserialize highest timeout certificate")
@@ -484,6 +497,26 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             .save_highest_2chain_timeout_certificate(bcs::to_bytes(highest_timeout_cert)?)?)
     }
 
+    fn save_batches(&self, batches: Vec<Batch>) -> Result<()> {
+        Ok(self.db.save_batches(batches)?)
+    }
+
+    fn prune_batches(&self, digests: Vec<HashValue>) -> Result<()> {
+        if !digests.is_empty() {
+            self.db.delete_batches(digests)?;
+        }
+        Ok(())
+    }
+
+    fn get_batches(&self) -> Result<Vec<Batch>> {
+        Ok(self
+            .db
+            .get_batches()?
+            .into_iter()
+            .map(|(_digest, batch)| batch)
+            .collect())
+    }
+
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
         let (_, proofs, _) = self
             .diem_db
@@ -0,0 +1,259 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics_safety_rules::MetricsSafetyRules,
+    network::NetworkSender,
+    network_interface::ConsensusMsg,
+    quorum_store::{batch_store::BatchStore, proof_queue::ProofQueue},
+    round_manager::VerifiedEvent,
+    state_replication::TxnManager,
+};
+use anyhow::{bail, ensure};
+use channel::Receiver;
+use consensus_types::{
+    common::{Author, Payload},
+    proof_of_store::{Batch, BatchInfo, ProofOfStore, SignedBatchInfo},
+};
+use futures::StreamExt;
+use mango_config::config::QuorumStoreConfig;
+use mango_crypto::{ed25519::Ed25519Signature, HashValue};
+use mango_infallible::Mutex;
+use mango_logger::prelude::*;
+use safety_rules::TSafetyRules;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
+/// A batch created by this validator that is not committed yet.
+struct OwnBatch {
+    info: BatchInfo,
+    // The transactions of the batch, excluded from the next pulls from mempool.
+    payload: Payload,
+    signatures: BTreeMap<Author, Ed25519Signature>,
+    certified: bool,
+}
+
+/// BatchCoordinator runs the quorum store of an epoch:
+/// * it periodically pulls transactions from mempool into a batch broadcast to all validators,
+/// * it persists and signs the batches of the other validators,
+/// * it aggregates the signatures of its own batches into proofs of store broadcast to all
+/// validators, which feed the proof queue the proposals are pulled from.
+pub struct BatchCoordinator {
+    author: Author,
+    epoch: u64,
+    config: QuorumStoreConfig,
+    network: NetworkSender,
+    txn_manager: Arc<dyn TxnManager>,
+    batch_store: Arc<BatchStore>,
+    proof_queue: Arc<ProofQueue>,
+    safety_rules: Arc<Mutex<MetricsSafetyRules>>,
+    next_batch_id: u64,
+    own_batches: HashMap<HashValue, OwnBatch>,
+}
+
+impl BatchCoordinator {
+    pub fn new(
+        author: Author,
+        epoch: u64,
+        config: QuorumStoreConfig,
+        network: NetworkSender,
+        txn_manager: Arc<dyn TxnManager>,
+        batch_store: Arc<BatchStore>,
+        proof_queue: Arc<ProofQueue>,
+        safety_rules: Arc<Mutex<MetricsSafetyRules>>,
+    ) -> Self {
+        Self {
+            author,
+            epoch,
+            config,
+            network,
+            txn_manager,
+            batch_store,
+            proof_queue,
+            safety_rules,
+            next_batch_id: 0,
+            own_batches: HashMap::new(),
+        }
+    }
+
+    async fn create_batch(&mut self) -> anyhow::Result<()> {
+        let batch_store = &self.batch_store;
+        self.own_batches
+            .retain(|digest, _| !batch_store.is_committed(*digest));
+
+        let exclude = self.own_batches.values().map(|b| &b.payload).collect();
+        let mut txns = match self
+            .txn_manager
+            .pull_txns(self.config.max_batch_size, exclude)
+            .await?
+        {
+            Payload::DirectMempool(txns) => txns,
+            Payload::InQuorumStore(_) => bail!("Batches are pulled from mempool"),
+        };
+        // The transactions left out stay in mempool for the next batches.
+        let mut batch_bytes: u64 = 0;
+        let fitting = txns
+            .iter()
+            .take_while(|txn| {
+                let txn_bytes = bcs::serialized_size(txn).map_or(u64::MAX, |size| size as u64);
+                batch_bytes = batch_bytes.saturating_add(txn_bytes);
+                batch_bytes <= self.config.max_batch_bytes
+            })
+            .count();
+        txns.truncate(fitting);
+        if txns.is_empty() {
+            return Ok(());
+        }
+
+        let batch = Batch::new(self.author, self.epoch, self.next_batch_id, txns);
+        self.next_batch_id += 1;
+        self.batch_store.persist(batch.clone())?;
+        self.own_batches.insert(
+            batch.digest(),
+            OwnBatch {
+                info: batch.info().clone(),
+                payload: Payload::DirectMempool(batch.payload().to_vec()),
+                signatures: BTreeMap::new(),
+                certified: false,
+            },
+        );
+        debug!("Broadcast {}", batch.info());
+        self.network
+            .broadcast(ConsensusMsg::BatchMsg(Box::new(batch)))
+            .await;
+        Ok(())
+    }
+
+    /// Peers can send any batch, so the limits are enforced before it's persisted: the ones of
+    /// honest validators never exceed them.
+    async fn process_batch(&mut self, peer_id: Author, batch: Batch) -> anyhow::Result<()> {
+        let info = batch.info().clone();
+        ensure!(
+            info.author() == peer_id,
+            "Batch {} received from {}",
+            info,
+            peer_id
+        );
+        ensure!(
+            info.epoch() == self.epoch,
+            "Batch {} is not from epoch {}",
+            info,
+            self.epoch
+        );
+        ensure!(
+            info.num_txns() <= self.config.max_batch_size,
+            "Batch {} exceeds {} transactions",
+            info,
+            self.config.max_batch_size
+        );
+        let batch_bytes = batch
+            .payload()
+            .iter()
+            .map(|txn| bcs::serialized_size(txn).map(|size| size as u64))
+            .sum::<Result<u64, _>>()?;
+        ensure!(
+            batch_bytes <= self.config.max_batch_bytes,
+            "Batch {} of {} bytes exceeds {} bytes",
+            info,
+            batch_bytes,
+            self.config.max_batch_bytes
+        );
+        ensure!(
+            self.batch_store.contains(info.digest())
+                || self.batch_store.num_batches(peer_id) < self.config.max_batches_per_author,
+            "{} already has {} uncommitted batches",
+            peer_id,
+            self.config.max_batches_per_author
+        );
+        self.batch_store.persist(batch)?;
+        let signature = self.safety_rules.lock().sign_batch_info(&info)?;
+        let signed = SignedBatchInfo::new(info, self.author, signature);
+        self.network
+            .send(
+                ConsensusMsg::SignedBatchInfoMsg(Box::new(signed)),
+                vec![peer_id],
+            )
+            .await;
+        Ok(())
+    }
+
+    async fn process_signed_batch_info(
+        &mut self,
+        peer_id: Author,
+        signed: SignedBatchInfo,
+    ) -> anyhow::Result<()> {
+        ensure!(
+            signed.signer() == peer_id,
+            "Signature of {} received from {}",
+            signed.info(),
+            peer_id
+        );
+        let own_batch = match self.own_batches.get_mut(&signed.info().digest()) {
+            Some(own_batch) if !own_batch.certified && &own_batch.info == signed.info() => {
+                own_batch
+            }
+            _ => return Ok(()),
+        };
+        own_batch
+            .signatures
+            .insert(signed.signer(), signed.signature().clone());
+        if self
+            .network
+            .validators()
+            .check_voting_power(own_batch.signatures.keys())
+            .is_err()
+        {
+            return Ok(());
+        }
+        own_batch.certified = true;
+        let proof = ProofOfStore::new(own_batch.info.clone(), own_batch.signatures.clone());
+        debug!("Broadcast {}", proof);
+        self.network
+            .broadcast(ConsensusMsg::ProofOfStoreMsg(Box::new(proof)))
+            .await;
+        Ok(())
+    }
+
+    async fn process_event(&mut self, peer_id: Author, event: VerifiedEvent) -> anyhow::Result<()> {
+        match event {
+            VerifiedEvent::Batch(batch) => self.process_batch(peer_id, *batch).await,
+            VerifiedEvent::SignedBatchInfo(signed) => {
+                self.process_signed_batch_info(peer_id, *signed).await
+            }
+            VerifiedEvent::ProofOfStore(proof) => {
+                if !self.batch_store.is_committed(proof.digest()) {
+                    self.proof_queue.insert(*proof);
+                }
+                Ok(())
+            }
+            _ => bail!("Unexpected quorum store event: {:?}", event),
+        }
+    }
+
+    /// The coordinator stops when the epoch manager drops the sender of the epoch.
+    pub async fn start(mut self, mut event_rx: Receiver<(Author, VerifiedEvent)>) {
+        let mut interval =
+            tokio::time::interval(Duration::from_millis(self.config.batch_interval_ms));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = self.create_batch().await {
+                        error!(error = ?e, "[QuorumStore] Failed to create batch");
+                    }
+                }
+                event = event_rx.next() => match event {
+                    Some((peer_id, event)) => {
+                        if let Err(e) = self.process_event(peer_id, event).await {
+                            error!(remote_peer = peer_id, error = ?e, "[QuorumStore] Failed to process event");
+                        }
+                    }
+                    None => break,
+                },
+            }
+        }
+        info!(epoch = self.epoch, "[QuorumStore] stopped");
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    network::{IncomingBatchRequest, NetworkSender},
+    network_interface::ConsensusMsg,
+    persistent_liveness_storage::PersistentLivenessStorage,
+};
+use anyhow::Context;
+use consensus_types::{
+    common::{Author, Payload},
+    proof_of_store::{Batch, BatchRequest, BatchResponse, ProofOfStore},
+};
+use executor_types::Error as ExecutionError;
+use mango_crypto::HashValue;
+use mango_infallible::Mutex;
+use mango_logger::prelude::*;
+use mango_types::transaction::SignedTransaction;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+/// BatchStore keeps the quorum store batches this validator certified or fetched, backed by
+/// consensusdb so that the blocks referencing them can be executed after a restart.
+/// Batches are pruned as soon as the blocks referencing them are committed.
+pub struct BatchStore {
+    author: Author,
+    storage: Arc<dyn PersistentLivenessStorage>,
+    batches: Mutex<HashMap<HashValue, Batch>>,
+    // Digests of the batches committed in the current epoch, their proofs must not be proposed.
+    committed: Mutex<HashSet<HashValue>>,
+    network: Mutex<Option<NetworkSender>>,
+    request_timeout: Duration,
+}
+
+impl BatchStore {
+    pub fn new(
+        author: Author,
+        storage: Arc<dyn PersistentLivenessStorage>,
+        request_timeout_ms: u64,
+    ) -> Self {
+        let batches = match storage.get_batches() {
+            Ok(batches) => batches
+                .into_iter()
+                .map(|batch| (batch.digest(), batch))
+                .collect(),
+            Err(e) => {
+                error!(error = ?e, "Failed to load the quorum store batches");
+                HashMap::new()
+            }
+        };
+        Self {
+            author,
+            storage,
+            batches: Mutex::new(batches),
+            committed: Mutex::new(HashSet::new()),
+            network: Mutex::new(None),
+            request_timeout: Duration::from_millis(request_timeout_ms),
+        }
+    }
+
+    /// Batches are certified by the validators of an epoch: the ones of previous epochs can't be
+    /// referenced anymore.
+    pub fn new_epoch(&self, epoch: u64, network: NetworkSender) {
+        *self.network.lock() = Some(network);
+        self.committed.lock().clear();
+        let stale: Vec<_> = {
+            let mut batches = self.batches.lock();
+            let stale = batches
+                .values()
+                .filter(|batch| batch.info().epoch() < epoch)
+                .map(Batch::digest)
+                .collect();
+            batches.retain(|_, batch| batch.info().epoch() >= epoch);
+            stale
+        };
+        if let Err(e) = self.storage.prune_batches(stale) {
+            error!(error = ?e, "Failed to prune the quorum store batches of previous epochs");
+        }
+    }
+
+    /// Persists the batch before it's signed or used to execute a block.
+    pub fn persist(&self, batch: Batch) -> anyhow::Result<()> {
+        if self.contains(batch.digest()) {
+            return Ok(());
+        }
+        self.storage
+            .save_batches(vec![batch.clone()])
+            .context("Failed to persist quorum store batch")?;
+        self.batches.lock().insert(batch.digest(), batch);
+        Ok(())
+    }
+
+    pub fn get(&self, digest: HashValue) -> Option<Batch> {
+        self.batches.lock().get(&digest).cloned()
+    }
+
+    pub fn contains(&self, digest: HashValue) -> bool {
+        self.batches.lock().contains_key(&digest)
+    }
+
+    /// Number of uncommitted batches stored for the author.
+    pub fn num_batches(&self, author: Author) -> usize {
+        self.batches
+            .lock()
+            .values()
+            .filter(|batch| batch.info().author() == author)
+            .count()
+    }
+
+    pub fn is_committed(&self, digest: HashValue) -> bool {
+        self.committed.lock().contains(&digest)
+    }
+
+    /// Fetches the batches referenced by the proofs that are not available locally from their
+    /// signers: at least one honest signer stores each of them.
+    pub async fn fetch_missing(&self, proofs: &[ProofOfStore]) -> Result<(), ExecutionError> {
+        for proof in proofs {
+            if self.contains(proof.digest()) {
+                continue;
+            }
+            let network =
+                self.network
+                    .lock()
+                    .clone()
+                    .ok_or_else(|| ExecutionError::InternalError {
+                        error: "BatchStore not started yet".into(),
+                    })?;
+            let mut fetched = false;
+            for signer in proof.signatures().keys().filter(|s| **s != self.author) {
+                match network
+                    .request_batch(
+                        BatchRequest::new(proof.digest()),
+                        *signer,
+                        self.request_timeout,
+                    )
+                    .await
+                {
+                    Ok(batch) => {
+                        self.persist(batch)?;
+                        fetched = true;
+                        break;
+                    }
+                    Err(e) => {
+                        warn!(remote_peer = *signer, error = ?e, "Failed to fetch batch");
+                    }
+                }
+            }
+            if !fetched {
+                return Err(ExecutionError::InternalError {
+                    error: format!("Failed to fetch {}", proof),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves the transactions of the payload, the batches it references must be available.
+    pub fn get_transactions(
+        &self,
+        payload: &Payload,
+    ) -> Result<Vec<SignedTransaction>, ExecutionError> {
+        match payload {
+            Payload::DirectMempool(txns) => Ok(txns.clone()),
+            Payload::InQuorumStore(proofs) => {
+                let batches = self.batches.lock();
+                let mut txns = vec![];
+                for proof in proofs {
+                    let batch = batches.get(&proof.digest()).ok_or_else(|| {
+                        ExecutionError::InternalError {
+                            error: format!("Missing batch of {}", proof),
+                        }
+                    })?;
+                    txns.extend(batch.payload().iter().cloned());
+                }
+                Ok(txns)
+            }
+        }
+    }
+
+    /// Prunes the batches of the committed blocks.
+    pub fn commit(&self, digests: Vec<HashValue>) {
+        if digests.is_empty() {
+            return;
+        }
+        {
+            let mut batches = self.batches.lock();
+            for digest in &digests {
+                batches.remove(digest);
+            }
+        }
+        self.committed.lock().extend(digests.iter().cloned());
+        if let Err(e) = self.storage.prune_batches(digests) {
+            error!(error = ?e, "Failed to prune the committed quorum store batches");
+        }
+    }
+
+    /// Serves a batch to a peer that is missing it.
+    pub fn process_batch_request(&self, request: IncomingBatchRequest) -> anyhow::Result<()> {
+        let digest = request.req.digest();
+        let response = match self.get(digest) {
+            Some(batch) => BatchResponse::Batch(batch),
+            None => BatchResponse::NotFound(digest),
+        };
+        bcs::to_bytes(&ConsensusMsg::BatchResponseMsg(Box::new(response)))
+            .and_then(|bytes| {
+                request
+                    .response_sender
+                    .send(Ok(bytes.into()))
+                    .map_err(|e| bcs::Error::Custom(format!("{:?}", e)))
+            })
+            .context("[BatchStore] Failed to process batch request")
+    }
+}
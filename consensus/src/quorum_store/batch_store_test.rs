@@ -0,0 +1,89 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    persistent_liveness_storage::PersistentLivenessStorage,
+    quorum_store::{batch_store::BatchStore, proof_queue::ProofQueue},
+    test_utils::MockStorage,
+};
+use consensus_types::{
+    block::block_test_utils::random_payload,
+    common::{Author, Payload},
+    proof_of_store::{Batch, ProofOfStore},
+};
+use mango_types::validator_verifier::random_validator_verifier;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
+
+fn make_batch(batch_id: u64, num_txns: usize) -> Batch {
+    let txns = random_payload(num_txns).inline_transactions().to_vec();
+    Batch::new(Author::random(), 1, batch_id, txns)
+}
+
+fn make_proof(batch: &Batch) -> ProofOfStore {
+    ProofOfStore::new(batch.info().clone(), BTreeMap::new())
+}
+
+#[test]
+fn test_batch_store_persist_and_commit() {
+    let (signers, validators) = random_validator_verifier(1, None, false);
+    let (_, storage) = MockStorage::start_for_testing((&validators).into());
+    let batch_store = BatchStore::new(signers[0].author(), storage.clone(), 1000);
+
+    let batch = make_batch(0, 3);
+    let payload = Payload::InQuorumStore(vec![make_proof(&batch)]);
+    assert!(batch_store.get_transactions(&payload).is_err());
+
+    batch_store.persist(batch.clone()).unwrap();
+    assert_eq!(
+        batch_store.get_transactions(&payload).unwrap(),
+        batch.payload().to_vec()
+    );
+    assert_eq!(storage.get_batches().unwrap(), vec![batch.clone()]);
+    assert_eq!(batch_store.num_batches(batch.info().author()), 1);
+    assert_eq!(batch_store.num_batches(Author::random()), 0);
+    // the batches are reloaded from storage
+    let reloaded = BatchStore::new(signers[0].author(), storage.clone(), 1000);
+    assert!(reloaded.contains(batch.digest()));
+
+    batch_store.commit(payload.batch_digests());
+    assert!(!batch_store.contains(batch.digest()));
+    assert!(batch_store.is_committed(batch.digest()));
+    assert_eq!(batch_store.num_batches(batch.info().author()), 0);
+    assert!(storage.get_batches().unwrap().is_empty());
+}
+
+#[test]
+fn test_proof_queue_pull() {
+    let (signers, validators) = random_validator_verifier(1, None, false);
+    let (_, storage) = MockStorage::start_for_testing((&validators).into());
+    let batch_store = Arc::new(BatchStore::new(signers[0].author(), storage, 1000));
+    let proof_queue = ProofQueue::new(batch_store.clone());
+
+    let batches: Vec<_> = (0..3).map(|i| make_batch(i, 2)).collect();
+    for batch in &batches {
+        proof_queue.insert(make_proof(batch));
+    }
+    // duplicated proofs are ignored
+    proof_queue.insert(make_proof(&batches[0]));
+
+    let digests =
+        |proofs: Vec<ProofOfStore>| -> Vec<_> { proofs.iter().map(ProofOfStore::digest).collect() };
+    assert_eq!(
+        digests(proof_queue.pull(4, &HashSet::new())),
+        vec![batches[0].digest(), batches[1].digest()]
+    );
+    let exclude = vec![batches[0].digest()].into_iter().collect();
+    assert_eq!(
+        digests(proof_queue.pull(10, &exclude)),
+        vec![batches[1].digest(), batches[2].digest()]
+    );
+
+    batch_store.commit(vec![batches[1].digest()]);
+    assert_eq!(
+        digests(proof_queue.pull(10, &HashSet::new())),
+        vec![batches[0].digest(), batches[2].digest()]
+    );
+}
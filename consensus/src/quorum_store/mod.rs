@@ -0,0 +1,12 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Quorum store disseminates the transaction batches ahead of the proposals: validators certify
+//! they store a batch and the proposals only reference the certified batches by digest.
+
+pub mod batch_coordinator;
+pub mod batch_store;
+pub mod proof_queue;
+
+#[cfg(test)]
+mod batch_store_test;
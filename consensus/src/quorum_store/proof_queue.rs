@@ -0,0 +1,101 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::MempoolError, quorum_store::batch_store::BatchStore, state_replication::TxnManager,
+};
+use anyhow::Result;
+use consensus_types::{block::Block, common::Payload, proof_of_store::ProofOfStore};
+use executor_types::StateComputeResult;
+use mango_crypto::HashValue;
+use mango_infallible::Mutex;
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
+
+/// ProofQueue keeps the proofs of store received in the current epoch, in arrival order, until
+/// the batches they certify are committed.
+pub struct ProofQueue {
+    proofs: Mutex<VecDeque<ProofOfStore>>,
+    batch_store: Arc<BatchStore>,
+}
+
+impl ProofQueue {
+    pub fn new(batch_store: Arc<BatchStore>) -> Self {
+        Self {
+            proofs: Mutex::new(VecDeque::new()),
+            batch_store,
+        }
+    }
+
+    pub fn insert(&self, proof: ProofOfStore) {
+        let mut proofs = self.proofs.lock();
+        if proofs.iter().all(|p| p.digest() != proof.digest()) {
+            proofs.push_back(proof);
+        }
+    }
+
+    /// Pulls the oldest proofs carrying up to `max_txns` transactions, skipping the excluded
+    /// digests that are pending in the branch being extended.
+    pub fn pull(&self, max_txns: u64, exclude: &HashSet<HashValue>) -> Vec<ProofOfStore> {
+        let mut proofs = self.proofs.lock();
+        proofs.retain(|proof| !self.batch_store.is_committed(proof.digest()));
+
+        let mut num_txns = 0;
+        let mut result = vec![];
+        for proof in proofs.iter() {
+            if exclude.contains(&proof.digest()) {
+                continue;
+            }
+            if num_txns + proof.info().num_txns() > max_txns {
+                break;
+            }
+            num_txns += proof.info().num_txns();
+            result.push(proof.clone());
+        }
+        result
+    }
+}
+
+/// TxnManager used when the quorum store is enabled: proposals carry the proofs of store of the
+/// batches instead of the transactions pulled from mempool.
+pub struct QuorumStoreTxnManager {
+    proof_queue: Arc<ProofQueue>,
+    inner: Arc<dyn TxnManager>,
+}
+
+impl QuorumStoreTxnManager {
+    pub fn new(proof_queue: Arc<ProofQueue>, inner: Arc<dyn TxnManager>) -> Self {
+        Self { proof_queue, inner }
+    }
+}
+
+#[async_trait::async_trait]
+impl TxnManager for QuorumStoreTxnManager {
+    async fn pull_txns(
+        &self,
+        max_size: u64,
+        exclude: Vec<&Payload>,
+    ) -> Result<Payload, MempoolError> {
+        let exclude_digests = exclude
+            .into_iter()
+            .flat_map(Payload::batch_digests)
+            .collect();
+        Ok(Payload::InQuorumStore(
+            self.proof_queue.pull(max_size, &exclude_digests),
+        ))
+    }
+
+    async fn notify(
+        &self,
+        block: &Block,
+        compute_result: &StateComputeResult,
+    ) -> Result<(), MempoolError> {
+        self.inner.notify(block, compute_result).await
+    }
+
+    fn trace_transactions(&self, block: &Block) {
+        self.inner.trace_transactions(block)
+    }
+}
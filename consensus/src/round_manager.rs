@@ -27,6 +27,7 @@ use consensus_types::{
     block_retrieval::{BlockRetrievalResponse, BlockRetrievalStatus},
     common::{Author, Round},
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
+    proof_of_store::{Batch, ProofOfStore, SignedBatchInfo},
     proposal_msg::ProposalMsg,
     quorum_cert::QuorumCert,
     sync_info::SyncInfo,
@@ -37,6 +38,7 @@ use consensus_types::{
 };
 use core::sync::atomic::Ordering;
 use fail::fail_point;
+use mango_crypto::bls12381::Bls12381Signature;
 use mango_infallible::{checked, Mutex};
 use mango_logger::prelude::*;
 use mango_types::{
@@ -60,6 +62,9 @@ pub enum UnverifiedEvent {
     SyncInfo(Box<SyncInfo>),
     CommitVote(Box<CommitVote>),
     CommitDecision(Box<CommitDecision>),
+    Batch(Box<Batch>),
+    SignedBatchInfo(Box<SignedBatchInfo>),
+    ProofOfStore(Box<ProofOfStore>),
 }
 
 impl UnverifiedEvent {
@@ -85,6 +90,18 @@ impl UnverifiedEvent {
                 cd.verify(validator)?;
                 VerifiedEvent::CommitDecision(cd)
             }
+            UnverifiedEvent::Batch(b) => {
+                b.verify()?;
+                VerifiedEvent::Batch(b)
+            }
+            UnverifiedEvent::SignedBatchInfo(sbi) => {
+                sbi.verify(validator)?;
+                VerifiedEvent::SignedBatchInfo(sbi)
+            }
+            UnverifiedEvent::ProofOfStore(pos) => {
+                pos.verify(validator)?;
+                VerifiedEvent::ProofOfStore(pos)
+            }
         })
     }
 
@@ -95,6 +112,9 @@ impl UnverifiedEvent {
             UnverifiedEvent::SyncInfo(s) => s.epoch(),
             UnverifiedEvent::CommitVote(cv) => cv.epoch(),
             UnverifiedEvent::CommitDecision(cd) => cd.epoch(),
+            UnverifiedEvent::Batch(b) => b.info().epoch(),
+            UnverifiedEvent::SignedBatchInfo(sbi) => sbi.info().epoch(),
+            UnverifiedEvent::ProofOfStore(pos) => pos.info().epoch(),
         }
     }
 }
@@ -107,6 +127,9 @@ impl From<ConsensusMsg> for UnverifiedEvent {
             ConsensusMsg::SyncInfo(m) => UnverifiedEvent::SyncInfo(m),
             ConsensusMsg::CommitVoteMsg(m) => UnverifiedEvent::CommitVote(m),
             ConsensusMsg::CommitDecisionMsg(m) => UnverifiedEvent::CommitDecision(m),
+            ConsensusMsg::BatchMsg(m) => UnverifiedEvent::Batch(m),
+            ConsensusMsg::SignedBatchInfoMsg(m) => UnverifiedEvent::SignedBatchInfo(m),
+            ConsensusMsg::ProofOfStoreMsg(m) => UnverifiedEvent::ProofOfStore(m),
             _ => unreachable!("Unexpected conversion"),
         }
     }
@@ -119,6 +142,9 @@ pub enum VerifiedEvent {
     SyncInfo(Box<SyncInfo>),
     CommitVote(Box<CommitVote>),
     CommitDecision(Box<CommitDecision>),
+    Batch(Box<Batch>),
+    SignedBatchInfo(Box<SignedBatchInfo>),
+    ProofOfStore(Box<ProofOfStore>),
 }
 
 #[cfg(test)]
@@ -367,7 +393,7 @@ impl RoundManager {
         // Proposal generator will ensure that at most one proposal is generated per round
         let proposal = self
             .proposal_generator
-            .generate_proposal(new_round_event.round)
+            .generate_proposal(new_round_event.round, self.proposer_election.as_ref())
             .await?;
         let signature = self.safety_rules.lock().sign_proposal(&proposal)?;
         let signed_proposal =
@@ -596,10 +622,7 @@ impl RoundManager {
         }
 
         self.round_state.record_vote(timeout_vote.clone());
-        let timeout_vote_msg = ConsensusMsg::VoteMsg(Box::new(VoteMsg::new(
-            timeout_vote,
-            self.block_store.sync_info(),
-        )));
+        let timeout_vote_msg = ConsensusMsg::VoteMsg(Box::new(self.create_vote_msg(timeout_vote)));
         self.network.broadcast(timeout_vote_msg).await;
         error!(
             round = round,
@@ -643,6 +666,25 @@ impl RoundManager {
             proposal,
         );
 
+        proposal
+            .verify_payload_type(self.onchain_config.quorum_store())
+            .context("[RoundManager] Proposal payload doesn't match the on-chain config")?;
+
+        if let Some(failed_authors) = proposal.failed_authors() {
+            let expected_failed_authors = self.proposal_generator.failed_authors(
+                proposal.round(),
+                proposal.quorum_cert().certified_block().round(),
+                self.proposer_election.as_ref(),
+            );
+            ensure!(
+                failed_authors == &expected_failed_authors,
+                "[RoundManager] Proposal for block {} has invalid failed authors {:?}, expected {:?}",
+                proposal,
+                failed_authors,
+                expected_failed_authors,
+            );
+        }
+
         let block_time_since_epoch = Duration::from_micros(proposal.timestamp_usecs());
 
         ensure!(
@@ -667,17 +709,42 @@ impl RoundManager {
         debug!(self.new_log(LogEvent::Vote).remote_peer(author), "{}", vote);
 
         self.round_state.record_vote(vote.clone());
-        let vote_msg = VoteMsg::new(vote, self.block_store.sync_info());
+        let vote_msg = self.create_vote_msg(vote);
         self.network.send_vote(vote_msg, vec![recipients]).await;
         Ok(())
     }
 
+    /// Wraps the vote with the current sync info, attaching the BLS12-381 share of the vote when
+    /// aggregated signatures are enabled on-chain. Failing to sign the share is not fatal: the
+    /// Ed25519 vote still counts towards a QC.
+    fn create_vote_msg(&mut self, vote: Vote) -> VoteMsg {
+        let vote_msg = VoteMsg::new(vote, self.block_store.sync_info());
+        if !self.onchain_config.aggregate_signatures() {
+            return vote_msg;
+        }
+        let bls_signature = self.safety_rules.lock().sign_vote_bls(vote_msg.vote());
+        match bls_signature {
+            Ok(bls_signature) => vote_msg.with_bls_signature(bls_signature),
+            Err(error) => {
+                warn!(
+                    error = ?error,
+                    "[RoundManager] Failed to sign the BLS share of the vote"
+                );
+                vote_msg
+            }
+        }
+    }
+
     /// The function generates a VoteMsg for a given proposed_block:
     /// * first execute the block and add it to the block store
     /// * then verify the voting rules
     /// * save the updated state to consensus DB
     /// * return a VoteMsg with the LedgerInfo to be committed in case the vote gathers QC.
     async fn execute_and_vote(&mut self, proposed_block: Block) -> anyhow::Result<Vote> {
+        self.block_store
+            .fetch_payload(&proposed_block)
+            .await
+            .context("[RoundManager] Failed to fetch the payload of the block")?;
         let executed_block = self
             .block_store
             .execute_and_insert_block(proposed_block)
@@ -756,7 +823,7 @@ impl RoundManager {
             .await
             .context("[RoundManager] Stop processing vote")?
         {
-            self.process_vote(vote_msg.vote())
+            self.process_vote(vote_msg.vote(), vote_msg.bls_signature())
                 .await
                 .context("[RoundManager] Add a new vote")?;
         }
@@ -767,7 +834,11 @@ impl RoundManager {
     /// If a new QC / TC is formed then
     /// 1) fetch missing dependencies if required, and then
     /// 2) call process_certificates(), which will start a new round in return.
-    async fn process_vote(&mut self, vote: &Vote) -> anyhow::Result<()> {
+    async fn process_vote(
+        &mut self,
+        vote: &Vote,
+        bls_signature: Option<&Bls12381Signature>,
+    ) -> anyhow::Result<()> {
         let round = vote.vote_data().proposed().round();
 
         info!(
@@ -803,7 +874,7 @@ impl RoundManager {
         // Add the vote and check whether it completes a new QC or a TC
         match self
             .round_state
-            .insert_vote(vote, &self.epoch_state.verifier)
+            .insert_vote(vote, bls_signature, &self.epoch_state.verifier)
         {
             VoteReceptionResult::NewQuorumCertificate(qc) => {
                 self.new_qc_aggregated(qc, vote.author()).await
@@ -27,8 +27,10 @@ use consensus_types::{
         block_test_utils::{certificate_for_genesis, gen_test_certificate},
         Block,
     },
+    block_data::BlockData,
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalStatus},
     common::{Author, Payload},
+    proof_of_store::{BatchInfo, ProofOfStore},
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     timeout::Timeout,
@@ -58,7 +60,7 @@ use network::{
     ProtocolId,
 };
 use safety_rules::{PersistentSafetyStorage, SafetyRulesManager};
-use std::{sync::Arc, time::Duration};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 use tokio::runtime::Handle;
 
 /// Auxiliary struct that is setting up node environment for the test.
@@ -202,6 +204,7 @@ impl NodeSetup {
             Arc::new(MockTransactionManager::new(None)),
             time_service.clone(),
             1,
+            true,
         );
 
         let round_state = Self::create_round_state(time_service);
@@ -342,7 +345,8 @@ fn vote_on_successful_proposal() {
         // Start round 1 and clear the message queue
         node.next_proposal().await;
 
-        let proposal = Block::new_proposal(vec![], 1, 1, genesis_qc.clone(), &node.signer);
+        let proposal =
+            Block::new_proposal(Payload::empty(), 1, 1, genesis_qc.clone(), &node.signer);
         let proposal_id = proposal.id();
         node.round_manager.process_proposal(proposal).await.unwrap();
         let vote_msg = node.next_vote().await;
@@ -367,9 +371,9 @@ fn no_vote_on_old_proposal() {
     let mut nodes = NodeSetup::create_nodes(&mut playground, runtime.handle().clone(), 1);
     let node = &mut nodes[0];
     let genesis_qc = certificate_for_genesis();
-    let new_block = Block::new_proposal(vec![], 1, 1, genesis_qc.clone(), &node.signer);
+    let new_block = Block::new_proposal(Payload::empty(), 1, 1, genesis_qc.clone(), &node.signer);
     let new_block_id = new_block.id();
-    let old_block = Block::new_proposal(vec![], 1, 2, genesis_qc, &node.signer);
+    let old_block = Block::new_proposal(Payload::empty(), 1, 2, genesis_qc, &node.signer);
     let old_block_id = old_block.id();
     timed_block_on(&mut runtime, async {
         // clear the message queue
@@ -403,8 +407,10 @@ fn no_vote_on_mismatch_round() {
         .pop()
         .unwrap();
     let genesis_qc = certificate_for_genesis();
-    let correct_block = Block::new_proposal(vec![], 1, 1, genesis_qc.clone(), &node.signer);
-    let block_skip_round = Block::new_proposal(vec![], 2, 2, genesis_qc.clone(), &node.signer);
+    let correct_block =
+        Block::new_proposal(Payload::empty(), 1, 1, genesis_qc.clone(), &node.signer);
+    let block_skip_round =
+        Block::new_proposal(Payload::empty(), 2, 2, genesis_qc.clone(), &node.signer);
     timed_block_on(&mut runtime, async {
         let bad_proposal = ProposalMsg::new(
             block_skip_round,
@@ -484,9 +490,15 @@ fn no_vote_on_invalid_proposer() {
     let incorrect_proposer = nodes.pop().unwrap();
     let mut node = nodes.pop().unwrap();
     let genesis_qc = certificate_for_genesis();
-    let correct_block = Block::new_proposal(vec![], 1, 1, genesis_qc.clone(), &node.signer);
-    let block_incorrect_proposer =
-        Block::new_proposal(vec![], 1, 1, genesis_qc.clone(), &incorrect_proposer.signer);
+    let correct_block =
+        Block::new_proposal(Payload::empty(), 1, 1, genesis_qc.clone(), &node.signer);
+    let block_incorrect_proposer = Block::new_proposal(
+        Payload::empty(),
+        1,
+        1,
+        genesis_qc.clone(),
+        &incorrect_proposer.signer,
+    );
     timed_block_on(&mut runtime, async {
         let bad_proposal = ProposalMsg::new(
             block_incorrect_proposer,
@@ -520,8 +532,20 @@ fn new_round_on_timeout_certificate() {
         .pop()
         .unwrap();
     let genesis_qc = certificate_for_genesis();
-    let correct_block = Block::new_proposal(vec![], 1, 1, genesis_qc.clone(), &node.signer);
-    let block_skip_round = Block::new_proposal(vec![], 2, 2, genesis_qc.clone(), &node.signer);
+    let correct_block =
+        Block::new_proposal(Payload::empty(), 1, 1, genesis_qc.clone(), &node.signer);
+    // The proposal skipping round 1 records its proposer as failed
+    let block_skip_round = Block::new_proposal_from_block_data(
+        BlockData::new_proposal(
+            Payload::empty(),
+            node.signer.author(),
+            vec![(1, node.signer.author())],
+            2,
+            2,
+            genesis_qc.clone(),
+        ),
+        &node.signer,
+    );
     let timeout = Timeout::new(1, 1);
     let timeout_signature = timeout.sign(&node.signer);
 
@@ -549,6 +573,62 @@ fn new_round_on_timeout_certificate() {
     });
 }
 
+#[test]
+fn reject_proposal_with_invalid_failed_authors() {
+    let mut runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut node = NodeSetup::create_nodes(&mut playground, runtime.handle().clone(), 1)
+        .pop()
+        .unwrap();
+    let genesis_qc = certificate_for_genesis();
+    // The proposal skipping round 1 does not record its proposer as failed
+    let block_skip_round =
+        Block::new_proposal(Payload::empty(), 2, 2, genesis_qc.clone(), &node.signer);
+    let timeout = Timeout::new(1, 1);
+    let timeout_signature = timeout.sign(&node.signer);
+
+    let mut tc = TimeoutCertificate::new(timeout);
+    tc.add_signature(node.signer.author(), timeout_signature);
+
+    timed_block_on(&mut runtime, async {
+        let skip_round_proposal = ProposalMsg::new(
+            block_skip_round,
+            SyncInfo::new(genesis_qc.clone(), genesis_qc.clone(), Some(tc), None),
+        );
+        assert!(node
+            .round_manager
+            .process_proposal_msg(skip_round_proposal)
+            .await
+            .is_err());
+    });
+}
+
+#[test]
+fn reject_proposal_with_mismatching_payload_type() {
+    let mut runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut node = NodeSetup::create_nodes(&mut playground, runtime.handle().clone(), 1)
+        .pop()
+        .unwrap();
+    let genesis_qc = certificate_for_genesis();
+    // The quorum store is disabled by the default on-chain config
+    let info = BatchInfo::new(node.signer.author(), 1, 0, HashValue::random(), 1);
+    let payload = Payload::InQuorumStore(vec![ProofOfStore::new(info, BTreeMap::new())]);
+    let block = Block::new_proposal(payload, 1, 1, genesis_qc.clone(), &node.signer);
+
+    timed_block_on(&mut runtime, async {
+        let proposal = ProposalMsg::new(
+            block,
+            SyncInfo::new(genesis_qc.clone(), genesis_qc, None, None),
+        );
+        assert!(node
+            .round_manager
+            .process_proposal_msg(proposal)
+            .await
+            .is_err());
+    });
+}
+
 #[test]
 fn response_on_block_retrieval() {
     let mut runtime = consensus_runtime();
@@ -558,7 +638,7 @@ fn response_on_block_retrieval() {
         .unwrap();
 
     let genesis_qc = certificate_for_genesis();
-    let block = Block::new_proposal(vec![], 1, 1, genesis_qc.clone(), &node.signer);
+    let block = Block::new_proposal(Payload::empty(), 1, 1, genesis_qc.clone(), &node.signer);
     let block_id = block.id();
     let proposal = ProposalMsg::new(
         block,
@@ -659,7 +739,7 @@ fn recover_on_restart() {
     let num_proposals = 100;
     // insert a few successful proposals
     for i in 1..=num_proposals {
-        let proposal = inserter.create_block_with_qc(genesis_qc.clone(), i, i, vec![]);
+        let proposal = inserter.create_block_with_qc(genesis_qc.clone(), i, i, Payload::empty());
         let timeout = Timeout::new(1, i - 1);
         let mut tc = TimeoutCertificate::new(timeout.clone());
         tc.add_signature(inserter.signer().author(), inserter.signer().sign(&timeout));
@@ -769,7 +849,7 @@ fn sync_info_sent_on_stale_sync_info() {
     let mut nodes = NodeSetup::create_nodes(&mut playground, runtime.handle().clone(), 2);
     runtime.spawn(playground.start());
     let genesis_qc = certificate_for_genesis();
-    let block_0 = Block::new_proposal(vec![], 1, 1, genesis_qc, &nodes[0].signer);
+    let block_0 = Block::new_proposal(Payload::empty(), 1, 1, genesis_qc, &nodes[0].signer);
     let parent_block_info = block_0.quorum_cert().certified_block();
     let block_0_quorum_cert = gen_test_certificate(
         vec![&nodes[0].signer, &nodes[1].signer],
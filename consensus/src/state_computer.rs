@@ -3,18 +3,23 @@
 
 use crate::{
     error::StateSyncError,
+    quorum_store::batch_store::BatchStore,
     state_replication::{StateComputer, StateComputerCommitCallBackType},
 };
 use anyhow::Result;
 use consensus_notifications::ConsensusNotificationSender;
-use consensus_types::{block::Block, executed_block::ExecutedBlock};
+use consensus_types::{block::Block, common::Payload, executed_block::ExecutedBlock};
 use execution_correctness::ExecutionCorrectness;
 use executor_types::{Error as ExecutionError, StateComputeResult};
 use fail::fail_point;
 use mango_crypto::HashValue;
+use mango_infallible::Mutex;
 use mango_logger::prelude::*;
 use mango_metrics::monitor;
-use mango_types::ledger_info::LedgerInfoWithSignatures;
+use mango_types::{
+    account_address::AccountAddress, epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction,
+};
 use std::{boxed::Box, sync::Arc};
 
 /// Basic communication with the Execution module;
@@ -22,18 +27,31 @@ use std::{boxed::Box, sync::Arc};
 pub struct ExecutionProxy {
     execution_correctness_client: Box<dyn ExecutionCorrectness + Send + Sync>,
     state_sync_notifier: Box<dyn ConsensusNotificationSender>,
+    // Ordered account addresses of the validators of the current epoch.
+    validators: Mutex<Vec<AccountAddress>>,
+    // Resolves the transactions of the quorum store batches referenced by the blocks.
+    batch_store: Arc<BatchStore>,
 }
 
 impl ExecutionProxy {
     pub fn new(
         execution_correctness_client: Box<dyn ExecutionCorrectness + Send + Sync>,
         state_sync_notifier: Box<dyn ConsensusNotificationSender>,
+        batch_store: Arc<BatchStore>,
     ) -> Self {
         Self {
             execution_correctness_client,
             state_sync_notifier,
+            validators: Mutex::new(vec![]),
+            batch_store,
         }
     }
+
+    fn transactions(&self, block: &Block) -> Result<Vec<SignedTransaction>, ExecutionError> {
+        block.payload().map_or(Ok(vec![]), |payload| {
+            self.batch_store.get_transactions(payload)
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -57,13 +75,31 @@ impl StateComputer for ExecutionProxy {
         );
 
         // TODO: figure out error handling for the prologue txn
+        let validators = self.validators.lock().clone();
+        let txns = self.transactions(block)?;
         monitor!(
             "execute_block",
-            self.execution_correctness_client
-                .execute_block(block.clone(), parent_block_id)
+            self.execution_correctness_client.execute_block(
+                block.clone(),
+                parent_block_id,
+                &validators,
+                txns
+            )
         )
     }
 
+    async fn fetch_payload(&self, block: &Block) -> Result<(), ExecutionError> {
+        match block.payload() {
+            Some(Payload::InQuorumStore(proofs)) => {
+                monitor!(
+                    "fetch_batches",
+                    self.batch_store.fetch_missing(proofs).await
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Send a successful commit. A future is fulfilled when the state is finalized.
     async fn commit(
         &self,
@@ -74,11 +110,15 @@ impl StateComputer for ExecutionProxy {
         let mut block_ids = Vec::new();
         let mut txns = Vec::new();
         let mut reconfig_events = Vec::new();
+        let mut batch_digests = Vec::new();
+        let validators = self.validators.lock().clone();
 
         for block in blocks {
             block_ids.push(block.id());
-            txns.extend(block.transactions_to_commit());
+            let block_txns = self.transactions(block.block())?;
+            txns.extend(block.transactions_to_commit(&validators, block_txns));
             reconfig_events.extend(block.reconfig_event());
+            batch_digests.extend(block.payload().map_or(vec![], Payload::batch_digests));
         }
 
         monitor!(
@@ -95,6 +135,7 @@ impl StateComputer for ExecutionProxy {
         ) {
             error!(error = ?e, "Failed to notify state synchronizer");
         }
+        self.batch_store.commit(batch_digests);
 
         callback(blocks, finality_proof);
 
@@ -124,4 +165,11 @@ impl StateComputer for ExecutionProxy {
             anyhow_error.into()
         })
     }
+
+    fn new_epoch(&self, epoch_state: &EpochState) {
+        *self.validators.lock() = epoch_state
+            .verifier
+            .get_ordered_account_addresses_iter()
+            .collect();
+    }
 }
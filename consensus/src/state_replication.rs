@@ -6,7 +6,7 @@ use anyhow::Result;
 use consensus_types::{block::Block, common::Payload, executed_block::ExecutedBlock};
 use executor_types::{Error as ExecutionError, StateComputeResult};
 use mango_crypto::HashValue;
-use mango_types::ledger_info::LedgerInfoWithSignatures;
+use mango_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures};
 use std::sync::Arc;
 
 pub type StateComputerCommitCallBackType =
@@ -56,6 +56,12 @@ pub trait StateComputer: Send + Sync {
         parent_block_id: HashValue,
     ) -> Result<StateComputeResult, ExecutionError>;
 
+    /// Makes sure the payload of the block is available locally before it's executed, e.g. by
+    /// fetching the quorum store batches it references.
+    async fn fetch_payload(&self, _block: &Block) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
     /// Send a successful commit. A future is fulfilled when the state is finalized.
     async fn commit(
         &self,
@@ -69,4 +75,8 @@ pub trait StateComputer: Send + Sync {
     /// In case of failure (`Result::Error`) the LI of storage remains unchanged, and the validator
     /// can assume there were no modifications to the storage made.
     async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError>;
+
+    /// Notifies the StateComputer of the validators of a new epoch, whose order is needed to
+    /// resolve the voters of aggregate signatures when building block metadata.
+    fn new_epoch(&self, _epoch_state: &EpochState) {}
 }
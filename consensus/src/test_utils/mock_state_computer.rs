@@ -10,18 +10,56 @@ use anyhow::{format_err, Result};
 use consensus_types::{block::Block, common::Payload, executed_block::ExecutedBlock};
 use executor_types::{Error, StateComputeResult};
 use futures::channel::mpsc;
-use mango_crypto::HashValue;
+use mango_crypto::{hash::TransactionAccumulatorHasher, HashValue};
 use mango_infallible::Mutex;
 use mango_logger::prelude::*;
-use mango_types::ledger_info::LedgerInfoWithSignatures;
+use mango_types::{
+    block_info::BlockInfo, ledger_info::LedgerInfoWithSignatures,
+    proof::accumulator::InMemoryAccumulator, transaction::SignedTransaction,
+};
 use std::{collections::HashMap, sync::Arc};
 use termion::color::*;
 
+/// Transaction accumulators of the blocks executed by the `MockStateComputer`s sharing it.
+/// Executing a block appends its id as a single leaf to the accumulator of its parent, so every
+/// block gets its own version and a state id committing to its whole chain. Nodes of a test
+/// share one history since a node that synced has to extend blocks it never executed.
+#[derive(Default)]
+pub struct MockExecutionHistory {
+    accumulators: Mutex<HashMap<HashValue, InMemoryAccumulator<TransactionAccumulatorHasher>>>,
+}
+
+impl MockExecutionHistory {
+    fn execute(&self, block_id: HashValue, parent_block_id: HashValue) -> StateComputeResult {
+        let mut accumulators = self.accumulators.lock();
+        // The mock genesis has an empty ledger (its state id is the placeholder hash)
+        let parent = accumulators
+            .get(&parent_block_id)
+            .cloned()
+            .unwrap_or_default();
+        let accumulator = parent.append(&[block_id]);
+        accumulators.insert(block_id, accumulator.clone());
+        StateComputeResult::new(
+            accumulator.root_hash(),
+            accumulator.frozen_subtree_roots().clone(),
+            accumulator.num_leaves(),
+            parent.frozen_subtree_roots().clone(),
+            parent.num_leaves(),
+            None,
+            vec![],
+            vec![block_id],
+            vec![],
+        )
+    }
+}
+
 pub struct MockStateComputer {
     state_sync_client: mpsc::UnboundedSender<Payload>,
     commit_callback: mpsc::UnboundedSender<LedgerInfoWithSignatures>,
     consensus_db: Arc<MockStorage>,
-    block_cache: Mutex<HashMap<HashValue, Payload>>,
+    block_cache: Mutex<HashMap<HashValue, Vec<SignedTransaction>>>,
+    execution_history: Option<Arc<MockExecutionHistory>>,
+    committed_blocks: Mutex<Vec<BlockInfo>>,
 }
 
 impl MockStateComputer {
@@ -35,8 +73,21 @@ impl MockStateComputer {
             commit_callback,
             consensus_db,
             block_cache: Mutex::new(HashMap::new()),
+            execution_history: None,
+            committed_blocks: Mutex::new(vec![]),
         }
     }
+
+    /// Executes blocks on top of a shared history instead of returning dummy results.
+    pub fn with_execution_history(mut self, execution_history: Arc<MockExecutionHistory>) -> Self {
+        self.execution_history = Some(execution_history);
+        self
+    }
+
+    /// Every block committed so far, in commit order. A sync only adds the block synced to.
+    pub fn committed_blocks(&self) -> Vec<BlockInfo> {
+        self.committed_blocks.lock().clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -44,12 +95,18 @@ impl StateComputer for MockStateComputer {
     fn compute(
         &self,
         block: &Block,
-        _parent_block_id: HashValue,
+        parent_block_id: HashValue,
     ) -> Result<StateComputeResult, Error> {
-        self.block_cache
-            .lock()
-            .insert(block.id(), block.payload().unwrap_or(&vec![]).clone());
-        let result = StateComputeResult::new_dummy();
+        self.block_cache.lock().insert(
+            block.id(),
+            block
+                .payload()
+                .map_or(vec![], |payload| payload.inline_transactions().to_vec()),
+        );
+        let result = match &self.execution_history {
+            Some(history) => history.execute(block.id(), parent_block_id),
+            None => StateComputeResult::new_dummy(),
+        };
         Ok(result)
     }
 
@@ -72,8 +129,13 @@ impl StateComputer for MockStateComputer {
                 .ok_or_else(|| format_err!("Cannot find block"))?;
             txns.append(&mut payload);
         }
+        self.committed_blocks
+            .lock()
+            .extend(blocks.iter().map(|block| block.block_info()));
         // they may fail during shutdown
-        let _ = self.state_sync_client.unbounded_send(txns);
+        let _ = self
+            .state_sync_client
+            .unbounded_send(Payload::DirectMempool(txns));
 
         let _ = self.commit_callback.unbounded_send(commit.clone());
 
@@ -91,6 +153,9 @@ impl StateComputer for MockStateComputer {
         );
         self.consensus_db
             .commit_to_storage(commit.ledger_info().clone());
+        self.committed_blocks
+            .lock()
+            .push(commit.ledger_info().commit_info().clone());
         self.commit_callback
             .unbounded_send(commit)
             .expect("Fail to notify about sync");
@@ -9,8 +9,9 @@ use crate::{
 };
 use anyhow::Result;
 use consensus_types::{
-    block::Block, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate,
-    timeout_certificate::TimeoutCertificate, vote::Vote,
+    block::Block, proof_of_store::Batch, quorum_cert::QuorumCert,
+    timeout_2chain::TwoChainTimeoutCertificate, timeout_certificate::TimeoutCertificate,
+    vote::Vote,
 };
 use mango_crypto::HashValue;
 use mango_infallible::Mutex;
@@ -31,6 +32,7 @@ pub struct MockSharedStorage {
     pub qc: Mutex<HashMap<HashValue, QuorumCert>>,
     pub lis: Mutex<HashMap<u64, LedgerInfoWithSignatures>>,
     pub last_vote: Mutex<Option<Vote>>,
+    pub batch: Mutex<HashMap<HashValue, Batch>>,
 
     // Liveness state
     pub highest_timeout_certificate: Mutex<Option<TimeoutCertificate>>,
@@ -45,6 +47,7 @@ impl MockSharedStorage {
             qc: Mutex::new(HashMap::new()),
             lis: Mutex::new(HashMap::new()),
             last_vote: Mutex::new(None),
+            batch: Mutex::new(HashMap::new()),
             highest_timeout_certificate: Mutex::new(None),
             highest_2chain_timeout_certificate: Mutex::new(None),
             validator_set,
@@ -233,6 +236,26 @@ impl PersistentLivenessStorage for MockStorage {
         Ok(())
     }
 
+    fn save_batches(&self, batches: Vec<Batch>) -> Result<()> {
+        let mut stored = self.shared_storage.batch.lock();
+        for batch in batches {
+            stored.insert(batch.digest(), batch);
+        }
+        Ok(())
+    }
+
+    fn prune_batches(&self, digests: Vec<HashValue>) -> Result<()> {
+        let mut stored = self.shared_storage.batch.lock();
+        for digest in digests {
+            stored.remove(&digest);
+        }
+        Ok(())
+    }
+
+    fn get_batches(&self) -> Result<Vec<Batch>> {
+        Ok(self.shared_storage.batch.lock().values().cloned().collect())
+    }
+
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
         let lis = self
             .shared_storage
@@ -312,6 +335,18 @@ impl PersistentLivenessStorage for EmptyStorage {
         Ok(())
     }
 
+    fn save_batches(&self, _: Vec<Batch>) -> Result<()> {
+        Ok(())
+    }
+
+    fn prune_batches(&self, _: Vec<HashValue>) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_batches(&self) -> Result<Vec<Batch>> {
+        Ok(vec![])
+    }
+
     fn retrieve_epoch_change_proof(&self, _version: u64) -> Result<EpochChangeProof> {
         unimplemented!()
     }
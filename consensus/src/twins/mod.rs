@@ -2,4 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod basic_twins_test;
+mod scenario;
+mod scenario_runner;
+mod twins_campaign_test;
 mod twins_node;
@@ -0,0 +1,478 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use consensus_types::common::Round;
+use mango_types::transaction::Version;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A Twins scenario: which nodes have a twin, which node leads every round and how the network
+/// is partitioned in that round. Nodes have indices `0..num_nodes` and the twin of `twins[k]` has
+/// index `num_nodes + k`.
+///
+/// Scenarios are serialized as JSON so a failing one can be stored and replayed.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Scenario {
+    pub num_nodes: usize,
+    /// Nodes that run with a twin.
+    pub twins: Vec<usize>,
+    /// Leader (a node index in `0..num_nodes`) of every round; twins lead with their node.
+    pub round_leaders: BTreeMap<Round, usize>,
+    /// Network partitions of every round, rounds not listed are fully connected.
+    pub round_partitions: BTreeMap<Round, Vec<Vec<usize>>>,
+}
+
+impl Scenario {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let scenario: Scenario = serde_json::from_str(json)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Scenario serialization can't fail")
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let mut twinned = BTreeSet::new();
+        for node in &self.twins {
+            ensure!(*node < self.num_nodes, "Twin of unknown node {}", node);
+            ensure!(twinned.insert(*node), "Node {} has several twins", node);
+        }
+        for (round, leader) in &self.round_leaders {
+            ensure!(
+                *leader < self.num_nodes,
+                "Leader {} of round {} is not a node",
+                leader,
+                round
+            );
+        }
+        for (round, partitions) in &self.round_partitions {
+            let mut seen = BTreeSet::new();
+            for index in partitions.iter().flatten() {
+                ensure!(
+                    *index < self.total_nodes(),
+                    "Unknown node {} in round {}",
+                    index,
+                    round
+                );
+                ensure!(
+                    seen.insert(*index),
+                    "Node {} is in several partitions in round {}",
+                    index,
+                    round
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn num_twins(&self) -> usize {
+        self.twins.len()
+    }
+
+    /// Number of running instances, twins included.
+    pub fn total_nodes(&self) -> usize {
+        self.num_nodes + self.num_twins()
+    }
+
+    /// The node index an instance votes as, which is the same for a node and its twin.
+    pub fn author_index(&self, index: usize) -> usize {
+        if index >= self.num_nodes {
+            self.twins[index - self.num_nodes]
+        } else {
+            index
+        }
+    }
+
+    /// Nodes without a twin; safety only has to hold among those.
+    pub fn honest_nodes(&self) -> Vec<usize> {
+        (0..self.num_nodes)
+            .filter(|node| !self.twins.contains(node))
+            .collect()
+    }
+
+    /// Scenario indices in the order of the instances started by
+    /// `SMRNode::start_num_nodes_with_twins`, which gives a twin to its first `num_twins` nodes.
+    pub fn instance_order(&self) -> Vec<usize> {
+        let mut order = self.twins.clone();
+        order.extend(self.honest_nodes());
+        order.extend(self.num_nodes..self.total_nodes());
+        order
+    }
+
+    pub fn quorum_size(&self) -> usize {
+        self.num_nodes * 2 / 3 + 1
+    }
+
+    /// A round is synchronous if one partition holds a quorum of distinct authors, the leader
+    /// of the round and the leader of the next round (which collects the votes).
+    pub fn is_round_synchronous(&self, round: Round) -> bool {
+        let leaders: Vec<usize> = [round, round + 1]
+            .iter()
+            .filter_map(|r| self.round_leaders.get(r).copied())
+            .collect();
+        let partitions = match self.round_partitions.get(&round) {
+            Some(partitions) => partitions.clone(),
+            None => vec![(0..self.total_nodes()).collect()],
+        };
+        partitions.iter().any(|partition| {
+            let authors: BTreeSet<usize> =
+                partition.iter().map(|i| self.author_index(*i)).collect();
+            authors.len() >= self.quorum_size()
+                && leaders.iter().all(|leader| authors.contains(leader))
+        })
+    }
+
+    /// Without timeouts the honest nodes are only guaranteed to commit if no round stalls.
+    pub fn expects_liveness(&self) -> bool {
+        self.round_leaders
+            .keys()
+            .all(|round| self.is_round_synchronous(*round))
+    }
+}
+
+/// Enumerates Twins scenarios over a fixed number of rounds. Every scenario places the twins on
+/// a set of nodes, and every round picks a leader and a partitioning of all instances into at
+/// most `max_partitions` parts.
+pub struct ScenarioGenerator {
+    num_nodes: usize,
+    num_rounds: usize,
+    placements: Vec<Vec<usize>>,
+    partition_options: Vec<Vec<Vec<usize>>>,
+}
+
+impl ScenarioGenerator {
+    pub fn new(
+        num_nodes: usize,
+        num_twins: usize,
+        num_rounds: usize,
+        max_partitions: usize,
+    ) -> Self {
+        assert!(num_twins <= num_nodes);
+        assert!(max_partitions > 0);
+        Self {
+            num_nodes,
+            num_rounds,
+            placements: combinations(num_nodes, num_twins),
+            partition_options: set_partitions(num_nodes + num_twins, max_partitions),
+        }
+    }
+
+    /// Number of distinct choices of the nodes that have a twin.
+    pub fn num_placements(&self) -> usize {
+        self.placements.len()
+    }
+
+    /// Number of distinct (leader, partitioning) choices for a single round.
+    pub fn options_per_round(&self) -> usize {
+        self.num_nodes * self.partition_options.len()
+    }
+
+    /// Deterministically enumerates scenarios, varying the twin placement fastest and then the
+    /// last round.
+    pub fn enumerate(&self) -> impl Iterator<Item = Scenario> + '_ {
+        let options = self.options_per_round();
+        let mut choices = vec![0; self.num_rounds];
+        let mut placement = 0;
+        let mut done = options == 0;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let scenario = self.scenario_from_choices(placement, &choices);
+            placement += 1;
+            if placement < self.num_placements() {
+                return Some(scenario);
+            }
+            placement = 0;
+            done = true;
+            for choice in choices.iter_mut().rev() {
+                *choice += 1;
+                if *choice < options {
+                    done = false;
+                    break;
+                }
+                *choice = 0;
+            }
+            Some(scenario)
+        })
+    }
+
+    /// Samples a scenario uniformly, for randomized campaigns.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Scenario {
+        let options = self.options_per_round();
+        let placement = rng.gen_range(0..self.num_placements());
+        let choices: Vec<usize> = (0..self.num_rounds)
+            .map(|_| rng.gen_range(0..options))
+            .collect();
+        self.scenario_from_choices(placement, &choices)
+    }
+
+    fn scenario_from_choices(&self, placement: usize, choices: &[usize]) -> Scenario {
+        let mut round_leaders = BTreeMap::new();
+        let mut round_partitions = BTreeMap::new();
+        for (i, choice) in choices.iter().enumerate() {
+            // Round 0 is genesis.
+            let round = i as Round + 1;
+            round_leaders.insert(round, choice % self.num_nodes);
+            let partitions = &self.partition_options[choice / self.num_nodes];
+            if partitions.len() > 1 {
+                round_partitions.insert(round, partitions.clone());
+            }
+        }
+        Scenario {
+            num_nodes: self.num_nodes,
+            twins: self.placements[placement].clone(),
+            round_leaders,
+            round_partitions,
+        }
+    }
+}
+
+/// All subsets of `0..n` with `k` elements, in lexicographic order.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    fn extend(
+        subset: &mut Vec<usize>,
+        start: usize,
+        n: usize,
+        k: usize,
+        result: &mut Vec<Vec<usize>>,
+    ) {
+        if subset.len() == k {
+            result.push(subset.clone());
+            return;
+        }
+        for next in start..n {
+            subset.push(next);
+            extend(subset, next + 1, n, k, result);
+            subset.pop();
+        }
+    }
+
+    let mut result = vec![];
+    extend(&mut vec![], 0, n, k, &mut result);
+    result
+}
+
+/// All partitions of `0..n` into at most `max_parts` non-empty parts, generated from restricted
+/// growth strings so every partition shows up exactly once.
+fn set_partitions(n: usize, max_parts: usize) -> Vec<Vec<Vec<usize>>> {
+    fn extend(
+        assignment: &mut Vec<usize>,
+        n: usize,
+        max_parts: usize,
+        result: &mut Vec<Vec<Vec<usize>>>,
+    ) {
+        if assignment.len() == n {
+            let num_parts = assignment.iter().max().map_or(0, |max| max + 1);
+            let mut parts = vec![vec![]; num_parts];
+            for (node, part) in assignment.iter().enumerate() {
+                parts[*part].push(node);
+            }
+            result.push(parts);
+            return;
+        }
+        let next_part = assignment.iter().max().map_or(0, |max| max + 1);
+        for part in 0..=next_part.min(max_parts - 1) {
+            assignment.push(part);
+            extend(assignment, n, max_parts, result);
+            assignment.pop();
+        }
+    }
+
+    let mut result = vec![];
+    extend(&mut vec![], n, max_parts, &mut result);
+    result
+}
+
+/// A block committed by an instance, along with the version and state id it executed to.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ObservedCommit {
+    pub node: usize,
+    pub round: Round,
+    pub block_id: String,
+    pub version: Version,
+    pub executed_state_id: String,
+}
+
+impl ObservedCommit {
+    /// Two commits can be part of the same chain if they are the same block, or if they are at
+    /// different rounds and versions, ordered the same way.
+    fn is_consistent_with(&self, other: &ObservedCommit) -> bool {
+        if self.round == other.round || self.version == other.version {
+            self.round == other.round
+                && self.version == other.version
+                && self.block_id == other.block_id
+                && self.executed_state_id == other.executed_state_id
+        } else {
+            (self.round < other.round) == (self.version < other.version)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Violation {
+    /// Two honest nodes committed chains where neither is a prefix of the other: the two commits
+    /// are at the same round or version but differ, or are ordered differently by round and by
+    /// version.
+    ConflictingCommits {
+        first: ObservedCommit,
+        second: ObservedCommit,
+    },
+    /// No honest node committed although every round was synchronous.
+    NoProgress,
+}
+
+/// Checks the safety and liveness invariants of a scenario against the observed commits. Every
+/// block executes to its own version and its state id commits to the whole chain, so comparing
+/// the commits of the honest nodes at equal versions checks their chains are prefixes of each
+/// other (a node that synced is only compared at the versions it committed).
+pub fn check_invariants(scenario: &Scenario, commits: &[ObservedCommit]) -> Vec<Violation> {
+    let honest: BTreeSet<usize> = scenario.honest_nodes().into_iter().collect();
+    let mut violations = vec![];
+    let mut committed: Vec<&ObservedCommit> = vec![];
+    for commit in commits.iter().filter(|c| honest.contains(&c.node)) {
+        match committed
+            .iter()
+            .find(|existing| !existing.is_consistent_with(commit))
+        {
+            Some(existing) => violations.push(Violation::ConflictingCommits {
+                first: (*existing).clone(),
+                second: commit.clone(),
+            }),
+            None => committed.push(commit),
+        }
+    }
+    if scenario.expects_liveness() && committed.is_empty() {
+        violations.push(Violation::NoProgress);
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_set_partitions() {
+        // Bell numbers, restricted to two parts: 2^(n-1).
+        assert_eq!(set_partitions(4, 4).len(), 15);
+        assert_eq!(set_partitions(5, 2).len(), 16);
+        assert_eq!(set_partitions(3, 1), vec![vec![vec![0, 1, 2]]]);
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(combinations(3, 0), vec![Vec::<usize>::new()]);
+        assert_eq!(combinations(3, 2), vec![vec![0, 1], vec![0, 2], vec![1, 2]]);
+        assert_eq!(combinations(4, 2).len(), 6);
+    }
+
+    #[test]
+    fn test_enumerate_and_sample() {
+        let generator = ScenarioGenerator::new(4, 1, 2, 2);
+        let options = generator.options_per_round();
+        assert_eq!(options, 4 * 16);
+        assert_eq!(generator.num_placements(), 4);
+
+        let scenarios: Vec<_> = generator.enumerate().collect();
+        assert_eq!(scenarios.len(), 4 * options * options);
+        let distinct: BTreeSet<_> = scenarios.iter().map(|s| s.to_json()).collect();
+        assert_eq!(distinct.len(), scenarios.len());
+        // Every node gets to be the twinned one.
+        let placements: BTreeSet<_> = scenarios.iter().map(|s| s.twins.clone()).collect();
+        assert_eq!(placements.len(), 4);
+        // The first scenario is fully connected with node 0 leading.
+        assert!(scenarios[0].round_partitions.is_empty());
+        assert!(scenarios[0].expects_liveness());
+
+        let mut rng = StdRng::from_seed([7u8; 32]);
+        let sampled = generator.sample(&mut rng);
+        assert!(sampled.validate().is_ok());
+        assert_eq!(Scenario::from_json(&sampled.to_json()).unwrap(), sampled);
+    }
+
+    #[test]
+    fn test_twin_placement() {
+        let scenario = Scenario {
+            num_nodes: 4,
+            twins: vec![2],
+            round_leaders: BTreeMap::new(),
+            round_partitions: BTreeMap::new(),
+        };
+        assert_eq!(scenario.honest_nodes(), vec![0, 1, 3]);
+        assert_eq!(scenario.author_index(4), 2);
+        assert_eq!(scenario.instance_order(), vec![2, 0, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_check_invariants() {
+        let scenario = Scenario {
+            num_nodes: 4,
+            twins: vec![0],
+            round_leaders: vec![(1, 0), (2, 1)].into_iter().collect(),
+            round_partitions: vec![(1, vec![vec![0, 1, 2], vec![3, 4]])]
+                .into_iter()
+                .collect(),
+        };
+        assert!(scenario.expects_liveness());
+        assert_eq!(
+            check_invariants(&scenario, &[]),
+            vec![Violation::NoProgress]
+        );
+
+        let commit = |node, round, version, block_id: &str| ObservedCommit {
+            node,
+            round,
+            block_id: block_id.to_string(),
+            version,
+            executed_state_id: format!("{}-state", block_id),
+        };
+        let conflict = |first, second| {
+            vec![Violation::ConflictingCommits {
+                first: commit(1, 1, 0, first),
+                second,
+            }]
+        };
+        // Node 0 has a twin, so its commits don't count towards safety.
+        assert!(
+            check_invariants(&scenario, &[commit(0, 1, 0, "a"), commit(1, 1, 0, "b")]).is_empty()
+        );
+        // Honest nodes extending the same chain are fine, at any point of it.
+        assert!(check_invariants(
+            &scenario,
+            &[
+                commit(1, 1, 0, "a"),
+                commit(2, 1, 0, "a"),
+                commit(2, 3, 1, "b")
+            ]
+        )
+        .is_empty());
+        // Different blocks at the same round.
+        assert_eq!(
+            check_invariants(&scenario, &[commit(1, 1, 0, "a"), commit(2, 1, 0, "b")]),
+            conflict("a", commit(2, 1, 0, "b"))
+        );
+        // Different blocks at the same version, even from different rounds.
+        assert_eq!(
+            check_invariants(&scenario, &[commit(1, 1, 0, "a"), commit(3, 2, 0, "b")]),
+            conflict("a", commit(3, 2, 0, "b"))
+        );
+        // A later round committed at an earlier version forks off the chain.
+        assert_eq!(
+            check_invariants(
+                &scenario,
+                &[
+                    commit(1, 1, 0, "a"),
+                    commit(1, 2, 1, "b"),
+                    commit(2, 3, 0, "c")
+                ]
+            ),
+            conflict("a", commit(2, 3, 0, "c"))
+        );
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    network_tests::{NetworkPlayground, TwinId},
+    test_utils::consensus_runtime,
+    twins::{
+        scenario::{check_invariants, ObservedCommit, Scenario, Violation},
+        twins_node::SMRNode,
+    },
+};
+use consensus_types::common::Round;
+use futures::{future::select_all, StreamExt};
+use mango_config::config::ConsensusProposerType::RoundProposer;
+use mango_logger::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path, time::Duration};
+
+/// Environment variable naming the directory failing scenarios are written to.
+pub const FAILURE_DIR_ENV: &str = "TWINS_FAILURE_DIR";
+
+/// How long a scenario expecting liveness may take for its first honest commit.
+const LIVENESS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The result of running a scenario, serialized as JSON when it has violations.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ScenarioOutcome {
+    pub scenario: Scenario,
+    pub commits: Vec<ObservedCommit>,
+    pub violations: Vec<Violation>,
+}
+
+impl ScenarioOutcome {
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Runs a scenario against real `RoundManager`s connected through a `NetworkPlayground` and
+/// checks the blocks committed by every instance. A scenario expecting liveness runs until an
+/// honest node commits (or `LIVENESS_TIMEOUT` expires), any other one for `duration`.
+pub fn run_scenario(scenario: &Scenario, duration: Duration) -> ScenarioOutcome {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    // The started instances twin their first nodes, so the scenario's nodes are relabeled to
+    // put the twinned ones first.
+    let instance_order = scenario.instance_order();
+    let instance_of = |index: usize| {
+        instance_order
+            .iter()
+            .position(|i| *i == index)
+            .expect("Every scenario index has an instance")
+    };
+    let round_proposers: HashMap<Round, usize> = scenario
+        .round_leaders
+        .iter()
+        .map(|(round, leader)| (*round, instance_of(*leader)))
+        .collect();
+    let mut nodes = SMRNode::start_num_nodes_with_twins(
+        scenario.num_nodes,
+        scenario.num_twins(),
+        &mut playground,
+        RoundProposer(HashMap::new()),
+        Some(round_proposers),
+    );
+
+    let twin_ids: Vec<TwinId> = (0..scenario.total_nodes())
+        .map(|index| nodes[instance_of(index)].id)
+        .collect();
+    let round_partitions: HashMap<u64, Vec<Vec<TwinId>>> = scenario
+        .round_partitions
+        .iter()
+        .map(|(round, partitions)| {
+            let partitions = partitions
+                .iter()
+                .map(|partition| partition.iter().map(|i| twin_ids[*i]).collect())
+                .collect();
+            (*round, partitions)
+        })
+        .collect();
+    assert!(playground.split_network_round(&round_partitions));
+    runtime.spawn(playground.start());
+
+    let honest = scenario.honest_nodes();
+    if scenario.expects_liveness() && !honest.is_empty() {
+        let honest_commits = nodes
+            .iter_mut()
+            .enumerate()
+            .filter(|(instance, _)| honest.contains(&instance_order[*instance]))
+            .map(|(_, node)| node.commit_cb_receiver.next());
+        let _ = runtime.block_on(tokio::time::timeout(
+            LIVENESS_TIMEOUT,
+            select_all(honest_commits),
+        ));
+    } else {
+        runtime.block_on(tokio::time::sleep(duration));
+    }
+
+    let mut commits = vec![];
+    for (instance, node) in nodes.iter().enumerate() {
+        for block_info in node.state_computer.committed_blocks() {
+            commits.push(ObservedCommit {
+                node: instance_order[instance],
+                round: block_info.round(),
+                block_id: block_info.id().to_hex(),
+                version: block_info.version(),
+                executed_state_id: block_info.executed_state_id().to_hex(),
+            });
+        }
+    }
+    let violations = check_invariants(scenario, &commits);
+    ScenarioOutcome {
+        scenario: scenario.clone(),
+        commits,
+        violations,
+    }
+}
+
+/// Writes a failing outcome as `<dir>/<name>.json`, returning the JSON so it can be logged.
+pub fn record_failure(outcome: &ScenarioOutcome, dir: Option<&Path>, name: &str) -> String {
+    let json = serde_json::to_string_pretty(outcome).expect("Outcome serialization can't fail");
+    if let Some(dir) = dir {
+        let path = dir.join(format!("{}.json", name));
+        if let Err(e) = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, &json)) {
+            error!(
+                path = path.display().to_string(),
+                error = e.to_string(),
+                "[TwinsTest] Failed to write failing scenario"
+            );
+        }
+    }
+    json
+}
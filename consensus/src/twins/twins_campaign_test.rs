@@ -0,0 +1,107 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::twins::{
+    scenario::{Scenario, ScenarioGenerator},
+    scenario_runner::{record_failure, run_scenario, ScenarioOutcome, FAILURE_DIR_ENV},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{env, path::PathBuf, time::Duration};
+
+const NUM_NODES: usize = 4;
+const NUM_TWINS: usize = 1;
+const NUM_ROUNDS: usize = 3;
+const MAX_PARTITIONS: usize = 2;
+/// How long scenarios not expecting liveness run; those expecting it wait for a commit instead.
+const SCENARIO_DURATION: Duration = Duration::from_secs(5);
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Runs every scenario and panics with the JSON of the failing ones, which are also written to
+/// `$TWINS_FAILURE_DIR` when set. `context` is added to the failure message so the run can be
+/// reproduced.
+fn run_scenarios(scenarios: impl Iterator<Item = Scenario>, name: &str, context: &str) {
+    let failure_dir = env::var(FAILURE_DIR_ENV).ok().map(PathBuf::from);
+    let failures: Vec<String> = scenarios
+        .enumerate()
+        .map(|(i, scenario)| (i, run_scenario(&scenario, SCENARIO_DURATION)))
+        .filter(|(_, outcome)| !outcome.is_ok())
+        .map(|(i, outcome)| {
+            record_failure(&outcome, failure_dir.as_deref(), &format!("{}-{}", name, i))
+        })
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "[TwinsTest] {} failing scenario(s) in {}:\n{}",
+        failures.len(),
+        context,
+        failures.join("\n")
+    );
+}
+
+#[test]
+/// This test runs a fixed, evenly spread subset of the enumerated scenarios
+///
+/// Setup:
+///
+/// 4 nodes, one of which has a twin, over 3 rounds with at most 2 partitions per round
+///
+/// Run the test:
+/// cargo xtest -p consensus twins_small_suite_test -- --nocapture
+fn twins_small_suite_test() {
+    let generator = ScenarioGenerator::new(NUM_NODES, NUM_TWINS, NUM_ROUNDS, MAX_PARTITIONS);
+    // The twin placement varies fastest, so stepping by a multiple of the placements plus one
+    // moves the twin to another node every time, and the multiple of 61 (a prime close to the
+    // options per round) varies every round across the suite.
+    let step = generator.num_placements() * 61 + 1;
+    let scenarios = generator.enumerate().step_by(step).take(4);
+    run_scenarios(scenarios, "small-suite", "the small suite");
+}
+
+#[test]
+#[ignore]
+/// This test samples random scenarios, for long running campaigns
+///
+/// Run the test:
+/// TWINS_CAMPAIGN_ITERATIONS=1000 TWINS_CAMPAIGN_SEED=42 TWINS_FAILURE_DIR=/tmp/twins \
+/// cargo xtest -p consensus twins_random_campaign_test -- --ignored --nocapture
+fn twins_random_campaign_test() {
+    let iterations: usize = env_or("TWINS_CAMPAIGN_ITERATIONS", 100);
+    let seed: u64 = env_or("TWINS_CAMPAIGN_SEED", rand::thread_rng().gen());
+    let num_rounds: usize = env_or("TWINS_CAMPAIGN_ROUNDS", NUM_ROUNDS);
+    let generator = ScenarioGenerator::new(NUM_NODES, NUM_TWINS, num_rounds, MAX_PARTITIONS);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let scenarios = (0..iterations).map(move |_| generator.sample(&mut rng));
+    run_scenarios(
+        scenarios,
+        &format!("campaign-{}", seed),
+        &format!("the campaign with TWINS_CAMPAIGN_SEED={}", seed),
+    );
+}
+
+#[test]
+#[ignore]
+/// This test replays a scenario, or a recorded failure, from a JSON file
+///
+/// Run the test:
+/// TWINS_SCENARIO=/tmp/twins/campaign-42-7.json \
+/// cargo xtest -p consensus twins_replay_scenario_test -- --ignored --nocapture
+fn twins_replay_scenario_test() {
+    let path = env::var("TWINS_SCENARIO").expect("TWINS_SCENARIO is not set");
+    let json = std::fs::read_to_string(&path).expect("Unable to read the scenario");
+    let scenario = Scenario::from_json(&json).unwrap_or_else(|_| {
+        serde_json::from_str::<ScenarioOutcome>(&json)
+            .expect("Neither a scenario nor a recorded failure")
+            .scenario
+    });
+    run_scenarios(
+        std::iter::once(scenario),
+        "replay",
+        &format!("the replay of {}", path),
+    );
+}
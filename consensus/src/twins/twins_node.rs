@@ -7,7 +7,8 @@ use crate::{
     network::NetworkTask,
     network_interface::{ConsensusNetworkEvents, ConsensusNetworkSender},
     network_tests::{NetworkPlayground, TwinId},
-    test_utils::{MockStateComputer, MockStorage, MockTransactionManager},
+    quorum_store::batch_store::BatchStore,
+    test_utils::{MockExecutionHistory, MockStateComputer, MockStorage, MockTransactionManager},
     util::time_service::ClockTimeService,
 };
 use channel::{self, diem_channel, message_queues::QueueStyle};
@@ -40,6 +41,7 @@ pub struct SMRNode {
     pub id: TwinId,
     pub storage: Arc<MockStorage>,
     pub commit_cb_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
+    pub state_computer: Arc<MockStateComputer>,
     _runtime: Runtime,
     _shared_mempool: MockSharedMempool,
     _state_sync: mpsc::UnboundedReceiver<Payload>,
@@ -55,6 +57,7 @@ impl SMRNode {
         config: NodeConfig,
         storage: Arc<MockStorage>,
         twin_id: TwinId,
+        execution_history: Arc<MockExecutionHistory>,
     ) -> Self {
         let (network_reqs_tx, network_reqs_rx) = diem_channel::new(QueueStyle::FIFO, 8, None);
         let (connection_reqs_tx, _) = diem_channel::new(QueueStyle::FIFO, 8, None);
@@ -74,11 +77,10 @@ impl SMRNode {
         let (commit_cb_sender, commit_cb_receiver) = mpsc::unbounded::<LedgerInfoWithSignatures>();
         let shared_mempool = MockSharedMempool::new(None);
         let consensus_to_mempool_sender = shared_mempool.consensus_sender.clone();
-        let state_computer = Arc::new(MockStateComputer::new(
-            state_sync_client,
-            commit_cb_sender,
-            Arc::clone(&storage),
-        ));
+        let state_computer = Arc::new(
+            MockStateComputer::new(state_sync_client, commit_cb_sender, Arc::clone(&storage))
+                .with_execution_history(execution_history),
+        );
         let txn_manager = Arc::new(MockTransactionManager::new(Some(
             consensus_to_mempool_sender,
         )));
@@ -114,9 +116,14 @@ impl SMRNode {
             network_sender,
             timeout_sender,
             txn_manager,
-            state_computer,
+            state_computer.clone(),
             storage.clone(),
             reconfig_events,
+            Arc::new(BatchStore::new(
+                author_from_config(&config),
+                storage.clone(),
+                1000,
+            )),
         );
         let (network_task, network_receiver) =
             NetworkTask::new(network_events, self_receiver, playground.peer_protocols());
@@ -127,6 +134,7 @@ impl SMRNode {
             id: twin_id,
             _runtime: runtime,
             commit_cb_receiver,
+            state_computer,
             storage,
             _shared_mempool: shared_mempool,
             _state_sync: state_sync,
@@ -198,6 +206,7 @@ impl SMRNode {
         }
 
         let mut smr_nodes = vec![];
+        let execution_history = Arc::new(MockExecutionHistory::default());
 
         for (smr_id, mut config) in node_configs.into_iter().enumerate() {
             let (_, storage) = MockStorage::start_for_testing(validator_set.clone());
@@ -221,7 +230,13 @@ impl SMRNode {
 
             let twin_id = TwinId { id: smr_id, author };
 
-            smr_nodes.push(Self::start(playground, config, storage, twin_id));
+            smr_nodes.push(Self::start(
+                playground,
+                config,
+                storage,
+                twin_id,
+                execution_history.clone(),
+            ));
         }
         smr_nodes
     }
@@ -72,7 +72,7 @@ impl MempoolProxy {
                 Err(anyhow::anyhow!("[consensus] did not receive GetBlockResponse on time").into())
             }
             Ok(resp) => match resp.map_err(anyhow::Error::from)?? {
-                ConsensusResponse::GetBlockResponse(txns) => Ok(txns),
+                ConsensusResponse::GetBlockResponse(txns) => Ok(Payload::DirectMempool(txns)),
                 _ => Err(
                     anyhow::anyhow!("[consensus] did not receive expected GetBlockResponse").into(),
                 ),
@@ -93,7 +93,7 @@ impl TxnManager for MempoolProxy {
         });
         let mut exclude_txns = vec![];
         for payload in exclude_payloads {
-            for transaction in payload {
+            for transaction in payload.inline_transactions() {
                 exclude_txns.push(TransactionSummary {
                     sender: transaction.sender(),
                     sequence_number: transaction.sequence_number(),
@@ -126,9 +126,10 @@ impl TxnManager for MempoolProxy {
         compute_results: &StateComputeResult,
     ) -> Result<(), MempoolError> {
         let mut rejected_txns = vec![];
+        // The transactions of quorum store batches aren't tracked, they expire from mempool.
         let txns = match block.payload() {
-            Some(txns) => txns,
-            None => return Ok(()),
+            Some(Payload::DirectMempool(txns)) => txns,
+            _ => return Ok(()),
         };
         // skip the block metadata txn result
         for (txn, status) in txns
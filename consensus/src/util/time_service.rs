@@ -3,10 +3,11 @@
 
 use futures::{Future, FutureExt, SinkExt};
 use mango_logger::prelude::*;
-use std::{pin::Pin, thread, time::Duration};
+use mango_time_service::TimeServiceTrait;
+use std::{pin::Pin, time::Duration};
 
 use crate::counters;
-use tokio::{runtime::Handle, time::sleep};
+use tokio::runtime::Handle;
 
 /// Time service is an abstraction for operations that depend on time
 /// It supports implementations that can simulated time or depend on actual time
@@ -92,33 +93,44 @@ where
     }
 }
 
-/// TimeService implementation that uses actual clock to schedule tasks
+/// TimeService implementation that uses a clock to schedule tasks
 pub struct ClockTimeService {
     executor: Handle,
+    clock: mango_time_service::TimeService,
 }
 
 impl ClockTimeService {
     /// Creates new TimeService that runs tasks based on actual clock
     /// It needs executor to schedule internal tasks that facilitates it's work
     pub fn new(executor: Handle) -> ClockTimeService {
-        ClockTimeService { executor }
+        Self::new_with_clock(executor, mango_time_service::TimeService::real())
+    }
+
+    /// Creates new TimeService that runs tasks based on the given clock, which lets simulations
+    /// drive the round timeouts and block timestamps of consensus with a mock clock
+    pub fn new_with_clock(
+        executor: Handle,
+        clock: mango_time_service::TimeService,
+    ) -> ClockTimeService {
+        ClockTimeService { executor, clock }
     }
 }
 
 impl TimeService for ClockTimeService {
     fn run_after(&self, timeout: Duration, mut t: Box<dyn ScheduledTask>) {
+        let clock = self.clock.clone();
         let task = async move {
-            sleep(timeout).await;
+            clock.sleep(timeout).await;
             t.run().await;
         };
         self.executor.spawn(task);
     }
 
     fn get_current_timestamp(&self) -> Duration {
-        mango_infallible::duration_since_epoch()
+        self.clock.now_unix_time()
     }
 
     fn sleep(&self, t: Duration) {
-        thread::sleep(t)
+        self.clock.sleep_blocking(t)
     }
 }
@@ -5,7 +5,8 @@ use super::{
     request::{JsonRpcRequest, MethodRequest},
     response::{MethodResponse, Response},
     state::StateManager,
-    validate, validate_batch, BatchResponse, USER_AGENT,
+    validate, validate_batch, validate_bcs, validate_bcs_batch, BatchResponse, RawResponse,
+    USER_AGENT,
 };
 use crate::{
     error::WaitForTransactionError,
@@ -19,14 +20,18 @@ use crate::{
     Error, Result, Retry, State,
 };
 use mango_crypto::{hash::CryptoHash, HashValue};
+use mango_json_rpc_types::response::CONTENT_TYPE_BCS;
 use mango_types::{
     account_address::AccountAddress,
     event::EventKey,
     transaction::{SignedTransaction, Transaction},
 };
-use move_core_types::move_resource::{MoveResource, MoveStructType};
+use move_core_types::{
+    language_storage::TypeTag,
+    move_resource::{MoveResource, MoveStructType},
+};
 use serde::{de::DeserializeOwned, Serialize};
-use std::{mem, time::Duration};
+use std::{io::Read, mem, time::Duration};
 
 // In order to avoid needing to publish the proxy crate to crates.io we simply include the small
 // library in inline by making it a module instead of a dependency. 'src/proxy.rs' is a symlink to
@@ -129,9 +134,9 @@ impl BlockingClient {
     }
 
     pub fn request(&self, request: MethodRequest) -> Result<Response<MethodResponse>> {
-        let method = request.method();
-        let resp: Response<serde_json::Value> = self.send(request)?;
-        resp.and_then(|json| MethodResponse::from_json(method, json).map_err(Error::decode))
+        let request = JsonRpcRequest::new(request);
+        self.retry
+            .retry(|| self.send_method_without_retry(&request))
     }
 
     pub fn submit(&self, txn: &SignedTransaction) -> Result<Response<()>> {
@@ -200,6 +205,14 @@ impl BlockingClient {
         ))
     }
 
+    pub fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        include_events: bool,
+    ) -> Result<Response<Option<TransactionView>>> {
+        self.send(MethodRequest::get_transaction_by_hash(hash, include_events))
+    }
+
     pub fn get_events(
         &self,
         key: EventKey,
@@ -209,6 +222,23 @@ impl BlockingClient {
         self.send(MethodRequest::get_events(key, start_seq, limit))
     }
 
+    pub fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: u64,
+        start_index: u64,
+        end_version: u64,
+        limit: u64,
+    ) -> Result<Response<Vec<EventView>>> {
+        self.send(MethodRequest::get_events_by_type(
+            type_tag,
+            start_version,
+            start_index,
+            end_version,
+            limit,
+        ))
+    }
+
     pub fn get_currencies(&self) -> Result<Response<Vec<CurrencyInfoView>>> {
         self.send(MethodRequest::get_currencies())
     }
@@ -346,7 +376,8 @@ impl BlockingClient {
         ignore_stale: bool,
     ) -> Result<Response<T>> {
         let req_state = self.last_known_state();
-        let resp: mango_json_rpc_types::response::JsonRpcResponse = self.send_impl(&request)?;
+        let resp: mango_json_rpc_types::response::JsonRpcResponse =
+            self.send_impl(&request, false)?.json()?;
 
         let (id, state, result) = validate(&self.state, req_state.as_ref(), &resp, ignore_stale)?;
 
@@ -364,20 +395,62 @@ impl BlockingClient {
     ) -> Result<Vec<Result<Response<MethodResponse>>>> {
         let request: Vec<JsonRpcRequest> = requests.into_iter().map(JsonRpcRequest::new).collect();
         let req_state = self.last_known_state();
-        let resp: BatchResponse = self.send_impl(&request)?;
+        let accept_bcs = request
+            .iter()
+            .all(|request| request.method().supports_bcs_encoding());
+
+        match self.send_impl(&request, accept_bcs)? {
+            RawResponse::Json(resp) => {
+                let resp: BatchResponse = resp;
+                validate_batch(&self.state, req_state.as_ref(), &request, resp.success()?)
+            }
+            RawResponse::Bcs(resp_state, body) => {
+                validate_bcs_batch(&self.state, req_state.as_ref(), &request, resp_state, &body)
+            }
+        }
+    }
+
+    /// Sends a request whose result is decoded according to its method, as BCS if the method
+    /// supports it
+    fn send_method_without_retry(
+        &self,
+        request: &JsonRpcRequest,
+    ) -> Result<Response<MethodResponse>> {
+        let req_state = self.last_known_state();
+        let accept_bcs = request.method().supports_bcs_encoding();
 
-        let resp = resp.success()?;
+        match self.send_impl(&request, accept_bcs)? {
+            RawResponse::Json(resp) => {
+                let (id, state, result) = validate(&self.state, req_state.as_ref(), &resp, false)?;
 
-        validate_batch(&self.state, req_state.as_ref(), &request, resp)
+                if request.id() != id {
+                    return Err(Error::rpc_response("invalid response id"));
+                }
+
+                let inner =
+                    MethodResponse::from_json(request.method(), result).map_err(Error::decode)?;
+                Ok(Response::new(inner, state))
+            }
+            RawResponse::Bcs(resp_state, body) => {
+                validate_bcs(&self.state, req_state.as_ref(), request, resp_state, &body)
+            }
+        }
     }
 
     // Executes the specified request method using the given parameters by contacting the JSON RPC
     // server. If the 'http_proxy' or 'https_proxy' environment variable is set, enable the proxy.
-    fn send_impl<S: Serialize, T: DeserializeOwned>(&self, payload: &S) -> Result<T> {
+    fn send_impl<S: Serialize, T: DeserializeOwned>(
+        &self,
+        payload: &S,
+        accept_bcs: bool,
+    ) -> Result<RawResponse<T>> {
         let mut request = ureq::post(&self.url)
             .timeout_connect(REQUEST_TIMEOUT)
             .set("User-Agent", USER_AGENT)
             .build();
+        if accept_bcs {
+            request.set("Accept", CONTENT_TYPE_BCS);
+        }
 
         let proxy = proxy::Proxy::new();
         let host = request.get_host().expect("unable to get the host");
@@ -425,6 +498,17 @@ impl BlockingClient {
             return Err(Error::status(resp.status()));
         }
 
-        resp.into_json_deserialize().map_err(Error::decode)
+        if resp.header("Content-Type") == Some(CONTENT_TYPE_BCS) {
+            let state = State::from_headers(|name| resp.header(name).map(str::to_owned))?;
+            let mut body = Vec::new();
+            resp.into_reader()
+                .read_to_end(&mut body)
+                .map_err(Error::decode)?;
+            Ok(RawResponse::Bcs(state, body))
+        } else {
+            resp.into_json_deserialize()
+                .map(RawResponse::Json)
+                .map_err(Error::decode)
+        }
     }
 }
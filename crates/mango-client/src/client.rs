@@ -5,7 +5,8 @@ use super::{
     request::{JsonRpcRequest, MethodRequest},
     response::{MethodResponse, Response},
     state::StateManager,
-    validate, validate_batch, BatchResponse, USER_AGENT,
+    validate, validate_batch, validate_bcs, validate_bcs_batch, BatchResponse, RawResponse,
+    USER_AGENT,
 };
 use crate::{
     error::WaitForTransactionError,
@@ -19,12 +20,16 @@ use crate::{
     Error, Result, Retry, State,
 };
 use mango_crypto::{hash::CryptoHash, HashValue};
+use mango_json_rpc_types::response::CONTENT_TYPE_BCS;
 use mango_types::{
     account_address::AccountAddress,
     event::EventKey,
     transaction::{SignedTransaction, Transaction},
 };
-use move_core_types::move_resource::{MoveResource, MoveStructType};
+use move_core_types::{
+    language_storage::TypeTag,
+    move_resource::{MoveResource, MoveStructType},
+};
 use reqwest::Client as ReqwestClient;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{mem, time::Duration};
@@ -135,9 +140,11 @@ impl Client {
     }
 
     pub async fn request(&self, request: MethodRequest) -> Result<Response<MethodResponse>> {
-        let method = request.method();
-        let resp: Response<serde_json::Value> = self.send(request).await?;
-        resp.and_then(|json| MethodResponse::from_json(method, json).map_err(Error::decode))
+        let request = JsonRpcRequest::new(request);
+
+        self.retry
+            .retry_async(|| async { self.send_method_without_retry(&request).await })
+            .await
     }
 
     pub async fn submit(&self, txn: &SignedTransaction) -> Result<Response<()>> {
@@ -214,6 +221,15 @@ impl Client {
         .await
     }
 
+    pub async fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        include_events: bool,
+    ) -> Result<Response<Option<TransactionView>>> {
+        self.send(MethodRequest::get_transaction_by_hash(hash, include_events))
+            .await
+    }
+
     pub async fn get_events(
         &self,
         key: EventKey,
@@ -224,6 +240,24 @@ impl Client {
             .await
     }
 
+    pub async fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: u64,
+        start_index: u64,
+        end_version: u64,
+        limit: u64,
+    ) -> Result<Response<Vec<EventView>>> {
+        self.send(MethodRequest::get_events_by_type(
+            type_tag,
+            start_version,
+            start_index,
+            end_version,
+            limit,
+        ))
+        .await
+    }
+
     pub async fn get_currencies(&self) -> Result<Response<Vec<CurrencyInfoView>>> {
         self.send(MethodRequest::get_currencies()).await
     }
@@ -373,7 +407,7 @@ impl Client {
     ) -> Result<Response<T>> {
         let req_state = self.last_known_state();
         let resp: mango_json_rpc_types::response::JsonRpcResponse =
-            self.send_impl(&request).await?;
+            self.send_impl(&request, false).await?.json()?;
 
         let (id, state, result) = validate(&self.state, req_state.as_ref(), &resp, ignore_stale)?;
 
@@ -391,27 +425,84 @@ impl Client {
     ) -> Result<Vec<Result<Response<MethodResponse>>>> {
         let request: Vec<JsonRpcRequest> = requests.into_iter().map(JsonRpcRequest::new).collect();
         let req_state = self.last_known_state();
-        let resp: BatchResponse = self.send_impl(&request).await?;
+        let accept_bcs = request
+            .iter()
+            .all(|request| request.method().supports_bcs_encoding());
+
+        match self.send_impl(&request, accept_bcs).await? {
+            RawResponse::Json(resp) => {
+                let resp: BatchResponse = resp;
+                validate_batch(&self.state, req_state.as_ref(), &request, resp.success()?)
+            }
+            RawResponse::Bcs(resp_state, body) => {
+                validate_bcs_batch(&self.state, req_state.as_ref(), &request, resp_state, &body)
+            }
+        }
+    }
+
+    /// Sends a request whose result is decoded according to its method, as BCS if the method
+    /// supports it
+    async fn send_method_without_retry(
+        &self,
+        request: &JsonRpcRequest,
+    ) -> Result<Response<MethodResponse>> {
+        let req_state = self.last_known_state();
+        let accept_bcs = request.method().supports_bcs_encoding();
+
+        match self.send_impl(&request, accept_bcs).await? {
+            RawResponse::Json(resp) => {
+                let (id, state, result) = validate(&self.state, req_state.as_ref(), &resp, false)?;
 
-        let resp = resp.success()?;
+                if request.id() != id {
+                    return Err(Error::rpc_response("invalid response id"));
+                }
 
-        validate_batch(&self.state, req_state.as_ref(), &request, resp)
+                let inner =
+                    MethodResponse::from_json(request.method(), result).map_err(Error::decode)?;
+                Ok(Response::new(inner, state))
+            }
+            RawResponse::Bcs(resp_state, body) => {
+                validate_bcs(&self.state, req_state.as_ref(), request, resp_state, &body)
+            }
+        }
     }
 
-    async fn send_impl<S: Serialize, T: DeserializeOwned>(&self, payload: &S) -> Result<T> {
-        let response = self
+    async fn send_impl<S: Serialize, T: DeserializeOwned>(
+        &self,
+        payload: &S,
+        accept_bcs: bool,
+    ) -> Result<RawResponse<T>> {
+        let mut request = self
             .inner
             .post(&self.url)
             .json(payload)
-            .header(reqwest::header::USER_AGENT, USER_AGENT)
-            .send()
-            .await
-            .map_err(Error::from_reqwest_error)?;
+            .header(reqwest::header::USER_AGENT, USER_AGENT);
+        if accept_bcs {
+            request = request.header(reqwest::header::ACCEPT, CONTENT_TYPE_BCS);
+        }
+        let response = request.send().await.map_err(Error::from_reqwest_error)?;
 
         if response.status() != 200 {
             return Err(Error::status(response.status().as_u16()));
         }
 
-        response.json().await.map_err(Error::from_reqwest_error)
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        };
+        if header(reqwest::header::CONTENT_TYPE.as_str()).as_deref() == Some(CONTENT_TYPE_BCS) {
+            let state = State::from_headers(header)?;
+            let body = response.bytes().await.map_err(Error::from_reqwest_error)?;
+            Ok(RawResponse::Bcs(state, body.to_vec()))
+        } else {
+            response
+                .json()
+                .await
+                .map(RawResponse::Json)
+                .map_err(Error::from_reqwest_error)
+        }
     }
 }
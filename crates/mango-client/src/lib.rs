@@ -65,7 +65,7 @@ enum JsonRpcVersion {
     V2,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Method {
     Submit,
@@ -74,7 +74,9 @@ pub enum Method {
     GetTransactions,
     GetAccountTransaction,
     GetAccountTransactions,
+    GetTransactionByHash,
     GetEvents,
+    GetEventsByType,
     GetCurrencies,
     GetNetworkStatus,
 
@@ -90,6 +92,24 @@ pub enum Method {
     GetEventByVersionWithProof,
 }
 
+impl Method {
+    /// Whether the server can answer calls to the method with BCS encoded responses, when requests
+    /// accept `application/x-bcs`. These are the methods returning proofs, whose views are mostly
+    /// BCS bytes that JSON hex-encodes.
+    pub fn supports_bcs_encoding(&self) -> bool {
+        matches!(
+            self,
+            Method::GetStateProof
+                | Method::GetAccumulatorConsistencyProof
+                | Method::GetAccountStateWithProof
+                | Method::GetTransactionsWithProofs
+                | Method::GetAccountTransactionsWithProofs
+                | Method::GetEventsWithProofs
+                | Method::GetEventByVersionWithProof
+        )
+    }
+}
+
 cfg_async_or_blocking! {
     fn validate(
         state_manager: &state::StateManager,
@@ -156,6 +176,88 @@ cfg_async_or_blocking! {
         Ok(result)
     }
 
+    /// Validates the BCS encoded response to a single request, a
+    /// `Result<MethodResponse, JsonRpcError>`
+    fn validate_bcs(
+        state_manager: &state::StateManager,
+        req_state: Option<&State>,
+        request: &JsonRpcRequest,
+        resp_state: State,
+        body: &[u8],
+    ) -> Result<Response<MethodResponse>> {
+        let result: std::result::Result<MethodResponse, errors::JsonRpcError> =
+            bcs::from_bytes(body).map_err(Error::decode)?;
+        let result = result.map_err(Error::json_rpc)?;
+        if result.method() != request.method() {
+            return Err(Error::rpc_response(format!(
+                "expected a response to {:?}, found {:?}",
+                request.method(),
+                result.method()
+            )));
+        }
+
+        state_manager.update_state(false, req_state, &resp_state)?;
+
+        Ok(Response::new(result, resp_state))
+    }
+
+    /// Validates the BCS encoded response to a batch, a `Vec<Result<MethodResponse, JsonRpcError>>`
+    /// in the order of the requests
+    fn validate_bcs_batch(
+        state_manager: &state::StateManager,
+        req_state: Option<&State>,
+        requests: &[JsonRpcRequest],
+        resp_state: State,
+        body: &[u8],
+    ) -> Result<Vec<Result<Response<MethodResponse>>>> {
+        let results: Vec<std::result::Result<MethodResponse, errors::JsonRpcError>> =
+            bcs::from_bytes(body).map_err(Error::decode)?;
+        if results.len() != requests.len() {
+            return Err(Error::batch(format!(
+                "expected {} responses, found {}",
+                requests.len(),
+                results.len()
+            )));
+        }
+
+        let responses = requests
+            .iter()
+            .zip(results)
+            .map(|(request, result)| {
+                let result = result.map_err(Error::json_rpc)?;
+                if result.method() != request.method() {
+                    return Err(Error::batch(format!(
+                        "expected a response to {:?}, found {:?}",
+                        request.method(),
+                        result.method()
+                    )));
+                }
+
+                state_manager.update_state(false, req_state, &resp_state)?;
+
+                Ok(Response::new(result, resp_state.clone()))
+            })
+            .collect();
+
+        Ok(responses)
+    }
+
+    /// Response body, decoded from JSON unless the server answered with BCS
+    enum RawResponse<T> {
+        Json(T),
+        Bcs(State, Vec<u8>),
+    }
+
+    impl<T> RawResponse<T> {
+        /// The JSON response to a request that doesn't accept BCS
+        fn json(self) -> Result<T> {
+            match self {
+                RawResponse::Json(inner) => Ok(inner),
+                RawResponse::Bcs(..) => Err(Error::rpc_response("unexpected BCS response")),
+            }
+        }
+    }
+
     fn get_id(resp: &mango_json_rpc_types::response::JsonRpcResponse) -> Result<u64> {
         let id = if let Some(id) = &resp.id {
             if let Ok(index) = serde_json::from_value::<u64>(id.clone()) {
@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{JsonRpcVersion, Method};
+use mango_crypto::HashValue;
 use mango_types::{
     account_address::AccountAddress, event::EventKey, transaction::SignedTransaction,
 };
+use move_core_types::language_storage::TypeTag;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::AtomicU64;
 
@@ -18,7 +20,9 @@ pub enum MethodRequest {
     GetTransactions(u64, u64, bool),
     GetAccountTransaction(AccountAddress, u64, bool),
     GetAccountTransactions(AccountAddress, u64, u64, bool),
+    GetTransactionByHash(HashValue, bool),
     GetEvents(EventKey, u64, u64),
+    GetEventsByType(String, u64, u64, u64, u64),
     GetCurrencies([(); 0]),
     GetNetworkStatus([(); 0]),
 
@@ -77,10 +81,30 @@ impl MethodRequest {
         Self::GetAccountTransactions(address, start_seq, limit, include_events)
     }
 
+    pub fn get_transaction_by_hash(hash: HashValue, include_events: bool) -> Self {
+        Self::GetTransactionByHash(hash, include_events)
+    }
+
     pub fn get_events(key: EventKey, start_seq: u64, limit: u64) -> Self {
         Self::GetEvents(key, start_seq, limit)
     }
 
+    pub fn get_events_by_type(
+        type_tag: &TypeTag,
+        start_version: u64,
+        start_index: u64,
+        end_version: u64,
+        limit: u64,
+    ) -> Self {
+        Self::GetEventsByType(
+            type_tag.to_string(),
+            start_version,
+            end_version,
+            limit,
+            start_index,
+        )
+    }
+
     pub fn get_currencies() -> Self {
         Self::GetCurrencies([])
     }
@@ -152,7 +176,9 @@ impl MethodRequest {
             MethodRequest::GetTransactions(_, _, _) => Method::GetTransactions,
             MethodRequest::GetAccountTransaction(_, _, _) => Method::GetAccountTransaction,
             MethodRequest::GetAccountTransactions(_, _, _, _) => Method::GetAccountTransactions,
+            MethodRequest::GetTransactionByHash(_, _) => Method::GetTransactionByHash,
             MethodRequest::GetEvents(_, _, _) => Method::GetEvents,
+            MethodRequest::GetEventsByType(_, _, _, _, _) => Method::GetEventsByType,
             MethodRequest::GetCurrencies(_) => Method::GetCurrencies,
             MethodRequest::GetNetworkStatus(_) => Method::GetNetworkStatus,
             MethodRequest::GetStateProof(_) => Method::GetStateProof,
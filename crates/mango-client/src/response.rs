@@ -11,6 +11,7 @@ use crate::{
     },
     Error, State,
 };
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 #[derive(Debug)]
@@ -53,8 +54,10 @@ impl<T> Response<T> {
     }
 }
 
+/// Result of a method call. Its serde encoding is only used for BCS encoded responses, JSON
+/// responses are decoded with `MethodResponse::from_json`.
 #[allow(clippy::large_enum_variant)]
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
 pub enum MethodResponse {
     Submit,
     GetMetadata(MetadataView),
@@ -62,7 +65,9 @@ pub enum MethodResponse {
     GetTransactions(Vec<TransactionView>),
     GetAccountTransaction(Option<TransactionView>),
     GetAccountTransactions(Vec<TransactionView>),
+    GetTransactionByHash(Option<TransactionView>),
     GetEvents(Vec<EventView>),
+    GetEventsByType(Vec<EventView>),
     GetCurrencies(Vec<CurrencyInfoView>),
     GetNetworkStatus(u64),
 
@@ -93,7 +98,13 @@ impl MethodResponse {
             Method::GetAccountTransactions => {
                 MethodResponse::GetAccountTransactions(serde_json::from_value(json)?)
             }
+            Method::GetTransactionByHash => {
+                MethodResponse::GetTransactionByHash(serde_json::from_value(json)?)
+            }
             Method::GetEvents => MethodResponse::GetEvents(serde_json::from_value(json)?),
+            Method::GetEventsByType => {
+                MethodResponse::GetEventsByType(serde_json::from_value(json)?)
+            }
             Method::GetCurrencies => MethodResponse::GetCurrencies(serde_json::from_value(json)?),
             Method::GetNetworkStatus => {
                 MethodResponse::GetNetworkStatus(serde_json::from_value(json)?)
@@ -130,7 +141,9 @@ impl MethodResponse {
             MethodResponse::GetTransactions(_) => Method::GetTransactions,
             MethodResponse::GetAccountTransaction(_) => Method::GetAccountTransaction,
             MethodResponse::GetAccountTransactions(_) => Method::GetAccountTransactions,
+            MethodResponse::GetTransactionByHash(_) => Method::GetTransactionByHash,
             MethodResponse::GetEvents(_) => Method::GetEvents,
+            MethodResponse::GetEventsByType(_) => Method::GetEventsByType,
             MethodResponse::GetCurrencies(_) => Method::GetCurrencies,
             MethodResponse::GetNetworkStatus(_) => Method::GetNetworkStatus,
             MethodResponse::GetStateProof(_) => Method::GetStateProof,
@@ -230,6 +243,16 @@ impl MethodResponse {
         }
     }
 
+    pub fn try_into_get_transaction_by_hash(self) -> Result<Option<TransactionView>, Error> {
+        match self {
+            MethodResponse::GetTransactionByHash(tx) => Ok(tx),
+            _ => Err(Error::rpc_response(format!(
+                "expected MethodResponse::GetTransactionByHash found MethodResponse::{:?}",
+                self.method()
+            ))),
+        }
+    }
+
     pub fn try_into_get_account_transactions(self) -> Result<Vec<TransactionView>, Error> {
         match self {
             MethodResponse::GetAccountTransactions(txs) => Ok(txs),
@@ -260,6 +283,16 @@ impl MethodResponse {
         }
     }
 
+    pub fn try_into_get_events_by_type(self) -> Result<Vec<EventView>, Error> {
+        match self {
+            MethodResponse::GetEventsByType(events) => Ok(events),
+            _ => Err(Error::rpc_response(format!(
+                "expected MethodResponse::GetEventsByType found MethodResponse::{:?}",
+                self.method()
+            ))),
+        }
+    }
+
     pub fn try_into_get_event_by_version_with_proof(
         self,
     ) -> Result<EventByVersionWithProofView, Error> {
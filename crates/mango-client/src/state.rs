@@ -23,6 +23,31 @@ impl State {
 
 cfg_async_or_blocking! {
     use crate::{Error, Result};
+    use mango_json_rpc_types::response::{
+        X_DIEM_CHAIN_ID, X_DIEM_TIMESTAMP_USEC_ID, X_DIEM_VERSION_ID,
+    };
+
+    impl State {
+        /// Reads the state from the ledger info headers, which BCS encoded responses carry it in
+        pub(crate) fn from_headers<F>(header: F) -> Result<Self>
+        where
+            F: Fn(&str) -> Option<String>,
+        {
+            fn parse<T: std::str::FromStr>(value: Option<String>, name: &str) -> Result<T> {
+                value
+                    .and_then(|value| value.parse().ok())
+                    .ok_or_else(|| {
+                        Error::rpc_response(format!("missing or invalid {} header", name))
+                    })
+            }
+
+            Ok(Self {
+                chain_id: parse(header(X_DIEM_CHAIN_ID), X_DIEM_CHAIN_ID)?,
+                version: parse(header(X_DIEM_VERSION_ID), X_DIEM_VERSION_ID)?,
+                timestamp_usecs: parse(header(X_DIEM_TIMESTAMP_USEC_ID), X_DIEM_TIMESTAMP_USEC_ID)?,
+            })
+        }
+    }
 
     #[derive(Debug)]
     pub(crate) struct StateManager {
@@ -0,0 +1,529 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides an API for the BLS signature scheme over the BLS12-381 pairing-friendly
+//! curve, following the [IETF draft](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-bls-signature-04)
+//! in the "minimal-pubkey-size" variant: public keys live in G1 (48 bytes compressed) and
+//! signatures live in G2 (96 bytes compressed).
+//!
+//! Signatures on the same message can be aggregated into a single signature that verifies
+//! against the aggregate of the signers' public keys. To be safe against rogue-key attacks,
+//! aggregation must only be used with public keys whose [`Bls12381ProofOfPossession`] has been
+//! verified.
+//!
+//! # Examples
+//!
+//! ```
+//! use mango_crypto_derive::{CryptoHasher, BCSCryptoHash};
+//! use mango_crypto::{
+//!     bls12381::*,
+//!     traits::{Signature, SigningKey, Uniform},
+//! };
+//! use rand::{rngs::StdRng, SeedableRng};
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+//! pub struct TestCryptoDocTest(String);
+//! let message = TestCryptoDocTest("Test message".to_string());
+//!
+//! let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+//! let private_key_a = Bls12381PrivateKey::generate(&mut rng);
+//! let private_key_b = Bls12381PrivateKey::generate(&mut rng);
+//! let public_key_a: Bls12381PublicKey = (&private_key_a).into();
+//! let public_key_b: Bls12381PublicKey = (&private_key_b).into();
+//!
+//! let pop = Bls12381ProofOfPossession::create(&private_key_a);
+//! assert!(pop.verify(&public_key_a).is_ok());
+//!
+//! let signature = Bls12381Signature::aggregate(vec![
+//!     private_key_a.sign(&message),
+//!     private_key_b.sign(&message),
+//! ])
+//! .unwrap();
+//! let public_key = Bls12381PublicKey::aggregate(vec![&public_key_a, &public_key_b]).unwrap();
+//! assert!(signature.verify(&message, &public_key).is_ok());
+//! ```
+//! **Note**: The above example generates a private key using a private function intended only for
+//! testing purposes. Production code should find an alternate means for secure key generation.
+
+use crate::{
+    hash::{CryptoHash, CryptoHasher},
+    traits::*,
+};
+use anyhow::{anyhow, Result};
+use core::convert::TryFrom;
+use mango_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use serde::Serialize;
+use std::fmt;
+
+pub use blst;
+
+/// The length of the Bls12381PrivateKey
+pub const BLS12381_PRIVATE_KEY_LENGTH: usize = 32;
+/// The length of the Bls12381PublicKey
+pub const BLS12381_PUBLIC_KEY_LENGTH: usize = 48;
+/// The length of the Bls12381Signature
+pub const BLS12381_SIGNATURE_LENGTH: usize = 96;
+
+/// Domain separation tag for signatures, as defined by the proof-of-possession ciphersuite.
+const DST_BLS_SIG_IN_G2_WITH_POP: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// Domain separation tag for proofs of possession, so that a proof of possession can never be
+/// mistaken for a signature on a message.
+const DST_BLS_POP_IN_G2: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// A BLS12-381 private key
+#[derive(DeserializeKey, SerializeKey, SilentDebug, SilentDisplay)]
+pub struct Bls12381PrivateKey(blst::min_pk::SecretKey);
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(Bls12381PrivateKey: Clone);
+
+#[cfg(any(test, feature = "cloneable-private-keys"))]
+impl Clone for Bls12381PrivateKey {
+    fn clone(&self) -> Self {
+        Bls12381PrivateKey(self.0.clone())
+    }
+}
+
+/// A BLS12-381 public key. Deserialization checks that the key is a non-identity point of the
+/// prime-order subgroup of G1.
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Bls12381PublicKey(blst::min_pk::PublicKey);
+
+/// A BLS12-381 signature, either from a single signer or an aggregate of several signers.
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Bls12381Signature(blst::min_pk::Signature);
+
+/// A proof that the holder of a `Bls12381PublicKey` knows the corresponding private key. It is
+/// a signature on the public key itself, under a dedicated domain separation tag.
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Bls12381ProofOfPossession(blst::min_pk::Signature);
+
+impl Bls12381PrivateKey {
+    /// The length of the Bls12381PrivateKey
+    pub const LENGTH: usize = BLS12381_PRIVATE_KEY_LENGTH;
+
+    /// Serialize a Bls12381PrivateKey.
+    pub fn to_bytes(&self) -> [u8; BLS12381_PRIVATE_KEY_LENGTH] {
+        self.0.to_bytes()
+    }
+
+    /// Private function aimed at minimizing code duplication between sign
+    /// methods of the SigningKey implementation. This should remain private.
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Bls12381Signature {
+        Bls12381Signature(self.0.sign(message, DST_BLS_SIG_IN_G2_WITH_POP, &[]))
+    }
+}
+
+impl Bls12381PublicKey {
+    /// The length of the Bls12381PublicKey
+    pub const LENGTH: usize = BLS12381_PUBLIC_KEY_LENGTH;
+
+    /// Serialize a Bls12381PublicKey.
+    pub fn to_bytes(&self) -> [u8; BLS12381_PUBLIC_KEY_LENGTH] {
+        self.0.compress()
+    }
+
+    /// Aggregates the given public keys into a single public key, against which an aggregate
+    /// signature of the same message by all of their owners verifies. Every key must have had
+    /// its proof of possession verified beforehand.
+    pub fn aggregate(public_keys: Vec<&Self>) -> Result<Self> {
+        let public_keys: Vec<_> = public_keys.iter().map(|pk| &pk.0).collect();
+        let aggregate = blst::min_pk::AggregatePublicKey::aggregate(&public_keys, false)
+            .map_err(|e| anyhow!("Failed to aggregate public keys: {:?}", e))?;
+        Ok(Bls12381PublicKey(aggregate.to_public_key()))
+    }
+}
+
+impl Bls12381Signature {
+    /// The length of the Bls12381Signature
+    pub const LENGTH: usize = BLS12381_SIGNATURE_LENGTH;
+
+    /// Serialize a Bls12381Signature.
+    pub fn to_bytes(&self) -> [u8; BLS12381_SIGNATURE_LENGTH] {
+        self.0.compress()
+    }
+
+    /// Aggregates signatures on the same message into a single signature. Every signature is
+    /// checked to be in the prime-order subgroup of G2.
+    pub fn aggregate(signatures: Vec<Self>) -> Result<Self> {
+        let signatures: Vec<_> = signatures.iter().map(|sig| &sig.0).collect();
+        let aggregate = blst::min_pk::AggregateSignature::aggregate(&signatures, true)
+            .map_err(|e| anyhow!("Failed to aggregate signatures: {:?}", e))?;
+        Ok(Bls12381Signature(aggregate.to_signature()))
+    }
+
+    /// return a signature on an empty message (for test only)
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn dummy_signature() -> Self {
+        Bls12381PrivateKey::generate_for_testing().sign_arbitrary_message(&[])
+    }
+}
+
+impl Bls12381ProofOfPossession {
+    /// Creates a proof of possession of `private_key`.
+    pub fn create(private_key: &Bls12381PrivateKey) -> Self {
+        let public_key: Bls12381PublicKey = private_key.into();
+        Bls12381ProofOfPossession(private_key.0.sign(
+            &public_key.to_bytes(),
+            DST_BLS_POP_IN_G2,
+            &[],
+        ))
+    }
+
+    /// Verifies that this is a valid proof of possession of the private key of `public_key`.
+    pub fn verify(&self, public_key: &Bls12381PublicKey) -> Result<()> {
+        let result = self.0.verify(
+            true,
+            &public_key.to_bytes(),
+            DST_BLS_POP_IN_G2,
+            &[],
+            &public_key.0,
+            false,
+        );
+        if result == blst::BLST_ERROR::BLST_SUCCESS {
+            Ok(())
+        } else {
+            Err(anyhow!("Invalid proof of possession: {:?}", result))
+        }
+    }
+
+    /// Serialize a Bls12381ProofOfPossession.
+    pub fn to_bytes(&self) -> [u8; BLS12381_SIGNATURE_LENGTH] {
+        self.0.compress()
+    }
+}
+
+///////////////////////
+// PrivateKey Traits //
+///////////////////////
+
+impl PrivateKey for Bls12381PrivateKey {
+    type PublicKeyMaterial = Bls12381PublicKey;
+}
+
+impl SigningKey for Bls12381PrivateKey {
+    type VerifyingKeyMaterial = Bls12381PublicKey;
+    type SignatureMaterial = Bls12381Signature;
+
+    fn sign<T: CryptoHash + Serialize>(&self, message: &T) -> Bls12381Signature {
+        let mut bytes = <T::Hasher as CryptoHasher>::seed().to_vec();
+        bcs::serialize_into(&mut bytes, &message)
+            .map_err(|_| CryptoMaterialError::SerializationError)
+            .expect("Serialization of signable material should not fail.");
+        Bls12381PrivateKey::sign_arbitrary_message(self, bytes.as_ref())
+    }
+
+    #[cfg(any(test, feature = "fuzzing"))]
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Bls12381Signature {
+        Bls12381PrivateKey::sign_arbitrary_message(self, message)
+    }
+}
+
+impl Uniform for Bls12381PrivateKey {
+    fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand::RngCore + ::rand::CryptoRng,
+    {
+        // The key generation procedure requires at least 32 bytes of input keying material.
+        let mut ikm = [0u8; 32];
+        rng.fill_bytes(&mut ikm);
+        Bls12381PrivateKey(
+            blst::min_pk::SecretKey::key_gen(&ikm, &[])
+                .expect("32 bytes of input keying material should be enough"),
+        )
+    }
+}
+
+impl PartialEq<Self> for Bls12381PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Bls12381PrivateKey {}
+
+impl TryFrom<&[u8]> for Bls12381PrivateKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Bls12381PrivateKey. This method will also check that the key is a non-zero
+    /// scalar smaller than the group order.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Bls12381PrivateKey, CryptoMaterialError> {
+        if bytes.len() != BLS12381_PRIVATE_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        blst::min_pk::SecretKey::from_bytes(bytes)
+            .map(Bls12381PrivateKey)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl Length for Bls12381PrivateKey {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Bls12381PrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+//////////////////////
+// PublicKey Traits //
+//////////////////////
+
+impl From<&Bls12381PrivateKey> for Bls12381PublicKey {
+    fn from(private_key: &Bls12381PrivateKey) -> Self {
+        Bls12381PublicKey(private_key.0.sk_to_pk())
+    }
+}
+
+impl PublicKey for Bls12381PublicKey {
+    type PrivateKeyMaterial = Bls12381PrivateKey;
+}
+
+impl std::hash::Hash for Bls12381PublicKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_pubkey = self.to_bytes();
+        state.write(&encoded_pubkey);
+    }
+}
+
+impl PartialEq for Bls12381PublicKey {
+    fn eq(&self, other: &Bls12381PublicKey) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Bls12381PublicKey {}
+
+impl VerifyingKey for Bls12381PublicKey {
+    type SigningKeyMaterial = Bls12381PrivateKey;
+    type SignatureMaterial = Bls12381Signature;
+}
+
+impl fmt::Display for Bls12381PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Bls12381PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bls12381PublicKey({})", self)
+    }
+}
+
+impl TryFrom<&[u8]> for Bls12381PublicKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Bls12381PublicKey. This method will also check for key validity: the key
+    /// must be a point of the prime-order subgroup and must not be the identity.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Bls12381PublicKey, CryptoMaterialError> {
+        if bytes.len() != BLS12381_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        match blst::min_pk::PublicKey::key_validate(bytes) {
+            Ok(public_key) => Ok(Bls12381PublicKey(public_key)),
+            Err(blst::BLST_ERROR::BLST_POINT_NOT_IN_GROUP) => {
+                Err(CryptoMaterialError::SmallSubgroupError)
+            }
+            Err(_) => Err(CryptoMaterialError::DeserializationError),
+        }
+    }
+}
+
+impl Length for Bls12381PublicKey {
+    fn length(&self) -> usize {
+        BLS12381_PUBLIC_KEY_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Bls12381PublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for Bls12381Signature {
+    type VerifyingKeyMaterial = Bls12381PublicKey;
+    type SigningKeyMaterial = Bls12381PrivateKey;
+
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Bls12381PublicKey,
+    ) -> Result<()> {
+        let mut bytes = <T::Hasher as CryptoHasher>::seed().to_vec();
+        bcs::serialize_into(&mut bytes, &message)
+            .map_err(|_| CryptoMaterialError::SerializationError)?;
+        Self::verify_arbitrary_msg(self, &bytes, public_key)
+    }
+
+    /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`. The
+    /// signature is checked to be in the prime-order subgroup; the public key was already
+    /// validated on deserialization.
+    fn verify_arbitrary_msg(&self, message: &[u8], public_key: &Bls12381PublicKey) -> Result<()> {
+        let result = self.0.verify(
+            true,
+            message,
+            DST_BLS_SIG_IN_G2_WITH_POP,
+            &[],
+            &public_key.0,
+            false,
+        );
+        if result == blst::BLST_ERROR::BLST_SUCCESS {
+            Ok(())
+        } else {
+            Err(anyhow!("Invalid BLS12-381 signature: {:?}", result))
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+
+    /// Since all signatures are on the same message, they are verified at once by checking their
+    /// aggregate against the aggregate public key. This requires every key to have had its proof
+    /// of possession verified.
+    fn batch_verify<T: CryptoHash + Serialize>(
+        message: &T,
+        keys_and_signatures: Vec<(Self::VerifyingKeyMaterial, Self)>,
+    ) -> Result<()> {
+        let (public_keys, signatures): (Vec<_>, Vec<_>) = keys_and_signatures.into_iter().unzip();
+        let public_key = Bls12381PublicKey::aggregate(public_keys.iter().collect())?;
+        Bls12381Signature::aggregate(signatures)?.verify(message, &public_key)
+    }
+}
+
+impl Length for Bls12381Signature {
+    fn length(&self) -> usize {
+        BLS12381_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Bls12381Signature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl std::hash::Hash for Bls12381Signature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_signature = self.to_bytes();
+        state.write(&encoded_signature);
+    }
+}
+
+impl TryFrom<&[u8]> for Bls12381Signature {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Bls12381Signature. Subgroup membership is checked on verification and
+    /// aggregation rather than here, as it is comparatively expensive.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Bls12381Signature, CryptoMaterialError> {
+        if bytes.len() != BLS12381_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        blst::min_pk::Signature::from_bytes(bytes)
+            .map(Bls12381Signature)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl PartialEq for Bls12381Signature {
+    fn eq(&self, other: &Bls12381Signature) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Bls12381Signature {}
+
+impl fmt::Display for Bls12381Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Bls12381Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bls12381Signature({})", self)
+    }
+}
+
+///////////////////////////////
+// ProofOfPossession Traits //
+///////////////////////////////
+
+impl Length for Bls12381ProofOfPossession {
+    fn length(&self) -> usize {
+        BLS12381_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Bls12381ProofOfPossession {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl TryFrom<&[u8]> for Bls12381ProofOfPossession {
+    type Error = CryptoMaterialError;
+
+    fn try_from(
+        bytes: &[u8],
+    ) -> std::result::Result<Bls12381ProofOfPossession, CryptoMaterialError> {
+        if bytes.len() != BLS12381_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        blst::min_pk::Signature::from_bytes(bytes)
+            .map(Bls12381ProofOfPossession)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl PartialEq for Bls12381ProofOfPossession {
+    fn eq(&self, other: &Bls12381ProofOfPossession) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Bls12381ProofOfPossession {}
+
+impl fmt::Debug for Bls12381ProofOfPossession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Bls12381ProofOfPossession({})",
+            hex::encode(&self.to_bytes()[..])
+        )
+    }
+}
+
+#[cfg(any(test, feature = "fuzzing"))]
+use crate::test_utils::{self, KeyPair};
+#[cfg(any(test, feature = "fuzzing"))]
+use proptest::prelude::*;
+
+/// Produces a uniformly random BLS12-381 keypair from a seed
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn keypair_strategy() -> impl Strategy<Value = KeyPair<Bls12381PrivateKey, Bls12381PublicKey>> {
+    test_utils::uniform_keypair_strategy::<Bls12381PrivateKey, Bls12381PublicKey>()
+}
+
+#[cfg(any(test, feature = "fuzzing"))]
+impl proptest::arbitrary::Arbitrary for Bls12381PublicKey {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        crate::test_utils::uniform_keypair_strategy::<Bls12381PrivateKey, Bls12381PublicKey>()
+            .prop_map(|v| v.public_key)
+            .boxed()
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bls12381::{
+        Bls12381PrivateKey, Bls12381ProofOfPossession, Bls12381PublicKey, Bls12381Signature,
+        BLS12381_PRIVATE_KEY_LENGTH, BLS12381_PUBLIC_KEY_LENGTH, BLS12381_SIGNATURE_LENGTH,
+    },
+    test_utils::{TestDiemCrypto, TEST_SEED},
+    traits::*,
+};
+
+use core::convert::TryFrom;
+use once_cell::sync::Lazy;
+use rand::{rngs::StdRng, SeedableRng};
+
+static MESSAGE: Lazy<TestDiemCrypto> = Lazy::new(|| TestDiemCrypto("Test Message".to_string()));
+fn message() -> &'static TestDiemCrypto {
+    &MESSAGE
+}
+
+// Helper function to generate N bls12381 private keys.
+fn generate_keys(n: usize) -> Vec<Bls12381PrivateKey> {
+    let mut rng = StdRng::from_seed(TEST_SEED);
+    (0..n)
+        .map(|_| Bls12381PrivateKey::generate(&mut rng))
+        .collect()
+}
+
+#[test]
+fn test_bls12381_sign_and_verify() {
+    let private_key = &generate_keys(1)[0];
+    let public_key = private_key.public_key();
+    let signature = private_key.sign(message());
+    assert!(signature.verify(message(), &public_key).is_ok());
+
+    let other_message = TestDiemCrypto("Other Message".to_string());
+    assert!(signature.verify(&other_message, &public_key).is_err());
+
+    let other_public_key = generate_keys(2)[1].public_key();
+    assert!(signature.verify(message(), &other_public_key).is_err());
+}
+
+#[test]
+fn test_bls12381_serialization() {
+    let private_key = &generate_keys(1)[0];
+    let public_key = private_key.public_key();
+    let signature = private_key.sign(message());
+
+    let serialized = private_key.to_bytes();
+    assert_eq!(serialized.len(), BLS12381_PRIVATE_KEY_LENGTH);
+    assert_eq!(
+        &Bls12381PrivateKey::try_from(&serialized[..]).unwrap(),
+        private_key
+    );
+
+    let serialized = public_key.to_bytes();
+    assert_eq!(serialized.len(), BLS12381_PUBLIC_KEY_LENGTH);
+    assert_eq!(
+        Bls12381PublicKey::try_from(&serialized[..]).unwrap(),
+        public_key
+    );
+
+    let serialized = signature.to_bytes();
+    assert_eq!(serialized.len(), BLS12381_SIGNATURE_LENGTH);
+    assert_eq!(
+        Bls12381Signature::try_from(&serialized[..]).unwrap(),
+        signature
+    );
+
+    let bcs_bytes = bcs::to_bytes(&public_key).unwrap();
+    assert_eq!(
+        bcs::from_bytes::<Bls12381PublicKey>(&bcs_bytes).unwrap(),
+        public_key
+    );
+    let bcs_bytes = bcs::to_bytes(&signature).unwrap();
+    assert_eq!(
+        bcs::from_bytes::<Bls12381Signature>(&bcs_bytes).unwrap(),
+        signature
+    );
+}
+
+#[test]
+fn test_bls12381_invalid_public_key() {
+    // Wrong length.
+    assert_eq!(
+        Bls12381PublicKey::try_from(&[0u8; BLS12381_PUBLIC_KEY_LENGTH - 1][..]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+    // The compressed identity point is not an acceptable public key.
+    let mut identity = [0u8; BLS12381_PUBLIC_KEY_LENGTH];
+    identity[0] = 0xc0;
+    assert!(Bls12381PublicKey::try_from(&identity[..]).is_err());
+}
+
+#[test]
+fn test_bls12381_aggregation() {
+    let private_keys = generate_keys(10);
+    let public_keys: Vec<_> = private_keys.iter().map(|k| k.public_key()).collect();
+    let signatures: Vec<_> = private_keys.iter().map(|k| k.sign(message())).collect();
+
+    let aggregate_signature = Bls12381Signature::aggregate(signatures.clone()).unwrap();
+    let aggregate_public_key = Bls12381PublicKey::aggregate(public_keys.iter().collect()).unwrap();
+    assert!(aggregate_signature
+        .verify(message(), &aggregate_public_key)
+        .is_ok());
+
+    // The aggregate does not verify against a strict subset of the signers.
+    let partial_public_key =
+        Bls12381PublicKey::aggregate(public_keys[1..].iter().collect()).unwrap();
+    assert!(aggregate_signature
+        .verify(message(), &partial_public_key)
+        .is_err());
+
+    // Batch verification goes through aggregation as well.
+    let keys_and_signatures: Vec<_> = public_keys.into_iter().zip(signatures).collect();
+    assert!(Bls12381Signature::batch_verify(message(), keys_and_signatures.clone()).is_ok());
+
+    let mut tampered = keys_and_signatures;
+    tampered[0].1 = private_keys[0].sign(&TestDiemCrypto("Other Message".to_string()));
+    assert!(Bls12381Signature::batch_verify(message(), tampered).is_err());
+}
+
+#[test]
+fn test_bls12381_proof_of_possession() {
+    let private_keys = generate_keys(2);
+    let public_key = private_keys[0].public_key();
+    let pop = Bls12381ProofOfPossession::create(&private_keys[0]);
+    assert!(pop.verify(&public_key).is_ok());
+
+    // A proof of possession does not carry over to another key.
+    assert!(pop.verify(&private_keys[1].public_key()).is_err());
+
+    // A signature on the public key bytes is not a proof of possession, thanks to the
+    // separate domain separation tags.
+    let signature = private_keys[0].sign_arbitrary_message(&public_key.to_bytes());
+    let forged = Bls12381ProofOfPossession::try_from(&signature.to_bytes()[..]).unwrap();
+    assert!(forged.verify(&public_key).is_err());
+
+    let serialized = pop.to_bytes();
+    assert_eq!(
+        Bls12381ProofOfPossession::try_from(&serialized[..]).unwrap(),
+        pop
+    );
+}
@@ -9,9 +9,11 @@ use mango_client::{
     },
     Client, MethodRequest, MethodResponse, Response, Result,
 };
+use mango_crypto::HashValue;
 use mango_types::{
     account_address::AccountAddress, event::EventKey, transaction::SignedTransaction,
 };
+use move_core_types::language_storage::TypeTag;
 use rand::seq::{SliceChooseIter, SliceRandom};
 use std::cmp::Reverse;
 
@@ -119,6 +121,18 @@ impl BroadcastingClient {
         collect_results(results)
     }
 
+    pub async fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        include_events: bool,
+    ) -> Result<Response<Option<TransactionView>>> {
+        let futures = self
+            .random_clients()
+            .map(|client| client.get_transaction_by_hash(hash, include_events));
+        let results = join_all(futures).await;
+        collect_results(results)
+    }
+
     pub async fn get_account_transactions(
         &self,
         address: AccountAddress,
@@ -146,6 +160,21 @@ impl BroadcastingClient {
         collect_results(results)
     }
 
+    pub async fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: u64,
+        start_index: u64,
+        end_version: u64,
+        limit: u64,
+    ) -> Result<Response<Vec<EventView>>> {
+        let futures = self.random_clients().map(|client| {
+            client.get_events_by_type(type_tag, start_version, start_index, end_version, limit)
+        });
+        let results = join_all(futures).await;
+        collect_results(results)
+    }
+
     pub async fn get_currencies(&self) -> Result<Response<Vec<CurrencyInfoView>>> {
         let futures = self.random_clients().map(|client| client.get_currencies());
         let results = join_all(futures).await;
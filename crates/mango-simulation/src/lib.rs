@@ -0,0 +1,345 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! A harness running full validator stacks (consensus, mempool, state sync, executor and storage)
+//! in one process, connected over memsocket through a simulated network.
+//!
+//! Every validator runs its networking stack, mempool, state sync and consensus on a
+//! current-thread runtime with a paused clock, and the simulated network runs on one more. The
+//! simulation drives all of them from its own thread, one after the other, a tick at a time: no
+//! wall-clock time is involved. The networking stacks and consensus (round timeouts and block
+//! timestamps) as well as the simulated network follow a `MockTimeService` advanced by the same
+//! ticks, and faults (link delays, drops, partitions, validator restarts) are scripted against
+//! that clock. Genesis, the validator keys and every fault decision are derived from the
+//! simulation seed, so a scenario replays the same way from the same seed.
+
+mod network;
+
+pub use crate::network::LinkConditions;
+
+use crate::network::SimulatedNetwork;
+use anyhow::{ensure, Result};
+use diem_genesis_tool::validator_builder::ValidatorBuilder;
+use mango_config::config::{
+    DiscoveryMethod, ExecutionCorrectnessService, NodeConfig, Peer, PeerRole, SafetyRulesService,
+};
+use mango_node::{setup_environment_on, DiemHandle};
+use mango_temppath::TempPath;
+use mango_time_service::{MockTimeService, TimeService};
+use mango_types::ledger_info::LedgerInfoWithSignatures;
+use rand::{rngs::StdRng, SeedableRng};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
+use tokio::runtime::{Builder, Runtime};
+
+/// A fault injected in the simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fault {
+    /// Sets the conditions of the link between two validators.
+    Link(usize, usize, LinkConditions),
+    /// Cuts the links between validators of different groups. Validators that are not part of
+    /// any group are isolated.
+    Partition(Vec<Vec<usize>>),
+    /// Restores all the links cut by partitions, other link conditions are kept.
+    Heal,
+    /// Stops a validator.
+    Stop(usize),
+    /// Starts a stopped validator again, from its persisted state.
+    Start(usize),
+    /// Stops and starts a validator again.
+    Restart(usize),
+}
+
+/// Builds a `Simulation` of a network of validators.
+pub struct SimulationBuilder {
+    num_validators: NonZeroUsize,
+    seed: u64,
+    tick: Duration,
+    template: NodeConfig,
+}
+
+impl SimulationBuilder {
+    pub fn new(num_validators: NonZeroUsize) -> Self {
+        Self {
+            num_validators,
+            seed: 0,
+            tick: Duration::from_millis(10),
+            template: NodeConfig::default_for_validator(),
+        }
+    }
+
+    /// The seed from which genesis, the validator keys and the fault decisions are derived.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The granularity at which the simulated clock advances.
+    pub fn tick(mut self, tick: Duration) -> Self {
+        self.tick = tick;
+        self
+    }
+
+    /// The config every validator config is derived from.
+    pub fn template(mut self, template: NodeConfig) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Generates genesis and the configs of the validators, and starts all of them.
+    pub fn build(self) -> Result<Simulation> {
+        let config_dir = TempPath::new();
+        config_dir.create_as_dir()?;
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let (_root_keys, _genesis, _waypoint, validators) = ValidatorBuilder::new(
+            config_dir.path(),
+            diem_framework_releases::current_module_blobs().to_vec(),
+        )
+        .num_validators(self.num_validators)
+        .template(self.template)
+        .build(&mut rng)?;
+
+        let network_runtime = paused_runtime()?;
+        let mock_time = MockTimeService::new();
+        let time_service = TimeService::from(mock_time.clone());
+        let network = SimulatedNetwork::new(
+            validators.len(),
+            self.seed,
+            time_service.clone(),
+            network_runtime.handle(),
+        )?;
+
+        let identities: Vec<_> = validators
+            .iter()
+            .map(|validator| {
+                let network_config = validator.config.validator_network.as_ref().unwrap();
+                (
+                    network_config.peer_id(),
+                    network_config.identity_key().public_key(),
+                )
+            })
+            .collect();
+
+        // Validators discover each other through seeds pointing at the proxies of the simulated
+        // network instead of the addresses published on chain.
+        let configs = validators
+            .into_iter()
+            .enumerate()
+            .map(|(index, validator)| {
+                let mut config = validator.config;
+                // Safety rules and execution correctness run in the consensus task instead of
+                // threads of their own.
+                config.consensus.safety_rules.service = SafetyRulesService::Local;
+                config.execution.service = ExecutionCorrectnessService::Local;
+                let network_config = config.validator_network.as_mut().unwrap();
+                network_config.listen_addresses = vec![network.listen_address(index)];
+                network_config.discovery_method = DiscoveryMethod::None;
+                network_config.discovery_methods.clear();
+                network_config.seeds = identities
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != index)
+                    .map(|(other, (peer_id, pubkey))| {
+                        let address = network.proxy_address(index, other, *pubkey);
+                        (
+                            *peer_id,
+                            Peer::from_addrs(PeerRole::Validator, vec![address]),
+                        )
+                    })
+                    .collect();
+                config
+            })
+            .collect();
+
+        let mut simulation = Simulation {
+            _config_dir: config_dir,
+            network_runtime,
+            mock_time,
+            time_service,
+            network,
+            configs,
+            nodes: vec![],
+            tick: self.tick,
+            elapsed: Duration::from_secs(0),
+            schedule: vec![],
+        };
+        simulation.nodes = (0..simulation.configs.len())
+            .map(|index| simulation.start_node(index).map(Some))
+            .collect::<Result<_>>()?;
+        Ok(simulation)
+    }
+}
+
+/// A current-thread runtime whose clock only moves when it has nothing left to run.
+fn paused_runtime() -> Result<Runtime> {
+    Ok(Builder::new_current_thread()
+        .enable_all()
+        .start_paused(true)
+        .build()?)
+}
+
+/// A running validator and the runtime its components are spawned on.
+struct Node {
+    handle: DiemHandle,
+    runtime: Runtime,
+}
+
+/// A network of validators running in one process, see the crate documentation.
+pub struct Simulation {
+    _config_dir: TempPath,
+    network_runtime: Runtime,
+    mock_time: MockTimeService,
+    time_service: TimeService,
+    network: Arc<SimulatedNetwork>,
+    configs: Vec<NodeConfig>,
+    nodes: Vec<Option<Node>>,
+    tick: Duration,
+    elapsed: Duration,
+    // Scripted faults, sorted by the simulated time they are applied at.
+    schedule: Vec<(Duration, Fault)>,
+}
+
+impl Simulation {
+    pub fn num_validators(&self) -> usize {
+        self.configs.len()
+    }
+
+    pub fn config(&self, index: usize) -> &NodeConfig {
+        &self.configs[index]
+    }
+
+    /// The simulated time elapsed since the simulation started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn is_running(&self, index: usize) -> bool {
+        self.nodes[index].is_some()
+    }
+
+    pub fn link_conditions(&self, a: usize, b: usize) -> LinkConditions {
+        self.network.link_conditions(a, b)
+    }
+
+    /// The latest ledger info committed by a running validator.
+    pub fn latest_ledger_info(&self, index: usize) -> Option<LedgerInfoWithSignatures> {
+        self.nodes[index]
+            .as_ref()
+            .and_then(|node| node.handle.db_reader().get_latest_ledger_info().ok())
+    }
+
+    /// The round of the latest block committed by a running validator.
+    pub fn committed_round(&self, index: usize) -> Option<u64> {
+        self.latest_ledger_info(index)
+            .map(|ledger_info| ledger_info.ledger_info().round())
+    }
+
+    /// Schedules a fault to be applied once the simulated time reaches `at`.
+    pub fn schedule(&mut self, at: Duration, fault: Fault) {
+        let position = self.schedule.partition_point(|(time, _)| *time <= at);
+        self.schedule.insert(position, (at, fault));
+    }
+
+    /// Applies a fault right away.
+    pub fn apply(&mut self, fault: Fault) {
+        match fault {
+            Fault::Link(a, b, conditions) => self.network.set_link_conditions(a, b, conditions),
+            Fault::Partition(groups) => {
+                let group_of =
+                    |index: usize| groups.iter().position(|group| group.contains(&index));
+                for a in 0..self.num_validators() {
+                    for b in (a + 1)..self.num_validators() {
+                        let mut conditions = self.network.link_conditions(a, b);
+                        conditions.partitioned = match (group_of(a), group_of(b)) {
+                            (Some(group_a), Some(group_b)) => group_a != group_b,
+                            _ => true,
+                        };
+                        self.network.set_link_conditions(a, b, conditions);
+                    }
+                }
+            }
+            Fault::Heal => {
+                for a in 0..self.num_validators() {
+                    for b in (a + 1)..self.num_validators() {
+                        let mut conditions = self.network.link_conditions(a, b);
+                        conditions.partitioned = false;
+                        self.network.set_link_conditions(a, b, conditions);
+                    }
+                }
+            }
+            Fault::Stop(index) => {
+                self.nodes[index] = None;
+            }
+            Fault::Start(index) => {
+                if self.nodes[index].is_none() {
+                    let node = self
+                        .start_node(index)
+                        .expect("Failed to create the runtime of a validator");
+                    self.nodes[index] = Some(node);
+                }
+            }
+            Fault::Restart(index) => {
+                self.apply(Fault::Stop(index));
+                self.apply(Fault::Start(index));
+            }
+        }
+    }
+
+    /// Runs the simulation for `duration` of simulated time, applying the scheduled faults on
+    /// the way. The clock advances one tick at a time, after every runtime ran up to it.
+    pub fn run_for(&mut self, duration: Duration) {
+        let deadline = self.elapsed + duration;
+        while self.elapsed < deadline {
+            self.step();
+        }
+    }
+
+    /// Runs the simulation until `condition` holds, failing if it doesn't within `timeout` of
+    /// simulated time.
+    pub fn run_until<F>(&mut self, timeout: Duration, mut condition: F) -> Result<()>
+    where
+        F: FnMut(&Simulation) -> bool,
+    {
+        let deadline = self.elapsed + timeout;
+        while !condition(self) {
+            ensure!(
+                self.elapsed < deadline,
+                "Condition not met after {:?} of simulated time",
+                timeout
+            );
+            self.step();
+        }
+        Ok(())
+    }
+
+    fn step(&mut self) {
+        while let Some((at, _)) = self.schedule.first() {
+            if *at > self.elapsed {
+                break;
+            }
+            let (_, fault) = self.schedule.remove(0);
+            self.apply(fault);
+        }
+        // Each runtime runs until it has nothing left to do or its paused clock reaches the end
+        // of the tick, in a fixed order, so the interleaving of the validators only depends on
+        // the seed. Bytes written during a tick are picked up by the others the next one.
+        self.network_runtime.block_on(tokio::time::sleep(self.tick));
+        for node in self.nodes.iter().flatten() {
+            node.runtime.block_on(tokio::time::sleep(self.tick));
+        }
+        self.mock_time.advance(self.tick);
+        self.elapsed += self.tick;
+    }
+
+    fn start_node(&self, index: usize) -> Result<Node> {
+        let runtime = paused_runtime()?;
+        let handle = setup_environment_on(
+            &self.configs[index],
+            None,
+            self.time_service.clone(),
+            runtime.handle().clone(),
+        );
+        Ok(Node { handle, runtime })
+    }
+}
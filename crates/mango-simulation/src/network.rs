@@ -0,0 +1,410 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The simulated network between validators.
+//!
+//! Every ordered pair of validators is connected through a proxy listening on its own memsocket
+//! port: validator `i` reaches validator `j` by dialing the proxy `(i, j)`, which forwards the
+//! bytes to the listener of `j` under the conditions currently set for the link between `i` and
+//! `j`. The links carry Noise streams, so the proxies can't look into the messages but split the
+//! stream along its framing: the two handshake messages, then length prefixed Noise frames. Faults
+//! are injected per message: a dropped message severs the connection carrying it and the
+//! validators have to reconnect.
+
+use futures::{
+    channel::mpsc,
+    future::{self, AbortHandle, Abortable},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    StreamExt,
+};
+use mango_config::config::HANDSHAKE_VERSION;
+use mango_crypto::x25519;
+use mango_infallible::Mutex;
+use mango_time_service::{TimeService, TimeServiceTrait};
+use mango_types::network_address::{NetworkAddress, Protocol};
+use memsocket::{MemoryListener, MemorySocket};
+use network::noise::NoiseUpgrader;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::runtime::Handle;
+
+/// Size of the length prefixing every Noise frame once the handshake is done.
+const FRAME_LENGTH_SIZE: usize = 2;
+
+/// The conditions of the link between two validators, applied in both directions.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkConditions {
+    /// Delay applied to every message sent over the link.
+    pub delay: Duration,
+    /// Probability for a message to be dropped, which severs the connection carrying it.
+    pub drop_rate: f64,
+    /// Whether the link is cut: open connections are severed and new ones are refused.
+    pub partitioned: bool,
+}
+
+struct Link {
+    conditions: LinkConditions,
+    // Drop decisions are drawn from a generator per direction of the link, seeded from the
+    // simulation seed, so that they don't depend on the traffic of the other links nor on the
+    // interleaving of the two directions.
+    rngs: [StdRng; 2],
+    connections: HashMap<u64, AbortHandle>,
+}
+
+pub(crate) struct SimulatedNetwork {
+    listen_ports: Vec<u16>,
+    proxy_ports: HashMap<(usize, usize), u16>,
+    links: Mutex<HashMap<(usize, usize), Link>>,
+    next_connection_id: Mutex<u64>,
+    time_service: TimeService,
+}
+
+/// Links are undirected, the pair of validators is normalized so that the lowest index comes first.
+fn link_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// The index of the generator of the direction `from` -> `to` in the generators of its link.
+fn direction(from: usize, to: usize) -> usize {
+    usize::from(from > to)
+}
+
+/// Seeds the generator of the direction `from` -> `to`.
+fn direction_rng(seed: u64, from: usize, to: usize) -> StdRng {
+    StdRng::seed_from_u64(seed ^ ((from as u64) << 32 | to as u64))
+}
+
+/// Reserves a memsocket port: the switchboard assigns ports incrementally, so a port bound and
+/// released here is not handed out again to another listener binding port 0.
+fn reserve_port() -> io::Result<u16> {
+    Ok(MemoryListener::bind(0)?.local_addr())
+}
+
+impl SimulatedNetwork {
+    /// Binds the proxies between `num_nodes` validators and spawns them on `executor`.
+    pub fn new(
+        num_nodes: usize,
+        seed: u64,
+        time_service: TimeService,
+        executor: &Handle,
+    ) -> io::Result<Arc<Self>> {
+        let listen_ports = (0..num_nodes)
+            .map(|_| reserve_port())
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut links = HashMap::new();
+        for a in 0..num_nodes {
+            for b in (a + 1)..num_nodes {
+                links.insert(
+                    (a, b),
+                    Link {
+                        conditions: LinkConditions::default(),
+                        rngs: [direction_rng(seed, a, b), direction_rng(seed, b, a)],
+                        connections: HashMap::new(),
+                    },
+                );
+            }
+        }
+
+        let mut listeners = vec![];
+        let mut proxy_ports = HashMap::new();
+        for from in 0..num_nodes {
+            for to in 0..num_nodes {
+                if from != to {
+                    let listener = MemoryListener::bind(0)?;
+                    proxy_ports.insert((from, to), listener.local_addr());
+                    listeners.push((from, to, listener));
+                }
+            }
+        }
+
+        let network = Arc::new(Self {
+            listen_ports,
+            proxy_ports,
+            links: Mutex::new(links),
+            next_connection_id: Mutex::new(0),
+            time_service,
+        });
+        for (from, to, listener) in listeners {
+            executor.spawn(Arc::clone(&network).serve(from, to, listener));
+        }
+        Ok(network)
+    }
+
+    /// The address validator `index` listens on.
+    pub fn listen_address(&self, index: usize) -> NetworkAddress {
+        NetworkAddress::from(Protocol::Memory(self.listen_ports[index]))
+    }
+
+    /// The address validator `from` dials to reach validator `to`.
+    pub fn proxy_address(
+        &self,
+        from: usize,
+        to: usize,
+        pubkey: x25519::PublicKey,
+    ) -> NetworkAddress {
+        NetworkAddress::from(Protocol::Memory(self.proxy_ports[&(from, to)]))
+            .append_prod_protos(pubkey, HANDSHAKE_VERSION)
+    }
+
+    pub fn link_conditions(&self, a: usize, b: usize) -> LinkConditions {
+        self.links.lock()[&link_key(a, b)].conditions
+    }
+
+    pub fn set_link_conditions(&self, a: usize, b: usize, conditions: LinkConditions) {
+        let mut links = self.links.lock();
+        let link = links
+            .get_mut(&link_key(a, b))
+            .expect("Link between unknown validators");
+        link.conditions = conditions;
+        if conditions.partitioned {
+            for (_, connection) in link.connections.drain() {
+                connection.abort();
+            }
+        }
+    }
+
+    async fn serve(self: Arc<Self>, from: usize, to: usize, mut listener: MemoryListener) {
+        let mut incoming = listener.incoming();
+        while let Some(Ok(inbound)) = incoming.next().await {
+            tokio::spawn(Arc::clone(&self).forward(from, to, inbound));
+        }
+    }
+
+    async fn forward(self: Arc<Self>, from: usize, to: usize, inbound: MemorySocket) {
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let connection_id = match self.register(from, to, abort_handle) {
+            Some(id) => id,
+            // The link is partitioned, refuse the connection by dropping it.
+            None => return,
+        };
+
+        // Fails while the dialed validator is down.
+        if let Ok(outbound) = MemorySocket::connect(self.listen_ports[to]) {
+            let (inbound_reader, inbound_writer) = inbound.split();
+            let (outbound_reader, outbound_writer) = outbound.split();
+            let pumps = future::try_join(
+                Arc::clone(&self).pump(
+                    from,
+                    to,
+                    NoiseUpgrader::CLIENT_MESSAGE_SIZE,
+                    inbound_reader,
+                    outbound_writer,
+                ),
+                Arc::clone(&self).pump(
+                    to,
+                    from,
+                    NoiseUpgrader::SERVER_MESSAGE_SIZE,
+                    outbound_reader,
+                    inbound_writer,
+                ),
+            );
+            let _ = Abortable::new(pumps, abort_registration).await;
+        }
+
+        if let Some(link) = self.links.lock().get_mut(&link_key(from, to)) {
+            link.connections.remove(&connection_id);
+        }
+    }
+
+    fn register(&self, from: usize, to: usize, abort_handle: AbortHandle) -> Option<u64> {
+        let mut links = self.links.lock();
+        let link = links.get_mut(&link_key(from, to))?;
+        if link.conditions.partitioned {
+            return None;
+        }
+        let mut next_connection_id = self.next_connection_id.lock();
+        let id = *next_connection_id;
+        *next_connection_id += 1;
+        link.connections.insert(id, abort_handle);
+        Some(id)
+    }
+
+    /// Decides the fate of a message sent from `from` to `to`: the delay to apply, or an error if
+    /// the connection has to be severed.
+    fn on_message(&self, from: usize, to: usize) -> io::Result<Duration> {
+        let mut links = self.links.lock();
+        let link = links
+            .get_mut(&link_key(from, to))
+            .ok_or_else(|| io::Error::from(ErrorKind::NotConnected))?;
+        if link.conditions.partitioned {
+            return Err(io::Error::new(
+                ErrorKind::ConnectionReset,
+                "link partitioned",
+            ));
+        }
+        let rng = &mut link.rngs[direction(from, to)];
+        if link.conditions.drop_rate > 0.0 && rng.gen_bool(link.conditions.drop_rate) {
+            return Err(io::Error::new(
+                ErrorKind::ConnectionReset,
+                "message dropped",
+            ));
+        }
+        Ok(link.conditions.delay)
+    }
+
+    /// Forwards the messages sent from `from` to `to` read from `reader` to `writer`, delivering
+    /// every message once its delay elapsed while preserving the order of the stream. The first
+    /// message is the handshake message of `handshake_message_size` bytes.
+    async fn pump<R, W>(
+        self: Arc<Self>,
+        from: usize,
+        to: usize,
+        handshake_message_size: usize,
+        mut reader: R,
+        mut writer: W,
+    ) -> io::Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let (sender, mut receiver) = mpsc::unbounded::<(Instant, Vec<u8>)>();
+        let time_service = self.time_service.clone();
+
+        let read = async move {
+            let mut message_size = Some(handshake_message_size);
+            while let Some(message) = read_message(&mut reader, message_size.take()).await? {
+                let deadline = self.time_service.now() + self.on_message(from, to)?;
+                sender
+                    .unbounded_send((deadline, message))
+                    .map_err(|_| io::Error::from(ErrorKind::BrokenPipe))?;
+            }
+            Ok(())
+        };
+        let write = async move {
+            while let Some((deadline, message)) = receiver.next().await {
+                time_service.sleep_until(deadline).await;
+                writer.write_all(&message).await?;
+            }
+            writer.close().await
+        };
+
+        future::try_join(read, write).await.map(|_| ())
+    }
+}
+
+/// Reads the next message of `reader`: `size` bytes if given, a Noise frame and its length
+/// otherwise. Returns `None` if the stream ends before the message starts.
+async fn read_message<R>(reader: &mut R, size: Option<usize>) -> io::Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut first_byte = [0; 1];
+    if reader.read(&mut first_byte).await? == 0 {
+        return Ok(None);
+    }
+    let mut message = first_byte.to_vec();
+    let size = match size {
+        Some(size) => size,
+        None => {
+            let mut second_byte = [0; 1];
+            reader.read_exact(&mut second_byte).await?;
+            message.push(second_byte[0]);
+            FRAME_LENGTH_SIZE + u16::from_be_bytes([first_byte[0], second_byte[0]]) as usize
+        }
+    };
+    let offset = message.len();
+    message.resize(size, 0);
+    reader.read_exact(&mut message[offset..]).await?;
+    Ok(Some(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    fn setup(delay: Duration) -> (Arc<SimulatedNetwork>, TimeService, MemoryListener) {
+        let time_service = TimeService::mock();
+        let network =
+            SimulatedNetwork::new(2, 0, time_service.clone(), &Handle::current()).unwrap();
+        network.set_link_conditions(
+            0,
+            1,
+            LinkConditions {
+                delay,
+                ..LinkConditions::default()
+            },
+        );
+        let listener = MemoryListener::bind(network.listen_ports[1]).unwrap();
+        (network, time_service, listener)
+    }
+
+    #[tokio::test]
+    async fn test_delayed_forwarding() {
+        let delay = Duration::from_millis(500);
+        let (network, time_service, mut listener) = setup(delay);
+        let mock_time = time_service.into_mock();
+
+        let mut dialer = MemorySocket::connect(network.proxy_ports[&(0, 1)]).unwrap();
+        let mut accepted = listener.incoming().next().await.unwrap().unwrap();
+        let handshake = vec![1; NoiseUpgrader::CLIENT_MESSAGE_SIZE];
+        let frame = [&10u16.to_be_bytes()[..], b"simulation"].concat();
+        for message in [handshake, frame].iter() {
+            dialer.write_all(message).await.unwrap();
+            dialer.flush().await.unwrap();
+
+            // Wait for the message to be held back by the proxy.
+            while mock_time.num_waiters() == 0 {
+                tokio::task::yield_now().await;
+            }
+            let mut buf = vec![0; message.len()];
+            assert!(accepted.read_exact(&mut buf).now_or_never().is_none());
+
+            mock_time.advance(delay);
+            accepted.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, message);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_message() {
+        let frame = [&3u16.to_be_bytes()[..], b"abc"].concat();
+        let stream = [&[1, 2][..], &frame, &frame[..4]].concat();
+        let mut reader = &stream[..];
+
+        let handshake = read_message(&mut reader, Some(2)).await.unwrap();
+        assert_eq!(handshake.unwrap(), vec![1, 2]);
+        let message = read_message(&mut reader, None).await.unwrap();
+        assert_eq!(message.unwrap(), frame);
+        // A stream ending in the middle of a message is an error, at its boundary the end.
+        assert!(read_message(&mut reader, None).await.is_err());
+        assert!(read_message(&mut &b""[..], None).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_partition() {
+        let (network, _time_service, mut listener) = setup(Duration::from_millis(0));
+
+        let mut dialer = MemorySocket::connect(network.proxy_ports[&(1, 0)]).unwrap();
+        let mut dialer_to_1 = MemorySocket::connect(network.proxy_ports[&(0, 1)]).unwrap();
+        let mut accepted = listener.incoming().next().await.unwrap().unwrap();
+
+        // Validator 0 isn't listening, the connection is closed by the proxy.
+        let mut buf = [0; 1];
+        assert_eq!(dialer.read(&mut buf).await.unwrap(), 0);
+
+        // Cutting the link severs the open connection and refuses new ones.
+        network.set_link_conditions(
+            0,
+            1,
+            LinkConditions {
+                partitioned: true,
+                ..LinkConditions::default()
+            },
+        );
+        assert_eq!(accepted.read(&mut buf).await.unwrap(), 0);
+        assert_eq!(dialer_to_1.read(&mut buf).await.unwrap(), 0);
+        let mut refused = MemorySocket::connect(network.proxy_ports[&(0, 1)]).unwrap();
+        assert_eq!(refused.read(&mut buf).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use mango_simulation::{Fault, LinkConditions, Simulation, SimulationBuilder};
+use mango_types::ledger_info::LedgerInfo;
+use std::{num::NonZeroUsize, time::Duration};
+
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+fn all_committed_past(simulation: &Simulation, validators: &[usize], round: u64) -> bool {
+    validators
+        .iter()
+        .all(|index| simulation.committed_round(*index).unwrap_or(0) > round)
+}
+
+/// Runs a lossy simulation from `seed` and samples the ledger infos committed by every validator
+/// each simulated second.
+fn committed_ledgers(seed: u64) -> Vec<Vec<Option<LedgerInfo>>> {
+    let mut simulation = SimulationBuilder::new(NonZeroUsize::new(4).unwrap())
+        .seed(seed)
+        .build()
+        .unwrap();
+    simulation.apply(Fault::Link(
+        0,
+        1,
+        LinkConditions {
+            delay: Duration::from_millis(50),
+            drop_rate: 0.05,
+            partitioned: false,
+        },
+    ));
+    simulation.schedule(Duration::from_secs(5), Fault::Restart(2));
+
+    (0..15)
+        .map(|_| {
+            simulation.run_for(Duration::from_secs(1));
+            (0..simulation.num_validators())
+                .map(|index| {
+                    simulation
+                        .latest_ledger_info(index)
+                        .map(|ledger_info| ledger_info.ledger_info().clone())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_replay_from_seed() {
+    let ledgers = committed_ledgers(7);
+    assert!(ledgers
+        .last()
+        .unwrap()
+        .iter()
+        .all(|ledger_info| ledger_info.as_ref().map_or(0, LedgerInfo::round) > 0));
+    assert_eq!(ledgers, committed_ledgers(7));
+    assert_ne!(ledgers, committed_ledgers(8));
+}
+
+#[test]
+fn test_progress_through_faults() {
+    let mut simulation = SimulationBuilder::new(NonZeroUsize::new(4).unwrap())
+        .seed(42)
+        .build()
+        .unwrap();
+    simulation
+        .run_until(TIMEOUT, |s| all_committed_past(s, &[0, 1, 2, 3], 5))
+        .unwrap();
+
+    // A slow and lossy link doesn't prevent progress.
+    simulation.apply(Fault::Link(
+        0,
+        1,
+        LinkConditions {
+            delay: Duration::from_millis(200),
+            drop_rate: 0.05,
+            partitioned: false,
+        },
+    ));
+    let round = simulation.committed_round(0).unwrap();
+    simulation
+        .run_until(TIMEOUT, |s| all_committed_past(s, &[0, 1, 2, 3], round))
+        .unwrap();
+
+    // The majority side of a partition keeps committing, the minority catches up once healed.
+    let start = simulation.elapsed();
+    let round = simulation.committed_round(1).unwrap();
+    simulation.schedule(start, Fault::Partition(vec![vec![0], vec![1, 2, 3]]));
+    simulation.schedule(start + Duration::from_secs(10), Fault::Heal);
+    simulation.run_for(Duration::from_secs(10));
+    assert!(all_committed_past(&simulation, &[1, 2, 3], round));
+    let round = simulation.committed_round(1).unwrap();
+    simulation
+        .run_until(TIMEOUT, |s| all_committed_past(s, &[0, 1, 2, 3], round))
+        .unwrap();
+
+    // A restarted validator recovers from its storage and rejoins.
+    simulation.apply(Fault::Restart(3));
+    assert!(simulation.is_running(3));
+    let round = simulation.committed_round(1).unwrap();
+    simulation
+        .run_until(TIMEOUT, |s| all_committed_past(s, &[0, 1, 2, 3], round))
+        .unwrap();
+}
@@ -7,6 +7,7 @@ mod genesis_context;
 
 use crate::genesis_context::GenesisStateView;
 use diem_crypto::{
+    bls12381::{Bls12381PrivateKey, Bls12381ProofOfPossession},
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
     PrivateKey, Uniform,
 };
@@ -347,6 +348,22 @@ fn create_and_initialize_owners_operators(
             MoveValue::Vector(full_node_network_addresses),
         ]),
     );
+
+    // Register the BLS consensus keys provided up front. This goes through the same script as a
+    // later rotation, signed by each operator, so genesis and rotations are validated alike.
+    for v in validators
+        .iter()
+        .filter(|v| !v.bls_consensus_pubkey.is_empty())
+    {
+        let set_bls_key =
+            transaction_builder::encode_set_validator_bls_key_and_reconfigure_script_function(
+                v.address,
+                v.bls_consensus_pubkey.clone(),
+                v.bls_proof_of_possession.clone(),
+            )
+            .into_script_function();
+        exec_script_function(session, v.operator_address, &set_bls_key);
+    }
 }
 
 /// Publish the standard library.
@@ -463,6 +480,11 @@ pub struct Validator {
     pub auth_key: AuthenticationKey,
     /// Ed25519 public key used to sign consensus messages
     pub consensus_pubkey: Vec<u8>,
+    /// BLS12-381 public key used to sign aggregatable consensus messages, empty if the validator
+    /// does not register one at genesis
+    pub bls_consensus_pubkey: Vec<u8>,
+    /// Proof of possession of the private key behind `bls_consensus_pubkey`
+    pub bls_proof_of_possession: Vec<u8>,
     /// The mango account address of the validator's operator (same as `address` if the validator is
     /// its own operator)
     pub operator_address: AccountAddress,
@@ -478,6 +500,7 @@ pub struct Validator {
 
 pub struct TestValidator {
     pub key: Ed25519PrivateKey,
+    pub bls_key: Bls12381PrivateKey,
     pub data: Validator,
 }
 
@@ -495,6 +518,11 @@ impl TestValidator {
         let key = Ed25519PrivateKey::generate(rng);
         let auth_key = AuthenticationKey::ed25519(&key.public_key());
         let consensus_pubkey = key.public_key().to_bytes().to_vec();
+        let bls_key = Bls12381PrivateKey::generate(rng);
+        let bls_consensus_pubkey = bls_key.public_key().to_bytes().to_vec();
+        let bls_proof_of_possession = Bls12381ProofOfPossession::create(&bls_key)
+            .to_bytes()
+            .to_vec();
         let operator_auth_key = auth_key;
         let operator_address = operator_auth_key.derived_address();
         let operator_name = name.clone();
@@ -506,13 +534,15 @@ impl TestValidator {
             name,
             auth_key,
             consensus_pubkey,
+            bls_consensus_pubkey,
+            bls_proof_of_possession,
             operator_address,
             operator_name,
             operator_auth_key,
             network_address,
             full_node_network_address,
         };
-        Self { key, data }
+        Self { key, bls_key, data }
     }
 }
 
@@ -8,12 +8,15 @@ use executor::db_bootstrapper::calculate_genesis;
 use mango_config::config::RocksdbConfig;
 use mango_temppath::TempPath;
 use mango_types::{transaction::Transaction, waypoint::Waypoint};
+use serde_json::json;
 use std::{
+    env,
     fs::File,
     io::Read,
+    net::SocketAddr,
     path::{Path, PathBuf},
 };
-use storage_interface::DbReaderWriter;
+use storage_interface::{DbReader, DbReaderWriter};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -25,20 +28,48 @@ struct Opt {
     #[structopt(parse(from_os_str))]
     db_dir: PathBuf,
 
-    #[structopt(short, long, parse(from_os_str))]
-    genesis_txn_file: PathBuf,
+    #[structopt(short, long, parse(from_os_str), required_unless("checkpoint-dir"))]
+    genesis_txn_file: Option<PathBuf>,
 
     #[structopt(short, long)]
     waypoint_to_verify: Option<Waypoint>,
 
     #[structopt(long, requires("waypoint-to-verify"))]
     commit: bool,
+
+    /// Instead of bootstrapping, create a checkpoint of the DB in this dir: a consistent copy
+    /// that a new node can use as its storage dir. Files are hard linked, so the dir should be on
+    /// the same file system as the DB.
+    #[structopt(
+        long,
+        parse(from_os_str),
+        conflicts_with_all(&["genesis-txn-file", "waypoint-to-verify"])
+    )]
+    checkpoint_dir: Option<PathBuf>,
+
+    /// Backup service of the node running on the DB, through which the checkpoint is created
+    /// without stopping the node. If not provided, the DB is opened directly, which requires no
+    /// node to be running on it.
+    ///
+    /// The node creates the checkpoint on its own file system and the tool opens it afterwards to
+    /// check it, so the node must run on this machine, with `storage.checkpoint_root` set to a
+    /// parent of the checkpoint dir.
+    #[structopt(long, requires("checkpoint-dir"))]
+    backup_service_address: Option<SocketAddr>,
 }
 
 fn main() -> Result<()> {
     let opt = Opt::from_args();
 
-    let genesis_txn = load_genesis_txn(&opt.genesis_txn_file)
+    if let Some(checkpoint_dir) = &opt.checkpoint_dir {
+        return create_checkpoint(&opt.db_dir, checkpoint_dir, opt.backup_service_address);
+    }
+
+    let genesis_txn_file = opt
+        .genesis_txn_file
+        .as_ref()
+        .expect("Required unless creating a checkpoint.");
+    let genesis_txn = load_genesis_txn(genesis_txn_file)
         .with_context(|| format_err!("Failed loading genesis txn."))?;
     assert!(
         matches!(genesis_txn, Transaction::GenesisTransaction(_)),
@@ -107,6 +138,50 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+fn create_checkpoint(
+    db_dir: &Path,
+    checkpoint_dir: &Path,
+    backup_service_address: Option<SocketAddr>,
+) -> Result<()> {
+    match backup_service_address {
+        Some(address) => {
+            // The path is interpreted by the node.
+            let path = env::current_dir()?.join(checkpoint_dir);
+            reqwest::blocking::Client::new()
+                .post(&format!("http://{}/checkpoint", address))
+                .json(&json!({ "path": path }))
+                .send()?
+                .error_for_status()
+                .with_context(|| format_err!("Backup service failed to create checkpoint."))?;
+        }
+        // Opening the DB for writing fails if a node is running on it.
+        None => DiemDB::open(
+            db_dir,
+            false, /* readonly */
+            None,  /* pruner */
+            RocksdbConfig::default(),
+        )
+        .with_context(|| format_err!("Failed to open DB."))?
+        .create_checkpoint(checkpoint_dir)
+        .with_context(|| format_err!("Failed to create checkpoint."))?,
+    }
+
+    // Make sure the checkpoint can be opened.
+    let checkpoint = DiemDB::open(
+        checkpoint_dir,
+        true, /* readonly */
+        None, /* pruner */
+        RocksdbConfig::default(),
+    )
+    .with_context(|| format_err!("Failed to open checkpoint."))?;
+    println!(
+        "Successfully created checkpoint at version {}.",
+        checkpoint.get_latest_version()?
+    );
+
+    Ok(())
+}
+
 fn load_genesis_txn(path: &Path) -> Result<Transaction> {
     let mut file = File::open(&path)?;
     let mut buffer = vec![];
@@ -4,7 +4,10 @@
 use consensus_types::block::Block;
 use executor_types::{Error, StateComputeResult};
 use mango_crypto::HashValue;
-use mango_types::ledger_info::LedgerInfoWithSignatures;
+use mango_types::{
+    account_address::AccountAddress, ledger_info::LedgerInfoWithSignatures,
+    transaction::SignedTransaction,
+};
 
 /// Interface for ExecutionCorrectness.
 /// It is basically the same as BlockExecutor except some interfaces will return signature with result.
@@ -13,11 +16,15 @@ pub trait ExecutionCorrectness: Send {
 
     fn reset(&self) -> Result<(), Error>;
 
-    /// Executes a block.
+    /// Executes a block. `validators` are the ordered account addresses of the validators of the
+    /// block's epoch, used to build the block metadata transaction. `txns` are the user
+    /// transactions of the block payload, resolved by consensus when it references batches.
     fn execute_block(
         &self,
         block: Block,
         parent_block_id: HashValue,
+        validators: &[AccountAddress],
+        txns: Vec<SignedTransaction>,
     ) -> Result<StateComputeResult, Error>;
 
     fn commit_blocks(
@@ -5,7 +5,10 @@ use crate::execution_correctness::ExecutionCorrectness;
 use consensus_types::{block::Block, vote_proposal::VoteProposal};
 use executor_types::{BlockExecutor, Error, StateComputeResult};
 use mango_crypto::{ed25519::Ed25519PrivateKey, traits::SigningKey, HashValue};
-use mango_types::ledger_info::LedgerInfoWithSignatures;
+use mango_types::{
+    account_address::AccountAddress, ledger_info::LedgerInfoWithSignatures,
+    transaction::SignedTransaction,
+};
 use std::{boxed::Box, sync::Arc};
 
 pub struct LocalService {
@@ -48,10 +51,12 @@ impl ExecutionCorrectness for LocalClient {
         &self,
         block: Block,
         parent_block_id: HashValue,
+        validators: &[AccountAddress],
+        txns: Vec<SignedTransaction>,
     ) -> Result<StateComputeResult, Error> {
         let local = &self.internal;
         let mut result = local.block_executor.execute_block(
-            (block.id(), block.transactions_to_execute()),
+            (block.id(), block.transactions_to_execute(validators, txns)),
             parent_block_id,
         )?;
         if let Some(prikey) = local.prikey.as_ref() {
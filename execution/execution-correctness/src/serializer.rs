@@ -5,7 +5,10 @@ use crate::execution_correctness::ExecutionCorrectness;
 use consensus_types::{block::Block, vote_proposal::VoteProposal};
 use executor_types::{BlockExecutor, Error, StateComputeResult};
 use mango_crypto::{ed25519::Ed25519PrivateKey, traits::SigningKey, HashValue};
-use mango_types::ledger_info::LedgerInfoWithSignatures;
+use mango_types::{
+    account_address::AccountAddress, ledger_info::LedgerInfoWithSignatures,
+    transaction::SignedTransaction,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -13,7 +16,14 @@ use std::sync::Arc;
 pub enum ExecutionCorrectnessInput {
     CommittedBlockId,
     Reset,
-    ExecuteBlock(Box<(Block, HashValue)>),
+    ExecuteBlock(
+        Box<(
+            Block,
+            HashValue,
+            Vec<AccountAddress>,
+            Vec<SignedTransaction>,
+        )>,
+    ),
     CommitBlocks(Box<(Vec<HashValue>, LedgerInfoWithSignatures)>),
 }
 
@@ -35,29 +45,29 @@ impl SerializerService {
                 bcs::to_bytes(&self.internal.committed_block_id())
             }
             ExecutionCorrectnessInput::Reset => bcs::to_bytes(&self.internal.reset()),
-            ExecutionCorrectnessInput::ExecuteBlock(block_with_parent_id) => bcs::to_bytes(
-                &self
-                    .internal
-                    .execute_block(
-                        (
-                            block_with_parent_id.0.id(),
-                            block_with_parent_id.0.transactions_to_execute(),
-                        ),
-                        block_with_parent_id.1,
-                    )
-                    .map(|mut result| {
-                        if let Some(prikey) = self.prikey.as_ref() {
-                            let vote_proposal = VoteProposal::new(
-                                result.extension_proof(),
-                                block_with_parent_id.0.clone(),
-                                result.epoch_state().clone(),
-                            );
-                            let signature = prikey.sign(&vote_proposal);
-                            result.set_signature(signature);
-                        }
-                        result
-                    }),
-            ),
+            ExecutionCorrectnessInput::ExecuteBlock(block_with_parent_id) => {
+                let (block, parent_block_id, validators, txns) = *block_with_parent_id;
+                bcs::to_bytes(
+                    &self
+                        .internal
+                        .execute_block(
+                            (block.id(), block.transactions_to_execute(&validators, txns)),
+                            parent_block_id,
+                        )
+                        .map(|mut result| {
+                            if let Some(prikey) = self.prikey.as_ref() {
+                                let vote_proposal = VoteProposal::new(
+                                    result.extension_proof(),
+                                    block,
+                                    result.epoch_state().clone(),
+                                );
+                                let signature = prikey.sign(&vote_proposal);
+                                result.set_signature(signature);
+                            }
+                            result
+                        }),
+                )
+            }
             ExecutionCorrectnessInput::CommitBlocks(blocks_with_li) => bcs::to_bytes(
                 &self
                     .internal
@@ -102,10 +112,14 @@ impl ExecutionCorrectness for SerializerClient {
         &self,
         block: Block,
         parent_block_id: HashValue,
+        validators: &[AccountAddress],
+        txns: Vec<SignedTransaction>,
     ) -> Result<StateComputeResult, Error> {
         let response = self.request(ExecutionCorrectnessInput::ExecuteBlock(Box::new((
             block,
             parent_block_id,
+            validators.to_vec(),
+            txns,
         ))))?;
         bcs::from_bytes(&response)?
     }
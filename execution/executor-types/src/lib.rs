@@ -22,7 +22,8 @@ use mango_types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::{accumulator::InMemoryAccumulator, AccumulatorExtensionProof},
     transaction::{
-        Transaction, TransactionInfo, TransactionListWithProof, TransactionStatus, Version,
+        Transaction, TransactionInfo, TransactionListWithProof, TransactionOutputListWithProof,
+        TransactionStatus, Version,
     },
 };
 use serde::{Deserialize, Serialize};
@@ -45,6 +46,20 @@ pub trait ChunkExecutor: Send + Sync {
         // carrying any epoch change LI.
         epoch_change_li: Option<LedgerInfoWithSignatures>,
     ) -> Result<Vec<ContractEvent>>;
+
+    /// Verifies the transaction outputs based on the provided proofs and ledger info. If the
+    /// outputs are valid, applies their write sets (without re-executing the transactions) and
+    /// commits immediately if the resulting states match the proofs.
+    /// Returns a vector of reconfiguration events in the chunk
+    fn apply_and_commit_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        // Target LI that has been verified independently: the proofs are relative to this version.
+        verified_target_li: LedgerInfoWithSignatures,
+        // An optional end of epoch LedgerInfo. We do not allow chunks that end epoch without
+        // carrying any epoch change LI.
+        epoch_change_li: Option<LedgerInfoWithSignatures>,
+    ) -> Result<Vec<ContractEvent>>;
 }
 
 pub trait BlockExecutor: Send + Sync {
@@ -16,7 +16,10 @@ use mango_types::{
     account_address::AccountAddress,
     block_info::BlockInfo,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    transaction::{Transaction, TransactionListWithProof, Version},
+    transaction::{
+        Transaction, TransactionListWithProof, TransactionOutput, TransactionOutputListWithProof,
+        Version,
+    },
 };
 use proptest::prelude::*;
 use rand::Rng;
@@ -243,10 +246,11 @@ fn test_executor_execute_same_block_multiple_times() {
     assert_eq!(responses.len(), 1);
 }
 
-/// Generates a list of `TransactionListWithProof`s according to the given ranges.
-fn create_transaction_chunks(
-    chunk_ranges: Vec<std::ops::Range<Version>>,
-) -> (Vec<TransactionListWithProof>, LedgerInfoWithSignatures) {
+/// Executes and commits the transactions covering the given ranges in a separate DB, so that
+/// chunks of them can be retrieved from it.
+fn commit_source_transactions(
+    chunk_ranges: &[std::ops::Range<Version>],
+) -> (TestExecutor, LedgerInfoWithSignatures) {
     assert_eq!(chunk_ranges.first().unwrap().start, 1);
     for i in 1..chunk_ranges.len() {
         let previous_range = &chunk_ranges[i - 1];
@@ -257,13 +261,7 @@ fn create_transaction_chunks(
         assert!(previous_range.end <= range.end);
     }
 
-    // To obtain the batches of transactions, we first execute and save all these transactions in a
-    // separate DB. Then we call get_transactions to retrieve them.
-    let TestExecutor {
-        _path,
-        db: _,
-        executor,
-    } = TestExecutor::new();
+    let executor = TestExecutor::new();
 
     let mut txns = vec![];
     for i in 1..chunk_ranges.last().unwrap().end {
@@ -281,6 +279,18 @@ fn create_transaction_chunks(
         .commit_blocks(vec![id], ledger_info.clone())
         .unwrap();
 
+    (executor, ledger_info)
+}
+
+/// Generates a list of `TransactionListWithProof`s according to the given ranges.
+fn create_transaction_chunks(
+    chunk_ranges: Vec<std::ops::Range<Version>>,
+) -> (Vec<TransactionListWithProof>, LedgerInfoWithSignatures) {
+    // To obtain the batches of transactions, we first execute and save all these transactions in a
+    // separate DB. Then we call get_transactions to retrieve them.
+    let (executor, ledger_info) = commit_source_transactions(&chunk_ranges);
+    let ledger_version = ledger_info.ledger_info().version();
+
     let batches: Vec<_> = chunk_ranges
         .into_iter()
         .map(|range| {
@@ -300,6 +310,30 @@ fn create_transaction_chunks(
     (batches, ledger_info)
 }
 
+/// Generates a list of `TransactionOutputListWithProof`s according to the given ranges.
+fn create_transaction_output_chunks(
+    chunk_ranges: Vec<std::ops::Range<Version>>,
+) -> (
+    Vec<TransactionOutputListWithProof>,
+    LedgerInfoWithSignatures,
+) {
+    let (executor, ledger_info) = commit_source_transactions(&chunk_ranges);
+    let ledger_version = ledger_info.ledger_info().version();
+
+    let batches: Vec<_> = chunk_ranges
+        .into_iter()
+        .map(|range| {
+            executor
+                .db
+                .reader
+                .get_transaction_outputs(range.start, range.end - range.start, ledger_version)
+                .unwrap()
+        })
+        .collect();
+
+    (batches, ledger_info)
+}
+
 #[test]
 fn test_executor_execute_and_commit_chunk() {
     let first_batch_size = 30;
@@ -451,6 +485,79 @@ fn test_executor_execute_and_commit_chunk_local_result_mismatch() {
         .is_err());
 }
 
+#[test]
+fn test_executor_apply_and_commit_chunk() {
+    let first_batch_size = 30;
+    let second_batch_size = 40;
+    let overlapping_size = 5;
+
+    let (chunks, ledger_info) = {
+        let first_batch_start = 1;
+        let second_batch_start = first_batch_start + first_batch_size - overlapping_size;
+        create_transaction_output_chunks(vec![
+            first_batch_start..first_batch_start + first_batch_size,
+            second_batch_start..second_batch_start + second_batch_size,
+        ])
+    };
+
+    let TestExecutor {
+        _path,
+        db,
+        executor,
+    } = TestExecutor::new();
+
+    // Apply the first chunk. After that we should still get the genesis ledger info from DB.
+    executor
+        .apply_and_commit_chunk(chunks[0].clone(), ledger_info.clone(), None)
+        .unwrap();
+    let li = db.reader.get_latest_ledger_info().unwrap();
+    assert_eq!(li.ledger_info().version(), 0);
+    assert_eq!(li.ledger_info().consensus_block_id(), HashValue::zero());
+
+    // Apply an empty chunk. After that we should still get the genesis ledger info from DB.
+    executor
+        .apply_and_commit_chunk(
+            TransactionOutputListWithProof::new_empty(),
+            ledger_info.clone(),
+            None,
+        )
+        .unwrap();
+    let li = db.reader.get_latest_ledger_info().unwrap();
+    assert_eq!(li.ledger_info().version(), 0);
+
+    // Apply the overlapping second chunk. After that we should get the new ledger info.
+    executor
+        .apply_and_commit_chunk(chunks[1].clone(), ledger_info.clone(), None)
+        .unwrap();
+    let li = db.reader.get_latest_ledger_info().unwrap();
+    assert_eq!(li, ledger_info);
+}
+
+#[test]
+fn test_executor_apply_and_commit_chunk_tampered_output() {
+    let (mut chunks, ledger_info) = create_transaction_output_chunks(vec![1..11]);
+    let TestExecutor {
+        _path,
+        db: _,
+        executor,
+    } = TestExecutor::new();
+
+    // Replace the write set of the first output with the one of the second: the resulting state
+    // no longer matches the proof, so the chunk must be rejected.
+    let chunk = &mut chunks[0];
+    let (_, second_output) = chunk.transactions_and_outputs[1].clone();
+    let (_, first_output) = &mut chunk.transactions_and_outputs[0];
+    *first_output = TransactionOutput::new(
+        second_output.write_set().clone(),
+        first_output.events().to_vec(),
+        first_output.gas_used(),
+        first_output.status().clone(),
+    );
+    assert!(executor
+        .apply_and_commit_chunk(chunks[0].clone(), ledger_info, None)
+        .is_err());
+}
+
 #[test]
 fn test_noop_block_after_reconfiguration() {
     let executor = TestExecutor::new();
@@ -19,16 +19,16 @@ pub mod db_bootstrapper;
 use crate::{
     logging::{LogEntry, LogSchema},
     metrics::{
-        DIEM_EXECUTOR_COMMIT_BLOCKS_SECONDS, DIEM_EXECUTOR_ERRORS,
-        DIEM_EXECUTOR_EXECUTE_AND_COMMIT_CHUNK_SECONDS, DIEM_EXECUTOR_EXECUTE_BLOCK_SECONDS,
-        DIEM_EXECUTOR_SAVE_TRANSACTIONS_SECONDS, DIEM_EXECUTOR_TRANSACTIONS_SAVED,
-        DIEM_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS,
+        DIEM_EXECUTOR_APPLY_AND_COMMIT_CHUNK_SECONDS, DIEM_EXECUTOR_COMMIT_BLOCKS_SECONDS,
+        DIEM_EXECUTOR_ERRORS, DIEM_EXECUTOR_EXECUTE_AND_COMMIT_CHUNK_SECONDS,
+        DIEM_EXECUTOR_EXECUTE_BLOCK_SECONDS, DIEM_EXECUTOR_SAVE_TRANSACTIONS_SECONDS,
+        DIEM_EXECUTOR_TRANSACTIONS_SAVED, DIEM_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS,
     },
     speculation_cache::SpeculationCache,
     types::{ProcessedVMOutput, TransactionData},
 };
 use anyhow::{bail, ensure, format_err, Result};
-use diem_state_view::StateViewId;
+use diem_state_view::{StateView, StateViewId};
 use diem_vm::VMExecutor;
 use executor_types::{
     BlockExecutor, ChunkExecutor, Error, ExecutedTrees, ProofReader, StateComputeResult,
@@ -48,12 +48,14 @@ use mango_types::{
     contract_event::ContractEvent,
     epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
-    on_chain_config,
-    proof::accumulator::InMemoryAccumulator,
+    on_chain_config::{self, ValidatorBlsKeys},
+    proof::{accumulator::InMemoryAccumulator, TransactionListProof},
     transaction::{
         Transaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
-        TransactionPayload, TransactionStatus, TransactionToCommit, Version,
+        TransactionOutputListWithProof, TransactionPayload, TransactionStatus, TransactionToCommit,
+        Version,
     },
+    validator_verifier::ValidatorVerifier,
     write_set::{WriteOp, WriteSet},
 };
 use std::{
@@ -178,17 +180,76 @@ where
             txn_list_with_proof.first_transaction_version,
         )?;
 
-        // Return empty if there's no work to do.
-        if txn_list_with_proof.transactions.is_empty() {
-            return Ok((Vec::new(), Vec::new()));
+        // 2. Verify that skipped transactions match what's already persisted (no fork).
+        let num_txns_to_skip = self.verify_skipped_transactions(
+            txn_list_with_proof.first_transaction_version,
+            txn_list_with_proof.transactions.len(),
+            &txn_list_with_proof.proof,
+        )?;
+
+        // 3. Return verified transactions to be applied.
+        let mut txns: Vec<_> = txn_list_with_proof.transactions;
+        txns.drain(0..num_txns_to_skip);
+        let (_, mut txn_infos) = txn_list_with_proof.proof.unpack();
+        txn_infos.drain(0..num_txns_to_skip);
+
+        Ok((txns, txn_infos))
+    }
+
+    /// Verify input output chunk and return transactions and outputs to be applied, skipping
+    /// those already persisted. Same as `verify_chunk`, except that the outputs are additionally
+    /// verified against the transaction infos in the proof.
+    fn verify_output_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+    ) -> Result<(
+        Vec<Transaction>,
+        Vec<TransactionOutput>,
+        Vec<TransactionInfo>,
+    )> {
+        // 1. Verify that input outputs belongs to the ledger represented by the ledger info.
+        txn_output_list_with_proof.verify(
+            verified_target_li.ledger_info(),
+            txn_output_list_with_proof.first_transaction_output_version,
+        )?;
+
+        // 2. Verify that skipped transactions match what's already persisted (no fork).
+        let num_txns_to_skip = self.verify_skipped_transactions(
+            txn_output_list_with_proof.first_transaction_output_version,
+            txn_output_list_with_proof.len(),
+            &txn_output_list_with_proof.proof,
+        )?;
+
+        // 3. Return verified transactions and outputs to be applied.
+        let (txns, outputs) = txn_output_list_with_proof
+            .transactions_and_outputs
+            .into_iter()
+            .skip(num_txns_to_skip)
+            .unzip();
+        let (_, mut txn_infos) = txn_output_list_with_proof.proof.unpack();
+        txn_infos.drain(0..num_txns_to_skip);
+
+        Ok((txns, outputs, txn_infos))
+    }
+
+    /// Given a verified chunk of `num_txns` transactions starting at `first_txn_version`, verify
+    /// that the ones already persisted match the synced state (no fork) and return how many of
+    /// them should be skipped.
+    fn verify_skipped_transactions(
+        &self,
+        first_txn_version: Option<Version>,
+        num_txns: usize,
+        proof: &TransactionListProof,
+    ) -> Result<usize> {
+        // Nothing to skip if there's no work to do.
+        if num_txns == 0 {
+            return Ok(0);
         }
-        let first_txn_version = match txn_list_with_proof.first_transaction_version {
+        let first_txn_version = match first_txn_version {
             Some(tx) => tx as Version,
             None => {
-                bail!(
-                    "first_transaction_version doesn't exist in {:?}",
-                    txn_list_with_proof
-                );
+                bail!("first_transaction_version doesn't exist in a non-empty chunk");
             }
         };
         let read_lock = self.cache.read();
@@ -201,12 +262,11 @@ where
             first_txn_version
         );
         let versions_between_first_and_committed = num_committed_txns - first_txn_version;
-        if txn_list_with_proof.transactions.len() <= versions_between_first_and_committed as usize {
+        if num_txns <= versions_between_first_and_committed as usize {
             // All already in DB, nothing to do.
-            return Ok((Vec::new(), Vec::new()));
+            return Ok(num_txns);
         }
 
-        // 2. Verify that skipped transactions match what's already persisted (no fork):
         let num_txns_to_skip = num_committed_txns - first_txn_version;
 
         debug!(
@@ -215,13 +275,11 @@ where
         );
 
         // If the proof is verified, then the length of txn_infos and txns must be the same.
-        let skipped_transaction_infos =
-            &txn_list_with_proof.proof.transaction_infos()[..num_txns_to_skip as usize];
+        let skipped_transaction_infos = &proof.transaction_infos()[..num_txns_to_skip as usize];
 
         // Left side of the proof happens to be the frozen subtree roots of the accumulator
         // right before the list of txns are applied.
-        let frozen_subtree_roots_from_proof = txn_list_with_proof
-            .proof
+        let frozen_subtree_roots_from_proof = proof
             .left_siblings()
             .iter()
             .rev()
@@ -243,13 +301,7 @@ where
             "Fork happens because the current synced_trees doesn't match the txn list provided."
         );
 
-        // 3. Return verified transactions to be applied.
-        let mut txns: Vec<_> = txn_list_with_proof.transactions;
-        txns.drain(0..num_txns_to_skip as usize);
-        let (_, mut txn_infos) = txn_list_with_proof.proof.unpack();
-        txn_infos.drain(0..num_txns_to_skip as usize);
-
-        Ok((txns, txn_infos))
+        Ok(num_txns_to_skip as usize)
     }
 
     /// Post-processing of what the VM outputs. Returns the entire block's output.
@@ -354,6 +406,7 @@ where
             txn_data.push(TransactionData::new(
                 blobs,
                 new_node_hashes,
+                vm_output.write_set().clone(),
                 vm_output.events().to_vec(),
                 vm_output.status().clone(),
                 state_tree_hash,
@@ -371,6 +424,7 @@ where
                 TransactionData::new(
                     HashMap::new(),
                     HashMap::new(),
+                    WriteSet::default(),
                     vec![],
                     TransactionStatus::Retry,
                     current_state_tree.root_hash(),
@@ -396,9 +450,17 @@ where
                         .ok_or_else(|| format_err!("Configuration does not exist"))
                 })
                 .ok_or_else(|| format_err!("Association account does not exist"))??;
+            // Validators without a valid BLS key keep signing with Ed25519 only.
+            let bls_keys = account_to_state
+                .get(&on_chain_config::config_address())
+                .map(|state| state.get_config::<ValidatorBlsKeys>())
+                .transpose()?
+                .flatten()
+                .unwrap_or_default();
             Some(EpochState {
                 epoch: configuration.epoch(),
-                verifier: (&validator_set).into(),
+                verifier: ValidatorVerifier::from(&validator_set)
+                    .with_bls_public_keys(&bls_keys.verified_public_keys()),
             })
         } else {
             None
@@ -491,6 +553,37 @@ where
         });
         let vm_outputs = V::execute_block(transactions.clone(), &state_view)?;
 
+        let (account_to_state, account_to_proof) = state_view.into();
+
+        Self::process_chunk_outputs(
+            first_version,
+            transactions,
+            transaction_infos,
+            account_to_state,
+            account_to_proof,
+            vm_outputs,
+            read_lock.synced_trees(),
+        )
+    }
+
+    /// Post-processing of the outputs of a chunk of transactions on top of the synced trees.
+    /// Verifies that each TransactionInfo computed locally matches the one from the proof, and
+    /// returns the transactions to commit along with those that need to be retried.
+    fn process_chunk_outputs(
+        first_version: u64,
+        transactions: Vec<Transaction>,
+        transaction_infos: Vec<TransactionInfo>,
+        account_to_state: HashMap<AccountAddress, AccountState>,
+        account_to_proof: HashMap<HashValue, SparseMerkleProof>,
+        vm_outputs: Vec<TransactionOutput>,
+        synced_trees: &ExecutedTrees,
+    ) -> Result<(
+        ProcessedVMOutput,
+        Vec<TransactionToCommit>,
+        Vec<ContractEvent>,
+        Vec<Transaction>,
+        Vec<TransactionInfo>,
+    )> {
         // Since other validators have committed these transactions, their status should all be
         // TransactionStatus::Keep.
         for output in &vm_outputs {
@@ -499,14 +592,12 @@ where
             }
         }
 
-        let (account_to_state, account_to_proof) = state_view.into();
-
         let output = Self::process_vm_outputs(
             account_to_state,
             account_to_proof,
             &transactions,
             vm_outputs,
-            read_lock.synced_trees(),
+            synced_trees,
         )?;
 
         // Since we have verified the proofs, we just need to verify that each TransactionInfo
@@ -553,6 +644,7 @@ where
                 txn,
                 txn_data.account_blobs().clone(),
                 Some(txn_data.jf_node_hashes().clone()),
+                txn_data.write_set().clone(),
                 txn_data.events().to_vec(),
                 txn_data.gas_used(),
                 recorded_status,
@@ -596,6 +688,111 @@ where
 
         Ok((processed_vm_output, txns_to_commit, events))
     }
+
+    fn apply_chunk(
+        &self,
+        first_version: u64,
+        transactions: Vec<Transaction>,
+        outputs: Vec<TransactionOutput>,
+        transaction_infos: Vec<TransactionInfo>,
+    ) -> Result<(
+        ProcessedVMOutput,
+        Vec<TransactionToCommit>,
+        Vec<ContractEvent>,
+    )> {
+        let num_txns = transactions.len();
+
+        let read_lock = self.cache.read();
+        let state_view = VerifiedStateView::new(
+            StateViewId::ChunkExecution { first_version },
+            Arc::clone(&self.db.reader),
+            read_lock.synced_trees().version(),
+            read_lock.synced_trees().state_root(),
+            read_lock.synced_trees().state_tree(),
+        );
+
+        // Read every account touched by the write sets, so that their current states (and the
+        // proofs of them) are available when the writes are applied, as if the VM had run.
+        for output in &outputs {
+            for (access_path, _) in output.write_set() {
+                state_view.get(access_path)?;
+            }
+        }
+        let (account_to_state, account_to_proof) = state_view.into();
+
+        let (processed_vm_output, txns_to_commit, events, txns_to_retry, _txn_infos_to_retry) =
+            Self::process_chunk_outputs(
+                first_version,
+                transactions,
+                transaction_infos,
+                account_to_state,
+                account_to_proof,
+                outputs,
+                read_lock.synced_trees(),
+            )?;
+
+        ensure!(
+            txns_to_retry.is_empty(),
+            "The transaction output at version {} got the status of 'Retry'",
+            num_txns
+                .checked_sub(txns_to_retry.len())
+                .ok_or_else(|| format_err!("integer overflow occurred"))?
+                .checked_add(first_version as usize)
+                .ok_or_else(|| format_err!("integer overflow occurred"))?,
+        );
+
+        Ok((processed_vm_output, txns_to_commit, events))
+    }
+
+    /// Commits the result of a chunk to DB, together with the ledger info it ends with (if
+    /// any), and brings the cache up to date.
+    fn commit_chunk(
+        &self,
+        first_version: Version,
+        output: ProcessedVMOutput,
+        txns_to_commit: Vec<TransactionToCommit>,
+        verified_target_li: LedgerInfoWithSignatures,
+        epoch_change_li: Option<LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        // 1. Commit to DB.
+        let ledger_info_to_commit =
+            Self::find_chunk_li(verified_target_li, epoch_change_li, &output)?;
+        if ledger_info_to_commit.is_none() && txns_to_commit.is_empty() {
+            return Ok(());
+        }
+        fail_point!("executor::commit_chunk", |_| {
+            Err(anyhow::anyhow!("Injected error in commit_chunk"))
+        });
+        self.db.writer.save_transactions(
+            &txns_to_commit,
+            first_version,
+            ledger_info_to_commit.as_ref(),
+        )?;
+
+        // 2. Cache maintenance.
+        let mut write_lock = self.cache.write();
+        let output_trees = output.executed_trees().clone();
+        if let Some(ledger_info_with_sigs) = &ledger_info_to_commit {
+            write_lock.update_block_tree_root(output_trees, ledger_info_with_sigs.ledger_info());
+        } else {
+            write_lock.update_synced_trees(output_trees);
+        }
+        write_lock.reset();
+
+        info!(
+            LogSchema::new(LogEntry::ChunkExecutor)
+                .synced_to_version(
+                    write_lock
+                        .synced_trees()
+                        .version()
+                        .expect("version must exist")
+                )
+                .committed_with_ledger_info(ledger_info_to_commit.is_some()),
+            "sync_finished",
+        );
+
+        Ok(())
+    }
 }
 
 impl<V: VMExecutor> ChunkExecutor for Executor<V> {
@@ -631,43 +828,61 @@ impl<V: VMExecutor> ChunkExecutor for Executor<V> {
         let (output, txns_to_commit, events) =
             self.execute_chunk(first_version, transactions, transaction_infos)?;
 
-        // 4. Commit to DB.
-        let ledger_info_to_commit =
-            Self::find_chunk_li(verified_target_li, epoch_change_li, &output)?;
-        if ledger_info_to_commit.is_none() && txns_to_commit.is_empty() {
-            return Ok(events);
-        }
-        fail_point!("executor::commit_chunk", |_| {
-            Err(anyhow::anyhow!("Injected error in commit_chunk"))
-        });
-        self.db.writer.save_transactions(
-            &txns_to_commit,
+        // 4. Commit to DB and update the cache.
+        self.commit_chunk(
             first_version,
-            ledger_info_to_commit.as_ref(),
+            output,
+            txns_to_commit,
+            verified_target_li,
+            epoch_change_li,
         )?;
 
-        // 5. Cache maintenance.
-        let mut write_lock = self.cache.write();
-        let output_trees = output.executed_trees().clone();
-        if let Some(ledger_info_with_sigs) = &ledger_info_to_commit {
-            write_lock.update_block_tree_root(output_trees, ledger_info_with_sigs.ledger_info());
-        } else {
-            write_lock.update_synced_trees(output_trees);
-        }
-        write_lock.reset();
+        Ok(events)
+    }
+
+    fn apply_and_commit_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        // Target LI that has been verified independently: the proofs are relative to this version.
+        verified_target_li: LedgerInfoWithSignatures,
+        // An optional end of epoch LedgerInfo. We do not allow chunks that end epoch without
+        // carrying any epoch change LI.
+        epoch_change_li: Option<LedgerInfoWithSignatures>,
+    ) -> Result<Vec<ContractEvent>> {
+        let _timer = DIEM_EXECUTOR_APPLY_AND_COMMIT_CHUNK_SECONDS.start_timer();
+        // 1. Update the cache in executor to be consistent with latest synced state.
+        self.reset_cache()?;
+        let read_lock = self.cache.read();
 
         info!(
             LogSchema::new(LogEntry::ChunkExecutor)
-                .synced_to_version(
-                    write_lock
-                        .synced_trees()
-                        .version()
-                        .expect("version must exist")
+                .local_synced_version(read_lock.synced_trees().txn_accumulator().num_leaves() - 1)
+                .first_version_in_request(
+                    txn_output_list_with_proof.first_transaction_output_version
                 )
-                .committed_with_ledger_info(ledger_info_to_commit.is_some()),
-            "sync_finished",
+                .num_txns_in_request(txn_output_list_with_proof.len()),
+            "apply_request_received",
         );
 
+        // 2. Verify input transaction output list.
+        let (transactions, outputs, transaction_infos) =
+            self.verify_output_chunk(txn_output_list_with_proof, &verified_target_li)?;
+
+        // 3. Apply transaction outputs.
+        let first_version = read_lock.synced_trees().txn_accumulator().num_leaves();
+        drop(read_lock);
+        let (output, txns_to_commit, events) =
+            self.apply_chunk(first_version, transactions, outputs, transaction_infos)?;
+
+        // 4. Commit to DB and update the cache.
+        self.commit_chunk(
+            first_version,
+            output,
+            txns_to_commit,
+            verified_target_li,
+            epoch_change_li,
+        )?;
+
         Ok(events)
     }
 }
@@ -890,6 +1105,7 @@ impl<V: VMExecutor> BlockExecutor for Executor<V> {
                     txn.clone(),
                     txn_data.account_blobs().clone(),
                     Some(txn_data.jf_node_hashes().clone()),
+                    txn_data.write_set().clone(),
                     txn_data.events().to_vec(),
                     txn_data.gas_used(),
                     recorded_status.clone(),
@@ -996,7 +1212,7 @@ pub fn process_write_set(
                 // maybe other writeset transactions).
                 match transaction {
                     Transaction::GenesisTransaction(_) => (),
-                    Transaction::BlockMetadata(_) => {
+                    Transaction::BlockMetadata(_) | Transaction::BlockMetadataExt(_) => {
                         bail!("Write set should be a subset of read set.")
                     }
                     Transaction::UserTransaction(txn) => match txn.payload() {
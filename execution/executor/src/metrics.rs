@@ -14,6 +14,16 @@ pub static DIEM_EXECUTOR_EXECUTE_AND_COMMIT_CHUNK_SECONDS: Lazy<Histogram> = Laz
     .unwrap()
 });
 
+pub static DIEM_EXECUTOR_APPLY_AND_COMMIT_CHUNK_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "diem_executor_apply_and_commit_chunk_seconds",
+        // metric description
+        "The time spent in seconds of applying and committing transaction outputs in mango executor"
+    )
+    .unwrap()
+});
+
 pub static DIEM_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
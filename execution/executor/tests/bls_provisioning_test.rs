@@ -0,0 +1,101 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use consensus_types::{
+    block::Block,
+    common::Payload,
+    quorum_cert::QuorumCert,
+    vote_proposal::{MaybeSignedVoteProposal, VoteProposal},
+};
+use diem_genesis_tool::validator_builder::ValidatorBuilder;
+use diem_global_constants::{BLS_CONSENSUS_KEY, CONSENSUS_KEY, OWNER_ACCOUNT};
+use diem_secure_storage::{CryptoStorage, KVStorage, Storage};
+use diem_vm::DiemVM;
+use diemdb::DiemDB;
+use executor::db_bootstrapper::maybe_bootstrap;
+use mango_crypto::{bls12381::Bls12381PrivateKey, PrivateKey, Signature};
+use mango_temppath::TempPath;
+use mango_types::{
+    account_address::AccountAddress, proof::AccumulatorExtensionProof,
+    validator_signer::ValidatorSigner,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use safety_rules::{PersistentSafetyStorage, SafetyRules, TSafetyRules};
+use std::{convert::TryFrom, num::NonZeroUsize};
+use storage_interface::{DbReader, DbReaderWriter};
+
+/// Provisions a validator through the genesis ceremony and checks that the BLS key it was given
+/// is registered on-chain and used by safety rules to sign votes.
+#[test]
+fn test_bls_key_provisioning_to_signing() {
+    let config_dir = TempPath::new();
+    config_dir.create_as_dir().unwrap();
+    let (_root_keys, genesis_txn, waypoint, validators) = ValidatorBuilder::new(
+        config_dir.path(),
+        diem_framework_releases::current_module_blobs().to_vec(),
+    )
+    .num_validators(NonZeroUsize::new(1).unwrap())
+    .build(StdRng::from_seed([0u8; 32]))
+    .unwrap();
+    let config = &validators[0].config;
+
+    let tmp_dir = TempPath::new();
+    let db_rw = DbReaderWriter::new(DiemDB::new_for_test(&tmp_dir));
+    maybe_bootstrap::<DiemVM>(&db_rw, &genesis_txn, waypoint).unwrap();
+
+    // The key in the validator's storage is the one genesis registered on-chain
+    let storage = Storage::try_from(&config.consensus.safety_rules.backend).unwrap();
+    let author: AccountAddress = storage.get(OWNER_ACCOUNT).unwrap().value;
+    let bls_key: Bls12381PrivateKey = storage.get(BLS_CONSENSUS_KEY).unwrap().value;
+    let consensus_key = storage.export_private_key(CONSENSUS_KEY).unwrap();
+
+    let epoch_change_proof = db_rw.reader.get_epoch_ending_ledger_infos(0, 1).unwrap();
+    let genesis_li = epoch_change_proof.ledger_info_with_sigs[0].ledger_info();
+    let verifier = &genesis_li.next_epoch_state().unwrap().verifier;
+    assert_eq!(
+        verifier.get_bls_public_key(&author),
+        Some(bls_key.public_key())
+    );
+
+    // Safety rules picks the key up from storage and signs the vote with it
+    let mut safety_rules = SafetyRules::new(
+        PersistentSafetyStorage::new(storage, false),
+        false,
+        false,
+        true,
+    );
+    safety_rules.initialize(&epoch_change_proof).unwrap();
+
+    let genesis_block = Block::make_genesis_block_from_ledger_info(genesis_li);
+    let genesis_qc =
+        QuorumCert::certificate_for_genesis_from_ledger_info(genesis_li, genesis_block.id());
+    let signer = ValidatorSigner::new(author, consensus_key);
+    let proposal = Block::new_proposal(
+        Payload::empty(),
+        1,
+        genesis_qc.certified_block().timestamp_usecs() + 1,
+        genesis_qc,
+        &signer,
+    );
+    let vote_proposal = MaybeSignedVoteProposal {
+        vote_proposal: VoteProposal::new(
+            AccumulatorExtensionProof::new(vec![], 0, vec![]),
+            proposal,
+            None,
+        ),
+        signature: None,
+    };
+
+    let vote = safety_rules
+        .construct_and_sign_vote(&vote_proposal)
+        .unwrap();
+    let bls_signature = safety_rules.sign_vote_bls(&vote).unwrap();
+    bls_signature
+        .verify(
+            vote.ledger_info(),
+            &verifier.get_bls_public_key(&author).unwrap(),
+        )
+        .unwrap();
+}
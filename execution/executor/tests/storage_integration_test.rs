@@ -16,12 +16,13 @@ use mango_crypto::{ed25519::*, PrivateKey, Uniform};
 use mango_types::{
     account_config::{diem_root_address, treasury_compliance_account_address, xus_tag},
     account_state::AccountState,
-    block_metadata::BlockMetadata,
-    transaction::{Script, Transaction, TransactionPayload, WriteSetPayload},
+    block_metadata::{new_block_event_key, BlockMetadata, BlockMetadataExt, NewBlockEvent},
+    transaction::{Script, Transaction, TransactionPayload, TransactionStatus, WriteSetPayload},
     trusted_state::TrustedState,
     validator_signer::ValidatorSigner,
 };
 use std::convert::TryFrom;
+use storage_interface::Order;
 
 #[test]
 fn test_genesis() {
@@ -412,6 +413,52 @@ fn test_change_publishing_option_to_custom() {
     verify_committed_txn_status(txn3.as_ref(), &block2[1]).unwrap();
 }
 
+#[test]
+fn test_block_prologue_with_failed_proposers() {
+    let path = mango_temppath::TempPath::new();
+    path.create_as_dir().unwrap();
+    let (genesis, validators) = vm_genesis::test_genesis_change_set_and_validators(Some(1));
+    let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
+    let (_, db, executor, _waypoint) = create_db_and_executor(path.path(), &genesis_txn);
+    let parent_block_id = executor.committed_block_id();
+    let signer = ValidatorSigner::new(validators[0].data.address, validators[0].key.clone());
+    let validator_account = signer.author();
+
+    // A block without failed proposers keeps the original layout, the next one records the
+    // proposer of its failed round.
+    let txn1 = Transaction::BlockMetadata(gen_block_metadata(1, validator_account));
+    let txn2 = Transaction::BlockMetadataExt(BlockMetadataExt::new(
+        gen_block_metadata(3, validator_account),
+        vec![validator_account],
+    ));
+    let block_id = gen_block_id(1);
+    let output = executor
+        .execute_block((block_id, vec![txn1, txn2]), parent_block_id)
+        .unwrap();
+    assert!(output
+        .compute_status()
+        .iter()
+        .all(|status| matches!(status, TransactionStatus::Keep(_))));
+    let ledger_info_with_sigs = gen_ledger_info_with_sigs(1, &output, block_id, vec![&signer]);
+    executor
+        .commit_blocks(vec![block_id], ledger_info_with_sigs)
+        .unwrap();
+
+    let events = db
+        .reader
+        .get_events(&new_block_event_key(), 0, Order::Ascending, 10)
+        .unwrap();
+    let events: Vec<_> = events
+        .iter()
+        .map(|(_, event)| NewBlockEvent::try_from_bytes(event.event_data()).unwrap())
+        .collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].round(), 1);
+    assert!(events[0].failed_proposers().is_empty());
+    assert_eq!(events[1].round(), 3);
+    assert_eq!(events[1].failed_proposers(), &[validator_account]);
+}
+
 #[test]
 fn test_execution_with_storage() {
     test_execution_with_storage_impl();
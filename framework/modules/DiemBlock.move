@@ -5,6 +5,7 @@ module DiemFramework::DiemBlock {
     use DiemFramework::DiemTimestamp;
     use Std::Errors;
     use Std::Event;
+    use Std::Vector;
 
     struct BlockMetadata has key {
         /// Height of the current block
@@ -20,6 +21,8 @@ module DiemFramework::DiemBlock {
 
         /// On-chain time during  he block at the given height
         time_microseconds: u64,
+        /// Proposers of the rounds since the previous block that failed to produce a block
+        failed_proposers: vector<address>,
     }
 
     /// The `BlockMetadata` resource is in an invalid state
@@ -64,6 +67,31 @@ module DiemFramework::DiemBlock {
         timestamp: u64,
         previous_block_votes: vector<address>,
         proposer: address
+    ) acquires BlockMetadata {
+        block_prologue_ext(vm, round, timestamp, previous_block_votes, proposer, Vector::empty())
+    }
+    spec block_prologue {
+        include DiemTimestamp::AbortsIfNotOperating;
+        include CoreAddresses::AbortsIfNotVM{account: vm};
+        aborts_if proposer != @VMReserved && !DiemSystem::spec_is_validator(proposer)
+            with Errors::REQUIRES_ADDRESS;
+        ensures DiemTimestamp::spec_now_microseconds() == timestamp;
+        ensures get_current_block_height() == old(get_current_block_height()) + 1;
+
+        aborts_if get_current_block_height() + 1 > MAX_U64 with EXECUTION_FAILURE;
+        include BlockPrologueEmits{failed_proposers: vec()};
+    }
+
+    /// Set the metadata for the current block, recording the proposers of the rounds since the
+    /// previous block that failed to produce a block.
+    /// The runtime runs this instead of `block_prologue` for the blocks recording failed proposers.
+    fun block_prologue_ext(
+        vm: signer,
+        round: u64,
+        timestamp: u64,
+        previous_block_votes: vector<address>,
+        proposer: address,
+        failed_proposers: vector<address>
     ) acquires BlockMetadata {
         DiemTimestamp::assert_operating();
         // Operational constraint: can only be invoked by the VM.
@@ -85,10 +113,11 @@ module DiemFramework::DiemBlock {
                 proposer,
                 previous_block_votes,
                 time_microseconds: timestamp,
+                failed_proposers,
             }
         );
     }
-    spec block_prologue {
+    spec block_prologue_ext {
         include DiemTimestamp::AbortsIfNotOperating;
         include CoreAddresses::AbortsIfNotVM{account: vm};
         aborts_if proposer != @VMReserved && !DiemSystem::spec_is_validator(proposer)
@@ -104,12 +133,14 @@ module DiemFramework::DiemBlock {
         timestamp: u64;
         previous_block_votes: vector<address>;
         proposer: address;
+        failed_proposers: vector<address>;
         let handle = global<BlockMetadata>(@DiemRoot).new_block_events;
         let msg = NewBlockEvent {
             round,
             proposer,
             previous_block_votes,
             time_microseconds: timestamp,
+            failed_proposers,
         };
         emits msg to handle;
     }
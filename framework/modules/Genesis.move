@@ -9,7 +9,7 @@ module DiemFramework::Genesis {
     use DiemFramework::XUS;
     use DiemFramework::DualAttestation;
     use DiemFramework::XDX;
-    use DiemFramework::mango;
+    use DiemFramework::Diem;
     use DiemFramework::DiemAccount;
     use DiemFramework::DiemBlock;
     use DiemFramework::DiemConfig;
@@ -20,6 +20,7 @@ module DiemFramework::Genesis {
     use DiemFramework::DiemVersion;
     use DiemFramework::TransactionFee;
     use DiemFramework::DiemVMConfig;
+    use DiemFramework::ValidatorBlsKeys;
     use DiemFramework::ValidatorConfig;
     use DiemFramework::ValidatorOperatorConfig;
     use Std::Signer;
@@ -74,9 +75,10 @@ module DiemFramework::Genesis {
 
         // Consensus config setup
         DiemConsensusConfig::initialize(dr_account);
+        ValidatorBlsKeys::initialize(dr_account);
 
         // Currency setup
-        mango::initialize(dr_account);
+        Diem::initialize(dr_account);
 
         // Currency setup
         XUS::initialize(dr_account, tc_account);
@@ -2,6 +2,7 @@ address 0x1 {
 module ValidatorAdministrationScripts {
     use DiemFramework::DiemSystem;
     use DiemFramework::SlidingNonce;
+    use DiemFramework::ValidatorBlsKeys;
     use DiemFramework::ValidatorConfig;
     use DiemFramework::ValidatorOperatorConfig;
 
@@ -549,5 +550,41 @@ module ValidatorAdministrationScripts {
         /// Only a Validator account can set its Validator Operator [[H16]][PERMISSION].
         include Roles::AbortsIfNotValidator;
     }
+
+    /// # Summary
+    /// Registers or rotates the BLS12-381 consensus public key of a validator, and triggers a
+    /// reconfiguration of the system. This transaction can only be sent by the validator's
+    /// operator. The key is only used once aggregated signatures are enabled on-chain.
+    ///
+    /// # Parameters
+    /// | Name                          | Type         | Description                                                                                                        |
+    /// | ------                        | ------       | -------------                                                                                                      |
+    /// | `validator_operator_account`  | `signer`     | Signer of the sending account. Must be the registered validator operator for the validator at `validator_address`. |
+    /// | `validator_account`           | `address`    | The address of the validator whose BLS public key is being set.                                                    |
+    /// | `bls_public_key`              | `vector<u8>` | Compressed BLS12-381 public key.                                                                                   |
+    /// | `proof_of_possession`         | `vector<u8>` | Proof of possession of the private key corresponding to `bls_public_key`.                                          |
+    ///
+    /// # Common Abort Conditions
+    /// | Error Category             | Error Reason                                        | Description                                                                                           |
+    /// | ----------------           | --------------                                      | -------------                                                                                         |
+    /// | `Errors::INVALID_ARGUMENT` | `ValidatorBlsKeys::EINVALID_TRANSACTION_SENDER`     | `validator_operator_account` is not the registered operator for the validator at `validator_address`. |
+    /// | `Errors::INVALID_ARGUMENT` | `ValidatorBlsKeys::EINVALID_PUBLIC_KEY`             | `bls_public_key` is not 48 bytes long.                                                                |
+    /// | `Errors::INVALID_ARGUMENT` | `ValidatorBlsKeys::EINVALID_PROOF_OF_POSSESSION`    | `proof_of_possession` is not 96 bytes long.                                                           |
+    ///
+    /// # Related Scripts
+    /// * `ValidatorAdministrationScripts::set_validator_config_and_reconfigure`
+    public(script) fun set_validator_bls_key_and_reconfigure(
+        validator_operator_account: signer,
+        validator_account: address,
+        bls_public_key: vector<u8>,
+        proof_of_possession: vector<u8>,
+    ) {
+        ValidatorBlsKeys::set_bls_key(
+            &validator_operator_account,
+            validator_account,
+            bls_public_key,
+            proof_of_possession
+        );
+    }
 }
 }
@@ -0,0 +1,124 @@
+/// Maintains the BLS12-381 consensus public keys registered by validators. The keys are stored
+/// in a DiemConfig so that every change triggers a reconfiguration and reaches consensus at the
+/// start of the next epoch. They are only used once aggregated signatures are enabled in the
+/// `DiemConsensusConfig::DiemConsensusConfig`.
+module DiemFramework::ValidatorBlsKeys {
+    use DiemFramework::DiemConfig::{Self, ModifyConfigCapability};
+    use DiemFramework::DiemTimestamp;
+    use DiemFramework::Roles;
+    use DiemFramework::ValidatorConfig;
+    use Std::Errors;
+    use Std::Signer;
+    use Std::Vector;
+
+    struct ValidatorBlsKey has copy, drop, store {
+        /// The address of the validator owning the key
+        addr: address,
+        /// Compressed BLS12-381 public key (a G1 point)
+        public_key: vector<u8>,
+        /// Signature of `public_key` under the proof-of-possession domain separation tag
+        proof_of_possession: vector<u8>,
+    }
+
+    struct ValidatorBlsKeys has copy, drop, store {
+        keys: vector<ValidatorBlsKey>,
+    }
+
+    /// Holds the capability returned by `DiemConfig::publish_new_config_and_get_capability`,
+    /// which allows validator operators to update their own key through `set_bls_key`.
+    struct CapabilityHolder has key {
+        cap: ModifyConfigCapability<ValidatorBlsKeys>,
+    }
+
+    /// The `CapabilityHolder` resource was not in the required state
+    const ECAPABILITY_HOLDER: u64 = 0;
+    /// The sender is not the operator for the specified validator
+    const EINVALID_TRANSACTION_SENDER: u64 = 1;
+    /// The provided BLS public key has the wrong length
+    const EINVALID_PUBLIC_KEY: u64 = 2;
+    /// The provided proof of possession has the wrong length
+    const EINVALID_PROOF_OF_POSSESSION: u64 = 3;
+
+    /// Length of a compressed BLS12-381 public key
+    const BLS_PUBLIC_KEY_LENGTH: u64 = 48;
+    /// Length of a compressed BLS12-381 signature
+    const BLS_SIGNATURE_LENGTH: u64 = 96;
+
+    /// Publishes an empty ValidatorBlsKeys config along with the `CapabilityHolder`.
+    /// Must be invoked by the mango root a single time in Genesis.
+    public fun initialize(dr_account: &signer) {
+        DiemTimestamp::assert_genesis();
+        Roles::assert_diem_root(dr_account);
+        let cap = DiemConfig::publish_new_config_and_get_capability<ValidatorBlsKeys>(
+            dr_account,
+            ValidatorBlsKeys { keys: Vector::empty() },
+        );
+        assert(
+            !exists<CapabilityHolder>(@DiemRoot),
+            Errors::already_published(ECAPABILITY_HOLDER)
+        );
+        move_to(dr_account, CapabilityHolder { cap })
+    }
+    spec initialize {
+        include DiemTimestamp::AbortsIfNotGenesis;
+        include Roles::AbortsIfNotDiemRoot{account: dr_account};
+        aborts_if exists<CapabilityHolder>(@DiemRoot) with Errors::ALREADY_PUBLISHED;
+        ensures exists<CapabilityHolder>(@DiemRoot);
+    }
+
+    /// Registers or rotates the BLS public key of the validator at `validator_addr` and triggers
+    /// a reconfiguration. Only the validator's operator may do so. Genesis also calls it to
+    /// register the initial keys, in which case no reconfiguration event is emitted.
+    ///
+    /// The proof of possession cannot be checked in Move: nodes verify it when they build the
+    /// validator verifier for the new epoch and ignore keys whose proof does not verify.
+    public fun set_bls_key(
+        validator_operator_account: &signer,
+        validator_addr: address,
+        public_key: vector<u8>,
+        proof_of_possession: vector<u8>,
+    ) acquires CapabilityHolder {
+        assert(
+            Signer::address_of(validator_operator_account) == ValidatorConfig::get_operator(validator_addr),
+            Errors::invalid_argument(EINVALID_TRANSACTION_SENDER)
+        );
+        assert(
+            Vector::length(&public_key) == BLS_PUBLIC_KEY_LENGTH,
+            Errors::invalid_argument(EINVALID_PUBLIC_KEY)
+        );
+        assert(
+            Vector::length(&proof_of_possession) == BLS_SIGNATURE_LENGTH,
+            Errors::invalid_argument(EINVALID_PROOF_OF_POSSESSION)
+        );
+        assert(
+            exists<CapabilityHolder>(@DiemRoot),
+            Errors::not_published(ECAPABILITY_HOLDER)
+        );
+
+        let config = DiemConfig::get<ValidatorBlsKeys>();
+        let key = ValidatorBlsKey { addr: validator_addr, public_key, proof_of_possession };
+        let (found, i) = find_key_index(&config.keys, validator_addr);
+        if (found) {
+            *Vector::borrow_mut(&mut config.keys, i) = key;
+        } else {
+            Vector::push_back(&mut config.keys, key);
+        };
+        DiemConfig::set_with_capability_and_reconfigure<ValidatorBlsKeys>(
+            &borrow_global<CapabilityHolder>(@DiemRoot).cap,
+            config
+        )
+    }
+
+    /// Returns the index of the key registered for `addr`, if any.
+    fun find_key_index(keys: &vector<ValidatorBlsKey>, addr: address): (bool, u64) {
+        let i = 0;
+        let len = Vector::length(keys);
+        while (i < len) {
+            if (Vector::borrow(keys, i).addr == addr) {
+                return (true, i)
+            };
+            i = i + 1;
+        };
+        (false, 0)
+    }
+}
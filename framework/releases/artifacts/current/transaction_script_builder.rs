@@ -2771,6 +2771,34 @@ pub enum ScriptFunctionCall {
         default_account_size: u64,
     },
 
+    /// # Summary
+    /// Registers or rotates the BLS12-381 consensus public key of a validator, and triggers a
+    /// reconfiguration of the system. This transaction can only be sent by the validator's
+    /// operator. The key is only used once aggregated signatures are enabled on-chain.
+    ///
+    /// # Parameters
+    /// | Name                          | Type         | Description                                                                                                        |
+    /// | ------                        | ------       | -------------                                                                                                      |
+    /// | `validator_operator_account`  | `signer`     | Signer of the sending account. Must be the registered validator operator for the validator at `validator_address`. |
+    /// | `validator_account`           | `address`    | The address of the validator whose BLS public key is being set.                                                    |
+    /// | `bls_public_key`              | `vector<u8>` | Compressed BLS12-381 public key.                                                                                   |
+    /// | `proof_of_possession`         | `vector<u8>` | Proof of possession of the private key corresponding to `bls_public_key`.                                          |
+    ///
+    /// # Common Abort Conditions
+    /// | Error Category             | Error Reason                                        | Description                                                                                           |
+    /// | ----------------           | --------------                                      | -------------                                                                                         |
+    /// | `Errors::INVALID_ARGUMENT` | `ValidatorBlsKeys::EINVALID_TRANSACTION_SENDER`     | `validator_operator_account` is not the registered operator for the validator at `validator_address`. |
+    /// | `Errors::INVALID_ARGUMENT` | `ValidatorBlsKeys::EINVALID_PUBLIC_KEY`             | `bls_public_key` is not 48 bytes long.                                                                |
+    /// | `Errors::INVALID_ARGUMENT` | `ValidatorBlsKeys::EINVALID_PROOF_OF_POSSESSION`    | `proof_of_possession` is not 96 bytes long.                                                           |
+    ///
+    /// # Related Scripts
+    /// * `ValidatorAdministrationScripts::set_validator_config_and_reconfigure`
+    SetValidatorBlsKeyAndReconfigure {
+        validator_account: AccountAddress,
+        bls_public_key: Bytes,
+        proof_of_possession: Bytes,
+    },
+
     /// # Summary
     /// Updates a validator's configuration, and triggers a reconfiguration of the system to update the
     /// validator set with this new validator configuration.  Can only be successfully sent by a
@@ -3327,6 +3355,15 @@ impl ScriptCall {
             RotateSharedEd25519PublicKey { public_key } => {
                 encode_rotate_shared_ed25519_public_key_script(public_key)
             }
+            SetValidatorBlsKeyAndReconfigure {
+                validator_account,
+                bls_public_key,
+                proof_of_possession,
+            } => encode_set_validator_bls_key_and_reconfigure_script_function(
+                validator_account,
+                bls_public_key,
+                proof_of_possession,
+            ),
             SetValidatorConfigAndReconfigure {
                 validator_account,
                 consensus_pubkey,
@@ -5513,6 +5550,48 @@ pub fn encode_set_gas_constants_script_function(
     ))
 }
 
+/// # Summary
+/// Registers or rotates the BLS12-381 consensus public key of a validator, and triggers a
+/// reconfiguration of the system. This transaction can only be sent by the validator's
+/// operator. The key is only used once aggregated signatures are enabled on-chain.
+///
+/// # Parameters
+/// | Name                          | Type         | Description                                                                                                        |
+/// | ------                        | ------       | -------------                                                                                                      |
+/// | `validator_operator_account`  | `signer`     | Signer of the sending account. Must be the registered validator operator for the validator at `validator_address`. |
+/// | `validator_account`           | `address`    | The address of the validator whose BLS public key is being set.                                                    |
+/// | `bls_public_key`              | `vector<u8>` | Compressed BLS12-381 public key.                                                                                   |
+/// | `proof_of_possession`         | `vector<u8>` | Proof of possession of the private key corresponding to `bls_public_key`.                                          |
+///
+/// # Common Abort Conditions
+/// | Error Category             | Error Reason                                        | Description                                                                                           |
+/// | ----------------           | --------------                                      | -------------                                                                                         |
+/// | `Errors::INVALID_ARGUMENT` | `ValidatorBlsKeys::EINVALID_TRANSACTION_SENDER`     | `validator_operator_account` is not the registered operator for the validator at `validator_address`. |
+/// | `Errors::INVALID_ARGUMENT` | `ValidatorBlsKeys::EINVALID_PUBLIC_KEY`             | `bls_public_key` is not 48 bytes long.                                                                |
+/// | `Errors::INVALID_ARGUMENT` | `ValidatorBlsKeys::EINVALID_PROOF_OF_POSSESSION`    | `proof_of_possession` is not 96 bytes long.                                                           |
+///
+/// # Related Scripts
+/// * `ValidatorAdministrationScripts::set_validator_config_and_reconfigure`
+pub fn encode_set_validator_bls_key_and_reconfigure_script_function(
+    validator_account: AccountAddress,
+    bls_public_key: Vec<u8>,
+    proof_of_possession: Vec<u8>,
+) -> TransactionPayload {
+    TransactionPayload::ScriptFunction(ScriptFunction::new(
+        ModuleId::new(
+            AccountAddress::new([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+            ident_str!("ValidatorAdministrationScripts").to_owned(),
+        ),
+        ident_str!("set_validator_bls_key_and_reconfigure").to_owned(),
+        vec![],
+        vec![
+            bcs::to_bytes(&validator_account).unwrap(),
+            bcs::to_bytes(&bls_public_key).unwrap(),
+            bcs::to_bytes(&proof_of_possession).unwrap(),
+        ],
+    ))
+}
+
 /// # Summary
 /// Updates a validator's configuration, and triggers a reconfiguration of the system to update the
 /// validator set with this new validator configuration.  Can only be successfully sent by a
@@ -8162,6 +8241,20 @@ fn decode_set_gas_constants_script_function(
     }
 }
 
+fn decode_set_validator_bls_key_and_reconfigure_script_function(
+    payload: &TransactionPayload,
+) -> Option<ScriptFunctionCall> {
+    if let TransactionPayload::ScriptFunction(script) = payload {
+        Some(ScriptFunctionCall::SetValidatorBlsKeyAndReconfigure {
+            validator_account: bcs::from_bytes(script.args().get(0)?).ok()?,
+            bls_public_key: bcs::from_bytes(script.args().get(1)?).ok()?,
+            proof_of_possession: bcs::from_bytes(script.args().get(2)?).ok()?,
+        })
+    } else {
+        None
+    }
+}
+
 fn decode_set_validator_config_and_reconfigure_script_function(
     payload: &TransactionPayload,
 ) -> Option<ScriptFunctionCall> {
@@ -8832,6 +8925,10 @@ static SCRIPT_FUNCTION_DECODER_MAP: once_cell::sync::Lazy<ScriptFunctionDecoderM
             "SystemAdministrationScriptsset_gas_constants".to_string(),
             Box::new(decode_set_gas_constants_script_function),
         );
+        map.insert(
+            "ValidatorAdministrationScriptsset_validator_bls_key_and_reconfigure".to_string(),
+            Box::new(decode_set_validator_bls_key_and_reconfigure_script_function),
+        );
         map.insert(
             "ValidatorAdministrationScriptsset_validator_config_and_reconfigure".to_string(),
             Box::new(decode_set_validator_config_and_reconfigure_script_function),
@@ -1339,6 +1339,38 @@ impl PublicUsageTest for GetAccountTransactionsWithoutEvents {
     }
 }
 
+pub struct GetTransactionByHash;
+
+impl Test for GetTransactionByHash {
+    fn name(&self) -> &'static str {
+        "jsonrpc::get-transaction-by-hash"
+    }
+}
+
+impl PublicUsageTest for GetTransactionByHash {
+    fn run<'t>(&self, ctx: &mut PublicUsageContext<'t>) -> Result<()> {
+        let env = JsonRpcTestHelper::new(ctx.url().to_owned());
+        let response = env.send(
+            "get_account_transactions",
+            json!([treasury_compliance_account_address(), 0, 10, true]),
+        );
+        let txns = response.result.unwrap();
+        assert!(!txns.as_array().unwrap().is_empty());
+
+        for txn in txns.as_array().unwrap() {
+            let response = env.send("get_transaction_by_hash", json!([txn["hash"], true]));
+            assert_eq!(&response.result.unwrap(), txn);
+        }
+
+        let response = env.send(
+            "get_transaction_by_hash",
+            json!([HashValue::zero().to_hex(), false]),
+        );
+        assert_eq!(response.result.unwrap(), json!(null));
+        Ok(())
+    }
+}
+
 pub struct GetAccountTransactionsWithProofs;
 
 impl Test for GetAccountTransactionsWithProofs {
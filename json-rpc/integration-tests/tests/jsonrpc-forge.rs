@@ -25,6 +25,7 @@ fn main() -> Result<()> {
             &CreateAccountEvent,
             &GetTransactionsWithoutEvents,
             &GetAccountTransactionsWithoutEvents,
+            &GetTransactionByHash,
             &GetAccountTransactionsWithProofs,
             &GetTransactionsWithProofs,
             &GetTreasuryComplianceAccount,
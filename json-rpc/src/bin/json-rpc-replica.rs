@@ -0,0 +1,87 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Context, Result};
+use diemdb::DiemDB;
+use mango_config::config::NodeConfig;
+use mango_json_rpc::replica::bootstrap_replica;
+use mango_logger::{info, Logger};
+use mango_temppath::TempPath;
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, thread, time::Duration};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "json-rpc-replica",
+    about = "Serve the JSON-RPC API from a secondary instance of the DB of a running node."
+)]
+struct Opt {
+    /// Config of the node whose DB is served. Its storage and JSON-RPC settings are used.
+    #[structopt(short, long, parse(from_os_str))]
+    config: PathBuf,
+
+    /// Address to serve JSON-RPC on, overriding the one of the node config.
+    #[structopt(short, long)]
+    address: Option<SocketAddr>,
+
+    /// Directory for the files of the secondary instance. A temporary directory is used if not
+    /// provided.
+    #[structopt(long, parse(from_os_str))]
+    secondary_dir: Option<PathBuf>,
+
+    /// Interval at which the secondary instance catches up with the node.
+    #[structopt(long, default_value = "500")]
+    catch_up_interval_ms: u64,
+
+    /// JSON-RPC endpoint `submit` requests are forwarded to. Submission is disabled if not
+    /// provided.
+    #[structopt(long)]
+    upstream_url: Option<String>,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    let mut config = NodeConfig::load(&opt.config)
+        .with_context(|| format_err!("Failed to load node config."))?;
+    if let Some(address) = opt.address {
+        config.json_rpc.address = address;
+    }
+
+    Logger::new()
+        .channel_size(config.logger.chan_size)
+        .is_async(config.logger.is_async)
+        .level(config.logger.level)
+        .read_env()
+        .init();
+
+    let tmpdir;
+    let secondary_dir = match opt.secondary_dir {
+        Some(dir) => dir,
+        None => {
+            tmpdir = TempPath::new();
+            tmpdir.path().to_path_buf()
+        }
+    };
+    let db = DiemDB::open_as_secondary(
+        config.storage.dir(),
+        secondary_dir,
+        config.storage.rocksdb_config.clone(),
+    )
+    .with_context(|| format_err!("Failed to open DB."))?
+    .with_event_by_type_index(config.storage.enable_event_by_type_index);
+
+    let _runtime = bootstrap_replica(
+        &config,
+        Arc::new(db),
+        opt.upstream_url,
+        Duration::from_millis(opt.catch_up_interval_ms),
+    )?;
+    info!(
+        address = %config.json_rpc.address,
+        "JSON-RPC replica started."
+    );
+
+    loop {
+        thread::park();
+    }
+}
@@ -84,3 +84,52 @@ pub static METHOD_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Tokens allowed and throttled per second by a caller's rate limit bucket
+pub static RATE_LIMIT: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "mango_client_service_rate_limit",
+        "mango client service rate limiting histogram",
+        &[
+            "limiter", // ip / api_key
+            "metric"   // allowed / throttled
+        ]
+    )
+    .unwrap()
+});
+
+/// Cumulative number of calls rejected by rate limits
+pub static RATE_LIMITED_CALLS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "mango_client_service_rate_limited_calls_count",
+        "Cumulative number of calls that JSON RPC client service rejected for exceeding rate limits",
+        &[
+            "limiter", // ip / api_key
+            "method"   // JSON-RPC methods: submit, get_account ...
+        ]
+    )
+    .unwrap()
+});
+
+/// Cumulative number of requests that the REST API receives
+pub static REST_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "mango_client_service_rest_requests_count",
+        "Cumulative number of requests that the REST API of JSON RPC client service receives",
+        &[
+            "method",   // JSON-RPC method answering the request: get_account, get_events ...
+            "encoding", // encoding of the response: json / bcs
+            "status"    // HTTP status code of the response
+        ]
+    )
+    .unwrap()
+});
+
+pub static REST_REQUEST_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "mango_client_service_rest_request_latency_seconds",
+        "mango client service REST request latency histogram",
+        &["method"] // JSON-RPC method answering the request: get_account, get_events ...
+    )
+    .unwrap()
+});
@@ -11,11 +11,13 @@ use crate::{
     },
 };
 use anyhow::Result;
+use mango_crypto::HashValue;
 use mango_types::{
     account_address::AccountAddress, account_config::diem_root_address,
     account_state::AccountState, chain_id::ChainId, event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
 };
+use move_core_types::language_storage::TypeTag;
 use resource_viewer::{AnnotatedMoveStruct, MoveValueAnnotator};
 use std::{
     collections::BTreeMap,
@@ -149,6 +151,27 @@ pub fn get_account_transactions(
     Ok(txs.0)
 }
 
+/// Returns transaction by hash
+pub fn get_transaction_by_hash(
+    db: &dyn MoveDbReader,
+    hash: HashValue,
+    include_events: bool,
+    ledger_version: u64,
+) -> Result<Option<TransactionView>, JsonRpcError> {
+    let tx = db
+        .get_transaction_by_hash(hash, ledger_version, include_events)?
+        .map(|tx| {
+            TransactionView::try_from_tx_and_events(
+                tx.version,
+                tx.transaction,
+                tx.proof.transaction_info,
+                tx.events.unwrap_or_default(),
+            )
+        })
+        .transpose()?;
+    Ok(tx)
+}
+
 /// Return a serialized list of an account's transactions along with a proof for
 /// each transaction.
 pub fn get_account_transactions_with_proofs(
@@ -185,6 +208,32 @@ pub fn get_events(
     Ok(events)
 }
 
+/// Returns events of the given type in the given version range
+pub fn get_events_by_type(
+    db: &dyn MoveDbReader,
+    ledger_version: u64,
+    type_tag: &TypeTag,
+    start_version: u64,
+    start_index: u64,
+    end_version: u64,
+    limit: u64,
+) -> Result<Vec<EventView>, JsonRpcError> {
+    let events = db
+        .get_events_by_type(
+            type_tag,
+            start_version,
+            start_index,
+            end_version,
+            limit,
+            ledger_version,
+        )?
+        .into_iter()
+        .map(|event| event.try_into())
+        .collect::<Result<Vec<EventView>>>()?;
+
+    Ok(events)
+}
+
 /// Returns events by given access path along with their proofs
 pub fn get_events_with_proofs(
     db: &dyn MoveDbReader,
@@ -41,10 +41,21 @@ fn test_method_fuzzer() {
         &gen_request_params!(["000000000000000000000000000000dd", 0, 1, true]),
         "get_account_transactions",
     );
+    method_fuzzer(
+        &gen_request_params!([
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            true
+        ]),
+        "get_transaction_by_hash",
+    );
     method_fuzzer(
         &gen_request_params!(["00000000000000000000000000000000000000000a550c18", 0, 10]),
         "get_events",
     );
+    method_fuzzer(
+        &gen_request_params!(["0x1::DiemAccount::ReceivedPaymentEvent", 0, 100, 10, 0]),
+        "get_events_by_type",
+    );
     method_fuzzer(&gen_request_params!([]), "get_currencies");
     method_fuzzer(&gen_request_params!([]), "get_network_status");
     // TODO(philiphayes): fails because generated AccountStateWithProof doesn't
@@ -146,6 +157,8 @@ pub fn request_fuzzer(json_request: serde_json::Value) {
         mango_types::chain_id::ChainId::test(),
         config::DEFAULT_BATCH_SIZE_LIMIT,
         config::DEFAULT_PAGE_SIZE_LIMIT,
+        None,
+        &config::JsonRpcRateLimitConfig::default(),
     );
     let rt = tokio::runtime::Builder::new_current_thread()
         .enable_all()
@@ -164,9 +177,15 @@ pub fn request_fuzzer(json_request: serde_json::Value) {
         }
     });
     let body = rt.block_on(async {
-        let reply = runtime::rpc_endpoint(json_request, service, None)
-            .await
-            .unwrap();
+        let reply = runtime::rpc_endpoint(
+            json_request,
+            service,
+            None,
+            crate::rate_limit::Caller::default(),
+            None,
+        )
+        .await
+        .unwrap();
 
         let resp = reply.into_response();
         let (_, body) = resp.into_parts();
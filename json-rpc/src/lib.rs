@@ -10,6 +10,9 @@
 //!
 //! Module organization:
 //! ├── methods.rs        # contains all available JSON RPC method handlers
+//! ├── rate_limit.rs     # rate limits method calls by caller
+//! ├── replica.rs        # serves JSON RPC from a secondary instance of a node's DB
+//! ├── rest              # REST API answered by the JSON RPC method handlers
 //! ├── runtime.rs        # implementation of JSON RPC protocol over HTTP
 //! ├── tests.rs          # tests
 
@@ -19,6 +22,9 @@ pub mod util;
 mod counters;
 pub mod data;
 mod methods;
+mod rate_limit;
+pub mod replica;
+mod rest;
 pub mod runtime;
 
 pub use mango_json_rpc_types::{errors, response, views};
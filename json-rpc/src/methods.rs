@@ -5,26 +5,31 @@
 use crate::{
     data,
     errors::JsonRpcError,
+    rate_limit::{Caller, RateLimiter},
     views::{
         AccountStateWithProofView, AccountTransactionsWithProofView, AccountView,
         AccumulatorConsistencyProofView, CurrencyInfoView, EventByVersionWithProofView, EventView,
-        EventWithProofView, MetadataView, StateProofView, TransactionListView, TransactionView,
-        TransactionsWithProofsView,
+        EventWithProofView, FeeMarketBlockView, MetadataView, StateProofView, TransactionListView,
+        TransactionView, TransactionsWithProofsView,
     },
 };
 use anyhow::Result;
 use fail::fail_point;
 use futures::{channel::oneshot, SinkExt};
-use mango_config::config::RoleType;
-use mango_json_rpc_types::request::{
-    GetAccountParams, GetAccountStateWithProofParams, GetAccountTransactionParams,
-    GetAccountTransactionsParams, GetAccountTransactionsWithProofsParams,
-    GetAccumulatorConsistencyProofParams, GetCurrenciesParams, GetEventByVersionWithProof,
-    GetEventsParams, GetEventsWithProofsParams, GetMetadataParams, GetNetworkStatusParams,
-    GetResourcesParams, GetStateProofParams, GetTransactionsParams,
-    GetTransactionsWithProofsParams, MethodRequest, SubmitParams,
+use mango_config::config::{JsonRpcRateLimitConfig, RoleType};
+use mango_json_rpc_types::{
+    request::{
+        GetAccountParams, GetAccountStateWithProofParams, GetAccountTransactionParams,
+        GetAccountTransactionsParams, GetAccountTransactionsWithProofsParams,
+        GetAccumulatorConsistencyProofParams, GetCurrenciesParams, GetEventByVersionWithProof,
+        GetEventsByTypeParams, GetEventsParams, GetEventsWithProofsParams, GetMetadataParams,
+        GetNetworkStatusParams, GetResourcesParams, GetStateProofParams,
+        GetTransactionByHashParams, GetTransactionsParams, GetTransactionsWithProofsParams,
+        MethodRequest, SubmitParams,
+    },
+    Method,
 };
-use mango_mempool::{MempoolClientSender, SubmissionStatus};
+use mango_mempool::{FeeMarketHandle, MempoolClientSender, SubmissionStatus};
 use mango_types::{
     chain_id::ChainId, ledger_info::LedgerInfoWithSignatures, mempool_status::MempoolStatusCode,
     transaction::SignedTransaction,
@@ -42,6 +47,8 @@ pub(crate) struct JsonRpcService {
     chain_id: ChainId,
     batch_size_limit: u16,
     page_size_limit: u16,
+    fee_market: Option<FeeMarketHandle>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl JsonRpcService {
@@ -52,6 +59,8 @@ impl JsonRpcService {
         chain_id: ChainId,
         batch_size_limit: u16,
         page_size_limit: u16,
+        fee_market: Option<FeeMarketHandle>,
+        rate_limit_config: &JsonRpcRateLimitConfig,
     ) -> Self {
         Self {
             db,
@@ -60,6 +69,10 @@ impl JsonRpcService {
             chain_id,
             batch_size_limit,
             page_size_limit,
+            fee_market,
+            rate_limiter: Some(rate_limit_config)
+                .filter(|config| config.enabled)
+                .map(|config| Arc::new(RateLimiter::new(config))),
         }
     }
 
@@ -87,6 +100,10 @@ impl JsonRpcService {
         self.db.get_latest_ledger_info()
     }
 
+    pub fn db(&self) -> &dyn MoveDbReader {
+        self.db.borrow()
+    }
+
     pub fn chain_id(&self) -> ChainId {
         self.chain_id
     }
@@ -99,6 +116,18 @@ impl JsonRpcService {
         self.validate_size_limit("page size", self.page_size_limit, size)
     }
 
+    pub fn rate_limiter(&self) -> Option<&Arc<RateLimiter>> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Charges `caller` for a call to `method` if rate limiting is enabled.
+    pub fn acquire_rate_limit(&self, caller: &Caller, method: Method) -> Result<(), JsonRpcError> {
+        match &self.rate_limiter {
+            Some(rate_limiter) => rate_limiter.acquire(caller, method),
+            None => Ok(()),
+        }
+    }
+
     fn validate_size_limit(&self, name: &str, limit: u16, size: usize) -> Result<(), JsonRpcError> {
         if size > limit as usize {
             Err(JsonRpcError::invalid_request_with_msg(format!(
@@ -128,7 +157,7 @@ impl<'a> Handler<'a> {
         self.ledger_info.ledger_info().version()
     }
 
-    fn version_param(&self, version: Option<u64>, name: &str) -> Result<u64, JsonRpcError> {
+    pub fn version_param(&self, version: Option<u64>, name: &str) -> Result<u64, JsonRpcError> {
         let latest_ledger_version = self.version();
         let version = version.unwrap_or(latest_ledger_version);
 
@@ -160,9 +189,15 @@ impl<'a> Handler<'a> {
             MethodRequest::GetAccountTransactions(params) => {
                 serde_json::to_value(self.get_account_transactions(params).await?)?
             }
+            MethodRequest::GetTransactionByHash(params) => {
+                serde_json::to_value(self.get_transaction_by_hash(params).await?)?
+            }
             MethodRequest::GetEvents(params) => {
                 serde_json::to_value(self.get_events(params).await?)?
             }
+            MethodRequest::GetEventsByType(params) => {
+                serde_json::to_value(self.get_events_by_type(params).await?)?
+            }
             MethodRequest::GetCurrencies(params) => {
                 serde_json::to_value(self.get_currencies(params).await?)?
             }
@@ -215,7 +250,30 @@ impl<'a> Handler<'a> {
     async fn get_metadata(&self, params: GetMetadataParams) -> Result<MetadataView, JsonRpcError> {
         let chain_id = self.service.chain_id();
         let version = self.version_param(params.version, "version")?;
-        data::get_metadata(self.service.db.borrow(), self.version(), chain_id, version)
+        let mut metadata =
+            data::get_metadata(self.service.db.borrow(), self.version(), chain_id, version)?;
+        // the fee market only describes the live mempool, so it is reported for the latest version
+        if let Some(fee_market) = self
+            .service
+            .fee_market
+            .as_ref()
+            .filter(|_| version == self.version())
+        {
+            let snapshot = fee_market.snapshot();
+            metadata.with_fee_market(
+                snapshot.min_gas_price,
+                snapshot
+                    .history
+                    .into_iter()
+                    .map(|block| FeeMarketBlockView {
+                        min_gas_price: block.min_gas_price,
+                        block_size: block.block_size,
+                        fullness: block.fullness,
+                    })
+                    .collect(),
+            );
+        }
+        Ok(metadata)
     }
 
     /// Returns account state (AccountView) by given address
@@ -313,6 +371,23 @@ impl<'a> Handler<'a> {
         )
     }
 
+    /// Returns transaction by hash
+    async fn get_transaction_by_hash(
+        &self,
+        params: GetTransactionByHashParams,
+    ) -> Result<Option<TransactionView>, JsonRpcError> {
+        let GetTransactionByHashParams {
+            hash,
+            include_events,
+        } = params;
+        data::get_transaction_by_hash(
+            self.service.db.borrow(),
+            hash,
+            include_events,
+            self.version(),
+        )
+    }
+
     /// Return a serialized list of an account's transactions along with a proof for
     /// each transaction.
     async fn get_account_transactions_with_proofs(
@@ -347,6 +422,31 @@ impl<'a> Handler<'a> {
         data::get_events(self.service.db.borrow(), self.version(), key, start, limit)
     }
 
+    /// Returns events of the given type in the given version range
+    async fn get_events_by_type(
+        &self,
+        params: GetEventsByTypeParams,
+    ) -> Result<Vec<EventView>, JsonRpcError> {
+        let GetEventsByTypeParams {
+            type_tag,
+            start_version,
+            end_version,
+            limit,
+            start_index,
+        } = params;
+
+        self.service.validate_page_size_limit(limit as usize)?;
+        data::get_events_by_type(
+            self.service.db.borrow(),
+            self.version(),
+            &type_tag,
+            start_version,
+            start_index,
+            end_version,
+            limit,
+        )
+    }
+
     /// Returns events by given access path along with their proofs
     async fn get_events_with_proofs(
         &self,
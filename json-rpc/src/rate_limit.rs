@@ -0,0 +1,186 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rate limiting of method calls by caller, see `JsonRpcRateLimitConfig`
+
+use crate::{counters, errors::JsonRpcError};
+use mango_config::config::JsonRpcRateLimitConfig;
+use mango_infallible::Mutex;
+use mango_json_rpc_types::{request::X_DIEM_API_KEY, Method};
+use mango_rate_limiter::rate_limit::{Bucket, SharedBucket, TokenBucketRateLimiter};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Weak},
+    time::{Duration, Instant},
+};
+use warp::Filter;
+
+// Rate limiter labels for metrics
+const LABEL_IP: &str = "ip";
+const LABEL_API_KEY: &str = "api_key";
+const LOG_INFO: &str = "json-rpc";
+
+/// How often the buckets of idle IPs are dropped
+const GARBAGE_COLLECTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Origin of a request, as far as rate limiting is concerned
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Caller {
+    pub ip: Option<IpAddr>,
+    pub api_key: Option<String>,
+}
+
+/// Extracts the `Caller` of a request from its remote address and API key header
+pub(crate) fn caller() -> impl Filter<Extract = (Caller,), Error = warp::Rejection> + Clone {
+    warp::addr::remote()
+        .and(warp::header::optional::<String>(X_DIEM_API_KEY))
+        .map(|remote_addr: Option<SocketAddr>, api_key| Caller {
+            ip: remote_addr.map(|addr| addr.ip()),
+            api_key,
+        })
+}
+
+pub(crate) struct RateLimiter {
+    ip_limiter: TokenBucketRateLimiter<IpAddr>,
+    /// Last call of each IP with a bucket
+    ip_last_calls: Mutex<HashMap<IpAddr, Instant>>,
+    /// Time for an IP bucket to refill completely, after which it can be dropped: a new bucket
+    /// starts full, so the IP can't tell the difference
+    ip_idle_timeout: Duration,
+    api_key_buckets: HashMap<String, SharedBucket>,
+    method_costs: BTreeMap<String, usize>,
+    default_method_cost: usize,
+}
+
+impl RateLimiter {
+    pub fn new(config: &JsonRpcRateLimitConfig) -> Self {
+        let api_key_buckets = config
+            .api_keys
+            .iter()
+            .enumerate()
+            .map(|(index, (api_key, quota))| {
+                let bucket = Bucket::new(
+                    LABEL_API_KEY.to_string(),
+                    LOG_INFO.to_string(),
+                    // API keys are secrets, so buckets are logged by index rather than by key.
+                    format!("#{}", index),
+                    quota.bucket_size,
+                    quota.bucket_size,
+                    quota.bucket_rate,
+                    Some(counters::RATE_LIMIT.clone()),
+                );
+                (api_key.clone(), Arc::new(Mutex::new(bucket)))
+            })
+            .collect();
+
+        Self {
+            ip_limiter: TokenBucketRateLimiter::new(
+                LABEL_IP,
+                LOG_INFO.to_string(),
+                100, /* new_bucket_start_percentage */
+                config.ip_bucket_size,
+                config.ip_bucket_rate,
+                Some(counters::RATE_LIMIT.clone()),
+            ),
+            ip_last_calls: Mutex::new(HashMap::new()),
+            ip_idle_timeout: Duration::from_secs(
+                ((config.ip_bucket_size + config.ip_bucket_rate - 1) / config.ip_bucket_rate)
+                    as u64,
+            ),
+            api_key_buckets,
+            method_costs: config.method_costs.clone(),
+            default_method_cost: config.default_method_cost,
+        }
+    }
+
+    /// Takes the cost of a call to `method` from the bucket of `caller`: the bucket of its API
+    /// key if it passed a configured one, or else the bucket of its IP. Callers with neither are
+    /// not limited.
+    pub fn acquire(&self, caller: &Caller, method: Method) -> Result<(), JsonRpcError> {
+        let api_key_bucket = caller
+            .api_key
+            .as_ref()
+            .and_then(|api_key| self.api_key_buckets.get(api_key));
+        let (label, bucket) = match (api_key_bucket, caller.ip) {
+            (Some(bucket), _) => (LABEL_API_KEY, bucket.clone()),
+            (None, Some(ip)) => {
+                self.ip_last_calls.lock().insert(ip, Instant::now());
+                (LABEL_IP, self.ip_limiter.bucket(ip))
+            }
+            (None, None) => return Ok(()),
+        };
+
+        let cost = self
+            .method_costs
+            .get(method.as_str())
+            .copied()
+            .unwrap_or(self.default_method_cost);
+        let result = bucket.lock().acquire_all_tokens(cost);
+        result.map_err(|_| {
+            counters::RATE_LIMITED_CALLS
+                .with_label_values(&[label, method.as_str()])
+                .inc();
+            JsonRpcError::rate_limited(method.as_str())
+        })
+    }
+
+    /// Drops the buckets of the IPs which did not call since `now - ip_idle_timeout`
+    pub fn garbage_collect(&self, now: Instant) {
+        self.ip_last_calls.lock().retain(|ip, last_call| {
+            now.saturating_duration_since(*last_call) < self.ip_idle_timeout
+                || !self.ip_limiter.try_garbage_collect_key(ip)
+        });
+    }
+}
+
+/// Periodically drops the IP buckets of `rate_limiter` which are not needed anymore, until it is
+/// dropped
+pub(crate) async fn garbage_collect(rate_limiter: Weak<RateLimiter>) {
+    let mut interval = tokio::time::interval(GARBAGE_COLLECTION_INTERVAL);
+    loop {
+        interval.tick().await;
+        match rate_limiter.upgrade() {
+            Some(rate_limiter) => rate_limiter.garbage_collect(Instant::now()),
+            None => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_garbage_collect() {
+        let config = JsonRpcRateLimitConfig {
+            enabled: true,
+            ip_bucket_size: 10,
+            ip_bucket_rate: 4,
+            ..JsonRpcRateLimitConfig::default()
+        };
+        let rate_limiter = RateLimiter::new(&config);
+        assert_eq!(rate_limiter.ip_idle_timeout, Duration::from_secs(3));
+        let caller = Caller {
+            ip: Some("127.0.0.1".parse().unwrap()),
+            api_key: None,
+        };
+        rate_limiter.acquire(&caller, Method::GetMetadata).unwrap();
+
+        // the bucket is kept while it may be refilling
+        let last_call = Instant::now();
+        rate_limiter.garbage_collect(last_call + Duration::from_secs(2));
+        assert_eq!(rate_limiter.ip_last_calls.lock().len(), 1);
+        rate_limiter.garbage_collect(last_call + Duration::from_secs(3));
+        assert!(rate_limiter.ip_last_calls.lock().is_empty());
+
+        // a bucket in use is kept
+        rate_limiter.acquire(&caller, Method::GetMetadata).unwrap();
+        let bucket = rate_limiter.ip_limiter.bucket(caller.ip.unwrap());
+        rate_limiter.garbage_collect(Instant::now() + Duration::from_secs(3));
+        assert_eq!(rate_limiter.ip_last_calls.lock().len(), 1);
+        drop(bucket);
+        rate_limiter.garbage_collect(Instant::now() + Duration::from_secs(3));
+        assert!(rate_limiter.ip_last_calls.lock().is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! JSON-RPC read replica
+//!
+//! A replica opens the DB of a running validator or full node as a RocksDB secondary instance,
+//! catches up with the node periodically and serves the JSON-RPC methods and stream RPC from it,
+//! so that read traffic can be scaled on one host without running more full nodes.
+//!
+//! The replica has no mempool: transactions received through `submit` are forwarded to an
+//! upstream JSON-RPC endpoint, or rejected if none is configured.
+
+use crate::{
+    data,
+    errors::{JsonRpcError, ServerCode},
+    runtime::bootstrap,
+};
+use anyhow::{format_err, Result};
+use diemdb::DiemDB;
+use futures::{
+    channel::{mpsc, oneshot},
+    StreamExt,
+};
+use mango_client::Client;
+use mango_config::config::NodeConfig;
+use mango_logger::prelude::*;
+use mango_mempool::SubmissionStatus;
+use mango_types::{
+    account_config::diem_root_address,
+    chain_id::ChainId,
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    transaction::SignedTransaction,
+};
+use std::{sync::Arc, time::Duration};
+use storage_interface::DbReader;
+use tokio::runtime::Runtime;
+
+const SUBMISSION_CHANNEL_SIZE: usize = 1_024;
+
+/// Mempool errors reported by the upstream endpoint, mapped back to the mempool status they were
+/// created from.
+const MEMPOOL_ERRORS: [(i16, MempoolStatusCode); 6] = [
+    (
+        ServerCode::MempoolInvalidSeqNumber as i16,
+        MempoolStatusCode::InvalidSeqNumber,
+    ),
+    (
+        ServerCode::MempoolIsFull as i16,
+        MempoolStatusCode::MempoolIsFull,
+    ),
+    (
+        ServerCode::MempoolTooManyTransactions as i16,
+        MempoolStatusCode::TooManyTransactions,
+    ),
+    (
+        ServerCode::MempoolInvalidUpdate as i16,
+        MempoolStatusCode::InvalidUpdate,
+    ),
+    (
+        ServerCode::MempoolVmError as i16,
+        MempoolStatusCode::VmError,
+    ),
+    (
+        ServerCode::MempoolUnknownError as i16,
+        MempoolStatusCode::UnknownStatus,
+    ),
+];
+
+/// Starts serving JSON-RPC on `config.json_rpc` from `diem_db`, which must have been opened with
+/// `DiemDB::open_as_secondary`. The DB catches up with its primary every `catch_up_interval`, and
+/// submitted transactions are forwarded to `upstream_url` if provided.
+pub fn bootstrap_replica(
+    config: &NodeConfig,
+    diem_db: Arc<DiemDB>,
+    upstream_url: Option<String>,
+    catch_up_interval: Duration,
+) -> Result<Runtime> {
+    let chain_id = fetch_chain_id(&diem_db)?;
+    let (mp_sender, mp_receiver) = mpsc::channel(SUBMISSION_CHANNEL_SIZE);
+
+    let runtime = bootstrap(
+        config.json_rpc.address,
+        config.json_rpc.batch_size_limit,
+        config.json_rpc.page_size_limit,
+        config.json_rpc.content_length_limit,
+        &config.json_rpc.tls_cert_path,
+        &config.json_rpc.tls_key_path,
+        diem_db.clone(),
+        mp_sender,
+        config.base.role,
+        chain_id,
+        &config.json_rpc.stream_rpc,
+        None, /* fee_market */
+        &config.json_rpc.rate_limit,
+    );
+    runtime.spawn(forward_submissions(
+        mp_receiver,
+        upstream_url.map(Client::new),
+    ));
+    runtime.spawn(catch_up_with_primary(diem_db, catch_up_interval));
+    Ok(runtime)
+}
+
+fn fetch_chain_id(diem_db: &DiemDB) -> Result<ChainId> {
+    let version = diem_db.get_latest_version()?;
+    let account_state = data::get_account_state(diem_db, diem_root_address(), version)?
+        .ok_or_else(|| format_err!("missing mango root address account state"))?;
+    Ok(account_state
+        .get_chain_id_resource()?
+        .ok_or_else(|| format_err!("missing chain ID resource"))?
+        .chain_id())
+}
+
+async fn catch_up_with_primary(diem_db: Arc<DiemDB>, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        let db = diem_db.clone();
+        match tokio::task::spawn_blocking(move || db.try_catch_up_with_primary()).await {
+            Ok(Ok(())) => (),
+            Ok(Err(err)) => warn!(error = ?err, "Failed to catch up with the primary DiemDB."),
+            Err(err) => warn!(error = ?err, "Catching up with the primary DiemDB panicked."),
+        }
+    }
+}
+
+/// Serves the submissions the JSON-RPC service would send to mempool.
+async fn forward_submissions(
+    mut receiver: mpsc::Receiver<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>,
+    upstream: Option<Client>,
+) {
+    while let Some((txn, callback)) = receiver.next().await {
+        match upstream.clone() {
+            Some(client) => {
+                tokio::spawn(async move {
+                    let status = match client.submit(&txn).await {
+                        Ok(_) => Ok((MempoolStatus::new(MempoolStatusCode::Accepted), None)),
+                        Err(err) => match err.json_rpc_error() {
+                            Some(error) => submission_status(error),
+                            None => Err(format_err!("Failed to forward transaction: {}", err)),
+                        },
+                    };
+                    let _ = callback.send(status);
+                });
+            }
+            None => {
+                let _ = callback.send(Err(format_err!(
+                    "Transaction submission is disabled on this JSON-RPC replica"
+                )));
+            }
+        }
+    }
+}
+
+/// Converts an error returned by the upstream endpoint back into the status the `submit` handler
+/// expects from mempool, so that it is reported to the client the same way.
+fn submission_status(error: &JsonRpcError) -> Result<SubmissionStatus> {
+    if let Some(status_code) = error.as_status_code() {
+        return Ok((
+            MempoolStatus::new(MempoolStatusCode::VmError),
+            Some(status_code),
+        ));
+    }
+    MEMPOOL_ERRORS
+        .iter()
+        .find(|(code, _)| *code == error.code)
+        .map(|(_, status_code)| {
+            (
+                MempoolStatus::new(*status_code).with_message(error.message.clone()),
+                None,
+            )
+        })
+        .ok_or_else(|| format_err!("Upstream error: {}", error.message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::SinkExt;
+    use mango_proptest_helpers::ValueGenerator;
+    use mango_types::vm_status::StatusCode;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_submission_status() {
+        let (status, vm_status) = submission_status(&JsonRpcError::vm_status(
+            StatusCode::SEQUENCE_NUMBER_TOO_OLD,
+        ))
+        .unwrap();
+        assert_eq!(status.code, MempoolStatusCode::VmError);
+        assert_eq!(vm_status, Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD));
+
+        let error =
+            JsonRpcError::mempool_error(MempoolStatus::new(MempoolStatusCode::MempoolIsFull))
+                .unwrap();
+        let (status, vm_status) = submission_status(&error).unwrap();
+        assert_eq!(status.code, MempoolStatusCode::MempoolIsFull);
+        assert!(vm_status.is_none());
+
+        assert!(submission_status(&JsonRpcError::internal_error("error".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_submit_disabled() {
+        let (mut sender, receiver) = mpsc::channel(1);
+        tokio::spawn(forward_submissions(receiver, None));
+
+        let (callback, response) = oneshot::channel();
+        let txn = ValueGenerator::new().generate(any::<SignedTransaction>());
+        sender.send((txn, callback)).await.unwrap();
+        assert!(response.await.unwrap().is_err());
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resource-oriented REST API, served next to JSON RPC under `/v1`
+//!
+//! Requests are answered by the JSON RPC method handlers, so JSON responses are the views returned
+//! by the matching methods. Callers sending `Accept: application/x-bcs` get the BCS encoded
+//! on-chain types instead. The OpenAPI document describing the API is served from
+//! `/v1/openapi.json`.
+
+mod openapi;
+
+use crate::{
+    counters,
+    errors::{is_internal_error, JsonRpcError, ServerCode},
+    methods::{Handler, JsonRpcService},
+    rate_limit::{self, Caller},
+    runtime::{add_ledger_info_headers, DatabaseError},
+    util,
+};
+use mango_crypto::HashValue;
+use mango_json_rpc_types::{
+    request::{
+        GetAccountParams, GetEventsParams, GetResourcesParams, GetTransactionByHashParams,
+        GetTransactionsParams, MethodRequest, X_DIEM_API_KEY,
+    },
+    response::CONTENT_TYPE_BCS,
+    Method,
+};
+use mango_types::{
+    account_address::AccountAddress, account_state::AccountState, event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::convert::TryFrom;
+use storage_interface::Order;
+use warp::{
+    filters::BoxedFilter,
+    http::{header, StatusCode},
+    reject, Filter, Reply,
+};
+
+const CONTENT_TYPE_JSON: &str = "application/json";
+const DEFAULT_EVENTS_LIMIT: u64 = 100;
+
+/// Resource requested from the REST API
+#[derive(Debug)]
+enum Resource {
+    Account {
+        address: String,
+        version: Option<u64>,
+    },
+    AccountResources {
+        address: String,
+        version: Option<u64>,
+    },
+    Transaction {
+        version: u64,
+    },
+    TransactionByHash {
+        hash: String,
+    },
+    Events {
+        key: String,
+        start: u64,
+        limit: u64,
+    },
+}
+
+impl Resource {
+    /// JSON RPC method answering requests for the resource, which also decides their rate limit
+    /// cost
+    fn method(&self) -> Method {
+        match self {
+            Resource::Account { .. } => Method::GetAccount,
+            Resource::AccountResources { .. } => Method::GetResources,
+            Resource::Transaction { .. } => Method::GetTransactions,
+            Resource::TransactionByHash { .. } => Method::GetTransactionByHash,
+            Resource::Events { .. } => Method::GetEvents,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Encoding {
+    Json,
+    Bcs,
+}
+
+impl Encoding {
+    /// BCS if the `Accept` header lists it, JSON otherwise
+    fn from_accept(accept: Option<&str>) -> Self {
+        if util::accepts_bcs(accept) {
+            Encoding::Bcs
+        } else {
+            Encoding::Json
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Json => "json",
+            Encoding::Bcs => "bcs",
+        }
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            Encoding::Json => CONTENT_TYPE_JSON,
+            Encoding::Bcs => CONTENT_TYPE_BCS,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionQuery {
+    version: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct EventsQuery {
+    #[serde(default)]
+    start: u64,
+    limit: Option<u64>,
+}
+
+/// Gets the routes of all REST endpoints
+pub(crate) fn routes(service: JsonRpcService) -> BoxedFilter<(impl Reply,)> {
+    let account = warp::path!("accounts" / String)
+        .and(warp::query::<VersionQuery>())
+        .map(|address, query: VersionQuery| Resource::Account {
+            address,
+            version: query.version,
+        });
+    let account_resources = warp::path!("accounts" / String / "resources")
+        .and(warp::query::<VersionQuery>())
+        .map(|address, query: VersionQuery| Resource::AccountResources {
+            address,
+            version: query.version,
+        });
+    let transaction =
+        warp::path!("transactions" / u64).map(|version| Resource::Transaction { version });
+    let transaction_by_hash = warp::path!("transactions" / "by_hash" / String)
+        .map(|hash| Resource::TransactionByHash { hash });
+    let events = warp::path!("events" / String)
+        .and(warp::query::<EventsQuery>())
+        .map(|key, query: EventsQuery| Resource::Events {
+            key,
+            start: query.start,
+            limit: query.limit.unwrap_or(DEFAULT_EVENTS_LIMIT),
+        });
+
+    let resource_route = account
+        .or(account_resources)
+        .unify()
+        .or(transaction)
+        .unify()
+        .or(transaction_by_hash)
+        .unify()
+        .or(events)
+        .unify()
+        .and(warp::any().map(move || service.clone()))
+        .and(rate_limit::caller())
+        .and(warp::header::optional::<String>("accept"))
+        .and_then(rest_endpoint);
+
+    let openapi_route =
+        warp::path!("openapi.json").map(|| warp::reply::json(&*openapi::OPENAPI).into_response());
+
+    warp::path("v1")
+        .and(
+            warp::get()
+                .and(resource_route.or(openapi_route).unify())
+                .with(
+                    warp::cors()
+                        .allow_any_origin()
+                        .allow_methods(vec!["GET"])
+                        .allow_headers(vec![header::ACCEPT.as_str(), X_DIEM_API_KEY]),
+                ),
+        )
+        .boxed()
+}
+
+/// REST entry point
+async fn rest_endpoint(
+    resource: Resource,
+    service: JsonRpcService,
+    caller: Caller,
+    accept: Option<String>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    // take snapshot of latest version of DB, so that the response matches the ledger info headers
+    let ledger_info = service
+        .get_latest_ledger_info()
+        .map_err(|_| reject::custom(DatabaseError))?;
+
+    let method = resource.method();
+    let encoding = Encoding::from_accept(accept.as_deref());
+    let timer = counters::REST_REQUEST_LATENCY
+        .with_label_values(&[method.as_str()])
+        .start_timer();
+    let result = match service.acquire_rate_limit(&caller, method) {
+        Ok(()) => get_resource(resource, encoding, &service, &ledger_info).await,
+        Err(err) => Err(err),
+    };
+    timer.stop_and_record();
+
+    let mut response = match result {
+        Ok(Some(body)) => {
+            warp::reply::with_header(body, header::CONTENT_TYPE, encoding.content_type())
+                .into_response()
+        }
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(err) => {
+            let status = status_code(&err);
+            warp::reply::with_status(warp::reply::json(&err), status).into_response()
+        }
+    };
+    counters::REST_REQUESTS
+        .with_label_values(&[
+            method.as_str(),
+            encoding.as_str(),
+            response.status().as_str(),
+        ])
+        .inc();
+    add_ledger_info_headers(response.headers_mut(), service.chain_id(), &ledger_info);
+
+    Ok(response)
+}
+
+/// Returns the encoded resource, or None if it doesn't exist
+async fn get_resource(
+    resource: Resource,
+    encoding: Encoding,
+    service: &JsonRpcService,
+    ledger_info: &LedgerInfoWithSignatures,
+) -> Result<Option<Vec<u8>>, JsonRpcError> {
+    let handler = Handler::new(service, ledger_info);
+    let ledger_version = ledger_info.ledger_info().version();
+
+    match resource {
+        Resource::Account { address, version } => {
+            let account = parse_address(&address)?;
+            match encoding {
+                Encoding::Json => json(
+                    handler
+                        .handle(MethodRequest::GetAccount(GetAccountParams {
+                            account,
+                            version,
+                        }))
+                        .await?,
+                ),
+                Encoding::Bcs => {
+                    let version = handler.version_param(version, "version")?;
+                    let (blob, _) = service
+                        .db()
+                        .get_account_state_with_proof_by_version(account, version)?;
+                    blob.map(|blob| encode_bcs(&blob)).transpose()
+                }
+            }
+        }
+        Resource::AccountResources { address, version } => {
+            let account = parse_address(&address)?;
+            match encoding {
+                Encoding::Json => json(
+                    handler
+                        .handle(MethodRequest::GetResources(GetResourcesParams {
+                            account,
+                            version,
+                        }))
+                        .await?,
+                ),
+                Encoding::Bcs => {
+                    let version = handler.version_param(version, "version")?;
+                    // like `get_resources`, an account that doesn't exist has no resources
+                    let account_state = match service
+                        .db()
+                        .get_account_state_with_proof(account, version, ledger_version)?
+                        .blob
+                    {
+                        Some(blob) => AccountState::try_from(&blob)?,
+                        None => AccountState::default(),
+                    };
+                    encode_bcs(&account_state).map(Some)
+                }
+            }
+        }
+        Resource::Transaction { version } => match encoding {
+            Encoding::Json => {
+                let transactions = handler
+                    .handle(MethodRequest::GetTransactions(GetTransactionsParams {
+                        start_version: version,
+                        limit: 1,
+                        include_events: true,
+                    }))
+                    .await?;
+                match transactions {
+                    Value::Array(mut transactions) if !transactions.is_empty() => {
+                        json(transactions.swap_remove(0))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            Encoding::Bcs => {
+                if version > ledger_version {
+                    return Ok(None);
+                }
+                let txn_list = service
+                    .db()
+                    .get_transactions(version, 1, ledger_version, false)?;
+                txn_list.transactions.first().map(encode_bcs).transpose()
+            }
+        },
+        Resource::TransactionByHash { hash } => {
+            let hash = HashValue::from_hex(&hash).map_err(|_| invalid_path_param("hash", &hash))?;
+            match encoding {
+                Encoding::Json => json(
+                    handler
+                        .handle(MethodRequest::GetTransactionByHash(
+                            GetTransactionByHashParams {
+                                hash,
+                                include_events: true,
+                            },
+                        ))
+                        .await?,
+                ),
+                Encoding::Bcs => service
+                    .db()
+                    .get_transaction_by_hash(hash, ledger_version, false)?
+                    .map(|txn| encode_bcs(&txn.transaction))
+                    .transpose(),
+            }
+        }
+        Resource::Events { key, start, limit } => {
+            let key = EventKey::from_hex(&key).map_err(|_| invalid_path_param("key", &key))?;
+            match encoding {
+                Encoding::Json => json(
+                    handler
+                        .handle(MethodRequest::GetEvents(GetEventsParams {
+                            key,
+                            start,
+                            limit,
+                        }))
+                        .await?,
+                ),
+                Encoding::Bcs => {
+                    service.validate_page_size_limit(limit as usize)?;
+                    let events: Vec<_> = service
+                        .db()
+                        .get_events(&key, start, Order::Ascending, limit)?
+                        .into_iter()
+                        .filter(|(version, _event)| *version <= ledger_version)
+                        .collect();
+                    encode_bcs(&events).map(Some)
+                }
+            }
+        }
+    }
+}
+
+fn parse_address(address: &str) -> Result<AccountAddress, JsonRpcError> {
+    AccountAddress::from_hex(address).map_err(|_| invalid_path_param("address", address))
+}
+
+fn invalid_path_param(name: &str, value: &str) -> JsonRpcError {
+    JsonRpcError::invalid_param(&format!("{}: '{}'", name, value))
+}
+
+/// Method handlers return null for resources that don't exist
+fn json(value: Value) -> Result<Option<Vec<u8>>, JsonRpcError> {
+    if value.is_null() {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::to_vec(&value)?))
+    }
+}
+
+fn encode_bcs<T: Serialize>(value: &T) -> Result<Vec<u8>, JsonRpcError> {
+    Ok(bcs::to_bytes(value)?)
+}
+
+fn status_code(err: &JsonRpcError) -> StatusCode {
+    if err.code == ServerCode::RateLimited as i16 {
+        StatusCode::TOO_MANY_REQUESTS
+    } else if is_internal_error(&err.code) {
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+        StatusCode::BAD_REQUEST
+    }
+}
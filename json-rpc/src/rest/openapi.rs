@@ -0,0 +1,321 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! OpenAPI document of the REST API, for clients to be generated from
+
+use super::DEFAULT_EVENTS_LIMIT;
+use mango_json_rpc_types::{
+    request::X_DIEM_API_KEY,
+    response::{CONTENT_TYPE_BCS, X_DIEM_CHAIN_ID, X_DIEM_TIMESTAMP_USEC_ID, X_DIEM_VERSION_ID},
+};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+
+pub(super) static OPENAPI: Lazy<Value> = Lazy::new(|| {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Mango REST API",
+            "version": "1.0.0",
+            "description": format!(
+                "Resource-oriented view of the ledger, answered by the JSON-RPC methods. Responses \
+                 are JSON encoded views unless the `Accept` header requests `{}`, in which case \
+                 they are the BCS encoded on-chain types.",
+                CONTENT_TYPE_BCS,
+            ),
+        },
+        "servers": [{ "url": "/v1" }],
+        "paths": {
+            "/accounts/{address}": {
+                "get": {
+                    "operationId": "get_account",
+                    "summary": "Get an account, like JSON-RPC get_account",
+                    "parameters": [
+                        { "$ref": "#/components/parameters/address" },
+                        { "$ref": "#/components/parameters/version" },
+                        { "$ref": "#/components/parameters/api_key" },
+                    ],
+                    "responses": responses(
+                        "The account",
+                        "#/components/schemas/Account",
+                        "AccountStateBlob",
+                    ),
+                },
+            },
+            "/accounts/{address}/resources": {
+                "get": {
+                    "operationId": "get_account_resources",
+                    "summary": "Get the resources of an account, like JSON-RPC get_resources",
+                    "parameters": [
+                        { "$ref": "#/components/parameters/address" },
+                        { "$ref": "#/components/parameters/version" },
+                        { "$ref": "#/components/parameters/api_key" },
+                    ],
+                    "responses": responses(
+                        "The resources of the account by type, empty if the account doesn't exist",
+                        "#/components/schemas/Resources",
+                        "AccountState",
+                    ),
+                },
+            },
+            "/transactions/{version}": {
+                "get": {
+                    "operationId": "get_transaction",
+                    "summary": "Get a transaction by version, like JSON-RPC get_transactions",
+                    "parameters": [
+                        {
+                            "name": "version",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "integer", "format": "uint64" },
+                        },
+                        { "$ref": "#/components/parameters/api_key" },
+                    ],
+                    "responses": responses(
+                        "The transaction, with its events in JSON",
+                        "#/components/schemas/Transaction",
+                        "Transaction",
+                    ),
+                },
+            },
+            "/transactions/by_hash/{hash}": {
+                "get": {
+                    "operationId": "get_transaction_by_hash",
+                    "summary": "Get a transaction by hash, like JSON-RPC get_transaction_by_hash",
+                    "parameters": [
+                        {
+                            "name": "hash",
+                            "in": "path",
+                            "required": true,
+                            "description": "Hex-encoded transaction hash",
+                            "schema": { "type": "string" },
+                        },
+                        { "$ref": "#/components/parameters/api_key" },
+                    ],
+                    "responses": responses(
+                        "The transaction, with its events in JSON",
+                        "#/components/schemas/Transaction",
+                        "Transaction",
+                    ),
+                },
+            },
+            "/events/{key}": {
+                "get": {
+                    "operationId": "get_events",
+                    "summary": "Get the events of an event stream, like JSON-RPC get_events",
+                    "parameters": [
+                        {
+                            "name": "key",
+                            "in": "path",
+                            "required": true,
+                            "description": "Hex-encoded event key",
+                            "schema": { "type": "string" },
+                        },
+                        {
+                            "name": "start",
+                            "in": "query",
+                            "description": "Sequence number of the first event",
+                            "schema": { "type": "integer", "format": "uint64", "default": 0 },
+                        },
+                        {
+                            "name": "limit",
+                            "in": "query",
+                            "description": "Maximum number of events",
+                            "schema": {
+                                "type": "integer",
+                                "format": "uint64",
+                                "default": DEFAULT_EVENTS_LIMIT,
+                            },
+                        },
+                        { "$ref": "#/components/parameters/api_key" },
+                    ],
+                    "responses": responses(
+                        "The events, in sequence number order",
+                        "#/components/schemas/Events",
+                        "Vec<(u64, ContractEvent)>, pairs of transaction version and event",
+                    ),
+                },
+            },
+        },
+        "components": {
+            "parameters": {
+                "address": {
+                    "name": "address",
+                    "in": "path",
+                    "required": true,
+                    "description": "Hex-encoded account address",
+                    "schema": { "type": "string" },
+                },
+                "version": {
+                    "name": "version",
+                    "in": "query",
+                    "description": "Ledger version to read at, defaults to the latest version",
+                    "schema": { "type": "integer", "format": "uint64" },
+                },
+                "api_key": {
+                    "name": X_DIEM_API_KEY,
+                    "in": "header",
+                    "description": "API key whose quota the request is rate limited by",
+                    "schema": { "type": "string" },
+                },
+            },
+            "headers": {
+                "chain_id": {
+                    "description": "Chain id of the network",
+                    "schema": { "type": "integer", "format": "uint8" },
+                },
+                "ledger_version": {
+                    "description": "Latest ledger version of the node",
+                    "schema": { "type": "integer", "format": "uint64" },
+                },
+                "ledger_timestamp_usec": {
+                    "description": "Timestamp of the latest ledger version, in microseconds",
+                    "schema": { "type": "integer", "format": "uint64" },
+                },
+            },
+            "schemas": {
+                "Error": {
+                    "type": "object",
+                    "description": "JSON-RPC error object, see json-rpc-spec.md",
+                    "required": ["code", "message"],
+                    "properties": {
+                        "code": { "type": "integer", "format": "int16" },
+                        "message": { "type": "string" },
+                        "data": { "type": "object", "nullable": true },
+                    },
+                },
+                "Amount": {
+                    "type": "object",
+                    "required": ["amount", "currency"],
+                    "properties": {
+                        "amount": { "type": "integer", "format": "uint64" },
+                        "currency": { "type": "string" },
+                    },
+                },
+                "Account": {
+                    "type": "object",
+                    "description": "See json-rpc/docs/type_account.md",
+                    "required": [
+                        "address",
+                        "balances",
+                        "sequence_number",
+                        "authentication_key",
+                        "sent_events_key",
+                        "received_events_key",
+                        "delegated_key_rotation_capability",
+                        "delegated_withdrawal_capability",
+                        "is_frozen",
+                        "role",
+                    ],
+                    "properties": {
+                        "address": { "type": "string" },
+                        "balances": {
+                            "type": "array",
+                            "items": { "$ref": "#/components/schemas/Amount" },
+                        },
+                        "sequence_number": { "type": "integer", "format": "uint64" },
+                        "authentication_key": { "type": "string" },
+                        "sent_events_key": { "type": "string" },
+                        "received_events_key": { "type": "string" },
+                        "delegated_key_rotation_capability": { "type": "boolean" },
+                        "delegated_withdrawal_capability": { "type": "boolean" },
+                        "is_frozen": { "type": "boolean" },
+                        "role": tagged_object("Role of the account"),
+                        "version": { "type": "integer", "format": "uint64" },
+                    },
+                },
+                "Resources": {
+                    "type": "object",
+                    "description": "Resources by Move type, like JSON-RPC get_resources",
+                    "additionalProperties": { "type": "object" },
+                },
+                "Transaction": {
+                    "type": "object",
+                    "description": "See json-rpc/docs/type_transaction.md",
+                    "required": [
+                        "version",
+                        "transaction",
+                        "hash",
+                        "bytes",
+                        "events",
+                        "vm_status",
+                        "gas_used",
+                    ],
+                    "properties": {
+                        "version": { "type": "integer", "format": "uint64" },
+                        "transaction": tagged_object("Content of the transaction"),
+                        "hash": { "type": "string" },
+                        "bytes": { "type": "string" },
+                        "events": { "$ref": "#/components/schemas/Events" },
+                        "vm_status": tagged_object("Execution status of the transaction"),
+                        "gas_used": { "type": "integer", "format": "uint64" },
+                    },
+                },
+                "Event": {
+                    "type": "object",
+                    "description": "See json-rpc/docs/type_event.md",
+                    "required": ["key", "sequence_number", "transaction_version", "data"],
+                    "properties": {
+                        "key": { "type": "string" },
+                        "sequence_number": { "type": "integer", "format": "uint64" },
+                        "transaction_version": { "type": "integer", "format": "uint64" },
+                        "data": tagged_object("Content of the event"),
+                    },
+                },
+                "Events": {
+                    "type": "array",
+                    "items": { "$ref": "#/components/schemas/Event" },
+                },
+            },
+        },
+    })
+});
+
+/// Object whose fields depend on its `type` field, see the JSON-RPC docs for the variants
+fn tagged_object(description: &str) -> Value {
+    json!({
+        "type": "object",
+        "description": description,
+        "required": ["type"],
+        "properties": { "type": { "type": "string" } },
+        "additionalProperties": true,
+    })
+}
+
+/// Responses of an endpoint returning the view `json_schema`, or the BCS encoded `bcs_type`
+fn responses(description: &str, json_schema: &str, bcs_type: &str) -> Value {
+    let headers = json!({
+        X_DIEM_CHAIN_ID: { "$ref": "#/components/headers/chain_id" },
+        X_DIEM_VERSION_ID: { "$ref": "#/components/headers/ledger_version" },
+        X_DIEM_TIMESTAMP_USEC_ID: { "$ref": "#/components/headers/ledger_timestamp_usec" },
+    });
+    let error = |description: &str| {
+        json!({
+            "description": description,
+            "headers": headers,
+            "content": {
+                "application/json": { "schema": { "$ref": "#/components/schemas/Error" } },
+            },
+        })
+    };
+    json!({
+        "200": {
+            "description": description,
+            "headers": headers,
+            "content": {
+                "application/json": { "schema": { "$ref": json_schema } },
+                CONTENT_TYPE_BCS: {
+                    "schema": {
+                        "type": "string",
+                        "format": "binary",
+                        "description": format!("BCS encoded {}", bcs_type),
+                    },
+                },
+            },
+        },
+        "400": error("Invalid path or query parameter"),
+        "404": { "description": "Not found", "headers": headers },
+        "429": error("Rate limited"),
+        "500": error("Server error"),
+    })
+}
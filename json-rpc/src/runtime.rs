@@ -6,20 +6,26 @@
 
 use crate::{
     counters,
-    errors::is_internal_error,
+    errors::{is_internal_error, JsonRpcError},
     methods::{Handler, JsonRpcService},
-    response::{JsonRpcResponse, X_DIEM_CHAIN_ID, X_DIEM_TIMESTAMP_USEC_ID, X_DIEM_VERSION_ID},
-    stream_rpc,
-    util::{sdk_info_from_user_agent, SdkInfo},
+    rate_limit::{self, Caller},
+    response::{
+        JsonRpcResponse, CONTENT_TYPE_BCS, X_DIEM_CHAIN_ID, X_DIEM_TIMESTAMP_USEC_ID,
+        X_DIEM_VERSION_ID,
+    },
+    rest, stream_rpc,
+    util::{accepts_bcs, sdk_info_from_user_agent, SdkInfo},
 };
 use anyhow::{ensure, Result};
 use futures::future::{join_all, Either};
-use mango_config::config::{NodeConfig, RoleType, StreamConfig};
-use mango_json_rpc_types::Method;
+use mango_client::MethodResponse;
+use mango_config::config::{JsonRpcRateLimitConfig, NodeConfig, RoleType, StreamConfig};
+use mango_json_rpc_types::{request::X_DIEM_API_KEY, Method};
 use mango_logger::{debug, Schema};
-use mango_mempool::MempoolClientSender;
+use mango_mempool::{FeeMarketHandle, MempoolClientSender};
 use mango_types::{chain_id::ChainId, ledger_info::LedgerInfoWithSignatures};
 use rand::{rngs::OsRng, RngCore};
+use serde::Serialize;
 use serde_json::Value;
 use std::{
     net::SocketAddr,
@@ -110,6 +116,8 @@ pub fn bootstrap(
     role: RoleType,
     chain_id: ChainId,
     stream_config: &StreamConfig,
+    fee_market: Option<FeeMarketHandle>,
+    rate_limit_config: &JsonRpcRateLimitConfig,
 ) -> Runtime {
     let runtime = Builder::new_multi_thread()
         .thread_name("json-rpc")
@@ -124,7 +132,14 @@ pub fn bootstrap(
         chain_id,
         batch_size_limit,
         page_size_limit,
+        fee_market,
+        rate_limit_config,
     );
+    if let Some(rate_limiter) = service.rate_limiter() {
+        runtime.spawn(rate_limit::garbage_collect(Arc::downgrade(rate_limiter)));
+    }
+
+    let rest_route = rest::routes(service.clone());
 
     let base_route = warp::any()
         .and(warp::post())
@@ -133,6 +148,8 @@ pub fn bootstrap(
         .and(warp::body::json())
         .and(warp::any().map(move || service.clone()))
         .and(warp::filters::header::optional::<String>("user-agent"))
+        .and(rate_limit::caller())
+        .and(warp::filters::header::optional::<String>("accept"))
         .and_then(rpc_endpoint)
         .with(warp::log::custom(|info| {
             debug!(HttpRequestLog {
@@ -152,12 +169,12 @@ pub fn bootstrap(
         // CORS is required for full node server to accept requests from different domain web pages.
         // It needs to be configured for the json-rpc request accepting method and headers.
         // Technically it's fine for any headers, but for simplicity we only set must have header
-        // content-type.
+        // content-type, plus the API key header used for rate limiting.
         .with(
             warp::cors()
                 .allow_any_origin()
                 .allow_methods(vec!["POST"])
-                .allow_headers(vec![header::CONTENT_TYPE]),
+                .allow_headers(vec![header::CONTENT_TYPE.as_str(), X_DIEM_API_KEY]),
         );
 
     // For now we still allow user to use "/", but user should start to move to "/v1" soon
@@ -185,14 +202,9 @@ pub fn bootstrap(
 
     let _guard = runtime.enter();
 
-    let full_route =
-        health_route
-            .or(route_v1.or(route_root))
-            .or(stream_rpc::startup::get_stream_routes(
-                stream_config,
-                content_len_limit as u64,
-                diem_db,
-            ));
+    let full_route = health_route.or(route_v1.or(route_root).or(rest_route)).or(
+        stream_rpc::startup::get_stream_routes(stream_config, content_len_limit as u64, diem_db),
+    );
 
     let server = match tls_cert_path {
         None => Either::Left(warp::serve(full_route).bind(address)),
@@ -209,11 +221,13 @@ pub fn bootstrap(
 }
 
 /// Creates JSON RPC endpoint by given node config
+/// `fee_market` is reported through `get_metadata` when the mempool fee market is enabled
 pub fn bootstrap_from_config(
     config: &NodeConfig,
     chain_id: ChainId,
     diem_db: Arc<dyn MoveDbReader>,
     mp_sender: MempoolClientSender,
+    fee_market: FeeMarketHandle,
 ) -> Runtime {
     bootstrap(
         config.json_rpc.address,
@@ -227,6 +241,8 @@ pub fn bootstrap_from_config(
         config.base.role,
         chain_id,
         &config.json_rpc.stream_rpc,
+        Some(fee_market).filter(|_| config.mempool.fee_market.enabled),
+        &config.json_rpc.rate_limit,
     )
 }
 
@@ -266,6 +282,8 @@ pub(crate) async fn rpc_endpoint(
     data: Value,
     service: JsonRpcService,
     user_agent: Option<String>,
+    caller: Caller,
+    accept: Option<String>,
 ) -> Result<warp::reply::Response, warp::Rejection> {
     let label = match data {
         Value::Array(_) => LABEL_BATCH,
@@ -275,7 +293,14 @@ pub(crate) async fn rpc_endpoint(
     let timer = counters::RPC_REQUEST_LATENCY
         .with_label_values(&[label])
         .start_timer();
-    let ret = rpc_endpoint_without_metrics(data, service, user_agent.as_deref()).await;
+    let ret = rpc_endpoint_without_metrics(
+        data,
+        service,
+        user_agent.as_deref(),
+        &caller,
+        accepts_bcs(accept.as_deref()),
+    )
+    .await;
     timer.stop_and_record();
     ret
 }
@@ -284,6 +309,8 @@ async fn rpc_endpoint_without_metrics(
     data: Value,
     service: JsonRpcService,
     user_agent: Option<&str>,
+    caller: &Caller,
+    accept_bcs: bool,
 ) -> Result<warp::reply::Response, warp::Rejection> {
     // take snapshot of latest version of DB to be used across all requests, especially for batched requests
     let ledger_info = service
@@ -301,18 +328,38 @@ async fn rpc_endpoint_without_metrics(
     let latest_ledger_timestamp_usecs = ledger_info.ledger_info().timestamp_usecs();
     let sdk_info = sdk_info_from_user_agent(user_agent);
 
-    let resp = if let Value::Array(requests) = data {
+    let mut http_response = if let Value::Array(requests) = data {
         match service.validate_batch_size_limit(requests.len()) {
             Ok(_) => {
                 // batch API call
                 let futures = requests.into_iter().map(|req| {
-                    rpc_request_handler(req, &service, &ledger_info, LABEL_BATCH, sdk_info)
+                    rpc_request_handler(req, &service, &ledger_info, LABEL_BATCH, sdk_info, caller)
                 });
                 let responses = join_all(futures).await;
-                for resp in &responses {
+                for (resp, _) in &responses {
                     log_response!(&trace_id, resp, true);
                 }
-                warp::reply::json(&responses)
+
+                // BCS is only used if all methods of the batch support it, in request order
+                let methods: Option<Vec<_>> = responses
+                    .iter()
+                    .map(|(_, method)| bcs_method(*method))
+                    .collect();
+                match methods.filter(|_| accept_bcs) {
+                    Some(methods) => {
+                        let results: Vec<_> = responses
+                            .into_iter()
+                            .zip(methods)
+                            .map(|((resp, _), method)| bcs_result(resp, method))
+                            .collect();
+                        bcs_reply(&results)?
+                    }
+                    None => {
+                        let responses: Vec<_> =
+                            responses.into_iter().map(|(resp, _)| resp).collect();
+                        warp::reply::json(&responses).into_response()
+                    }
+                }
             }
             Err(err) => {
                 let mut response = JsonRpcResponse::new(
@@ -324,34 +371,45 @@ async fn rpc_endpoint_without_metrics(
                 bump_counters(&response, LABEL_BATCH, None, sdk_info);
                 log_response!(&trace_id, &response, true);
 
-                warp::reply::json(&response)
+                warp::reply::json(&response).into_response()
             }
         }
     } else {
         // single API call
-        let resp = rpc_request_handler(data, &service, &ledger_info, LABEL_SINGLE, sdk_info).await;
+        let (resp, method) =
+            rpc_request_handler(data, &service, &ledger_info, LABEL_SINGLE, sdk_info, caller).await;
         log_response!(&trace_id, &resp, false);
 
-        warp::reply::json(&resp)
+        match bcs_method(method).filter(|_| accept_bcs) {
+            Some(method) => bcs_reply(&bcs_result(resp, method))?,
+            None => warp::reply::json(&resp).into_response(),
+        }
     };
 
-    let mut http_response = resp.into_response();
-    let headers = http_response.headers_mut();
+    add_ledger_info_headers(http_response.headers_mut(), chain_id, &ledger_info);
+
+    Ok(http_response)
+}
 
+/// Adds the chain id and the version and timestamp of `ledger_info` to the response headers
+pub(crate) fn add_ledger_info_headers(
+    headers: &mut header::HeaderMap,
+    chain_id: ChainId,
+    ledger_info: &LedgerInfoWithSignatures,
+) {
     headers.insert(
         X_DIEM_CHAIN_ID,
         header::HeaderValue::from_str(&chain_id.id().to_string()).unwrap(),
     );
     headers.insert(
         X_DIEM_VERSION_ID,
-        header::HeaderValue::from_str(&latest_ledger_version.to_string()).unwrap(),
+        header::HeaderValue::from_str(&ledger_info.ledger_info().version().to_string()).unwrap(),
     );
     headers.insert(
         X_DIEM_TIMESTAMP_USEC_ID,
-        header::HeaderValue::from_str(&latest_ledger_timestamp_usecs.to_string()).unwrap(),
+        header::HeaderValue::from_str(&ledger_info.ledger_info().timestamp_usecs().to_string())
+            .unwrap(),
     );
-
-    Ok(http_response)
 }
 
 async fn rpc_request_handler(
@@ -360,7 +418,8 @@ async fn rpc_request_handler(
     ledger_info: &LedgerInfoWithSignatures,
     request_type_label: &str,
     sdk_info: SdkInfo,
-) -> JsonRpcResponse {
+    caller: &Caller,
+) -> (JsonRpcResponse, Option<Method>) {
     let handler = Handler::new(service, ledger_info);
 
     let mut response = JsonRpcResponse::new(
@@ -373,15 +432,23 @@ async fn rpc_request_handler(
     match mango_json_rpc_types::request::JsonRpcRequest::from_value(request) {
         Ok(request) => {
             method = Some(request.method_request.method());
-            let timer = counters::METHOD_LATENCY
-                .with_label_values(&[request_type_label, request.method_request.method().as_str()])
-                .start_timer();
             response.id = Some(serde_json::to_value(&request.id).unwrap());
-            match handler.handle(request.method_request).await {
-                Ok(ret) => response.result = Some(ret),
+            match service.acquire_rate_limit(caller, request.method_request.method()) {
+                Ok(()) => {
+                    let timer = counters::METHOD_LATENCY
+                        .with_label_values(&[
+                            request_type_label,
+                            request.method_request.method().as_str(),
+                        ])
+                        .start_timer();
+                    match handler.handle(request.method_request).await {
+                        Ok(ret) => response.result = Some(ret),
+                        Err(e) => response.error = Some(e),
+                    }
+                    timer.stop_and_record();
+                }
                 Err(e) => response.error = Some(e),
             }
-            timer.stop_and_record();
         }
         Err((e, m, id)) => {
             method = m;
@@ -392,7 +459,30 @@ async fn rpc_request_handler(
 
     bump_counters(&response, request_type_label, method, sdk_info);
 
-    response
+    (response, method)
+}
+
+/// Client method decoding the results of `method`, if its responses can be BCS encoded
+fn bcs_method(method: Option<Method>) -> Option<mango_client::Method> {
+    let method: mango_client::Method = serde_json::from_value(method?.as_str().into()).ok()?;
+    Some(method).filter(mango_client::Method::supports_bcs_encoding)
+}
+
+/// Result of a call in a BCS encoded response, see `mango_client::MethodResponse`
+fn bcs_result(
+    response: JsonRpcResponse,
+    method: mango_client::Method,
+) -> Result<MethodResponse, JsonRpcError> {
+    match response.error {
+        Some(err) => Err(err),
+        None => MethodResponse::from_json(method, response.result.unwrap_or(Value::Null))
+            .map_err(|err| JsonRpcError::internal_error(err.to_string())),
+    }
+}
+
+fn bcs_reply<T: Serialize>(body: &T) -> Result<warp::reply::Response, warp::Rejection> {
+    let body = bcs::to_bytes(body).map_err(|_| reject::custom(BcsEncodingError))?;
+    Ok(warp::reply::with_header(body, header::CONTENT_TYPE, CONTENT_TYPE_BCS).into_response())
 }
 
 fn bump_counters(
@@ -419,6 +509,7 @@ fn bump_counters(
                 -32601 => "method_not_found",
                 -32602 => "invalid_params",
                 -32604 => "invalid_format",
+                -32013 => "rate_limited",
                 _ => "unexpected_code",
             };
             counters::INVALID_REQUESTS
@@ -451,10 +542,15 @@ fn bump_counters(
 
 /// Warp rejection types
 #[derive(Debug)]
-struct DatabaseError;
+pub(crate) struct DatabaseError;
 
 impl Reject for DatabaseError {}
 
+#[derive(Debug)]
+struct BcsEncodingError;
+
+impl Reject for BcsEncodingError {}
+
 #[derive(Debug)]
 struct HealthCheckError;
 impl warp::reject::Reject for HealthCheckError {}
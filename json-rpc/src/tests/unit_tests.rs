@@ -2,19 +2,30 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    errors::ServerCode,
+    errors::{JsonRpcError, ServerCode},
     runtime::check_latest_ledger_info_timestamp,
     tests::utils::{
-        create_database_client_and_runtime, create_db_and_runtime, mock_db, test_bootstrap,
-        MockDiemDB,
+        assert_rest_response_matches_openapi, create_database_client_and_runtime,
+        create_db_and_runtime, mock_db, test_bootstrap, test_bootstrap_with_fee_market,
+        test_bootstrap_with_rate_limit, MockDiemDB,
     },
     util::{sdk_info_from_user_agent, SdkInfo, SdkLang, SdkVersion},
-    views::VMStatusView,
+    views::{AccountView, EventView, FeeMarketBlockView, TransactionView, VMStatusView},
 };
 use futures::{channel::mpsc::channel, StreamExt};
-use mango_client::{views::TransactionDataView, BlockingClient, MethodRequest};
-use mango_config::{config::DEFAULT_CONTENT_LENGTH_LIMIT, utils};
+use mango_client::{
+    views::TransactionDataView, BlockingClient, Method, MethodRequest, MethodResponse,
+};
+use mango_config::{
+    config::{ApiKeyQuota, JsonRpcRateLimitConfig, DEFAULT_CONTENT_LENGTH_LIMIT},
+    utils,
+};
 use mango_crypto::{ed25519::Ed25519PrivateKey, hash::CryptoHash, HashValue, PrivateKey, Uniform};
+use mango_json_rpc_types::{
+    request::X_DIEM_API_KEY,
+    response::{CONTENT_TYPE_BCS, X_DIEM_VERSION_ID},
+};
+use mango_mempool::{FeeMarketBlock, FeeMarketHandle, FeeMarketSnapshot};
 use mango_metrics::get_all_metrics;
 use mango_types::{
     account_address::AccountAddress,
@@ -22,6 +33,7 @@ use mango_types::{
     account_state::AccountState,
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
     chain_id::ChainId,
+    contract_event::ContractEvent,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     mempool_status::{MempoolStatus, MempoolStatusCode},
@@ -33,12 +45,13 @@ use mango_types::{
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use std::{
     cmp::{max, min},
+    collections::BTreeMap,
     convert::TryFrom,
     ops::Sub,
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use storage_interface::DbReader;
+use storage_interface::{DbReader, Order};
 use vm_validator::{
     mocks::mock_vm_validator::MockVMValidator, vm_validator::TransactionValidation,
 };
@@ -1111,6 +1124,273 @@ fn test_transaction_submission() {
     assert_eq!(status_code, StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST);
 }
 
+#[test]
+fn test_rate_limit() {
+    let (mp_sender, _mp_events) = channel(1);
+    let port = utils::get_available_port();
+    let address = format!("127.0.0.1:{}", port);
+    let mut api_keys = BTreeMap::new();
+    api_keys.insert(
+        "test-key".to_string(),
+        ApiKeyQuota {
+            bucket_size: 10,
+            bucket_rate: 1,
+        },
+    );
+    let rate_limit_config = JsonRpcRateLimitConfig {
+        enabled: true,
+        ip_bucket_size: 10,
+        ip_bucket_rate: 1,
+        api_keys,
+        method_costs: vec![("get_metadata".to_string(), 10)].into_iter().collect(),
+        default_method_cost: 1,
+    };
+    let _runtime = test_bootstrap_with_rate_limit(
+        address.parse().unwrap(),
+        Arc::new(mock_db()),
+        mp_sender,
+        &rate_limit_config,
+    );
+    let url = format!("http://{}", address);
+    let client = reqwest::blocking::Client::new();
+    let call = |method: &str, api_key: Option<&str>| {
+        let mut request = client
+            .post(&url)
+            .json(&json!({"jsonrpc": "2.0", "method": method, "params": [], "id": 1}));
+        if let Some(api_key) = api_key {
+            request = request.header(X_DIEM_API_KEY, api_key);
+        }
+        let resp: serde_json::Value = request.send().unwrap().json().unwrap();
+        resp["error"]["code"].as_i64()
+    };
+    let rate_limited = Some(ServerCode::RateLimited as i64);
+
+    // a single get_metadata call uses up the bucket of the IP
+    assert_eq!(call("get_metadata", None), None);
+    assert_eq!(call("get_metadata", None), rate_limited);
+    assert_eq!(call("get_currencies", None), rate_limited);
+
+    // a configured API key has its own bucket, unknown keys fall back to the IP bucket
+    assert_eq!(call("get_metadata", Some("test-key")), None);
+    assert_eq!(call("get_metadata", Some("test-key")), rate_limited);
+    assert_eq!(call("get_currencies", Some("unknown-key")), rate_limited);
+}
+
+#[test]
+fn test_rest_api() {
+    let (mock_db, _runtime, url, _) = create_db_and_runtime();
+    let client = reqwest::blocking::Client::new();
+    let get = |path: &str, accept: &str| {
+        client
+            .get(&format!("{}/v1/{}", url, path))
+            .header("accept", accept)
+            .send()
+            .unwrap()
+    };
+
+    // OpenAPI document, which JSON responses are checked against
+    let resp = get("openapi.json", "");
+    assert_eq!(resp.status(), 200);
+    let openapi: serde_json::Value = resp.json().unwrap();
+    let get_json = |path: &str, openapi_path: &str| {
+        let resp = get(path, "application/json");
+        let status = resp.status().as_u16();
+        let body: Option<serde_json::Value> = if status == 404 {
+            None
+        } else {
+            Some(resp.json().unwrap())
+        };
+        assert_rest_response_matches_openapi(&openapi, openapi_path, status, body.as_ref());
+        (status, body.unwrap_or_default())
+    };
+
+    // account
+    let address = *mock_db.all_accounts.keys().next().unwrap();
+    let resp = get(&format!("accounts/{}", address), "application/json");
+    assert_eq!(
+        resp.headers()[X_DIEM_VERSION_ID],
+        mock_db.version.to_string()
+    );
+    let (status, body) = get_json(&format!("accounts/{}", address), "/accounts/{address}");
+    assert_eq!(status, 200);
+    let account: AccountView = serde_json::from_value(body).unwrap();
+    assert_eq!(account.address, address);
+    let resp = get(&format!("accounts/{}", address), CONTENT_TYPE_BCS);
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], CONTENT_TYPE_BCS);
+    let blob: AccountStateBlob = bcs::from_bytes(&resp.bytes().unwrap()).unwrap();
+    assert_eq!(
+        Some(blob),
+        mock_db.get_latest_account_state(address).unwrap()
+    );
+
+    // account resources
+    get_json(
+        &format!("accounts/{}/resources", address),
+        "/accounts/{address}/resources",
+    );
+    let resp = get(&format!("accounts/{}/resources", address), CONTENT_TYPE_BCS);
+    assert_eq!(resp.status(), 200);
+    let account_state: AccountState = bcs::from_bytes(&resp.bytes().unwrap()).unwrap();
+    assert!(account_state.get_account_resource().unwrap().is_some());
+
+    // transactions
+    let (txn, _) = &mock_db.all_txns[1];
+    let (_, body) = get_json("transactions/1", "/transactions/{version}");
+    let txn_view: TransactionView = serde_json::from_value(body).unwrap();
+    assert_eq!(txn_view.version, 1);
+    assert_eq!(txn_view.hash, txn.hash());
+    let resp = get("transactions/1", CONTENT_TYPE_BCS);
+    let bcs_txn: Transaction = bcs::from_bytes(&resp.bytes().unwrap()).unwrap();
+    assert_eq!(&bcs_txn, txn);
+    let resp = get(&format!("transactions/by_hash/{}", txn.hash()), "");
+    let txn_view_by_hash: TransactionView = resp.json().unwrap();
+    assert_eq!(txn_view_by_hash, txn_view);
+    get_json(
+        &format!("transactions/by_hash/{}", txn.hash()),
+        "/transactions/by_hash/{hash}",
+    );
+    let resp = get(
+        &format!("transactions/by_hash/{}", txn.hash()),
+        CONTENT_TYPE_BCS,
+    );
+    let bcs_txn: Transaction = bcs::from_bytes(&resp.bytes().unwrap()).unwrap();
+    assert_eq!(&bcs_txn, txn);
+
+    // events
+    let (_, event) = &mock_db.events[0];
+    let expected = mock_db
+        .get_events(event.key(), 0, Order::Ascending, 10)
+        .unwrap();
+    let (_, body) = get_json(&format!("events/{}?limit=10", event.key()), "/events/{key}");
+    let events: Vec<EventView> = serde_json::from_value(body).unwrap();
+    assert_eq!(events.len(), expected.len());
+    let resp = get(
+        &format!("events/{}?limit=10", event.key()),
+        CONTENT_TYPE_BCS,
+    );
+    let bcs_events: Vec<(u64, ContractEvent)> = bcs::from_bytes(&resp.bytes().unwrap()).unwrap();
+    assert_eq!(bcs_events, expected);
+
+    // missing resources and invalid params
+    let version = mock_db.version + 1;
+    let (status, _) = get_json(
+        &format!("transactions/{}", version),
+        "/transactions/{version}",
+    );
+    assert_eq!(status, 404);
+    let (status, _) = get_json(
+        &format!("transactions/by_hash/{}", HashValue::zero()),
+        "/transactions/by_hash/{hash}",
+    );
+    assert_eq!(status, 404);
+    let (status, _) = get_json(
+        &format!("accounts/{}", AccountAddress::random()),
+        "/accounts/{address}",
+    );
+    assert_eq!(status, 404);
+    let (status, error) = get_json("accounts/invalid", "/accounts/{address}");
+    assert_eq!(status, 400);
+    assert_eq!(error["code"], -32602);
+    let (status, _) = get_json(
+        &format!("events/{}?limit=100000", event.key()),
+        "/events/{key}",
+    );
+    assert_eq!(status, 400);
+}
+
+#[test]
+fn test_bcs_encoding() {
+    let (mock_db, _runtime, url, _) = create_db_and_runtime();
+    let client = reqwest::blocking::Client::new();
+    let post = |body: &serde_json::Value, accept: &str| {
+        client
+            .post(&url)
+            .header("content-type", "application/json")
+            .header("accept", accept)
+            .json(body)
+            .send()
+            .unwrap()
+    };
+
+    let account = get_first_account_from_mock_db(&mock_db);
+    let batch = json!([
+        {"jsonrpc": "2.0", "method": "get_state_proof", "params": [mock_db.version], "id": 1},
+        {
+            "jsonrpc": "2.0",
+            "method": "get_account_state_with_proof",
+            "params": [account.to_string(), null, null],
+            "id": 2,
+        },
+        {"jsonrpc": "2.0", "method": "get_events_with_proofs", "params": ["00", 0, 1], "id": 3},
+    ]);
+
+    // JSON unless BCS is accepted
+    let resp = post(&batch, "application/json");
+    assert_eq!(resp.headers()["content-type"], "application/json");
+    let json_responses: Vec<serde_json::Value> = resp.json().unwrap();
+
+    let resp = post(&batch, CONTENT_TYPE_BCS);
+    assert_eq!(resp.headers()["content-type"], CONTENT_TYPE_BCS);
+    assert_eq!(
+        resp.headers()[X_DIEM_VERSION_ID],
+        mock_db.version.to_string()
+    );
+    let bcs_responses: Vec<Result<MethodResponse, JsonRpcError>> =
+        bcs::from_bytes(&resp.bytes().unwrap()).unwrap();
+    assert_eq!(bcs_responses.len(), 3);
+    for (index, method) in [Method::GetStateProof, Method::GetAccountStateWithProof]
+        .iter()
+        .enumerate()
+    {
+        let expected =
+            MethodResponse::from_json(*method, json_responses[index]["result"].clone()).unwrap();
+        assert_eq!(bcs_responses[index].as_ref().unwrap(), &expected);
+    }
+    // errors are BCS encoded too
+    let error = bcs_responses[2].as_ref().unwrap_err();
+    assert_eq!(error.code, -32602);
+
+    // single calls
+    let resp = post(&batch[1], CONTENT_TYPE_BCS);
+    let bcs_response: Result<MethodResponse, JsonRpcError> =
+        bcs::from_bytes(&resp.bytes().unwrap()).unwrap();
+    assert_eq!(bcs_response.unwrap(), bcs_responses[1].clone().unwrap());
+
+    // methods without proofs are always JSON, and so are batches including them
+    let metadata = json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 4});
+    let resp = post(&metadata, CONTENT_TYPE_BCS);
+    assert_eq!(resp.headers()["content-type"], "application/json");
+    let resp = post(&json!([batch[0], metadata]), CONTENT_TYPE_BCS);
+    assert_eq!(resp.headers()["content-type"], "application/json");
+
+    // the client accepts BCS for methods with proofs
+    let client = BlockingClient::new(url.clone());
+    let responses = client
+        .batch(vec![
+            MethodRequest::get_state_proof(mock_db.version),
+            MethodRequest::get_account_state_with_proof(account, None, None),
+            MethodRequest::get_metadata(),
+        ])
+        .unwrap();
+    assert_eq!(responses.len(), 3);
+    let responses = client
+        .batch(vec![
+            MethodRequest::get_state_proof(mock_db.version),
+            MethodRequest::get_account_state_with_proof(account, None, None),
+        ])
+        .unwrap();
+    for (response, expected) in responses.into_iter().zip(&bcs_responses) {
+        assert_eq!(response.unwrap().into_inner(), expected.clone().unwrap());
+    }
+    let response = client
+        .request(MethodRequest::get_account_state_with_proof(
+            account, None, None,
+        ))
+        .unwrap();
+    assert_eq!(response.state().version, mock_db.version);
+}
+
 #[test]
 fn test_get_account() {
     let (mock_db, client, _runtime) = create_database_client_and_runtime();
@@ -1198,6 +1478,48 @@ fn test_get_metadata() {
     assert_eq!(metadata.timestamp, mock_db.timestamps[1]);
 }
 
+#[test]
+fn test_get_metadata_fee_market() {
+    let (_, client, _runtime) = create_database_client_and_runtime();
+    let metadata = client.get_metadata().unwrap().into_inner();
+    assert!(metadata.fee_market_min_gas_price.is_none());
+    assert!(metadata.fee_market_history.is_none());
+
+    let mock_db = mock_db();
+    let address = format!("127.0.0.1:{}", utils::get_available_port());
+    let (mp_sender, _) = channel(1);
+    let snapshot = FeeMarketSnapshot {
+        min_gas_price: 7,
+        history: vec![FeeMarketBlock {
+            min_gas_price: 6,
+            block_size: 10,
+            fullness: 1_000,
+        }],
+    };
+    let _runtime = test_bootstrap_with_fee_market(
+        address.parse().unwrap(),
+        Arc::new(mock_db.clone()),
+        mp_sender,
+        FeeMarketHandle::from(snapshot),
+    );
+    let client = BlockingClient::new(format!("http://{}", address));
+
+    let metadata = client.get_metadata().unwrap().into_inner();
+    assert_eq!(metadata.fee_market_min_gas_price, Some(7));
+    assert_eq!(
+        metadata.fee_market_history,
+        Some(vec![FeeMarketBlockView {
+            min_gas_price: 6,
+            block_size: 10,
+            fullness: 1_000,
+        }])
+    );
+
+    // the fee market only describes the live mempool
+    let metadata = client.get_metadata_by_version(0).unwrap().into_inner();
+    assert!(metadata.fee_market_min_gas_price.is_none());
+}
+
 #[test]
 fn test_limit_batch_size() {
     let (_, client, _runtime) = create_database_client_and_runtime();
@@ -1271,6 +1593,45 @@ fn test_get_events() {
     );
 }
 
+#[test]
+fn test_get_events_by_type() {
+    let (mock_db, client, _runtime) = create_database_client_and_runtime();
+
+    let (first_event_version, first_event) = mock_db.events[0].clone();
+    let type_tag = first_event.type_tag();
+    let expected = mock_db
+        .events
+        .iter()
+        .filter(|(version, e)| {
+            e.type_tag() == type_tag
+                && *version >= first_event_version
+                && *version <= mock_db.version
+        })
+        .collect::<Vec<_>>();
+
+    // The second page resumes after the first event, which is the first of its transaction.
+    for start_index in 0..2 {
+        let events = client
+            .get_events_by_type(type_tag, first_event_version, start_index, u64::MAX, 10)
+            .unwrap()
+            .into_inner();
+        let expected = expected.iter().skip(start_index as usize).take(10);
+        assert_eq!(events.len(), expected.len());
+        for (event, (version, expected_event)) in events.iter().zip(expected) {
+            assert_eq!(event.key, *expected_event.key());
+            assert_eq!(event.sequence_number, expected_event.sequence_number());
+            assert_eq!(event.transaction_version, *version);
+        }
+    }
+
+    // `end_version` is exclusive.
+    let events = client
+        .get_events_by_type(type_tag, first_event_version, 0, first_event_version, 10)
+        .unwrap()
+        .into_inner();
+    assert!(events.is_empty());
+}
+
 #[test]
 fn test_get_transactions() {
     let (mock_db, client, _runtime) = create_database_client_and_runtime();
@@ -1321,6 +1682,12 @@ fn test_get_transactions() {
                     }
                     _ => panic!("Returned value doesn't match!"),
                 },
+                Transaction::BlockMetadataExt(t) => match view.transaction {
+                    TransactionDataView::BlockMetadata { timestamp_usecs } => {
+                        assert_eq!(t.block_metadata().timestamp_usec(), timestamp_usecs);
+                    }
+                    _ => panic!("Returned value doesn't match!"),
+                },
                 Transaction::GenesisTransaction(_) => match view.transaction {
                     TransactionDataView::WriteSet { .. } => {}
                     _ => panic!("Returned value doesn't match!"),
@@ -1417,6 +1784,27 @@ fn test_get_account_transaction() {
     }
 }
 
+#[test]
+fn test_get_transaction_by_hash() {
+    let (mock_db, client, _runtime) = create_database_client_and_runtime();
+
+    for (txn, _) in mock_db.all_txns.iter().take(mock_db.version as usize + 1) {
+        let tx_view = client
+            .get_transaction_by_hash(txn.hash(), false)
+            .unwrap()
+            .into_inner()
+            .unwrap();
+        assert_eq!(tx_view.hash, txn.hash());
+        assert!(tx_view.events.is_empty());
+    }
+
+    let tx_view = client
+        .get_transaction_by_hash(HashValue::zero(), true)
+        .unwrap()
+        .into_inner();
+    assert!(tx_view.is_none());
+}
+
 #[test]
 fn test_get_account_transactions() {
     let (mock_db, client, _runtime) = create_database_client_and_runtime();
@@ -5,15 +5,15 @@ use anyhow::{format_err, Error, Result};
 use diemdb::test_helper::arb_blocks_to_commit;
 use mango_config::{
     config::{
-        RoleType, StreamConfig, DEFAULT_BATCH_SIZE_LIMIT, DEFAULT_CONTENT_LENGTH_LIMIT,
-        DEFAULT_PAGE_SIZE_LIMIT, DEFAULT_STREAM_RPC_MAX_POLL_INTERVAL_MS,
-        DEFAULT_STREAM_RPC_POLL_INTERVAL_MS, DEFAULT_STREAM_RPC_SEND_QUEUE_SIZE,
-        DEFAULT_STREAM_RPC_SUBSCRIPTION_FETCH_SIZE,
+        JsonRpcRateLimitConfig, RoleType, StreamConfig, DEFAULT_BATCH_SIZE_LIMIT,
+        DEFAULT_CONTENT_LENGTH_LIMIT, DEFAULT_PAGE_SIZE_LIMIT,
+        DEFAULT_STREAM_RPC_MAX_POLL_INTERVAL_MS, DEFAULT_STREAM_RPC_POLL_INTERVAL_MS,
+        DEFAULT_STREAM_RPC_SEND_QUEUE_SIZE, DEFAULT_STREAM_RPC_SUBSCRIPTION_FETCH_SIZE,
     },
     utils,
 };
-use mango_crypto::HashValue;
-use mango_mempool::{MempoolClientSender, SubmissionStatus};
+use mango_crypto::{hash::CryptoHash, HashValue};
+use mango_mempool::{FeeMarketHandle, MempoolClientSender, SubmissionStatus};
 use mango_types::{
     account_address::AccountAddress,
     account_state::AccountState,
@@ -68,6 +68,51 @@ pub fn test_bootstrap(
     address: SocketAddr,
     diem_db: Arc<dyn MoveDbReader>,
     mp_sender: MempoolClientSender,
+) -> Runtime {
+    test_bootstrap_with_rate_limit(
+        address,
+        diem_db,
+        mp_sender,
+        &JsonRpcRateLimitConfig::default(),
+    )
+}
+
+/// Creates JSON RPC server for a Validator node, rate limiting callers with `rate_limit_config`
+/// Should only be used for unit-tests
+#[allow(unused)]
+pub fn test_bootstrap_with_rate_limit(
+    address: SocketAddr,
+    diem_db: Arc<dyn MoveDbReader>,
+    mp_sender: MempoolClientSender,
+    rate_limit_config: &JsonRpcRateLimitConfig,
+) -> Runtime {
+    test_bootstrap_with_options(address, diem_db, mp_sender, None, rate_limit_config)
+}
+
+/// Creates JSON RPC server for a Validator node reporting the fee market state of `fee_market`
+/// Should only be used for unit-tests
+#[allow(unused)]
+pub fn test_bootstrap_with_fee_market(
+    address: SocketAddr,
+    diem_db: Arc<dyn MoveDbReader>,
+    mp_sender: MempoolClientSender,
+    fee_market: FeeMarketHandle,
+) -> Runtime {
+    test_bootstrap_with_options(
+        address,
+        diem_db,
+        mp_sender,
+        Some(fee_market),
+        &JsonRpcRateLimitConfig::default(),
+    )
+}
+
+fn test_bootstrap_with_options(
+    address: SocketAddr,
+    diem_db: Arc<dyn MoveDbReader>,
+    mp_sender: MempoolClientSender,
+    fee_market: Option<FeeMarketHandle>,
+    rate_limit_config: &JsonRpcRateLimitConfig,
 ) -> Runtime {
     let mut stream_config: StreamConfig = StreamConfig {
         enabled: true,
@@ -88,6 +133,8 @@ pub fn test_bootstrap(
         RoleType::Validator,
         ChainId::test(),
         &stream_config,
+        fee_market,
+        rate_limit_config,
     )
 }
 
@@ -104,6 +151,37 @@ pub struct MockDiemDB {
     pub timestamps: Vec<u64>,
 }
 
+impl MockDiemDB {
+    fn transaction_with_proof(&self, version: u64, include_events: bool) -> TransactionWithProof {
+        let (tx, status) = &self.all_txns[version as usize];
+        TransactionWithProof {
+            version,
+            transaction: tx.clone(),
+            events: if include_events {
+                let events = self
+                    .events
+                    .iter()
+                    .filter(|(ev, _)| *ev == version)
+                    .map(|(_, e)| e.clone())
+                    .collect();
+                Some(events)
+            } else {
+                None
+            },
+            proof: TransactionInfoWithProof::new(
+                TransactionAccumulatorProof::new(vec![]),
+                TransactionInfo::new(
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                    0,
+                    status.clone(),
+                ),
+            ),
+        }
+    }
+}
+
 impl DbReader for MockDiemDB {
     fn get_latest_account_state(
         &self,
@@ -173,38 +251,25 @@ impl DbReader for MockDiemDB {
                     false
                 }
             })
-            .map(|(v, (tx, status))| {
-                let txn_with_proof = TransactionWithProof {
-                    version: v as u64,
-                    transaction: tx.clone(),
-                    events: if include_events {
-                        let events = self
-                            .events
-                            .iter()
-                            .filter(|(ev, _)| *ev == v as u64)
-                            .map(|(_, e)| e.clone())
-                            .collect();
-                        Some(events)
-                    } else {
-                        None
-                    },
-                    proof: TransactionInfoWithProof::new(
-                        TransactionAccumulatorProof::new(vec![]),
-                        TransactionInfo::new(
-                            Default::default(),
-                            Default::default(),
-                            Default::default(),
-                            0,
-                            status.clone(),
-                        ),
-                    ),
-                };
-                Ok(txn_with_proof)
-            })
+            .map(|(v, _)| Ok(self.transaction_with_proof(v as u64, include_events)))
             .collect::<Result<Vec<_>>>()?;
         Ok(AccountTransactionsWithProof::new(txns_with_proofs))
     }
 
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: u64,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        Ok(self
+            .all_txns
+            .iter()
+            .take(ledger_version as usize + 1)
+            .position(|(tx, _)| tx.hash() == hash)
+            .map(|v| self.transaction_with_proof(v as u64, fetch_events)))
+    }
+
     fn get_transactions(
         &self,
         start_version: u64,
@@ -284,6 +349,38 @@ impl DbReader for MockDiemDB {
         Ok(events)
     }
 
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: u64,
+        start_index: u64,
+        end_version: u64,
+        limit: u64,
+        ledger_version: u64,
+    ) -> Result<Vec<(u64, ContractEvent)>> {
+        let events = self
+            .events
+            .iter()
+            .filter(|(version, e)| {
+                e.type_tag() == type_tag
+                    && start_version <= *version
+                    && *version < end_version
+                    && *version <= ledger_version
+            })
+            .skip_while({
+                let mut num_to_skip = start_index;
+                move |(version, _)| {
+                    let skip = *version == start_version && num_to_skip > 0;
+                    num_to_skip = num_to_skip.saturating_sub(1);
+                    skip
+                }
+            })
+            .take(limit as usize)
+            .cloned()
+            .collect();
+        Ok(events)
+    }
+
     fn get_events_with_proofs(
         &self,
         _key: &EventKey,
@@ -544,3 +641,102 @@ pub fn create_db_and_runtime() -> (
     );
     (mock_db, runtime, format!("http://{}", address), mp_events)
 }
+
+/// Asserts that the JSON `body` returned with `status` by the REST endpoint `path`, as written in
+/// the paths of the `openapi` document, matches the schema the document gives for it
+#[allow(unused)]
+pub fn assert_rest_response_matches_openapi(
+    openapi: &serde_json::Value,
+    path: &str,
+    status: u16,
+    body: Option<&serde_json::Value>,
+) {
+    let response = &openapi["paths"][path]["get"]["responses"][status.to_string()];
+    assert!(
+        response.is_object(),
+        "no {} response for {} in the OpenAPI document",
+        status,
+        path
+    );
+    let schema = &response["content"]["application/json"]["schema"];
+    match body {
+        Some(body) => assert_matches_openapi_schema(openapi, schema, body, path),
+        None => assert!(
+            schema.is_null(),
+            "{} response for {} has no body",
+            status,
+            path
+        ),
+    }
+}
+
+/// Asserts that `value` matches `schema` from the `openapi` document. Only the keywords used by
+/// the REST API document are supported. Objects may only have the properties listed by their
+/// schema unless it sets `additionalProperties`, so that new view fields can't go undocumented.
+fn assert_matches_openapi_schema(
+    openapi: &serde_json::Value,
+    schema: &serde_json::Value,
+    value: &serde_json::Value,
+    location: &str,
+) {
+    if let Some(reference) = schema["$ref"].as_str() {
+        let schema = reference
+            .strip_prefix('#')
+            .and_then(|pointer| openapi.pointer(pointer))
+            .unwrap_or_else(|| panic!("unresolved reference {}", reference));
+        return assert_matches_openapi_schema(openapi, schema, value, location);
+    }
+    if value.is_null() && schema["nullable"] == true {
+        return;
+    }
+    let matches_type = match schema["type"].as_str() {
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        Some("string") => value.is_string(),
+        Some("integer") => value.is_u64() || value.is_i64(),
+        Some("boolean") => value.is_boolean(),
+        other => panic!("unsupported schema type {:?} at {}", other, location),
+    };
+    assert!(
+        matches_type,
+        "{} is not of type {}: {}",
+        location, schema["type"], value
+    );
+
+    match value {
+        serde_json::Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                let location = format!("{}[{}]", location, index);
+                assert_matches_openapi_schema(openapi, &schema["items"], item, &location);
+            }
+        }
+        serde_json::Value::Object(fields) => {
+            for name in schema["required"].as_array().into_iter().flatten() {
+                let name = name.as_str().unwrap();
+                assert!(
+                    fields.contains_key(name),
+                    "{} misses required property {}",
+                    location,
+                    name
+                );
+            }
+            for (name, field) in fields {
+                let location = format!("{}.{}", location, name);
+                match (
+                    schema["properties"].get(name),
+                    &schema["additionalProperties"],
+                ) {
+                    (Some(property), _) => {
+                        assert_matches_openapi_schema(openapi, property, field, &location)
+                    }
+                    (None, serde_json::Value::Bool(true)) => (),
+                    (None, additional @ serde_json::Value::Object(_)) => {
+                        assert_matches_openapi_schema(openapi, additional, field, &location)
+                    }
+                    (None, _) => panic!("{} is not in the OpenAPI schema", location),
+                }
+            }
+        }
+        _ => (),
+    }
+}
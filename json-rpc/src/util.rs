@@ -1,6 +1,7 @@
 // Copyright (c) The Mango Blockchain Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use mango_json_rpc_types::response::CONTENT_TYPE_BCS;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::{fmt, str::FromStr};
@@ -118,3 +119,13 @@ pub fn sdk_info_from_user_agent(user_agent: Option<&str>) -> SdkInfo {
         None => SdkInfo::default(),
     }
 }
+
+/// Whether the `Accept` header of a request lists the BCS content type
+pub fn accepts_bcs(accept: Option<&str>) -> bool {
+    accept.map_or(false, |accept| {
+        accept
+            .split(',')
+            .filter_map(|media_range| media_range.split(';').next())
+            .any(|media_type| media_type.trim() == CONTENT_TYPE_BCS)
+    })
+}
@@ -51,6 +51,9 @@ pub enum ServerCode {
     MempoolInvalidUpdate = -32010,
     MempoolVmError = -32011,
     MempoolUnknownError = -32012,
+
+    // The caller exceeded its rate limit, see `JsonRpcRateLimitConfig`
+    RateLimited = -32013,
 }
 
 /// JSON RPC server error codes for invalid request
@@ -188,6 +191,17 @@ impl JsonRpcError {
         }
     }
 
+    pub fn rate_limited(method_name: &str) -> Self {
+        Self {
+            code: ServerCode::RateLimited as i16,
+            message: format!(
+                "Server error: rate limit exceeded for method '{}'",
+                method_name
+            ),
+            data: None,
+        }
+    }
+
     pub fn code_as_str(&self) -> &'static str {
         match InvalidRequestCode::from_i16(self.code) {
             Some(code) => code.as_str(),
@@ -44,7 +44,9 @@ pub enum Method {
     GetTransactions,
     GetAccountTransaction,
     GetAccountTransactions,
+    GetTransactionByHash,
     GetEvents,
+    GetEventsByType,
     GetCurrencies,
     GetNetworkStatus,
 
@@ -70,7 +72,9 @@ impl Method {
             Method::GetTransactions => "get_transactions",
             Method::GetAccountTransaction => "get_account_transaction",
             Method::GetAccountTransactions => "get_account_transactions",
+            Method::GetTransactionByHash => "get_transaction_by_hash",
             Method::GetEvents => "get_events",
+            Method::GetEventsByType => "get_events_by_type",
             Method::GetCurrencies => "get_currencies",
             Method::GetNetworkStatus => "get_network_status",
             Method::GetResources => "get_resources",
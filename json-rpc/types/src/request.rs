@@ -3,12 +3,17 @@
 
 use super::{Id, JsonRpcVersion, Method};
 use crate::{errors::JsonRpcError, views::BytesView};
+use mango_crypto::HashValue;
 use mango_types::{
     account_address::AccountAddress, event::EventKey, transaction::SignedTransaction,
 };
+use move_core_types::{language_storage::TypeTag, parser::parse_type_tag};
 use serde::{de, Deserialize, Serialize};
 use std::fmt;
 
+// http request header names
+pub const X_DIEM_API_KEY: &str = "X-mango-Api-Key";
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct JsonRpcRequest {
     jsonrpc: JsonRpcVersion,
@@ -70,7 +75,9 @@ pub enum MethodRequest {
     GetTransactions(GetTransactionsParams),
     GetAccountTransaction(GetAccountTransactionParams),
     GetAccountTransactions(GetAccountTransactionsParams),
+    GetTransactionByHash(GetTransactionByHashParams),
     GetEvents(GetEventsParams),
+    GetEventsByType(GetEventsByTypeParams),
     GetCurrencies(GetCurrenciesParams),
     GetNetworkStatus(GetNetworkStatusParams),
 
@@ -102,7 +109,13 @@ impl MethodRequest {
             Method::GetAccountTransactions => {
                 MethodRequest::GetAccountTransactions(serde_json::from_value(value)?)
             }
+            Method::GetTransactionByHash => {
+                MethodRequest::GetTransactionByHash(serde_json::from_value(value)?)
+            }
             Method::GetEvents => MethodRequest::GetEvents(serde_json::from_value(value)?),
+            Method::GetEventsByType => {
+                MethodRequest::GetEventsByType(serde_json::from_value(value)?)
+            }
             Method::GetCurrencies => MethodRequest::GetCurrencies(serde_json::from_value(value)?),
             Method::GetNetworkStatus => {
                 MethodRequest::GetNetworkStatus(serde_json::from_value(value)?)
@@ -140,7 +153,9 @@ impl MethodRequest {
             MethodRequest::GetTransactions(_) => Method::GetTransactions,
             MethodRequest::GetAccountTransaction(_) => Method::GetAccountTransaction,
             MethodRequest::GetAccountTransactions(_) => Method::GetAccountTransactions,
+            MethodRequest::GetTransactionByHash(_) => Method::GetTransactionByHash,
             MethodRequest::GetEvents(_) => Method::GetEvents,
+            MethodRequest::GetEventsByType(_) => Method::GetEventsByType,
             MethodRequest::GetCurrencies(_) => Method::GetCurrencies,
             MethodRequest::GetNetworkStatus(_) => Method::GetNetworkStatus,
             MethodRequest::GetResources(_) => Method::GetResources,
@@ -244,6 +259,12 @@ pub struct GetAccountTransactionsParams {
     pub include_events: bool,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetTransactionByHashParams {
+    pub hash: HashValue,
+    pub include_events: bool,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct GetEventsParams {
     pub key: EventKey,
@@ -251,6 +272,35 @@ pub struct GetEventsParams {
     pub limit: u64,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetEventsByTypeParams {
+    #[serde(serialize_with = "serialize_type_tag")]
+    #[serde(deserialize_with = "deserialize_type_tag")]
+    pub type_tag: TypeTag,
+    pub start_version: u64,
+    pub end_version: u64,
+    pub limit: u64,
+    /// The number of events of the type emitted by the transaction at `start_version` to skip.
+    #[serde(default)]
+    pub start_index: u64,
+}
+
+fn serialize_type_tag<S>(type_tag: &TypeTag, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    type_tag.to_string().serialize(serializer)
+}
+
+fn deserialize_type_tag<'de, D>(deserializer: D) -> Result<TypeTag, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    let type_tag = String::deserialize(deserializer)?;
+    parse_type_tag(&type_tag).map_err(|_| D::Error::custom("expected Move type tag"))
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct GetCurrenciesParams;
 
@@ -560,6 +610,38 @@ mod test {
         }));
     }
 
+    #[test]
+    fn get_transaction_by_hash() {
+        let parse_ok = |value| serde_json::from_value::<GetTransactionByHashParams>(value).unwrap();
+        let parse_err =
+            |value| serde_json::from_value::<GetTransactionByHashParams>(value).unwrap_err();
+
+        let hash = "ee9b9c9d9d6a6f5b4d7f1e5a3ba7e0b4c3c16c4a6d3ab6d4f0ab3b2c5d8e7f61";
+
+        // Array with all params
+        parse_ok(json!([hash, false]));
+
+        // Array with too many params
+        parse_err(json!([hash, false, "foo"]));
+
+        // Array with wrong param
+        parse_err(json!(["foo", false]));
+
+        // Array with too few params
+        parse_err(json!([hash]));
+
+        // Object params
+        parse_ok(json!({
+            "hash": hash,
+            "include_events": true,
+        }));
+
+        // Object without all params
+        parse_err(json!({
+            "include_events": true,
+        }));
+    }
+
     #[test]
     fn get_account_transactions() {
         let parse_ok =
@@ -709,6 +791,54 @@ mod test {
         }));
     }
 
+    #[test]
+    fn get_events_by_type() {
+        let parse_ok = |value| serde_json::from_value::<GetEventsByTypeParams>(value).unwrap();
+        let parse_err = |value| serde_json::from_value::<GetEventsByTypeParams>(value).unwrap_err();
+
+        let type_tag = "0x1::DiemAccount::ReceivedPaymentEvent";
+
+        // Array with all params
+        let params = parse_ok(json!([type_tag, 0, 100, 10]));
+        assert_eq!(params.type_tag.to_string(), type_tag);
+
+        // Generic type tag
+        parse_ok(json!(["vector<0x1::XDX::XDX>", 0, 100, 10]));
+
+        // Array with the optional start index
+        let params = parse_ok(json!([type_tag, 0, 100, 10, 2]));
+        assert_eq!(params.start_index, 2);
+
+        // Array with too many params
+        parse_err(json!([type_tag, 0, 100, 10, 2, false]));
+
+        // Array with wrong param
+        parse_err(json!(["0x1::DiemAccount", 0, 100, 10]));
+
+        // Array with too few params
+        parse_err(json!([type_tag, 0, 100]));
+
+        // Object params
+        parse_ok(json!({
+            "type_tag": type_tag,
+            "start_version": 0,
+            "end_version": 100,
+            "limit": 10,
+        }));
+
+        // Object without all params
+        parse_err(json!({
+            "start_version": 0,
+            "end_version": 100,
+            "limit": 10,
+        }));
+
+        // Serialized params can be parsed back
+        let value = serde_json::to_value(&params).unwrap();
+        assert_eq!(value["type_tag"], type_tag);
+        parse_ok(value);
+    }
+
     #[test]
     fn get_currencies() {
         let parse_ok = |value| serde_json::from_value::<GetCurrenciesParams>(value).unwrap();
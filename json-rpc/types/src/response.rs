@@ -9,6 +9,9 @@ pub const X_DIEM_CHAIN_ID: &str = "X-mango-Chain-Id";
 pub const X_DIEM_VERSION_ID: &str = "X-mango-Ledger-Version";
 pub const X_DIEM_TIMESTAMP_USEC_ID: &str = "X-mango-Ledger-TimestampUsec";
 
+/// Media type of BCS encoded responses, requested through the `Accept` header
+pub const CONTENT_TYPE_BCS: &str = "application/x-bcs";
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct JsonRpcResponse {
     pub diem_chain_id: u8,
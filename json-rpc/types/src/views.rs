@@ -36,7 +36,9 @@ use move_core_types::{
     move_resource::MoveStructType,
     vm_status::AbortLocation,
 };
-use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+    de::Error as _, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer,
+};
 use std::{
     collections::BTreeMap,
     convert::{TryFrom, TryInto},
@@ -298,15 +300,36 @@ impl TryFrom<&EventWithProof> for EventWithProofView {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 pub struct EventByVersionWithProofView {
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub lower_bound_incl: Option<EventWithProofView>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub upper_bound_excl: Option<EventWithProofView>,
 }
 
+impl Serialize for EventByVersionWithProofView {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let human_readable = serializer.is_human_readable();
+        let mut state = serializer.serialize_struct("EventByVersionWithProofView", 2)?;
+        serialize_optional_field(
+            &mut state,
+            human_readable,
+            "lower_bound_incl",
+            &self.lower_bound_incl,
+        )?;
+        serialize_optional_field(
+            &mut state,
+            human_readable,
+            "upper_bound_excl",
+            &self.upper_bound_excl,
+        )?;
+        state.end()
+    }
+}
+
 impl TryFrom<&EventByVersionWithProofView> for EventByVersionWithProof {
     type Error = Error;
 
@@ -579,6 +602,10 @@ pub struct MetadataView {
     pub diem_version: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dual_attestation_limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_market_min_gas_price: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_market_history: Option<Vec<FeeMarketBlockView>>,
 }
 
 impl MetadataView {
@@ -597,9 +624,16 @@ impl MetadataView {
             module_publishing_allowed: None,
             diem_version: None,
             dual_attestation_limit: None,
+            fee_market_min_gas_price: None,
+            fee_market_history: None,
         }
     }
 
+    pub fn with_fee_market(&mut self, min_gas_price: u64, history: Vec<FeeMarketBlockView>) {
+        self.fee_market_min_gas_price = Some(min_gas_price);
+        self.fee_market_history = Some(history);
+    }
+
     pub fn with_diem_root(&mut self, diem_root: &AccountState) -> Result<()> {
         if let Some(vm_publishing_option) = diem_root.get_vm_publishing_option()? {
             self.script_hash_allow_list = Some(vm_publishing_option.script_allow_list);
@@ -615,6 +649,13 @@ impl MetadataView {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct FeeMarketBlockView {
+    pub min_gas_price: u64,
+    pub block_size: u64,
+    pub fullness: u64,
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct BytesView(Box<[u8]>);
 
@@ -678,19 +719,47 @@ impl<'de> Deserialize<'de> for BytesView {
     where
         D: Deserializer<'de>,
     {
-        let s = <String>::deserialize(deserializer)?;
-        <Vec<u8>>::from_hex(s)
-            .map_err(D::Error::custom)
-            .map(Into::into)
+        if deserializer.is_human_readable() {
+            let s = <String>::deserialize(deserializer)?;
+            <Vec<u8>>::from_hex(s)
+                .map_err(D::Error::custom)
+                .map(Into::into)
+        } else {
+            <Vec<u8>>::deserialize(deserializer).map(Into::into)
+        }
     }
 }
 
+/// Hex-encoded in human readable formats such as JSON, raw bytes in BCS
 impl Serialize for BytesView {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        hex::encode(self).serialize(serializer)
+        if serializer.is_human_readable() {
+            hex::encode(self).serialize(serializer)
+        } else {
+            serializer.serialize_bytes(self)
+        }
+    }
+}
+
+/// Serializes an optional struct field, which is omitted when `None` from human readable formats
+/// such as JSON. BCS can't tell which fields are missing, so it always gets the `Option`.
+fn serialize_optional_field<S, T>(
+    state: &mut S,
+    human_readable: bool,
+    key: &'static str,
+    value: &Option<T>,
+) -> Result<(), S::Error>
+where
+    S: SerializeStruct,
+    T: Serialize,
+{
+    if human_readable && value.is_none() {
+        state.skip_field(key)
+    } else {
+        state.serialize_field(key, value)
     }
 }
 
@@ -931,14 +1000,32 @@ impl TryFrom<AccountTransactionsWithProof> for TransactionListView {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 pub struct TransactionsWithProofsView {
     pub serialized_transactions: Vec<BytesView>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub serialized_events: Option<BytesView>,
     pub proofs: TransactionsProofsView,
 }
 
+impl Serialize for TransactionsWithProofsView {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let human_readable = serializer.is_human_readable();
+        let mut state = serializer.serialize_struct("TransactionsWithProofsView", 3)?;
+        state.serialize_field("serialized_transactions", &self.serialized_transactions)?;
+        serialize_optional_field(
+            &mut state,
+            human_readable,
+            "serialized_events",
+            &self.serialized_events,
+        )?;
+        state.serialize_field("proofs", &self.proofs)?;
+        state.end()
+    }
+}
+
 impl TransactionsWithProofsView {
     pub fn try_into_txn_list_with_proof(
         &self,
@@ -1102,6 +1189,9 @@ impl From<Transaction> for TransactionDataView {
             Transaction::BlockMetadata(t) => TransactionDataView::BlockMetadata {
                 timestamp_usecs: t.timestamp_usec(),
             },
+            Transaction::BlockMetadataExt(t) => TransactionDataView::BlockMetadata {
+                timestamp_usecs: t.block_metadata().timestamp_usec(),
+            },
             Transaction::GenesisTransaction(_) => TransactionDataView::WriteSet {},
             Transaction::UserTransaction(t) => {
                 let script_hash = match t.payload() {
@@ -1440,14 +1530,27 @@ impl TryFrom<&AccumulatorConsistencyProofView> for AccumulatorConsistencyProof {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 pub struct AccountStateWithProofView {
     pub version: u64,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub blob: Option<BytesView>,
     pub proof: AccountStateProofView,
 }
 
+impl Serialize for AccountStateWithProofView {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let human_readable = serializer.is_human_readable();
+        let mut state = serializer.serialize_struct("AccountStateWithProofView", 3)?;
+        state.serialize_field("version", &self.version)?;
+        serialize_optional_field(&mut state, human_readable, "blob", &self.blob)?;
+        state.serialize_field("proof", &self.proof)?;
+        state.end()
+    }
+}
+
 impl TryFrom<AccountStateWithProof> for AccountStateWithProofView {
     type Error = Error;
 
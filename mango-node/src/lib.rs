@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use backup_service::start_backup_service;
-use consensus::{consensus_provider::start_consensus, gen_consensus_reconfig_subscription};
+use consensus::{
+    consensus_provider::{start_consensus, start_consensus_on},
+    gen_consensus_reconfig_subscription,
+};
 use debug_interface::node_debug_service::NodeDebugService;
 use diem_vm::DiemVM;
 use diemdb::DiemDB;
@@ -16,7 +19,7 @@ use mango_config::{
 };
 use mango_json_rpc::bootstrap_from_config as bootstrap_rpc;
 use mango_logger::{prelude::*, Logger};
-use mango_mempool::gen_mempool_reconfig_subscription;
+use mango_mempool::{gen_mempool_reconfig_subscription, FeeMarketHandle};
 
 use mango_time_service::TimeService;
 use mango_types::{
@@ -37,9 +40,9 @@ use std::{
     },
     time::Instant,
 };
-use storage_interface::DbReaderWriter;
+use storage_interface::{DbReader, DbReaderWriter};
 use storage_service::start_storage_service_with_db;
-use tokio::runtime::{Builder, Runtime};
+use tokio::runtime::{Builder, Handle, Runtime};
 use tokio_stream::wrappers::IntervalStream;
 
 const AC_SMP_CHANNEL_BUFFER_SIZE: usize = 1_024;
@@ -48,12 +51,20 @@ const MEMPOOL_NETWORK_CHANNEL_BUFFER_SIZE: usize = 1_024;
 
 pub struct DiemHandle {
     _rpc: Runtime,
-    _mempool: Runtime,
+    _mempool: Option<Runtime>,
     _state_sync_bootstrapper: StateSyncBootstrapper,
     _network_runtimes: Vec<Runtime>,
     _consensus_runtime: Option<Runtime>,
     _debug: NodeDebugService,
     _backup: Runtime,
+    db_reader: Arc<dyn DbReader>,
+}
+
+impl DiemHandle {
+    /// Returns a reader of the node's DiemDB.
+    pub fn db_reader(&self) -> Arc<dyn DbReader> {
+        Arc::clone(&self.db_reader)
+    }
 }
 
 pub fn start(config: &NodeConfig, log_file: Option<PathBuf>) {
@@ -158,8 +169,8 @@ pub fn load_test_environment<R>(
     println!("\tStream-RPC enabled!");
 
     println!(
-        "\tFullNode network: {}",
-        config.full_node_networks[0].listen_address
+        "\tFullNode network: {:?}",
+        config.full_node_networks[0].listen_addresses
     );
     println!("\tChainId: {}", ChainId::test());
     println!();
@@ -252,6 +263,39 @@ async fn periodic_state_dump(node_config: NodeConfig, db: DbReaderWriter) {
 }
 
 pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>) -> DiemHandle {
+    setup_environment_with_time_service(node_config, logger, TimeService::real())
+}
+
+/// Same as `setup_environment` but the networks and consensus are driven by the given
+/// `TimeService`, which lets tests control the clock of the node's networking stack, the round
+/// timeouts and the block timestamps.
+pub fn setup_environment_with_time_service(
+    node_config: &NodeConfig,
+    logger: Option<Arc<Logger>>,
+    time_service: TimeService,
+) -> DiemHandle {
+    setup_environment_inner(node_config, logger, time_service, None)
+}
+
+/// Same as `setup_environment_with_time_service`, but the networks, mempool, state sync and
+/// consensus are spawned on `executor` instead of runtimes of their own, so that a test can drive
+/// them from a single thread. Consensus is started by a task on `executor` once state sync is
+/// initialized.
+pub fn setup_environment_on(
+    node_config: &NodeConfig,
+    logger: Option<Arc<Logger>>,
+    time_service: TimeService,
+    executor: Handle,
+) -> DiemHandle {
+    setup_environment_inner(node_config, logger, time_service, Some(executor))
+}
+
+fn setup_environment_inner(
+    node_config: &NodeConfig,
+    logger: Option<Arc<Logger>>,
+    time_service: TimeService,
+    executor: Option<Handle>,
+) -> DiemHandle {
     let debug_if = setup_debug_interface(node_config, logger);
 
     // let metrics_port = node_config.debug_interface.metrics_server_port;
@@ -269,14 +313,17 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
             &node_config.storage.dir(),
             false, /* readonly */
             node_config.storage.prune_window,
-            node_config.storage.rocksdb_config,
+            node_config.storage.rocksdb_config.clone(),
         )
-        .expect("DB should open."),
+        .expect("DB should open.")
+        .with_event_by_type_index(node_config.storage.enable_event_by_type_index)
+        .with_write_set_storage(node_config.storage.enable_write_set_storage),
     );
     let _simple_storage_service = start_storage_service_with_db(node_config, Arc::clone(&diem_db));
     let backup_service = start_backup_service(
         node_config.storage.backup_service_address,
         Arc::clone(&diem_db),
+        node_config.storage.checkpoint_root.clone(),
     );
 
     let genesis_waypoint = node_config.base.waypoint.genesis_waypoint();
@@ -324,22 +371,28 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
 
     // Instantiate every network and collect the requisite endpoints for state_sync, mempool, and consensus.
     for (idx, network_config) in network_configs.into_iter().enumerate() {
-        debug!("Creating runtime for {}", network_config.network_id);
-        let runtime = Builder::new_multi_thread()
-            .thread_name(format!("network-{}", network_config.network_id))
-            .enable_all()
-            .build()
-            .expect("Failed to start runtime. Won't be able to start networking.");
+        let (handle, runtime) = match &executor {
+            Some(executor) => (executor.clone(), None),
+            None => {
+                debug!("Creating runtime for {}", network_config.network_id);
+                let runtime = Builder::new_multi_thread()
+                    .thread_name(format!("network-{}", network_config.network_id))
+                    .enable_all()
+                    .build()
+                    .expect("Failed to start runtime. Won't be able to start networking.");
+                (runtime.handle().clone(), Some(runtime))
+            }
+        };
 
         // Entering here gives us a runtime to instantiate all the pieces of the builder
-        let _enter = runtime.enter();
+        let _enter = handle.enter();
 
         // Perform common instantiation steps
         let mut network_builder = NetworkBuilder::create(
             chain_id,
             node_config.base.role,
             network_config,
-            TimeService::real(),
+            time_service.clone(),
         );
         let network_id = network_config.network_id.clone();
 
@@ -379,10 +432,10 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         reconfig_subscriptions.append(network_builder.reconfig_subscriptions());
 
         let network_context = network_builder.network_context();
-        network_builder.build(runtime.handle().clone());
+        network_builder.build(handle.clone());
         network_builder.start();
         debug!("Network built for network context: {}", network_context);
-        network_runtimes.push(runtime);
+        network_runtimes.extend(runtime);
     }
 
     // TODO set up on-chain discovery network based on UpstreamConfig.fallback_network
@@ -397,33 +450,71 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         );
 
     // Create state sync bootstrapper
-    let state_sync_bootstrapper = StateSyncBootstrapper::bootstrap(
-        state_sync_network_handles,
-        mempool_notifier,
-        consensus_listener,
-        Arc::clone(&db_rw.reader),
-        chunk_executor,
-        node_config,
-        genesis_waypoint,
-        reconfig_subscriptions,
-    );
+    let state_sync_bootstrapper = match &executor {
+        Some(executor) => StateSyncBootstrapper::bootstrap_on(
+            executor,
+            state_sync_network_handles,
+            mempool_notifier,
+            consensus_listener,
+            db_rw.clone(),
+            chunk_executor,
+            node_config,
+            genesis_waypoint,
+            reconfig_subscriptions,
+        ),
+        None => StateSyncBootstrapper::bootstrap(
+            state_sync_network_handles,
+            mempool_notifier,
+            consensus_listener,
+            db_rw.clone(),
+            chunk_executor,
+            node_config,
+            genesis_waypoint,
+            reconfig_subscriptions,
+        ),
+    };
     let (mp_client_sender, mp_client_events) = channel(AC_SMP_CHANNEL_BUFFER_SIZE);
 
-    let rpc_runtime = bootstrap_rpc(node_config, chain_id, diem_db.clone(), mp_client_sender);
+    let fee_market_handle = FeeMarketHandle::default();
+
+    let rpc_runtime = bootstrap_rpc(
+        node_config,
+        chain_id,
+        diem_db.clone(),
+        mp_client_sender,
+        fee_market_handle.clone(),
+    );
 
     let mut consensus_runtime = None;
     let (consensus_to_mempool_sender, consensus_requests) = channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
 
     instant = Instant::now();
-    let mempool = mango_mempool::bootstrap(
-        node_config,
-        Arc::clone(&db_rw.reader),
-        mempool_network_handles,
-        mp_client_events,
-        consensus_requests,
-        mempool_listener,
-        mempool_reconfig_events,
-    );
+    let mempool = match &executor {
+        Some(executor) => {
+            mango_mempool::bootstrap_on(
+                executor,
+                node_config,
+                Arc::clone(&db_rw.reader),
+                mempool_network_handles,
+                mp_client_events,
+                consensus_requests,
+                mempool_listener,
+                mempool_reconfig_events,
+                fee_market_handle,
+            );
+            None
+        }
+        None => Some(mango_mempool::bootstrap(
+            node_config,
+            Arc::clone(&db_rw.reader),
+            mempool_network_handles,
+            mp_client_events,
+            consensus_requests,
+            mempool_listener,
+            mempool_reconfig_events,
+            fee_market_handle,
+        )),
+    };
     debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
     // StateSync should be instantiated and started before Consensus to avoid a cyclic dependency:
@@ -432,34 +523,60 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
     if let Some((consensus_network_sender, consensus_network_events)) = consensus_network_handles {
         let state_sync_client = state_sync_bootstrapper.create_client();
 
-        // Make sure that state synchronizer is caught up at least to its waypoint
-        // (in case it's present). There is no sense to start consensus prior to that.
-        // TODO: Note that we need the networking layer to be able to discover & connect to the
-        // peers with potentially outdated network identity public keys.
-        debug!("Wait until state sync is initialized");
-        block_on(state_sync_client.wait_until_initialized())
-            .expect("State sync initialization failure");
-        debug!("State sync initialization complete.");
-
-        // Initialize and start consensus.
-        instant = Instant::now();
-        consensus_runtime = Some(start_consensus(
-            node_config,
-            consensus_network_sender,
-            consensus_network_events,
-            Box::new(consensus_notifier),
-            consensus_to_mempool_sender,
-            diem_db,
-            consensus_reconfig_events,
-        ));
-        debug!("Consensus started in {} ms", instant.elapsed().as_millis());
+        match &executor {
+            // State sync runs on the executor too, so it can't be waited for here.
+            Some(executor) => {
+                let node_config = node_config.clone();
+                executor.spawn(async move {
+                    state_sync_client
+                        .wait_until_initialized()
+                        .await
+                        .expect("State sync initialization failure");
+                    start_consensus_on(
+                        &Handle::current(),
+                        &node_config,
+                        consensus_network_sender,
+                        consensus_network_events,
+                        Box::new(consensus_notifier),
+                        consensus_to_mempool_sender,
+                        diem_db,
+                        consensus_reconfig_events,
+                        time_service,
+                    );
+                });
+            }
+            None => {
+                // Make sure that state synchronizer is caught up at least to its waypoint
+                // (in case it's present). There is no sense to start consensus prior to that.
+                // TODO: Note that we need the networking layer to be able to discover & connect
+                // to the peers with potentially outdated network identity public keys.
+                debug!("Wait until state sync is initialized");
+                block_on(state_sync_client.wait_until_initialized())
+                    .expect("State sync initialization failure");
+                debug!("State sync initialization complete.");
+
+                // Initialize and start consensus.
+                instant = Instant::now();
+                consensus_runtime = Some(start_consensus(
+                    node_config,
+                    consensus_network_sender,
+                    consensus_network_events,
+                    Box::new(consensus_notifier),
+                    consensus_to_mempool_sender,
+                    diem_db,
+                    consensus_reconfig_events,
+                    time_service,
+                ));
+                debug!("Consensus started in {} ms", instant.elapsed().as_millis());
+            }
+        }
     }
 
     // Spawn a task which will periodically dump some interesting state
     debug_if
         .runtime()
         .handle()
-        .spawn(periodic_state_dump(node_config.to_owned(), db_rw));
+        .spawn(periodic_state_dump(node_config.to_owned(), db_rw.clone()));
 
     DiemHandle {
         _network_runtimes: network_runtimes,
@@ -469,5 +586,6 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         _consensus_runtime: consensus_runtime,
         _debug: debug_if,
         _backup: backup_service,
+        db_reader: db_rw.reader,
     }
 }
@@ -0,0 +1,133 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dynamic fee floor used when consensus pulls blocks in fee market mode.
+//!
+//! After every pull the floor is adjusted towards the configured target fullness, in the spirit of
+//! EIP-1559: a block fuller than the target raises the floor by up to `1/max_change_denominator`,
+//! an emptier one lowers it by the same proportion, never going below `min_gas_price`.
+use crate::counters;
+use mango_config::config::FeeMarketConfig;
+use mango_infallible::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{cmp::max, collections::VecDeque, sync::Arc};
+
+/// Block fullness is tracked in per-mille to keep all arithmetic in integers.
+const FULLNESS_SCALE: u64 = 1_000;
+
+/// Fee market state of a single pulled block.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FeeMarketBlock {
+    /// Floor that was applied while pulling the block.
+    pub min_gas_price: u64,
+    /// Number of transactions pulled for the block.
+    pub block_size: u64,
+    /// Fullness of the block in per-mille of the requested block size.
+    pub fullness: u64,
+}
+
+/// Point-in-time view of the fee market, shared with the JSON-RPC service.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FeeMarketSnapshot {
+    /// Floor that will be applied to the next pulled block.
+    pub min_gas_price: u64,
+    /// Most recent pulled blocks, oldest first.
+    pub history: Vec<FeeMarketBlock>,
+}
+
+/// Cloneable read handle on the latest `FeeMarketSnapshot`.
+#[derive(Clone, Debug, Default)]
+pub struct FeeMarketHandle(Arc<RwLock<FeeMarketSnapshot>>);
+
+impl FeeMarketHandle {
+    pub fn snapshot(&self) -> FeeMarketSnapshot {
+        self.0.read().clone()
+    }
+
+    fn publish(&self, snapshot: FeeMarketSnapshot) {
+        *self.0.write() = snapshot;
+    }
+}
+
+impl From<FeeMarketSnapshot> for FeeMarketHandle {
+    /// A handle on a fixed snapshot, for services that are not backed by a mempool.
+    fn from(snapshot: FeeMarketSnapshot) -> Self {
+        Self(Arc::new(RwLock::new(snapshot)))
+    }
+}
+
+pub(crate) struct FeeMarket {
+    config: FeeMarketConfig,
+    min_gas_price: u64,
+    history: VecDeque<FeeMarketBlock>,
+    handle: FeeMarketHandle,
+}
+
+impl FeeMarket {
+    pub(crate) fn new(config: &FeeMarketConfig, handle: FeeMarketHandle) -> Self {
+        let fee_market = Self {
+            config: config.clone(),
+            min_gas_price: config.min_gas_price,
+            history: VecDeque::with_capacity(config.history_size),
+            handle,
+        };
+        fee_market.publish();
+        fee_market
+    }
+
+    /// Floor to apply to the next pulled block.
+    pub(crate) fn min_gas_price(&self) -> u64 {
+        self.min_gas_price
+    }
+
+    /// Records a pulled block of `block_size` transactions out of `max_block_size` requested and
+    /// moves the floor accordingly.
+    pub(crate) fn record_block(&mut self, block_size: u64, max_block_size: u64) {
+        let fullness = block_size.min(max_block_size) * FULLNESS_SCALE / max(max_block_size, 1);
+        if self.history.len() >= self.config.history_size {
+            self.history.pop_front();
+        }
+        if self.config.history_size > 0 {
+            self.history.push_back(FeeMarketBlock {
+                min_gas_price: self.min_gas_price,
+                block_size,
+                fullness,
+            });
+        }
+        self.min_gas_price = self.next_min_gas_price(fullness);
+
+        counters::FEE_MARKET_MIN_GAS_PRICE.set(self.min_gas_price as i64);
+        counters::FEE_MARKET_BLOCK_FULLNESS.observe(fullness as f64 / FULLNESS_SCALE as f64);
+        self.publish();
+    }
+
+    fn next_min_gas_price(&self, fullness: u64) -> u64 {
+        let target = max(
+            self.config.target_block_fullness_pct.min(100) * FULLNESS_SCALE / 100,
+            1,
+        );
+        let denominator = max(self.config.max_change_denominator, 1);
+        let floor = self.min_gas_price;
+        let next = if fullness > target {
+            // always move up by at least one unit so a zero floor can start rising
+            let delta = floor.saturating_mul(fullness - target) / target / denominator;
+            floor.saturating_add(max(delta, 1))
+        } else {
+            // round the decrease up so that a small floor can still come back down
+            let divisor = target * denominator;
+            let delta = floor
+                .saturating_mul(target - fullness)
+                .saturating_add(divisor - 1)
+                / divisor;
+            floor.saturating_sub(delta)
+        };
+        max(next, self.config.min_gas_price)
+    }
+
+    fn publish(&self) {
+        self.handle.publish(FeeMarketSnapshot {
+            min_gas_price: self.min_gas_price,
+            history: self.history.iter().copied().collect(),
+        });
+    }
+}
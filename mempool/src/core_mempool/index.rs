@@ -11,7 +11,7 @@ use mango_logger::prelude::*;
 use mango_types::{account_address::AccountAddress, transaction::GovernanceRole};
 use rand::seq::SliceRandom;
 use std::{
-    cmp::Ordering,
+    cmp::{Ordering, Reverse},
     collections::{btree_set::Iter, BTreeMap, BTreeSet, HashMap},
     iter::Rev,
     ops::Bound,
@@ -26,25 +26,89 @@ pub type AccountTransactions = BTreeMap<u64, MempoolTransaction>;
 ///
 /// We don't store the full content of transactions in the index.
 /// Instead we use `OrderedQueueKey` - logical reference to the transaction in the main store.
+///
+/// The same transactions are also grouped by sender, with the senders ordered by fee, which is
+/// what the fee market walks to build blocks out of sender chains.
 pub struct PriorityIndex {
     data: BTreeSet<OrderedQueueKey>,
+    senders: HashMap<AccountAddress, BTreeMap<u64, OrderedQueueKey>>,
+    sender_order: BTreeSet<SenderOrderKey>,
 }
 
 pub type PriorityQueueIter<'a> = Rev<Iter<'a, OrderedQueueKey>>;
 
+/// Ranks senders by the highest governance role priority of their transactions, then by the
+/// average ranking score of their transactions.
+type SenderOrderKey = (Reverse<u64>, Reverse<u128>, AccountAddress);
+
 impl PriorityIndex {
     pub(crate) fn new() -> Self {
         Self {
             data: BTreeSet::new(),
+            senders: HashMap::new(),
+            sender_order: BTreeSet::new(),
         }
     }
 
     pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
-        self.data.insert(self.make_key(txn));
+        let key = self.make_key(txn);
+        let address = key.address;
+        let sequence_number = key.sequence_number.transaction_sequence_number;
+        self.data.insert(key.clone());
+
+        self.remove_sender_order(&address);
+        self.senders
+            .entry(address)
+            .or_default()
+            .insert(sequence_number, key);
+        self.insert_sender_order(&address);
     }
 
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
-        self.data.remove(&self.make_key(txn));
+        let key = self.make_key(txn);
+        self.data.remove(&key);
+
+        let address = key.address;
+        let sequence_number = key.sequence_number.transaction_sequence_number;
+        let is_indexed = self
+            .senders
+            .get(&address)
+            .and_then(|txns| txns.get(&sequence_number))
+            .map_or(false, |indexed| *indexed == key);
+        if is_indexed {
+            self.remove_sender_order(&address);
+            if let Some(txns) = self.senders.get_mut(&address) {
+                txns.remove(&sequence_number);
+                if txns.is_empty() {
+                    self.senders.remove(&address);
+                }
+            }
+            self.insert_sender_order(&address);
+        }
+    }
+
+    fn sender_order_key(&self, address: &AccountAddress) -> Option<SenderOrderKey> {
+        let txns = self.senders.get(address)?;
+        let priority = txns
+            .values()
+            .map(|key| key.governance_role.priority())
+            .max()
+            .unwrap_or(0);
+        let total_score: u128 = txns.values().map(|key| key.gas_ranking_score as u128).sum();
+        let average_score = total_score / txns.len() as u128;
+        Some((Reverse(priority), Reverse(average_score), *address))
+    }
+
+    fn remove_sender_order(&mut self, address: &AccountAddress) {
+        if let Some(order_key) = self.sender_order_key(address) {
+            self.sender_order.remove(&order_key);
+        }
+    }
+
+    fn insert_sender_order(&mut self, address: &AccountAddress) {
+        if let Some(order_key) = self.sender_order_key(address) {
+            self.sender_order.insert(order_key);
+        }
     }
 
     pub(crate) fn contains(&self, txn: &MempoolTransaction) -> bool {
@@ -65,6 +129,16 @@ impl PriorityIndex {
         self.data.iter().rev()
     }
 
+    /// Iterates over the senders, best paying first, along with their transactions ordered by
+    /// sequence number.
+    pub(crate) fn iter_senders(
+        &self,
+    ) -> impl Iterator<Item = (&AccountAddress, &BTreeMap<u64, OrderedQueueKey>)> {
+        self.sender_order
+            .iter()
+            .filter_map(move |(_, _, address)| self.senders.get_key_value(address))
+    }
+
     pub(crate) fn size(&self) -> usize {
         self.data.len()
     }
@@ -5,6 +5,7 @@
 //! agreed upon.
 use crate::{
     core_mempool::{
+        fee_market::{FeeMarket, FeeMarketHandle},
         index::TxnPointer,
         transaction::{MempoolTransaction, TimelineState},
         transaction_store::TransactionStore,
@@ -37,10 +38,26 @@ pub struct Mempool {
     // takes to pick it up by consensus.
     pub(crate) metrics_cache: TtlCache<(AccountAddress, u64), SystemTime>,
     pub system_transaction_timeout: Duration,
+    // Dynamic fee floor, only present when the fee market is enabled.
+    fee_market: Option<FeeMarket>,
 }
 
 impl Mempool {
     pub fn new(config: &NodeConfig) -> Self {
+        Self::new_with_fee_market(config, FeeMarketHandle::default())
+    }
+
+    /// Creates a mempool that publishes its fee market state to `fee_market_handle`.
+    /// The handle is left untouched if the fee market is disabled in `config`.
+    pub fn new_with_fee_market(config: &NodeConfig, fee_market_handle: FeeMarketHandle) -> Self {
+        let fee_market = if config.mempool.fee_market.enabled {
+            Some(FeeMarket::new(
+                &config.mempool.fee_market,
+                fee_market_handle,
+            ))
+        } else {
+            None
+        };
         Mempool {
             transactions: TransactionStore::new(&config.mempool),
             sequence_number_cache: TtlCache::new(config.mempool.capacity, Duration::from_secs(100)),
@@ -48,6 +65,7 @@ impl Mempool {
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
+            fee_market,
         }
     }
 
@@ -178,12 +196,56 @@ impl Mempool {
     /// `batch_size` - size of requested block.
     /// `seen_txns` - transactions that were sent to Consensus but were not committed yet,
     ///  mempool should filter out such transactions.
-    #[allow(clippy::explicit_counter_loop)]
     pub(crate) fn get_block(
         &mut self,
         batch_size: u64,
         mut seen: HashSet<TxnPointer>,
     ) -> Vec<SignedTransaction> {
+        let seen_size = seen.len();
+        let (result, txn_walked) = match self.fee_market.as_ref().map(FeeMarket::min_gas_price) {
+            Some(min_gas_price) => self.pull_sender_chains(batch_size, &mut seen, min_gas_price),
+            None => self.pull_by_priority(batch_size, &mut seen),
+        };
+        let result_size = result.len();
+        // convert transaction pointers to real values
+        let mut block_log = TxnsLog::new();
+        let block: Vec<_> = result
+            .into_iter()
+            .filter_map(|(address, tx_seq)| {
+                block_log.add(address, tx_seq);
+                self.transactions.get(&address, tx_seq)
+            })
+            .collect();
+
+        debug!(
+            LogSchema::new(LogEntry::GetBlock).txns(block_log),
+            seen_consensus = seen_size,
+            walked = txn_walked,
+            seen_after = seen.len(),
+            result_size = result_size,
+            block_size = block.len()
+        );
+        for transaction in &block {
+            self.log_latency(
+                transaction.sender(),
+                transaction.sequence_number(),
+                counters::GET_BLOCK_STAGE_LABEL,
+            );
+        }
+        if let Some(fee_market) = self.fee_market.as_mut() {
+            fee_market.record_block(block.len() as u64, batch_size);
+        }
+        block
+    }
+
+    /// Walks the priority index and returns pointers to the transactions of the next block,
+    /// together with the number of index entries walked.
+    #[allow(clippy::explicit_counter_loop)]
+    fn pull_by_priority(
+        &self,
+        batch_size: u64,
+        seen: &mut HashSet<TxnPointer>,
+    ) -> (Vec<TxnPointer>, usize) {
         let mut result = vec![];
         // Helper DS. Helps to mitigate scenarios where account submits several transactions
         // with increasing gas price (e.g. user submits transactions with sequence number 1, 2
@@ -192,7 +254,6 @@ impl Mempool {
         // but can't be executed before first txn. Once observed, such txn will be saved in
         // `skipped` DS and rechecked once it's ancestor becomes available
         let mut skipped = HashSet::new();
-        let seen_size = seen.len();
         let mut txn_walked = 0usize;
         // iterate over the queue of transactions based on gas price
         'main: for txn in self.transactions.iter_queue() {
@@ -233,33 +294,69 @@ impl Mempool {
                 skipped.insert(TxnPointer::from(txn));
             }
         }
-        let result_size = result.len();
-        // convert transaction pointers to real values
-        let mut block_log = TxnsLog::new();
-        let block: Vec<_> = result
-            .into_iter()
-            .filter_map(|(address, tx_seq)| {
-                block_log.add(address, tx_seq);
-                self.transactions.get(&address, tx_seq)
-            })
-            .collect();
+        (result, txn_walked)
+    }
 
-        debug!(
-            LogSchema::new(LogEntry::GetBlock).txns(block_log),
-            seen_consensus = seen_size,
-            walked = txn_walked,
-            seen_after = seen.len(),
-            result_size = result_size,
-            block_size = block.len()
-        );
-        for transaction in &block {
-            self.log_latency(
-                transaction.sender(),
-                transaction.sequence_number(),
-                counters::GET_BLOCK_STAGE_LABEL,
-            );
+    /// Fee market variant of the block pull. The ready transactions of every sender are grouped
+    /// into the chain that can be executed next, the chain is cut at the first transaction priced
+    /// below `min_gas_price`, and the block is filled with the best-paying chains first. Senders
+    /// are ranked by governance role priority, then by the average ranking score of their ready
+    /// transactions, as kept by the priority index, so only the senders needed to fill the block
+    /// are walked. Governance transactions are never held back by the floor.
+    fn pull_sender_chains(
+        &self,
+        batch_size: u64,
+        seen: &mut HashSet<TxnPointer>,
+        min_gas_price: u64,
+    ) -> (Vec<TxnPointer>, usize) {
+        let mut txn_walked = 0usize;
+        let mut below_floor = 0u64;
+        let mut result = vec![];
+        'senders: for (address, txns) in self.transactions.iter_senders() {
+            if (result.len() as u64) == batch_size {
+                break;
+            }
+            let address = *address;
+            let account_sequence_number = self.sequence_number_cache.get(&address);
+            let mut next_sequence_number = None;
+            for (tx_seq, txn) in txns {
+                txn_walked += 1;
+                let tx_seq = *tx_seq;
+                let is_crsn = matches!(
+                    txn.sequence_number.account_sequence_number_type,
+                    AccountSequenceInfo::CRSN { .. }
+                );
+                if seen.contains(&(address, tx_seq)) {
+                    next_sequence_number = Some(tx_seq + 1);
+                    continue;
+                }
+                // same inclusion rule as the priority walk: the transaction must be "next" for
+                // the account, or follow one that is already in consensus or in this chain
+                let is_next = is_crsn
+                    || next_sequence_number == Some(tx_seq)
+                    || account_sequence_number == Some(&tx_seq)
+                    || (tx_seq > 0 && seen.contains(&(address, tx_seq - 1)));
+                if !is_next {
+                    continue;
+                }
+                if txn.governance_role.priority() == 0 && txn.gas_ranking_score < min_gas_price {
+                    below_floor += 1;
+                    if is_crsn {
+                        continue;
+                    }
+                    // descendants can't be executed without this transaction
+                    break;
+                }
+                if (result.len() as u64) == batch_size {
+                    break 'senders;
+                }
+                seen.insert((address, tx_seq));
+                result.push((address, tx_seq));
+                next_sequence_number = Some(tx_seq + 1);
+            }
         }
-        block
+        counters::FEE_MARKET_BELOW_FLOOR_TXNS.inc_by(below_floor);
+        (result, txn_walked)
     }
 
     /// Periodic core mempool garbage collection.
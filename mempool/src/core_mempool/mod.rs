@@ -1,6 +1,7 @@
 // Copyright (c) The Mango Blockchain Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod fee_market;
 mod index;
 mod mempool;
 mod transaction;
@@ -9,4 +10,9 @@ mod ttl_cache;
 
 #[cfg(test)]
 pub use self::ttl_cache::TtlCache;
-pub use self::{index::TxnPointer, mempool::Mempool as CoreMempool, transaction::TimelineState};
+pub use self::{
+    fee_market::{FeeMarketBlock, FeeMarketHandle, FeeMarketSnapshot},
+    index::TxnPointer,
+    mempool::Mempool as CoreMempool,
+    transaction::TimelineState,
+};
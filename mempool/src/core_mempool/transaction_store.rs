@@ -4,8 +4,8 @@
 use crate::{
     core_mempool::{
         index::{
-            AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
-            TimelineIndex,
+            AccountTransactions, OrderedQueueKey, ParkingLotIndex, PriorityIndex,
+            PriorityQueueIter, TTLIndex, TimelineIndex,
         },
         transaction::{MempoolTransaction, TimelineState},
         ttl_cache::TtlCache,
@@ -22,7 +22,7 @@ use mango_types::{
     transaction::SignedTransaction,
 };
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     ops::Bound,
     time::{Duration, SystemTime},
 };
@@ -519,6 +519,12 @@ impl TransactionStore {
         self.priority_index.iter()
     }
 
+    pub(crate) fn iter_senders(
+        &self,
+    ) -> impl Iterator<Item = (&AccountAddress, &BTreeMap<u64, OrderedQueueKey>)> {
+        self.priority_index.iter_senders()
+    }
+
     pub(crate) fn gen_snapshot(
         &self,
         metrics_cache: &TtlCache<(AccountAddress, u64), SystemTime>,
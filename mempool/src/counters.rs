@@ -4,8 +4,8 @@
 use mango_config::{config::PeerNetworkId, network_id::NetworkId};
 use mango_metrics::{
     register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
-    register_int_gauge, register_int_gauge_vec, DurationHistogram, HistogramTimer, HistogramVec,
-    IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+    register_int_gauge, register_int_gauge_vec, DurationHistogram, Histogram, HistogramTimer,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use mango_types::PeerId;
 use once_cell::sync::Lazy;
@@ -131,6 +131,34 @@ pub static CORE_MEMPOOL_GC_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Gauge tracking the dynamic fee market floor applied to the next consensus pull
+pub static FEE_MARKET_MIN_GAS_PRICE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "diem_core_mempool_fee_market_min_gas_price",
+        "Minimum ranking score for a transaction to be pulled into the next block"
+    )
+    .unwrap()
+});
+
+/// Histogram of the fullness of blocks pulled in fee market mode, as a ratio of the requested size
+pub static FEE_MARKET_BLOCK_FULLNESS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "diem_core_mempool_fee_market_block_fullness",
+        "Fullness of blocks pulled by consensus in fee market mode",
+        vec![0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]
+    )
+    .unwrap()
+});
+
+/// Counter tracking txns left out of a pulled block because they were priced below the floor
+pub static FEE_MARKET_BELOW_FLOOR_TXNS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "diem_core_mempool_fee_market_below_floor_txns_count",
+        "Number of txns skipped by a consensus pull because they were priced below the fee floor"
+    )
+    .unwrap()
+});
+
 /// Counter of pending network events to Mempool
 pub static PENDING_MEMPOOL_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
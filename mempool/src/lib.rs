@@ -57,8 +57,9 @@
 
 #[cfg(any(test, feature = "fuzzing"))]
 mod tests;
+pub use core_mempool::{FeeMarketBlock, FeeMarketHandle, FeeMarketSnapshot};
 pub use shared_mempool::{
-    bootstrap, network,
+    bootstrap, bootstrap_on, network,
     types::{
         gen_mempool_reconfig_subscription, ConsensusRequest, ConsensusResponse,
         MempoolClientSender, SubmissionStatus, TransactionSummary,
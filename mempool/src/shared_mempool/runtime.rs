@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::CoreMempool,
+    core_mempool::{CoreMempool, FeeMarketHandle},
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator, snapshot_job},
@@ -98,16 +98,47 @@ pub fn bootstrap(
     consensus_requests: Receiver<ConsensusRequest>,
     mempool_listener: MempoolNotificationListener,
     mempool_reconfig_events: diem_channel::Receiver<(), OnChainConfigPayload>,
+    fee_market_handle: FeeMarketHandle,
 ) -> Runtime {
     let runtime = Builder::new_multi_thread()
         .thread_name("shared-mem")
         .enable_all()
         .build()
         .expect("[shared mempool] failed to create runtime");
-    let mempool = Arc::new(Mutex::new(CoreMempool::new(config)));
+    bootstrap_on(
+        runtime.handle(),
+        config,
+        db,
+        mempool_network_handles,
+        client_events,
+        consensus_requests,
+        mempool_listener,
+        mempool_reconfig_events,
+        fee_market_handle,
+    );
+    runtime
+}
+
+/// Same as `bootstrap`, but the shared mempool is spawned on `executor` instead of a runtime of
+/// its own.
+pub fn bootstrap_on(
+    executor: &Handle,
+    config: &NodeConfig,
+    db: Arc<dyn DbReader>,
+    mempool_network_handles: Vec<(NodeNetworkId, MempoolNetworkSender, MempoolNetworkEvents)>,
+    client_events: Receiver<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>,
+    consensus_requests: Receiver<ConsensusRequest>,
+    mempool_listener: MempoolNotificationListener,
+    mempool_reconfig_events: diem_channel::Receiver<(), OnChainConfigPayload>,
+    fee_market_handle: FeeMarketHandle,
+) {
+    let mempool = Arc::new(Mutex::new(CoreMempool::new_with_fee_market(
+        config,
+        fee_market_handle,
+    )));
     let vm_validator = Arc::new(RwLock::new(VMValidator::new(Arc::clone(&db))));
     start_shared_mempool(
-        runtime.handle(),
+        executor,
         config,
         mempool,
         mempool_network_handles,
@@ -119,5 +150,4 @@ pub fn bootstrap(
         vm_validator,
         vec![],
     );
-    runtime
 }
@@ -2,10 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TtlCache},
+    core_mempool::{CoreMempool, FeeMarketHandle, TimelineState, TtlCache},
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, exist_in_metrics_cache, setup_mempool,
-        TestTransaction,
+        ConsensusMock, TestTransaction,
     },
 };
 use mango_config::config::NodeConfig;
@@ -661,6 +661,105 @@ fn test_clean_stuck_transactions() {
     assert_eq!(block[0].sequence_number(), 10);
 }
 
+fn setup_fee_market_mempool(min_gas_price: u64) -> (CoreMempool, FeeMarketHandle) {
+    let mut config = NodeConfig::random();
+    config.mempool.fee_market.enabled = true;
+    config.mempool.fee_market.min_gas_price = min_gas_price;
+    let handle = FeeMarketHandle::default();
+    (
+        CoreMempool::new_with_fee_market(&config, handle.clone()),
+        handle,
+    )
+}
+
+#[test]
+fn test_fee_market_orders_sender_chains() {
+    let (mut pool, _) = setup_fee_market_mempool(0);
+    // account 0 pays little for its first transaction but a lot for its second one
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 100),
+            TestTransaction::new(1, 0, 10),
+        ],
+    );
+    let block = pool.get_block(2, HashSet::new());
+    assert_eq!(block, vec![txns[0].clone(), txns[1].clone()]);
+
+    let seen = block
+        .iter()
+        .map(|t| (t.sender(), t.sequence_number()))
+        .collect();
+    assert_eq!(pool.get_block(2, seen), vec![txns[2].clone()]);
+}
+
+#[test]
+fn test_fee_market_ranking_follows_commits() {
+    let (mut pool, _) = setup_fee_market_mempool(0);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 100),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(1, 0, 40),
+        ],
+    );
+    assert_eq!(pool.get_block(1, HashSet::new()), vec![txns[0].clone()]);
+
+    // once its best paying transaction is committed, account 0 ranks below account 1
+    pool.remove_transaction(&txns[0].sender(), txns[0].sequence_number(), false);
+    assert_eq!(pool.get_block(1, HashSet::new()), vec![txns[2].clone()]);
+}
+
+#[test]
+fn test_fee_market_excludes_txns_below_floor() {
+    let (mut pool, handle) = setup_fee_market_mempool(5);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 3),
+            TestTransaction::new(0, 1, 100),
+            TestTransaction::new(1, 0, 10),
+        ],
+    );
+    // account 0 is stuck behind a transaction priced below the floor
+    assert_eq!(pool.get_block(10, HashSet::new()), vec![txns[2].clone()]);
+
+    let snapshot = handle.snapshot();
+    assert_eq!(snapshot.history.len(), 1);
+    assert_eq!(snapshot.history[0].min_gas_price, 5);
+    assert_eq!(snapshot.history[0].block_size, 1);
+    assert_eq!(snapshot.history[0].fullness, 100);
+    // an emptier block than the target never takes the floor below its minimum
+    assert_eq!(snapshot.min_gas_price, 5);
+}
+
+#[test]
+fn test_fee_market_floor_follows_fullness() {
+    let (mut pool, handle) = setup_fee_market_mempool(0);
+    let mut consensus = ConsensusMock::new();
+    add_txns_to_mempool(
+        &mut pool,
+        (0..4)
+            .map(|address| TestTransaction::new(address, 0, 100))
+            .collect(),
+    );
+    assert_eq!(consensus.get_block(&mut pool, 2).len(), 2);
+    assert_eq!(handle.snapshot().min_gas_price, 1);
+    assert_eq!(consensus.get_block(&mut pool, 2).len(), 2);
+    assert_eq!(handle.snapshot().min_gas_price, 2);
+
+    // an empty block lowers the floor again
+    for _ in 0..8 {
+        assert!(consensus.get_block(&mut pool, 2).is_empty());
+    }
+    let snapshot = handle.snapshot();
+    assert_eq!(snapshot.min_gas_price, 0);
+    assert_eq!(snapshot.history.len(), 10);
+    assert_eq!(snapshot.history[0].fullness, 1_000);
+}
+
 #[test]
 fn test_ttl_cache() {
     let mut cache = TtlCache::new(2, Duration::from_secs(1));
@@ -85,7 +85,7 @@ impl NetworkBuilder {
         trusted_peers: Arc<RwLock<PeerSet>>,
         network_context: Arc<NetworkContext>,
         time_service: TimeService,
-        listen_address: NetworkAddress,
+        listen_addresses: Vec<NetworkAddress>,
         authentication_mode: AuthenticationMode,
         max_frame_size: usize,
         enable_proxy_protocol: bool,
@@ -102,7 +102,7 @@ impl NetworkBuilder {
             chain_id,
             network_context.clone(),
             time_service.clone(),
-            listen_address,
+            listen_addresses,
             peer_metadata_storage.clone(),
             trusted_peers,
             authentication_mode,
@@ -135,7 +135,7 @@ impl NetworkBuilder {
         trusted_peers: Arc<RwLock<PeerSet>>,
         network_context: Arc<NetworkContext>,
         time_service: TimeService,
-        listen_address: NetworkAddress,
+        listen_addresses: Vec<NetworkAddress>,
         authentication_mode: AuthenticationMode,
     ) -> NetworkBuilder {
         let mutual_authentication = matches!(authentication_mode, AuthenticationMode::Mutual(_));
@@ -145,7 +145,7 @@ impl NetworkBuilder {
             trusted_peers.clone(),
             network_context,
             time_service,
-            listen_address,
+            listen_addresses,
             authentication_mode,
             MAX_FRAME_SIZE,
             false, /* Disable proxy protocol */
@@ -200,7 +200,7 @@ impl NetworkBuilder {
             trusted_peers.clone(),
             network_context,
             time_service,
-            config.listen_address.clone(),
+            config.listen_addresses.clone(),
             authentication_mode,
             config.max_frame_size,
             config.enable_proxy_protocol,
@@ -316,8 +316,8 @@ impl NetworkBuilder {
             .map(|conn_mgr_builder| conn_mgr_builder.conn_mgr_reqs_tx())
     }
 
-    pub fn listen_address(&self) -> NetworkAddress {
-        self.peer_manager_builder.listen_address()
+    pub fn listen_addresses(&self) -> Vec<NetworkAddress> {
+        self.peer_manager_builder.listen_addresses()
     }
 
     /// Add a [`ConnectivityManager`] to the network.
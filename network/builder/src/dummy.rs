@@ -151,7 +151,7 @@ pub fn setup_network() -> DummyNetwork {
         trusted_peers,
         network_context,
         TimeService::real(),
-        listener_addr,
+        vec![listener_addr],
         authentication_mode,
     );
 
@@ -159,11 +159,11 @@ pub fn setup_network() -> DummyNetwork {
         .add_protocol_handler::<DummyNetworkSender, DummyNetworkEvents>(network_endpoint_config());
     network_builder.build(runtime.handle().clone()).start();
 
-    // Add the listener address with port
-    let listener_addr = network_builder.listen_address();
+    // Add the listener addresses with port
+    let listener_addrs = network_builder.listen_addresses();
     seeds.insert(
         listener_peer_id,
-        Peer::from_addrs(PeerRole::Validator, vec![listener_addr]),
+        Peer::from_addrs(PeerRole::Validator, listener_addrs),
     );
 
     let authentication_mode = AuthenticationMode::Mutual(dialer_identity_private_key);
@@ -179,7 +179,7 @@ pub fn setup_network() -> DummyNetwork {
         trusted_peers,
         network_context,
         TimeService::real(),
-        dialer_addr,
+        vec![dialer_addr],
         authentication_mode,
     );
 
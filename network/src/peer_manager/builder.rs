@@ -99,7 +99,6 @@ impl TransportContext {
 }
 
 struct PeerManagerContext {
-    // TODO(philiphayes): better support multiple listening addrs
     pm_reqs_tx: diem_channel::Sender<(PeerId, ProtocolId), PeerManagerRequest>,
     pm_reqs_rx: diem_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
     connection_reqs_tx: diem_channel::Sender<PeerId, ConnectionRequest>,
@@ -194,10 +193,17 @@ pub struct PeerManagerBuilder {
     time_service: TimeService,
     transport_context: Option<TransportContext>,
     peer_manager_context: Option<PeerManagerContext>,
-    // TODO(philiphayes): better support multiple listening addrs
     peer_manager: Option<TransportPeerManager>,
-    // ListenAddress will be updated when the PeerManager is built
-    listen_address: NetworkAddress,
+    // ListenAddresses will be updated when the PeerManager is built
+    listen_addresses: Vec<NetworkAddress>,
+}
+
+/// The transport shared by all of a [`PeerManagerBuilder`]'s listen addresses.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ListenTransport {
+    Tcp,
+    #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
+    Memory,
 }
 
 impl PeerManagerBuilder {
@@ -206,8 +212,7 @@ impl PeerManagerBuilder {
         chain_id: ChainId,
         network_context: Arc<NetworkContext>,
         time_service: TimeService,
-        // TODO(philiphayes): better support multiple listening addrs
-        listen_address: NetworkAddress,
+        listen_addresses: Vec<NetworkAddress>,
         peer_metadata_storage: Arc<PeerMetadataStorage>,
         trusted_peers: Arc<RwLock<PeerSet>>,
         authentication_mode: AuthenticationMode,
@@ -257,12 +262,12 @@ impl PeerManagerBuilder {
                 outbound_rate_limit_config,
             )),
             peer_manager: None,
-            listen_address,
+            listen_addresses,
         }
     }
 
-    pub fn listen_address(&self) -> NetworkAddress {
-        self.listen_address.clone()
+    pub fn listen_addresses(&self) -> Vec<NetworkAddress> {
+        self.listen_addresses.clone()
     }
 
     pub fn connection_reqs_tx(&self) -> diem_channel::Sender<PeerId, ConnectionRequest> {
@@ -273,11 +278,35 @@ impl PeerManagerBuilder {
             .clone()
     }
 
-    /// Create the configured transport and start PeerManager.
-    /// Return the actual NetworkAddress over which this peer is listening.
-    pub fn build(&mut self, executor: &Handle) -> &mut Self {
+    /// Pick the transport to listen with. Every listen address must use the same transport;
+    /// a peer without listen addresses only dials out, which is done over TCP.
+    fn listen_transport(&self) -> ListenTransport {
         use mango_types::network_address::Protocol::*;
 
+        let transport_of = |addr: &NetworkAddress| match addr.as_slice() {
+            [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)] => Some(ListenTransport::Tcp),
+            #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
+            [Memory(_)] => Some(ListenTransport::Memory),
+            _ => None,
+        };
+
+        let mut transports = self.listen_addresses.iter().map(transport_of);
+        match transports.next() {
+            None => ListenTransport::Tcp,
+            Some(Some(transport)) if transports.all(|other| other == Some(transport)) => transport,
+            _ => panic!(
+                "{} Unsupported listen_addresses: '{:?}', expected only '/memory/<port>' \
+                 addresses, or only '/ip4/<addr>/tcp/<port>' and '/ip6/<addr>/tcp/<port>' \
+                 addresses.",
+                self.network_context, self.listen_addresses
+            ),
+        }
+    }
+
+    /// Create the configured transport and start PeerManager.
+    /// Return the actual NetworkAddresses over which this peer is listening.
+    pub fn build(&mut self, executor: &Handle) -> &mut Self {
+        let listen_transport = self.listen_transport();
         let transport_context = self
             .transport_context
             .take()
@@ -298,11 +327,26 @@ impl PeerManagerBuilder {
             ),
         };
 
-        self.peer_manager = match self.listen_address.as_slice() {
-            [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)] => {
-                Some(TransportPeerManager::Tcp(self.build_with_transport(
+        self.peer_manager = match listen_transport {
+            ListenTransport::Tcp => Some(TransportPeerManager::Tcp(self.build_with_transport(
+                DiemNetTransport::new(
+                    DIEM_TCP_TRANSPORT.clone(),
+                    self.network_context.clone(),
+                    self.time_service.clone(),
+                    key,
+                    auth_mode,
+                    HANDSHAKE_VERSION,
+                    chain_id,
+                    protos,
+                    enable_proxy_protocol,
+                ),
+                executor,
+            ))),
+            #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
+            ListenTransport::Memory => {
+                Some(TransportPeerManager::Memory(self.build_with_transport(
                     DiemNetTransport::new(
-                        DIEM_TCP_TRANSPORT.clone(),
+                        MemoryTransport,
                         self.network_context.clone(),
                         self.time_service.clone(),
                         key,
@@ -315,33 +359,13 @@ impl PeerManagerBuilder {
                     executor,
                 )))
             }
-            #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
-            [Memory(_)] => Some(TransportPeerManager::Memory(self.build_with_transport(
-                DiemNetTransport::new(
-                    MemoryTransport,
-                    self.network_context.clone(),
-                    self.time_service.clone(),
-                    key,
-                    auth_mode,
-                    HANDSHAKE_VERSION,
-                    chain_id,
-                    protos,
-                    enable_proxy_protocol,
-                ),
-                executor,
-            ))),
-            _ => panic!(
-                "{} Unsupported listen_address: '{}', expected '/memory/<port>', \
-                 '/ip4/<addr>/tcp/<port>', or '/ip6/<addr>/tcp/<port>'.",
-                self.network_context, self.listen_address
-            ),
         };
 
         self
     }
 
     /// Given a transport build and launch PeerManager.
-    /// Return the actual NetworkAddress over which this peer is listening.
+    /// Return the actual NetworkAddresses over which this peer is listening.
     fn build_with_transport<TTransport, TSocket>(
         &mut self,
         transport: TTransport,
@@ -370,9 +394,7 @@ impl PeerManagerBuilder {
             self.time_service.clone(),
             transport,
             self.network_context.clone(),
-            // TODO(philiphayes): peer manager should take `Vec<NetworkAddress>`
-            // (which could be empty, like in client use case)
-            self.listen_address.clone(),
+            self.listen_addresses.clone(),
             pm_context.peer_metadata_storage,
             pm_context.trusted_peers,
             pm_context.pm_reqs_rx,
@@ -387,8 +409,8 @@ impl PeerManagerBuilder {
             outbound_rate_limiters,
         );
 
-        // PeerManager constructor appends a public key to the listen_address.
-        self.listen_address = peer_mgr.listen_addr().clone();
+        // PeerManager constructor appends a public key to the listen_addresses.
+        self.listen_addresses = peer_mgr.listen_addrs().to_vec();
 
         peer_mgr
     }
@@ -76,9 +76,9 @@ where
     executor: Handle,
     /// A handle to a time service for easily mocking time-related operations.
     time_service: TimeService,
-    /// Address to listen on for incoming connections.
-    listen_addr: NetworkAddress,
-    /// Connection Listener, listening on `listen_addr`
+    /// Addresses to listen on for incoming connections.
+    listen_addrs: Vec<NetworkAddress>,
+    /// Connection Listener, listening on every address in `listen_addrs`
     transport_handler: Option<TransportHandler<TTransport, TSocket>>,
     /// Map from PeerId to corresponding Peer object.
     active_peers: HashMap<
@@ -139,7 +139,7 @@ where
         time_service: TimeService,
         transport: TTransport,
         network_context: Arc<NetworkContext>,
-        listen_addr: NetworkAddress,
+        listen_addrs: Vec<NetworkAddress>,
         peer_metadata_storage: Arc<PeerMetadataStorage>,
         trusted_peers: Arc<RwLock<PeerSet>>,
         requests_rx: diem_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
@@ -166,11 +166,11 @@ where
         // rethink how we init the PeerManager so we don't have to do this funny thing.
         let transport_notifs_tx_clone = transport_notifs_tx.clone();
         let _guard = executor.enter();
-        let (transport_handler, listen_addr) = TransportHandler::new(
+        let (transport_handler, listen_addrs) = TransportHandler::new(
             network_context.clone(),
             time_service.clone(),
             transport,
-            listen_addr,
+            listen_addrs,
             transport_reqs_rx,
             transport_notifs_tx_clone,
         );
@@ -179,7 +179,7 @@ where
             network_context,
             executor,
             time_service,
-            listen_addr,
+            listen_addrs,
             transport_handler: Some(transport_handler),
             active_peers: HashMap::new(),
             peer_metadata_storage,
@@ -238,9 +238,9 @@ where
         });
     }
 
-    /// Get the [`NetworkAddress`] we're listening for incoming connections on
-    pub fn listen_addr(&self) -> &NetworkAddress {
-        &self.listen_addr
+    /// Get the [`NetworkAddress`]es we're listening for incoming connections on
+    pub fn listen_addrs(&self) -> &[NetworkAddress] {
+        &self.listen_addrs
     }
 
     /// Start listening on the set address and return a future which runs PeerManager
@@ -248,7 +248,7 @@ where
         // Start listening for connections.
         info!(
             NetworkSchema::new(&self.network_context),
-            "Start listening for incoming connections on {}", self.listen_addr
+            "Start listening for incoming connections on {:?}", self.listen_addrs
         );
         self.start_connection_listener();
         loop {
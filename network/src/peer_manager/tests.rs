@@ -103,7 +103,7 @@ fn build_test_peer_manager(
         TimeService::mock(),
         build_test_transport(),
         NetworkContext::mock_with_peer_id(peer_id),
-        "/memory/0".parse().unwrap(),
+        vec!["/memory/0".parse().unwrap()],
         Arc::new(PeerMetadataStorage::new()),
         Arc::new(RwLock::new(HashMap::new())),
         peer_manager_request_rx,
@@ -13,7 +13,7 @@ use futures::{
     future::{BoxFuture, FutureExt},
     io::{AsyncRead, AsyncWrite},
     sink::SinkExt,
-    stream::{Fuse, FuturesUnordered, StreamExt},
+    stream::{self, FuturesUnordered, SelectAll, StreamExt},
 };
 use mango_config::network_id::NetworkContext;
 use mango_logger::prelude::*;
@@ -42,7 +42,8 @@ where
     time_service: TimeService,
     /// [`Transport`] that is used to establish connections
     transport: TTransport,
-    listener: Fuse<TTransport::Listener>,
+    /// Listeners for every address the node accepts inbound connections on
+    listeners: SelectAll<TTransport::Listener>,
     transport_reqs_rx: channel::Receiver<TransportRequest>,
     transport_notifs_tx: channel::Sender<TransportNotification<TSocket>>,
 }
@@ -59,30 +60,36 @@ where
         network_context: Arc<NetworkContext>,
         time_service: TimeService,
         transport: TTransport,
-        listen_addr: NetworkAddress,
+        listen_addrs: Vec<NetworkAddress>,
         transport_reqs_rx: channel::Receiver<TransportRequest>,
         transport_notifs_tx: channel::Sender<TransportNotification<TSocket>>,
-    ) -> (Self, NetworkAddress) {
-        let (listener, listen_addr) = transport
-            .listen_on(listen_addr)
-            .expect("Transport listen on fails");
-        debug!(
-            NetworkSchema::new(&network_context),
-            listen_address = listen_addr,
-            "{} listening on '{}'",
-            network_context,
-            listen_addr
-        );
+    ) -> (Self, Vec<NetworkAddress>) {
+        let mut listeners = Vec::with_capacity(listen_addrs.len());
+        let mut actual_listen_addrs = Vec::with_capacity(listen_addrs.len());
+        for listen_addr in listen_addrs {
+            let (listener, listen_addr) = transport
+                .listen_on(listen_addr)
+                .expect("Transport listen on fails");
+            debug!(
+                NetworkSchema::new(&network_context),
+                listen_address = listen_addr,
+                "{} listening on '{}'",
+                network_context,
+                listen_addr
+            );
+            listeners.push(listener);
+            actual_listen_addrs.push(listen_addr);
+        }
         (
             Self {
                 network_context,
                 time_service,
                 transport,
-                listener: listener.fuse(),
+                listeners: stream::select_all(listeners),
                 transport_reqs_rx,
                 transport_notifs_tx,
             },
-            listen_addr,
+            actual_listen_addrs,
         )
     }
 
@@ -102,7 +109,7 @@ where
                         pending_outbound_connections.push(fut);
                     }
                 },
-                inbound_connection = self.listener.select_next_some() => {
+                inbound_connection = self.listeners.select_next_some() => {
                     if let Some(fut) = self.upgrade_inbound_connection(inbound_connection) {
                         pending_inbound_connections.push(fut);
                     }
@@ -0,0 +1,141 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! HTTP client sending JWS-signed commands to a counterparty VASP.
+
+use crate::{
+    compliance::{ComplianceKeyError, ComplianceKeyResolver},
+    identifier::{decode_account, encode_account, HumanReadablePrefix, IntentIdentifierError},
+    jws::{self, JwsError},
+    payment_command::PaymentCommand,
+    subaddress::Subaddress,
+    types::{
+        CommandRequestObject, CommandResponseObject, REQUEST_ID_HEADER, REQUEST_SENDER_ADDRESS,
+    },
+};
+use diem_sdk::{crypto::ed25519::ed25519_dalek::Keypair, types::account_address::AccountAddress};
+use reqwest::{Client as ReqwestClient, StatusCode};
+use std::{sync::Arc, time::Duration};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Path of the command endpoint, relative to a VASP's base URL.
+pub const COMMAND_PATH: &str = "v2/command";
+
+#[derive(Debug, Error)]
+pub enum OffChainClientError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("counterparty responded with HTTP status {0} and no valid command response")]
+    Status(StatusCode),
+    #[error("counterparty responded to request {expected} with X-REQUEST-ID {actual:?}")]
+    RequestIdMismatch {
+        expected: Uuid,
+        actual: Option<String>,
+    },
+    #[error(transparent)]
+    Jws(#[from] JwsError),
+    #[error(transparent)]
+    ComplianceKey(#[from] ComplianceKeyError),
+    #[error(transparent)]
+    Identifier(#[from] IntentIdentifierError),
+}
+
+/// Sends commands on behalf of the VASP at `address`, signed with its compliance key, and verifies
+/// the counterparty's responses against the counterparty's compliance key.
+#[derive(Clone)]
+pub struct OffChainClient {
+    inner: ReqwestClient,
+    address: AccountAddress,
+    sender_identifier: String,
+    signing_key: Arc<Keypair>,
+    compliance_keys: Arc<dyn ComplianceKeyResolver>,
+}
+
+impl OffChainClient {
+    pub fn new(
+        hrp: HumanReadablePrefix,
+        address: AccountAddress,
+        signing_key: Arc<Keypair>,
+        compliance_keys: Arc<dyn ComplianceKeyResolver>,
+    ) -> Result<Self, OffChainClientError> {
+        let inner = ReqwestClient::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        let sender_identifier =
+            encode_account(hrp, address, Subaddress::ZERO).map_err(IntentIdentifierError::from)?;
+
+        Ok(Self {
+            inner,
+            address,
+            sender_identifier,
+            signing_key,
+            compliance_keys,
+        })
+    }
+
+    pub fn address(&self) -> AccountAddress {
+        self.address
+    }
+
+    /// Sends `request` to the VASP at `counterparty` whose off-chain API is served at `base_url`.
+    ///
+    /// A `CommandResponseObject` is returned whenever the counterparty answered with a correctly
+    /// signed one, including when it reports a failure; its status must be checked by the caller.
+    pub async fn send_command(
+        &self,
+        base_url: &str,
+        counterparty: AccountAddress,
+        request: &CommandRequestObject,
+    ) -> Result<CommandResponseObject, OffChainClientError> {
+        let request_id = Uuid::new_v4();
+        let body = jws::serialize(request, self.signing_key.as_ref())?;
+        let response = self
+            .inner
+            .post(format!(
+                "{}/{}",
+                base_url.trim_end_matches('/'),
+                COMMAND_PATH
+            ))
+            .header(REQUEST_ID_HEADER, request_id.to_string())
+            .header(REQUEST_SENDER_ADDRESS, &self.sender_identifier)
+            .body(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let echoed_request_id = response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+        if echoed_request_id.as_deref() != Some(request_id.to_string().as_str()) {
+            if !status.is_success() {
+                return Err(OffChainClientError::Status(status));
+            }
+            return Err(OffChainClientError::RequestIdMismatch {
+                expected: request_id,
+                actual: echoed_request_id,
+            });
+        }
+
+        let body = response.bytes().await?;
+        let compliance_key = self.compliance_keys.compliance_key(counterparty).await?;
+        match jws::deserialize(&body, &compliance_key) {
+            Ok(response) => Ok(response),
+            Err(_) if !status.is_success() => Err(OffChainClientError::Status(status)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Sends `command` to the counterparty actor of the payment.
+    pub async fn send_payment(
+        &self,
+        base_url: &str,
+        command: &PaymentCommand,
+    ) -> Result<CommandResponseObject, OffChainClientError> {
+        let (_, counterparty, _) = decode_account(&command.counterparty_actor_object().address)?;
+        self.send_command(base_url, counterparty, &command.to_request())
+            .await
+    }
+}
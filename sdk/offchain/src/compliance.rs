@@ -0,0 +1,88 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lookup of the compliance keys used to sign and verify off-chain requests.
+
+use async_trait::async_trait;
+use diem_sdk::{
+    client::{views::AccountRoleView, Client},
+    crypto::ed25519::ed25519_dalek::PublicKey,
+    types::account_address::AccountAddress,
+};
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ComplianceKeyError {
+    #[error("no compliance key for account {0}")]
+    NotFound(AccountAddress),
+    #[error("compliance key of account {0} is not a valid ed25519 public key")]
+    InvalidKey(AccountAddress),
+    #[error(transparent)]
+    Client(#[from] diem_sdk::client::Error),
+}
+
+/// Finds the compliance key a VASP signs its off-chain requests and responses with.
+#[async_trait]
+pub trait ComplianceKeyResolver: Send + Sync {
+    async fn compliance_key(
+        &self,
+        address: AccountAddress,
+    ) -> Result<PublicKey, ComplianceKeyError>;
+}
+
+/// Reads compliance keys from the `ParentVASP` or `DesignatedDealer` role of on-chain accounts.
+/// A child VASP resolves to the key of its parent.
+pub struct OnChainComplianceKeys {
+    client: Client,
+}
+
+impl OnChainComplianceKeys {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ComplianceKeyResolver for OnChainComplianceKeys {
+    async fn compliance_key(
+        &self,
+        address: AccountAddress,
+    ) -> Result<PublicKey, ComplianceKeyError> {
+        let mut account_address = address;
+        // a child VASP is only ever one hop away from its parent
+        for _ in 0..2 {
+            let account = self
+                .client
+                .get_account(account_address)
+                .await?
+                .into_inner()
+                .ok_or(ComplianceKeyError::NotFound(address))?;
+            match account.role {
+                AccountRoleView::ParentVASP { compliance_key, .. }
+                | AccountRoleView::DesignatedDealer { compliance_key, .. } => {
+                    return PublicKey::from_bytes(compliance_key.inner())
+                        .map_err(|_| ComplianceKeyError::InvalidKey(address));
+                }
+                AccountRoleView::ChildVASP {
+                    parent_vasp_address,
+                } => account_address = parent_vasp_address,
+                _ => break,
+            }
+        }
+        Err(ComplianceKeyError::NotFound(address))
+    }
+}
+
+/// A fixed set of compliance keys, for tests and private deployments.
+#[async_trait]
+impl ComplianceKeyResolver for HashMap<AccountAddress, PublicKey> {
+    async fn compliance_key(
+        &self,
+        address: AccountAddress,
+    ) -> Result<PublicKey, ComplianceKeyError> {
+        self.get(&address)
+            .copied()
+            .ok_or(ComplianceKeyError::NotFound(address))
+    }
+}
@@ -170,7 +170,7 @@ impl HumanReadablePrefix {
 }
 
 /// Encode onchain address and subaddress with human readable prefix (hrp) into bech32 format.
-fn encode_account(
+pub fn encode_account(
     hrp: HumanReadablePrefix,
     account_address: AccountAddress,
     subaddress: Subaddress,
@@ -184,7 +184,7 @@ fn encode_account(
 }
 
 /// Decodes an encoded address using bech32, ensuring a matching hrp (human readable prefix).
-fn decode_account(
+pub fn decode_account(
     encoded_address: &str,
 ) -> Result<(HumanReadablePrefix, AccountAddress, Subaddress), IntentIdentifierError> {
     let (hrp_str, data, _variant) = bech32::decode(encoded_address)?;
@@ -97,7 +97,7 @@ pub struct JwsError {
 
 #[derive(Debug)]
 struct InnerError {
-    _kind: ErrorKind,
+    kind: ErrorKind,
     source: Option<BoxError>,
 }
 
@@ -112,10 +112,10 @@ impl JwsError {
     //
     // Constructors
     //
-    fn new<E: Into<BoxError>>(_kind: ErrorKind, source: Option<E>) -> Self {
+    fn new<E: Into<BoxError>>(kind: ErrorKind, source: Option<E>) -> Self {
         Self {
             inner: Box::new(InnerError {
-                _kind,
+                kind,
                 source: source.map(Into::into),
             }),
         }
@@ -132,6 +132,24 @@ impl JwsError {
     fn signature(e: ed25519_dalek::SignatureError) -> Self {
         Self::new(ErrorKind::Signature, Some(e))
     }
+
+    //
+    // Accessors
+    //
+    /// The message is not a well-formed compact JWS.
+    pub fn is_jws(&self) -> bool {
+        self.inner.kind == ErrorKind::JwsCompact
+    }
+
+    /// The header or the payload is not valid JSON for the expected type.
+    pub fn is_json(&self) -> bool {
+        self.inner.kind == ErrorKind::Json
+    }
+
+    /// The signature could not be produced or did not verify.
+    pub fn is_signature(&self) -> bool {
+        self.inner.kind == ErrorKind::Signature
+    }
 }
 
 impl std::fmt::Display for JwsError {
@@ -185,6 +203,6 @@ mod test {
 
         let keypair2 = Keypair::generate(&mut rand_core::OsRng);
         let error = deserialize::<CommandResponseObject, _>(s.as_bytes(), &keypair2).unwrap_err();
-        assert_eq!(error.inner._kind, ErrorKind::Signature);
+        assert_eq!(error.inner.kind, ErrorKind::Signature);
     }
 }
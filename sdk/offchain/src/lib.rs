@@ -2,8 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 #![allow(clippy::recursive_format_impl)]
+pub mod client;
+pub mod compliance;
 pub mod identifier;
 pub mod jws;
 pub mod payment_command;
+pub mod service;
+pub mod store;
 pub mod subaddress;
+pub mod travel_rule;
 pub mod types;
@@ -0,0 +1,533 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Server side of the off-chain protocol: a warp filter accepting commands from counterparty VASPs.
+//!
+//! Every request is verified against the compliance key of the VASP named in the
+//! `X-REQUEST-SENDER-ADDRESS` header, validated against the latest stored command of the same
+//! payment (including the state transition), and saved before a signed response is returned.
+
+use crate::{
+    compliance::ComplianceKeyResolver,
+    identifier::decode_account,
+    jws,
+    payment_command::{Actor, Origin, PaymentCommand},
+    store::{PaymentStore, PaymentStoreError},
+    travel_rule::verify_recipient_signature,
+    types::{
+        Command, CommandRequestObject, CommandResponseObject, CommandStatus, ErrorCode,
+        OffChainError, PaymentObject, REQUEST_ID_HEADER, REQUEST_SENDER_ADDRESS,
+    },
+};
+use diem_sdk::{crypto::ed25519::ed25519_dalek::Keypair, types::account_address::AccountAddress};
+use std::sync::Arc;
+use uuid::Uuid;
+use warp::{
+    http::{Response, StatusCode},
+    hyper::body::Bytes,
+    Filter,
+};
+
+/// Off-chain requests are small; anything bigger than this is rejected before being read.
+const MAX_REQUEST_SIZE: u64 = 1 << 20;
+
+pub struct OffChainService {
+    address: AccountAddress,
+    signing_key: Arc<Keypair>,
+    compliance_keys: Arc<dyn ComplianceKeyResolver>,
+    store: Arc<dyn PaymentStore>,
+}
+
+impl OffChainService {
+    pub fn new(
+        address: AccountAddress,
+        signing_key: Arc<Keypair>,
+        compliance_keys: Arc<dyn ComplianceKeyResolver>,
+        store: Arc<dyn PaymentStore>,
+    ) -> Self {
+        Self {
+            address,
+            signing_key,
+            compliance_keys,
+            store,
+        }
+    }
+
+    pub fn store(&self) -> &Arc<dyn PaymentStore> {
+        &self.store
+    }
+
+    /// The `POST /v2/command` route, to be served under the VASP's on-chain base URL.
+    pub fn filter(
+        self: Arc<Self>,
+    ) -> impl Filter<Extract = (Response<String>,), Error = warp::Rejection> + Clone {
+        // keep in sync with `COMMAND_PATH`
+        warp::path!("v2" / "command")
+            .and(warp::post())
+            .and(warp::header::optional::<String>(REQUEST_ID_HEADER))
+            .and(warp::header::optional::<String>(REQUEST_SENDER_ADDRESS))
+            .and(warp::body::content_length_limit(MAX_REQUEST_SIZE))
+            .and(warp::body::bytes())
+            .and(warp::any().map(move || self.clone()))
+            .then(
+                |request_id: Option<String>,
+                 sender: Option<String>,
+                 body: Bytes,
+                 service: Arc<Self>| async move {
+                    service.handle(request_id, sender, body).await
+                },
+            )
+    }
+
+    /// Validates and records a new version of a payment produced by this VASP, returning the
+    /// command to send to the counterparty with `OffChainClient::send_payment`.
+    pub async fn save_outbound(
+        &self,
+        payment: PaymentObject,
+    ) -> Result<PaymentCommand, OffChainError> {
+        let (my_actor, _) = self.actors(&payment)?;
+        let prior = self.prior_payment(payment.reference_id()).await?;
+        let command = PaymentCommand::new(
+            payment,
+            Origin::Outbound,
+            my_actor,
+            Uuid::new_v4(),
+            prior.as_ref(),
+        )
+        .map_err(|e| OffChainError::command(e.code()).with_message(e.to_string()))?;
+        self.save_payment(command.clone(), prior.map(|prior| prior.cid()))
+            .await?;
+        Ok(command)
+    }
+
+    async fn handle(
+        &self,
+        request_id: Option<String>,
+        sender: Option<String>,
+        body: Bytes,
+    ) -> Response<String> {
+        let result = match (&request_id, &sender) {
+            (Some(request_id), Some(sender)) => self.process(request_id, sender, &body).await,
+            _ => Err((None, OffChainError::protocol(ErrorCode::MissingHttpHeader))),
+        };
+        let (status, response) = match result {
+            Ok(cid) => (
+                StatusCode::OK,
+                CommandResponseObject::new(CommandStatus::Success).with_cid(cid),
+            ),
+            Err((cid, error)) => {
+                let response = CommandResponseObject::new(CommandStatus::Failure).with_error(error);
+                (
+                    StatusCode::BAD_REQUEST,
+                    match cid {
+                        Some(cid) => response.with_cid(cid),
+                        None => response,
+                    },
+                )
+            }
+        };
+
+        let mut builder = Response::builder().status(status);
+        if let Some(request_id) = request_id {
+            builder = builder.header(REQUEST_ID_HEADER, request_id);
+        }
+        match jws::serialize(&response, self.signing_key.as_ref()) {
+            Ok(body) => builder.body(body),
+            Err(_) => builder
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(String::new()),
+        }
+        .expect("response parts are valid")
+    }
+
+    /// Processes a single request, returning its cid on success. Errors carry the cid when the
+    /// request could be decoded far enough to know it.
+    async fn process(
+        &self,
+        request_id: &str,
+        sender: &str,
+        body: &[u8],
+    ) -> Result<Uuid, (Option<Uuid>, OffChainError)> {
+        Uuid::parse_str(request_id).map_err(|_| {
+            (
+                None,
+                OffChainError::protocol(ErrorCode::InvalidHttpHeader).with_field(REQUEST_ID_HEADER),
+            )
+        })?;
+        let invalid_sender = || {
+            OffChainError::protocol(ErrorCode::InvalidHttpHeader).with_field(REQUEST_SENDER_ADDRESS)
+        };
+        let (_, sender, _) = decode_account(sender).map_err(|_| (None, invalid_sender()))?;
+        let compliance_key = self
+            .compliance_keys
+            .compliance_key(sender)
+            .await
+            .map_err(|e| (None, invalid_sender().with_message(e.to_string())))?;
+
+        let request: CommandRequestObject =
+            jws::deserialize(body, &compliance_key).map_err(|e| {
+                let code = if e.is_signature() {
+                    ErrorCode::InvalidJwsSignature
+                } else if e.is_json() {
+                    ErrorCode::InvalidJson
+                } else {
+                    ErrorCode::InvalidJws
+                };
+                (None, OffChainError::protocol(code))
+            })?;
+        let (command, cid) = request.into_parts();
+        let payment = match command {
+            Command::PaymentCommand(payment) => payment.into_payment(),
+            Command::FundPullPreApprovalCommand => {
+                return Err((
+                    Some(cid),
+                    OffChainError::protocol(ErrorCode::UnknownCommandType),
+                ))
+            }
+        };
+
+        let (my_actor, counterparty_account) = self.actors(&payment).map_err(|e| (Some(cid), e))?;
+        if counterparty_account != sender {
+            return Err((Some(cid), invalid_sender()));
+        }
+
+        let prior = self
+            .prior_payment(payment.reference_id())
+            .await
+            .map_err(|e| (Some(cid), e))?;
+        if let Some(prior) = prior.as_ref().filter(|prior| prior.cid() == cid) {
+            // a retry of the latest command is acknowledged again, anything else reusing its cid
+            // is a conflict
+            return if prior.payment() == &payment && prior.origin().is_inbound() {
+                Ok(cid)
+            } else {
+                Err((Some(cid), OffChainError::command(ErrorCode::Conflict)))
+            };
+        }
+
+        // the receiver attests the payment with the same compliance key it signs requests with
+        if my_actor == Actor::Sender && payment.recipient_signature().is_some() {
+            verify_recipient_signature(&payment, &compliance_key).map_err(|e| {
+                (
+                    Some(cid),
+                    OffChainError::command(ErrorCode::InvalidRecipientSignature)
+                        .with_field("payment.recipient_signature")
+                        .with_message(e.to_string()),
+                )
+            })?;
+        }
+
+        let command = PaymentCommand::new(payment, Origin::Inbound, my_actor, cid, prior.as_ref())
+            .map_err(|e| {
+                (
+                    Some(cid),
+                    OffChainError::command(e.code()).with_message(e.to_string()),
+                )
+            })?;
+        self.save_payment(command, prior.map(|prior| prior.cid()))
+            .await
+            .map_err(|e| (Some(cid), e))?;
+        Ok(cid)
+    }
+
+    /// Returns the actor this VASP plays in `payment`, and the account of the counterparty.
+    fn actors(&self, payment: &PaymentObject) -> Result<(Actor, AccountAddress), OffChainError> {
+        let account_of = |actor: Actor| {
+            decode_account(&payment.actor_object_by_actor(actor).address)
+                .map(|(_, address, _)| address)
+                .map_err(|_| {
+                    OffChainError::command(ErrorCode::InvalidFieldValue)
+                        .with_field(format!("payment.{:?}.address", actor).to_lowercase())
+                })
+        };
+        let sender_account = account_of(Actor::Sender)?;
+        let receiver_account = account_of(Actor::Receiver)?;
+        if receiver_account == self.address {
+            Ok((Actor::Receiver, sender_account))
+        } else if sender_account == self.address {
+            Ok((Actor::Sender, receiver_account))
+        } else {
+            Err(OffChainError::command(ErrorCode::UnknownAddress))
+        }
+    }
+
+    async fn prior_payment(
+        &self,
+        reference_id: Uuid,
+    ) -> Result<Option<PaymentCommand>, OffChainError> {
+        self.store
+            .get_payment(reference_id)
+            .await
+            .map_err(store_error)
+    }
+
+    async fn save_payment(
+        &self,
+        command: PaymentCommand,
+        prior_cid: Option<Uuid>,
+    ) -> Result<(), OffChainError> {
+        self.store
+            .save_payment(command, prior_cid)
+            .await
+            .map_err(store_error)
+    }
+}
+
+fn store_error(error: PaymentStoreError) -> OffChainError {
+    OffChainError::command(ErrorCode::Conflict).with_message(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::OffChainClient,
+        identifier::{encode_account, HumanReadablePrefix},
+        payment_command::PaymentState,
+        store::InMemoryPaymentStore,
+        subaddress::Subaddress,
+        travel_rule::sign_payment,
+        types::{
+            ActionType, KycDataObject, PaymentActionObject, PaymentActorObject,
+            PaymentCommandObject, Status, StatusObject,
+        },
+    };
+    use diem_sdk::crypto::ed25519::ed25519_dalek::PublicKey;
+    use std::collections::HashMap;
+
+    struct Vasp {
+        service: Arc<OffChainService>,
+        client: OffChainClient,
+        base_url: String,
+    }
+
+    impl Vasp {
+        async fn payment_state(&self, reference_id: Uuid) -> PaymentState {
+            self.service
+                .store()
+                .get_payment(reference_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .payment_state()
+        }
+    }
+
+    fn start_vasps() -> (Vasp, Vasp) {
+        let addresses = [AccountAddress::new([1; 16]), AccountAddress::new([2; 16])];
+        let keys: Vec<_> = addresses
+            .iter()
+            .map(|_| Arc::new(Keypair::generate(&mut rand_core::OsRng)))
+            .collect();
+        let compliance_keys: Arc<dyn ComplianceKeyResolver> = Arc::new(
+            addresses
+                .iter()
+                .zip(&keys)
+                .map(|(address, key)| (*address, key.public))
+                .collect::<HashMap<AccountAddress, PublicKey>>(),
+        );
+
+        let mut vasps = addresses.iter().zip(keys).map(|(address, key)| {
+            let service = Arc::new(OffChainService::new(
+                *address,
+                key.clone(),
+                compliance_keys.clone(),
+                Arc::new(InMemoryPaymentStore::new()),
+            ));
+            let (socket, server) =
+                warp::serve(service.clone().filter()).bind_ephemeral(([127, 0, 0, 1], 0));
+            tokio::spawn(server);
+            let client = OffChainClient::new(
+                HumanReadablePrefix::TDM,
+                *address,
+                key,
+                compliance_keys.clone(),
+            )
+            .unwrap();
+            Vasp {
+                service,
+                client,
+                base_url: format!("http://{}", socket),
+            }
+        });
+        (vasps.next().unwrap(), vasps.next().unwrap())
+    }
+
+    fn actor(vasp: &Vasp, status: Status) -> PaymentActorObject {
+        let address = encode_account(
+            HumanReadablePrefix::TDM,
+            vasp.client.address(),
+            Subaddress::new([7; Subaddress::LENGTH]),
+        )
+        .unwrap();
+        PaymentActorObject {
+            address: address.into_boxed_str(),
+            kyc_data: None,
+            status: StatusObject {
+                status,
+                abort_code: None,
+                abort_message: None,
+            },
+            metadata: vec![],
+            additional_kyc_data: None,
+        }
+    }
+
+    fn sender_init(sender: &Vasp, receiver: &Vasp) -> PaymentObject {
+        let mut payment = PaymentObject {
+            sender: actor(sender, Status::NeedsKycData),
+            receiver: actor(receiver, Status::None),
+            reference_id: Uuid::new_v4(),
+            originial_payment_reference_id: None,
+            recipient_signature: None,
+            action: PaymentActionObject {
+                amount: 1_000_000,
+                currency: "XUS".to_owned(),
+                action: ActionType::Charge,
+                timestamp: 0,
+            },
+            description: None,
+        };
+        payment.sender.kyc_data = Some(KycDataObject::new_individual());
+        payment
+    }
+
+    fn assert_failure(response: CommandResponseObject, code: ErrorCode) {
+        assert_eq!(response.status(), &CommandStatus::Failure);
+        assert_eq!(response.error().unwrap().code(), &code);
+    }
+
+    #[tokio::test]
+    async fn test_payment_between_two_vasps() {
+        let (sender, receiver) = start_vasps();
+
+        let mut payment = sender_init(&sender, &receiver);
+        let reference_id = payment.reference_id;
+        let command = sender.service.save_outbound(payment.clone()).await.unwrap();
+        let response = sender
+            .client
+            .send_payment(&receiver.base_url, &command)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), &CommandStatus::Success);
+        assert_eq!(response.cid(), Some(command.cid()));
+        assert_eq!(
+            receiver.payment_state(reference_id).await,
+            PaymentState::SenderInit
+        );
+
+        // R_SEND
+        payment.receiver.status.status = Status::ReadyForSettlement;
+        payment.receiver.kyc_data = Some(KycDataObject::new_entity());
+        payment.recipient_signature = Some("00".to_owned());
+        let request = CommandRequestObject::new(
+            Command::PaymentCommand(PaymentCommandObject::new(payment.clone())),
+            Uuid::new_v4(),
+        );
+        let response = receiver
+            .client
+            .send_command(&sender.base_url, sender.client.address(), &request)
+            .await
+            .unwrap();
+        assert_failure(response, ErrorCode::InvalidRecipientSignature);
+        assert_eq!(
+            sender.payment_state(reference_id).await,
+            PaymentState::SenderInit
+        );
+
+        sign_payment(&mut payment, &receiver.service.signing_key).unwrap();
+        let command = receiver
+            .service
+            .save_outbound(payment.clone())
+            .await
+            .unwrap();
+        let response = receiver
+            .client
+            .send_payment(&sender.base_url, &command)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), &CommandStatus::Success);
+        assert_eq!(
+            sender.payment_state(reference_id).await,
+            PaymentState::RecieverSend
+        );
+
+        // R_SEND -> R_SOFT is not a valid transition, even when sent without local validation
+        let mut soft_match = payment.clone();
+        soft_match.receiver.status.status = Status::SoftMatch;
+        let request = CommandRequestObject::new(
+            Command::PaymentCommand(PaymentCommandObject::new(soft_match)),
+            Uuid::new_v4(),
+        );
+        let response = receiver
+            .client
+            .send_command(&sender.base_url, sender.client.address(), &request)
+            .await
+            .unwrap();
+        assert_failure(response, ErrorCode::InvalidTransition);
+        assert_eq!(
+            sender.payment_state(reference_id).await,
+            PaymentState::RecieverSend
+        );
+
+        // READY
+        payment.sender.status.status = Status::ReadyForSettlement;
+        let command = sender.service.save_outbound(payment.clone()).await.unwrap();
+        let response = sender
+            .client
+            .send_payment(&receiver.base_url, &command)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), &CommandStatus::Success);
+        assert_eq!(
+            receiver.payment_state(reference_id).await,
+            PaymentState::Ready
+        );
+
+        // a retry of the same command is acknowledged without changing anything
+        let response = sender
+            .client
+            .send_payment(&receiver.base_url, &command)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), &CommandStatus::Success);
+        assert_eq!(
+            receiver.payment_state(reference_id).await,
+            PaymentState::Ready
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_requests_not_signed_by_compliance_key() {
+        let (sender, receiver) = start_vasps();
+        let payment = sender_init(&sender, &receiver);
+        let command = sender.service.save_outbound(payment).await.unwrap();
+
+        // the receiver's response is still verified against its registered key
+        let compliance_keys: HashMap<_, _> = vec![(
+            receiver.client.address(),
+            receiver.service.signing_key.public,
+        )]
+        .into_iter()
+        .collect();
+        let impostor = OffChainClient::new(
+            HumanReadablePrefix::TDM,
+            sender.client.address(),
+            Arc::new(Keypair::generate(&mut rand_core::OsRng)),
+            Arc::new(compliance_keys),
+        )
+        .unwrap();
+        let response = impostor
+            .send_payment(&receiver.base_url, &command)
+            .await
+            .unwrap();
+        assert_failure(response, ErrorCode::InvalidJwsSignature);
+        assert!(receiver
+            .service
+            .store()
+            .get_payment(command.reference_id())
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
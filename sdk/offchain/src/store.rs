@@ -0,0 +1,74 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Storage of the latest `PaymentCommand` of every payment a VASP takes part in.
+
+use crate::payment_command::PaymentCommand;
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Mutex};
+use thiserror::Error;
+use uuid::Uuid;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+#[derive(Debug, Error)]
+pub enum PaymentStoreError {
+    /// Another command for the same payment was saved in the meantime.
+    #[error("payment {0} was updated concurrently")]
+    Conflict(Uuid),
+    #[error(transparent)]
+    Other(#[from] BoxError),
+}
+
+#[async_trait]
+pub trait PaymentStore: Send + Sync {
+    /// Returns the latest command of the payment with `reference_id`, if any.
+    async fn get_payment(
+        &self,
+        reference_id: Uuid,
+    ) -> Result<Option<PaymentCommand>, PaymentStoreError>;
+
+    /// Saves `command` as the latest command of its payment. `prior_cid` is the cid of the latest
+    /// command the caller validated `command` against, or `None` for a new payment; the store must
+    /// fail with `PaymentStoreError::Conflict` if that is no longer the latest command.
+    async fn save_payment(
+        &self,
+        command: PaymentCommand,
+        prior_cid: Option<Uuid>,
+    ) -> Result<(), PaymentStoreError>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryPaymentStore {
+    payments: Mutex<HashMap<Uuid, PaymentCommand>>,
+}
+
+impl InMemoryPaymentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PaymentStore for InMemoryPaymentStore {
+    async fn get_payment(
+        &self,
+        reference_id: Uuid,
+    ) -> Result<Option<PaymentCommand>, PaymentStoreError> {
+        Ok(self.payments.lock().unwrap().get(&reference_id).cloned())
+    }
+
+    async fn save_payment(
+        &self,
+        command: PaymentCommand,
+        prior_cid: Option<Uuid>,
+    ) -> Result<(), PaymentStoreError> {
+        let reference_id = command.reference_id();
+        let mut payments = self.payments.lock().unwrap();
+        if payments.get(&reference_id).map(PaymentCommand::cid) != prior_cid {
+            return Err(PaymentStoreError::Conflict(reference_id));
+        }
+        payments.insert(reference_id, command);
+        Ok(())
+    }
+}
@@ -0,0 +1,464 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dual attestation for payments subject to the travel rule.
+//!
+//! A peer-to-peer payment between VASPs whose value reaches the on-chain dual attestation limit
+//! must carry travel rule metadata and the receiving VASP's signature over
+//! `metadata || sender address || amount || domain separator`, made with its on-chain compliance
+//! key. The signature is exchanged as the `recipient_signature` of the off-chain `PaymentObject`.
+
+use crate::{
+    client::{OffChainClient, OffChainClientError},
+    compliance::{ComplianceKeyError, ComplianceKeyResolver, OnChainComplianceKeys},
+    identifier::{decode_account, IntentIdentifierError},
+    payment_command::PaymentCommand,
+    service::OffChainService,
+    types::{CommandStatus, OffChainError, PaymentObject},
+};
+use diem_sdk::{
+    client::{views::AccountRoleView, Client},
+    crypto::ed25519::ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier},
+    transaction_builder::{
+        Currency, DualAttestationMessage, TransactionBuilder, TransactionFactory,
+    },
+    types::{
+        account_address::AccountAddress,
+        account_config::{diem_root_address, CurrencyInfoResource},
+        account_state::AccountState,
+        account_state_blob::AccountStateBlob,
+        transaction::metadata::{Metadata, TravelRuleMetadata, TravelRuleMetadataV0},
+    },
+};
+use std::{convert::TryFrom, sync::Arc};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum TravelRuleError {
+    #[error(transparent)]
+    Client(#[from] diem_sdk::client::Error),
+    #[error(transparent)]
+    ComplianceKey(#[from] ComplianceKeyError),
+    #[error(transparent)]
+    OffChainClient(#[from] OffChainClientError),
+    #[error(transparent)]
+    Identifier(#[from] IntentIdentifierError),
+    #[error("off-chain command failed: {0:?}")]
+    Command(Option<OffChainError>),
+    #[error("dual attestation limit is not published on chain")]
+    MissingLimit,
+    #[error("currency {0} is not registered on chain")]
+    UnknownCurrency(Currency),
+    #[error("on-chain currency info could not be decoded: {0}")]
+    CurrencyInfo(String),
+    #[error("XDX value of {0} overflows, the payment would abort on chain")]
+    ValueOverflow(u64),
+    #[error("payment {0} has no recipient signature")]
+    MissingSignature(Uuid),
+    #[error("recipient signature of payment {0} is invalid")]
+    InvalidSignature(Uuid),
+    #[error("payment {reference_id} is in {payment_currency}, not in {currency}")]
+    CurrencyMismatch {
+        reference_id: Uuid,
+        payment_currency: String,
+        currency: Currency,
+    },
+}
+
+/// Travel rule metadata referring to the off-chain payment `reference_id`.
+pub fn travel_rule_metadata(reference_id: Uuid) -> Vec<u8> {
+    let metadata = Metadata::TravelRuleMetadata(TravelRuleMetadata::TravelRuleMetadataVersion0(
+        TravelRuleMetadataV0 {
+            off_chain_reference_id: Some(reference_id.to_string()),
+        },
+    ));
+    bcs::to_bytes(&metadata).expect("metadata serialization cannot fail")
+}
+
+/// The message the receiving VASP signs to attest `payment`.
+pub fn signing_message(payment: &PaymentObject) -> Result<DualAttestationMessage, TravelRuleError> {
+    let (_, sender, _) = decode_account(&payment.sender().address)?;
+    Ok(DualAttestationMessage::new(
+        travel_rule_metadata(payment.reference_id()),
+        sender,
+        payment.action.amount,
+    ))
+}
+
+/// Whether a payment of `amount` in the currency described by `currency_info` reaches the dual
+/// attestation `limit`. The XDX value is computed with the same 32|32 fixed-point arithmetic as
+/// `DualAttestation::dual_attestation_required`, so the result agrees with the VM even next to the
+/// limit.
+pub fn dual_attestation_required(
+    currency_info: &CurrencyInfoResource,
+    amount: u64,
+    limit: u64,
+) -> Result<bool, TravelRuleError> {
+    currency_info
+        .approx_xdx_for_value(amount)
+        .map(|xdx_value| xdx_value >= limit)
+        .ok_or(TravelRuleError::ValueOverflow(amount))
+}
+
+/// The parent VASP of the account at `address` with `role`, None if the account isn't a VASP.
+pub fn parent_vasp_address(
+    address: AccountAddress,
+    role: &AccountRoleView,
+) -> Option<AccountAddress> {
+    match role {
+        AccountRoleView::ParentVASP { .. } => Some(address),
+        AccountRoleView::ChildVASP {
+            parent_vasp_address,
+        } => Some(*parent_vasp_address),
+        _ => None,
+    }
+}
+
+/// Whether a payment between accounts under the parent VASPs `payer_parent` and `payee_parent`
+/// (None for accounts that aren't VASPs) can be subject to dual attestation. As in
+/// `DualAttestation::dual_attestation_required`, only payments between distinct VASPs are:
+/// payments to or from other accounts, and between a parent VASP and its children, are exempt.
+pub fn between_distinct_vasps(
+    payer_parent: Option<AccountAddress>,
+    payee_parent: Option<AccountAddress>,
+) -> bool {
+    matches!((payer_parent, payee_parent), (Some(payer), Some(payee)) if payer != payee)
+}
+
+/// Sets the `recipient_signature` of `payment`; called by the receiving VASP with its compliance
+/// key before it reports itself ready for settlement.
+pub fn sign_payment(
+    payment: &mut PaymentObject,
+    compliance_key: &Keypair,
+) -> Result<(), TravelRuleError> {
+    let signature = compliance_key.sign(signing_message(payment)?.message());
+    payment.recipient_signature = Some(hex::encode(signature.to_bytes()));
+    Ok(())
+}
+
+/// Checks the `recipient_signature` of `payment` against the receiving VASP's compliance key and
+/// returns the decoded signature.
+pub fn verify_recipient_signature(
+    payment: &PaymentObject,
+    compliance_key: &PublicKey,
+) -> Result<Vec<u8>, TravelRuleError> {
+    let reference_id = payment.reference_id();
+    let signature = hex::decode(
+        payment
+            .recipient_signature()
+            .ok_or(TravelRuleError::MissingSignature(reference_id))?,
+    )
+    .map_err(|_| TravelRuleError::InvalidSignature(reference_id))?;
+    let message = signing_message(payment)?;
+    Signature::try_from(signature.as_slice())
+        .and_then(|parsed| compliance_key.verify(message.message(), &parsed))
+        .map_err(|_| TravelRuleError::InvalidSignature(reference_id))?;
+    Ok(signature)
+}
+
+/// Reads the dual attestation parameters from chain and assembles attested payments.
+pub struct TravelRule {
+    client: Client,
+    compliance_keys: Arc<dyn ComplianceKeyResolver>,
+}
+
+impl TravelRule {
+    pub fn new(client: Client) -> Self {
+        let compliance_keys = Arc::new(OnChainComplianceKeys::new(client.clone()));
+        Self {
+            client,
+            compliance_keys,
+        }
+    }
+
+    /// The on-chain dual attestation limit, in micro-XDX.
+    pub async fn dual_attestation_limit(&self) -> Result<u64, TravelRuleError> {
+        self.client
+            .get_metadata()
+            .await?
+            .into_inner()
+            .dual_attestation_limit
+            .ok_or(TravelRuleError::MissingLimit)
+    }
+
+    /// Whether a payment of `amount` `currency` from `payer` to `payee` needs dual attestation,
+    /// i.e. whether it is made between distinct VASPs and its approximate XDX value reaches the
+    /// on-chain limit.
+    pub async fn is_dual_attestation_required(
+        &self,
+        currency: Currency,
+        payer: AccountAddress,
+        payee: AccountAddress,
+        amount: u64,
+    ) -> Result<bool, TravelRuleError> {
+        let payer_parent = self.parent_vasp(payer).await?;
+        let payee_parent = self.parent_vasp(payee).await?;
+        if !between_distinct_vasps(payer_parent, payee_parent) {
+            return Ok(false);
+        }
+        let limit = self.dual_attestation_limit().await?;
+        let currency_info = self.currency_info(currency).await?;
+        dual_attestation_required(&currency_info, amount, limit)
+    }
+
+    /// The parent VASP of `address`, None if the account doesn't exist or isn't a VASP.
+    async fn parent_vasp(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Option<AccountAddress>, TravelRuleError> {
+        Ok(self
+            .client
+            .get_account(address)
+            .await?
+            .into_inner()
+            .and_then(|account| parent_vasp_address(address, &account.role)))
+    }
+
+    /// The on-chain `CurrencyInfo` of `currency`. The JSON-RPC currency view only carries a
+    /// rounded exchange rate, so the resource is read from the diem root account state instead.
+    async fn currency_info(
+        &self,
+        currency: Currency,
+    ) -> Result<CurrencyInfoResource, TravelRuleError> {
+        let blob = self
+            .client
+            .get_account_state_with_proof(diem_root_address(), None, None)
+            .await?
+            .into_inner()
+            .blob
+            .ok_or(TravelRuleError::UnknownCurrency(currency))?;
+        let blob = bcs::from_bytes::<AccountStateBlob>(blob.inner())
+            .map_err(|e| TravelRuleError::CurrencyInfo(e.to_string()))?;
+        AccountState::try_from(&blob)
+            .and_then(|state| state.get_registered_currency_info_resources())
+            .map_err(|e| TravelRuleError::CurrencyInfo(e.to_string()))?
+            .into_iter()
+            .find(|info| info.currency_code().as_str() == currency.as_str())
+            .ok_or(TravelRuleError::UnknownCurrency(currency))
+    }
+
+    /// Records `payment`, which must be in its initial sender state, and sends it to the receiving
+    /// VASP, which answers asynchronously with a payment carrying its signature.
+    pub async fn request_signature(
+        &self,
+        off_chain: &OffChainClient,
+        service: &OffChainService,
+        base_url: &str,
+        payment: PaymentObject,
+    ) -> Result<PaymentCommand, TravelRuleError> {
+        let command = service
+            .save_outbound(payment)
+            .await
+            .map_err(|e| TravelRuleError::Command(Some(e)))?;
+        let response = off_chain.send_payment(base_url, &command).await?;
+        if response.status() == &CommandStatus::Success {
+            Ok(command)
+        } else {
+            Err(TravelRuleError::Command(response.into_error()))
+        }
+    }
+
+    /// Verifies the recipient signature of `payment` against the receiving VASP's on-chain
+    /// compliance key and builds the attested `peer_to_peer_with_metadata` transaction.
+    /// `currency` must be the currency of the payment, which the signature doesn't cover.
+    pub async fn peer_to_peer(
+        &self,
+        transaction_factory: &TransactionFactory,
+        currency: Currency,
+        payment: &PaymentObject,
+    ) -> Result<TransactionBuilder, TravelRuleError> {
+        if currency.as_str() != payment.action.currency {
+            return Err(TravelRuleError::CurrencyMismatch {
+                reference_id: payment.reference_id(),
+                payment_currency: payment.action.currency.clone(),
+                currency,
+            });
+        }
+        let (_, receiver, _) = decode_account(&payment.receiver().address)?;
+        let compliance_key = self.compliance_keys.compliance_key(receiver).await?;
+        let signature = verify_recipient_signature(payment, &compliance_key)?;
+        Ok(transaction_factory.peer_to_peer_with_metadata(
+            currency,
+            receiver,
+            payment.action.amount,
+            travel_rule_metadata(payment.reference_id()),
+            signature,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        identifier::{encode_account, HumanReadablePrefix},
+        subaddress::Subaddress,
+        types::{
+            ActionType, KycDataObject, PaymentActionObject, PaymentActorObject, Status,
+            StatusObject,
+        },
+    };
+    use diem_sdk::{
+        move_types::identifier::Identifier,
+        types::{
+            chain_id::ChainId,
+            event::{EventHandle, EventKey},
+        },
+    };
+
+    fn actor(address: AccountAddress, status: Status) -> PaymentActorObject {
+        PaymentActorObject {
+            address: encode_account(HumanReadablePrefix::TDM, address, Subaddress::ZERO)
+                .unwrap()
+                .into_boxed_str(),
+            kyc_data: Some(KycDataObject::new_individual()),
+            status: StatusObject {
+                status,
+                abort_code: None,
+                abort_message: None,
+            },
+            metadata: vec![],
+            additional_kyc_data: None,
+        }
+    }
+
+    fn payment(sender: AccountAddress) -> PaymentObject {
+        PaymentObject {
+            sender: actor(sender, Status::NeedsKycData),
+            receiver: actor(AccountAddress::new([2; 16]), Status::ReadyForSettlement),
+            reference_id: Uuid::new_v4(),
+            originial_payment_reference_id: None,
+            recipient_signature: None,
+            action: PaymentActionObject {
+                amount: 2_000_000_000,
+                currency: "XUS".to_owned(),
+                action: ActionType::Charge,
+                timestamp: 0,
+            },
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_recipient_signature() {
+        let sender = AccountAddress::new([1; 16]);
+        let mut payment = payment(sender);
+        let keypair = Keypair::generate(&mut rand_core::OsRng);
+        assert!(matches!(
+            verify_recipient_signature(&payment, &keypair.public),
+            Err(TravelRuleError::MissingSignature(_))
+        ));
+
+        sign_payment(&mut payment, &keypair).unwrap();
+        let signature = verify_recipient_signature(&payment, &keypair.public).unwrap();
+        assert_eq!(signature.len(), 64);
+
+        // the message is bound to the sender, the amount and the reference id
+        let mut message = travel_rule_metadata(payment.reference_id);
+        message.extend(bcs::to_bytes(&sender).unwrap());
+        message.extend(bcs::to_bytes(&payment.action.amount).unwrap());
+        message.extend(b"@@$$DIEM_ATTEST$$@@");
+        assert_eq!(
+            signing_message(&payment).unwrap().message(),
+            message.as_slice()
+        );
+
+        let other_keypair = Keypair::generate(&mut rand_core::OsRng);
+        assert!(verify_recipient_signature(&payment, &other_keypair.public).is_err());
+        payment.action.amount += 1;
+        assert!(verify_recipient_signature(&payment, &keypair.public).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_peer_to_peer_currency_mismatch() {
+        let mut payment = payment(AccountAddress::new([1; 16]));
+        sign_payment(&mut payment, &Keypair::generate(&mut rand_core::OsRng)).unwrap();
+        // the currency is checked before anything is read from chain
+        let travel_rule = TravelRule::new(Client::new("http://localhost:0"));
+        let result = travel_rule
+            .peer_to_peer(
+                &TransactionFactory::new(ChainId::test()),
+                Currency::XDX,
+                &payment,
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(TravelRuleError::CurrencyMismatch {
+                currency: Currency::XDX,
+                ..
+            })
+        ));
+    }
+
+    fn currency_info(to_xdx_exchange_rate: u64) -> CurrencyInfoResource {
+        let events = || EventHandle::new(EventKey::new_from_address(&AccountAddress::ZERO, 0), 0);
+        CurrencyInfoResource::new(
+            0,
+            0,
+            to_xdx_exchange_rate,
+            false,
+            1_000_000,
+            100,
+            Identifier::new("XUS").unwrap(),
+            true,
+            events(),
+            events(),
+            events(),
+            events(),
+            events(),
+        )
+    }
+
+    #[test]
+    fn test_dual_attestation_required() {
+        // 0.5 as a 32|32 fixed-point number
+        let half = currency_info(1 << 31);
+        assert!(!dual_attestation_required(&half, 1_999, 1_000).unwrap());
+        assert!(dual_attestation_required(&half, 2_000, 1_000).unwrap());
+
+        // 1 + 2^-32 rounds to 1.0 as a float, but not in fixed-point arithmetic
+        let above_one = currency_info((1 << 32) + 1);
+        let amount = 1 << 32;
+        assert!(dual_attestation_required(&above_one, amount, amount + 1).unwrap());
+        assert!(!dual_attestation_required(&above_one, amount, amount + 2).unwrap());
+
+        // the on-chain multiplication aborts when the result doesn't fit in a u64
+        let max = currency_info(u64::max_value());
+        assert!(matches!(
+            dual_attestation_required(&max, u64::max_value(), 0),
+            Err(TravelRuleError::ValueOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_between_distinct_vasps() {
+        let parent = AccountAddress::new([1; AccountAddress::LENGTH]);
+        let other_parent = AccountAddress::new([2; AccountAddress::LENGTH]);
+        let child = AccountAddress::new([3; AccountAddress::LENGTH]);
+        let parent_role = AccountRoleView::ParentVASP {
+            human_name: "vasp".to_string(),
+            base_url: "http://localhost".to_string(),
+            expiration_time: 0,
+            compliance_key: vec![].into(),
+            num_children: 1,
+            compliance_key_rotation_events_key: EventKey::new_from_address(&parent, 0),
+            base_url_rotation_events_key: EventKey::new_from_address(&parent, 1),
+            vasp_domains: None,
+        };
+        let child_role = AccountRoleView::ChildVASP {
+            parent_vasp_address: parent,
+        };
+        assert_eq!(parent_vasp_address(parent, &parent_role), Some(parent));
+        assert_eq!(parent_vasp_address(child, &child_role), Some(parent));
+        assert_eq!(parent_vasp_address(child, &AccountRoleView::Unknown), None);
+
+        assert!(between_distinct_vasps(Some(parent), Some(other_parent)));
+        // a child VASP paying its parent, or a VASP paying itself
+        assert!(!between_distinct_vasps(Some(parent), Some(parent)));
+        // payments to or from accounts that aren't VASPs
+        assert!(!between_distinct_vasps(Some(parent), None));
+        assert!(!between_distinct_vasps(None, Some(parent)));
+    }
+}
@@ -117,6 +117,32 @@ impl CommandResponseObject {
             cid: None,
         }
     }
+
+    pub fn with_cid(mut self, cid: Uuid) -> Self {
+        self.cid = Some(cid);
+        self
+    }
+
+    pub fn with_error(mut self, error: OffChainError) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    pub fn status(&self) -> &CommandStatus {
+        &self.status
+    }
+
+    pub fn error(&self) -> Option<&OffChainError> {
+        self.error.as_ref()
+    }
+
+    pub fn into_error(self) -> Option<OffChainError> {
+        self.error
+    }
+
+    pub fn cid(&self) -> Option<Uuid> {
+        self.cid
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -257,6 +283,51 @@ pub struct OffChainError {
     message: Option<String>,
 }
 
+impl OffChainError {
+    pub fn new(error_type: OffChainErrorType, code: ErrorCode) -> Self {
+        Self {
+            error_type,
+            field: None,
+            code,
+            message: None,
+        }
+    }
+
+    pub fn protocol(code: ErrorCode) -> Self {
+        Self::new(OffChainErrorType::Protocol, code)
+    }
+
+    pub fn command(code: ErrorCode) -> Self {
+        Self::new(OffChainErrorType::Command, code)
+    }
+
+    pub fn with_field<T: Into<String>>(mut self, field: T) -> Self {
+        self.field = Some(field.into());
+        self
+    }
+
+    pub fn with_message<T: Into<String>>(mut self, message: T) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn error_type(&self) -> &OffChainErrorType {
+        &self.error_type
+    }
+
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    pub fn code(&self) -> &ErrorCode {
+        &self.code
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(tag = "command_type", content = "command")]
 #[allow(clippy::large_enum_variant)]
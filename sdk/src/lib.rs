@@ -11,6 +11,7 @@
 //! * `crypto` - Types used for signing and verifying
 //! * `transaction_builder` - Includes helpers for constructing transactions
 //! * `types` - Includes types for mango on-chain data structures
+//! * `wallet` - Includes a deterministic wallet deriving accounts from a mnemonic
 //!
 //! ## Example
 //!
@@ -64,6 +65,8 @@ pub mod transaction_builder;
 
 pub mod types;
 
+pub mod wallet;
+
 pub mod move_types {
     pub use move_core_types::*;
 }
@@ -4,12 +4,19 @@
 use crate::{
     client::AccountAddress,
     crypto::{
-        ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-        traits::Uniform,
+        ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+        multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+        traits::{SigningKey, Uniform},
     },
     transaction_builder::TransactionBuilder,
-    types::transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+    types::transaction::{
+        authenticator::{AccountAuthenticator, AuthenticationKey},
+        RawTransaction, RawTransactionWithData, SignedTransaction,
+    },
 };
+use anyhow::{ensure, format_err, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub use mango_types::*;
 
@@ -162,3 +169,631 @@ impl From<Ed25519PrivateKey> for AccountKey {
         Self::from_private_key(private_key)
     }
 }
+
+/// A local account authenticated by a K-of-N `MultiEd25519` key.
+///
+/// The account may hold only a subset of the private keys, in which case signatures are collected
+/// from the other key holders through a [`PartiallySignedTransaction`].
+#[derive(Debug)]
+pub struct LocalMultiAccount {
+    /// Address of the account.
+    address: AccountAddress,
+    /// Multi-key authentication key of the account.
+    key: MultiAccountKey,
+    /// Latest known sequence number of the account, it can be different from validator.
+    sequence_number: u64,
+}
+
+impl LocalMultiAccount {
+    pub fn new(address: AccountAddress, key: MultiAccountKey, sequence_number: u64) -> Self {
+        Self {
+            address,
+            key,
+            sequence_number,
+        }
+    }
+
+    /// Generates `num_keys` keys, all held locally, with the given signing `threshold`.
+    pub fn generate<R>(rng: &mut R, num_keys: u8, threshold: u8) -> Result<Self>
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let key = MultiAccountKey::generate(rng, num_keys, threshold)?;
+        let address = key.authentication_key().derived_address();
+
+        Ok(Self::new(address, key, 0))
+    }
+
+    /// Signs `txn` with the local keys. Fails if fewer than `threshold` keys are held locally.
+    pub fn sign_transaction(&self, txn: RawTransaction) -> Result<SignedTransaction> {
+        let mut partial = PartiallySignedTransaction::new(txn, self.public_key().clone());
+        self.key.sign_partial(&mut partial)?;
+        partial.finish()
+    }
+
+    pub fn sign_with_transaction_builder(
+        &mut self,
+        builder: TransactionBuilder,
+    ) -> Result<SignedTransaction> {
+        let raw_txn = builder
+            .sender(self.address())
+            .sequence_number(self.sequence_number())
+            .build();
+        let signed_txn = self.sign_transaction(raw_txn)?;
+        *self.sequence_number_mut() += 1;
+        Ok(signed_txn)
+    }
+
+    pub fn sign_multi_agent_with_transaction_builder(
+        &mut self,
+        secondary_signers: Vec<&Self>,
+        builder: TransactionBuilder,
+    ) -> Result<SignedTransaction> {
+        let raw_txn = builder
+            .sender(self.address())
+            .sequence_number(self.sequence_number())
+            .build();
+        let mut partial = PartiallySignedTransaction::new_multi_agent(
+            raw_txn,
+            self.public_key().clone(),
+            secondary_signers
+                .iter()
+                .map(|signer| (signer.address(), signer.public_key().clone().into()))
+                .collect(),
+        );
+        self.key.sign_partial(&mut partial)?;
+        for signer in secondary_signers {
+            signer.key.sign_partial(&mut partial)?;
+        }
+        let signed_txn = partial.finish()?;
+        *self.sequence_number_mut() += 1;
+        Ok(signed_txn)
+    }
+
+    /// Starts collecting signatures for a transaction built from `builder`. The sequence number
+    /// is consumed, so the partially signed transaction should be completed or abandoned.
+    pub fn partially_sign_with_transaction_builder(
+        &mut self,
+        builder: TransactionBuilder,
+    ) -> Result<PartiallySignedTransaction> {
+        let raw_txn = builder
+            .sender(self.address())
+            .sequence_number(self.sequence_number())
+            .build();
+        let mut partial = PartiallySignedTransaction::new(raw_txn, self.public_key().clone());
+        self.key.sign_partial(&mut partial)?;
+        *self.sequence_number_mut() += 1;
+        Ok(partial)
+    }
+
+    /// Adds the signatures of the local keys to `partial`, wherever this account is a signer.
+    pub fn sign_partial(&self, partial: &mut PartiallySignedTransaction) -> Result<()> {
+        self.key.sign_partial(partial)
+    }
+
+    pub fn address(&self) -> AccountAddress {
+        self.address
+    }
+
+    pub fn public_key(&self) -> &MultiEd25519PublicKey {
+        self.key.public_key()
+    }
+
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        self.key.authentication_key()
+    }
+
+    pub fn sequence_number(&self) -> u64 {
+        self.sequence_number
+    }
+
+    pub fn sequence_number_mut(&mut self) -> &mut u64 {
+        &mut self.sequence_number
+    }
+
+    pub fn rotate_key(&mut self, new_key: MultiAccountKey) -> MultiAccountKey {
+        std::mem::replace(&mut self.key, new_key)
+    }
+}
+
+/// A `MultiEd25519` public key together with the private keys held locally, indexed by their
+/// position in the public key.
+#[derive(Debug)]
+pub struct MultiAccountKey {
+    private_keys: BTreeMap<u8, Ed25519PrivateKey>,
+    public_key: MultiEd25519PublicKey,
+    authentication_key: AuthenticationKey,
+}
+
+impl MultiAccountKey {
+    pub fn generate<R>(rng: &mut R, num_keys: u8, threshold: u8) -> Result<Self>
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let private_keys = (0..num_keys)
+            .map(|_| Ed25519PrivateKey::generate(&mut *rng))
+            .collect();
+        Self::from_private_keys(private_keys, threshold)
+    }
+
+    /// Creates a key where every private key is held locally.
+    pub fn from_private_keys(private_keys: Vec<Ed25519PrivateKey>, threshold: u8) -> Result<Self> {
+        let public_keys = private_keys.iter().map(Ed25519PublicKey::from).collect();
+        let public_key = MultiEd25519PublicKey::new(public_keys, threshold)?;
+        let private_keys = (0..).zip(private_keys).collect();
+        Self::new(public_key, private_keys)
+    }
+
+    /// Creates a key holding `private_keys` locally, keyed by their index in `public_key`.
+    pub fn new(
+        public_key: MultiEd25519PublicKey,
+        private_keys: BTreeMap<u8, Ed25519PrivateKey>,
+    ) -> Result<Self> {
+        for (index, private_key) in &private_keys {
+            ensure!(
+                public_key.public_keys().get(*index as usize)
+                    == Some(&Ed25519PublicKey::from(private_key)),
+                "private key does not match public key {}",
+                index
+            );
+        }
+        let authentication_key = AuthenticationKey::multi_ed25519(&public_key);
+
+        Ok(Self {
+            private_keys,
+            public_key,
+            authentication_key,
+        })
+    }
+
+    pub fn private_keys(&self) -> &BTreeMap<u8, Ed25519PrivateKey> {
+        &self.private_keys
+    }
+
+    pub fn public_key(&self) -> &MultiEd25519PublicKey {
+        &self.public_key
+    }
+
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        self.authentication_key
+    }
+
+    /// Adds the signatures of the local keys to `partial`.
+    pub fn sign_partial(&self, partial: &mut PartiallySignedTransaction) -> Result<()> {
+        for private_key in self.private_keys.values() {
+            partial.sign(private_key)?;
+        }
+        Ok(())
+    }
+}
+
+/// Signatures collected so far for one signer of a [`PartiallySignedTransaction`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PartialAuthenticator {
+    Ed25519 {
+        public_key: Ed25519PublicKey,
+        signature: Option<Ed25519Signature>,
+    },
+    MultiEd25519 {
+        public_key: MultiEd25519PublicKey,
+        signatures: BTreeMap<u8, Ed25519Signature>,
+    },
+}
+
+impl PartialAuthenticator {
+    /// Whether enough signatures were collected to build the authenticator.
+    pub fn is_complete(&self) -> bool {
+        match self {
+            Self::Ed25519 { signature, .. } => signature.is_some(),
+            Self::MultiEd25519 {
+                public_key,
+                signatures,
+            } => signatures.len() >= *public_key.threshold() as usize,
+        }
+    }
+
+    /// Records `signature` for every position held by `public_key`, returning whether any did.
+    fn add_signature(
+        &mut self,
+        public_key: &Ed25519PublicKey,
+        signature: &Ed25519Signature,
+    ) -> bool {
+        match self {
+            Self::Ed25519 {
+                public_key: signer,
+                signature: slot,
+            } => {
+                if signer == public_key {
+                    *slot = Some(signature.clone());
+                }
+                signer == public_key
+            }
+            Self::MultiEd25519 {
+                public_key: signer,
+                signatures,
+            } => {
+                let mut found = false;
+                for (index, key) in (0..).zip(signer.public_keys()) {
+                    if key == public_key {
+                        signatures.insert(index, signature.clone());
+                        found = true;
+                    }
+                }
+                found
+            }
+        }
+    }
+
+    /// Whether `other` collects signatures for the same key.
+    fn is_same_signer(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Ed25519 { public_key, .. },
+                Self::Ed25519 {
+                    public_key: other_public_key,
+                    ..
+                },
+            ) => public_key == other_public_key,
+            (
+                Self::MultiEd25519 { public_key, .. },
+                Self::MultiEd25519 {
+                    public_key: other_public_key,
+                    ..
+                },
+            ) => public_key == other_public_key,
+            _ => false,
+        }
+    }
+
+    /// Adds the signatures collected in `other`, which must be for the same key.
+    fn merge(&mut self, other: Self) {
+        match (self, other) {
+            (
+                Self::Ed25519 { signature, .. },
+                Self::Ed25519 {
+                    signature: other_signature,
+                    ..
+                },
+            ) => {
+                if signature.is_none() {
+                    *signature = other_signature;
+                }
+            }
+            (
+                Self::MultiEd25519 { signatures, .. },
+                Self::MultiEd25519 {
+                    signatures: other_signatures,
+                    ..
+                },
+            ) => signatures.extend(other_signatures),
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> Result<AccountAuthenticator> {
+        match self {
+            Self::Ed25519 {
+                public_key,
+                signature,
+            } => {
+                let signature = signature.ok_or_else(|| format_err!("missing signature"))?;
+                Ok(AccountAuthenticator::ed25519(public_key, signature))
+            }
+            Self::MultiEd25519 {
+                public_key,
+                signatures,
+            } => {
+                let signature = multi_ed25519_signature(&public_key, signatures)?;
+                Ok(AccountAuthenticator::multi_ed25519(public_key, signature))
+            }
+        }
+    }
+}
+
+/// Builds a `threshold`-of-N signature out of the collected `signatures`.
+fn multi_ed25519_signature(
+    public_key: &MultiEd25519PublicKey,
+    signatures: BTreeMap<u8, Ed25519Signature>,
+) -> Result<MultiEd25519Signature> {
+    let threshold = *public_key.threshold() as usize;
+    ensure!(
+        signatures.len() >= threshold,
+        "{} signatures collected, {} required",
+        signatures.len(),
+        threshold
+    );
+    Ok(MultiEd25519Signature::new(
+        signatures
+            .into_iter()
+            .take(threshold)
+            .map(|(index, signature)| (signature, index))
+            .collect(),
+    )?)
+}
+
+impl From<Ed25519PublicKey> for PartialAuthenticator {
+    fn from(public_key: Ed25519PublicKey) -> Self {
+        Self::Ed25519 {
+            public_key,
+            signature: None,
+        }
+    }
+}
+
+impl From<MultiEd25519PublicKey> for PartialAuthenticator {
+    fn from(public_key: MultiEd25519PublicKey) -> Self {
+        Self::MultiEd25519 {
+            public_key,
+            signatures: BTreeMap::new(),
+        }
+    }
+}
+
+/// A transaction whose signatures are collected across several machines.
+///
+/// It is created by the coordinator, serialized with BCS and passed to every key holder, who adds
+/// signatures with [`PartiallySignedTransaction::sign`]. Copies signed in parallel can be combined
+/// with [`PartiallySignedTransaction::merge`]. Once every signer reached its threshold,
+/// [`PartiallySignedTransaction::finish`] produces the `SignedTransaction`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PartiallySignedTransaction {
+    raw_txn: RawTransaction,
+    sender: PartialAuthenticator,
+    secondary_signer_addresses: Vec<AccountAddress>,
+    secondary_signers: Vec<PartialAuthenticator>,
+}
+
+impl PartiallySignedTransaction {
+    pub fn new<T: Into<PartialAuthenticator>>(raw_txn: RawTransaction, sender: T) -> Self {
+        Self::new_multi_agent(raw_txn, sender, vec![])
+    }
+
+    pub fn new_multi_agent<T: Into<PartialAuthenticator>>(
+        raw_txn: RawTransaction,
+        sender: T,
+        secondary_signers: Vec<(AccountAddress, PartialAuthenticator)>,
+    ) -> Self {
+        let (secondary_signer_addresses, secondary_signers) = secondary_signers.into_iter().unzip();
+        Self {
+            raw_txn,
+            sender: sender.into(),
+            secondary_signer_addresses,
+            secondary_signers,
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(bytes)?)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    pub fn raw_transaction(&self) -> &RawTransaction {
+        &self.raw_txn
+    }
+
+    fn is_multi_agent(&self) -> bool {
+        !self.secondary_signer_addresses.is_empty()
+    }
+
+    fn signers_mut(&mut self) -> impl Iterator<Item = &mut PartialAuthenticator> {
+        std::iter::once(&mut self.sender).chain(self.secondary_signers.iter_mut())
+    }
+
+    /// Signs with `private_key` for every signer that includes its public key. Fails if the key
+    /// belongs to none of the signers.
+    pub fn sign(&mut self, private_key: &Ed25519PrivateKey) -> Result<()> {
+        let public_key = Ed25519PublicKey::from(private_key);
+        let signature = if self.is_multi_agent() {
+            private_key.sign(&RawTransactionWithData::new_multi_agent(
+                self.raw_txn.clone(),
+                self.secondary_signer_addresses.clone(),
+            ))
+        } else {
+            private_key.sign(&self.raw_txn)
+        };
+
+        let mut found = false;
+        for signer in self.signers_mut() {
+            found |= signer.add_signature(&public_key, &signature);
+        }
+        ensure!(
+            found,
+            "key {} is not a signer of the transaction",
+            public_key
+        );
+        Ok(())
+    }
+
+    /// Combines the signatures collected in `other`, which must be a copy of the same transaction
+    /// with the same signers. Nothing is merged if it isn't.
+    pub fn merge(&mut self, other: Self) -> Result<()> {
+        ensure!(
+            self.raw_txn == other.raw_txn
+                && self.secondary_signer_addresses == other.secondary_signer_addresses,
+            "cannot merge signatures of different transactions"
+        );
+        ensure!(
+            self.secondary_signers.len() == self.secondary_signer_addresses.len()
+                && other.secondary_signers.len() == other.secondary_signer_addresses.len(),
+            "the number of secondary signers doesn't match their addresses"
+        );
+        ensure!(
+            self.sender.is_same_signer(&other.sender)
+                && self
+                    .secondary_signers
+                    .iter()
+                    .zip(&other.secondary_signers)
+                    .all(|(signer, other)| signer.is_same_signer(other)),
+            "cannot merge signatures of different signers"
+        );
+        self.sender.merge(other.sender);
+        for (signer, other) in self
+            .secondary_signers
+            .iter_mut()
+            .zip(other.secondary_signers)
+        {
+            signer.merge(other);
+        }
+        Ok(())
+    }
+
+    /// Whether every signer has collected enough signatures.
+    pub fn is_complete(&self) -> bool {
+        self.sender.is_complete() && self.secondary_signers.iter().all(|s| s.is_complete())
+    }
+
+    /// Builds the signed transaction and checks its signatures.
+    pub fn finish(self) -> Result<SignedTransaction> {
+        let signed_txn = if self.is_multi_agent() {
+            let sender = self.sender.finish()?;
+            let secondary_signers = self
+                .secondary_signers
+                .into_iter()
+                .map(PartialAuthenticator::finish)
+                .collect::<Result<_>>()?;
+            SignedTransaction::new_multi_agent(
+                self.raw_txn,
+                sender,
+                self.secondary_signer_addresses,
+                secondary_signers,
+            )
+        } else {
+            match self.sender {
+                PartialAuthenticator::Ed25519 {
+                    public_key,
+                    signature,
+                } => {
+                    let signature = signature.ok_or_else(|| format_err!("missing signature"))?;
+                    SignedTransaction::new(self.raw_txn, public_key, signature)
+                }
+                PartialAuthenticator::MultiEd25519 {
+                    public_key,
+                    signatures,
+                } => {
+                    let signature = multi_ed25519_signature(&public_key, signatures)?;
+                    SignedTransaction::new_multisig(self.raw_txn, public_key, signature)
+                }
+            }
+        };
+        Ok(signed_txn.check_signature()?.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        transaction_builder::{Currency, TransactionFactory},
+        types::{chain_id::ChainId, transaction::authenticator::TransactionAuthenticator},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn raw_txn(sender: AccountAddress) -> RawTransaction {
+        TransactionFactory::new(ChainId::test())
+            .peer_to_peer(Currency::XUS, AccountAddress::random(), 10)
+            .sender(sender)
+            .sequence_number(0)
+            .build()
+    }
+
+    #[test]
+    fn test_multi_account_signs_transaction() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let account = LocalMultiAccount::generate(&mut rng, 3, 2).unwrap();
+        let signed_txn = account
+            .sign_transaction(raw_txn(account.address()))
+            .unwrap();
+        match signed_txn.authenticator() {
+            TransactionAuthenticator::MultiEd25519 { signature, .. } => {
+                assert_eq!(signature.signatures().len(), 2)
+            }
+            authenticator => panic!("unexpected authenticator {}", authenticator),
+        }
+    }
+
+    #[test]
+    fn test_partial_signatures_across_key_holders() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let keys: Vec<_> = (0..3)
+            .map(|_| Ed25519PrivateKey::generate(&mut rng))
+            .collect();
+        let public_key =
+            MultiEd25519PublicKey::new(keys.iter().map(Ed25519PublicKey::from).collect(), 2)
+                .unwrap();
+        let sender = AuthenticationKey::multi_ed25519(&public_key).derived_address();
+        let partial = PartiallySignedTransaction::new(raw_txn(sender), public_key);
+
+        // two key holders sign their own copy of the serialized transaction
+        let mut first =
+            PartiallySignedTransaction::from_bytes(&partial.to_bytes().unwrap()).unwrap();
+        first.sign(&keys[0]).unwrap();
+        assert!(!first.is_complete());
+        let mut second =
+            PartiallySignedTransaction::from_bytes(&partial.to_bytes().unwrap()).unwrap();
+        second.sign(&keys[2]).unwrap();
+
+        let mut other_key_partial = partial;
+        assert!(other_key_partial
+            .sign(&Ed25519PrivateKey::generate(&mut rng))
+            .is_err());
+        assert!(other_key_partial.clone().finish().is_err());
+
+        first.merge(second).unwrap();
+        assert!(first.is_complete());
+        first.finish().unwrap();
+    }
+
+    #[test]
+    fn test_merge_rejects_different_signers() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let sender = LocalAccount::generate(&mut rng);
+        let secondary = LocalAccount::generate(&mut rng);
+        let other_key = Ed25519PublicKey::from(&Ed25519PrivateKey::generate(&mut rng));
+        let raw_txn = raw_txn(sender.address());
+        let partial = |secondary_key: Ed25519PublicKey| {
+            PartiallySignedTransaction::new_multi_agent(
+                raw_txn.clone(),
+                sender.public_key().clone(),
+                vec![(secondary.address(), secondary_key.into())],
+            )
+        };
+
+        let mut first = partial(secondary.public_key().clone());
+        first.sign(sender.private_key()).unwrap();
+        let before = first.clone();
+        let mut second = partial(other_key);
+        second.sign(sender.private_key()).unwrap();
+        assert!(first.merge(second).is_err());
+        assert_eq!(first, before);
+
+        // a copy missing a secondary signer
+        let mut truncated = partial(secondary.public_key().clone());
+        truncated.secondary_signers.clear();
+        assert!(first.merge(truncated).is_err());
+        assert_eq!(first, before);
+    }
+
+    #[test]
+    fn test_multi_agent_with_mixed_signers() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let account = LocalMultiAccount::generate(&mut rng, 2, 2).unwrap();
+        let secondary = LocalAccount::generate(&mut rng);
+        let mut partial = PartiallySignedTransaction::new_multi_agent(
+            raw_txn(account.address()),
+            account.public_key().clone(),
+            vec![(secondary.address(), secondary.public_key().clone().into())],
+        );
+        account.sign_partial(&mut partial).unwrap();
+        assert!(!partial.is_complete());
+        partial.sign(secondary.private_key()).unwrap();
+
+        let signed_txn = partial.finish().unwrap();
+        assert_eq!(
+            signed_txn.authenticator().secondary_signer_addreses(),
+            vec![secondary.address()]
+        );
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! [BIP-39](https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki) mnemonic phrases
+//! over the English word list.
+
+use anyhow::{bail, ensure, format_err, Result};
+use hmac::Hmac;
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256, Sha512};
+use std::{fmt, str::FromStr};
+
+/// Number of PBKDF2 rounds used to stretch a mnemonic into a seed, as specified by BIP-39.
+const PBKDF2_ROUNDS: u32 = 2048;
+/// Length in bytes of the seed produced by `Mnemonic::to_seed`.
+pub const SEED_LENGTH: usize = 64;
+
+static WORDS: Lazy<Vec<&'static str>> =
+    Lazy::new(|| include_str!("english.txt").split_whitespace().collect());
+
+/// A BIP-39 mnemonic: 12, 15, 18, 21 or 24 words encoding 128 to 256 bits of entropy plus a
+/// checksum.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Mnemonic(Vec<&'static str>);
+
+impl Mnemonic {
+    /// Generates a 24-word mnemonic.
+    pub fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let mut entropy = [0u8; 32];
+        rng.fill_bytes(&mut entropy);
+        Self::from_entropy(&entropy).expect("32 bytes is a valid entropy length")
+    }
+
+    /// Encodes `entropy`, which must be 16 to 32 bytes long and a multiple of 4 bytes.
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self> {
+        ensure!(
+            (16..=32).contains(&entropy.len()) && entropy.len() % 4 == 0,
+            "invalid entropy length {}",
+            entropy.len()
+        );
+        let checksum = Sha256::digest(entropy);
+        let num_checksum_bits = entropy.len() / 4;
+        let num_words = (entropy.len() * 8 + num_checksum_bits) / 11;
+
+        let words = (0..num_words)
+            .map(|word| {
+                let index = (0..11).fold(0, |index, bit| {
+                    let position = word * 11 + bit;
+                    let set = if position < entropy.len() * 8 {
+                        get_bit(entropy, position)
+                    } else {
+                        get_bit(&checksum, position - entropy.len() * 8)
+                    };
+                    (index << 1) | set as usize
+                });
+                WORDS[index]
+            })
+            .collect();
+        Ok(Self(words))
+    }
+
+    /// Returns the entropy encoded by the mnemonic, after checking its checksum.
+    pub fn to_entropy(&self) -> Result<Vec<u8>> {
+        let num_bits = self.0.len() * 11;
+        let num_entropy_bits = num_bits * 32 / 33;
+        let mut bits = Vec::with_capacity(num_bits);
+        for word in &self.0 {
+            let index = word_index(word)?;
+            bits.extend((0..11).rev().map(|bit| (index >> bit) & 1 == 1));
+        }
+
+        let mut entropy = vec![0u8; num_entropy_bits / 8];
+        for (position, bit) in bits[..num_entropy_bits].iter().enumerate() {
+            if *bit {
+                entropy[position / 8] |= 0x80 >> (position % 8);
+            }
+        }
+        let checksum = Sha256::digest(&entropy);
+        for (position, bit) in bits[num_entropy_bits..].iter().enumerate() {
+            ensure!(get_bit(&checksum, position) == *bit, "invalid checksum");
+        }
+        Ok(entropy)
+    }
+
+    /// Stretches the mnemonic and an optional `passphrase` into a 64-byte seed.
+    pub fn to_seed(&self, passphrase: &str) -> [u8; SEED_LENGTH] {
+        let mut seed = [0u8; SEED_LENGTH];
+        let salt = format!("mnemonic{}", passphrase);
+        pbkdf2::pbkdf2::<Hmac<Sha512>>(
+            self.to_string().as_bytes(),
+            salt.as_bytes(),
+            PBKDF2_ROUNDS,
+            &mut seed,
+        );
+        seed
+    }
+
+    pub fn words(&self) -> &[&'static str] {
+        &self.0
+    }
+}
+
+fn get_bit(bytes: &[u8], position: usize) -> bool {
+    bytes[position / 8] & (0x80 >> (position % 8)) != 0
+}
+
+fn word_index(word: &str) -> Result<usize> {
+    WORDS
+        .binary_search_by(|probe| (*probe).cmp(word))
+        .map_err(|_| format_err!("unknown mnemonic word '{}'", word))
+}
+
+impl FromStr for Mnemonic {
+    type Err = anyhow::Error;
+
+    fn from_str(phrase: &str) -> Result<Self> {
+        let words = phrase
+            .split_whitespace()
+            .map(|word| word_index(word).map(|index| WORDS[index]))
+            .collect::<Result<Vec<_>>>()?;
+        if ![12, 15, 18, 21, 24].contains(&words.len()) {
+            bail!("invalid number of mnemonic words {}", words.len());
+        }
+        let mnemonic = Self(words);
+        mnemonic.to_entropy()?;
+        Ok(mnemonic)
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.join(" "))
+    }
+}
+
+// Mnemonics are secrets: keep them out of logs.
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<elided secret for Mnemonic>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bip39_vectors() {
+        let mnemonic = Mnemonic::from_entropy(&[0u8; 16]).unwrap();
+        assert_eq!(
+            mnemonic.to_string(),
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+             abandon about"
+        );
+        assert_eq!(
+            hex::encode(&mnemonic.to_seed("TREZOR")[..]),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1\
+             e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+
+        let mnemonic = Mnemonic::from_entropy(&[0x80; 16]).unwrap();
+        assert_eq!(
+            mnemonic.to_string(),
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage above"
+        );
+
+        let mnemonic = Mnemonic::from_entropy(&[0x7f; 32]).unwrap();
+        assert_eq!(mnemonic.words().len(), 24);
+        assert_eq!(mnemonic.words()[23], "title");
+        assert_eq!(mnemonic.to_entropy().unwrap(), vec![0x7f; 32]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let phrase =
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage above";
+        let mnemonic = Mnemonic::from_str(phrase).unwrap();
+        assert_eq!(mnemonic.to_entropy().unwrap(), vec![0x80; 16]);
+
+        // wrong checksum word
+        assert!(Mnemonic::from_str(&phrase.replace("above", "absent")).is_err());
+        // unknown word
+        assert!(Mnemonic::from_str(&phrase.replace("letter", "xyzzy")).is_err());
+        // wrong length
+        assert!(Mnemonic::from_str("letter advice cage").is_err());
+    }
+}
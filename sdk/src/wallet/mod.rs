@@ -0,0 +1,118 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A deterministic wallet: every account is derived from a single BIP-39 mnemonic.
+//!
+//! ```
+//! use mango_sdk::wallet::{Mnemonic, Wallet};
+//! use rand::{rngs::StdRng, SeedableRng};
+//! use std::str::FromStr;
+//!
+//! let mut wallet = Wallet::generate(&mut StdRng::seed_from_u64(0));
+//! let account = wallet.new_account().unwrap();
+//!
+//! // the same mnemonic rebuilds the same accounts
+//! let mnemonic = Mnemonic::from_str(&wallet.mnemonic().to_string()).unwrap();
+//! let recovered = Wallet::from_mnemonic(mnemonic, "", 1).unwrap();
+//! assert_eq!(recovered.accounts().unwrap()[0].address(), account.address());
+//! ```
+
+mod key_factory;
+mod mnemonic;
+mod wallet_file;
+
+pub use key_factory::KeyFactory;
+pub use mnemonic::{Mnemonic, SEED_LENGTH};
+pub use wallet_file::WalletFile;
+
+use crate::types::LocalAccount;
+use anyhow::Result;
+use std::fmt;
+
+/// A mnemonic together with the number of accounts derived from it so far.
+pub struct Wallet {
+    mnemonic: Mnemonic,
+    passphrase: String,
+    key_factory: KeyFactory,
+    num_accounts: u64,
+}
+
+impl Wallet {
+    /// Creates a wallet from a freshly generated 24-word mnemonic, without passphrase.
+    pub fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        Self::from_mnemonic(Mnemonic::generate(rng), "", 0)
+            .expect("A 64-byte seed is always long enough")
+    }
+
+    /// Recovers a wallet from its mnemonic, the optional BIP-39 `passphrase` and the number of
+    /// accounts that were derived from it.
+    pub fn from_mnemonic(mnemonic: Mnemonic, passphrase: &str, num_accounts: u64) -> Result<Self> {
+        let key_factory = KeyFactory::new(&mnemonic.to_seed(passphrase))?;
+        Ok(Self {
+            mnemonic,
+            passphrase: passphrase.to_owned(),
+            key_factory,
+            num_accounts,
+        })
+    }
+
+    pub fn mnemonic(&self) -> &Mnemonic {
+        &self.mnemonic
+    }
+
+    pub fn passphrase(&self) -> &str {
+        &self.passphrase
+    }
+
+    pub fn key_factory(&self) -> &KeyFactory {
+        &self.key_factory
+    }
+
+    pub fn num_accounts(&self) -> u64 {
+        self.num_accounts
+    }
+
+    /// Derives the next account of the wallet.
+    pub fn new_account(&mut self) -> Result<LocalAccount> {
+        let account = self.key_factory.account(self.num_accounts)?;
+        self.num_accounts += 1;
+        Ok(account)
+    }
+
+    /// Rebuilds every account derived so far, in derivation order.
+    pub fn accounts(&self) -> Result<Vec<LocalAccount>> {
+        (0..self.num_accounts)
+            .map(|index| self.key_factory.account(index))
+            .collect()
+    }
+}
+
+impl fmt::Debug for Wallet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Wallet {{ <elided secrets>, num_accounts: {} }}",
+            self.num_accounts
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_debug_elides_secrets() {
+        let mnemonic = Wallet::generate(&mut StdRng::seed_from_u64(0))
+            .mnemonic()
+            .to_string();
+        let wallet =
+            Wallet::from_mnemonic(mnemonic.parse().unwrap(), "secret passphrase", 2).unwrap();
+        let debug = format!("{:?}", wallet);
+        assert_eq!(debug, "Wallet { <elided secrets>, num_accounts: 2 }");
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Password-encrypted wallet files.
+//!
+//! The mnemonic, passphrase and account count are BCS-encoded and sealed with AES-256-GCM under a
+//! key stretched from the password with PBKDF2-HMAC-SHA256. The file itself is JSON, with every
+//! binary field hex-encoded, so that it can be inspected and versioned by operators.
+
+use crate::wallet::{Mnemonic, Wallet};
+use aes_gcm::{
+    aead::{generic_array::GenericArray, Aead, NewAead},
+    Aes256Gcm,
+};
+use anyhow::{ensure, format_err, Result};
+use hmac::Hmac;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{fs, path::Path, str::FromStr};
+
+const WALLET_FILE_VERSION: u8 = 1;
+const DEFAULT_KDF_ITERATIONS: u32 = 100_000;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WalletFile {
+    version: u8,
+    kdf_iterations: u32,
+    kdf_salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct WalletContents {
+    mnemonic: String,
+    passphrase: String,
+    num_accounts: u64,
+}
+
+impl WalletFile {
+    /// Encrypts `wallet` under `password`.
+    pub fn encrypt<R>(rng: &mut R, wallet: &Wallet, password: &str) -> Result<Self>
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        Self::encrypt_with_iterations(rng, wallet, password, DEFAULT_KDF_ITERATIONS)
+    }
+
+    fn encrypt_with_iterations<R>(
+        rng: &mut R,
+        wallet: &Wallet,
+        password: &str,
+        kdf_iterations: u32,
+    ) -> Result<Self>
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let mut kdf_salt = [0u8; SALT_LENGTH];
+        rng.fill_bytes(&mut kdf_salt);
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rng.fill_bytes(&mut nonce);
+
+        let contents = bcs::to_bytes(&WalletContents {
+            mnemonic: wallet.mnemonic().to_string(),
+            passphrase: wallet.passphrase().to_owned(),
+            num_accounts: wallet.num_accounts(),
+        })?;
+        let ciphertext = cipher(password, &kdf_salt, kdf_iterations)
+            .encrypt(GenericArray::from_slice(&nonce), contents.as_slice())
+            .map_err(|_| format_err!("failed to encrypt wallet"))?;
+
+        Ok(Self {
+            version: WALLET_FILE_VERSION,
+            kdf_iterations,
+            kdf_salt: hex::encode(kdf_salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Decrypts the wallet. Fails on a wrong password or a tampered file.
+    pub fn decrypt(&self, password: &str) -> Result<Wallet> {
+        ensure!(
+            self.version == WALLET_FILE_VERSION,
+            "unsupported wallet file version {}",
+            self.version
+        );
+        let kdf_salt = hex::decode(&self.kdf_salt)?;
+        let nonce = hex::decode(&self.nonce)?;
+        ensure!(nonce.len() == NONCE_LENGTH, "invalid nonce length");
+        let ciphertext = hex::decode(&self.ciphertext)?;
+
+        let contents = cipher(password, &kdf_salt, self.kdf_iterations)
+            .decrypt(GenericArray::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| format_err!("failed to decrypt wallet: wrong password"))?;
+        let contents: WalletContents = bcs::from_bytes(&contents)?;
+        Wallet::from_mnemonic(
+            Mnemonic::from_str(&contents.mnemonic)?,
+            &contents.passphrase,
+            contents.num_accounts,
+        )
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+fn cipher(password: &str, kdf_salt: &[u8], kdf_iterations: u32) -> Aes256Gcm {
+    let mut key = [0u8; KEY_LENGTH];
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(password.as_bytes(), kdf_salt, kdf_iterations, &mut key);
+    Aes256Gcm::new(GenericArray::from_slice(&key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_wallet_file_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut wallet = Wallet::generate(&mut rng);
+        let addresses: Vec<_> = (0..3)
+            .map(|_| wallet.new_account().unwrap().address())
+            .collect();
+
+        let file = WalletFile::encrypt_with_iterations(&mut rng, &wallet, "password", 10).unwrap();
+        let file: WalletFile = serde_json::from_slice(&serde_json::to_vec(&file).unwrap()).unwrap();
+        assert!(file.decrypt("wrong password").is_err());
+
+        let recovered = file.decrypt("password").unwrap();
+        assert_eq!(recovered.mnemonic(), wallet.mnemonic());
+        assert_eq!(
+            recovered
+                .accounts()
+                .unwrap()
+                .iter()
+                .map(|account| account.address())
+                .collect::<Vec<_>>(),
+            addresses
+        );
+    }
+}
@@ -12,15 +12,15 @@ use futures::channel::mpsc;
 use mango_config::{config::NodeConfig, network_id::NodeNetworkId};
 use mango_types::waypoint::Waypoint;
 use mempool_notifications::MempoolNotificationSender;
-use std::{boxed::Box, collections::HashMap, sync::Arc};
-use storage_interface::DbReader;
+use std::{boxed::Box, collections::HashMap};
+use storage_interface::DbReaderWriter;
 use subscription_service::ReconfigSubscription;
-use tokio::runtime::{Builder, Runtime};
+use tokio::runtime::{Builder, Handle, Runtime};
 
 /// Creates and bootstraps new state syncs and creates clients for
 /// communicating with those state syncs.
 pub struct StateSyncBootstrapper {
-    _runtime: Runtime,
+    _runtime: Option<Runtime>,
     coordinator_sender: mpsc::UnboundedSender<CoordinatorMessage>,
 }
 
@@ -29,7 +29,7 @@ impl StateSyncBootstrapper {
         network: Vec<(NodeNetworkId, StateSyncSender, StateSyncEvents)>,
         mempool_notifier: M,
         consensus_listener: ConsensusNotificationListener,
-        storage: Arc<dyn DbReader>,
+        storage: DbReaderWriter,
         executor: Box<dyn ChunkExecutor>,
         node_config: &NodeConfig,
         waypoint: Waypoint,
@@ -53,6 +53,34 @@ impl StateSyncBootstrapper {
         )
     }
 
+    /// Same as `bootstrap`, but state sync is spawned on `handle` instead of a runtime of its own.
+    pub fn bootstrap_on<M: MempoolNotificationSender + 'static>(
+        handle: &Handle,
+        network: Vec<(NodeNetworkId, StateSyncSender, StateSyncEvents)>,
+        mempool_notifier: M,
+        consensus_listener: ConsensusNotificationListener,
+        storage: DbReaderWriter,
+        executor: Box<dyn ChunkExecutor>,
+        node_config: &NodeConfig,
+        waypoint: Waypoint,
+        reconfig_event_subscriptions: Vec<ReconfigSubscription>,
+    ) -> Self {
+        let executor_proxy = ExecutorProxy::new(storage, executor, reconfig_event_subscriptions);
+        let coordinator_sender = Self::start_coordinator(
+            handle,
+            network,
+            mempool_notifier,
+            consensus_listener,
+            node_config,
+            waypoint,
+            executor_proxy,
+        );
+        Self {
+            _runtime: None,
+            coordinator_sender,
+        }
+    }
+
     pub fn bootstrap_with_executor_proxy<
         E: ExecutorProxyTrait + 'static,
         M: MempoolNotificationSender + 'static,
@@ -65,6 +93,33 @@ impl StateSyncBootstrapper {
         waypoint: Waypoint,
         executor_proxy: E,
     ) -> Self {
+        let coordinator_sender = Self::start_coordinator(
+            runtime.handle(),
+            network,
+            mempool_notifier,
+            consensus_listener,
+            node_config,
+            waypoint,
+            executor_proxy,
+        );
+        Self {
+            _runtime: Some(runtime),
+            coordinator_sender,
+        }
+    }
+
+    fn start_coordinator<
+        E: ExecutorProxyTrait + 'static,
+        M: MempoolNotificationSender + 'static,
+    >(
+        handle: &Handle,
+        network: Vec<(NodeNetworkId, StateSyncSender, StateSyncEvents)>,
+        mempool_notifier: M,
+        consensus_listener: ConsensusNotificationListener,
+        node_config: &NodeConfig,
+        waypoint: Waypoint,
+        executor_proxy: E,
+    ) -> mpsc::UnboundedSender<CoordinatorMessage> {
         let (coordinator_sender, coordinator_receiver) = mpsc::unbounded();
         let initial_state = executor_proxy
             .get_local_storage_state()
@@ -85,12 +140,8 @@ impl StateSyncBootstrapper {
             initial_state,
         )
         .expect("[State Sync] Unable to create state sync coordinator!");
-        runtime.spawn(coordinator.start(network));
-
-        Self {
-            _runtime: runtime,
-            coordinator_sender,
-        }
+        handle.spawn(coordinator.start(network));
+        coordinator_sender
     }
 
     pub fn create_client(&self) -> StateSyncClient {
@@ -3,7 +3,7 @@
 
 use mango_types::{
     ledger_info::LedgerInfoWithSignatures,
-    transaction::{TransactionListWithProof, Version},
+    transaction::{Transaction, TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -52,6 +52,36 @@ impl ResponseLedgerInfo {
     }
 }
 
+impl fmt::Display for ResponseLedgerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResponseLedgerInfo::VerifiableLedgerInfo(li) => {
+                write!(f, "[verifiable LI {}]", li.ledger_info())
+            }
+            ResponseLedgerInfo::ProgressiveLedgerInfo {
+                target_li,
+                highest_li,
+            } => write!(
+                f,
+                "[progressive LI: target LI {}, highest LI {}]",
+                target_li.ledger_info(),
+                highest_li.as_ref().unwrap_or(target_li).ledger_info(),
+            ),
+            ResponseLedgerInfo::LedgerInfoForWaypoint {
+                waypoint_li,
+                end_of_epoch_li,
+            } => write!(
+                f,
+                "[waypoint LI {}, end of epoch LI {}]",
+                waypoint_li.ledger_info(),
+                end_of_epoch_li
+                    .as_ref()
+                    .map_or("None".to_string(), |li| li.ledger_info().to_string())
+            ),
+        }
+    }
+}
+
 /// The returned chunk is bounded by the end of the known_epoch of the requester
 /// (i.e., a chunk never crosses epoch boundaries).
 #[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
@@ -83,44 +113,136 @@ impl fmt::Debug for GetChunkResponse {
 
 impl fmt::Display for GetChunkResponse {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let txns_repr = match self.txn_list_with_proof.first_transaction_version {
-            None => "empty".to_string(),
-            Some(first_version) => {
-                let last_version = first_version
-                    .checked_add(self.txn_list_with_proof.len() as u64)
-                    .and_then(|v| v.checked_sub(1)) // last_version = first_version + txns.len() - 1
-                    .map(|v| v.to_string())
-                    .unwrap_or_else(|| "Last version has overflown!".into());
-                format!("versions [{} - {}]", first_version, last_version)
-            }
-        };
-        let response_li_repr = match &self.response_li {
-            ResponseLedgerInfo::VerifiableLedgerInfo(li) => {
-                format!("[verifiable LI {}]", li.ledger_info())
-            }
-            ResponseLedgerInfo::ProgressiveLedgerInfo {
-                target_li,
-                highest_li,
-            } => format!(
-                "[progressive LI: target LI {}, highest LI {}]",
-                target_li.ledger_info(),
-                highest_li.as_ref().unwrap_or(target_li).ledger_info(),
+        write!(
+            f,
+            "[ChunkResponse: response li: {}, txns: {}]",
+            self.response_li,
+            versions_repr(
+                self.txn_list_with_proof.first_transaction_version,
+                self.txn_list_with_proof.len()
             ),
-            ResponseLedgerInfo::LedgerInfoForWaypoint {
-                waypoint_li,
-                end_of_epoch_li,
-            } => format!(
-                "[waypoint LI {}, end of epoch LI {}]",
-                waypoint_li.ledger_info(),
-                end_of_epoch_li
-                    .as_ref()
-                    .map_or("None".to_string(), |li| li.ledger_info().to_string())
+        )
+    }
+}
+
+/// The transaction output counterpart of `GetChunkResponse`: the outputs are applied by the
+/// requester instead of re-executing the transactions.
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GetTransactionOutputChunkResponse {
+    /// The proofs are built relative to the LedgerInfo in `response_li`.
+    /// The specifics of ledger info verification depend on its type.
+    pub response_li: ResponseLedgerInfo,
+    /// Chunk of transactions and their outputs with proof corresponding to the ledger info
+    /// carried by the response.
+    pub output_list_with_proof: TransactionOutputListWithProof,
+}
+
+impl GetTransactionOutputChunkResponse {
+    pub fn new(
+        response_li: ResponseLedgerInfo,
+        output_list_with_proof: TransactionOutputListWithProof,
+    ) -> Self {
+        Self {
+            response_li,
+            output_list_with_proof,
+        }
+    }
+}
+
+impl fmt::Debug for GetTransactionOutputChunkResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetTransactionOutputChunkResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[TransactionOutputChunkResponse: response li: {}, outputs: {}]",
+            self.response_li,
+            versions_repr(
+                self.output_list_with_proof.first_transaction_output_version,
+                self.output_list_with_proof.len()
             ),
+        )
+    }
+}
+
+/// The chunk carried by a chunk response: either transactions to execute or transaction
+/// outputs to apply.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ResponseChunk {
+    Transactions(TransactionListWithProof),
+    TransactionOutputs(TransactionOutputListWithProof),
+}
+
+impl ResponseChunk {
+    /// The version of the first transaction in the chunk (if it's not empty).
+    pub fn first_version(&self) -> Option<Version> {
+        match self {
+            ResponseChunk::Transactions(txn_list_with_proof) => {
+                txn_list_with_proof.first_transaction_version
+            }
+            ResponseChunk::TransactionOutputs(output_list_with_proof) => {
+                output_list_with_proof.first_transaction_output_version
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            ResponseChunk::Transactions(txn_list_with_proof) => txn_list_with_proof.len(),
+            ResponseChunk::TransactionOutputs(output_list_with_proof) => {
+                output_list_with_proof.len()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The transactions in the chunk.
+    pub fn transactions(&self) -> Vec<Transaction> {
+        match self {
+            ResponseChunk::Transactions(txn_list_with_proof) => {
+                txn_list_with_proof.transactions.clone()
+            }
+            ResponseChunk::TransactionOutputs(output_list_with_proof) => output_list_with_proof
+                .transactions_and_outputs
+                .iter()
+                .map(|(txn, _)| txn.clone())
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ResponseChunk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chunk_type = match self {
+            ResponseChunk::Transactions(_) => "txns",
+            ResponseChunk::TransactionOutputs(_) => "outputs",
         };
         write!(
             f,
-            "[ChunkResponse: response li: {}, txns: {}]",
-            response_li_repr, txns_repr,
+            "{}: {}",
+            chunk_type,
+            versions_repr(self.first_version(), self.len())
         )
     }
 }
+
+fn versions_repr(first_version: Option<Version>, len: usize) -> String {
+    match first_version {
+        None => "empty".to_string(),
+        Some(first_version) => {
+            let last_version = first_version
+                .checked_add(len as u64)
+                .and_then(|v| v.checked_sub(1)) // last_version = first_version + len - 1
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "Last version has overflown!".into());
+            format!("versions [{} - {}]", first_version, last_version)
+        }
+    }
+}
@@ -3,7 +3,9 @@
 
 use crate::{
     chunk_request::{GetChunkRequest, TargetType},
-    chunk_response::{GetChunkResponse, ResponseLedgerInfo},
+    chunk_response::{
+        GetChunkResponse, GetTransactionOutputChunkResponse, ResponseChunk, ResponseLedgerInfo,
+    },
     client::CoordinatorMessage,
     counters,
     error::Error,
@@ -12,6 +14,10 @@ use crate::{
     network::{StateSyncEvents, StateSyncMessage, StateSyncSender},
     request_manager::RequestManager,
     shared_components::SyncState,
+    state_snapshot::{
+        GetEpochEndingLedgerInfosRequest, GetEpochEndingLedgerInfosResponse,
+        GetStateSnapshotRequest, GetStateSnapshotResponse,
+    },
 };
 use consensus_notifications::{
     ConsensusCommitNotification, ConsensusNotification, ConsensusNotificationListener,
@@ -25,12 +31,18 @@ use futures::{
     StreamExt,
 };
 use mango_config::{
-    config::{NodeConfig, PeerNetworkId, RoleType, StateSyncConfig},
+    config::{
+        BootstrappingMode, ContinuousSyncingMode, NodeConfig, PeerNetworkId, RoleType,
+        StateSyncConfig,
+    },
     network_id::NodeNetworkId,
 };
+use mango_crypto::{hash::SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue};
 use mango_logger::prelude::*;
 use mango_types::{
     contract_event::ContractEvent,
+    epoch_change::Verifier,
+    epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{Transaction, TransactionListWithProof, Version},
     waypoint::Waypoint,
@@ -53,6 +65,42 @@ struct PendingRequestInfo {
     request_epoch: u64,
     target_li: Option<LedgerInfoWithSignatures>,
     chunk_limit: u64,
+    syncing_mode: ContinuousSyncingMode,
+}
+
+/// Progress of a full node bootstrapping from a state snapshot (see
+/// `BootstrappingMode::FastSyncStateSnapshot`). The epoch ending ledger infos are fetched and
+/// verified first, the last one becomes the target, and the accounts at the target version are
+/// then restored chunk by chunk.
+struct FastSync {
+    // The epoch state used to verify the next epoch ending ledger infos
+    epoch_state: EpochState,
+    // The epoch ending ledger infos verified so far
+    epoch_ending_lis: Vec<LedgerInfoWithSignatures>,
+    // The ledger info at the snapshot version, once all epoch ending ledger infos are fetched
+    target_li: Option<LedgerInfoWithSignatures>,
+    // The transaction at the snapshot version, once its proof has been verified
+    txn_list_with_proof: Option<TransactionListWithProof>,
+    // The key of the last restored account
+    last_key: Option<HashValue>,
+    // The number of restored accounts
+    num_accounts: u64,
+    // The time the last request was sent (if any is outstanding)
+    last_request_time: Option<SystemTime>,
+}
+
+impl FastSync {
+    fn new(epoch_state: EpochState) -> Self {
+        Self {
+            epoch_state,
+            epoch_ending_lis: vec![],
+            target_li: None,
+            txn_list_with_proof: None,
+            last_key: None,
+            num_accounts: 0,
+            last_request_time: None,
+        }
+    }
 }
 
 /// A sync request for a specified target ledger info.
@@ -102,6 +150,12 @@ pub(crate) struct StateSyncCoordinator<T, M> {
     // queue of incoming long polling requests
     // peer will be notified about new chunk of transactions if it's available before expiry time
     subscriptions: HashMap<PeerNetworkId, PendingRequestInfo>,
+    // Progress of bootstrapping from a state snapshot, if this node is doing so
+    fast_sync: Option<FastSync>,
+    // Whether the next chunk request should fetch transactions even if configured to apply
+    // transaction outputs (e.g., because the previous request timed out, which happens with peers
+    // that can't serve the outputs).
+    execute_next_chunk_request: bool,
     executor_proxy: T,
 }
 
@@ -137,6 +191,21 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
             network_senders,
         );
 
+        // Only full nodes with nothing but genesis in storage bootstrap from a state snapshot.
+        let fast_sync = if node_config.state_sync.bootstrapping_mode
+            == BootstrappingMode::FastSyncStateSnapshot
+            && role == RoleType::FullNode
+            && initial_state.synced_version() == 0
+        {
+            info!(
+                LogSchema::new(LogEntry::FastSync),
+                "Bootstrapping from a state snapshot"
+            );
+            Some(FastSync::new(initial_state.trusted_epoch_state().clone()))
+        } else {
+            None
+        };
+
         Ok(Self {
             client_events,
             mempool_notifier,
@@ -150,6 +219,8 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
             sync_request: None,
             target_ledger_info: None,
             initialization_listener: None,
+            fast_sync,
+            execute_next_chunk_request: false,
             executor_proxy,
         })
     }
@@ -269,45 +340,40 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
     ) -> Result<(), Error> {
         let peer = PeerNetworkId(network_id, peer_id);
         match msg {
-            StateSyncMessage::GetChunkRequest(request) => {
-                // Time request handling
+            StateSyncMessage::GetChunkRequest(request) => self.process_chunk_request_message(
+                peer,
+                *request,
+                ContinuousSyncingMode::ExecuteTransactions,
+            ),
+            StateSyncMessage::GetTransactionOutputChunkRequest(request) => self
+                .process_chunk_request_message(
+                    peer,
+                    *request,
+                    ContinuousSyncingMode::ApplyTransactionOutputs,
+                ),
+            StateSyncMessage::GetChunkResponse(response) => {
+                // Time response handling
                 let _timer = counters::PROCESS_MSG_LATENCY
                     .with_label_values(&[
                         &peer.raw_network_id().to_string(),
                         &peer.peer_id().to_string(),
-                        counters::CHUNK_REQUEST_MSG_LABEL,
+                        counters::CHUNK_RESPONSE_MSG_LABEL,
                     ])
                     .start_timer();
 
-                // Process chunk request
-                let process_result = self.process_chunk_request(peer.clone(), *request.clone());
-                if let Err(ref error) = process_result {
-                    error!(
-                        LogSchema::event_log(LogEntry::ProcessChunkRequest, LogEvent::Fail)
-                            .peer(&peer)
-                            .error(&error.clone())
-                            .local_li_version(self.local_state.committed_version())
-                            .chunk_request(*request)
-                    );
-                    counters::PROCESS_CHUNK_REQUEST_COUNT
-                        .with_label_values(&[
-                            &peer.raw_network_id().to_string(),
-                            &peer.peer_id().to_string(),
-                            counters::FAIL_LABEL,
-                        ])
-                        .inc();
-                } else {
-                    counters::PROCESS_CHUNK_REQUEST_COUNT
-                        .with_label_values(&[
-                            &peer.raw_network_id().to_string(),
-                            &peer.peer_id().to_string(),
-                            counters::SUCCESS_LABEL,
-                        ])
-                        .inc();
-                }
-                process_result
+                // Process chunk response
+                let GetChunkResponse {
+                    response_li,
+                    txn_list_with_proof,
+                } = *response;
+                self.process_chunk_response(
+                    &peer,
+                    response_li,
+                    ResponseChunk::Transactions(txn_list_with_proof),
+                )
+                .await
             }
-            StateSyncMessage::GetChunkResponse(response) => {
+            StateSyncMessage::GetTransactionOutputChunkResponse(response) => {
                 // Time response handling
                 let _timer = counters::PROCESS_MSG_LATENCY
                     .with_label_values(&[
@@ -318,11 +384,112 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
                     .start_timer();
 
                 // Process chunk response
-                self.process_chunk_response(&peer, *response).await
+                let GetTransactionOutputChunkResponse {
+                    response_li,
+                    output_list_with_proof,
+                } = *response;
+                self.process_chunk_response(
+                    &peer,
+                    response_li,
+                    ResponseChunk::TransactionOutputs(output_list_with_proof),
+                )
+                .await
+            }
+            StateSyncMessage::GetEpochEndingLedgerInfosRequest(request) => {
+                let _timer = counters::PROCESS_MSG_LATENCY
+                    .with_label_values(&[
+                        &peer.raw_network_id().to_string(),
+                        &peer.peer_id().to_string(),
+                        counters::EPOCH_ENDING_REQUEST_MSG_LABEL,
+                    ])
+                    .start_timer();
+                let process_result =
+                    self.process_epoch_ending_ledger_infos_request(&peer, *request);
+                self.update_state_snapshot_request_counters(&peer, &process_result);
+                process_result
+            }
+            StateSyncMessage::GetEpochEndingLedgerInfosResponse(response) => {
+                let _timer = counters::PROCESS_MSG_LATENCY
+                    .with_label_values(&[
+                        &peer.raw_network_id().to_string(),
+                        &peer.peer_id().to_string(),
+                        counters::EPOCH_ENDING_RESPONSE_MSG_LABEL,
+                    ])
+                    .start_timer();
+                self.process_epoch_ending_ledger_infos_response(&peer, *response)
+            }
+            StateSyncMessage::GetStateSnapshotRequest(request) => {
+                let _timer = counters::PROCESS_MSG_LATENCY
+                    .with_label_values(&[
+                        &peer.raw_network_id().to_string(),
+                        &peer.peer_id().to_string(),
+                        counters::STATE_SNAPSHOT_REQUEST_MSG_LABEL,
+                    ])
+                    .start_timer();
+                let process_result = self.process_state_snapshot_request(&peer, *request);
+                self.update_state_snapshot_request_counters(&peer, &process_result);
+                process_result
+            }
+            StateSyncMessage::GetStateSnapshotResponse(response) => {
+                let _timer = counters::PROCESS_MSG_LATENCY
+                    .with_label_values(&[
+                        &peer.raw_network_id().to_string(),
+                        &peer.peer_id().to_string(),
+                        counters::STATE_SNAPSHOT_RESPONSE_MSG_LABEL,
+                    ])
+                    .start_timer();
+                self.process_state_snapshot_response(&peer, *response).await
             }
         }
     }
 
+    /// Processes a chunk request (timing it and updating the counters), responding with
+    /// transactions or transaction outputs according to the requester's `syncing_mode`.
+    fn process_chunk_request_message(
+        &mut self,
+        peer: PeerNetworkId,
+        request: GetChunkRequest,
+        syncing_mode: ContinuousSyncingMode,
+    ) -> Result<(), Error> {
+        // Time request handling
+        let _timer = counters::PROCESS_MSG_LATENCY
+            .with_label_values(&[
+                &peer.raw_network_id().to_string(),
+                &peer.peer_id().to_string(),
+                counters::CHUNK_REQUEST_MSG_LABEL,
+            ])
+            .start_timer();
+
+        // Process chunk request
+        let process_result =
+            self.process_chunk_request(peer.clone(), request.clone(), syncing_mode);
+        if let Err(ref error) = process_result {
+            error!(
+                LogSchema::event_log(LogEntry::ProcessChunkRequest, LogEvent::Fail)
+                    .peer(&peer)
+                    .error(&error.clone())
+                    .local_li_version(self.local_state.committed_version())
+                    .chunk_request(request)
+            );
+            counters::PROCESS_CHUNK_REQUEST_COUNT
+                .with_label_values(&[
+                    &peer.raw_network_id().to_string(),
+                    &peer.peer_id().to_string(),
+                    counters::FAIL_LABEL,
+                ])
+                .inc();
+        } else {
+            counters::PROCESS_CHUNK_REQUEST_COUNT
+                .with_label_values(&[
+                    &peer.raw_network_id().to_string(),
+                    &peer.peer_id().to_string(),
+                    counters::SUCCESS_LABEL,
+                ])
+                .inc();
+        }
+        process_result
+    }
+
     /// Sync up coordinator state with the local storage
     /// and updates the pending ledger info accordingly
     fn sync_state_with_local_storage(&mut self) -> Result<(), Error> {
@@ -650,10 +817,12 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
     /// There are two types of ChunkRequests:
     /// 1) Validator chunk requests are for a specific target LI and don't ask for long polling.
     /// 2) FullNode chunk requests don't specify a target LI and can allow long polling.
+    /// Either type can ask for transaction outputs instead of transactions (see `syncing_mode`).
     fn process_chunk_request(
         &mut self,
         peer: PeerNetworkId,
         request: GetChunkRequest,
+        syncing_mode: ContinuousSyncingMode,
     ) -> Result<(), Error> {
         debug!(
             LogSchema::event_log(LogEntry::ProcessChunkRequest, LogEvent::Received)
@@ -676,9 +845,13 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
         }
 
         match request.target.clone() {
-            TargetType::TargetLedgerInfo(li) => {
-                self.process_request_for_target_and_highest(peer, request, Some(li), None)
-            }
+            TargetType::TargetLedgerInfo(li) => self.process_request_for_target_and_highest(
+                peer,
+                request,
+                Some(li),
+                None,
+                syncing_mode,
+            ),
             TargetType::HighestAvailable {
                 target_li,
                 timeout_ms,
@@ -687,9 +860,10 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
                 request,
                 target_li,
                 Some(timeout_ms),
+                syncing_mode,
             ),
             TargetType::Waypoint(waypoint_version) => {
-                self.process_request_for_waypoint(peer, request, waypoint_version)
+                self.process_request_for_waypoint(peer, request, waypoint_version, syncing_mode)
             }
         }
     }
@@ -745,6 +919,7 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
         request: GetChunkRequest,
         target_li: Option<LedgerInfoWithSignatures>,
         timeout_ms: Option<u64>,
+        syncing_mode: ContinuousSyncingMode,
     ) -> Result<(), Error> {
         let chunk_limit = std::cmp::min(request.limit, self.config.max_chunk_limit);
         let timeout = if let Some(timeout_ms) = timeout_ms {
@@ -765,6 +940,7 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
                     request_epoch: request.current_epoch,
                     target_li,
                     chunk_limit,
+                    syncing_mode,
                 };
                 self.subscriptions.insert(peer, request_info);
             }
@@ -782,6 +958,7 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
                 highest_li,
             },
             chunk_limit,
+            syncing_mode,
         )
     }
 
@@ -812,6 +989,7 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
         peer: PeerNetworkId,
         request: GetChunkRequest,
         waypoint_version: Version,
+        syncing_mode: ContinuousSyncingMode,
     ) -> Result<(), Error> {
         let mut limit = std::cmp::min(request.limit, self.config.max_chunk_limit);
         if self.local_state.committed_version() < waypoint_version {
@@ -860,12 +1038,13 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
                 end_of_epoch_li,
             },
             limit,
+            syncing_mode,
         )
     }
 
     /// Generate and send the ChunkResponse to the given peer.
-    /// The chunk response contains transactions from the local storage with the proofs relative to
-    /// the given target ledger info.
+    /// The chunk response contains transactions (or their outputs, depending on `syncing_mode`)
+    /// from the local storage with the proofs relative to the given target ledger info.
     /// In case target is None, the ledger info is set to the local highest ledger info.
     fn deliver_chunk(
         &mut self,
@@ -873,15 +1052,60 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
         known_version: u64,
         response_li: ResponseLedgerInfo,
         limit: u64,
+        syncing_mode: ContinuousSyncingMode,
     ) -> Result<(), Error> {
-        let txns = self
-            .executor_proxy
-            .get_chunk(known_version, limit, response_li.version())?;
-        let chunk_response = GetChunkResponse::new(response_li, txns);
+        let chunk = match syncing_mode {
+            ContinuousSyncingMode::ExecuteTransactions => ResponseChunk::Transactions(
+                self.executor_proxy
+                    .get_chunk(known_version, limit, response_li.version())?,
+            ),
+            ContinuousSyncingMode::ApplyTransactionOutputs => {
+                match self.executor_proxy.get_transaction_output_chunk(
+                    known_version,
+                    limit,
+                    response_li.version(),
+                ) {
+                    Ok(output_list_with_proof) => {
+                        ResponseChunk::TransactionOutputs(output_list_with_proof)
+                    }
+                    Err(error) => {
+                        // The write sets aren't stored (or have been pruned), so the requester
+                        // has to execute the transactions instead.
+                        warn!(LogSchema::event_log(
+                            LogEntry::ProcessChunkRequest,
+                            LogEvent::OutputsUnavailable
+                        )
+                        .peer(&peer)
+                        .error(&error));
+                        ResponseChunk::Transactions(self.executor_proxy.get_chunk(
+                            known_version,
+                            limit,
+                            response_li.version(),
+                        )?)
+                    }
+                }
+            }
+        };
         let log = LogSchema::event_log(LogEntry::ProcessChunkRequest, LogEvent::DeliverChunk)
-            .chunk_response(chunk_response.clone())
+            .response_li(&response_li)
+            .response_chunk(&chunk)
             .peer(&peer);
-        let msg = StateSyncMessage::GetChunkResponse(Box::new(chunk_response));
+        let msg = match chunk.clone() {
+            ResponseChunk::Transactions(txn_list_with_proof) => {
+                StateSyncMessage::GetChunkResponse(Box::new(GetChunkResponse::new(
+                    response_li.clone(),
+                    txn_list_with_proof,
+                )))
+            }
+            ResponseChunk::TransactionOutputs(output_list_with_proof) => {
+                StateSyncMessage::GetTransactionOutputChunkResponse(Box::new(
+                    GetTransactionOutputChunkResponse::new(
+                        response_li.clone(),
+                        output_list_with_proof,
+                    ),
+                ))
+            }
+        };
         let send_result = self.request_manager.send_chunk_response(&peer, msg);
         let send_result_label = if send_result.is_err() {
             counters::SEND_FAIL_LABEL
@@ -932,15 +1156,18 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
         Ok(target_li)
     }
 
-    /// Applies (i.e., executes and stores) the chunk to storage iff `response` is valid.
+    /// Applies (i.e., executes or applies the outputs, and stores) the chunk to storage iff the
+    /// response is valid.
     fn apply_chunk(
         &mut self,
         peer: &PeerNetworkId,
-        response: GetChunkResponse,
+        response_li: ResponseLedgerInfo,
+        chunk: ResponseChunk,
     ) -> Result<(), Error> {
         debug!(
             LogSchema::event_log(LogEntry::ProcessChunkResponse, LogEvent::Received)
-                .chunk_response(response.clone())
+                .response_li(&response_li)
+                .response_chunk(&chunk)
                 .peer(peer)
         );
         fail_point!("state_sync_v1::apply_chunk", |_| {
@@ -950,32 +1177,23 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
         });
 
         // Process the chunk based on the response type
-        let txn_list_with_proof = response.txn_list_with_proof.clone();
-        let chunk_size = response.txn_list_with_proof.len() as u64;
+        let chunk_size = chunk.len() as u64;
         let known_version = self.local_state.synced_version();
-        match response.response_li {
+        match response_li {
             ResponseLedgerInfo::VerifiableLedgerInfo(li) => {
-                self.process_response_with_target_and_highest(txn_list_with_proof, li, None)
+                self.process_response_with_target_and_highest(chunk, li, None)
             }
             ResponseLedgerInfo::ProgressiveLedgerInfo {
                 target_li,
                 highest_li,
             } => {
                 let highest_li = highest_li.unwrap_or_else(|| target_li.clone());
-                self.process_response_with_target_and_highest(
-                    txn_list_with_proof,
-                    target_li,
-                    Some(highest_li),
-                )
+                self.process_response_with_target_and_highest(chunk, target_li, Some(highest_li))
             }
             ResponseLedgerInfo::LedgerInfoForWaypoint {
                 waypoint_li,
                 end_of_epoch_li,
-            } => self.process_response_with_waypoint_li(
-                txn_list_with_proof,
-                waypoint_li,
-                end_of_epoch_li,
-            ),
+            } => self.process_response_with_waypoint_li(chunk, waypoint_li, end_of_epoch_li),
         }
         .map_err(|error| {
             self.request_manager.process_invalid_chunk(peer);
@@ -1026,7 +1244,8 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
     async fn process_chunk_response(
         &mut self,
         peer: &PeerNetworkId,
-        response: GetChunkResponse,
+        response_li: ResponseLedgerInfo,
+        chunk: ResponseChunk,
     ) -> Result<(), Error> {
         // Ensure consensus isn't running, otherwise we might get a race with storage writes.
         if self.is_consensus_executing() {
@@ -1038,11 +1257,12 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
         }
 
         // Verify the chunk response is well formed before trying to process it.
-        self.verify_chunk_response_is_valid(peer, &response)?;
+        self.verify_chunk_response_is_valid(peer, &response_li, &chunk)?;
 
         // Validate the response and store the chunk if possible.
         // Any errors thrown here should be for detecting bad chunks.
-        match self.apply_chunk(peer, response.clone()) {
+        let transactions = chunk.transactions();
+        match self.apply_chunk(peer, response_li, chunk) {
             Ok(()) => {
                 counters::APPLY_CHUNK_COUNT
                     .with_label_values(&[
@@ -1071,27 +1291,24 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
         }
 
         // Process the newly committed chunk
-        self.process_commit_notification(
-            response.txn_list_with_proof.transactions.clone(),
-            vec![],
-            None,
-            Some(peer),
-        )
-        .await
-        .map_err(|error| {
-            error!(
-                LogSchema::event_log(LogEntry::ProcessChunkResponse, LogEvent::PostCommitFail)
-                    .peer(peer)
-                    .error(&error)
-            );
-            error
-        })
+        self.process_commit_notification(transactions, vec![], None, Some(peer))
+            .await
+            .map_err(|error| {
+                error!(LogSchema::event_log(
+                    LogEntry::ProcessChunkResponse,
+                    LogEvent::PostCommitFail
+                )
+                .peer(peer)
+                .error(&error));
+                error
+            })
     }
 
     fn verify_chunk_response_is_valid(
         &mut self,
         peer: &PeerNetworkId,
-        response: &GetChunkResponse,
+        response_li: &ResponseLedgerInfo,
+        chunk: &ResponseChunk,
     ) -> Result<(), Error> {
         // Verify response comes from known peer
         if !self.request_manager.is_known_state_sync_peer(peer) {
@@ -1106,7 +1323,7 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
         }
 
         // Verify the chunk is not empty and that it starts at the correct version
-        if let Some(first_chunk_version) = response.txn_list_with_proof.first_transaction_version {
+        if let Some(first_chunk_version) = chunk.first_version() {
             let known_version = self.local_state.synced_version();
             let expected_version = known_version
                 .checked_add(1)
@@ -1125,8 +1342,20 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
             return Err(Error::ReceivedEmptyChunk(peer.to_string()));
         }
 
+        // Verify the chunk only carries transaction outputs if we've opted in to applying them
+        // (i.e., outside of waypoint syncing)
+        if let ResponseChunk::TransactionOutputs(_) = chunk {
+            if self.config.continuous_syncing_mode != ContinuousSyncingMode::ApplyTransactionOutputs
+                || !self.is_initialized()
+            {
+                return Err(Error::ReceivedWrongChunkType(
+                    "Received transaction outputs, but we're not applying outputs!".into(),
+                ));
+            }
+        }
+
         // Verify the chunk has the expected type for the current syncing mode
-        match &response.response_li {
+        match response_li {
             ResponseLedgerInfo::LedgerInfoForWaypoint {
                 waypoint_li,
                 end_of_epoch_li,
@@ -1238,13 +1467,13 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
     /// we assume the next chunk will be for our current epoch.
     fn calculate_new_known_version_and_epoch(
         &mut self,
-        txn_list_with_proof: TransactionListWithProof,
+        chunk: &ResponseChunk,
         ledger_info: Option<LedgerInfoWithSignatures>,
     ) -> Result<(u64, u64), Error> {
         let new_version = self
             .local_state
             .synced_version()
-            .checked_add(txn_list_with_proof.len() as u64)
+            .checked_add(chunk.len() as u64)
             .ok_or_else(|| {
                 Error::IntegerOverflow("Potential state sync version has overflown".into())
             })?;
@@ -1308,16 +1537,14 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
     /// current local trusted validator set.
     fn process_response_with_target_and_highest(
         &mut self,
-        txn_list_with_proof: TransactionListWithProof,
+        chunk: ResponseChunk,
         response_li: LedgerInfoWithSignatures,
         new_highest_li: Option<LedgerInfoWithSignatures>,
     ) -> Result<(), Error> {
         // Optimistically calculate the new known version and epoch (assume the current chunk
         // is applied successfully).
-        let (known_version, known_epoch) = self.calculate_new_known_version_and_epoch(
-            txn_list_with_proof.clone(),
-            Some(response_li.clone()),
-        )?;
+        let (known_version, known_epoch) =
+            self.calculate_new_known_version_and_epoch(&chunk, Some(response_li.clone()))?;
 
         // Send the next chunk request based on the sync mode (sync request or highest available).
         if self.sync_request.is_some() {
@@ -1363,7 +1590,7 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
 
         // Validate and store the chunk
         self.log_highest_seen_version(new_highest_li.clone());
-        self.validate_and_store_chunk(txn_list_with_proof, response_li, None)?;
+        self.validate_and_store_chunk(chunk, response_li, None)?;
 
         // Need to sync with local storage to update synced version
         self.sync_state_with_local_storage()?;
@@ -1392,16 +1619,14 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
     /// Processing chunk responses that carry a LedgerInfo corresponding to the waypoint.
     fn process_response_with_waypoint_li(
         &mut self,
-        txn_list_with_proof: TransactionListWithProof,
+        chunk: ResponseChunk,
         waypoint_li: LedgerInfoWithSignatures,
         end_of_epoch_li: Option<LedgerInfoWithSignatures>,
     ) -> Result<(), Error> {
         // Optimistically calculate the new known version and epoch (assume the current chunk
         // is applied successfully).
-        let (known_version, known_epoch) = self.calculate_new_known_version_and_epoch(
-            txn_list_with_proof.clone(),
-            end_of_epoch_li.clone(),
-        )?;
+        let (known_version, known_epoch) =
+            self.calculate_new_known_version_and_epoch(&chunk, end_of_epoch_li.clone())?;
         if known_version < self.waypoint.version() {
             // Send the chunk request and log any errors. If errors are logged
             // continue processing the chunk.
@@ -1445,7 +1670,7 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
                 Error::UnexpectedError(format!("Waypoint verification failed: {}", error))
             })?;
 
-        self.validate_and_store_chunk(txn_list_with_proof, waypoint_li, end_of_epoch_li_to_commit)?;
+        self.validate_and_store_chunk(chunk, waypoint_li, end_of_epoch_li_to_commit)?;
         self.log_highest_seen_version(None);
 
         Ok(())
@@ -1454,7 +1679,7 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
     // Assumes that the target LI has been already verified by the caller.
     fn validate_and_store_chunk(
         &mut self,
-        txn_list_with_proof: TransactionListWithProof,
+        chunk: ResponseChunk,
         target: LedgerInfoWithSignatures,
         intermediate_end_of_epoch_li: Option<LedgerInfoWithSignatures>,
     ) -> Result<(), Error> {
@@ -1473,8 +1698,401 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
             return Ok(());
         }
 
-        self.executor_proxy
-            .execute_chunk(txn_list_with_proof, target, intermediate_end_of_epoch_li)
+        match chunk {
+            ResponseChunk::Transactions(txn_list_with_proof) => self.executor_proxy.execute_chunk(
+                txn_list_with_proof,
+                target,
+                intermediate_end_of_epoch_li,
+            ),
+            ResponseChunk::TransactionOutputs(output_list_with_proof) => self
+                .executor_proxy
+                .apply_chunk(output_list_with_proof, target, intermediate_end_of_epoch_li),
+        }
+    }
+
+    /// Updates the counters for a processed epoch ending ledger info or state snapshot request.
+    fn update_state_snapshot_request_counters(
+        &self,
+        peer: &PeerNetworkId,
+        process_result: &Result<(), Error>,
+    ) {
+        let result_label = if let Err(error) = process_result {
+            error!(
+                LogSchema::event_log(LogEntry::ProcessStateSnapshotRequest, LogEvent::Fail)
+                    .peer(peer)
+                    .error(error)
+            );
+            counters::FAIL_LABEL
+        } else {
+            counters::SUCCESS_LABEL
+        };
+        counters::PROCESS_STATE_SNAPSHOT_REQUEST_COUNT
+            .with_label_values(&[
+                &peer.raw_network_id().to_string(),
+                &peer.peer_id().to_string(),
+                result_label,
+            ])
+            .inc();
+    }
+
+    /// Responds with the epoch ending ledger infos from the requested epoch on.
+    fn process_epoch_ending_ledger_infos_request(
+        &mut self,
+        peer: &PeerNetworkId,
+        request: GetEpochEndingLedgerInfosRequest,
+    ) -> Result<(), Error> {
+        let proof = self
+            .executor_proxy
+            .get_epoch_ending_ledger_infos(request.start_epoch)?;
+        let response = GetEpochEndingLedgerInfosResponse::new(request.start_epoch, proof);
+        self.request_manager.send_chunk_response(
+            peer,
+            StateSyncMessage::GetEpochEndingLedgerInfosResponse(Box::new(response)),
+        )
+    }
+
+    /// Responds with the requested chunk of a state snapshot. The response to the first chunk
+    /// request also carries the transaction at the snapshot version.
+    fn process_state_snapshot_request(
+        &mut self,
+        peer: &PeerNetworkId,
+        request: GetStateSnapshotRequest,
+    ) -> Result<(), Error> {
+        if request.limit == 0 || request.limit > self.config.max_state_snapshot_chunk_limit {
+            self.request_manager.process_invalid_chunk_request(peer);
+            return Err(Error::InvalidChunkRequest(format!(
+                "State snapshot request limit is invalid: {}. Max limit: {}.",
+                request.limit, self.config.max_state_snapshot_chunk_limit
+            )));
+        }
+
+        let (account_blobs, proof) = self.executor_proxy.get_state_snapshot_chunk(
+            request.version,
+            request.last_key,
+            request.limit,
+        )?;
+        let txn_list_with_proof = if request.last_key.is_none() {
+            Some(
+                self.executor_proxy
+                    .get_state_snapshot_transaction(request.version)?,
+            )
+        } else {
+            None
+        };
+        let response = GetStateSnapshotResponse::new(
+            request.version,
+            request.last_key,
+            account_blobs,
+            proof,
+            txn_list_with_proof,
+        );
+        self.request_manager.send_chunk_response(
+            peer,
+            StateSyncMessage::GetStateSnapshotResponse(Box::new(response)),
+        )
+    }
+
+    /// Verifies the epoch ending ledger infos sent by a peer and picks the last one as the
+    /// state snapshot target once there are no more.
+    fn process_epoch_ending_ledger_infos_response(
+        &mut self,
+        peer: &PeerNetworkId,
+        response: GetEpochEndingLedgerInfosResponse,
+    ) -> Result<(), Error> {
+        let fast_sync = match self.fast_sync.as_mut() {
+            Some(fast_sync) if fast_sync.target_li.is_none() => fast_sync,
+            _ => {
+                return Err(Error::ReceivedWrongChunkType(
+                    "Received epoch ending ledger infos, but we're not fetching them!".into(),
+                ))
+            }
+        };
+        if response.start_epoch != fast_sync.epoch_state.epoch {
+            return Err(Error::ReceivedWrongChunkType(format!(
+                "Received epoch ending ledger infos starting at epoch {}, but expected epoch {}!",
+                response.start_epoch, fast_sync.epoch_state.epoch
+            )));
+        }
+
+        let proof = response.proof;
+        if !proof.ledger_info_with_sigs.is_empty() {
+            let epoch_state = &fast_sync.epoch_state;
+            let waypoint = &self.waypoint;
+            let verify_result = proof
+                .verify(epoch_state)
+                .map_err(|error| Error::ProcessInvalidChunk(error.to_string()))
+                .and_then(|_| {
+                    proof
+                        .ledger_info_with_sigs
+                        .iter()
+                        .filter(|li| li.ledger_info().version() == waypoint.version())
+                        .try_for_each(|li| {
+                            waypoint
+                                .verify(li.ledger_info())
+                                .map_err(|error| Error::ProcessInvalidChunk(error.to_string()))
+                        })
+                });
+            if let Err(error) = verify_result {
+                self.request_manager.process_invalid_chunk(peer);
+                return Err(error);
+            }
+
+            // Only keep the ledger infos that weren't stale (and so have been verified)
+            let new_lis: Vec<_> = proof
+                .ledger_info_with_sigs
+                .into_iter()
+                .skip_while(|li| fast_sync.epoch_state.is_ledger_info_stale(li.ledger_info()))
+                .collect();
+            if let Some(next_epoch_state) = new_lis
+                .last()
+                .and_then(|li| li.ledger_info().next_epoch_state())
+            {
+                fast_sync.epoch_state = next_epoch_state.clone();
+            }
+            info!(
+                LogSchema::event_log(LogEntry::FastSync, LogEvent::EpochEndingLedgerInfos)
+                    .peer(peer)
+                    .count(new_lis.len())
+                    .local_epoch(fast_sync.epoch_state.epoch)
+            );
+            fast_sync.epoch_ending_lis.extend(new_lis);
+        }
+        fast_sync.last_request_time = None;
+        self.request_manager.process_success_response(peer);
+
+        if proof.more {
+            return self.send_fast_sync_request();
+        }
+
+        // Pick the state snapshot target
+        let target_li = match fast_sync.epoch_ending_lis.last() {
+            Some(li) if li.ledger_info().version() > self.local_state.synced_version() => {
+                li.clone()
+            }
+            _ => {
+                info!(
+                    LogSchema::new(LogEntry::FastSync),
+                    "No state snapshot is newer than the local state, continuing with chunk sync"
+                );
+                self.fast_sync = None;
+                return self.check_progress();
+            }
+        };
+        if target_li.ledger_info().version() < self.waypoint.version() {
+            // The peer may be lagging behind: start over with the next response.
+            self.fast_sync = Some(FastSync::new(
+                self.local_state.trusted_epoch_state().clone(),
+            ));
+            return Err(Error::ProcessInvalidChunk(format!(
+                "The last epoch ending ledger info (version {}) is behind the waypoint (version {})!",
+                target_li.ledger_info().version(),
+                self.waypoint.version()
+            )));
+        }
+
+        let target_version = target_li.ledger_info().version();
+        info!(LogSchema::new(LogEntry::FastSync)
+            .target_version(target_version)
+            .ledger_info(target_li.clone()));
+        counters::STATE_SNAPSHOT_PROGRESS
+            .with_label_values(&["version"])
+            .set(target_version as i64);
+        fast_sync.target_li = Some(target_li);
+        self.send_fast_sync_request()
+    }
+
+    /// Restores a chunk of the state snapshot sent by a peer. Once the last chunk is restored,
+    /// the transaction at the snapshot version is committed and chunk sync takes over.
+    async fn process_state_snapshot_response(
+        &mut self,
+        peer: &PeerNetworkId,
+        response: GetStateSnapshotResponse,
+    ) -> Result<(), Error> {
+        let fast_sync = match self.fast_sync.as_mut() {
+            Some(fast_sync) if fast_sync.target_li.is_some() => fast_sync,
+            _ => {
+                return Err(Error::ReceivedWrongChunkType(
+                    "Received a state snapshot chunk, but we're not restoring a snapshot!".into(),
+                ))
+            }
+        };
+        let target_li = fast_sync
+            .target_li
+            .as_ref()
+            .expect("The target ledger info must exist!");
+        let target_version = target_li.ledger_info().version();
+        if response.version != target_version || response.last_key != fast_sync.last_key {
+            return Err(Error::ReceivedWrongChunkType(format!(
+                "Received a state snapshot chunk at version {} after key {:?}, but expected version {} after key {:?}!",
+                response.version, response.last_key, target_version, fast_sync.last_key
+            )));
+        }
+
+        if response.account_blobs.is_empty() {
+            self.request_manager.process_invalid_chunk(peer);
+            return Err(Error::ProcessInvalidChunk(
+                "Received an empty state snapshot chunk!".into(),
+            ));
+        }
+
+        // The first chunk carries the transaction proving the state root hash of the snapshot
+        if fast_sync.txn_list_with_proof.is_none() {
+            let verify_result = match response.txn_list_with_proof {
+                Some(txn_list_with_proof)
+                    if txn_list_with_proof.transactions.len() == 1
+                        && txn_list_with_proof.events.is_some() =>
+                {
+                    txn_list_with_proof
+                        .verify(target_li.ledger_info(), Some(target_version))
+                        .map(|()| txn_list_with_proof)
+                        .map_err(|error| Error::ProcessInvalidChunk(error.to_string()))
+                }
+                _ => Err(Error::ProcessInvalidChunk(
+                    "The first state snapshot chunk is missing the transaction at the snapshot version!".into(),
+                )),
+            };
+            let txn_list_with_proof = match verify_result {
+                Ok(txn_list_with_proof) => txn_list_with_proof,
+                Err(error) => {
+                    self.request_manager.process_invalid_chunk(peer);
+                    return Err(error);
+                }
+            };
+            let state_root_hash =
+                txn_list_with_proof.proof.transaction_infos()[0].state_root_hash();
+            self.executor_proxy
+                .start_state_snapshot_restore(target_version, state_root_hash)?;
+            fast_sync.txn_list_with_proof = Some(txn_list_with_proof);
+        }
+
+        // Restore the chunk. Once it is verified, the proof having no siblings on the right
+        // means there are no accounts after it.
+        let num_accounts = response.account_blobs.len();
+        let last_key = response.account_blobs.last().map(|(key, _)| *key);
+        let is_last_chunk = response
+            .proof
+            .right_siblings()
+            .iter()
+            .all(|sibling| *sibling == *SPARSE_MERKLE_PLACEHOLDER_HASH);
+        let apply_result = self
+            .executor_proxy
+            .add_state_snapshot_chunk(response.account_blobs, response.proof);
+        let result_label = if apply_result.is_ok() {
+            counters::SUCCESS_LABEL
+        } else {
+            counters::FAIL_LABEL
+        };
+        counters::APPLY_STATE_SNAPSHOT_CHUNK_COUNT
+            .with_label_values(&[
+                &peer.raw_network_id().to_string(),
+                &peer.peer_id().to_string(),
+                result_label,
+            ])
+            .inc();
+        if let Err(error) = apply_result {
+            error!(
+                LogSchema::event_log(LogEntry::FastSync, LogEvent::RestoreStateSnapshotChunk)
+                    .peer(peer)
+                    .error(&error)
+            );
+            self.request_manager.process_invalid_chunk(peer);
+            return Err(error);
+        }
+
+        fast_sync.last_key = last_key;
+        fast_sync.num_accounts += num_accounts as u64;
+        fast_sync.last_request_time = None;
+        counters::STATE_SNAPSHOT_PROGRESS
+            .with_label_values(&["accounts"])
+            .set(fast_sync.num_accounts as i64);
+        debug!(
+            LogSchema::event_log(LogEntry::FastSync, LogEvent::RestoreStateSnapshotChunk)
+                .peer(peer)
+                .version(target_version)
+                .count(num_accounts)
+        );
+        self.request_manager.process_success_response(peer);
+
+        if is_last_chunk {
+            self.finish_fast_sync().await
+        } else {
+            self.send_fast_sync_request()
+        }
+    }
+
+    /// Commits the restored state snapshot and hands over to chunk sync. If the commit fails,
+    /// the fast sync starts over.
+    async fn finish_fast_sync(&mut self) -> Result<(), Error> {
+        let fast_sync = match self.fast_sync.take() {
+            Some(fast_sync) => fast_sync,
+            None => return Ok(()),
+        };
+        let txn_list_with_proof = fast_sync.txn_list_with_proof.ok_or_else(|| {
+            Error::UnexpectedError("The state snapshot transaction is missing!".into())
+        })?;
+        if let Err(error) = self
+            .executor_proxy
+            .finish_state_snapshot_restore(txn_list_with_proof, fast_sync.epoch_ending_lis)
+        {
+            self.fast_sync = Some(FastSync::new(
+                self.local_state.trusted_epoch_state().clone(),
+            ));
+            return Err(error);
+        }
+
+        self.sync_state_with_local_storage()?;
+        let synced_version = self.local_state.synced_version();
+        info!(LogSchema::event_log(LogEntry::FastSync, LogEvent::Complete)
+            .version(synced_version)
+            .count(fast_sync.num_accounts as usize));
+        self.update_sync_state_metrics_and_logs()?;
+        self.check_initialized_or_sync_request_completed(synced_version)
+            .await?;
+        self.check_progress()
+    }
+
+    /// Resends the outstanding fast sync request if it hasn't been answered in time (or sends
+    /// the first one).
+    fn check_fast_sync_progress(&mut self) -> Result<(), Error> {
+        let last_request_time = self
+            .fast_sync
+            .as_ref()
+            .and_then(|fast_sync| fast_sync.last_request_time);
+        if let Some(last_request_time) = last_request_time {
+            let timeout = Duration::from_millis(self.config.state_snapshot_request_timeout_ms);
+            match SystemTime::now().duration_since(last_request_time) {
+                Ok(elapsed) if elapsed >= timeout => {
+                    counters::TIMEOUT.inc();
+                    warn!(LogSchema::event_log(LogEntry::FastSync, LogEvent::Timeout));
+                }
+                _ => return Ok(()),
+            }
+        }
+        self.send_fast_sync_request()
+    }
+
+    /// Requests the next epoch ending ledger infos or, once the target is known, the next chunk
+    /// of the state snapshot.
+    fn send_fast_sync_request(&mut self) -> Result<(), Error> {
+        let fast_sync = match self.fast_sync.as_mut() {
+            Some(fast_sync) => fast_sync,
+            None => return Ok(()),
+        };
+        let msg = match &fast_sync.target_li {
+            None => StateSyncMessage::GetEpochEndingLedgerInfosRequest(Box::new(
+                GetEpochEndingLedgerInfosRequest::new(fast_sync.epoch_state.epoch),
+            )),
+            Some(target_li) => {
+                StateSyncMessage::GetStateSnapshotRequest(Box::new(GetStateSnapshotRequest::new(
+                    target_li.ledger_info().version(),
+                    fast_sync.last_key,
+                    self.config.state_snapshot_chunk_limit,
+                )))
+            }
+        };
+        self.request_manager.send_fast_sync_request(msg)?;
+        fast_sync.last_request_time = Some(SystemTime::now());
+        Ok(())
     }
 
     /// Returns true if consensus is currently executing and state sync should
@@ -1492,6 +2110,11 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
             return Ok(()); // No need to check progress or issue any requests (consensus is running).
         }
 
+        // Chunk sync only starts once the state snapshot has been restored.
+        if self.fast_sync.is_some() {
+            return self.check_fast_sync_progress();
+        }
+
         // Check if the sync request has timed out (i.e., if we aren't committing fast enough)
         if let Some(sync_request) = self.sync_request.as_ref() {
             let timeout_between_commits =
@@ -1535,6 +2158,10 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
             counters::TIMEOUT.inc();
             warn!(LogSchema::new(LogEntry::Timeout).version(known_version));
 
+            // The peers might not be able to serve transaction outputs, so fall back to executing
+            // the transactions for the retry.
+            self.execute_next_chunk_request = true;
+
             let trusted_epoch = self.local_state.trusted_epoch();
             let chunk_target = if !self.is_initialized() {
                 self.create_waypoint_chunk_target()
@@ -1600,8 +2227,15 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
             .unwrap_or_else(|| known_version.wrapping_add(1));
         counters::set_version(counters::VersionType::Target, target_version);
 
+        // Waypoint syncing, and retries after a timeout, always execute the transactions
+        let execute_transactions = std::mem::take(&mut self.execute_next_chunk_request);
+        let syncing_mode = match target {
+            TargetType::Waypoint(_) => ContinuousSyncingMode::ExecuteTransactions,
+            _ if execute_transactions => ContinuousSyncingMode::ExecuteTransactions,
+            _ => self.config.continuous_syncing_mode,
+        };
         let req = GetChunkRequest::new(known_version, known_epoch, self.config.chunk_limit, target);
-        self.request_manager.send_chunk_request(req)
+        self.request_manager.send_chunk_request(req, syncing_mode)
     }
 
     fn deliver_subscription(
@@ -1624,6 +2258,7 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
                 highest_li,
             },
             request_info.chunk_limit,
+            request_info.syncing_mode,
         )
     }
 
@@ -1719,19 +2354,32 @@ impl<T: ExecutorProxyTrait, M: MempoolNotificationSender> StateSyncCoordinator<T
 mod tests {
     use crate::{
         chunk_request::{GetChunkRequest, TargetType},
-        chunk_response::{GetChunkResponse, ResponseLedgerInfo},
+        chunk_response::{GetChunkResponse, GetTransactionOutputChunkResponse, ResponseLedgerInfo},
         coordinator::StateSyncCoordinator,
         error::Error,
-        executor_proxy::ExecutorProxy,
+        executor_proxy::{ExecutorProxy, ExecutorProxyTrait},
         network::StateSyncMessage,
         shared_components::{test_utils, test_utils::create_coordinator_with_config_and_waypoint},
+        state_snapshot::{
+            GetEpochEndingLedgerInfosResponse, GetStateSnapshotRequest, GetStateSnapshotResponse,
+        },
     };
     use consensus_notifications::{
         ConsensusCommitNotification, ConsensusNotificationResponse, ConsensusSyncNotification,
     };
+    use diem_transaction_builder::stdlib::encode_update_diem_version_script;
+    use diem_vm::DiemVM;
+    use diemdb::DiemDB;
+    use executor::Executor;
+    use executor_test_helpers::{
+        bootstrap_genesis, gen_block_id, gen_ledger_info_with_sigs, get_test_signed_transaction,
+    };
+    use executor_types::BlockExecutor;
     use futures::{channel::oneshot, executor::block_on};
     use mango_config::{
-        config::{NodeConfig, PeerNetworkId, PeerRole, RoleType},
+        config::{
+            BootstrappingMode, ContinuousSyncingMode, NodeConfig, PeerNetworkId, PeerRole, RoleType,
+        },
         network_id::{NetworkId, NodeNetworkId},
     };
     use mango_crypto::{
@@ -1740,22 +2388,30 @@ mod tests {
     };
     use mango_types::{
         account_address::AccountAddress,
+        account_config::diem_root_address,
         block_info::BlockInfo,
+        block_metadata::BlockMetadata,
         chain_id::ChainId,
         contract_event::ContractEvent,
+        epoch_change::EpochChangeProof,
         ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-        proof::TransactionListProof,
+        proof::{SparseMerkleRangeProof, TransactionListProof},
         transaction::{
             RawTransaction, Script, SignedTransaction, Transaction, TransactionListWithProof,
-            TransactionPayload, Version,
+            TransactionOutput, TransactionOutputListWithProof, TransactionPayload,
+            TransactionStatus, Version, WriteSetPayload,
         },
+        validator_signer::ValidatorSigner,
+        vm_status::KeptVMStatus,
         waypoint::Waypoint,
+        write_set::WriteSet,
         PeerId,
     };
     use mempool_notifications::MempoolNotifier;
     use netcore::transport::ConnectionOrigin;
     use network::transport::ConnectionMetadata;
     use std::collections::BTreeMap;
+    use storage_interface::DbReaderWriter;
 
     #[test]
     fn test_process_sync_request() {
@@ -2308,6 +2964,365 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_process_transaction_output_chunk_responses() {
+        // Create a coordinator for a full node that executes transactions
+        let mut full_node_coordinator = test_utils::create_full_node_coordinator();
+
+        // Create a peer for the node and add the peer as a known peer
+        let peer_network_id = PeerNetworkId::random_validator();
+        process_new_peer_event(&mut full_node_coordinator, &peer_network_id);
+
+        // Verify wrong chunk type for all transaction output messages
+        let output_chunk_responses = create_non_empty_output_chunk_responses(1);
+        verify_all_chunk_responses_are_the_wrong_type(
+            &mut full_node_coordinator,
+            &peer_network_id,
+            &output_chunk_responses,
+        );
+
+        // Create a coordinator for a full node that applies transaction outputs
+        let mut node_config = NodeConfig::default();
+        node_config.base.role = RoleType::FullNode;
+        node_config.state_sync.continuous_syncing_mode =
+            ContinuousSyncingMode::ApplyTransactionOutputs;
+        let mut full_node_coordinator =
+            create_coordinator_with_config_and_waypoint(node_config, Waypoint::default());
+        process_new_peer_event(&mut full_node_coordinator, &peer_network_id);
+
+        // Verify wrong chunk type for the waypoint message (waypoint syncing always executes)
+        verify_all_chunk_responses_are_the_wrong_type(
+            &mut full_node_coordinator,
+            &peer_network_id,
+            &output_chunk_responses[0..1],
+        );
+
+        // Verify the target and highest messages are processed (and their invalid ledger
+        // infos rejected)
+        verify_all_chunk_responses_are_invalid(
+            &mut full_node_coordinator,
+            &peer_network_id,
+            &output_chunk_responses[1..],
+        );
+    }
+
+    #[test]
+    fn test_apply_transaction_output_chunk() {
+        // Create the executor proxy of a peer that has committed a reconfiguration
+        let (peer_executor_proxy, epoch_ending_li) = create_executor_proxy_with_epoch_change();
+        let target_version = epoch_ending_li.ledger_info().version();
+
+        // Create a coordinator for a full node that applies transaction outputs
+        let mut node_config = NodeConfig::default();
+        node_config.base.role = RoleType::FullNode;
+        node_config.state_sync.continuous_syncing_mode =
+            ContinuousSyncingMode::ApplyTransactionOutputs;
+        let mut full_node_coordinator =
+            create_coordinator_with_config_and_waypoint(node_config, Waypoint::default());
+        let peer_network_id = PeerNetworkId::random_validator();
+        process_new_peer_event(&mut full_node_coordinator, &peer_network_id);
+
+        // Process the peer's transaction outputs (the follow-up mempool notification fails as
+        // nobody is listening)
+        let known_version = full_node_coordinator.local_state.synced_version();
+        let trusted_epoch = full_node_coordinator.local_state.trusted_epoch();
+        let output_list_with_proof = peer_executor_proxy
+            .get_transaction_output_chunk(known_version, 100, target_version)
+            .unwrap();
+        let response = StateSyncMessage::GetTransactionOutputChunkResponse(Box::new(
+            GetTransactionOutputChunkResponse::new(
+                ResponseLedgerInfo::VerifiableLedgerInfo(epoch_ending_li),
+                output_list_with_proof.clone(),
+            ),
+        ));
+        let _ = block_on(full_node_coordinator.process_chunk_message(
+            peer_network_id.network_id(),
+            peer_network_id.peer_id(),
+            response,
+        ));
+
+        // Verify the outputs were applied and the node moved to the next epoch
+        assert_eq!(
+            full_node_coordinator.local_state.synced_version(),
+            target_version
+        );
+        assert_eq!(
+            full_node_coordinator.local_state.committed_version(),
+            target_version
+        );
+        assert_eq!(
+            full_node_coordinator.local_state.trusted_epoch(),
+            trusted_epoch + 1
+        );
+
+        // Verify the node can now serve the same outputs itself
+        assert_eq!(
+            full_node_coordinator
+                .executor_proxy
+                .get_transaction_output_chunk(known_version, 100, target_version)
+                .unwrap(),
+            output_list_with_proof
+        );
+    }
+
+    #[test]
+    fn test_state_snapshot_request_messages() {
+        // Create a coordinator for a validator node
+        let mut validator_coordinator = test_utils::create_validator_coordinator();
+        let peer_network_id = PeerNetworkId::random();
+
+        // Verify requests with an invalid limit are rejected
+        let max_limit = NodeConfig::default()
+            .state_sync
+            .max_state_snapshot_chunk_limit;
+        for limit in [0, max_limit + 1] {
+            let request = StateSyncMessage::GetStateSnapshotRequest(Box::new(
+                GetStateSnapshotRequest::new(0, None, limit),
+            ));
+            let result = block_on(validator_coordinator.process_chunk_message(
+                peer_network_id.network_id(),
+                peer_network_id.peer_id(),
+                request,
+            ));
+            if !matches!(result, Err(Error::InvalidChunkRequest(..))) {
+                panic!("Expected an invalid chunk request, but got: {:?}", result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fast_sync_responses_without_fast_sync() {
+        // Create a coordinator for a validator node (validators never fast sync)
+        let mut validator_coordinator = test_utils::create_validator_coordinator();
+        assert!(validator_coordinator.fast_sync.is_none());
+
+        // Verify fast sync responses are the wrong type
+        let responses = vec![
+            StateSyncMessage::GetEpochEndingLedgerInfosResponse(Box::new(
+                GetEpochEndingLedgerInfosResponse::new(1, EpochChangeProof::new(vec![], false)),
+            )),
+            StateSyncMessage::GetStateSnapshotResponse(Box::new(GetStateSnapshotResponse::new(
+                0,
+                None,
+                vec![],
+                SparseMerkleRangeProof::new(vec![]),
+                None,
+            ))),
+        ];
+        verify_all_chunk_responses_are_the_wrong_type(
+            &mut validator_coordinator,
+            &PeerNetworkId::random(),
+            &responses,
+        );
+    }
+
+    #[test]
+    fn test_fast_sync_without_newer_snapshot() {
+        // Create a coordinator for a full node bootstrapping from a state snapshot
+        let mut node_config = NodeConfig::default();
+        node_config.base.role = RoleType::FullNode;
+        node_config.state_sync.bootstrapping_mode = BootstrappingMode::FastSyncStateSnapshot;
+        let mut full_node_coordinator =
+            create_coordinator_with_config_and_waypoint(node_config, Waypoint::default());
+        assert!(full_node_coordinator.fast_sync.is_some());
+        let peer_network_id = PeerNetworkId::random();
+
+        // Verify a response for the wrong epoch is rejected
+        let trusted_epoch = full_node_coordinator.local_state.trusted_epoch();
+        let response = StateSyncMessage::GetEpochEndingLedgerInfosResponse(Box::new(
+            GetEpochEndingLedgerInfosResponse::new(
+                trusted_epoch + 1,
+                EpochChangeProof::new(vec![], false),
+            ),
+        ));
+        let result = block_on(full_node_coordinator.process_chunk_message(
+            peer_network_id.network_id(),
+            peer_network_id.peer_id(),
+            response,
+        ));
+        if !matches!(result, Err(Error::ReceivedWrongChunkType(..))) {
+            panic!("Expected the wrong chunk type, but got: {:?}", result);
+        }
+        assert!(full_node_coordinator.fast_sync.is_some());
+
+        // Verify the fast sync ends when there are no newer epoch ending ledger infos
+        let response = StateSyncMessage::GetEpochEndingLedgerInfosResponse(Box::new(
+            GetEpochEndingLedgerInfosResponse::new(
+                trusted_epoch,
+                EpochChangeProof::new(vec![], false),
+            ),
+        ));
+        let _ = block_on(full_node_coordinator.process_chunk_message(
+            peer_network_id.network_id(),
+            peer_network_id.peer_id(),
+            response,
+        ));
+        assert!(full_node_coordinator.fast_sync.is_none());
+
+        // Verify a node with the default bootstrapping mode doesn't fast sync
+        let full_node_coordinator = test_utils::create_full_node_coordinator();
+        assert!(full_node_coordinator.fast_sync.is_none());
+    }
+
+    #[test]
+    fn test_fast_sync_from_state_snapshot() {
+        // Create the executor proxy of a peer that has committed a reconfiguration
+        let (peer_executor_proxy, epoch_ending_li) = create_executor_proxy_with_epoch_change();
+        let target_version = epoch_ending_li.ledger_info().version();
+
+        // Create a coordinator for a full node bootstrapping from a state snapshot
+        let mut node_config = NodeConfig::default();
+        node_config.base.role = RoleType::FullNode;
+        node_config.state_sync.bootstrapping_mode = BootstrappingMode::FastSyncStateSnapshot;
+        let mut full_node_coordinator =
+            create_coordinator_with_config_and_waypoint(node_config, Waypoint::default());
+        let peer_network_id = PeerNetworkId::random();
+
+        // Process the epoch ending ledger infos and verify the snapshot target is picked (the
+        // follow-up request fails to send as there are no connected peers)
+        let trusted_epoch = full_node_coordinator.local_state.trusted_epoch();
+        let response = StateSyncMessage::GetEpochEndingLedgerInfosResponse(Box::new(
+            GetEpochEndingLedgerInfosResponse::new(
+                trusted_epoch,
+                peer_executor_proxy
+                    .get_epoch_ending_ledger_infos(trusted_epoch)
+                    .unwrap(),
+            ),
+        ));
+        let _ = block_on(full_node_coordinator.process_chunk_message(
+            peer_network_id.network_id(),
+            peer_network_id.peer_id(),
+            response,
+        ));
+        let target_li = full_node_coordinator
+            .fast_sync
+            .as_ref()
+            .unwrap()
+            .target_li
+            .clone();
+        assert_eq!(target_li, Some(epoch_ending_li));
+
+        // Restore the state snapshot chunk by chunk, until the fast sync completes
+        let chunk_limit = 3;
+        let mut num_chunks = 0;
+        while let Some(fast_sync) = full_node_coordinator.fast_sync.as_ref() {
+            let last_key = fast_sync.last_key;
+            let txn_list_with_proof = if fast_sync.txn_list_with_proof.is_none() {
+                Some(
+                    peer_executor_proxy
+                        .get_state_snapshot_transaction(target_version)
+                        .unwrap(),
+                )
+            } else {
+                None
+            };
+            let (account_blobs, proof) = peer_executor_proxy
+                .get_state_snapshot_chunk(target_version, last_key, chunk_limit)
+                .unwrap();
+            let response = StateSyncMessage::GetStateSnapshotResponse(Box::new(
+                GetStateSnapshotResponse::new(
+                    target_version,
+                    last_key,
+                    account_blobs,
+                    proof,
+                    txn_list_with_proof,
+                ),
+            ));
+            let _ = block_on(full_node_coordinator.process_chunk_message(
+                peer_network_id.network_id(),
+                peer_network_id.peer_id(),
+                response,
+            ));
+            num_chunks += 1;
+            assert!(num_chunks < 1000, "The fast sync never completed!");
+        }
+        assert!(num_chunks > 1);
+
+        // Verify chunk sync takes over from the snapshot version, in the next epoch
+        assert_eq!(
+            full_node_coordinator.local_state.synced_version(),
+            target_version
+        );
+        assert_eq!(
+            full_node_coordinator.local_state.committed_version(),
+            target_version
+        );
+        assert_eq!(
+            full_node_coordinator.local_state.trusted_epoch(),
+            trusted_epoch + 1
+        );
+
+        // Verify the restored state matches the peer's
+        let executor_proxy = &full_node_coordinator.executor_proxy;
+        let max_limit = NodeConfig::default()
+            .state_sync
+            .max_state_snapshot_chunk_limit;
+        assert_eq!(
+            executor_proxy
+                .get_state_snapshot_chunk(target_version, None, max_limit)
+                .unwrap(),
+            peer_executor_proxy
+                .get_state_snapshot_chunk(target_version, None, max_limit)
+                .unwrap(),
+        );
+    }
+
+    /// Creates an executor proxy whose storage has committed a reconfiguration after genesis,
+    /// and returns it along with the (signed) ledger info ending the first epoch.
+    fn create_executor_proxy_with_epoch_change() -> (ExecutorProxy, LedgerInfoWithSignatures) {
+        // Generate a genesis change set
+        let (genesis, validators) = vm_genesis::test_genesis_change_set_and_validators(Some(1));
+
+        // Create test mango database
+        let db_path = mango_temppath::TempPath::new();
+        db_path.create_as_dir().unwrap();
+        let db_rw = DbReaderWriter::new(DiemDB::new_for_test(db_path.path()));
+
+        // Bootstrap the genesis transaction
+        let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
+        bootstrap_genesis::<DiemVM>(&db_rw, &genesis_txn).unwrap();
+
+        // Execute and commit a block that bumps the timer and the mango version
+        let validator = validators.into_iter().next().unwrap();
+        let validator_account = validator.data.address;
+        let signer = ValidatorSigner::new(validator_account, validator.key);
+        let block_id = gen_block_id(1);
+        let genesis_key = vm_genesis::GENESIS_KEYPAIR.0.clone();
+        let block = vec![
+            Transaction::BlockMetadata(BlockMetadata::new(
+                block_id,
+                1,
+                200000020,
+                vec![],
+                validator_account,
+            )),
+            get_test_signed_transaction(
+                diem_root_address(),
+                0, /* sequence_number */
+                genesis_key.clone(),
+                genesis_key.public_key(),
+                Some(TransactionPayload::Script(
+                    encode_update_diem_version_script(0, 7),
+                )),
+            ),
+        ];
+        let mut block_executor = Executor::<DiemVM>::new(db_rw.clone());
+        let output = block_executor
+            .execute_block((block_id, block), block_executor.committed_block_id())
+            .unwrap();
+        assert!(output.has_reconfiguration());
+        let epoch_ending_li = gen_ledger_info_with_sigs(1, &output, block_id, vec![&signer]);
+        block_executor
+            .commit_blocks(vec![block_id], epoch_ending_li.clone())
+            .unwrap();
+
+        // Create executor proxy
+        let chunk_executor = Box::new(Executor::<DiemVM>::new(db_rw.clone()));
+        let executor_proxy = ExecutorProxy::new(db_rw, chunk_executor, vec![]);
+
+        (executor_proxy, epoch_ending_li)
+    }
+
     fn create_test_transaction() -> Transaction {
         let private_key = Ed25519PrivateKey::generate_for_testing();
         let public_key = private_key.public_key();
@@ -2433,6 +3448,43 @@ mod tests {
         create_chunk_responses(version, transaction_list_with_proof)
     }
 
+    /// Creates the transaction output counterparts of `create_non_empty_chunk_responses()`.
+    fn create_non_empty_output_chunk_responses(version: Version) -> Vec<StateSyncMessage> {
+        create_non_empty_chunk_responses(version)
+            .into_iter()
+            .map(|message| match message {
+                StateSyncMessage::GetChunkResponse(response) => {
+                    let txn_list_with_proof = response.txn_list_with_proof;
+                    let transactions_and_outputs = txn_list_with_proof
+                        .transactions
+                        .into_iter()
+                        .map(|txn| {
+                            let output = TransactionOutput::new(
+                                WriteSet::default(),
+                                vec![],
+                                0,
+                                TransactionStatus::Keep(KeptVMStatus::Executed),
+                            );
+                            (txn, output)
+                        })
+                        .collect();
+                    let output_list_with_proof = TransactionOutputListWithProof::new(
+                        transactions_and_outputs,
+                        txn_list_with_proof.first_transaction_version,
+                        txn_list_with_proof.proof,
+                    );
+                    StateSyncMessage::GetTransactionOutputChunkResponse(Box::new(
+                        GetTransactionOutputChunkResponse::new(
+                            response.response_li,
+                            output_list_with_proof,
+                        ),
+                    ))
+                }
+                message => panic!("Expected a chunk response, but got: {:?}", message),
+            })
+            .collect()
+    }
+
     /// Creates a set of chunk responses (one for each type of possible response).
     /// The returned response types are: [waypoint, target, highest].
     fn create_chunk_responses(
@@ -17,6 +17,10 @@ pub const SYNC_MSG_LABEL: &str = "sync";
 pub const COMMIT_MSG_LABEL: &str = "commit";
 pub const CHUNK_REQUEST_MSG_LABEL: &str = "chunk_request";
 pub const CHUNK_RESPONSE_MSG_LABEL: &str = "chunk_response";
+pub const EPOCH_ENDING_REQUEST_MSG_LABEL: &str = "epoch_ending_request";
+pub const EPOCH_ENDING_RESPONSE_MSG_LABEL: &str = "epoch_ending_response";
+pub const STATE_SNAPSHOT_REQUEST_MSG_LABEL: &str = "state_snapshot_request";
+pub const STATE_SNAPSHOT_RESPONSE_MSG_LABEL: &str = "state_snapshot_response";
 
 pub fn set_timestamp(timestamp_type: TimestampType, time_as_usecs: u64) {
     TIMESTAMP
@@ -162,6 +166,36 @@ pub static STATE_SYNC_CHUNK_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of times a state snapshot (or epoch ending ledger info) request was processed
+pub static PROCESS_STATE_SNAPSHOT_REQUEST_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "diem_state_sync_process_state_snapshot_request_total",
+        "Number of times a state snapshot request was processed",
+        &["network", "sender", "result"]
+    )
+    .unwrap()
+});
+
+/// Number of attempts to restore a state snapshot chunk
+pub static APPLY_STATE_SNAPSHOT_CHUNK_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "diem_state_sync_apply_state_snapshot_chunk_total",
+        "Number of results of restoring a state snapshot chunk",
+        &["network", "sender", "result"]
+    )
+    .unwrap()
+});
+
+/// Version of the state snapshot being restored and number of accounts restored so far
+pub static STATE_SNAPSHOT_PROGRESS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "diem_state_sync_state_snapshot_progress",
+        "Progress of the state snapshot restore when fast syncing",
+        &["type"] // version, accounts
+    )
+    .unwrap()
+});
+
 /// Number of peers that are currently active and upstream.
 /// They are the set of nodes a node can make sync requests to
 pub static ACTIVE_UPSTREAM_PEERS: Lazy<IntGaugeVec> = Lazy::new(|| {
@@ -269,6 +303,15 @@ pub static EXECUTE_CHUNK_DURATION: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Time it takes for state sync to fully apply a chunk of transaction outputs (via executor proxy)
+pub static APPLY_CHUNK_DURATION: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "diem_state_sync_apply_chunk_duration_s",
+        "Histogram of time it takes for state sync's executor proxy to fully apply a chunk of transaction outputs"
+    )
+    .unwrap()
+});
+
 /// Number of times a long-poll subscription is successfully delivered
 pub static SUBSCRIPTION_DELIVERY_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -8,22 +8,26 @@ use crate::{
     shared_components::SyncState,
 };
 use executor_types::{ChunkExecutor, ExecutedTrees};
+use mango_crypto::HashValue;
 use mango_logger::prelude::*;
 use mango_types::{
     account_state::AccountState,
+    account_state_blob::AccountStateBlob,
     contract_event::ContractEvent,
+    epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
     move_resource::MoveStorage,
     on_chain_config,
     on_chain_config::{config_address, ConfigID, OnChainConfigPayload, ON_CHAIN_CONFIG_REGISTRY},
-    transaction::TransactionListWithProof,
+    proof::SparseMerkleRangeProof,
+    transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use std::{
     collections::{HashMap, HashSet},
     convert::TryFrom,
     sync::Arc,
 };
-use storage_interface::DbReader;
+use storage_interface::{DbReader, DbReaderWriter, DbWriter, StateSnapshotReceiver};
 use subscription_service::ReconfigSubscription;
 
 /// Proxies interactions with execution and storage for state synchronization
@@ -47,6 +51,23 @@ pub trait ExecutorProxyTrait: Send {
         target_version: u64,
     ) -> Result<TransactionListWithProof, Error>;
 
+    /// Apply and commit a batch of transaction outputs
+    fn apply_chunk(
+        &mut self,
+        output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+        intermediate_end_of_epoch_li: Option<LedgerInfoWithSignatures>,
+    ) -> Result<(), Error>;
+
+    /// Gets chunk of transaction outputs given the known version, target version and the max
+    /// limit.
+    fn get_transaction_output_chunk(
+        &self,
+        known_version: u64,
+        limit: u64,
+        target_version: u64,
+    ) -> Result<TransactionOutputListWithProof, Error>;
+
     /// Get the epoch changing ledger info for the given epoch so that we can move to next epoch.
     fn get_epoch_change_ledger_info(&self, epoch: u64) -> Result<LedgerInfoWithSignatures, Error>;
 
@@ -59,51 +80,115 @@ pub trait ExecutorProxyTrait: Send {
 
     /// publishes on-chain config updates to subscribed components
     fn publish_on_chain_config_updates(&mut self, events: Vec<ContractEvent>) -> Result<(), Error>;
+
+    /// Gets the epoch ending ledger infos from `start_epoch` up to the latest epoch.
+    fn get_epoch_ending_ledger_infos(&self, start_epoch: u64) -> Result<EpochChangeProof, Error>;
+
+    /// Gets at most `limit` accounts of the state snapshot at `version` that follow `last_key`,
+    /// along with the proof of the range.
+    fn get_state_snapshot_chunk(
+        &self,
+        version: Version,
+        last_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof), Error>;
+
+    /// Gets the transaction at `version` with its events and its proof relative to the ledger
+    /// info at `version`.
+    fn get_state_snapshot_transaction(
+        &self,
+        version: Version,
+    ) -> Result<TransactionListWithProof, Error>;
+
+    /// Starts restoring the state snapshot at `version`, whose state root hash has been verified
+    /// by the caller.
+    fn start_state_snapshot_restore(
+        &mut self,
+        version: Version,
+        expected_root_hash: HashValue,
+    ) -> Result<(), Error>;
+
+    /// Verifies a chunk of accounts of the state snapshot being restored and writes it to storage.
+    fn add_state_snapshot_chunk(
+        &mut self,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<(), Error>;
+
+    /// Completes the state snapshot restore and commits the (verified) transaction at the
+    /// snapshot version along with the epoch ending ledger infos up to it, so that syncing can
+    /// continue from the snapshot version.
+    fn finish_state_snapshot_restore(
+        &mut self,
+        txn_list_with_proof: TransactionListWithProof,
+        epoch_ending_lis: Vec<LedgerInfoWithSignatures>,
+    ) -> Result<(), Error>;
 }
 
 pub(crate) struct ExecutorProxy {
     storage: Arc<dyn DbReader>,
+    storage_writer: Arc<dyn DbWriter>,
     executor: Box<dyn ChunkExecutor>,
     reconfig_subscriptions: Vec<ReconfigSubscription>,
     on_chain_configs: OnChainConfigPayload,
+    state_snapshot_restore: Option<Box<dyn StateSnapshotReceiver>>,
 }
 
 impl ExecutorProxy {
     pub(crate) fn new(
-        storage: Arc<dyn DbReader>,
+        storage: DbReaderWriter,
         executor: Box<dyn ChunkExecutor>,
         mut reconfig_subscriptions: Vec<ReconfigSubscription>,
     ) -> Self {
         let on_chain_configs = Self::publish_initial_on_chain_configs(
             ON_CHAIN_CONFIG_REGISTRY,
-            &*storage,
+            &*storage.reader,
             &mut reconfig_subscriptions,
         );
         Self {
-            storage,
+            storage: storage.reader,
+            storage_writer: storage.writer,
             executor,
             reconfig_subscriptions,
             on_chain_configs,
+            state_snapshot_restore: None,
         }
     }
 
     #[cfg(test)]
     pub(crate) fn new_for_test(
-        storage: Arc<dyn DbReader>,
+        storage: DbReaderWriter,
         executor: Box<dyn ChunkExecutor>,
         mut reconfig_subscriptions: Vec<ReconfigSubscription>,
         config_registry: &[ConfigID],
     ) -> Self {
         let on_chain_configs = Self::publish_initial_on_chain_configs(
             config_registry,
-            &*storage,
+            &*storage.reader,
             &mut reconfig_subscriptions,
         );
         Self {
-            storage,
+            storage: storage.reader,
+            storage_writer: storage.writer,
             executor,
             reconfig_subscriptions,
             on_chain_configs,
+            state_snapshot_restore: None,
+        }
+    }
+
+    /// Publishes the reconfig events of a committed chunk, logging (but not returning) any
+    /// failure: the chunk itself has been committed successfully.
+    fn publish_chunk_reconfig_events(&mut self, events: Vec<ContractEvent>, operation: &str) {
+        let reconfig_events = extract_reconfig_events(events);
+        if let Err(e) = self.publish_on_chain_config_updates(reconfig_events) {
+            error!(
+                LogSchema::event_log(LogEntry::Reconfig, LogEvent::Fail).error(&e),
+                "Failed to publish reconfig updates in {}", operation
+            );
+            counters::RECONFIG_PUBLISH_COUNT
+                .with_label_values(&[counters::FAIL_LABEL])
+                .inc();
         }
     }
 
@@ -247,16 +332,7 @@ impl ExecutorProxyTrait for ExecutorProxy {
                 Error::UnexpectedError(format!("Execute and commit chunk failed: {}", error))
             })?;
         timer.stop_and_record();
-        let reconfig_events = extract_reconfig_events(events);
-        if let Err(e) = self.publish_on_chain_config_updates(reconfig_events) {
-            error!(
-                LogSchema::event_log(LogEntry::Reconfig, LogEvent::Fail).error(&e),
-                "Failed to publish reconfig updates in execute_chunk"
-            );
-            counters::RECONFIG_PUBLISH_COUNT
-                .with_label_values(&[counters::FAIL_LABEL])
-                .inc();
-        }
+        self.publish_chunk_reconfig_events(events, "execute_chunk");
         Ok(())
     }
 
@@ -276,6 +352,48 @@ impl ExecutorProxyTrait for ExecutorProxy {
             })
     }
 
+    fn apply_chunk(
+        &mut self,
+        output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+        intermediate_end_of_epoch_li: Option<LedgerInfoWithSignatures>,
+    ) -> Result<(), Error> {
+        // track chunk apply time
+        let timer = counters::APPLY_CHUNK_DURATION.start_timer();
+        let events = self
+            .executor
+            .apply_and_commit_chunk(
+                output_list_with_proof,
+                verified_target_li,
+                intermediate_end_of_epoch_li,
+            )
+            .map_err(|error| {
+                Error::UnexpectedError(format!("Apply and commit chunk failed: {}", error))
+            })?;
+        timer.stop_and_record();
+        self.publish_chunk_reconfig_events(events, "apply_chunk");
+        Ok(())
+    }
+
+    fn get_transaction_output_chunk(
+        &self,
+        known_version: u64,
+        limit: u64,
+        target_version: u64,
+    ) -> Result<TransactionOutputListWithProof, Error> {
+        let starting_version = known_version
+            .checked_add(1)
+            .ok_or_else(|| Error::IntegerOverflow("Starting version has overflown!".into()))?;
+        self.storage
+            .get_transaction_outputs(starting_version, limit, target_version)
+            .map_err(|error| {
+                Error::UnexpectedError(format!(
+                    "Failed to get transaction outputs from storage {}",
+                    error
+                ))
+            })
+    }
+
     fn get_epoch_change_ledger_info(&self, epoch: u64) -> Result<LedgerInfoWithSignatures, Error> {
         let next_epoch = epoch
             .checked_add(1)
@@ -384,6 +502,112 @@ impl ExecutorProxyTrait for ExecutorProxy {
             ))
         }
     }
+
+    fn get_epoch_ending_ledger_infos(&self, start_epoch: u64) -> Result<EpochChangeProof, Error> {
+        let end_epoch = self
+            .storage
+            .get_latest_ledger_info()
+            .map_err(|error| Error::UnexpectedError(error.to_string()))?
+            .ledger_info()
+            .next_block_epoch();
+        self.storage
+            .get_epoch_ending_ledger_infos(start_epoch, end_epoch)
+            .map_err(|error| Error::UnexpectedError(error.to_string()))
+    }
+
+    fn get_state_snapshot_chunk(
+        &self,
+        version: Version,
+        last_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof), Error> {
+        self.storage
+            .get_state_snapshot_chunk(version, last_key, limit as usize)
+            .map_err(|error| {
+                Error::UnexpectedError(format!("Failed to get state snapshot chunk: {}", error))
+            })
+    }
+
+    fn get_state_snapshot_transaction(
+        &self,
+        version: Version,
+    ) -> Result<TransactionListWithProof, Error> {
+        self.storage
+            .get_transactions(version, 1, version, true)
+            .map_err(|error| {
+                Error::UnexpectedError(format!("Failed to get transactions from storage {}", error))
+            })
+    }
+
+    fn start_state_snapshot_restore(
+        &mut self,
+        version: Version,
+        expected_root_hash: HashValue,
+    ) -> Result<(), Error> {
+        let receiver = self
+            .storage_writer
+            .get_state_snapshot_receiver(version, expected_root_hash)
+            .map_err(|error| {
+                Error::UnexpectedError(format!("Failed to start state snapshot restore: {}", error))
+            })?;
+        self.state_snapshot_restore = Some(receiver);
+        Ok(())
+    }
+
+    fn add_state_snapshot_chunk(
+        &mut self,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<(), Error> {
+        self.state_snapshot_restore
+            .as_mut()
+            .ok_or_else(|| {
+                Error::UnexpectedError("No state snapshot restore is in progress!".into())
+            })?
+            .add_chunk(account_blobs, proof)
+            .map_err(|error| Error::ProcessInvalidChunk(error.to_string()))
+    }
+
+    fn finish_state_snapshot_restore(
+        &mut self,
+        txn_list_with_proof: TransactionListWithProof,
+        epoch_ending_lis: Vec<LedgerInfoWithSignatures>,
+    ) -> Result<(), Error> {
+        let receiver = self.state_snapshot_restore.take().ok_or_else(|| {
+            Error::UnexpectedError("No state snapshot restore is in progress!".into())
+        })?;
+        receiver.finish_box().map_err(|error| {
+            Error::UnexpectedError(format!(
+                "Failed to finish state snapshot restore: {}",
+                error
+            ))
+        })?;
+
+        // Commit the transaction at the snapshot version and the ledger infos proving it.
+        let version = txn_list_with_proof
+            .first_transaction_version
+            .ok_or_else(|| {
+                Error::UnexpectedError("Missing the transaction at the snapshot version!".into())
+            })?;
+        self.storage_writer
+            .finalize_state_snapshot(version, txn_list_with_proof, &epoch_ending_lis)
+            .map_err(|error| {
+                Error::UnexpectedError(format!(
+                    "Failed to commit the state snapshot version: {}",
+                    error
+                ))
+            })?;
+
+        // The whole state has changed, so every subscriber is sent the configs of the snapshot.
+        let on_chain_configs = Self::fetch_all_configs(ON_CHAIN_CONFIG_REGISTRY, &*self.storage)?;
+        for subscription in self.reconfig_subscriptions.iter_mut() {
+            subscription
+                .publish(on_chain_configs.clone())
+                .map_err(|error| Error::UnexpectedError(error.to_string()))?;
+        }
+        self.on_chain_configs = on_chain_configs;
+        Ok(())
+    }
 }
 
 fn extract_reconfig_events(events: Vec<ContractEvent>) -> Vec<ContractEvent> {
@@ -755,7 +979,7 @@ mod tests {
         // Create a test mango database
         let db_path = mango_temppath::TempPath::new();
         db_path.create_as_dir().unwrap();
-        let (db, db_rw) = DbReaderWriter::wrap(DiemDB::new_for_test(db_path.path()));
+        let db_rw = DbReaderWriter::new(DiemDB::new_for_test(db_path.path()));
 
         // Bootstrap the database with regular genesis
         let (genesis, validators) = vm_genesis::test_genesis_change_set_and_validators(Some(1));
@@ -767,8 +991,12 @@ mod tests {
         let chunk_executor = Box::new(Executor::<DiemVM>::new(db_rw.clone()));
         let mut config_registry = ON_CHAIN_CONFIG_REGISTRY.to_owned();
         config_registry.push(TestOnChainConfig::CONFIG_ID);
-        let mut executor_proxy =
-            ExecutorProxy::new_for_test(db, chunk_executor, vec![subscription], &config_registry);
+        let mut executor_proxy = ExecutorProxy::new_for_test(
+            db_rw.clone(),
+            chunk_executor,
+            vec![subscription],
+            &config_registry,
+        );
 
         // Verify that the initial configs returned to the subscriber don't contain the unknown on-chain config
         let payload = reconfig_receiver.select_next_some().now_or_never().unwrap();
@@ -810,7 +1038,7 @@ mod tests {
         // Create test mango database
         let db_path = mango_temppath::TempPath::new();
         db_path.create_as_dir().unwrap();
-        let (db, db_rw) = DbReaderWriter::wrap(DiemDB::new_for_test(db_path.path()));
+        let db_rw = DbReaderWriter::new(DiemDB::new_for_test(db_path.path()));
 
         // Boostrap the genesis transaction
         let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
@@ -818,8 +1046,8 @@ mod tests {
 
         // Create executor proxy with given subscription
         let block_executor = Box::new(Executor::<DiemVM>::new(db_rw.clone()));
-        let chunk_executor = Box::new(Executor::<DiemVM>::new(db_rw));
-        let executor_proxy = ExecutorProxy::new(db, chunk_executor, vec![subscription]);
+        let chunk_executor = Box::new(Executor::<DiemVM>::new(db_rw.clone()));
+        let executor_proxy = ExecutorProxy::new(db_rw, chunk_executor, vec![subscription]);
 
         // Verify initial reconfiguration notification is sent
         assert!(
@@ -20,6 +20,7 @@ mod logging;
 pub mod network;
 mod request_manager;
 pub mod shared_components;
+pub mod state_snapshot;
 
 #[cfg(any(feature = "fuzzing", test))]
 pub mod fuzzing;
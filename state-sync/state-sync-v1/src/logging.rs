@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    chunk_request::GetChunkRequest, chunk_response::GetChunkResponse, error::Error,
+    chunk_request::GetChunkRequest,
+    chunk_response::{ResponseChunk, ResponseLedgerInfo},
+    error::Error,
     request_manager::ChunkRequestInfo,
 };
 use mango_config::{config::PeerNetworkId, network_id::NetworkId};
@@ -25,7 +27,9 @@ pub struct LogSchema<'a> {
     chunk_request: Option<GetChunkRequest>,
     version: Option<u64>,
     #[schema(display)]
-    chunk_response: Option<GetChunkResponse>,
+    response_li: Option<&'a ResponseLedgerInfo>,
+    #[schema(display)]
+    response_chunk: Option<&'a ResponseChunk>,
     #[schema(display)]
     waypoint: Option<Waypoint>,
     subscription_name: Option<String>,
@@ -63,7 +67,8 @@ impl<'a> LogSchema<'a> {
             is_valid_peer: None,
             error: None,
             chunk_request: None,
-            chunk_response: None,
+            response_li: None,
+            response_chunk: None,
             version: None,
             waypoint: None,
             subscription_name: None,
@@ -108,6 +113,8 @@ pub enum LogEntry {
     Multicast,
     SubscriptionDeliveryFail,
     ProgressCheck,
+    FastSync,
+    ProcessStateSnapshotRequest,
 }
 
 #[derive(Clone, Copy, Serialize)]
@@ -139,8 +146,13 @@ pub enum LogEvent {
     // ProcessChunkRequest events
     PastEpochRequested,
     DeliverChunk,
+    OutputsUnavailable,
 
     // Multicast network events
     Failover,
     Recover,
+
+    // FastSync events
+    EpochEndingLedgerInfos,
+    RestoreStateSnapshotChunk,
 }
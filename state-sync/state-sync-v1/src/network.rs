@@ -4,7 +4,14 @@
 //! Interface between State Sync and Network layers.
 
 use crate::{
-    chunk_request::GetChunkRequest, chunk_response::GetChunkResponse, counters, error::Error,
+    chunk_request::GetChunkRequest,
+    chunk_response::{GetChunkResponse, GetTransactionOutputChunkResponse},
+    counters,
+    error::Error,
+    state_snapshot::{
+        GetEpochEndingLedgerInfosRequest, GetEpochEndingLedgerInfosResponse,
+        GetStateSnapshotRequest, GetStateSnapshotResponse,
+    },
 };
 use channel::message_queues::QueueStyle;
 use mango_metrics::IntCounterVec;
@@ -23,6 +30,12 @@ const STATE_SYNC_MAX_BUFFER_SIZE: usize = 1;
 pub enum StateSyncMessage {
     GetChunkRequest(Box<GetChunkRequest>),
     GetChunkResponse(Box<GetChunkResponse>),
+    GetEpochEndingLedgerInfosRequest(Box<GetEpochEndingLedgerInfosRequest>),
+    GetEpochEndingLedgerInfosResponse(Box<GetEpochEndingLedgerInfosResponse>),
+    GetStateSnapshotRequest(Box<GetStateSnapshotRequest>),
+    GetStateSnapshotResponse(Box<GetStateSnapshotResponse>),
+    GetTransactionOutputChunkRequest(Box<GetChunkRequest>),
+    GetTransactionOutputChunkResponse(Box<GetTransactionOutputChunkResponse>),
 }
 
 /// The interface from Network to StateSync layer.
@@ -10,7 +10,7 @@ use crate::{
 };
 use itertools::Itertools;
 use mango_config::{
-    config::{PeerNetworkId, PeerRole},
+    config::{ContinuousSyncingMode, PeerNetworkId, PeerRole},
     network_id::{NetworkId, NodeNetworkId},
 };
 use mango_logger::prelude::*;
@@ -255,7 +255,13 @@ impl RequestManager {
         chosen_peers
     }
 
-    pub fn send_chunk_request(&mut self, req: GetChunkRequest) -> Result<(), Error> {
+    /// Sends the chunk request to the chosen peers, asking them for transaction outputs instead
+    /// of transactions if `syncing_mode` is `ApplyTransactionOutputs`.
+    pub fn send_chunk_request(
+        &mut self,
+        req: GetChunkRequest,
+        syncing_mode: ContinuousSyncingMode,
+    ) -> Result<(), Error> {
         let log = LogSchema::new(LogEntry::SendChunkRequest).chunk_request(req.clone());
 
         let peers = self.pick_peers();
@@ -272,7 +278,14 @@ impl RequestManager {
             .event(LogEvent::ChunkRequestInfo)
             .chunk_req_info(&req_info));
 
-        let msg = StateSyncMessage::GetChunkRequest(Box::new(req));
+        let msg = match syncing_mode {
+            ContinuousSyncingMode::ExecuteTransactions => {
+                StateSyncMessage::GetChunkRequest(Box::new(req))
+            }
+            ContinuousSyncingMode::ApplyTransactionOutputs => {
+                StateSyncMessage::GetTransactionOutputChunkRequest(Box::new(req))
+            }
+        };
         let mut failed_peer_sends = vec![];
 
         for peer in peers {
@@ -307,6 +320,53 @@ impl RequestManager {
         }
     }
 
+    /// Sends a fast sync request (i.e., for epoch ending ledger infos or a state snapshot chunk)
+    /// to the same peers a chunk request would go to. These requests are not tracked here:
+    /// the coordinator resends them itself if no valid response arrives in time.
+    pub fn send_fast_sync_request(&mut self, msg: StateSyncMessage) -> Result<(), Error> {
+        let log = LogSchema::new(LogEntry::FastSync);
+
+        let peers = self.pick_peers();
+        if peers.is_empty() {
+            warn!(log.event(LogEvent::MissingPeers));
+            return Err(Error::NoAvailablePeers(
+                "No peers to send fast sync request to".into(),
+            ));
+        }
+
+        let mut failed_peer_sends = vec![];
+        for peer in peers {
+            let mut sender = self.get_network_sender(&peer);
+            let peer_id = peer.peer_id();
+            let send_result = sender.send_to(peer_id, msg.clone());
+            let curr_log = log.clone().peer(&peer);
+            let result_label = if let Err(e) = send_result {
+                failed_peer_sends.push(peer.clone());
+                error!(curr_log.event(LogEvent::NetworkSendError).error(&e));
+                counters::SEND_FAIL_LABEL
+            } else {
+                debug!(curr_log.event(LogEvent::Success));
+                counters::SEND_SUCCESS_LABEL
+            };
+            counters::REQUESTS_SENT
+                .with_label_values(&[
+                    &peer.raw_network_id().to_string(),
+                    &peer_id.to_string(),
+                    result_label,
+                ])
+                .inc();
+        }
+
+        if failed_peer_sends.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnexpectedError(format!(
+                "Failed to send fast sync request to: {:?}",
+                failed_peer_sends
+            )))
+        }
+    }
+
     fn get_network_sender(&mut self, peer: &PeerNetworkId) -> StateSyncSender {
         self.network_senders
             .get_mut(&peer.network_id())
@@ -68,6 +68,10 @@ impl SyncState {
         self.trusted_epoch_state.epoch
     }
 
+    pub fn trusted_epoch_state(&self) -> &EpochState {
+        &self.trusted_epoch_state
+    }
+
     pub fn verify_ledger_info(&self, ledger_info: &LedgerInfoWithSignatures) -> Result<(), Error> {
         self.trusted_epoch_state
             .verify(ledger_info)
@@ -138,15 +142,15 @@ pub(crate) mod test_utils {
         // Create test mango database
         let db_path = mango_temppath::TempPath::new();
         db_path.create_as_dir().unwrap();
-        let (db, db_rw) = DbReaderWriter::wrap(DiemDB::new_for_test(db_path.path()));
+        let db_rw = DbReaderWriter::new(DiemDB::new_for_test(db_path.path()));
 
         // Bootstrap the genesis transaction
         let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
         bootstrap_genesis::<DiemVM>(&db_rw, &genesis_txn).unwrap();
 
         // Create executor proxy
-        let chunk_executor = Box::new(Executor::<DiemVM>::new(db_rw));
-        let executor_proxy = ExecutorProxy::new(db, chunk_executor, vec![]);
+        let chunk_executor = Box::new(Executor::<DiemVM>::new(db_rw.clone()));
+        let executor_proxy = ExecutorProxy::new(db_rw, chunk_executor, vec![]);
 
         // Get initial state
         let initial_state = executor_proxy.get_local_storage_state().unwrap();
@@ -0,0 +1,171 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Messages used by nodes bootstrapping from a state snapshot (see
+//! `BootstrappingMode::FastSyncStateSnapshot`): the epoch ending ledger infos are fetched first
+//! to pick a verified snapshot version, then the accounts at that version are streamed in chunks.
+
+use mango_crypto::HashValue;
+use mango_types::{
+    account_state_blob::AccountStateBlob,
+    epoch_change::EpochChangeProof,
+    proof::SparseMerkleRangeProof,
+    transaction::{TransactionListWithProof, Version},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GetEpochEndingLedgerInfosRequest {
+    /// The response should start with the ledger info ending `start_epoch`.
+    pub start_epoch: u64,
+}
+
+impl GetEpochEndingLedgerInfosRequest {
+    pub fn new(start_epoch: u64) -> Self {
+        Self { start_epoch }
+    }
+}
+
+impl fmt::Debug for GetEpochEndingLedgerInfosRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetEpochEndingLedgerInfosRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[EpochEndingLedgerInfosRequest: start epoch: {}]",
+            self.start_epoch
+        )
+    }
+}
+
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GetEpochEndingLedgerInfosResponse {
+    /// The start epoch of the request this responds to.
+    pub start_epoch: u64,
+    /// The epoch ending ledger infos from `start_epoch` on. If `proof.more` is set, the
+    /// responder limited the number of ledger infos and the rest should be requested separately.
+    pub proof: EpochChangeProof,
+}
+
+impl GetEpochEndingLedgerInfosResponse {
+    pub fn new(start_epoch: u64, proof: EpochChangeProof) -> Self {
+        Self { start_epoch, proof }
+    }
+}
+
+impl fmt::Debug for GetEpochEndingLedgerInfosResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetEpochEndingLedgerInfosResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[EpochEndingLedgerInfosResponse: start epoch: {}, ledger infos: {}, more: {}]",
+            self.start_epoch,
+            self.proof.ledger_info_with_sigs.len(),
+            self.proof.more,
+        )
+    }
+}
+
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GetStateSnapshotRequest {
+    /// The version of the state snapshot.
+    pub version: Version,
+    /// The response should start with the first account after `last_key`, or with the first
+    /// account of the snapshot if `None`.
+    pub last_key: Option<HashValue>,
+    /// Max number of accounts in the response.
+    pub limit: u64,
+}
+
+impl GetStateSnapshotRequest {
+    pub fn new(version: Version, last_key: Option<HashValue>, limit: u64) -> Self {
+        Self {
+            version,
+            last_key,
+            limit,
+        }
+    }
+}
+
+impl fmt::Debug for GetStateSnapshotRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetStateSnapshotRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[StateSnapshotRequest: version: {}, last key: {}, limit: {}]",
+            self.version,
+            self.last_key
+                .map_or_else(|| String::from("None"), |key| key.to_string()),
+            self.limit,
+        )
+    }
+}
+
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GetStateSnapshotResponse {
+    /// The version of the state snapshot.
+    pub version: Version,
+    /// The last key of the request this responds to.
+    pub last_key: Option<HashValue>,
+    /// The accounts following `last_key`, in increasing key order.
+    pub account_blobs: Vec<(HashValue, AccountStateBlob)>,
+    /// Proves `account_blobs` against the state root hash of the snapshot.
+    pub proof: SparseMerkleRangeProof,
+    /// Only in responses to requests without a `last_key`: the transaction at `version`, with its
+    /// events and its proof relative to the ledger info at `version`. Its transaction info carries
+    /// the state root hash of the snapshot.
+    pub txn_list_with_proof: Option<TransactionListWithProof>,
+}
+
+impl GetStateSnapshotResponse {
+    pub fn new(
+        version: Version,
+        last_key: Option<HashValue>,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+        txn_list_with_proof: Option<TransactionListWithProof>,
+    ) -> Self {
+        Self {
+            version,
+            last_key,
+            account_blobs,
+            proof,
+            txn_list_with_proof,
+        }
+    }
+}
+
+impl fmt::Debug for GetStateSnapshotResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetStateSnapshotResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[StateSnapshotResponse: version: {}, last key: {}, accounts: {}, with transaction: {}]",
+            self.version,
+            self.last_key
+                .map_or_else(|| String::from("None"), |key| key.to_string()),
+            self.account_blobs.len(),
+            self.txn_list_with_proof.is_some(),
+        )
+    }
+}
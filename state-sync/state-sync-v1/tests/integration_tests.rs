@@ -843,8 +843,8 @@ fn check_chunk_request(message: Message, known_version: u64, target_version: Opt
             assert_eq!(chunk_request.known_version, known_version);
             assert_eq!(chunk_request.target.version(), target_version);
         }
-        StateSyncMessage::GetChunkResponse(_) => {
-            panic!("Received chunk response but expecting chunk request!");
+        message => {
+            panic!("Received {:?} but expecting chunk request!", message);
         }
     }
 }
@@ -857,9 +857,6 @@ fn check_chunk_response(
 ) {
     let chunk_response: StateSyncMessage = bcs::from_bytes(&message.mdata).unwrap();
     match chunk_response {
-        StateSyncMessage::GetChunkRequest(_) => {
-            panic!("Received chunk response but expecting chunk request!");
-        }
         StateSyncMessage::GetChunkResponse(chunk_response) => {
             assert_eq!(chunk_response.response_li.version(), response_li_version);
             assert_eq!(
@@ -874,6 +871,9 @@ fn check_chunk_response(
                 chunk_length
             )
         }
+        message => {
+            panic!("Received {:?} but expecting chunk response!", message);
+        }
     }
 }
 
@@ -19,9 +19,11 @@ use mango_time_service::TimeService;
 use mango_types::{
     account_address::AccountAddress,
     account_config::xus_tag,
+    account_state_blob::AccountStateBlob,
     block_info::BlockInfo,
     chain_id::ChainId,
     contract_event::ContractEvent,
+    epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     network_address::{
@@ -29,17 +31,20 @@ use mango_types::{
         parse_memory, NetworkAddress, Protocol,
     },
     on_chain_config::ValidatorSet,
-    proof::TransactionListProof,
+    proof::{SparseMerkleRangeProof, TransactionListProof},
     test_helpers::transaction_test_helpers::get_test_signed_txn,
     transaction::{
         authenticator::AuthenticationKey, SignedTransaction, Transaction, TransactionListWithProof,
-        TransactionPayload,
+        TransactionOutput, TransactionOutputListWithProof, TransactionPayload, TransactionStatus,
+        Version,
     },
     validator_config::ValidatorConfig,
     validator_info::ValidatorInfo,
     validator_signer::ValidatorSigner,
     validator_verifier::random_validator_verifier,
+    vm_status::KeptVMStatus,
     waypoint::Waypoint,
+    write_set::WriteSet,
     PeerId,
 };
 use memsocket::MemoryListener;
@@ -413,7 +418,7 @@ impl StateSyncEnvironment {
                 trusted_peers,
                 network_context,
                 TimeService::real(),
-                base_addr,
+                vec![base_addr],
                 auth_mode,
             );
 
@@ -883,6 +888,53 @@ impl ExecutorProxyTrait for MockExecutorProxy {
         (self.handler)(txns_with_proof)
     }
 
+    fn apply_chunk(
+        &mut self,
+        output_list_with_proof: TransactionOutputListWithProof,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        intermediate_end_of_epoch_li: Option<LedgerInfoWithSignatures>,
+    ) -> Result<(), Error> {
+        let txns = output_list_with_proof
+            .transactions_and_outputs
+            .into_iter()
+            .map(|(txn, _)| txn)
+            .collect();
+        self.storage.write().add_txns_with_li(
+            txns,
+            ledger_info_with_sigs,
+            intermediate_end_of_epoch_li,
+        );
+        Ok(())
+    }
+
+    fn get_transaction_output_chunk(
+        &self,
+        known_version: u64,
+        limit: u64,
+        target_version: u64,
+    ) -> Result<TransactionOutputListWithProof, Error> {
+        // The mock storage doesn't execute transactions, so every output is an empty one
+        let txns_with_proof = self.get_chunk(known_version, limit, target_version)?;
+        let transactions_and_outputs = txns_with_proof
+            .transactions
+            .into_iter()
+            .map(|txn| {
+                let output = TransactionOutput::new(
+                    WriteSet::default(),
+                    vec![],
+                    0,
+                    TransactionStatus::Keep(KeptVMStatus::Executed),
+                );
+                (txn, output)
+            })
+            .collect();
+        Ok(TransactionOutputListWithProof::new(
+            transactions_and_outputs,
+            txns_with_proof.first_transaction_version,
+            txns_with_proof.proof,
+        ))
+    }
+
     fn get_epoch_change_ledger_info(&self, epoch: u64) -> Result<LedgerInfoWithSignatures, Error> {
         self.storage.read().get_epoch_changes(epoch)
     }
@@ -905,4 +957,54 @@ impl ExecutorProxyTrait for MockExecutorProxy {
     ) -> Result<(), Error> {
         Ok(())
     }
+
+    // The mock storage holds no account state, so state snapshots are not supported
+
+    fn get_epoch_ending_ledger_infos(&self, _start_epoch: u64) -> Result<EpochChangeProof, Error> {
+        Err(state_snapshots_unsupported())
+    }
+
+    fn get_state_snapshot_chunk(
+        &self,
+        _version: Version,
+        _last_key: Option<HashValue>,
+        _limit: u64,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof), Error> {
+        Err(state_snapshots_unsupported())
+    }
+
+    fn get_state_snapshot_transaction(
+        &self,
+        _version: Version,
+    ) -> Result<TransactionListWithProof, Error> {
+        Err(state_snapshots_unsupported())
+    }
+
+    fn start_state_snapshot_restore(
+        &mut self,
+        _version: Version,
+        _expected_root_hash: HashValue,
+    ) -> Result<(), Error> {
+        Err(state_snapshots_unsupported())
+    }
+
+    fn add_state_snapshot_chunk(
+        &mut self,
+        _account_blobs: Vec<(HashValue, AccountStateBlob)>,
+        _proof: SparseMerkleRangeProof,
+    ) -> Result<(), Error> {
+        Err(state_snapshots_unsupported())
+    }
+
+    fn finish_state_snapshot_restore(
+        &mut self,
+        _txn_list_with_proof: TransactionListWithProof,
+        _epoch_ending_lis: Vec<LedgerInfoWithSignatures>,
+    ) -> Result<(), Error> {
+        Err(state_snapshots_unsupported())
+    }
+}
+
+fn state_snapshots_unsupported() -> Error {
+    Error::UnexpectedError("State snapshots are not supported by the mock executor proxy".into())
 }
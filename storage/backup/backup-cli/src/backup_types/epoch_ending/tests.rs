@@ -48,6 +48,7 @@ fn end_to_end() {
     let rt = start_backup_service(
         SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
         src_db,
+        None, /* checkpoint_root */
     );
     let client = Arc::new(BackupServiceClient::new(format!(
         "http://localhost:{}",
@@ -83,6 +84,7 @@ fn end_to_end() {
                 trusted_waypoints: TrustedWaypointOpt::default(),
                 rocksdb_opt: RocksdbOpt::default(),
                 concurernt_downloads: ConcurrentDownloadsOpt::default(),
+                enable_write_set_storage: false,
             }
             .try_into()
             .unwrap(),
@@ -220,6 +222,7 @@ async fn test_trusted_waypoints_impl(
             trusted_waypoints: TrustedWaypointOpt::default(),
             rocksdb_opt: RocksdbOpt::default(),
             concurernt_downloads: ConcurrentDownloadsOpt::default(),
+            enable_write_set_storage: false,
         }
         .try_into()
         .unwrap(),
@@ -240,6 +243,7 @@ async fn test_trusted_waypoints_impl(
             },
             rocksdb_opt: RocksdbOpt::default(),
             concurernt_downloads: ConcurrentDownloadsOpt::default(),
+            enable_write_set_storage: false,
         }
         .try_into()
         .unwrap(),
@@ -18,6 +18,7 @@ use crate::{
 };
 use anyhow::{anyhow, bail, ensure, Result};
 use diem_vm::DiemVM;
+use diemdb::backup::backup_handler::TransactionRecord;
 use executor::Executor;
 use executor_types::TransactionReplayer;
 use futures::StreamExt;
@@ -27,6 +28,7 @@ use mango_types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::{TransactionAccumulatorRangeProof, TransactionListProof},
     transaction::{Transaction, TransactionInfo, TransactionListWithProof, Version},
+    write_set::WriteSet,
 };
 use std::{
     cmp::{max, min},
@@ -71,6 +73,7 @@ struct LoadedChunk {
     pub txns: Vec<Transaction>,
     pub txn_infos: Vec<TransactionInfo>,
     pub event_vecs: Vec<Vec<ContractEvent>>,
+    pub write_sets: Vec<Option<WriteSet>>,
     pub range_proof: TransactionAccumulatorRangeProof,
     pub ledger_info: LedgerInfoWithSignatures,
 }
@@ -85,12 +88,14 @@ impl LoadedChunk {
         let mut txns = Vec::new();
         let mut txn_infos = Vec::new();
         let mut event_vecs = Vec::new();
+        let mut write_sets = Vec::new();
 
         while let Some(record_bytes) = file.read_record_bytes().await? {
-            let (txn, txn_info, events) = bcs::from_bytes(&record_bytes)?;
-            txns.push(txn);
-            txn_infos.push(txn_info);
-            event_vecs.push(events);
+            let record = TransactionRecord::decode(&record_bytes)?;
+            txns.push(record.transaction);
+            txn_infos.push(record.transaction_info);
+            event_vecs.push(record.events);
+            write_sets.push(record.write_set);
         }
 
         ensure!(
@@ -128,6 +133,7 @@ impl LoadedChunk {
             txns,
             txn_infos,
             event_vecs,
+            write_sets,
             range_proof,
             ledger_info,
         })
@@ -212,10 +218,12 @@ impl TransactionRestoreController {
                     &chunk.txns[..num_txns_to_save],
                     &chunk.txn_infos[..num_txns_to_save],
                     &chunk.event_vecs[..num_txns_to_save],
+                    &chunk.write_sets[..num_txns_to_save],
                 )?;
                 chunk.txns.drain(0..num_txns_to_save);
                 chunk.txn_infos.drain(0..num_txns_to_save);
                 chunk.event_vecs.drain(0..num_txns_to_save);
+                chunk.write_sets.drain(0..num_txns_to_save);
                 TRANSACTION_SAVE_VERSION.set(last_to_save as i64);
             }
             // Those to replay:
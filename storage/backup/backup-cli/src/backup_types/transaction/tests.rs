@@ -44,11 +44,15 @@ fn end_to_end() {
         .flat_map(|(txns, _li)| txns)
         .map(|txn_to_commit| txn_to_commit.transaction())
         .collect::<Vec<_>>();
-    let max_chunk_size = txns
+    let max_chunk_size = blocks
         .iter()
-        .map(|t| bcs::to_bytes(t).unwrap().len())
+        .flat_map(|(txns, _li)| txns)
+        .map(|t| {
+            bcs::to_bytes(t.transaction()).unwrap().len()
+                + bcs::to_bytes(t.write_set()).unwrap().len()
+        })
         .max()
-        .unwrap() // biggest txn
+        .unwrap() // biggest txn and write set
         + 115 // size of a serialized TransactionInfo
         + size_of::<u32>(); // record len header
     let first_ver_to_backup = (total_txns / 4) as Version;
@@ -84,6 +88,7 @@ fn end_to_end() {
                 trusted_waypoints: TrustedWaypointOpt::default(),
                 rocksdb_opt: RocksdbOpt::default(),
                 concurernt_downloads: ConcurrentDownloadsOpt::default(),
+                enable_write_set_storage: true,
             }
             .try_into()
             .unwrap(),
@@ -143,5 +148,28 @@ fn end_to_end() {
             .collect::<Vec<_>>()
     );
 
+    assert_eq!(
+        tgt_db
+            .get_transaction_outputs(
+                first_ver_to_backup,
+                num_txns_to_restore as u64,
+                target_version
+            )
+            .unwrap()
+            .transactions_and_outputs
+            .into_iter()
+            .map(|(_txn, output)| output.write_set().clone())
+            .collect::<Vec<_>>(),
+        blocks
+            .iter()
+            .flat_map(|(txns, _li)| {
+                txns.iter()
+                    .map(|txn_to_commit| txn_to_commit.write_set().clone())
+            })
+            .skip(first_ver_to_backup as usize)
+            .take(num_txns_to_restore)
+            .collect::<Vec<_>>()
+    );
+
     rt.shutdown_timeout(Duration::from_secs(1));
 }
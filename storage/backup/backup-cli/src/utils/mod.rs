@@ -54,6 +54,7 @@ impl From<RocksdbOpt> for RocksdbConfig {
         Self {
             max_open_files: opt.max_open_files,
             max_total_wal_size: opt.max_total_wal_size,
+            ..Default::default()
         }
     }
 }
@@ -92,6 +93,14 @@ pub struct GlobalRestoreOpt {
 
     #[structopt(flatten)]
     pub concurernt_downloads: ConcurrentDownloadsOpt,
+
+    #[structopt(
+        long,
+        help = "Save the transaction write sets found in the backups, and those produced by \
+        replaying transactions, so the restored node can serve transaction outputs to peers. \
+        Should match `storage.enable_write_set_storage` of the node using the DB."
+    )]
+    pub enable_write_set_storage: bool,
 }
 
 pub enum RestoreRunMode {
@@ -155,12 +164,15 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
         let target_version = opt.target_version.unwrap_or(Version::max_value());
         let concurrent_downloads = opt.concurernt_downloads.get();
         let run_mode = if let Some(db_dir) = &opt.db_dir {
-            let restore_handler = Arc::new(DiemDB::open(
-                db_dir,
-                false, /* read_only */
-                None,  /* pruner */
-                opt.rocksdb_opt.into(),
-            )?)
+            let restore_handler = Arc::new(
+                DiemDB::open(
+                    db_dir,
+                    false, /* read_only */
+                    None,  /* pruner */
+                    opt.rocksdb_opt.into(),
+                )?
+                .with_write_set_storage(opt.enable_write_set_storage),
+            )
             .get_restore_handler();
             RestoreRunMode::Restore { restore_handler }
         } else {
@@ -4,12 +4,14 @@
 mod utils;
 
 use crate::handlers::utils::{
-    handle_rejection, reply_with_async_channel_writer, reply_with_bcs_bytes,
+    handle_rejection, reply_with_async_channel_writer, reply_with_bcs_bytes, reply_with_checkpoint,
     send_size_prefixed_bcs_bytes, unwrap_or_500, LATENCY_HISTOGRAM,
 };
-use diemdb::backup::backup_handler::BackupHandler;
+use diemdb::{backup::backup_handler::BackupHandler, DiemDB};
 use mango_crypto::hash::HashValue;
 use mango_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, path::PathBuf, sync::Arc};
 use warp::{filters::BoxedFilter, reply::Reply, Filter};
 
 static DB_STATE: &str = "db_state";
@@ -19,8 +21,21 @@ static STATE_ROOT_PROOF: &str = "state_root_proof";
 static EPOCH_ENDING_LEDGER_INFOS: &str = "epoch_ending_ledger_infos";
 static TRANSACTIONS: &str = "transactions";
 static TRANSACTION_RANGE_PROOF: &str = "transaction_range_proof";
+static CHECKPOINT: &str = "checkpoint";
 
-pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Reply,)> {
+/// Body of a `checkpoint` request.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct CheckpointRequest {
+    /// Storage dir of the node to create from the checkpoint. Interpreted by the node, it must be
+    /// an absolute path under the configured checkpoint root.
+    pub path: PathBuf,
+}
+
+pub(crate) fn get_routes(
+    backup_handler: BackupHandler,
+    db: Arc<DiemDB>,
+    checkpoint_root: Option<PathBuf>,
+) -> BoxedFilter<(impl Reply,)> {
     // GET db_state
     let bh = backup_handler.clone();
     let db_state = warp::path::end()
@@ -87,7 +102,7 @@ pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Rep
             // latter references the former.
             reply_with_async_channel_writer(&bh, TRANSACTIONS, |bh, sender| async move {
                 send_size_prefixed_bcs_bytes(
-                    bh.get_transaction_iter(start_version, num_transactions),
+                    bh.get_transaction_record_iter(start_version, num_transactions),
                     sender,
                 )
                 .await
@@ -107,6 +122,18 @@ pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Rep
         .map(unwrap_or_500)
         .recover(handle_rejection);
 
+    // POST checkpoint, with a `CheckpointRequest` body
+    let checkpoint = warp::path::end()
+        .and(warp::body::json())
+        .and_then(move |request: CheckpointRequest| {
+            let db = Arc::clone(&db);
+            let checkpoint_root = checkpoint_root.clone();
+            async move {
+                Ok::<_, Infallible>(reply_with_checkpoint(db, checkpoint_root, request.path).await)
+            }
+        })
+        .recover(handle_rejection);
+
     // Route by endpoint name.
     let routes = warp::any()
         .and(warp::path(DB_STATE).and(db_state))
@@ -117,9 +144,10 @@ pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Rep
         .or(warp::path(TRANSACTIONS).and(transactions))
         .or(warp::path(TRANSACTION_RANGE_PROOF).and(transaction_range_proof));
 
-    // Serve all routes for GET only.
+    // Serve all routes for GET only, except for `checkpoint` which changes the file system.
     warp::get()
         .and(routes)
+        .or(warp::path(CHECKPOINT).and(warp::post()).and(checkpoint))
         .with(warp::log::custom(|info| {
             let endpoint = info.path().split('/').nth(1).unwrap_or("-");
             LATENCY_HISTOGRAM
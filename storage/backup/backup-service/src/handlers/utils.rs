@@ -3,7 +3,7 @@
 
 use anyhow::Result;
 use bytes::Bytes;
-use diemdb::backup::backup_handler::BackupHandler;
+use diemdb::{backup::backup_handler::BackupHandler, DiemDB};
 use hyper::Body;
 use mango_logger::prelude::*;
 use mango_metrics::{
@@ -11,8 +11,13 @@ use mango_metrics::{
 };
 use once_cell::sync::Lazy;
 use serde::Serialize;
-use std::{convert::Infallible, future::Future};
-use warp::{reply::Response, Rejection, Reply};
+use std::{
+    convert::Infallible,
+    future::Future,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+use warp::{http::StatusCode, reply::Response, Rejection, Reply};
 
 pub(super) static LATENCY_HISTOGRAM: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -115,6 +120,46 @@ where
     Ok(())
 }
 
+/// Creates a checkpoint of the DB at `path`, off the async runtime since it writes to the disk.
+///
+/// Returns 404 if no checkpoint root is configured and 400 unless `path` is an absolute path under
+/// the checkpoint root.
+pub(super) async fn reply_with_checkpoint(
+    db: Arc<DiemDB>,
+    checkpoint_root: Option<PathBuf>,
+    path: PathBuf,
+) -> Box<dyn Reply> {
+    let checkpoint_root = match checkpoint_root {
+        Some(checkpoint_root) => checkpoint_root,
+        None => return Box::new(StatusCode::NOT_FOUND),
+    };
+    if !is_under_checkpoint_root(&checkpoint_root, &path) {
+        warn!(
+            path = ?path,
+            checkpoint_root = ?checkpoint_root,
+            "Checkpoint request rejected, not under the checkpoint root.",
+        );
+        return Box::new(StatusCode::BAD_REQUEST);
+    }
+
+    let result = tokio::task::spawn_blocking(move || db.create_checkpoint(&path))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|result| result);
+    unwrap_or_500(result.map(|()| Box::new(warp::reply()) as Box<dyn Reply>))
+}
+
+/// Whether `path` is absolute, strictly under `checkpoint_root` and without `..` components, which
+/// could escape it.
+fn is_under_checkpoint_root(checkpoint_root: &Path, path: &Path) -> bool {
+    path.is_absolute()
+        && path.starts_with(checkpoint_root)
+        && path != checkpoint_root
+        && path
+            .components()
+            .all(|component| component != Component::ParentDir)
+}
+
 /// Return 500 on any error raised by the request handler.
 pub(super) fn unwrap_or_500(result: Result<Box<dyn Reply>>) -> Box<dyn Reply> {
     match result {
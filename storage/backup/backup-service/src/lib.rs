@@ -6,12 +6,18 @@ mod handlers;
 use crate::handlers::get_routes;
 use diemdb::DiemDB;
 use mango_logger::prelude::*;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 use tokio::runtime::{Builder, Runtime};
 
-pub fn start_backup_service(address: SocketAddr, db: Arc<DiemDB>) -> Runtime {
+/// Serves the backup endpoints on `address`. The `checkpoint` endpoint, which writes to the file
+/// system, is only served if `checkpoint_root` is provided, and only creates checkpoints under it.
+pub fn start_backup_service(
+    address: SocketAddr,
+    db: Arc<DiemDB>,
+    checkpoint_root: Option<PathBuf>,
+) -> Runtime {
     let backup_handler = db.get_backup_handler();
-    let routes = get_routes(backup_handler);
+    let routes = get_routes(backup_handler, db, checkpoint_root);
 
     let runtime = Builder::new_multi_thread()
         .thread_name("backup")
@@ -36,11 +42,15 @@ pub fn start_backup_service(address: SocketAddr, db: Arc<DiemDB>) -> Runtime {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mango_config::utils::get_available_port;
+    use crate::handlers::CheckpointRequest;
+    use mango_config::{config::RocksdbConfig, utils::get_available_port};
     use mango_crypto::hash::HashValue;
     use mango_temppath::TempPath;
     use reqwest::blocking::get;
-    use std::net::{IpAddr, Ipv4Addr};
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        path::PathBuf,
+    };
 
     /// 404 - endpoint not found
     /// 400 - params not provided or failed parsing
@@ -52,7 +62,11 @@ mod tests {
         let tmpdir = TempPath::new();
         let db = Arc::new(DiemDB::new_for_test(&tmpdir));
         let port = get_available_port();
-        let _rt = start_backup_service(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port), db);
+        let _rt = start_backup_service(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+            db,
+            None, /* checkpoint_root */
+        );
 
         // Endpoint doesn't exist.
         let resp = get(&format!("http://127.0.0.1:{}/", port)).unwrap();
@@ -94,4 +108,81 @@ mod tests {
         assert_eq!(resp.content_length(), None);
         assert!(resp.bytes().is_err());
     }
+
+    #[test]
+    fn checkpoint_disabled() {
+        let tmpdir = TempPath::new();
+        let checkpoint_dir = TempPath::new();
+        let db = Arc::new(DiemDB::new_for_test(&tmpdir));
+        let port = get_available_port();
+        let _rt = start_backup_service(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+            db,
+            None, /* checkpoint_root */
+        );
+
+        let request = CheckpointRequest {
+            path: checkpoint_dir.path().to_path_buf(),
+        };
+        let resp = reqwest::blocking::Client::new()
+            .post(&format!("http://127.0.0.1:{}/checkpoint", port))
+            .json(&request)
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 404);
+        assert!(!checkpoint_dir.path().exists());
+    }
+
+    #[test]
+    fn checkpoint() {
+        let tmpdir = TempPath::new();
+        let checkpoint_root = TempPath::new();
+        checkpoint_root.create_as_dir().unwrap();
+        let checkpoint_dir = checkpoint_root.path().join("node");
+        let db = Arc::new(DiemDB::new_for_test(&tmpdir));
+        let port = get_available_port();
+        let _rt = start_backup_service(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+            db,
+            Some(checkpoint_root.path().to_path_buf()),
+        );
+        let url = format!("http://127.0.0.1:{}/checkpoint", port);
+        let client = reqwest::blocking::Client::new();
+
+        // Only POST is served.
+        assert_eq!(get(&url).unwrap().status(), 405);
+        // Body not provided.
+        assert_eq!(client.post(&url).send().unwrap().status(), 400);
+
+        // Targets outside of the checkpoint root are rejected.
+        for path in [
+            PathBuf::from("node"),
+            checkpoint_root.path().to_path_buf(),
+            checkpoint_root.path().join("..").join("node"),
+            tmpdir.path().join("node"),
+        ]
+        .iter()
+        {
+            let request = CheckpointRequest { path: path.clone() };
+            let resp = client.post(&url).json(&request).send().unwrap();
+            assert_eq!(resp.status(), 400);
+        }
+
+        let request = CheckpointRequest {
+            path: checkpoint_dir.clone(),
+        };
+        let resp = client.post(&url).json(&request).send().unwrap();
+        assert_eq!(resp.status(), 200);
+        DiemDB::open(
+            &checkpoint_dir,
+            true, /* readonly */
+            None, /* pruner */
+            RocksdbConfig::default(),
+        )
+        .unwrap();
+
+        // Checkpoint already exists.
+        let resp = client.post(&url).json(&request).send().unwrap();
+        assert_eq!(resp.status(), 500);
+    }
 }
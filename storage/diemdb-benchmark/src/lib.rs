@@ -20,7 +20,7 @@ use mango_types::{
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     transaction::{ChangeSet, Transaction, TransactionToCommit, WriteSetPayload},
     vm_status::KeptVMStatus,
-    write_set::WriteSetMut,
+    write_set::{WriteSet, WriteSetMut},
 };
 use rand::Rng;
 use std::{
@@ -67,6 +67,7 @@ fn gen_txn_to_commit<R: Rng>(
         txn,
         states,
         None,
+        WriteSet::default(),
         vec![], /* events */
         0,      /* gas_used */
         KeptVMStatus::Executed,
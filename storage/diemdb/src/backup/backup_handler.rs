@@ -21,8 +21,9 @@ use mango_types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::{SparseMerkleRangeProof, TransactionAccumulatorRangeProof, TransactionInfoWithProof},
     transaction::{Transaction, TransactionInfo, Version},
+    write_set::WriteSet,
 };
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeTuple, Deserialize, Serialize, Serializer};
 use std::{fmt, sync::Arc};
 
 /// `BackupHandler` provides functionalities for DiemDB data backup.
@@ -75,6 +76,29 @@ impl BackupHandler {
         Ok(zipped)
     }
 
+    /// Gets an iterator that yields a range of transaction backup records, which carry the write
+    /// sets of the transactions if the DB stores them.
+    pub fn get_transaction_record_iter(
+        &self,
+        start_version: Version,
+        num_transactions: usize,
+    ) -> Result<impl Iterator<Item = Result<TransactionRecord>> + '_> {
+        Ok(self
+            .get_transaction_iter(start_version, num_transactions)?
+            .enumerate()
+            .map(move |(idx, res)| {
+                let (transaction, transaction_info, events) = res?;
+                Ok(TransactionRecord {
+                    transaction,
+                    transaction_info,
+                    events,
+                    write_set: self
+                        .transaction_store
+                        .get_write_set_option(start_version + idx as Version)?,
+                })
+            }))
+    }
+
     /// Gets the proof for a transaction chunk.
     /// N.B. the `LedgerInfo` returned will always be in the same epoch of the `last_version`.
     pub fn get_transaction_range_proof(
@@ -118,6 +142,16 @@ impl BackupHandler {
         Ok(Box::new(iterator))
     }
 
+    /// Gets an iterator which yields the accounts in the state tree whose keys are not less than
+    /// `starting_key`, so a snapshot can be served in chunks.
+    pub fn get_account_iter_from(
+        &self,
+        version: Version,
+        starting_key: HashValue,
+    ) -> Result<impl Iterator<Item = Result<(HashValue, AccountStateBlob)>> + Send + Sync> {
+        JellyfishMerkleIterator::new(Arc::clone(&self.state_store), version, starting_key)
+    }
+
     /// Gets the proof that proves a range of accounts.
     pub fn get_account_state_range_proof(
         &self,
@@ -183,6 +217,54 @@ impl BackupHandler {
     }
 }
 
+/// A transaction in a backup.
+///
+/// Without a write set, the record is serialized as the `(Transaction, TransactionInfo,
+/// Vec<ContractEvent>)` tuple backups have always contained, and with one, as a 4-tuple with the
+/// write set appended. `decode` accepts both.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionRecord {
+    pub transaction: Transaction,
+    pub transaction_info: TransactionInfo,
+    pub events: Vec<ContractEvent>,
+    pub write_set: Option<WriteSet>,
+}
+
+impl TransactionRecord {
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        // BCS rejects both missing and trailing bytes, so at most one of the layouts matches.
+        if let Ok((transaction, transaction_info, events, write_set)) = bcs::from_bytes(bytes) {
+            return Ok(Self {
+                transaction,
+                transaction_info,
+                events,
+                write_set: Some(write_set),
+            });
+        }
+        let (transaction, transaction_info, events) = bcs::from_bytes(bytes)?;
+        Ok(Self {
+            transaction,
+            transaction_info,
+            events,
+            write_set: None,
+        })
+    }
+}
+
+impl Serialize for TransactionRecord {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let len = if self.write_set.is_some() { 4 } else { 3 };
+        let mut tuple = serializer.serialize_tuple(len)?;
+        tuple.serialize_element(&self.transaction)?;
+        tuple.serialize_element(&self.transaction_info)?;
+        tuple.serialize_element(&self.events)?;
+        if let Some(write_set) = &self.write_set {
+            tuple.serialize_element(write_set)?;
+        }
+        tuple.end()
+    }
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct DbState {
     pub epoch: u64,
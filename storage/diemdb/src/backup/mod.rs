@@ -3,6 +3,7 @@
 
 pub mod backup_handler;
 pub mod restore_handler;
+pub(crate) mod restore_utils;
 
 #[cfg(test)]
 mod test;
@@ -2,23 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    change_set::ChangeSet, event_store::EventStore, ledger_store::LedgerStore,
-    schema::transaction_accumulator::TransactionAccumulatorSchema, state_store::StateStore,
-    transaction_store::TransactionStore, DiemDB,
+    backup::restore_utils, event_store::EventStore, ledger_store::LedgerStore,
+    state_store::StateStore, transaction_store::TransactionStore, DiemDB,
 };
-use anyhow::{ensure, Result};
+use anyhow::Result;
 use diem_jellyfish_merkle::restore::JellyfishMerkleRestore;
 use mango_crypto::{hash::SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue};
 use mango_types::{
     account_state_blob::AccountStateBlob,
     contract_event::ContractEvent,
     ledger_info::LedgerInfoWithSignatures,
-    proof::{definition::LeafCount, position::FrozenSubTreeIterator},
+    proof::{definition::LeafCount, SparseMerkleRangeProof},
     transaction::{Transaction, TransactionInfo, Version, PRE_GENESIS_VERSION},
+    write_set::WriteSet,
 };
 use schemadb::DB;
 use std::sync::Arc;
-use storage_interface::{DbReader, TreeState};
+use storage_interface::{DbReader, StateSnapshotReceiver, TreeState};
 
 /// Provides functionalities for DiemDB data restore.
 #[derive(Clone)]
@@ -63,25 +63,7 @@ impl RestoreHandler {
     }
 
     pub fn save_ledger_infos(&self, ledger_infos: &[LedgerInfoWithSignatures]) -> Result<()> {
-        ensure!(!ledger_infos.is_empty(), "No LedgerInfos to save.");
-
-        let mut cs = ChangeSet::new();
-        ledger_infos
-            .iter()
-            .map(|li| self.ledger_store.put_ledger_info(li, &mut cs))
-            .collect::<Result<Vec<_>>>()?;
-        self.db.write_schemas(cs.batch)?;
-
-        if let Some(li) = self.ledger_store.get_latest_ledger_info_option() {
-            if li.ledger_info().epoch() > ledger_infos.last().unwrap().ledger_info().epoch() {
-                // No need to update latest ledger info.
-                return Ok(());
-            }
-        }
-
-        self.ledger_store
-            .set_latest_ledger_info(ledger_infos.last().unwrap().clone());
-        Ok(())
+        restore_utils::save_ledger_infos(&self.db, &self.ledger_store, ledger_infos)
     }
 
     pub fn confirm_or_save_frozen_subtrees(
@@ -89,34 +71,7 @@ impl RestoreHandler {
         num_leaves: LeafCount,
         frozen_subtrees: &[HashValue],
     ) -> Result<()> {
-        let mut cs = ChangeSet::new();
-        let positions: Vec<_> = FrozenSubTreeIterator::new(num_leaves).collect();
-
-        ensure!(
-            positions.len() == frozen_subtrees.len(),
-            "Number of frozen subtree roots not expected. Expected: {}, actual: {}",
-            positions.len(),
-            frozen_subtrees.len(),
-        );
-
-        positions
-            .iter()
-            .zip(frozen_subtrees.iter().rev())
-            .map(|(p, h)| {
-                if let Some(_h) = self.db.get::<TransactionAccumulatorSchema>(p)? {
-                    ensure!(
-                        h == &_h,
-                        "Frozen subtree root does not match that already in DB. Provided: {}, in db: {}.",
-                        h,
-                        _h,
-                    );
-                } else {
-                    cs.batch.put::<TransactionAccumulatorSchema>(p, h)?;
-                }
-                Ok(())
-            })
-            .collect::<Result<Vec<_>>>()?;
-        self.db.write_schemas(cs.batch)
+        restore_utils::confirm_or_save_frozen_subtrees(&self.db, num_leaves, frozen_subtrees)
     }
 
     pub fn save_transactions(
@@ -125,18 +80,19 @@ impl RestoreHandler {
         txns: &[Transaction],
         txn_infos: &[TransactionInfo],
         events: &[Vec<ContractEvent>],
+        write_sets: &[Option<WriteSet>],
     ) -> Result<()> {
-        let mut cs = ChangeSet::new();
-        for (idx, txn) in txns.iter().enumerate() {
-            self.transaction_store
-                .put_transaction(first_version + idx as Version, txn, &mut cs)?;
-        }
-        self.ledger_store
-            .put_transaction_infos(first_version, txn_infos, &mut cs)?;
-        self.event_store
-            .put_events_multiple_versions(first_version, events, &mut cs)?;
-
-        self.db.write_schemas(cs.batch)
+        restore_utils::save_transactions(
+            &self.db,
+            &self.ledger_store,
+            &self.transaction_store,
+            &self.event_store,
+            first_version,
+            txns,
+            txn_infos,
+            events,
+            write_sets,
+        )
     }
 
     pub fn get_tree_state(&self, num_transactions: LeafCount) -> Result<TreeState> {
@@ -165,3 +121,20 @@ impl RestoreHandler {
             .map_or(0, |(ver, _txn_info)| ver + 1))
     }
 }
+
+/// Restores a state snapshot through the `StateSnapshotReceiver` interface of `DbWriter`.
+pub(crate) struct StateSnapshotRestore(pub(crate) JellyfishMerkleRestore<AccountStateBlob>);
+
+impl StateSnapshotReceiver for StateSnapshotRestore {
+    fn add_chunk(
+        &mut self,
+        chunk: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<()> {
+        self.0.add_chunk(chunk, proof)
+    }
+
+    fn finish_box(self: Box<Self>) -> Result<()> {
+        self.0.finish()
+    }
+}
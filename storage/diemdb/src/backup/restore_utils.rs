@@ -0,0 +1,110 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module contains the restore routines shared by [`RestoreHandler`] and the state snapshot
+//! restore exposed through `DbWriter`.
+//!
+//! [`RestoreHandler`]: crate::backup::restore_handler::RestoreHandler
+
+use crate::{
+    change_set::ChangeSet, event_store::EventStore, ledger_store::LedgerStore,
+    schema::transaction_accumulator::TransactionAccumulatorSchema,
+    transaction_store::TransactionStore,
+};
+use anyhow::{ensure, Result};
+use mango_crypto::HashValue;
+use mango_types::{
+    contract_event::ContractEvent,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{definition::LeafCount, position::FrozenSubTreeIterator},
+    transaction::{Transaction, TransactionInfo, Version},
+    write_set::WriteSet,
+};
+use schemadb::DB;
+
+pub(crate) fn save_ledger_infos(
+    db: &DB,
+    ledger_store: &LedgerStore,
+    ledger_infos: &[LedgerInfoWithSignatures],
+) -> Result<()> {
+    ensure!(!ledger_infos.is_empty(), "No LedgerInfos to save.");
+
+    let mut cs = ChangeSet::new();
+    ledger_infos
+        .iter()
+        .map(|li| ledger_store.put_ledger_info(li, &mut cs))
+        .collect::<Result<Vec<_>>>()?;
+    db.write_schemas(cs.batch)?;
+
+    if let Some(li) = ledger_store.get_latest_ledger_info_option() {
+        if li.ledger_info().epoch() > ledger_infos.last().unwrap().ledger_info().epoch() {
+            // No need to update latest ledger info.
+            return Ok(());
+        }
+    }
+
+    ledger_store.set_latest_ledger_info(ledger_infos.last().unwrap().clone());
+    Ok(())
+}
+
+pub(crate) fn confirm_or_save_frozen_subtrees(
+    db: &DB,
+    num_leaves: LeafCount,
+    frozen_subtrees: &[HashValue],
+) -> Result<()> {
+    let mut cs = ChangeSet::new();
+    let positions: Vec<_> = FrozenSubTreeIterator::new(num_leaves).collect();
+
+    ensure!(
+        positions.len() == frozen_subtrees.len(),
+        "Number of frozen subtree roots not expected. Expected: {}, actual: {}",
+        positions.len(),
+        frozen_subtrees.len(),
+    );
+
+    positions
+        .iter()
+        .zip(frozen_subtrees.iter().rev())
+        .map(|(p, h)| {
+            if let Some(_h) = db.get::<TransactionAccumulatorSchema>(p)? {
+                ensure!(
+                    h == &_h,
+                    "Frozen subtree root does not match that already in DB. Provided: {}, in db: {}.",
+                    h,
+                    _h,
+                );
+            } else {
+                cs.batch.put::<TransactionAccumulatorSchema>(p, h)?;
+            }
+            Ok(())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    db.write_schemas(cs.batch)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn save_transactions(
+    db: &DB,
+    ledger_store: &LedgerStore,
+    transaction_store: &TransactionStore,
+    event_store: &EventStore,
+    first_version: Version,
+    txns: &[Transaction],
+    txn_infos: &[TransactionInfo],
+    events: &[Vec<ContractEvent>],
+    write_sets: &[Option<WriteSet>],
+) -> Result<()> {
+    let mut cs = ChangeSet::new();
+    for (idx, txn) in txns.iter().enumerate() {
+        transaction_store.put_transaction(first_version + idx as Version, txn, &mut cs)?;
+    }
+    for (idx, write_set) in write_sets.iter().enumerate() {
+        if let Some(write_set) = write_set {
+            transaction_store.put_write_set(first_version + idx as Version, write_set, &mut cs)?;
+        }
+    }
+    ledger_store.put_transaction_infos(first_version, txn_infos, &mut cs)?;
+    event_store.put_events_multiple_versions(first_version, events, &mut cs)?;
+
+    db.write_schemas(cs.batch)
+}
@@ -1,9 +1,8 @@
 // Copyright (c) The Mango Blockchain Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{test_helper::arb_blocks_to_commit, DiemDB};
+use crate::{backup::backup_handler::TransactionRecord, test_helper::arb_blocks_to_commit, DiemDB};
 use anyhow::Result;
-use mango_temppath::TempPath;
 use proptest::prelude::*;
 use storage_interface::DbWriter;
 
@@ -12,8 +11,7 @@ proptest! {
 
     #[test]
     fn test_get_transaction_iter(input in arb_blocks_to_commit()) {
-        let tmp_dir = TempPath::new();
-        let db = DiemDB::new_for_test(&tmp_dir);
+        let db = DiemDB::new_in_memory_for_test();
 
         let mut cur_ver = 0;
         for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
@@ -41,4 +39,56 @@ proptest! {
             .unwrap();
         prop_assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_get_transaction_record_iter(input in arb_blocks_to_commit()) {
+        let db = DiemDB::new_in_memory_for_test();
+
+        let mut cur_ver = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+            db.save_transactions(txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+                .unwrap();
+            cur_ver += txns_to_commit.len() as u64;
+        }
+
+        let expected_write_sets: Vec<_> = input
+            .iter()
+            .flat_map(|(txns_to_commit, _ledger_info_with_sigs)| {
+                txns_to_commit
+                    .iter()
+                    .map(|txn_to_commit| Some(txn_to_commit.write_set().clone()))
+            })
+            .collect();
+
+        let records = db
+            .get_backup_handler()
+            .get_transaction_record_iter(0, cur_ver as usize)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        prop_assert_eq!(
+            records.iter().map(|r| r.write_set.clone()).collect::<Vec<_>>(),
+            expected_write_sets
+        );
+
+        for record in records {
+            // Records with a write set round trip.
+            let bytes = bcs::to_bytes(&record).unwrap();
+            prop_assert_eq!(&TransactionRecord::decode(&bytes).unwrap(), &record);
+
+            // Records in the legacy format decode without one.
+            let legacy_bytes = bcs::to_bytes(&(
+                &record.transaction,
+                &record.transaction_info,
+                &record.events,
+            ))
+            .unwrap();
+            let legacy = TransactionRecord::decode(&legacy_bytes).unwrap();
+            prop_assert!(legacy.write_set.is_none());
+            prop_assert_eq!(
+                bcs::to_bytes(&legacy).unwrap(),
+                legacy_bytes
+            );
+        }
+    }
 }
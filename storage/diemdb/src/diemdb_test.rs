@@ -78,8 +78,7 @@ fn verify_epochs(db: &DiemDB, ledger_infos_with_sigs: &[LedgerInfoWithSignatures
 }
 
 pub fn test_save_blocks_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
 
     let num_batches = input.len();
     let mut cur_ver = 0;
@@ -139,9 +138,120 @@ pub fn test_save_blocks_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWit
     verify_epochs(&db, &ledger_infos_with_sigs);
 }
 
-fn test_sync_transactions_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+fn test_write_set_storage_disabled_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let db = DiemDB::new_in_memory_for_test().with_write_set_storage(false);
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+
+    // The transactions are served, but not their outputs.
+    let ledger_version = cur_ver - 1;
+    assert_eq!(
+        db.get_transactions(0, cur_ver, ledger_version, false)
+            .unwrap()
+            .transactions
+            .len() as u64,
+        cur_ver
+    );
+    assert!(db.get_transaction_outputs(0, 1, ledger_version).is_err());
+    assert!(db
+        .transaction_store
+        .get_write_set_option(0)
+        .unwrap()
+        .is_none());
+}
+
+fn test_catch_up_with_primary_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
     let tmp_dir = TempPath::new();
+    let tmp_dir_sec = TempPath::new();
     let db = DiemDB::new_for_test(&tmp_dir);
+    let db_sec =
+        DiemDB::open_as_secondary(tmp_dir.path(), tmp_dir_sec.path(), RocksdbConfig::default())
+            .unwrap();
+    assert!(db_sec
+        .ledger_store
+        .get_latest_ledger_info_option()
+        .is_none());
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+        )
+        .unwrap();
+
+        db_sec.try_catch_up_with_primary().unwrap();
+        assert_eq!(
+            db_sec.get_latest_ledger_info().unwrap(),
+            *ledger_info_with_sigs
+        );
+        verify_committed_transactions(
+            &db_sec,
+            txns_to_commit,
+            cur_ver,
+            ledger_info_with_sigs,
+            true, /* is_latest */
+        );
+
+        cur_ver += txns_to_commit.len() as u64;
+    }
+}
+
+fn test_create_checkpoint_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let checkpoint_dir = TempPath::new();
+    let db = DiemDB::new_for_test(&tmp_dir);
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    db.create_checkpoint(&checkpoint_dir).unwrap();
+
+    let checkpoint = DiemDB::new_for_test(&checkpoint_dir);
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        verify_committed_transactions(
+            &checkpoint,
+            txns_to_commit,
+            cur_ver,
+            ledger_info_with_sigs,
+            false, /* is_latest */
+        );
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    assert_eq!(
+        checkpoint.get_latest_ledger_info().unwrap(),
+        db.get_latest_ledger_info().unwrap(),
+    );
+    assert_eq!(
+        checkpoint.get_latest_tree_state().unwrap(),
+        db.get_latest_tree_state().unwrap(),
+    );
+}
+
+fn test_sync_transactions_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let db = DiemDB::new_in_memory_for_test();
 
     let num_batches = input.len();
     let mut cur_ver = 0;
@@ -519,6 +629,19 @@ fn verify_committed_transactions(
             .unwrap();
         assert_eq!(txn_list_with_proof.len(), 1);
 
+        let output_list_with_proof = db
+            .get_transaction_outputs(cur_ver, 1, ledger_version)
+            .unwrap();
+        output_list_with_proof
+            .verify(ledger_info, Some(cur_ver))
+            .unwrap();
+        assert_eq!(
+            output_list_with_proof.transactions_and_outputs[0]
+                .1
+                .write_set(),
+            txn_to_commit.write_set()
+        );
+
         // Fetch and verify account states.
         for (addr, expected_blob) in txn_to_commit.account_states() {
             let account_state_with_proof = db
@@ -557,6 +680,21 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit()) {
         test_sync_transactions_impl(input);
     }
+
+    #[test]
+    fn test_catch_up_with_primary(input in arb_blocks_to_commit()) {
+        test_catch_up_with_primary_impl(input);
+    }
+
+    #[test]
+    fn test_create_checkpoint(input in arb_blocks_to_commit()) {
+        test_create_checkpoint_impl(input);
+    }
+
+    #[test]
+    fn test_write_set_storage_disabled(input in arb_blocks_to_commit()) {
+        test_write_set_storage_disabled_impl(input);
+    }
 }
 
 #[test]
@@ -594,16 +732,14 @@ fn test_get_first_seq_num_and_limit() {
 
 #[test]
 fn test_too_many_requested() {
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
 
     assert!(db.get_transactions(0, 1001 /* limit */, 0, true).is_err());
 }
 
 #[test]
 fn test_get_latest_tree_state() {
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
 
     // entirely emtpy db
     let empty = db.get_latest_tree_state().unwrap();
@@ -641,6 +777,64 @@ fn test_get_latest_tree_state() {
     );
 }
 
+#[test]
+fn test_open_with_rocksdb_cf_config() {
+    let tmp_dir = TempPath::new();
+    let mut rocksdb_config = RocksdbConfig {
+        block_cache_size: Some(1 << 20),
+        ..Default::default()
+    };
+    rocksdb_config.column_families.insert(
+        EVENT_CF_NAME.to_string(),
+        mango_config::config::RocksdbCfConfig {
+            compression: RocksdbCompression::Zstd,
+            block_cache_share: 3,
+            bloom_filter_bits_per_key: Some(10),
+            ..Default::default()
+        },
+    );
+    let capacity = |db: &DiemDB, cf_name| {
+        db.db
+            .get_property(cf_name, "rocksdb.block-cache-capacity")
+            .unwrap()
+    };
+
+    let db = DiemDB::open(&tmp_dir, false, None, rocksdb_config.clone()).unwrap();
+    assert!(capacity(&db, EVENT_CF_NAME) > capacity(&db, TRANSACTION_CF_NAME));
+    db.update_rocksdb_properties().unwrap();
+    drop(db);
+
+    // The column family options apply to readonly and secondary instances as well.
+    let db = DiemDB::open(&tmp_dir, true, None, rocksdb_config.clone()).unwrap();
+    assert!(capacity(&db, EVENT_CF_NAME) > capacity(&db, TRANSACTION_CF_NAME));
+    drop(db);
+    let tmp_dir_sec = TempPath::new();
+    let db = DiemDB::open_as_secondary(tmp_dir.path(), tmp_dir_sec.path(), rocksdb_config).unwrap();
+    assert!(capacity(&db, EVENT_CF_NAME) > capacity(&db, TRANSACTION_CF_NAME));
+}
+
+#[test]
+fn test_open_with_unknown_rocksdb_cf_config() {
+    let tmp_dir = TempPath::new();
+    let mut rocksdb_config = RocksdbConfig::default();
+    rocksdb_config.column_families.insert(
+        "transactions".to_string(),
+        mango_config::config::RocksdbCfConfig::default(),
+    );
+    assert!(DiemDB::open(&tmp_dir, false, None, rocksdb_config).is_err());
+}
+
+#[test]
+fn test_parse_rocksdb_tickers() {
+    let statistics = "rocksdb.block.cache.miss COUNT : 12\n\
+                      rocksdb.block.cache.hit COUNT : 34\n\
+                      rocksdb.db.get.micros P50 : 1.000000 P95 : 2.000000 COUNT : 5 SUM : 6\n";
+    let tickers = parse_rocksdb_tickers(statistics);
+    assert_eq!(tickers.get("rocksdb.block.cache.miss"), Some(&12));
+    assert_eq!(tickers.get("rocksdb.block.cache.hit"), Some(&34));
+    assert_eq!(tickers.get("rocksdb.db.get.micros"), None);
+}
+
 fn put_transaction_info(db: &DiemDB, version: Version, txn_info: &TransactionInfo) {
     let mut cs = ChangeSet::new();
     db.ledger_store
@@ -12,7 +12,8 @@ use crate::{
     ledger_counters::{LedgerCounter, LedgerCounterBumps},
     schema::{
         event::EventSchema, event_accumulator::EventAccumulatorSchema,
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+        event_by_key::EventByKeySchema, event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema,
     },
 };
 use accumulator::{HashReader, MerkleAccumulator};
@@ -30,6 +31,7 @@ use mango_types::{
     proof::{position::Position, EventAccumulatorProof, EventProof},
     transaction::Version,
 };
+use move_core_types::language_storage::TypeTag;
 use schemadb::{schema::ValueCodec, ReadOptions, SchemaIterator, DB};
 use std::{
     convert::{TryFrom, TryInto},
@@ -40,11 +42,13 @@ use std::{
 #[derive(Debug)]
 pub(crate) struct EventStore {
     db: Arc<DB>,
+    /// Whether events are indexed by type in `EventByTypeSchema` when saved.
+    index_by_type: bool,
 }
 
 impl EventStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, index_by_type: bool) -> Self {
+        Self { db, index_by_type }
     }
 
     /// Get all of the events given a transaction version.
@@ -83,7 +87,7 @@ impl EventStore {
         })
     }
 
-    fn get_event_by_version_and_index(
+    pub fn get_event_by_version_and_index(
         &self,
         version: Version,
         index: u64,
@@ -207,6 +211,53 @@ impl EventStore {
         Ok(result)
     }
 
+    /// Given `type_tag`, returns at most `limit` events of that type emitted by transactions with
+    /// `start_version <= version < end_version`, identified by transaction version and index among
+    /// all events emitted by the same transaction, in ascending order.
+    ///
+    /// The first `start_index` events of that type emitted by the transaction at `start_version`
+    /// are skipped, so that a page ending in the middle of a transaction can be resumed.
+    pub fn lookup_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_index: u64,
+        end_version: Version,
+        limit: u64,
+    ) -> Result<
+        Vec<(
+            Version, // transaction version it belongs to
+            u64,     // index among events for the same transaction
+        )>,
+    > {
+        ensure!(
+            self.index_by_type,
+            "Event by type index is not enabled in the storage config."
+        );
+        let type_tag_hash = hash_type_tag(type_tag)?;
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(type_tag_hash, start_version, 0))?;
+
+        let mut num_to_skip = start_index;
+        let mut result = Vec::new();
+        for res in iter {
+            if result.len() as u64 >= limit {
+                break;
+            }
+            let ((hash, ver, idx), ()) = res?;
+            if hash != type_tag_hash || ver >= end_version {
+                break;
+            }
+            if ver == start_version && num_to_skip > 0 {
+                num_to_skip -= 1;
+                continue;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
     fn lookup_event_by_key(
         &self,
         event_key: &EventKey,
@@ -251,6 +302,12 @@ impl EventStore {
                     &(*event.key(), version, event.sequence_number()),
                     &(idx as u64),
                 )?;
+                if self.index_by_type {
+                    cs.batch.put::<EventByTypeSchema>(
+                        &(hash_type_tag(event.type_tag())?, version, idx as u64),
+                        &(),
+                    )?;
+                }
                 Ok(())
             })?;
 
@@ -362,6 +419,11 @@ impl EventStore {
     }
 }
 
+/// Key of `type_tag` in `EventByTypeSchema`.
+fn hash_type_tag(type_tag: &TypeTag) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&bcs::to_bytes(type_tag)?))
+}
+
 type Accumulator<'a> = MerkleAccumulator<EventHashReader<'a>, EventAccumulatorHasher>;
 
 struct EventHashReader<'a> {
@@ -6,7 +6,6 @@ use crate::DiemDB;
 use itertools::Itertools;
 use mango_crypto::hash::ACCUMULATOR_PLACEHOLDER_HASH;
 use mango_proptest_helpers::Index;
-use mango_temppath::TempPath;
 use mango_types::{
     account_address::AccountAddress,
     contract_event::ContractEvent,
@@ -36,8 +35,7 @@ fn save(store: &EventStore, version: Version, events: &[ContractEvent]) -> HashV
 
 #[test]
 fn test_put_empty() {
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
     let store = &db.event_store;
     let mut cs = ChangeSet::new();
     assert_eq!(
@@ -48,8 +46,7 @@ fn test_put_empty() {
 
 #[test]
 fn test_error_on_get_from_empty() {
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
     let store = &db.event_store;
 
     assert!(store
@@ -62,8 +59,7 @@ proptest! {
 
     #[test]
     fn test_put_get_verify(events in vec(any::<ContractEvent>().no_shrink(), 1..100)) {
-        let tmp_dir = TempPath::new();
-        let db = DiemDB::new_for_test(&tmp_dir);
+        let db = DiemDB::new_in_memory_for_test();
         let store = &db.event_store;
 
         let root_hash = save(store, 100, &events);
@@ -94,8 +90,7 @@ proptest! {
         events3 in vec(any::<ContractEvent>().no_shrink(), 1..100),
     ) {
 
-        let tmp_dir = TempPath::new();
-        let db = DiemDB::new_for_test(&tmp_dir);
+        let db = DiemDB::new_in_memory_for_test();
         let store = &db.event_store;
         // Save 3 chunks at different versions
         save(store, 99 /*version*/, &events1);
@@ -186,8 +181,7 @@ proptest! {
 
 fn test_index_get_impl(event_batches: Vec<Vec<ContractEvent>>) {
     // Put into db.
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
     let store = &db.event_store;
 
     let mut cs = ChangeSet::new();
@@ -287,6 +281,7 @@ prop_compose! {
                 address, // proposer
                 Vec::new(), // prev block voters
                 timestamp,
+                Vec::new(), // failed proposers
             );
             let event = ContractEvent::new(
                 new_block_event_key(),
@@ -301,8 +296,7 @@ prop_compose! {
 }
 
 fn test_get_last_version_before_timestamp_impl(new_block_events: Vec<(Version, ContractEvent)>) {
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
     let store = &db.event_store;
     // error on no blocks
     assert!(store.get_last_version_before_timestamp(1000, 2000).is_err());
@@ -367,3 +361,116 @@ proptest! {
         test_get_last_version_before_timestamp_impl(new_block_events)
     }
 }
+
+fn traverse_events_by_type(
+    store: &EventStore,
+    type_tag: &TypeTag,
+    end_version: Version,
+) -> Vec<(Version, ContractEvent)> {
+    const LIMIT: u64 = 3;
+
+    let mut start_version = 0;
+    let mut start_index = 0;
+    let mut events = Vec::new();
+    loop {
+        let batch = store
+            .lookup_events_by_type(type_tag, start_version, start_index, end_version, LIMIT)
+            .unwrap();
+        let last_version = match batch.last() {
+            Some((ver, _)) => *ver,
+            None => break,
+        };
+        assert!(batch.first().unwrap().0 >= start_version);
+        assert!(last_version < end_version);
+        assert!(batch.len() as u64 <= LIMIT);
+
+        // Resume after the events of the last transaction returned so far.
+        let num_in_last_version = batch.iter().filter(|(ver, _)| *ver == last_version).count();
+        if last_version != start_version {
+            start_index = 0;
+        }
+        start_index += num_in_last_version as u64;
+        start_version = last_version;
+        events.extend(
+            batch
+                .into_iter()
+                .map(|(ver, idx)| (ver, store.get_event_by_version_and_index(ver, idx).unwrap())),
+        );
+    }
+
+    events
+}
+
+fn test_index_by_type_impl(event_batches: Vec<Vec<ContractEvent>>) {
+    let db = DiemDB::new_in_memory_for_test();
+    let store = EventStore::new(Arc::clone(&db.db), true /* index_by_type */);
+
+    let mut cs = ChangeSet::new();
+    event_batches.iter().enumerate().for_each(|(ver, events)| {
+        store.put_events(ver as u64, events, &mut cs).unwrap();
+    });
+    store.db.write_schemas(cs.batch).unwrap();
+
+    let mut events_by_type = HashMap::new();
+    event_batches
+        .into_iter()
+        .enumerate()
+        .for_each(|(ver, batch)| {
+            batch.into_iter().for_each(|e| {
+                events_by_type
+                    .entry(e.type_tag().clone())
+                    .or_insert_with(Vec::new)
+                    .push((ver as Version, e));
+            })
+        });
+
+    events_by_type
+        .into_iter()
+        .for_each(|(type_tag, events_and_versions)| {
+            let end_version = events_and_versions.last().unwrap().0 + 1;
+            assert_eq!(
+                traverse_events_by_type(&store, &type_tag, end_version),
+                events_and_versions,
+            );
+            // `end_version` is exclusive.
+            let first_version = events_and_versions.first().unwrap().0;
+            assert!(store
+                .lookup_events_by_type(&type_tag, 0, 0, first_version, 100)
+                .unwrap()
+                .is_empty());
+        });
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_index_by_type(
+        event_pool in vec(any::<ContractEvent>().no_shrink(), 1..5),
+        index_batches in vec(vec(any::<Index>(), 0..6), 0..50),
+    ) {
+        // Draw events from a small pool so that several events of one type are emitted by the
+        // same and by different transactions.
+        let event_batches = index_batches
+            .into_iter()
+            .map(|indices| {
+                indices
+                    .into_iter()
+                    .map(|index| event_pool[index.index(event_pool.len())].clone())
+                    .collect()
+            })
+            .collect();
+
+        test_index_by_type_impl(event_batches);
+    }
+}
+
+#[test]
+fn test_index_by_type_disabled() {
+    let db = DiemDB::new_in_memory_for_test();
+
+    assert!(db
+        .event_store
+        .lookup_events_by_type(&TypeTag::Bool, 0, 0, 1, 10)
+        .is_err());
+}
@@ -3,7 +3,6 @@
 
 use super::*;
 use crate::DiemDB;
-use mango_temppath::TempPath;
 use proptest::{collection::vec, prelude::*};
 
 fn verify(
@@ -52,8 +51,7 @@ proptest! {
         batch1 in vec(any::<TransactionInfo>(), 1..100),
         batch2 in vec(any::<TransactionInfo>(), 1..100),
     ) {
-        let tmp_dir = TempPath::new();
-        let db = DiemDB::new_for_test(&tmp_dir);
+        let db = DiemDB::new_in_memory_for_test();
         let store = &db.ledger_store;
 
         // insert two batches of transaction infos
@@ -83,8 +81,7 @@ proptest! {
                     (Just(infos), Just(start_version), 0..num_infos as usize * 2)
                 })
     ) {
-        let tmp_dir = TempPath::new();
-        let db = DiemDB::new_for_test(&tmp_dir);
+        let db = DiemDB::new_in_memory_for_test();
         let store = &db.ledger_store;
         save(store, 0, &infos);
 
@@ -38,7 +38,11 @@ mod diemdb_test;
 pub use diemdb_test::test_save_blocks_impl;
 
 use crate::{
-    backup::{backup_handler::BackupHandler, restore_handler::RestoreHandler},
+    backup::{
+        backup_handler::BackupHandler,
+        restore_handler::{RestoreHandler, StateSnapshotRestore},
+        restore_utils,
+    },
     change_set::{ChangeSet, SealedChangeSet},
     errors::DiemDbError,
     event_store::EventStore,
@@ -48,7 +52,7 @@ use crate::{
         DIEM_STORAGE_API_LATENCY_SECONDS, DIEM_STORAGE_COMMITTED_TXNS,
         DIEM_STORAGE_LATEST_TXN_VERSION, DIEM_STORAGE_LEDGER_VERSION,
         DIEM_STORAGE_NEXT_BLOCK_EPOCH, DIEM_STORAGE_OTHER_TIMERS_SECONDS,
-        DIEM_STORAGE_ROCKSDB_PROPERTIES,
+        DIEM_STORAGE_ROCKSDB_PROPERTIES, DIEM_STORAGE_ROCKSDB_TICKERS,
     },
     pruner::Pruner,
     schema::*,
@@ -57,8 +61,9 @@ use crate::{
     transaction_store::TransactionStore,
 };
 use anyhow::{ensure, format_err, Result};
+use diem_jellyfish_merkle::{iterator::JellyfishMerkleIterator, restore::JellyfishMerkleRestore};
 use itertools::{izip, zip_eq};
-use mango_config::config::RocksdbConfig;
+use mango_config::config::{RocksdbCompression, RocksdbConfig};
 use mango_crypto::hash::{CryptoHash, HashValue, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use mango_logger::prelude::*;
 use mango_types::{
@@ -71,20 +76,23 @@ use mango_types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::{
         AccountStateProof, AccumulatorConsistencyProof, EventProof, SparseMerkleProof,
-        TransactionListProof,
+        SparseMerkleRangeProof, TransactionListProof,
     },
     state_proof::StateProof,
     transaction::{
-        AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof,
-        TransactionToCommit, TransactionWithProof, Version, PRE_GENESIS_VERSION,
+        AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof, TransactionOutput,
+        TransactionOutputListWithProof, TransactionStatus, TransactionToCommit,
+        TransactionWithProof, Version, PRE_GENESIS_VERSION,
     },
 };
 use move_core_types::{
-    language_storage::{ModuleId, StructTag},
+    language_storage::{ModuleId, StructTag, TypeTag},
     resolver::{ModuleResolver, ResourceResolver},
 };
 use once_cell::sync::Lazy;
-use schemadb::{ColumnFamilyName, Options, DB, DEFAULT_CF_NAME};
+use schemadb::{
+    BlockBasedOptions, Cache, ColumnFamilyName, DBCompressionType, Options, DB, DEFAULT_CF_NAME,
+};
 use std::{
     collections::HashMap,
     convert::TryFrom,
@@ -94,7 +102,9 @@ use std::{
     thread::{self, JoinHandle},
     time::{Duration, Instant},
 };
-use storage_interface::{DbReader, DbWriter, MoveDbReader, Order, StartupInfo, TreeState};
+use storage_interface::{
+    DbReader, DbWriter, MoveDbReader, Order, StartupInfo, StateSnapshotReceiver, TreeState,
+};
 
 const MAX_LIMIT: u64 = 1000;
 
@@ -128,6 +138,22 @@ static ROCKSDB_PROPERTY_MAP: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
             "diem_rocksdb_cf_size_bytes",
             "rocksdb.estimate-live-data-size",
         ),
+        (
+            "diem_rocksdb_total_sst_files_size_bytes",
+            "rocksdb.total-sst-files-size",
+        ),
+        (
+            "diem_rocksdb_estimate_num_keys",
+            "rocksdb.estimate-num-keys",
+        ),
+        (
+            "diem_rocksdb_block_cache_capacity_bytes",
+            "rocksdb.block-cache-capacity",
+        ),
+        (
+            "diem_rocksdb_block_cache_pinned_usage_bytes",
+            "rocksdb.block-cache-pinned-usage",
+        ),
     ]
     .iter()
     .cloned()
@@ -142,13 +168,91 @@ fn error_if_too_many_requested(num_requested: u64, max_allowed: u64) -> Result<(
     }
 }
 
+/// RocksDB statistics tickers reported to `DIEM_STORAGE_ROCKSDB_TICKERS`.
+const ROCKSDB_TICKERS: &[&str] = &[
+    "rocksdb.block.cache.hit",
+    "rocksdb.block.cache.miss",
+    "rocksdb.block.cache.data.hit",
+    "rocksdb.block.cache.data.miss",
+    "rocksdb.block.cache.index.hit",
+    "rocksdb.block.cache.index.miss",
+    "rocksdb.block.cache.filter.hit",
+    "rocksdb.block.cache.filter.miss",
+    "rocksdb.bloom.filter.useful",
+];
+
 fn gen_rocksdb_options(config: &RocksdbConfig) -> Options {
     let mut db_opts = Options::default();
     db_opts.set_max_open_files(config.max_open_files);
     db_opts.set_max_total_wal_size(config.max_total_wal_size);
+    if config.enable_statistics {
+        db_opts.enable_statistics();
+    }
     db_opts
 }
 
+fn gen_cf_options(
+    config: &RocksdbConfig,
+    column_families: Vec<ColumnFamilyName>,
+) -> Result<Vec<(ColumnFamilyName, Options)>> {
+    // A misspelled column family would silently get the default options otherwise.
+    for cf_name in config.column_families.keys() {
+        ensure!(
+            column_families.contains(&cf_name.as_str()),
+            "Unknown column family in the RocksDB config: {}",
+            cf_name,
+        );
+    }
+    let total_shares: u64 = column_families
+        .iter()
+        .map(|cf_name| config.cf_config(cf_name).block_cache_share as u64)
+        .sum();
+
+    column_families
+        .into_iter()
+        .map(|cf_name| {
+            let cf_config = config.cf_config(cf_name);
+            let mut cf_opts = Options::default();
+            cf_opts.set_compression_type(match cf_config.compression {
+                RocksdbCompression::None => DBCompressionType::None,
+                RocksdbCompression::Snappy => DBCompressionType::Snappy,
+                RocksdbCompression::Lz4 => DBCompressionType::Lz4,
+                RocksdbCompression::Zstd => DBCompressionType::Zstd,
+            });
+            cf_opts.set_write_buffer_size(cf_config.write_buffer_size as usize);
+
+            let mut table_opts = BlockBasedOptions::default();
+            if let Some(block_cache_size) = config.block_cache_size {
+                let cache_size = if total_shares == 0 {
+                    0
+                } else {
+                    block_cache_size as u128 * cf_config.block_cache_share as u128
+                        / total_shares as u128
+                };
+                table_opts.set_block_cache(&Cache::new_lru_cache(cache_size as usize)?);
+            }
+            if let Some(bits_per_key) = cf_config.bloom_filter_bits_per_key {
+                table_opts.set_bloom_filter(bits_per_key as f64, false);
+            }
+            cf_opts.set_block_based_table_factory(&table_opts);
+
+            Ok((cf_name, cf_opts))
+        })
+        .collect()
+}
+
+/// Parses the ticker counts out of a RocksDB statistics dump, in which a ticker is reported as
+/// a line like `rocksdb.block.cache.hit COUNT : 42`.
+fn parse_rocksdb_tickers(statistics: &str) -> HashMap<&str, u64> {
+    statistics
+        .lines()
+        .filter_map(|line| {
+            let (name, count) = line.split_once(" COUNT : ")?;
+            Some((name.trim(), count.trim().parse().ok()?))
+        })
+        .collect()
+}
+
 fn update_rocksdb_properties(db: &DB) -> Result<()> {
     let _timer = DIEM_STORAGE_OTHER_TIMERS_SECONDS
         .with_label_values(&["update_rocksdb_properties"])
@@ -160,6 +264,16 @@ fn update_rocksdb_properties(db: &DB) -> Result<()> {
                 .set(db.get_property(cf_name, rocksdb_property_argument)? as i64);
         }
     }
+    if let Some(statistics) = db.get_statistics()? {
+        let tickers = parse_rocksdb_tickers(&statistics);
+        for ticker in ROCKSDB_TICKERS {
+            if let Some(count) = tickers.get(ticker) {
+                DIEM_STORAGE_ROCKSDB_TICKERS
+                    .with_label_values(&[ticker])
+                    .set(*count as i64);
+            }
+        }
+    }
     Ok(())
 }
 
@@ -215,7 +329,7 @@ pub struct DiemDB {
     state_store: Arc<StateStore>,
     event_store: Arc<EventStore>,
     system_store: SystemStore,
-    rocksdb_property_reporter: RocksdbPropertyReporter,
+    rocksdb_property_reporter: Option<RocksdbPropertyReporter>,
     pruner: Option<Pruner>,
 }
 
@@ -226,6 +340,7 @@ impl DiemDB {
             EPOCH_BY_VERSION_CF_NAME,
             EVENT_ACCUMULATOR_CF_NAME,
             EVENT_BY_KEY_CF_NAME,
+            EVENT_BY_TYPE_CF_NAME,
             EVENT_BY_VERSION_CF_NAME,
             EVENT_CF_NAME,
             JELLYFISH_MERKLE_NODE_CF_NAME,
@@ -234,7 +349,9 @@ impl DiemDB {
             TRANSACTION_CF_NAME,
             TRANSACTION_ACCUMULATOR_CF_NAME,
             TRANSACTION_BY_ACCOUNT_CF_NAME,
+            TRANSACTION_BY_HASH_CF_NAME,
             TRANSACTION_INFO_CF_NAME,
+            WRITE_SET_CF_NAME,
         ]
     }
 
@@ -243,12 +360,20 @@ impl DiemDB {
 
         DiemDB {
             db: Arc::clone(&db),
-            event_store: Arc::new(EventStore::new(Arc::clone(&db))),
+            event_store: Arc::new(EventStore::new(
+                Arc::clone(&db),
+                false, /* index_by_type */
+            )),
             ledger_store: Arc::new(LedgerStore::new(Arc::clone(&db))),
             state_store: Arc::new(StateStore::new(Arc::clone(&db))),
-            transaction_store: Arc::new(TransactionStore::new(Arc::clone(&db))),
+            transaction_store: Arc::new(TransactionStore::new(
+                Arc::clone(&db),
+                false, /* store_write_sets */
+            )),
             system_store: SystemStore::new(Arc::clone(&db)),
-            rocksdb_property_reporter: RocksdbPropertyReporter::new(Arc::clone(&db)),
+            // An in-memory DB doesn't have RocksDB properties to report.
+            rocksdb_property_reporter: (!db.is_in_memory())
+                .then(|| RocksdbPropertyReporter::new(Arc::clone(&db))),
             pruner: prune_window.map(|n| Pruner::new(Arc::clone(&db), n)),
         }
     }
@@ -270,24 +395,34 @@ impl DiemDB {
         let mut rocksdb_opts = gen_rocksdb_options(&rocksdb_config);
 
         let db = if readonly {
-            DB::open_readonly(
+            DB::open_readonly_with_cf_options(
                 path.clone(),
                 "diemdb_ro",
-                Self::column_families(),
+                gen_cf_options(&rocksdb_config, Self::column_families())?,
                 &rocksdb_opts,
             )?
         } else {
             rocksdb_opts.create_if_missing(true);
             rocksdb_opts.create_missing_column_families(true);
-            DB::open(
+            DB::open_with_cf_options(
                 path.clone(),
                 "diemdb",
-                Self::column_families(),
+                gen_cf_options(&rocksdb_config, Self::column_families())?,
                 &rocksdb_opts,
             )?
         };
 
         let ret = Self::new_with_db(db, prune_window);
+        if !readonly {
+            // Transactions committed before the hash index existed aren't in it.
+            let num_indexed = ret.transaction_store.backfill_transaction_hash_index()?;
+            if num_indexed > 0 {
+                info!(
+                    num_indexed = num_indexed,
+                    "Backfilled the transaction hash index.",
+                );
+            }
+        }
         info!(
             path = path,
             time_ms = %instant.elapsed().as_millis(),
@@ -308,18 +443,56 @@ impl DiemDB {
         let rocksdb_opts = gen_rocksdb_options(&rocksdb_config);
 
         Ok(Self::new_with_db(
-            DB::open_as_secondary(
+            DB::open_as_secondary_with_cf_options(
                 primary_path,
                 secondary_path,
                 "diemdb_sec",
-                Self::column_families(),
+                gen_cf_options(&rocksdb_config, Self::column_families())?,
                 &rocksdb_opts,
             )?,
             None, // prune_window
         ))
     }
 
-    /// This opens db in non-readonly mode, without the pruner.
+    /// Creates a consistent copy of the DB under `checkpoint_root_path` while it keeps serving
+    /// reads and writes, so that a new node can be started from it with `checkpoint_root_path` as
+    /// its storage dir. Files are hard linked if `checkpoint_root_path` is on the same file system
+    /// as the DB, which makes the checkpoint cheap in both time and disk space.
+    pub fn create_checkpoint<P: AsRef<Path>>(&self, checkpoint_root_path: P) -> Result<()> {
+        let _timer = DIEM_STORAGE_OTHER_TIMERS_SECONDS
+            .with_label_values(&["create_checkpoint"])
+            .start_timer();
+        let checkpoint_root_path = checkpoint_root_path.as_ref();
+        std::fs::create_dir_all(checkpoint_root_path)?;
+        self.db
+            .create_checkpoint(checkpoint_root_path.join("diemdb"))?;
+        info!(path = ?checkpoint_root_path, "Created DiemDB checkpoint.");
+        Ok(())
+    }
+
+    /// Sets whether committed events are indexed by type, which `get_events_by_type` requires.
+    /// Only events committed while the index is enabled can be found by type.
+    pub fn with_event_by_type_index(mut self, enabled: bool) -> Self {
+        self.event_store = Arc::new(EventStore::new(Arc::clone(&self.db), enabled));
+        self
+    }
+
+    /// Sets whether the write sets of committed transactions are stored, which
+    /// `get_transaction_outputs` requires. Only transactions committed while enabled have their
+    /// write sets stored.
+    pub fn with_write_set_storage(mut self, enabled: bool) -> Self {
+        self.transaction_store = Arc::new(TransactionStore::new(Arc::clone(&self.db), enabled));
+        self
+    }
+
+    /// Catches up a DB opened with `open_as_secondary` with the primary instance, making the
+    /// transactions committed on the primary since then visible.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        self.db.try_catch_up_with_primary()?;
+        self.ledger_store.reload_latest_ledger_info()
+    }
+
+    /// This opens db in non-readonly mode, without the pruner, storing write sets.
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn new_for_test<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
         Self::open(
@@ -329,6 +502,15 @@ impl DiemDB {
             RocksdbConfig::default(),
         )
         .expect("Unable to open DiemDB")
+        .with_write_set_storage(true)
+    }
+
+    /// Same as `new_for_test`, but the db only lives in memory, so the test doesn't touch disk.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn new_in_memory_for_test() -> Self {
+        let db = DB::open_in_memory("diemdb_in_memory", Self::column_families())
+            .expect("Unable to open DiemDB");
+        Self::new_with_db(db, None /* pruner */).with_write_set_storage(true)
     }
 
     /// This force the db to update rocksdb properties immediately.
@@ -565,11 +747,13 @@ impl DiemDB {
             })
             .collect::<Result<Vec<_>>>()?;
 
-        // Transaction updates. Gather transaction hashes.
+        // Transaction and write set updates. Gather transaction hashes.
         zip_eq(first_version..=last_version, txns_to_commit).try_for_each(
             |(ver, txn_to_commit)| {
                 self.transaction_store
-                    .put_transaction(ver, txn_to_commit.transaction(), cs)
+                    .put_transaction(ver, txn_to_commit.transaction(), cs)?;
+                self.transaction_store
+                    .put_write_set(ver, txn_to_commit.write_set(), cs)
             },
         )?;
 
@@ -660,6 +844,22 @@ impl DbReader for DiemDB {
         })
     }
 
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        gauged_api("get_transaction_by_hash", || {
+            self.transaction_store
+                .get_transaction_version_by_hash(&hash, ledger_version)?
+                .map(|txn_version| {
+                    self.get_transaction_with_proof(txn_version, ledger_version, fetch_events)
+                })
+                .transpose()
+        })
+    }
+
     fn get_account_transactions(
         &self,
         address: AccountAddress,
@@ -742,6 +942,87 @@ impl DbReader for DiemDB {
         })
     }
 
+    /// Gets the transactions in `[start_version, start_version + limit)` with their outputs,
+    /// and the proof of the transactions relative to `ledger_version`.
+    fn get_transaction_outputs(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<TransactionOutputListWithProof> {
+        gauged_api("get_transaction_outputs", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+            if start_version > ledger_version || limit == 0 {
+                return Ok(TransactionOutputListWithProof::new_empty());
+            }
+
+            let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+
+            let (transactions_and_outputs, txn_infos) = (start_version..start_version + limit)
+                .map(|version| {
+                    let txn = self.transaction_store.get_transaction(version)?;
+                    let txn_info = self.ledger_store.get_transaction_info(version)?;
+                    let output = TransactionOutput::new(
+                        self.transaction_store.get_write_set(version)?,
+                        self.event_store.get_events_by_version(version)?,
+                        txn_info.gas_used(),
+                        TransactionStatus::Keep(txn_info.status().clone()),
+                    );
+                    Ok(((txn, output), txn_info))
+                })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .unzip();
+            let proof = TransactionListProof::new(
+                self.ledger_store.get_transaction_range_proof(
+                    Some(start_version),
+                    limit,
+                    ledger_version,
+                )?,
+                txn_infos,
+            );
+
+            Ok(TransactionOutputListWithProof::new(
+                transactions_and_outputs,
+                Some(start_version),
+                proof,
+            ))
+        })
+    }
+
+    /// Gets at most `limit` accounts of the state snapshot at `version` that follow `last_key`,
+    /// and the range proof of the rightmost of them. Fails if no account follows `last_key`.
+    fn get_state_snapshot_chunk(
+        &self,
+        version: Version,
+        last_key: Option<HashValue>,
+        limit: usize,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        gauged_api("get_state_snapshot_chunk", || {
+            let account_blobs = JellyfishMerkleIterator::new(
+                Arc::clone(&self.state_store),
+                version,
+                last_key.unwrap_or_else(HashValue::zero),
+            )?
+            // The iterator starts at `last_key` itself, which the requester already has.
+            .skip_while(|result| matches!(result, Ok((key, _)) if Some(*key) == last_key))
+            .take(limit)
+            .collect::<Result<Vec<_>>>()?;
+            let rightmost_key = account_blobs.last().map(|(key, _)| *key).ok_or_else(|| {
+                format_err!(
+                    "No accounts left in the state snapshot at version {}.",
+                    version
+                )
+            })?;
+            let proof = self
+                .state_store
+                .get_account_state_range_proof(rightmost_key, version)?;
+
+            Ok((account_blobs, proof))
+        })
+    }
+
     fn get_events(
         &self,
         event_key: &EventKey,
@@ -760,6 +1041,36 @@ impl DbReader for DiemDB {
         })
     }
 
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_index: u64,
+        end_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(u64, ContractEvent)>> {
+        gauged_api("get_events_by_type", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+            let end_version = std::cmp::min(end_version, ledger_version.saturating_add(1));
+            self.event_store
+                .lookup_events_by_type(type_tag, start_version, start_index, end_version, limit)?
+                .into_iter()
+                .map(|(ver, idx)| {
+                    let event = self.event_store.get_event_by_version_and_index(ver, idx)?;
+                    ensure!(
+                        event.type_tag() == type_tag,
+                        "Index broken, expected type: {}, actual: {}",
+                        type_tag,
+                        event.type_tag()
+                    );
+                    Ok((ver, event))
+                })
+                .collect()
+        })
+    }
+
     fn get_events_with_proofs(
         &self,
         event_key: &EventKey,
@@ -1176,6 +1487,63 @@ impl DbWriter for DiemDB {
             Ok(())
         })
     }
+
+    /// Starts restoring the state snapshot at `version`, overwriting any partially restored
+    /// nodes left over from a previous attempt.
+    fn get_state_snapshot_receiver(
+        &self,
+        version: Version,
+        expected_root_hash: HashValue,
+    ) -> Result<Box<dyn StateSnapshotReceiver>> {
+        gauged_api("get_state_snapshot_receiver", || {
+            let restore = JellyfishMerkleRestore::new_overwrite(
+                Arc::clone(&self.state_store),
+                version,
+                expected_root_hash,
+            )?;
+            Ok(Box::new(StateSnapshotRestore(restore)) as Box<dyn StateSnapshotReceiver>)
+        })
+    }
+
+    /// Commits the transaction at `version` (extending the transaction accumulator from the
+    /// frozen subtrees to its left) and the ledger infos proving it. The state snapshot at
+    /// `version` must have been restored already.
+    fn finalize_state_snapshot(
+        &self,
+        version: Version,
+        txn_list_with_proof: TransactionListWithProof,
+        ledger_infos: &[LedgerInfoWithSignatures],
+    ) -> Result<()> {
+        gauged_api("finalize_state_snapshot", || {
+            ensure!(
+                txn_list_with_proof.first_transaction_version == Some(version)
+                    && txn_list_with_proof.transactions.len() == 1,
+                "Expected exactly the transaction at the snapshot version {}.",
+                version,
+            );
+            let events = txn_list_with_proof
+                .events
+                .ok_or_else(|| format_err!("Missing the events at the snapshot version."))?;
+
+            restore_utils::confirm_or_save_frozen_subtrees(
+                &self.db,
+                version,
+                txn_list_with_proof.proof.left_siblings(),
+            )?;
+            restore_utils::save_transactions(
+                &self.db,
+                &self.ledger_store,
+                &self.transaction_store,
+                &self.event_store,
+                version,
+                &txn_list_with_proof.transactions,
+                txn_list_with_proof.proof.transaction_infos(),
+                &events,
+                &[], /* write_sets, not part of the state sync proof */
+            )?;
+            restore_utils::save_ledger_infos(&self.db, &self.ledger_store, ledger_infos)
+        })
+    }
 }
 
 // Convert requested range and order to a range in ascending order.
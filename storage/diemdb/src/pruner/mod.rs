@@ -11,10 +11,13 @@ use crate::{
     },
     schema::{
         jellyfish_merkle_node::JellyfishMerkleNodeSchema, stale_node_index::StaleNodeIndexSchema,
+        transaction::TransactionSchema, transaction_by_hash::TransactionByHashSchema,
+        write_set::WriteSetSchema,
     },
 };
 use anyhow::Result;
 use diem_jellyfish_merkle::StaleNodeIndex;
+use mango_crypto::hash::CryptoHash;
 use mango_infallible::Mutex;
 use mango_logger::prelude::*;
 use mango_types::transaction::Version;
@@ -294,6 +297,9 @@ impl Worker {
     ///
     /// We issue (range) deletes on the index only periodically instead of after every pruning batch
     /// to avoid sending too many deletions to the DB, which takes disk space and slows it down.
+    ///
+    /// Write sets of the same version range are deleted along with the index, so that the (opt-in)
+    /// write set storage is bounded by the prune window as well.
     fn maybe_purge_index(&mut self) -> Result<()> {
         const MIN_INTERVAL: Duration = Duration::from_secs(60);
         const MIN_VERSIONS: u64 = 60000;
@@ -311,6 +317,10 @@ impl Worker {
                     &self.index_min_nonpurged_version,
                     &new_min_non_purged_version, // end is exclusive
                 )?;
+                self.db.range_delete::<WriteSetSchema, Version>(
+                    &self.index_min_nonpurged_version,
+                    &new_min_non_purged_version, // end is exclusive
+                )?;
                 self.index_min_nonpurged_version = new_min_non_purged_version;
                 self.index_purged_at = now;
             }
@@ -408,5 +418,25 @@ pub fn prune_state(
     }
 }
 
+/// Deletes the transactions in `[begin, end)` together with their hash index entries, in one
+/// batch so that the index never misses a transaction that is still stored. The hash index can't
+/// be range deleted, the hashes are read from the transactions themselves.
+///
+/// Nothing prunes transactions yet, this is for a ledger pruner to call.
+pub fn prune_transactions(db: &DB, begin: Version, end: Version) -> Result<()> {
+    let mut iter = db.iter::<TransactionSchema>(ReadOptions::default())?;
+    iter.seek(&begin)?;
+    let mut batch = SchemaBatch::new();
+    for res in iter {
+        let (version, txn) = res?;
+        if version >= end {
+            break;
+        }
+        batch.delete::<TransactionByHashSchema>(&txn.hash())?;
+        batch.delete::<TransactionSchema>(&version)?;
+    }
+    db.write_schemas(batch)
+}
+
 #[cfg(test)]
 mod test;
@@ -2,10 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::{change_set::ChangeSet, state_store::StateStore, DiemDB};
+use crate::{
+    change_set::ChangeSet, state_store::StateStore, transaction_store::TransactionStore, DiemDB,
+};
 use mango_crypto::HashValue;
-use mango_temppath::TempPath;
-use mango_types::{account_address::AccountAddress, account_state_blob::AccountStateBlob};
+use mango_types::{
+    account_address::AccountAddress, account_state_blob::AccountStateBlob,
+    block_metadata::BlockMetadata, transaction::Transaction,
+};
 use std::collections::HashMap;
 
 fn put_account_state_set(
@@ -47,8 +51,7 @@ fn test_pruner() {
     let value1 = AccountStateBlob::from(vec![0x02]);
     let value2 = AccountStateBlob::from(vec![0x03]);
 
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir).db;
+    let db = DiemDB::new_in_memory_for_test().db;
     let state_store = &StateStore::new(Arc::clone(&db));
     let pruner = Pruner::new(Arc::clone(&db), 0 /* historical_versions_to_keep */);
 
@@ -108,8 +111,7 @@ fn test_worker_quit_eagerly() {
     let value1 = AccountStateBlob::from(vec![0x02]);
     let value2 = AccountStateBlob::from(vec![0x03]);
 
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir).db;
+    let db = DiemDB::new_in_memory_for_test().db;
     let state_store = &StateStore::new(Arc::clone(&db));
 
     let _root0 = put_account_state_set(
@@ -156,3 +158,45 @@ fn test_worker_quit_eagerly() {
         verify_state_in_store(state_store, address, Some(&value2), 2);
     }
 }
+
+#[test]
+fn test_prune_transactions() {
+    let db = DiemDB::new_in_memory_for_test().db;
+    let transaction_store =
+        TransactionStore::new(Arc::clone(&db), false /* store_write_sets */);
+
+    let txns = (0..4)
+        .map(|round| {
+            Transaction::BlockMetadata(BlockMetadata::new(
+                HashValue::random(),
+                round,
+                round * 1000,
+                vec![],
+                AccountAddress::random(),
+            ))
+        })
+        .collect::<Vec<_>>();
+    let mut cs = ChangeSet::new();
+    for (version, txn) in txns.iter().enumerate() {
+        transaction_store
+            .put_transaction(version as Version, txn, &mut cs)
+            .unwrap();
+    }
+    db.write_schemas(cs.batch).unwrap();
+
+    prune_transactions(&db, 0, 2).unwrap();
+    for (version, txn) in txns.iter().enumerate() {
+        let version = version as Version;
+        let indexed = transaction_store
+            .get_transaction_version_by_hash(&txn.hash(), 3 /* ledger_version */)
+            .unwrap();
+        // A transaction is found by hash exactly as long as it is stored.
+        if version < 2 {
+            assert_eq!(indexed, None);
+            assert!(transaction_store.get_transaction(version).is_err());
+        } else {
+            assert_eq!(indexed, Some(version));
+            assert_eq!(&transaction_store.get_transaction(version).unwrap(), txn);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an optional event index via which the
+//! ContractEvents of a given type (represented by <txn_version, event_idx> tuples so that they can
+//! be fetched from `EventSchema`) can be found in the order they were emitted. The type is
+//! represented by the SHA3-256 hash of its BCS bytes.
+//!
+//! ```text
+//! |<-------------key------------->|
+//! | type_tag_hash | txn_ver | idx |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, EVENT_BY_TYPE_CF_NAME};
+use anyhow::Result;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use mango_crypto::HashValue;
+use mango_types::transaction::Version;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (HashValue, Version, Index);
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref type_tag_hash, version, index) = *self;
+
+        let mut encoded = type_tag_hash.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        const VERSION_OFFSET: usize = HashValue::LENGTH;
+        const INDEX_OFFSET: usize = VERSION_OFFSET + size_of::<Version>();
+        let type_tag_hash = HashValue::from_slice(&data[..VERSION_OFFSET])?;
+        let version = (&data[VERSION_OFFSET..INDEX_OFFSET]).read_u64::<BigEndian>()?;
+        let index = (&data[INDEX_OFFSET..]).read_u64::<BigEndian>()?;
+
+        Ok((type_tag_hash, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
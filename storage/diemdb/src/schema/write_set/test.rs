@@ -0,0 +1,13 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::schema::assert_encode_decode;
+
+proptest! {
+    #[test]
+    fn test_encode_decode(write_set in any::<WriteSet>()) {
+        assert_encode_decode::<WriteSetSchema>(&0u64, &write_set);
+    }
+}
@@ -4,7 +4,6 @@
 use super::*;
 use crate::{pruner, DiemDB};
 use diem_jellyfish_merkle::restore::JellyfishMerkleRestore;
-use mango_temppath::TempPath;
 use mango_types::{
     account_address::{AccountAddress, HashAccountAddress},
     account_state_blob::AccountStateBlob,
@@ -79,8 +78,7 @@ fn verify_state_in_store(
 
 #[test]
 fn test_empty_store() {
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
     let store = &db.state_store;
     let address = AccountAddress::new([1u8; AccountAddress::LENGTH]);
     assert!(store
@@ -90,8 +88,7 @@ fn test_empty_store() {
 
 #[test]
 fn test_state_store_reader_writer() {
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
     let store = &db.state_store;
     let address1 = AccountAddress::new([1u8; AccountAddress::LENGTH]);
     let address2 = AccountAddress::new([2u8; AccountAddress::LENGTH]);
@@ -144,8 +141,7 @@ fn test_retired_records() {
     let value3 = AccountStateBlob::from(vec![0x03]);
     let value3_update = AccountStateBlob::from(vec![0x13]);
 
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
     let store = &db.state_store;
 
     // Update.
@@ -237,8 +233,7 @@ proptest! {
         // Convert to a vector so iteration order becomes deterministic.
         let kvs: Vec<_> = input.into_iter().collect();
 
-        let tmp_dir = TempPath::new();
-        let db = DiemDB::new_for_test(&tmp_dir);
+        let db = DiemDB::new_in_memory_for_test();
         let store = &db.state_store;
         init_store(store, kvs.clone().into_iter());
 
@@ -267,16 +262,14 @@ proptest! {
                 (Just(input), 1..len)
             })
     ) {
-        let tmp_dir1 = TempPath::new();
-        let db1 = DiemDB::new_for_test(&tmp_dir1);
+        let db1 = DiemDB::new_in_memory_for_test();
         let store1 = &db1.state_store;
         init_store(store1, input.clone().into_iter());
 
         let version = (input.len() - 1) as Version;
         let expected_root_hash = store1.get_root_hash(version).unwrap();
 
-        let tmp_dir2 = TempPath::new();
-        let db2 = DiemDB::new_for_test(&tmp_dir2);
+        let db2 = DiemDB::new_in_memory_for_test();
         let store2 = &db2.state_store;
 
         let mut restore =
@@ -325,16 +318,14 @@ proptest! {
                 (Just(input), 1..len)
             })
     ) {
-        let tmp_dir1 = TempPath::new();
-        let db1 = DiemDB::new_for_test(&tmp_dir1);
+        let db1 = DiemDB::new_in_memory_for_test();
         let store1 = &db1.state_store;
         init_store(store1, input.clone().into_iter());
 
         let version = (input.len() - 1) as Version;
         let expected_root_hash = store1.get_root_hash(version).unwrap();
 
-        let tmp_dir2 = TempPath::new();
-        let db2 = DiemDB::new_for_test(&tmp_dir2);
+        let db2 = DiemDB::new_in_memory_for_test();
         let store2 = &db2.state_store;
 
         let mut restore =
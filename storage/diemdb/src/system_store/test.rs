@@ -7,7 +7,6 @@ use crate::{
     ledger_counters::{LedgerCounter, LedgerCounterBumps},
     DiemDB,
 };
-use mango_temppath::TempPath;
 use std::collections::HashMap;
 
 fn bump_ledger_counters(
@@ -43,8 +42,7 @@ fn create_bumps_map(
 
 #[test]
 fn test_inc_ledger_counters() {
-    let tmp_dir = TempPath::new();
-    let db = DiemDB::new_for_test(&tmp_dir);
+    let db = DiemDB::new_in_memory_for_test();
     let store = &db.system_store;
 
     // First batch, add to zeros.
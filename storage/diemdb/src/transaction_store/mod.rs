@@ -6,25 +6,35 @@
 use crate::{
     change_set::ChangeSet,
     errors::DiemDbError,
-    schema::{transaction::TransactionSchema, transaction_by_account::TransactionByAccountSchema},
+    schema::{
+        transaction::TransactionSchema, transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema, write_set::WriteSetSchema,
+    },
 };
 use anyhow::{ensure, format_err, Result};
+use mango_crypto::hash::{CryptoHash, HashValue};
 use mango_types::{
     account_address::AccountAddress,
     block_metadata::BlockMetadata,
     transaction::{Transaction, Version},
+    write_set::WriteSet,
 };
-use schemadb::{ReadOptions, SchemaIterator, DB};
+use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
 use std::sync::Arc;
 
 #[derive(Debug)]
 pub(crate) struct TransactionStore {
     db: Arc<DB>,
+    /// Whether the write sets of transactions are saved in `WriteSetSchema`.
+    store_write_sets: bool,
 }
 
 impl TransactionStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, store_write_sets: bool) -> Self {
+        Self {
+            db,
+            store_write_sets,
+        }
     }
 
     /// Gets the version of a transaction by the sender `address` and `sequence_number`.
@@ -46,6 +56,18 @@ impl TransactionStore {
         Ok(None)
     }
 
+    /// Gets the version of a transaction by its hash.
+    pub fn get_transaction_version_by_hash(
+        &self,
+        hash: &HashValue,
+        ledger_version: Version,
+    ) -> Result<Option<Version>> {
+        Ok(self
+            .db
+            .get::<TransactionByHashSchema>(hash)?
+            .filter(|version| *version <= ledger_version))
+    }
+
     /// Gets an iterator that yields `(sequence_number, version)` for each
     /// transaction sent by an account, starting at `start_seq_num`, and returning
     /// at most `num_versions` results with `version <= ledger_version`.
@@ -82,6 +104,18 @@ impl TransactionStore {
             .ok_or_else(|| DiemDbError::NotFound(format!("Txn {}", version)).into())
     }
 
+    /// Get the write set of the transaction at `version`
+    pub fn get_write_set(&self, version: Version) -> Result<WriteSet> {
+        self.get_write_set_option(version)?
+            .ok_or_else(|| DiemDbError::NotFound(format!("WriteSet {}", version)).into())
+    }
+
+    /// Get the write set of the transaction at `version`, if it is stored (it isn't if write
+    /// set storage was disabled when the transaction was committed, or if it has been pruned)
+    pub fn get_write_set_option(&self, version: Version) -> Result<Option<WriteSet>> {
+        self.db.get::<WriteSetSchema>(&version)
+    }
+
     /// Gets an iterator that yields `num_transactions` transactions starting from `start_version`.
     pub fn get_transaction_iter(
         &self,
@@ -114,9 +148,14 @@ impl TransactionStore {
         iter.seek(&version)?;
         for res in iter.take(MAX_VERSIONS_TO_SEARCH) {
             let (v, txn) = res?;
-            if let Transaction::BlockMetadata(block_meta) = txn {
-                return Ok(Some((v, block_meta)));
-            } else if v == 0 {
+            match txn {
+                Transaction::BlockMetadata(block_meta) => return Ok(Some((v, block_meta))),
+                Transaction::BlockMetadataExt(block_meta) => {
+                    return Ok(Some((v, block_meta.into_inner().0)))
+                }
+                _ => (),
+            }
+            if v == 0 {
                 return Ok(None);
             }
         }
@@ -137,10 +176,66 @@ impl TransactionStore {
                 &version,
             )?;
         }
+        cs.batch
+            .put::<TransactionByHashSchema>(&transaction.hash(), &version)?;
         cs.batch.put::<TransactionSchema>(&version, transaction)?;
 
         Ok(())
     }
+
+    /// Indexes by hash the transactions committed before `TransactionByHashSchema` existed and
+    /// returns the number of transactions indexed.
+    ///
+    /// Transactions are indexed as they are saved and the backfill goes from the first version
+    /// up, so the index is complete if the latest transaction is in it. Otherwise every
+    /// transaction is indexed again, which is harmless for the ones already in the index.
+    pub fn backfill_transaction_hash_index(&self) -> Result<usize> {
+        const BATCH_SIZE: usize = 10000;
+
+        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        let latest_indexed = match iter.next().transpose()? {
+            Some((_, txn)) => self
+                .db
+                .get::<TransactionByHashSchema>(&txn.hash())?
+                .is_some(),
+            // The ledger is empty.
+            None => true,
+        };
+        if latest_indexed {
+            return Ok(0);
+        }
+
+        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        let mut num_indexed = 0;
+        let mut batch = SchemaBatch::new();
+        for res in iter {
+            let (version, txn) = res?;
+            batch.put::<TransactionByHashSchema>(&txn.hash(), &version)?;
+            num_indexed += 1;
+            if num_indexed % BATCH_SIZE == 0 {
+                self.db
+                    .write_schemas(std::mem::replace(&mut batch, SchemaBatch::new()))?;
+            }
+        }
+        self.db.write_schemas(batch)?;
+
+        Ok(num_indexed)
+    }
+
+    /// Save the write set of the transaction at `version`, if write set storage is enabled
+    pub fn put_write_set(
+        &self,
+        version: Version,
+        write_set: &WriteSet,
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        if self.store_write_sets {
+            cs.batch.put::<WriteSetSchema>(&version, write_set)?;
+        }
+        Ok(())
+    }
 }
 
 pub struct TransactionIter<'a> {
@@ -4,7 +4,6 @@
 use super::*;
 use crate::DiemDB;
 use mango_proptest_helpers::Index;
-use mango_temppath::TempPath;
 use mango_types::{
     block_metadata::BlockMetadata,
     proptest_types::{AccountInfoUniverse, SignatureCheckedTransactionGen},
@@ -24,8 +23,7 @@ proptest! {
             1..10
         ),
     ) {
-        let tmp_dir = TempPath::new();
-        let db = DiemDB::new_for_test(&tmp_dir);
+        let db = DiemDB::new_in_memory_for_test();
         let store = &db.transaction_store;
         let txns = init_store(universe, gens, store);
 
@@ -45,11 +43,60 @@ proptest! {
                     .unwrap(),
                 Some(ver as Version)
             );
+            prop_assert_eq!(
+                store
+                    .get_transaction_version_by_hash(&txn.hash(), ledger_version)
+                    .unwrap(),
+                Some(ver as Version)
+            );
+            if ver > 0 {
+                prop_assert_eq!(
+                    store
+                        .get_transaction_version_by_hash(&txn.hash(), ver as Version - 1)
+                        .unwrap(),
+                    None
+                );
+            }
         }
 
         prop_assert!(store.get_transaction(ledger_version + 1).is_err());
     }
 
+    #[test]
+    fn test_backfill_transaction_hash_index(
+        universe in any_with::<AccountInfoUniverse>(3),
+        gens in vec(
+            (any::<Index>(), any::<SignatureCheckedTransactionGen>()),
+            1..10
+        ),
+    ) {
+        let db = DiemDB::new_in_memory_for_test();
+        let store = &db.transaction_store;
+        let txns = init_store(universe, gens, store);
+
+        // Nothing to do if the index is complete.
+        prop_assert_eq!(store.backfill_transaction_hash_index().unwrap(), 0);
+
+        // Drop the index, as if the transactions were committed before it existed.
+        let mut batch = SchemaBatch::new();
+        for txn in &txns {
+            batch.delete::<TransactionByHashSchema>(&txn.hash()).unwrap();
+        }
+        store.db.write_schemas(batch).unwrap();
+
+        let ledger_version = txns.len() as Version - 1;
+        prop_assert_eq!(store.backfill_transaction_hash_index().unwrap(), txns.len());
+        for (ver, txn) in txns.iter().enumerate() {
+            prop_assert_eq!(
+                store
+                    .get_transaction_version_by_hash(&txn.hash(), ledger_version)
+                    .unwrap(),
+                Some(ver as Version)
+            );
+        }
+        prop_assert_eq!(store.backfill_transaction_hash_index().unwrap(), 0);
+    }
+
     #[test]
     fn test_get_transaction_iter(
         universe in any_with::<AccountInfoUniverse>(3),
@@ -58,8 +105,7 @@ proptest! {
             1..10
         ),
     ) {
-        let tmp_dir = TempPath::new();
-        let db = DiemDB::new_for_test(&tmp_dir);
+        let db = DiemDB::new_in_memory_for_test();
         let store = &db.transaction_store;
         let txns = init_store(universe, gens, store);
 
@@ -114,8 +160,7 @@ proptest! {
             1..100,
         )
     ) {
-        let tmp_dir = TempPath::new();
-        let db = DiemDB::new_for_test(&tmp_dir);
+        let db = DiemDB::new_in_memory_for_test();
         let store = &db.transaction_store;
 
         let mut cs = ChangeSet::new();
@@ -163,8 +208,7 @@ proptest! {
         ledger_version in 0_u64..50,
         num_versions in 0_u64..=50,
     ) {
-        let tmp_dir = TempPath::new();
-        let db = DiemDB::new_for_test(&tmp_dir);
+        let db = DiemDB::new_in_memory_for_test();
         let store = &db.transaction_store;
         let txns = init_store(universe, gens, store);
 
@@ -13,6 +13,7 @@ use storage_interface::DbReader;
 
 use mango_types::{
     account_address::AccountAddress, account_config::AccountResource, account_state::AccountState,
+    ledger_info::LedgerInfoWithSignatures,
 };
 use std::convert::TryFrom;
 use structopt::StructOpt;
@@ -56,7 +57,10 @@ fn print_head(db: &DiemDB) -> Result<()> {
         si.latest_ledger_info.ledger_info()
     );
 
-    info!("Signatures: {:?}", si.latest_ledger_info.signatures());
+    match &si.latest_ledger_info {
+        LedgerInfoWithSignatures::V0(li) => info!("Signatures: {:?}", li.signatures()),
+        LedgerInfoWithSignatures::V1(li) => info!("Aggregate signature: {:?}", li.signatures()),
+    }
 
     info!("Current EpochState: {}", si.get_epoch_state());
 
@@ -107,7 +107,7 @@ where
 
 pub struct JellyfishMerkleRestore<V> {
     /// The underlying storage.
-    store: Arc<dyn TreeWriter<V>>,
+    store: Arc<dyn TreeWriter<V> + Send + Sync>,
 
     /// The version of the tree we are restoring.
     version: Version,
@@ -162,7 +162,7 @@ impl<V> JellyfishMerkleRestore<V>
 where
     V: crate::Value,
 {
-    pub fn new<D: 'static + TreeReader<V> + TreeWriter<V>>(
+    pub fn new<D: 'static + TreeReader<V> + TreeWriter<V> + Send + Sync>(
         store: Arc<D>,
         version: Version,
         expected_root_hash: HashValue,
@@ -195,7 +195,7 @@ where
         })
     }
 
-    pub fn new_overwrite<D: 'static + TreeWriter<V>>(
+    pub fn new_overwrite<D: 'static + TreeWriter<V> + Send + Sync>(
         store: Arc<D>,
         version: Version,
         expected_root_hash: HashValue,
@@ -0,0 +1,216 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{KVBackend, RawIterator};
+use crate::{ColumnFamilyName, ReadOptions, SchemaBatch, WriteOp};
+use anyhow::{bail, ensure, format_err, Result};
+use mango_infallible::RwLock;
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+    path::Path,
+    sync::Arc,
+};
+
+type ColumnFamily = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// Keeps each column family in a `BTreeMap`. Iterators hold a reference to the map they were
+/// created on, which writes copy before modifying, so that iterators see a snapshot like they do
+/// on RocksDB.
+#[derive(Debug)]
+pub(crate) struct InMemoryBackend {
+    column_families: RwLock<HashMap<ColumnFamilyName, Arc<ColumnFamily>>>,
+}
+
+impl InMemoryBackend {
+    pub fn new(column_families: &[ColumnFamilyName]) -> Self {
+        Self {
+            column_families: RwLock::new(
+                column_families
+                    .iter()
+                    .map(|cf_name| (*cf_name, Arc::new(ColumnFamily::new())))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn get_cf_snapshot(&self, cf_name: &str) -> Result<Arc<ColumnFamily>> {
+        self.column_families
+            .read()
+            .get(cf_name)
+            .cloned()
+            .ok_or_else(|| cf_not_found(cf_name))
+    }
+}
+
+fn cf_not_found(cf_name: &str) -> anyhow::Error {
+    format_err!("Column family not found in in-memory DB: {}", cf_name)
+}
+
+impl KVBackend for InMemoryBackend {
+    fn get_cf(&self, cf_name: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.get_cf_snapshot(cf_name)?.get(key).cloned())
+    }
+
+    fn write_batch(&self, batch: &SchemaBatch) -> Result<usize> {
+        let mut column_families = self.column_families.write();
+        // Check all column families before modifying any, so a failed batch has no effect.
+        for cf_name in batch.rows.keys() {
+            column_families
+                .get(cf_name)
+                .ok_or_else(|| cf_not_found(cf_name))?;
+        }
+
+        let mut size = 0;
+        for (cf_name, rows) in &batch.rows {
+            let cf = Arc::make_mut(
+                column_families
+                    .get_mut(cf_name)
+                    .expect("Column family checked above."),
+            );
+            for (key, write_op) in rows {
+                size += key.len();
+                match write_op {
+                    WriteOp::Value(value) => {
+                        size += value.len();
+                        cf.insert(key.clone(), value.clone());
+                    }
+                    WriteOp::Deletion => {
+                        cf.remove(key);
+                    }
+                }
+            }
+        }
+        Ok(size)
+    }
+
+    fn delete_range_cf(&self, cf_name: &str, begin: &[u8], end: &[u8]) -> Result<()> {
+        ensure!(begin <= end, "End key comes before start key.");
+
+        let mut column_families = self.column_families.write();
+        let cf = Arc::make_mut(
+            column_families
+                .get_mut(cf_name)
+                .ok_or_else(|| cf_not_found(cf_name))?,
+        );
+        let keys: Vec<_> = cf
+            .range::<[u8], _>((Bound::Included(begin), Bound::Excluded(end)))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            cf.remove(&key);
+        }
+        Ok(())
+    }
+
+    /// `opts` are ignored.
+    fn raw_iterator_cf<'a>(
+        &'a self,
+        cf_name: &str,
+        _opts: ReadOptions,
+    ) -> Result<Box<dyn RawIterator + 'a>> {
+        Ok(Box::new(InMemoryIterator {
+            cf: self.get_cf_snapshot(cf_name)?,
+            position: None,
+        }))
+    }
+
+    fn flush_cf(&self, cf_name: &str) -> Result<()> {
+        self.get_cf_snapshot(cf_name)?;
+        Ok(())
+    }
+
+    fn try_catch_up_with_primary(&self) -> Result<()> {
+        bail!("In-memory DB can't be opened as secondary.")
+    }
+
+    fn create_checkpoint(&self, _path: &Path) -> Result<()> {
+        bail!("In-memory DB doesn't support checkpoints.")
+    }
+
+    fn get_statistics(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn get_property(&self, _cf_name: &str, property_name: &str) -> Result<Option<u64>> {
+        bail!(
+            "In-memory DB doesn't support property \"{}\".",
+            property_name
+        )
+    }
+
+    fn is_in_memory(&self) -> bool {
+        true
+    }
+}
+
+struct InMemoryIterator {
+    cf: Arc<ColumnFamily>,
+    /// Key the iterator is positioned at, None if the iterator is not valid.
+    position: Option<Vec<u8>>,
+}
+
+impl RawIterator for InMemoryIterator {
+    fn seek_to_first(&mut self) {
+        self.position = self.cf.keys().next().cloned();
+    }
+
+    fn seek_to_last(&mut self) {
+        self.position = self.cf.keys().next_back().cloned();
+    }
+
+    fn seek(&mut self, key: &[u8]) {
+        self.position = self
+            .cf
+            .range::<[u8], _>((Bound::Included(key), Bound::Unbounded))
+            .next()
+            .map(|(key, _)| key.clone());
+    }
+
+    fn seek_for_prev(&mut self, key: &[u8]) {
+        self.position = self
+            .cf
+            .range::<[u8], _>((Bound::Unbounded, Bound::Included(key)))
+            .next_back()
+            .map(|(key, _)| key.clone());
+    }
+
+    fn next(&mut self) {
+        if let Some(current) = self.position.take() {
+            self.position = self
+                .cf
+                .range::<[u8], _>((Bound::Excluded(current.as_slice()), Bound::Unbounded))
+                .next()
+                .map(|(key, _)| key.clone());
+        }
+    }
+
+    fn prev(&mut self) {
+        if let Some(current) = self.position.take() {
+            self.position = self
+                .cf
+                .range::<[u8], _>((Bound::Unbounded, Bound::Excluded(current.as_slice())))
+                .next_back()
+                .map(|(key, _)| key.clone());
+        }
+    }
+
+    fn valid(&self) -> bool {
+        self.position.is_some()
+    }
+
+    fn key(&self) -> Option<&[u8]> {
+        self.position.as_deref()
+    }
+
+    fn value(&self) -> Option<&[u8]> {
+        self.position
+            .as_ref()
+            .and_then(|key| self.cf.get(key))
+            .map(Vec::as_slice)
+    }
+
+    fn status(&self) -> Result<()> {
+        Ok(())
+    }
+}
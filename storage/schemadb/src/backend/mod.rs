@@ -0,0 +1,75 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Key-value stores a [`DB`](crate::DB) can sit on: RocksDB, or an in-memory store for tests.
+
+mod in_memory;
+mod rocks;
+
+pub(crate) use in_memory::InMemoryBackend;
+
+use crate::{ReadOptions, SchemaBatch};
+use anyhow::Result;
+use std::{fmt::Debug, path::Path};
+
+/// Raw key-value store with column families, on which the schematized `DB` is implemented.
+pub(crate) trait KVBackend: Debug + Send + Sync {
+    /// Reads the value of `key` in the column family.
+    fn get_cf(&self, cf_name: &str, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Applies all updates in the batch atomically, returning the size of the batch in bytes.
+    fn write_batch(&self, batch: &SchemaBatch) -> Result<usize>;
+
+    /// Deletes all keys in range [begin, end) of the column family.
+    fn delete_range_cf(&self, cf_name: &str, begin: &[u8], end: &[u8]) -> Result<()>;
+
+    /// Returns an iterator over a consistent view of the column family as of the call.
+    fn raw_iterator_cf<'a>(
+        &'a self,
+        cf_name: &str,
+        opts: ReadOptions,
+    ) -> Result<Box<dyn RawIterator + 'a>>;
+
+    fn flush_cf(&self, cf_name: &str) -> Result<()>;
+
+    fn try_catch_up_with_primary(&self) -> Result<()>;
+
+    fn create_checkpoint(&self, path: &Path) -> Result<()>;
+
+    fn get_statistics(&self) -> Result<Option<String>>;
+
+    fn get_property(&self, cf_name: &str, property_name: &str) -> Result<Option<u64>>;
+
+    /// Whether the data only lives in the memory of this process.
+    fn is_in_memory(&self) -> bool {
+        false
+    }
+}
+
+/// Cursor over the raw keys and values of a column family, following the semantics of
+/// `rocksdb::DBRawIterator`.
+pub(crate) trait RawIterator {
+    fn seek_to_first(&mut self);
+
+    fn seek_to_last(&mut self);
+
+    /// Positions at the first key equal to or greater than `key`.
+    fn seek(&mut self, key: &[u8]);
+
+    /// Positions at the last key equal to or less than `key`.
+    fn seek_for_prev(&mut self, key: &[u8]);
+
+    fn next(&mut self);
+
+    fn prev(&mut self);
+
+    /// Whether the iterator is positioned at a key.
+    fn valid(&self) -> bool;
+
+    fn key(&self) -> Option<&[u8]>;
+
+    fn value(&self) -> Option<&[u8]>;
+
+    /// Returns the error the iterator ran into, if any.
+    fn status(&self) -> Result<()>;
+}
@@ -0,0 +1,133 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{KVBackend, RawIterator};
+use crate::{ReadOptions, SchemaBatch, WriteOp};
+use anyhow::{format_err, Result};
+use std::path::Path;
+
+fn get_cf_handle<'a>(db: &'a rocksdb::DB, cf_name: &str) -> Result<&'a rocksdb::ColumnFamily> {
+    db.cf_handle(cf_name).ok_or_else(|| {
+        format_err!(
+            "DB::cf_handle not found for column family name: {}",
+            cf_name
+        )
+    })
+}
+
+/// For now we always use synchronous writes. This makes sure that once the operation returns
+/// `Ok(())` the data is persisted even if the machine crashes. In the future we might consider
+/// selectively turning this off for some non-critical writes to improve performance.
+fn default_write_options() -> rocksdb::WriteOptions {
+    let mut opts = rocksdb::WriteOptions::default();
+    opts.set_sync(true);
+    opts
+}
+
+impl KVBackend for rocksdb::DB {
+    fn get_cf(&self, cf_name: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(rocksdb::DB::get_cf(
+            self,
+            get_cf_handle(self, cf_name)?,
+            key,
+        )?)
+    }
+
+    fn write_batch(&self, batch: &SchemaBatch) -> Result<usize> {
+        let mut db_batch = rocksdb::WriteBatch::default();
+        for (cf_name, rows) in &batch.rows {
+            let cf_handle = get_cf_handle(self, cf_name)?;
+            for (key, write_op) in rows {
+                match write_op {
+                    WriteOp::Value(value) => db_batch.put_cf(cf_handle, key, value),
+                    WriteOp::Deletion => db_batch.delete_cf(cf_handle, key),
+                }
+            }
+        }
+        let serialized_size = db_batch.size_in_bytes();
+
+        self.write_opt(db_batch, &default_write_options())?;
+        Ok(serialized_size)
+    }
+
+    fn delete_range_cf(&self, cf_name: &str, begin: &[u8], end: &[u8]) -> Result<()> {
+        rocksdb::DB::delete_range_cf(self, get_cf_handle(self, cf_name)?, begin, end)?;
+        Ok(())
+    }
+
+    fn raw_iterator_cf<'a>(
+        &'a self,
+        cf_name: &str,
+        opts: ReadOptions,
+    ) -> Result<Box<dyn RawIterator + 'a>> {
+        Ok(Box::new(
+            self.raw_iterator_cf_opt(get_cf_handle(self, cf_name)?, opts),
+        ))
+    }
+
+    fn flush_cf(&self, cf_name: &str) -> Result<()> {
+        rocksdb::DB::flush_cf(self, get_cf_handle(self, cf_name)?)?;
+        Ok(())
+    }
+
+    fn try_catch_up_with_primary(&self) -> Result<()> {
+        rocksdb::DB::try_catch_up_with_primary(self)?;
+        Ok(())
+    }
+
+    fn create_checkpoint(&self, path: &Path) -> Result<()> {
+        rocksdb::checkpoint::Checkpoint::new(self)?.create_checkpoint(path)?;
+        Ok(())
+    }
+
+    fn get_statistics(&self) -> Result<Option<String>> {
+        Ok(self.property_value("rocksdb.options-statistics")?)
+    }
+
+    fn get_property(&self, cf_name: &str, property_name: &str) -> Result<Option<u64>> {
+        Ok(self.property_int_value_cf(get_cf_handle(self, cf_name)?, property_name)?)
+    }
+}
+
+impl<'a> RawIterator for rocksdb::DBRawIterator<'a> {
+    fn seek_to_first(&mut self) {
+        rocksdb::DBRawIterator::seek_to_first(self)
+    }
+
+    fn seek_to_last(&mut self) {
+        rocksdb::DBRawIterator::seek_to_last(self)
+    }
+
+    fn seek(&mut self, key: &[u8]) {
+        rocksdb::DBRawIterator::seek(self, key)
+    }
+
+    fn seek_for_prev(&mut self, key: &[u8]) {
+        rocksdb::DBRawIterator::seek_for_prev(self, key)
+    }
+
+    fn next(&mut self) {
+        rocksdb::DBRawIterator::next(self)
+    }
+
+    fn prev(&mut self) {
+        rocksdb::DBRawIterator::prev(self)
+    }
+
+    fn valid(&self) -> bool {
+        rocksdb::DBRawIterator::valid(self)
+    }
+
+    fn key(&self) -> Option<&[u8]> {
+        rocksdb::DBRawIterator::key(self)
+    }
+
+    fn value(&self) -> Option<&[u8]> {
+        rocksdb::DBRawIterator::value(self)
+    }
+
+    fn status(&self) -> Result<()> {
+        rocksdb::DBRawIterator::status(self)?;
+        Ok(())
+    }
+}
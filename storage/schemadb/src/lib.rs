@@ -6,18 +6,21 @@
 //! This library implements a schematized DB on top of [RocksDB](https://rocksdb.org/). It makes
 //! sure all data passed in and out are structured according to predefined schemas and prevents
 //! access to raw keys and values. This library also enforces a set of mango specific DB options,
-//! like custom comparators and schema-to-column-family mapping.
+//! like custom comparators and schema-to-column-family mapping. For tests, the DB can also be kept
+//! in memory, see [`DB::open_in_memory`].
 //!
 //! It requires that different kinds of key-value pairs be stored in separate column
 //! families.  To use this library to store a kind of key-value pairs, the user needs to use the
 //! [`define_schema!`] macro to define the schema name, the types of key and value, and name of the
 //! column family.
 
+mod backend;
 mod metrics;
 #[macro_use]
 pub mod schema;
 
 use crate::{
+    backend::{InMemoryBackend, KVBackend, RawIterator},
     metrics::{
         DIEM_SCHEMADB_BATCH_COMMIT_BYTES, DIEM_SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS,
         DIEM_SCHEMADB_DELETES, DIEM_SCHEMADB_GET_BYTES, DIEM_SCHEMADB_GET_LATENCY_SECONDS,
@@ -40,6 +43,15 @@ pub type ReadOptions = rocksdb::ReadOptions;
 /// Type alias to `rocksdb::Options`.
 pub type Options = rocksdb::Options;
 
+/// Type alias to `rocksdb::BlockBasedOptions`.
+pub type BlockBasedOptions = rocksdb::BlockBasedOptions;
+
+/// Type alias to `rocksdb::Cache`.
+pub type Cache = rocksdb::Cache;
+
+/// Type alias to `rocksdb::DBCompressionType`.
+pub type DBCompressionType = rocksdb::DBCompressionType;
+
 /// Type alias to improve readability.
 pub type ColumnFamilyName = &'static str;
 
@@ -98,7 +110,7 @@ pub enum ScanDirection {
 /// DB Iterator parameterized on [`Schema`] that seeks with [`Schema::Key`] and yields
 /// [`Schema::Key`] and [`Schema::Value`]
 pub struct SchemaIterator<'a, S> {
-    db_iter: rocksdb::DBRawIterator<'a>,
+    db_iter: Box<dyn RawIterator + 'a>,
     direction: ScanDirection,
     phantom: PhantomData<S>,
 }
@@ -107,7 +119,7 @@ impl<'a, S> SchemaIterator<'a, S>
 where
     S: Schema,
 {
-    fn new(db_iter: rocksdb::DBRawIterator<'a>, direction: ScanDirection) -> Self {
+    fn new(db_iter: Box<dyn RawIterator + 'a>, direction: ScanDirection) -> Self {
         SchemaIterator {
             db_iter,
             direction,
@@ -193,8 +205,42 @@ where
 #[derive(Debug)]
 pub struct DB {
     name: &'static str, // for logging
-    inner: rocksdb::DB,
+    inner: Box<dyn KVBackend>,
+    column_families: Vec<ColumnFamilyName>,
+}
+
+fn check_column_families<'a>(
+    column_families: impl ExactSizeIterator<Item = &'a ColumnFamilyName>,
+) -> Result<()> {
+    let num_cfs = column_families.len();
+    let cfs_set: HashSet<_> = column_families.collect();
+    ensure!(
+        cfs_set.contains(&DEFAULT_CF_NAME),
+        "No \"default\" column family name is provided.",
+    );
+    ensure!(
+        cfs_set.len() == num_cfs,
+        "Duplicate column family name found.",
+    );
+    Ok(())
+}
+
+/// The RocksDB default options for each of `column_families`.
+fn default_cf_options(
     column_families: Vec<ColumnFamilyName>,
+) -> Vec<(ColumnFamilyName, rocksdb::Options)> {
+    column_families
+        .into_iter()
+        .map(|cf_name| (cf_name, rocksdb::Options::default()))
+        .collect()
+}
+
+fn cf_descriptors(
+    cf_opts: Vec<(ColumnFamilyName, rocksdb::Options)>,
+) -> impl Iterator<Item = rocksdb::ColumnFamilyDescriptor> {
+    cf_opts
+        .into_iter()
+        .map(|(cf_name, cf_opts)| rocksdb::ColumnFamilyDescriptor::new(cf_name, cf_opts))
 }
 
 impl DB {
@@ -206,22 +252,49 @@ impl DB {
         column_families: Vec<ColumnFamilyName>,
         db_opts: &rocksdb::Options,
     ) -> Result<Self> {
-        {
-            let cfs_set: HashSet<_> = column_families.iter().collect();
-            ensure!(
-                cfs_set.contains(&DEFAULT_CF_NAME),
-                "No \"default\" column family name is provided.",
-            );
-            ensure!(
-                cfs_set.len() == column_families.len(),
-                "Duplicate column family name found.",
-            );
-        }
+        let cf_opts = column_families
+            .into_iter()
+            .map(|cf_name| {
+                let mut cf_opts = rocksdb::Options::default();
+                cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
+                (cf_name, cf_opts)
+            })
+            .collect();
+        DB::open_with_cf_options(path, name, cf_opts, db_opts)
+    }
+
+    /// Same as `open`, with the options of each column family provided, which are used when the
+    /// column family is created as well as when it is opened.
+    pub fn open_with_cf_options(
+        path: impl AsRef<Path>,
+        name: &'static str,
+        cf_opts: Vec<(ColumnFamilyName, rocksdb::Options)>,
+        db_opts: &rocksdb::Options,
+    ) -> Result<Self> {
+        check_column_families(cf_opts.iter().map(|(cf_name, _)| cf_name))?;
 
-        let db = DB::open_cf(db_opts, path, name, column_families)?;
+        let db = DB::open_cf(db_opts, path, name, cf_opts)?;
         Ok(db)
     }
 
+    /// Creates an empty db with all the column families provided that only lives in memory, so
+    /// tests can run without touching disk. It behaves like a RocksDB backed one, except that
+    /// [`ReadOptions`] are ignored and checkpoints and RocksDB properties are not supported.
+    pub fn open_in_memory(
+        name: &'static str,
+        column_families: Vec<ColumnFamilyName>,
+    ) -> Result<Self> {
+        check_column_families(column_families.iter())?;
+
+        let inner = InMemoryBackend::new(&column_families);
+        info!(db_name = name, "Opened in-memory DB.");
+        Ok(DB {
+            name,
+            inner: Box::new(inner),
+            column_families,
+        })
+    }
+
     /// Open db in readonly mode
     /// Note that this still assumes there's only one process that opens the same DB.
     /// See `open_as_secondary`
@@ -231,7 +304,23 @@ impl DB {
         column_families: Vec<ColumnFamilyName>,
         db_opts: &rocksdb::Options,
     ) -> Result<Self> {
-        DB::open_cf_readonly(db_opts, path, name, column_families)
+        DB::open_readonly_with_cf_options(path, name, default_cf_options(column_families), db_opts)
+    }
+
+    /// Same as `open_readonly`, with the options of each column family provided.
+    pub fn open_readonly_with_cf_options(
+        path: impl AsRef<Path>,
+        name: &'static str,
+        cf_opts: Vec<(ColumnFamilyName, rocksdb::Options)>,
+        db_opts: &rocksdb::Options,
+    ) -> Result<Self> {
+        DB::open_cf_readonly(db_opts, path, name, cf_opts)
+    }
+
+    /// Lists the column families of the db at `path`, so a db written by an older version can be
+    /// opened in readonly mode without the column families added since.
+    pub fn list_cf(path: impl AsRef<Path>) -> Result<Vec<String>> {
+        Ok(rocksdb::DB::list_cf(&rocksdb::Options::default(), path)?)
     }
 
     /// Open db as secondary.
@@ -245,24 +334,34 @@ impl DB {
         column_families: Vec<ColumnFamilyName>,
         db_opts: &rocksdb::Options,
     ) -> Result<Self> {
-        DB::open_cf_as_secondary(db_opts, primary_path, secondary_path, name, column_families)
+        DB::open_as_secondary_with_cf_options(
+            primary_path,
+            secondary_path,
+            name,
+            default_cf_options(column_families),
+            db_opts,
+        )
+    }
+
+    /// Same as `open_as_secondary`, with the options of each column family provided.
+    pub fn open_as_secondary_with_cf_options<P: AsRef<Path>>(
+        primary_path: P,
+        secondary_path: P,
+        name: &'static str,
+        cf_opts: Vec<(ColumnFamilyName, rocksdb::Options)>,
+        db_opts: &rocksdb::Options,
+    ) -> Result<Self> {
+        DB::open_cf_as_secondary(db_opts, primary_path, secondary_path, name, cf_opts)
     }
 
     fn open_cf(
         db_opts: &rocksdb::Options,
         path: impl AsRef<Path>,
         name: &'static str,
-        column_families: Vec<ColumnFamilyName>,
+        cf_opts: Vec<(ColumnFamilyName, rocksdb::Options)>,
     ) -> Result<DB> {
-        let inner = rocksdb::DB::open_cf_descriptors(
-            db_opts,
-            path,
-            column_families.iter().map(|cf_name| {
-                let mut cf_opts = rocksdb::Options::default();
-                cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
-                rocksdb::ColumnFamilyDescriptor::new((*cf_name).to_string(), cf_opts)
-            }),
-        )?;
+        let column_families = cf_opts.iter().map(|(cf_name, _)| *cf_name).collect();
+        let inner = rocksdb::DB::open_cf_descriptors(db_opts, path, cf_descriptors(cf_opts))?;
         Ok(Self::log_construct(name, column_families, inner))
     }
 
@@ -270,13 +369,14 @@ impl DB {
         opts: &rocksdb::Options,
         path: impl AsRef<Path>,
         name: &'static str,
-        column_families: Vec<ColumnFamilyName>,
+        cf_opts: Vec<(ColumnFamilyName, rocksdb::Options)>,
     ) -> Result<DB> {
+        let column_families = cf_opts.iter().map(|(cf_name, _)| *cf_name).collect();
         let error_if_log_file_exists = false;
-        let inner = rocksdb::DB::open_cf_for_read_only(
+        let inner = rocksdb::DB::open_cf_descriptors_read_only(
             opts,
             path,
-            &column_families,
+            cf_descriptors(cf_opts),
             error_if_log_file_exists,
         )?;
 
@@ -288,13 +388,14 @@ impl DB {
         primary_path: P,
         secondary_path: P,
         name: &'static str,
-        column_families: Vec<ColumnFamilyName>,
+        cf_opts: Vec<(ColumnFamilyName, rocksdb::Options)>,
     ) -> Result<DB> {
-        let inner = rocksdb::DB::open_cf_as_secondary(
+        let column_families = cf_opts.iter().map(|(cf_name, _)| *cf_name).collect();
+        let inner = rocksdb::DB::open_cf_descriptors_as_secondary(
             opts,
             primary_path,
             secondary_path,
-            &column_families,
+            cf_descriptors(cf_opts),
         )?;
 
         Ok(Self::log_construct(name, column_families, inner))
@@ -308,7 +409,7 @@ impl DB {
         info!(rocksdb_name = name, "Opened RocksDB.");
         DB {
             name,
-            inner,
+            inner: Box::new(inner),
             column_families,
         }
     }
@@ -320,9 +421,8 @@ impl DB {
             .start_timer();
 
         let k = <S::Key as KeyCodec<S>>::encode_key(schema_key)?;
-        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
 
-        let result = self.inner.get_cf(cf_handle, &k)?;
+        let result = self.inner.get_cf(S::COLUMN_FAMILY_NAME, &k)?;
         DIEM_SCHEMADB_GET_BYTES
             .with_label_values(&[S::COLUMN_FAMILY_NAME])
             .observe(result.as_ref().map_or(0.0, |v| v.len() as f64));
@@ -352,11 +452,9 @@ impl DB {
     {
         let raw_begin = begin.encode_seek_key()?;
         let raw_end = end.encode_seek_key()?;
-        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
 
         self.inner
-            .delete_range_cf(cf_handle, &raw_begin, &raw_end)?;
-        Ok(())
+            .delete_range_cf(S::COLUMN_FAMILY_NAME, &raw_begin, &raw_end)
     }
 
     fn iter_with_direction<S: Schema>(
@@ -364,9 +462,8 @@ impl DB {
         opts: ReadOptions,
         direction: ScanDirection,
     ) -> Result<SchemaIterator<S>> {
-        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
         Ok(SchemaIterator::new(
-            self.inner.raw_iterator_cf_opt(cf_handle, opts),
+            self.inner.raw_iterator_cf(S::COLUMN_FAMILY_NAME, opts)?,
             direction,
         ))
     }
@@ -387,19 +484,7 @@ impl DB {
             .with_label_values(&[self.name])
             .start_timer();
 
-        let mut db_batch = rocksdb::WriteBatch::default();
-        for (cf_name, rows) in &batch.rows {
-            let cf_handle = self.get_cf_handle(cf_name)?;
-            for (key, write_op) in rows {
-                match write_op {
-                    WriteOp::Value(value) => db_batch.put_cf(cf_handle, key, value),
-                    WriteOp::Deletion => db_batch.delete_cf(cf_handle, key),
-                }
-            }
-        }
-        let serialized_size = db_batch.size_in_bytes();
-
-        self.inner.write_opt(db_batch, &default_write_options())?;
+        let serialized_size = self.inner.write_batch(&batch)?;
 
         // Bump counters only after DB write succeeds.
         for (cf_name, rows) in &batch.rows {
@@ -423,28 +508,38 @@ impl DB {
         Ok(())
     }
 
-    fn get_cf_handle(&self, cf_name: &str) -> Result<&rocksdb::ColumnFamily> {
-        self.inner.cf_handle(cf_name).ok_or_else(|| {
-            format_err!(
-                "DB::cf_handle not found for column family name: {}",
-                cf_name
-            )
-        })
-    }
-
     /// Flushes all memtable data. This is only used for testing `get_approximate_sizes_cf` in unit
     /// tests.
     pub fn flush_all(&self) -> Result<()> {
         for cf_name in &self.column_families {
-            let cf_handle = self.get_cf_handle(cf_name)?;
-            self.inner.flush_cf(cf_handle)?;
+            self.inner.flush_cf(cf_name)?;
         }
         Ok(())
     }
 
+    /// Makes a secondary instance (see `open_as_secondary`) see the latest writes made to the
+    /// primary since it was opened or last caught up.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        self.inner.try_catch_up_with_primary()
+    }
+
+    /// Creates a consistent copy of the DB at `path`, which must not exist, without blocking
+    /// writes. SST files are hard linked when `path` is on the same file system as the DB, and
+    /// copied otherwise. The copy can be opened with the same column families as this DB.
+    pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.inner.create_checkpoint(path.as_ref())?;
+        info!(rocksdb_name = self.name, "Created checkpoint.");
+        Ok(())
+    }
+
+    /// Returns the dump of the RocksDB statistics, if enabled in the DB options.
+    pub fn get_statistics(&self) -> Result<Option<String>> {
+        self.inner.get_statistics()
+    }
+
     pub fn get_property(&self, cf_name: &str, property_name: &str) -> Result<u64> {
         self.inner
-            .property_int_value_cf(self.get_cf_handle(cf_name)?, property_name)?
+            .get_property(cf_name, property_name)?
             .ok_or_else(|| {
                 format_err!(
                     "Unable to get property \"{}\" of  column family \"{}\".",
@@ -453,13 +548,14 @@ impl DB {
                 )
             })
     }
-}
 
-/// For now we always use synchronous writes. This makes sure that once the operation returns
-/// `Ok(())` the data is persisted even if the machine crashes. In the future we might consider
-/// selectively turning this off for some non-critical writes to improve performance.
-fn default_write_options() -> rocksdb::WriteOptions {
-    let mut opts = rocksdb::WriteOptions::default();
-    opts.set_sync(true);
-    opts
+    /// Whether the db was opened with the column family.
+    pub fn has_cf(&self, cf_name: &str) -> bool {
+        self.column_families.contains(&cf_name)
+    }
+
+    /// Whether this db only lives in memory, see `open_in_memory`.
+    pub fn is_in_memory(&self) -> bool {
+        self.inner.is_in_memory()
+    }
 }
@@ -349,6 +349,81 @@ fn test_open_as_secondary() {
         db_sec.get::<TestSchema1>(&TestField(0)).unwrap(),
         Some(TestField(0)),
     );
+
+    db.put::<TestSchema1>(&TestField(1), &TestField(1)).unwrap();
+    assert_eq!(db_sec.get::<TestSchema1>(&TestField(1)).unwrap(), None);
+    db_sec.try_catch_up_with_primary().unwrap();
+    assert_eq!(
+        db_sec.get::<TestSchema1>(&TestField(1)).unwrap(),
+        Some(TestField(1)),
+    );
+}
+
+#[test]
+fn test_create_checkpoint() {
+    let tmpdir = mango_temppath::TempPath::new();
+    let checkpoint_dir = mango_temppath::TempPath::new();
+
+    let db = open_db(&tmpdir);
+    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+    db.put::<TestSchema2>(&TestField(1), &TestField(1)).unwrap();
+    db.create_checkpoint(&checkpoint_dir).unwrap();
+
+    // Writes after the checkpoint is created don't show up in it.
+    db.put::<TestSchema1>(&TestField(2), &TestField(2)).unwrap();
+
+    let checkpoint = open_db(&checkpoint_dir);
+    assert_eq!(
+        checkpoint.get::<TestSchema1>(&TestField(0)).unwrap(),
+        Some(TestField(0)),
+    );
+    assert_eq!(
+        checkpoint.get::<TestSchema2>(&TestField(1)).unwrap(),
+        Some(TestField(1)),
+    );
+    assert_eq!(checkpoint.get::<TestSchema1>(&TestField(2)).unwrap(), None);
+
+    // The target must not exist.
+    assert!(db.create_checkpoint(&checkpoint_dir).is_err());
+}
+
+#[test]
+fn test_open_with_cf_options() {
+    let tmpdir = mango_temppath::TempPath::new();
+    let mut db_opts = rocksdb::Options::default();
+    db_opts.create_if_missing(true);
+    db_opts.create_missing_column_families(true);
+    db_opts.enable_statistics();
+
+    let gen_cf_opts = || {
+        get_column_families()
+            .into_iter()
+            .map(|cf_name| {
+                let mut cf_opts = rocksdb::Options::default();
+                cf_opts.set_compression_type(rocksdb::DBCompressionType::Zstd);
+                (cf_name, cf_opts)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let db = DB::open_with_cf_options(&tmpdir.path(), "test", gen_cf_opts(), &db_opts).unwrap();
+    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+    db.flush_all().unwrap();
+    assert_eq!(
+        db.get::<TestSchema1>(&TestField(0)).unwrap(),
+        Some(TestField(0)),
+    );
+    assert!(db
+        .get_statistics()
+        .unwrap()
+        .unwrap()
+        .contains("rocksdb.block.cache.miss"));
+    drop(db);
+
+    // Duplicate column families are rejected.
+    let mut cf_opts = gen_cf_opts();
+    cf_opts.push((TestSchema1::COLUMN_FAMILY_NAME, rocksdb::Options::default()));
+    assert!(DB::open_with_cf_options(&tmpdir.path(), "test", cf_opts, &db_opts).is_err());
 }
 
 #[test]
@@ -0,0 +1,192 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that the in-memory DB behaves the same as the RocksDB backed one.
+
+use anyhow::Result;
+use proptest::{collection::vec, prelude::*};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, Schema, ValueCodec},
+    ColumnFamilyName, SchemaBatch, SchemaIterator, DB, DEFAULT_CF_NAME,
+};
+
+define_schema!(TestSchema, TestKey, TestValue, "TestCF");
+
+#[derive(Debug, Eq, PartialEq)]
+struct TestKey(u8);
+
+#[derive(Debug, Eq, PartialEq)]
+struct TestValue(u8);
+
+impl KeyCodec<TestSchema> for TestKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.0])
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(TestKey(data[0]))
+    }
+}
+
+impl ValueCodec<TestSchema> for TestValue {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(vec![self.0])
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(TestValue(data[0]))
+    }
+}
+
+#[derive(Debug)]
+enum Op {
+    Put(u8, u8),
+    Delete(u8),
+    RangeDelete(u8, u8),
+}
+
+fn arb_op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (any::<u8>(), any::<u8>()).prop_map(|(k, v)| Op::Put(k, v)),
+        any::<u8>().prop_map(Op::Delete),
+        (any::<u8>(), any::<u8>()).prop_map(|(a, b)| Op::RangeDelete(a.min(b), a.max(b))),
+    ]
+}
+
+fn get_column_families() -> Vec<ColumnFamilyName> {
+    vec![DEFAULT_CF_NAME, TestSchema::COLUMN_FAMILY_NAME]
+}
+
+fn open_rocksdb(dir: &mango_temppath::TempPath) -> DB {
+    let mut db_opts = rocksdb::Options::default();
+    db_opts.create_if_missing(true);
+    db_opts.create_missing_column_families(true);
+    DB::open(&dir.path(), "test", get_column_families(), &db_opts).unwrap()
+}
+
+fn apply(db: &DB, batches: &[Vec<Op>]) {
+    for ops in batches {
+        let mut batch = SchemaBatch::new();
+        for op in ops {
+            match op {
+                Op::Put(k, v) => batch.put::<TestSchema>(&TestKey(*k), &TestValue(*v)),
+                Op::Delete(k) => batch.delete::<TestSchema>(&TestKey(*k)),
+                Op::RangeDelete(..) => Ok(()),
+            }
+            .unwrap();
+        }
+        db.write_schemas(batch).unwrap();
+
+        for op in ops {
+            if let Op::RangeDelete(begin, end) = op {
+                db.range_delete::<TestSchema, TestKey>(&TestKey(*begin), &TestKey(*end))
+                    .unwrap();
+            }
+        }
+    }
+}
+
+fn collect(iter: SchemaIterator<TestSchema>) -> Vec<(u8, u8)> {
+    iter.map(|row| {
+        let (key, value) = row.unwrap();
+        (key.0, value.0)
+    })
+    .collect()
+}
+
+fn scans(db: &DB, seek_keys: &[u8]) -> Vec<Vec<(u8, u8)>> {
+    let mut scans = Vec::new();
+
+    let mut iter = db.iter::<TestSchema>(Default::default()).unwrap();
+    iter.seek_to_first();
+    scans.push(collect(iter));
+    let mut iter = db.rev_iter::<TestSchema>(Default::default()).unwrap();
+    iter.seek_to_last();
+    scans.push(collect(iter));
+
+    for seek_key in seek_keys {
+        for rev in [false, true] {
+            let new_iter = || {
+                if rev {
+                    db.rev_iter::<TestSchema>(Default::default()).unwrap()
+                } else {
+                    db.iter::<TestSchema>(Default::default()).unwrap()
+                }
+            };
+            let mut iter = new_iter();
+            iter.seek(&TestKey(*seek_key)).unwrap();
+            scans.push(collect(iter));
+            let mut iter = new_iter();
+            iter.seek_for_prev(&TestKey(*seek_key)).unwrap();
+            scans.push(collect(iter));
+        }
+    }
+    scans
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(20))]
+
+    #[test]
+    fn test_same_as_rocksdb(
+        batches in vec(vec(arb_op(), 1..20), 1..10),
+        seek_keys in vec(any::<u8>(), 1..10),
+    ) {
+        let tmpdir = mango_temppath::TempPath::new();
+        let rocksdb = open_rocksdb(&tmpdir);
+        let in_memory = DB::open_in_memory("test", get_column_families()).unwrap();
+
+        apply(&rocksdb, &batches);
+        apply(&in_memory, &batches);
+
+        prop_assert_eq!(scans(&rocksdb, &seek_keys), scans(&in_memory, &seek_keys));
+        for key in seek_keys {
+            prop_assert_eq!(
+                rocksdb.get::<TestSchema>(&TestKey(key)).unwrap(),
+                in_memory.get::<TestSchema>(&TestKey(key)).unwrap(),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_iterator_snapshot() {
+    let db = DB::open_in_memory("test", get_column_families()).unwrap();
+    db.put::<TestSchema>(&TestKey(1), &TestValue(1)).unwrap();
+
+    let mut iter = db.iter::<TestSchema>(Default::default()).unwrap();
+    db.put::<TestSchema>(&TestKey(2), &TestValue(2)).unwrap();
+    db.range_delete::<TestSchema, TestKey>(&TestKey(0), &TestKey(2))
+        .unwrap();
+
+    // Writes made after the iterator is created are not visible to it.
+    iter.seek_to_first();
+    assert_eq!(collect(iter), [(1, 1)]);
+
+    let mut iter = db.iter::<TestSchema>(Default::default()).unwrap();
+    iter.seek_to_first();
+    assert_eq!(collect(iter), [(2, 2)]);
+}
+
+#[test]
+fn test_open_in_memory() {
+    assert!(DB::open_in_memory("test", vec![TestSchema::COLUMN_FAMILY_NAME]).is_err());
+    assert!(DB::open_in_memory(
+        "test",
+        vec![
+            DEFAULT_CF_NAME,
+            TestSchema::COLUMN_FAMILY_NAME,
+            TestSchema::COLUMN_FAMILY_NAME,
+        ],
+    )
+    .is_err());
+
+    // Writing to a column family that the DB wasn't opened with fails.
+    let db = DB::open_in_memory("test", vec![DEFAULT_CF_NAME]).unwrap();
+    assert!(db.is_in_memory());
+    let mut batch = SchemaBatch::new();
+    batch.put::<TestSchema>(&TestKey(1), &TestValue(1)).unwrap();
+    assert!(db.write_schemas(batch).is_err());
+    assert!(db.get::<TestSchema>(&TestKey(1)).is_err());
+}
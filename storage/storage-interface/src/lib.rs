@@ -17,15 +17,18 @@ use mango_types::{
     move_resource::MoveStorage,
     proof::{
         definition::LeafCount, AccumulatorConsistencyProof, SparseMerkleProof,
-        TransactionAccumulatorSummary,
+        SparseMerkleRangeProof, TransactionAccumulatorSummary,
     },
     state_proof::StateProof,
     transaction::{
         AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof,
-        TransactionToCommit, TransactionWithProof, Version,
+        TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
     },
 };
-use move_core_types::resolver::{ModuleResolver, ResourceResolver};
+use move_core_types::{
+    language_storage::TypeTag,
+    resolver::{ModuleResolver, ResourceResolver},
+};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
@@ -190,6 +193,35 @@ pub trait DbReader: Send + Sync {
         fetch_events: bool,
     ) -> Result<TransactionListWithProof>;
 
+    /// See [`DiemDB::get_transaction_outputs`].
+    ///
+    /// [`DiemDB::get_transaction_outputs`]:
+    /// ../diemdb/struct.DiemDB.html#method.get_transaction_outputs
+    fn get_transaction_outputs(
+        &self,
+        _start_version: Version,
+        _limit: u64,
+        _ledger_version: Version,
+    ) -> Result<TransactionOutputListWithProof> {
+        unimplemented!()
+    }
+
+    /// Gets at most `limit` accounts of the state snapshot at `version` that follow `last_key`
+    /// (or that start the snapshot if `last_key` is `None`), along with the range proof of the
+    /// rightmost account returned.
+    /// See [`DiemDB::get_state_snapshot_chunk`].
+    ///
+    /// [`DiemDB::get_state_snapshot_chunk`]:
+    /// ../diemdb/struct.DiemDB.html#method.get_state_snapshot_chunk
+    fn get_state_snapshot_chunk(
+        &self,
+        _version: Version,
+        _last_key: Option<HashValue>,
+        _limit: usize,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        unimplemented!()
+    }
+
     /// Returns events by given event key
     fn get_events(
         &self,
@@ -199,6 +231,25 @@ pub trait DbReader: Send + Sync {
         limit: u64,
     ) -> Result<Vec<(u64, ContractEvent)>>;
 
+    /// Returns events of the given type emitted by transactions with
+    /// `start_version <= version < end_version` and `version <= ledger_version`, together with the
+    /// version of their transaction. Requires the event by type index to be enabled.
+    ///
+    /// At most `limit` events are returned. The first `start_index` events of the given type
+    /// emitted by the transaction at `start_version` are skipped, so that the next page resumes
+    /// after the last returned event even if it ended in the middle of a transaction.
+    fn get_events_by_type(
+        &self,
+        _type_tag: &TypeTag,
+        _start_version: Version,
+        _start_index: u64,
+        _end_version: Version,
+        _limit: u64,
+        _ledger_version: Version,
+    ) -> Result<Vec<(u64, ContractEvent)>> {
+        unimplemented!()
+    }
+
     /// Returns events by given event key
     fn get_events_with_proofs(
         &self,
@@ -287,6 +338,17 @@ pub trait DbReader: Send + Sync {
         ledger_version: Version,
     ) -> Result<AccountTransactionsWithProof>;
 
+    /// Returns the transaction with the given `hash`, with a proof relative to `ledger_version`.
+    /// If no such transaction is committed at or before `ledger_version`, returns `None`.
+    fn get_transaction_by_hash(
+        &self,
+        _hash: HashValue,
+        _ledger_version: Version,
+        _fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        unimplemented!()
+    }
+
     /// Returns proof of new state for a given ledger info with signatures relative to version known
     /// to client
     fn get_state_proof_with_ledger_info(
@@ -464,6 +526,49 @@ pub trait DbWriter: Send + Sync {
         first_version: Version,
         ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
     ) -> Result<()>;
+
+    /// Starts restoring the state snapshot at `version`, whose state root hash is
+    /// `expected_root_hash`.
+    /// See [`DiemDB::get_state_snapshot_receiver`].
+    ///
+    /// [`DiemDB::get_state_snapshot_receiver`]:
+    /// ../diemdb/struct.DiemDB.html#method.get_state_snapshot_receiver
+    fn get_state_snapshot_receiver(
+        &self,
+        _version: Version,
+        _expected_root_hash: HashValue,
+    ) -> Result<Box<dyn StateSnapshotReceiver>> {
+        unimplemented!()
+    }
+
+    /// Commits the transaction at the version of a restored state snapshot, along with the
+    /// epoch ending ledger infos proving it, so that the DB can continue from that version.
+    /// See [`DiemDB::finalize_state_snapshot`].
+    ///
+    /// [`DiemDB::finalize_state_snapshot`]:
+    /// ../diemdb/struct.DiemDB.html#method.finalize_state_snapshot
+    fn finalize_state_snapshot(
+        &self,
+        _version: Version,
+        _txn_list_with_proof: TransactionListWithProof,
+        _ledger_infos: &[LedgerInfoWithSignatures],
+    ) -> Result<()> {
+        unimplemented!()
+    }
+}
+
+/// Receives the chunks of a state snapshot being restored, verifying each of them against its
+/// range proof before writing it to the DB.
+pub trait StateSnapshotReceiver: Send {
+    /// Verifies and writes a chunk of accounts, which must follow the previously added ones.
+    fn add_chunk(
+        &mut self,
+        chunk: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<()>;
+
+    /// Writes the remaining nodes of the state tree once every chunk has been added.
+    fn finish_box(self: Box<Self>) -> Result<()>;
 }
 
 pub trait MoveDbReader:
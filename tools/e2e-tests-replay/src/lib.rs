@@ -23,8 +23,8 @@ use diem_vm::{
     natives::diem_natives,
     script_to_script_function::remapping,
     system_module_names::{
-        BLOCK_PROLOGUE, DIEM_BLOCK_MODULE, SCRIPT_PROLOGUE_NAME, USER_EPILOGUE_NAME,
-        WRITESET_EPILOGUE_NAME, WRITESET_PROLOGUE_NAME,
+        BLOCK_PROLOGUE, BLOCK_PROLOGUE_EXT, DIEM_BLOCK_MODULE, SCRIPT_PROLOGUE_NAME,
+        USER_EPILOGUE_NAME, WRITESET_EPILOGUE_NAME, WRITESET_PROLOGUE_NAME,
     },
     transaction_metadata::TransactionMetadata,
 };
@@ -482,21 +482,35 @@ impl<'env> TraceReplayer<'env> {
     pub fn replay_txn_block_metadata(
         &self,
         block_metadata: BlockMetadata,
+        failed_proposers: Option<Vec<AccountAddress>>,
         expect_output: &TransactionOutput,
     ) {
         // args
         let signer = reserved_vm_address();
         let (round, timestamp, previous_votes, proposer) = block_metadata.into_inner();
-        let args: Vec<_> = vec![
+        let mut args = vec![
             MoveValue::Signer(signer),
             MoveValue::U64(round),
             MoveValue::U64(timestamp),
             MoveValue::Vector(previous_votes.into_iter().map(MoveValue::Address).collect()),
             MoveValue::Address(proposer),
-        ]
-        .into_iter()
-        .map(|v| v.simple_serialize().unwrap())
-        .collect();
+        ];
+        let function_name = match failed_proposers {
+            Some(failed_proposers) => {
+                args.push(MoveValue::Vector(
+                    failed_proposers
+                        .into_iter()
+                        .map(MoveValue::Address)
+                        .collect(),
+                ));
+                BLOCK_PROLOGUE_EXT
+            }
+            None => BLOCK_PROLOGUE,
+        };
+        let args: Vec<_> = args
+            .into_iter()
+            .map(|v| v.simple_serialize().unwrap())
+            .collect();
 
         // execute
         let move_vm = MoveVM::new(diem_natives()).unwrap();
@@ -515,7 +529,7 @@ impl<'env> TraceReplayer<'env> {
             &mut session,
             xrunner.as_mut(),
             &*DIEM_BLOCK_MODULE,
-            BLOCK_PROLOGUE,
+            function_name,
             vec![],
             args,
         );
@@ -940,7 +954,7 @@ fn replay_trace<P: AsRef<Path>>(
                         return Ok(());
                     }
                 }
-                Transaction::BlockMetadata(block_metadata) => {
+                Transaction::BlockMetadata(_) | Transaction::BlockMetadataExt(_) => {
                     if !matches!(
                         res.status(),
                         TransactionStatus::Keep(KeptVMStatus::Executed)
@@ -953,7 +967,15 @@ fn replay_trace<P: AsRef<Path>>(
                         }
                         return Ok(());
                     }
-                    replayer.replay_txn_block_metadata(block_metadata, &res);
+                    let (block_metadata, failed_proposers) = match txn {
+                        Transaction::BlockMetadata(block_metadata) => (block_metadata, None),
+                        Transaction::BlockMetadataExt(block_metadata) => {
+                            let (block_metadata, failed_proposers) = block_metadata.into_inner();
+                            (block_metadata, Some(failed_proposers))
+                        }
+                        _ => unreachable!(),
+                    };
+                    replayer.replay_txn_block_metadata(block_metadata, failed_proposers, &res);
                     replayer.data_store.add_write_set(res.write_set());
                 }
                 Transaction::UserTransaction(signed_txn) => {
@@ -6,6 +6,7 @@ use handlebars::Handlebars;
 use mango_types::{
     account_address::AccountAddress,
     account_config::diem_root_address,
+    on_chain_config::OnChainConsensusConfig,
     transaction::{Script, WriteSetPayload},
 };
 use move_lang::{compiled_unit::AnnotatedCompiledUnit, Compiler, Flags};
@@ -102,3 +103,28 @@ pub fn encode_halt_network_payload() -> WriteSetPayload {
         execute_as: diem_root_address(),
     }
 }
+
+pub fn encode_update_consensus_config_payload(config: OnChainConsensusConfig) -> WriteSetPayload {
+    let mut script = template_path();
+    script.push("update_consensus_config.move");
+
+    let script = {
+        let mut hb = Handlebars::new();
+        hb.set_strict_mode(true);
+        hb.register_template_file("script", script).unwrap();
+        let mut data = HashMap::new();
+        data.insert(
+            "config",
+            hex::encode(bcs::to_bytes(&config).expect("Unable to serialize consensus config")),
+        );
+
+        let output = hb.render("script", &data).unwrap();
+
+        compile_admin_script(output.as_str()).unwrap()
+    };
+
+    WriteSetPayload::Script {
+        script,
+        execute_as: diem_root_address(),
+    }
+}
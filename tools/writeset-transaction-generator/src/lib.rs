@@ -9,6 +9,7 @@ mod writeset_builder;
 
 pub use admin_script_builder::{
     encode_custom_script, encode_halt_network_payload, encode_remove_validators_payload,
+    encode_update_consensus_config_payload,
 };
 
 pub use release_flow::{create_release, verify_release};
@@ -5,13 +5,14 @@ use anyhow::{bail, format_err, Result};
 use mango_types::{
     account_address::AccountAddress,
     chain_id::ChainId,
+    on_chain_config::OnChainConsensusConfig,
     transaction::{Transaction, TransactionPayload},
 };
 
 use diem_writeset_generator::{
     create_release, encode_custom_script, encode_halt_network_payload,
-    encode_remove_validators_payload, release_flow::artifacts::load_latest_artifact,
-    verify_release,
+    encode_remove_validators_payload, encode_update_consensus_config_payload,
+    release_flow::artifacts::load_latest_artifact, verify_release,
 };
 use move_binary_format::CompiledModule;
 use std::path::PathBuf;
@@ -39,6 +40,9 @@ enum Command {
     /// Block the execution of any transaction in the network
     #[structopt(name = "halt-network")]
     HaltNetwork,
+    /// Replace the on-chain consensus config with the JSON-encoded `OnChainConsensusConfig`
+    #[structopt(name = "update-consensus-config")]
+    UpdateConsensusConfig { config: String },
     /// Build a custom file in templates into admin script
     #[structopt(name = "build-custom-script")]
     BuildCustomScript {
@@ -110,6 +114,9 @@ fn main() -> Result<()> {
     let payload = match opt.cmd {
         Command::RemoveValidators { addresses } => encode_remove_validators_payload(addresses),
         Command::HaltNetwork => encode_halt_network_payload(),
+        Command::UpdateConsensusConfig { config } => encode_update_consensus_config_payload(
+            serde_json::from_str::<OnChainConsensusConfig>(config.as_str())?,
+        ),
         Command::BuildCustomScript {
             script_name,
             args,
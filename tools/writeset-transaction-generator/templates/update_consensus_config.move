@@ -0,0 +1,6 @@
+script {
+    use DiemFramework::DiemConsensusConfig;
+    fun main(diem_root: signer) {
+        DiemConsensusConfig::set(&diem_root, x"{{config}}");
+    }
+}
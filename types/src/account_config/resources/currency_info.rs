@@ -101,6 +101,24 @@ impl CurrencyInfoResource {
         (self.to_xdx_exchange_rate as f32) / 2f32.powf(32f32)
     }
 
+    /// The raw 32|32 fixed-point exchange rate to XDX stored on-chain.
+    pub fn to_xdx_exchange_rate(&self) -> u64 {
+        self.to_xdx_exchange_rate
+    }
+
+    /// Approximates the XDX value of `value` exactly like `Diem::approx_xdx_for_value` does
+    /// on-chain, i.e. with `FixedPoint32::multiply_u64`. Returns `None` where the Move function
+    /// aborts because the result does not fit in a u64.
+    pub fn approx_xdx_for_value(&self, value: u64) -> Option<u64> {
+        let product = (value as u128) * (self.to_xdx_exchange_rate as u128);
+        let xdx = product >> 32;
+        if xdx > u64::max_value() as u128 {
+            None
+        } else {
+            Some(xdx as u64)
+        }
+    }
+
     pub fn convert_to_xdx(&self, amount: u64) -> u64 {
         (self.exchange_rate() * (amount as f32)) as u64
     }
@@ -0,0 +1,42 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use mango_bitvec::BitVec;
+use mango_crypto::bls12381::Bls12381Signature;
+use serde::{Deserialize, Serialize};
+
+/// A single BLS12-381 signature aggregated from the signatures of several validators on the
+/// same message. The signers are identified by a bit vector over the validators ordered by
+/// account address, as returned by `ValidatorVerifier::get_ordered_account_addresses_iter`.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AggregateSignature {
+    validator_bitmask: BitVec,
+    sig: Option<Bls12381Signature>,
+}
+
+impl AggregateSignature {
+    pub fn new(validator_bitmask: BitVec, aggregated_signature: Option<Bls12381Signature>) -> Self {
+        Self {
+            validator_bitmask,
+            sig: aggregated_signature,
+        }
+    }
+
+    /// An aggregate signature without any signer, e.g. for the genesis ledger info.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    pub fn get_signers_bitvec(&self) -> &BitVec {
+        &self.validator_bitmask
+    }
+
+    pub fn sig(&self) -> Option<&Bls12381Signature> {
+        self.sig.as_ref()
+    }
+
+    /// Returns the number of validators whose signature is aggregated.
+    pub fn num_signers(&self) -> usize {
+        self.validator_bitmask.count_ones() as usize
+    }
+}
@@ -61,7 +61,7 @@ impl BlockMetadata {
         (
             self.round,
             self.timestamp_usecs,
-            self.previous_block_votes.clone(),
+            self.previous_block_votes,
             self.proposer,
         )
     }
@@ -75,6 +75,55 @@ impl BlockMetadata {
     }
 }
 
+/// Block metadata carrying the fields added after the layout of [`BlockMetadata`] was persisted.
+/// New fields go into a new variant, so that every committed block metadata transaction keeps
+/// decoding.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockMetadataExt {
+    V0 {
+        block_metadata: BlockMetadata,
+        // Proposers of the rounds between the parent block and this one that produced no
+        // certified block, in increasing round order.
+        failed_proposers: Vec<AccountAddress>,
+    },
+}
+
+impl BlockMetadataExt {
+    pub fn new(block_metadata: BlockMetadata, failed_proposers: Vec<AccountAddress>) -> Self {
+        BlockMetadataExt::V0 {
+            block_metadata,
+            failed_proposers,
+        }
+    }
+
+    pub fn block_metadata(&self) -> &BlockMetadata {
+        match self {
+            BlockMetadataExt::V0 { block_metadata, .. } => block_metadata,
+        }
+    }
+
+    pub fn failed_proposers(&self) -> &[AccountAddress] {
+        match self {
+            BlockMetadataExt::V0 {
+                failed_proposers, ..
+            } => failed_proposers,
+        }
+    }
+
+    pub fn id(&self) -> HashValue {
+        self.block_metadata().id()
+    }
+
+    pub fn into_inner(self) -> (BlockMetadata, Vec<AccountAddress>) {
+        match self {
+            BlockMetadataExt::V0 {
+                block_metadata,
+                failed_proposers,
+            } => (block_metadata, failed_proposers),
+        }
+    }
+}
+
 pub fn new_block_event_key() -> EventKey {
     EventKey::new_from_address(&diem_root_address(), 17)
 }
@@ -110,12 +159,16 @@ impl MoveStructType for DiemBlockResource {
 
 impl MoveResource for DiemBlockResource {}
 
+/// The NewBlockEvent emitted by `DiemBlock::block_prologue`. Events emitted before the
+/// `failed_proposers` field was added are decoded by [`NewBlockEvent::try_from_bytes`] with no
+/// failed proposers.
 #[derive(Clone, Deserialize, Serialize)]
 pub struct NewBlockEvent {
     round: u64,
     proposer: AccountAddress,
     votes: Vec<AccountAddress>,
     timestamp: u64,
+    failed_proposers: Vec<AccountAddress>,
 }
 
 impl NewBlockEvent {
@@ -124,12 +177,14 @@ impl NewBlockEvent {
         proposer: AccountAddress,
         votes: Vec<AccountAddress>,
         timestamp: u64,
+        failed_proposers: Vec<AccountAddress>,
     ) -> Self {
         Self {
             round,
             proposer,
             votes,
             timestamp,
+            failed_proposers,
         }
     }
     pub fn round(&self) -> u64 {
@@ -143,4 +198,37 @@ impl NewBlockEvent {
     pub fn votes(&self) -> Vec<AccountAddress> {
         self.votes.clone()
     }
+
+    pub fn failed_proposers(&self) -> &[AccountAddress] {
+        &self.failed_proposers
+    }
+
+    pub fn try_from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        bcs::from_bytes(bytes).or_else(|e| {
+            bcs::from_bytes::<NewBlockEventV0>(bytes)
+                .map(Into::into)
+                .map_err(|_| e.into())
+        })
+    }
+}
+
+/// The layout of [`NewBlockEvent`] before the failed proposers were recorded.
+#[derive(Deserialize)]
+struct NewBlockEventV0 {
+    round: u64,
+    proposer: AccountAddress,
+    votes: Vec<AccountAddress>,
+    timestamp: u64,
+}
+
+impl From<NewBlockEventV0> for NewBlockEvent {
+    fn from(event: NewBlockEventV0) -> Self {
+        Self::new(
+            event.round,
+            event.proposer,
+            event.votes,
+            event.timestamp,
+            vec![],
+        )
+    }
 }
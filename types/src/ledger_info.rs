@@ -3,6 +3,7 @@
 
 use crate::{
     account_address::AccountAddress,
+    aggregate_signature::AggregateSignature,
     block_info::{BlockInfo, Round},
     epoch_state::EpochState,
     on_chain_config::ValidatorSet,
@@ -16,7 +17,6 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     fmt::{Display, Formatter},
-    ops::{Deref, DerefMut},
 };
 
 /// This structure serves a dual purpose.
@@ -132,12 +132,14 @@ impl LedgerInfo {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum LedgerInfoWithSignatures {
     V0(LedgerInfoWithV0),
+    V1(LedgerInfoWithV1),
 }
 
 impl Display for LedgerInfoWithSignatures {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
             LedgerInfoWithSignatures::V0(ledger) => write!(f, "{}", ledger),
+            LedgerInfoWithSignatures::V1(ledger) => write!(f, "{}", ledger),
         }
     }
 }
@@ -151,30 +153,76 @@ impl LedgerInfoWithSignatures {
         LedgerInfoWithSignatures::V0(LedgerInfoWithV0::new(ledger_info, signatures))
     }
 
+    /// Creates a ledger info certified by a single BLS12-381 aggregate signature, used once
+    /// aggregated signatures are enabled in the on-chain consensus config.
+    pub fn new_with_aggregate_signature(
+        ledger_info: LedgerInfo,
+        signatures: AggregateSignature,
+    ) -> Self {
+        LedgerInfoWithSignatures::V1(LedgerInfoWithV1::new(ledger_info, signatures))
+    }
+
     pub fn genesis(genesis_state_root_hash: HashValue, validator_set: ValidatorSet) -> Self {
         LedgerInfoWithSignatures::V0(LedgerInfoWithV0::genesis(
             genesis_state_root_hash,
             validator_set,
         ))
     }
-}
 
-// Temporary hack to avoid massive changes, it won't work when new variant comes and needs proper
-// dispatch at that time.
-impl Deref for LedgerInfoWithSignatures {
-    type Target = LedgerInfoWithV0;
+    pub fn ledger_info(&self) -> &LedgerInfo {
+        match self {
+            LedgerInfoWithSignatures::V0(ledger) => ledger.ledger_info(),
+            LedgerInfoWithSignatures::V1(ledger) => ledger.ledger_info(),
+        }
+    }
 
-    fn deref(&self) -> &LedgerInfoWithV0 {
-        match &self {
-            LedgerInfoWithSignatures::V0(ledger) => ledger,
+    pub fn commit_info(&self) -> &BlockInfo {
+        self.ledger_info().commit_info()
+    }
+
+    /// Returns the number of validators who signed the ledger info.
+    pub fn num_signers(&self) -> usize {
+        match self {
+            LedgerInfoWithSignatures::V0(ledger) => ledger.signatures().len(),
+            LedgerInfoWithSignatures::V1(ledger) => ledger.signatures().num_signers(),
+        }
+    }
+
+    /// Returns the addresses of the validators who signed the ledger info, in account address
+    /// order. `validators` must be the ordered account addresses of the validator set of the
+    /// ledger info's epoch, as the aggregate signature only records the signers' positions.
+    pub fn get_voters(&self, validators: &[AccountAddress]) -> Vec<AccountAddress> {
+        match self {
+            LedgerInfoWithSignatures::V0(ledger) => ledger.signatures().keys().cloned().collect(),
+            LedgerInfoWithSignatures::V1(ledger) => {
+                let bitvec = ledger.signatures().get_signers_bitvec();
+                validators
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| *index <= u8::MAX as usize && bitvec.is_set(*index as u8))
+                    .map(|(_, address)| *address)
+                    .collect()
+            }
+        }
+    }
+
+    pub fn verify_signatures(
+        &self,
+        validator: &ValidatorVerifier,
+    ) -> ::std::result::Result<(), VerifyError> {
+        match self {
+            LedgerInfoWithSignatures::V0(ledger) => ledger.verify_signatures(validator),
+            LedgerInfoWithSignatures::V1(ledger) => ledger.verify_signatures(validator),
         }
     }
-}
 
-impl DerefMut for LedgerInfoWithSignatures {
-    fn deref_mut(&mut self) -> &mut LedgerInfoWithV0 {
+    pub fn check_voting_power(
+        &self,
+        validator: &ValidatorVerifier,
+    ) -> ::std::result::Result<(), VerifyError> {
         match self {
-            LedgerInfoWithSignatures::V0(ref mut ledger) => ledger,
+            LedgerInfoWithSignatures::V0(ledger) => ledger.check_voting_power(validator),
+            LedgerInfoWithSignatures::V1(ledger) => ledger.check_voting_power(validator),
         }
     }
 }
@@ -258,6 +306,56 @@ impl LedgerInfoWithV0 {
     }
 }
 
+/// A ledger info certified by one BLS12-381 signature aggregated from the signatures of a quorum
+/// of validators, whose positions in the epoch's validator set are recorded in a bitvec.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LedgerInfoWithV1 {
+    ledger_info: LedgerInfo,
+    signatures: AggregateSignature,
+}
+
+impl Display for LedgerInfoWithV1 {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.ledger_info)
+    }
+}
+
+impl LedgerInfoWithV1 {
+    pub fn new(ledger_info: LedgerInfo, signatures: AggregateSignature) -> Self {
+        LedgerInfoWithV1 {
+            ledger_info,
+            signatures,
+        }
+    }
+
+    pub fn ledger_info(&self) -> &LedgerInfo {
+        &self.ledger_info
+    }
+
+    pub fn commit_info(&self) -> &BlockInfo {
+        self.ledger_info.commit_info()
+    }
+
+    pub fn signatures(&self) -> &AggregateSignature {
+        &self.signatures
+    }
+
+    pub fn verify_signatures(
+        &self,
+        validator: &ValidatorVerifier,
+    ) -> ::std::result::Result<(), VerifyError> {
+        validator.verify_multi_signatures(self.ledger_info(), &self.signatures)
+    }
+
+    pub fn check_voting_power(
+        &self,
+        validator: &ValidatorVerifier,
+    ) -> ::std::result::Result<(), VerifyError> {
+        let signers = validator.get_signers_from_bitvec(self.signatures.get_signers_bitvec())?;
+        validator.check_voting_power(signers.iter())
+    }
+}
+
 //
 // Arbitrary implementation of LedgerInfoWithV0 (for fuzzing)
 //
@@ -294,7 +392,7 @@ impl Arbitrary for LedgerInfoWithV0 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::validator_signer::ValidatorSigner;
+    use crate::{validator_signer::ValidatorSigner, validator_verifier::random_validator_verifier};
 
     #[test]
     fn test_signatures_hash() {
@@ -333,4 +431,48 @@ mod tests {
             ledger_info_with_signatures_reversed_bytes
         );
     }
+
+    #[test]
+    fn test_aggregate_signature_ledger_info() {
+        let ledger_info = LedgerInfo::new(BlockInfo::empty(), HashValue::random());
+        let (signers, verifier) = random_validator_verifier(4, None, false);
+
+        let signatures: BTreeMap<_, _> = signers
+            .iter()
+            .take(3)
+            .map(|signer| (signer.author(), signer.sign_bls(&ledger_info).unwrap()))
+            .collect();
+        let aggregate_signature = verifier.aggregate_signatures(&signatures).unwrap();
+        let ledger_info_with_signatures = LedgerInfoWithSignatures::new_with_aggregate_signature(
+            ledger_info.clone(),
+            aggregate_signature,
+        );
+        assert_eq!(ledger_info_with_signatures.num_signers(), 3);
+        assert!(ledger_info_with_signatures
+            .verify_signatures(&verifier)
+            .is_ok());
+
+        let ordered_addresses: Vec<_> = verifier.get_ordered_account_addresses_iter().collect();
+        let mut expected_voters: Vec<_> = signatures.keys().cloned().collect();
+        expected_voters.sort();
+        assert_eq!(
+            ledger_info_with_signatures.get_voters(&ordered_addresses),
+            expected_voters
+        );
+
+        // Two signatures are not enough for a quorum of 4 validators.
+        let signatures: BTreeMap<_, _> = signatures.into_iter().take(2).collect();
+        let aggregate_signature = verifier.aggregate_signatures(&signatures).unwrap();
+        let ledger_info_with_signatures = LedgerInfoWithSignatures::new_with_aggregate_signature(
+            ledger_info,
+            aggregate_signature,
+        );
+        assert_eq!(
+            ledger_info_with_signatures.verify_signatures(&verifier),
+            Err(VerifyError::TooLittleVotingPower {
+                voting_power: 2,
+                quorum_voting_power: 3,
+            })
+        );
+    }
 }
@@ -275,7 +275,7 @@ impl NetworkAddress {
     /// `"/dns4/<domain>/tcp/<port>"` or
     /// `"/dns6/<domain>/tcp/<port>"` or
     /// `"/dns/<domain>/tcp/<port>"` or
-    /// cfg!(test) `"/memory/<port>"`
+    /// cfg!(any(test, feature = "fuzzing")) `"/memory/<port>"`
     ///
     /// followed by transport upgrade handshake protocols:
     ///
@@ -777,13 +777,13 @@ fn parse_diemnet_protos(protos: &[Protocol]) -> Option<&[Protocol]> {
     // ---
     // parse_ip_tcp
     // <or> parse_dns_tcp
-    // <or> cfg!(test) parse_memory
+    // <or> cfg!(any(test, feature = "fuzzing")) parse_memory
 
     let transport_suffix = parse_ip_tcp(protos)
         .map(|x| x.1)
         .or_else(|| parse_dns_tcp(protos).map(|x| x.1))
         .or_else(|| {
-            if cfg!(test) {
+            if cfg!(any(test, feature = "fuzzing")) {
                 parse_memory(protos).map(|x| x.1)
             } else {
                 None
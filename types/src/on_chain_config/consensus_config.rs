@@ -1,20 +1,63 @@
 // Copyright (c) The Mango Blockchain Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::on_chain_config::OnChainConfig;
+use crate::{account_address::AccountAddress, block_info::Round, on_chain_config::OnChainConfig};
 use anyhow::{format_err, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// The on-chain consensus config, in order to be able to add fields, we use enum to wrap the actual struct.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum OnChainConsensusConfig {
     V1(ConsensusConfigV1),
+    V2(ConsensusConfigV2),
 }
 
 impl OnChainConsensusConfig {
     pub fn two_chain(&self) -> bool {
         match &self {
             OnChainConsensusConfig::V1(config) => config.two_chain,
+            OnChainConsensusConfig::V2(config) => config.two_chain,
+        }
+    }
+
+    /// Whether quorum certificates and ledger infos should carry a single BLS12-381 aggregate
+    /// signature instead of one Ed25519 signature per validator.
+    pub fn aggregate_signatures(&self) -> bool {
+        match &self {
+            OnChainConsensusConfig::V1(_) => false,
+            OnChainConsensusConfig::V2(config) => config.aggregate_signatures,
+        }
+    }
+
+    /// Whether transactions are disseminated in batches ahead of the proposals, which then only
+    /// carry proofs of store of the batches.
+    pub fn quorum_store(&self) -> bool {
+        match &self {
+            OnChainConsensusConfig::V1(_) => false,
+            OnChainConsensusConfig::V2(config) => config.quorum_store,
+        }
+    }
+
+    /// The proposer election every validator should use, if it is governed on-chain.
+    pub fn proposer_election_type(&self) -> Option<&ProposerElectionType> {
+        match &self {
+            OnChainConsensusConfig::V1(_) => None,
+            OnChainConsensusConfig::V2(config) => config.proposer_election_type.as_ref(),
+        }
+    }
+
+    pub fn max_block_size(&self) -> Option<u64> {
+        match &self {
+            OnChainConsensusConfig::V1(_) => None,
+            OnChainConsensusConfig::V2(config) => config.max_block_size,
+        }
+    }
+
+    pub fn round_initial_timeout_ms(&self) -> Option<u64> {
+        match &self {
+            OnChainConsensusConfig::V1(_) => None,
+            OnChainConsensusConfig::V2(config) => config.round_initial_timeout_ms,
         }
     }
 }
@@ -37,6 +80,51 @@ impl Default for ConsensusConfigV1 {
     }
 }
 
+/// Parameters left as `None` fall back to the node's local consensus config.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ConsensusConfigV2 {
+    pub two_chain: bool,
+    pub aggregate_signatures: bool,
+    pub proposer_election_type: Option<ProposerElectionType>,
+    pub max_block_size: Option<u64>,
+    pub round_initial_timeout_ms: Option<u64>,
+    pub quorum_store: bool,
+}
+
+/// On-chain counterpart of the node's `ConsensusProposerType`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ProposerElectionType {
+    // Choose the smallest PeerId as the proposer, for the given number of contiguous rounds
+    FixedProposer(u32),
+    // Round robin rotation of proposers, for the given number of contiguous rounds
+    RotatingProposer(u32),
+    // Committed history based proposer election
+    LeaderReputation(LeaderReputationWeights),
+    // Pre-specified proposers for each round,
+    // or default proposer if round proposer not
+    // specified
+    RoundProposer(BTreeMap<Round, AccountAddress>),
+    // Committed history based proposer election, also weighting the failed proposer rounds and
+    // the vote participation of the validators. Requires `DiemBlock::block_prologue_ext`, as
+    // the proposals record the failed proposer rounds.
+    ProposerAndVoter(ProposerAndVoterWeights),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LeaderReputationWeights {
+    pub active_weights: u64,
+    pub inactive_weights: u64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProposerAndVoterWeights {
+    pub active_weights: u64,
+    pub inactive_weights: u64,
+    pub failed_weights: u64,
+    pub failure_threshold_percent: u64,
+    pub voter_threshold_percent: u64,
+}
+
 impl OnChainConfig for OnChainConsensusConfig {
     const IDENTIFIER: &'static str = "DiemConsensusConfig";
 
@@ -53,3 +141,55 @@ impl OnChainConfig for OnChainConsensusConfig {
             .map_err(|e| format_err!("[on-chain config] Failed to deserialize into config: {}", e))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_consensus_config_v2_round_trip() {
+        let config = OnChainConsensusConfig::V2(ConsensusConfigV2 {
+            two_chain: true,
+            aggregate_signatures: false,
+            proposer_election_type: Some(ProposerElectionType::LeaderReputation(
+                LeaderReputationWeights {
+                    active_weights: 99,
+                    inactive_weights: 1,
+                },
+            )),
+            max_block_size: Some(500),
+            round_initial_timeout_ms: None,
+            quorum_store: true,
+        });
+        // The move resource wraps the serialized config in a vector<u8>.
+        let bytes = bcs::to_bytes(&bcs::to_bytes(&config).unwrap()).unwrap();
+        let decoded = OnChainConsensusConfig::deserialize_into_config(&bytes).unwrap();
+        assert_eq!(decoded, config);
+        assert_eq!(decoded.max_block_size(), Some(500));
+        assert_eq!(decoded.round_initial_timeout_ms(), None);
+        assert!(decoded.quorum_store());
+
+        let config = OnChainConsensusConfig::V2(ConsensusConfigV2 {
+            proposer_election_type: Some(ProposerElectionType::ProposerAndVoter(
+                ProposerAndVoterWeights {
+                    active_weights: 1000,
+                    inactive_weights: 10,
+                    failed_weights: 1,
+                    failure_threshold_percent: 10,
+                    voter_threshold_percent: 50,
+                },
+            )),
+            ..ConsensusConfigV2::default()
+        });
+        let bytes = bcs::to_bytes(&bcs::to_bytes(&config).unwrap()).unwrap();
+        assert_eq!(
+            OnChainConsensusConfig::deserialize_into_config(&bytes).unwrap(),
+            config
+        );
+
+        let v1 = OnChainConsensusConfig::default();
+        assert!(v1.proposer_election_type().is_none());
+        assert!(v1.max_block_size().is_none());
+        assert!(!v1.quorum_store());
+    }
+}
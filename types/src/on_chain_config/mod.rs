@@ -20,16 +20,21 @@ use std::{collections::HashMap, fmt, sync::Arc};
 mod consensus_config;
 mod diem_version;
 mod registered_currencies;
+mod validator_bls_keys;
 mod validator_set;
 mod vm_config;
 mod vm_publishing_option;
 
 pub use self::{
-    consensus_config::{ConsensusConfigV1, OnChainConsensusConfig},
+    consensus_config::{
+        ConsensusConfigV1, ConsensusConfigV2, LeaderReputationWeights, OnChainConsensusConfig,
+        ProposerAndVoterWeights, ProposerElectionType,
+    },
     diem_version::{
         DiemVersion, DIEM_MAX_KNOWN_VERSION, DIEM_VERSION_2, DIEM_VERSION_3, DIEM_VERSION_4,
     },
     registered_currencies::RegisteredCurrencies,
+    validator_bls_keys::{ValidatorBlsKey, ValidatorBlsKeys},
     validator_set::ValidatorSet,
     vm_config::VMConfig,
     vm_publishing_option::VMPublishingOption,
@@ -75,6 +80,7 @@ pub const ON_CHAIN_CONFIG_REGISTRY: &[ConfigID] = &[
     ValidatorSet::CONFIG_ID,
     RegisteredCurrencies::CONFIG_ID,
     OnChainConsensusConfig::CONFIG_ID,
+    ValidatorBlsKeys::CONFIG_ID,
 ];
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -0,0 +1,76 @@
+// Copyright (c) The Mango Blockchain Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account_address::AccountAddress, on_chain_config::OnChainConfig};
+use anyhow::Result;
+use mango_crypto::bls12381::{Bls12381ProofOfPossession, Bls12381PublicKey};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, convert::TryFrom};
+
+/// A BLS12-381 consensus key registered on-chain by a validator operator.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorBlsKey {
+    account_address: AccountAddress,
+    public_key: Vec<u8>,
+    proof_of_possession: Vec<u8>,
+}
+
+impl ValidatorBlsKey {
+    pub fn new(
+        account_address: AccountAddress,
+        public_key: &Bls12381PublicKey,
+        proof_of_possession: &Bls12381ProofOfPossession,
+    ) -> Self {
+        Self {
+            account_address,
+            public_key: public_key.to_bytes().to_vec(),
+            proof_of_possession: proof_of_possession.to_bytes().to_vec(),
+        }
+    }
+
+    pub fn account_address(&self) -> &AccountAddress {
+        &self.account_address
+    }
+
+    /// Returns the public key if it is well-formed and its proof of possession verifies. The
+    /// Move module only checks the lengths, so this is the only place the proof is checked.
+    pub fn verified_public_key(&self) -> Result<Bls12381PublicKey> {
+        let public_key = Bls12381PublicKey::try_from(self.public_key.as_slice())?;
+        Bls12381ProofOfPossession::try_from(self.proof_of_possession.as_slice())?
+            .verify(&public_key)?;
+        Ok(public_key)
+    }
+}
+
+/// The BLS12-381 consensus keys registered by validators, used for aggregated signatures.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorBlsKeys {
+    keys: Vec<ValidatorBlsKey>,
+}
+
+impl ValidatorBlsKeys {
+    pub fn new(keys: Vec<ValidatorBlsKey>) -> Self {
+        Self { keys }
+    }
+
+    pub fn keys(&self) -> &[ValidatorBlsKey] {
+        &self.keys
+    }
+
+    /// Returns the registered keys whose proof of possession verifies, by validator address.
+    /// Invalid registrations are skipped: the owning validator keeps signing with Ed25519 only.
+    pub fn verified_public_keys(&self) -> BTreeMap<AccountAddress, Bls12381PublicKey> {
+        self.keys
+            .iter()
+            .filter_map(|key| {
+                key.verified_public_key()
+                    .ok()
+                    .map(|public_key| (*key.account_address(), public_key))
+            })
+            .collect()
+    }
+}
+
+impl OnChainConfig for ValidatorBlsKeys {
+    const IDENTIFIER: &'static str = "ValidatorBlsKeys";
+}
@@ -5,7 +5,7 @@ use crate::{
     account_address::AccountAddress,
     account_config::XUS_NAME,
     account_state_blob::AccountStateBlob,
-    block_metadata::BlockMetadata,
+    block_metadata::{BlockMetadata, BlockMetadataExt},
     chain_id::ChainId,
     contract_event::ContractEvent,
     ledger_info::LedgerInfo,
@@ -992,6 +992,7 @@ pub struct TransactionToCommit {
     transaction: Transaction,
     account_states: HashMap<AccountAddress, AccountStateBlob>,
     jf_node_hashes: Option<HashMap<NibblePath, HashValue>>,
+    write_set: WriteSet,
     events: Vec<ContractEvent>,
     gas_used: u64,
     status: KeptVMStatus,
@@ -1002,6 +1003,7 @@ impl TransactionToCommit {
         transaction: Transaction,
         account_states: HashMap<AccountAddress, AccountStateBlob>,
         jf_node_hashes: Option<HashMap<NibblePath, HashValue>>,
+        write_set: WriteSet,
         events: Vec<ContractEvent>,
         gas_used: u64,
         status: KeptVMStatus,
@@ -1010,6 +1012,7 @@ impl TransactionToCommit {
             transaction,
             account_states,
             jf_node_hashes,
+            write_set,
             events,
             gas_used,
             status,
@@ -1028,6 +1031,10 @@ impl TransactionToCommit {
         self.jf_node_hashes.as_ref()
     }
 
+    pub fn write_set(&self) -> &WriteSet {
+        &self.write_set
+    }
+
     pub fn events(&self) -> &[ContractEvent] {
         &self.events
     }
@@ -1141,6 +1148,105 @@ impl TransactionListWithProof {
     }
 }
 
+/// A list of transactions with their outputs, and a proof that the transactions are on the
+/// ledger. It allows syncing by applying the outputs instead of re-executing the transactions.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct TransactionOutputListWithProof {
+    pub transactions_and_outputs: Vec<(Transaction, TransactionOutput)>,
+    pub first_transaction_output_version: Option<Version>,
+    pub proof: TransactionListProof,
+}
+
+impl TransactionOutputListWithProof {
+    /// Constructor.
+    pub fn new(
+        transactions_and_outputs: Vec<(Transaction, TransactionOutput)>,
+        first_transaction_output_version: Option<Version>,
+        proof: TransactionListProof,
+    ) -> Self {
+        Self {
+            transactions_and_outputs,
+            first_transaction_output_version,
+            proof,
+        }
+    }
+
+    /// Creates an empty transaction output list.
+    pub fn new_empty() -> Self {
+        Self::new(vec![], None, TransactionListProof::new_empty())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions_and_outputs.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions_and_outputs.len()
+    }
+
+    /// Verifies the transaction output list with the proofs, both carried on `self`.
+    ///
+    /// Two things are ensured if no error is raised:
+    ///   1. All the transactions exist on the ledger represented by `ledger_info`, with
+    /// consecutive versions starting from `first_transaction_output_version`.
+    ///   2. The events, gas used and status of each output match its transaction info.
+    ///
+    /// Note: the write sets are not covered by the transaction infos directly. They are only
+    /// verified once applied, by comparing the resulting state root hashes with the ones in the
+    /// transaction infos.
+    pub fn verify(
+        &self,
+        ledger_info: &LedgerInfo,
+        first_transaction_output_version: Option<Version>,
+    ) -> Result<()> {
+        ensure!(
+            self.first_transaction_output_version == first_transaction_output_version,
+            "First transaction output version ({}) not expected ({}).",
+            TransactionListWithProof::display_option_version(self.first_transaction_output_version),
+            TransactionListWithProof::display_option_version(first_transaction_output_version),
+        );
+
+        let txn_hashes: Vec<_> = self
+            .transactions_and_outputs
+            .iter()
+            .map(|(txn, _)| txn.hash())
+            .collect();
+        self.proof.verify(
+            ledger_info,
+            self.first_transaction_output_version,
+            &txn_hashes,
+        )?;
+
+        itertools::zip_eq(
+            &self.transactions_and_outputs,
+            self.proof.transaction_infos(),
+        )
+        .map(|((_, output), txn_info)| {
+            let event_hashes: Vec<_> = output.events().iter().map(ContractEvent::hash).collect();
+            let event_root_hash =
+                InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes)
+                    .root_hash();
+            ensure!(
+                event_root_hash == txn_info.event_root_hash(),
+                "Some event root hash calculated doesn't match that carried on the \
+                     transaction info.",
+            );
+            ensure!(
+                output.gas_used() == txn_info.gas_used(),
+                "Some gas used doesn't match that carried on the transaction info.",
+            );
+            ensure!(
+                *output.status() == TransactionStatus::Keep(txn_info.status().clone()),
+                "Some status doesn't match that carried on the transaction info.",
+            );
+            Ok(())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+        Ok(())
+    }
+}
+
 /// A list of transactions under an account that are contiguous by sequence number
 /// and include proofs.
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
@@ -1243,6 +1349,10 @@ pub enum Transaction {
 
     /// Transaction to update the block metadata resource at the beginning of a block.
     BlockMetadata(BlockMetadata),
+
+    /// Transaction to update the block metadata resource at the beginning of a block, carrying
+    /// the metadata fields added after `BlockMetadata` was persisted.
+    BlockMetadataExt(BlockMetadataExt),
 }
 
 impl Transaction {
@@ -1262,6 +1372,8 @@ impl Transaction {
             Transaction::GenesisTransaction(_write_set) => String::from("genesis"),
             // TODO: display proper information for client
             Transaction::BlockMetadata(_block_metadata) => String::from("block_metadata"),
+            // TODO: display proper information for client
+            Transaction::BlockMetadataExt(_block_metadata) => String::from("block_metadata"),
         }
     }
 }
@@ -1,7 +1,11 @@
 // Copyright (c) The Mango Blockchain Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::block_metadata::BlockMetadata;
+use crate::{
+    account_address::AccountAddress,
+    account_config,
+    block_metadata::{BlockMetadata, BlockMetadataExt, NewBlockEvent},
+};
 use bcs::test_helpers::assert_canonical_encode_decode;
 use proptest::prelude::*;
 
@@ -12,4 +16,47 @@ proptest! {
     fn test_block_metadata_canonical_serialization(data in any::<BlockMetadata>()) {
         assert_canonical_encode_decode(data);
     }
+
+    #[test]
+    fn test_block_metadata_ext_canonical_serialization(
+        data in any::<BlockMetadata>(),
+        failed_proposers in prop::collection::vec(any::<AccountAddress>(), 0..10),
+    ) {
+        assert_canonical_encode_decode(BlockMetadataExt::new(data, failed_proposers));
+    }
+}
+
+#[test]
+fn test_new_block_event_legacy_layout() {
+    let proposer = AccountAddress::random();
+    let votes = vec![AccountAddress::random(), AccountAddress::random()];
+    // Events emitted before the failed proposers were recorded
+    let legacy_bytes = bcs::to_bytes(&(7u64, proposer, votes.clone(), 100u64)).unwrap();
+
+    let event = NewBlockEvent::try_from_bytes(&legacy_bytes).unwrap();
+    assert_eq!(event.round(), 7);
+    assert_eq!(event.proposer(), proposer);
+    assert_eq!(event.votes(), votes);
+    assert!(event.failed_proposers().is_empty());
+    let event = account_config::NewBlockEvent::try_from_bytes(&legacy_bytes).unwrap();
+    assert_eq!(event.proposed_time(), 100);
+    assert!(event.failed_proposers().is_empty());
+
+    let failed_proposers = vec![AccountAddress::random()];
+    let event = NewBlockEvent::new(7, proposer, votes, 100, failed_proposers.clone());
+    let bytes = bcs::to_bytes(&event).unwrap();
+    assert_eq!(
+        NewBlockEvent::try_from_bytes(&bytes)
+            .unwrap()
+            .failed_proposers(),
+        failed_proposers.as_slice()
+    );
+    assert_eq!(
+        account_config::NewBlockEvent::try_from_bytes(&bytes)
+            .unwrap()
+            .failed_proposers(),
+        failed_proposers.as_slice()
+    );
+
+    assert!(NewBlockEvent::try_from_bytes(&bytes[..bytes.len() - 1]).is_err());
 }
@@ -482,7 +482,10 @@ proptest! {
             Some(trusted_state.version()),
             good_li.version(),
         );
-        let sigs = latest_li.signatures();
+        let sigs = match &latest_li {
+            LedgerInfoWithSignatures::V0(latest_li) => latest_li.signatures(),
+            LedgerInfoWithSignatures::V1(_) => unreachable!("update proofs are signed with Ed25519"),
+        };
 
         // Verifying latest ledger infos with mismatched data and signatures should fail
         let bad_li_1 = LedgerInfoWithSignatures::new(
@@ -3,6 +3,7 @@
 
 use crate::account_address::AccountAddress;
 use mango_crypto::{
+    bls12381::{Bls12381PrivateKey, Bls12381PublicKey, Bls12381Signature},
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
     hash::CryptoHash,
     test_utils::TEST_SEED,
@@ -20,6 +21,8 @@ use std::convert::TryFrom;
 pub struct ValidatorSigner {
     author: AccountAddress,
     private_key: Ed25519PrivateKey,
+    /// Key used to sign the shares of aggregated BLS12-381 signatures, if the validator has one.
+    bls_private_key: Option<Bls12381PrivateKey>,
 }
 
 impl ValidatorSigner {
@@ -27,14 +30,28 @@ impl ValidatorSigner {
         ValidatorSigner {
             author,
             private_key,
+            bls_private_key: None,
         }
     }
 
+    /// Attaches a BLS12-381 private key used for aggregated signatures.
+    pub fn with_bls_private_key(mut self, bls_private_key: Bls12381PrivateKey) -> Self {
+        self.bls_private_key = Some(bls_private_key);
+        self
+    }
+
     /// Constructs a signature for `message` using `private_key`.
     pub fn sign<T: Serialize + CryptoHash>(&self, message: &T) -> Ed25519Signature {
         self.private_key.sign(message)
     }
 
+    /// Constructs a BLS12-381 signature share for `message`, if this signer has a BLS key.
+    pub fn sign_bls<T: Serialize + CryptoHash>(&self, message: &T) -> Option<Bls12381Signature> {
+        self.bls_private_key
+            .as_ref()
+            .map(|private_key| private_key.sign(message))
+    }
+
     /// Returns the author associated with this signer.
     pub fn author(&self) -> AccountAddress {
         self.author
@@ -45,6 +62,13 @@ impl ValidatorSigner {
         self.private_key.public_key()
     }
 
+    /// Returns the BLS12-381 public key associated with this signer, if any.
+    pub fn bls_public_key(&self) -> Option<Bls12381PublicKey> {
+        self.bls_private_key
+            .as_ref()
+            .map(|private_key| private_key.public_key())
+    }
+
     /// Returns the private key associated with this signer. Only available for testing purposes.
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn private_key(&self) -> &Ed25519PrivateKey {
@@ -63,6 +87,7 @@ impl ValidatorSigner {
             AccountAddress::random(),
             Ed25519PrivateKey::generate(&mut rng),
         )
+        .with_bls_private_key(Bls12381PrivateKey::generate(&mut rng))
     }
 
     /// For test only - makes signer with nicely looking account address that has specified integer
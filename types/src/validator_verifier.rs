@@ -1,8 +1,13 @@
 // Copyright (c) The Mango Blockchain Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{account_address::AccountAddress, on_chain_config::ValidatorSet};
+use crate::{
+    account_address::AccountAddress, aggregate_signature::AggregateSignature,
+    on_chain_config::ValidatorSet,
+};
+use mango_bitvec::BitVec;
 use mango_crypto::{
+    bls12381::{Bls12381PublicKey, Bls12381Signature},
     ed25519::{Ed25519PublicKey, Ed25519Signature},
     hash::CryptoHash,
     Signature, VerifyingKey,
@@ -43,6 +48,15 @@ pub enum VerifyError {
     InvalidSignature,
     #[error("Inconsistent Block Info")]
     InconsistentBlockInfo,
+    #[error("Author has not registered a BLS public key")]
+    /// The author of a BLS signature has no valid BLS public key in this epoch.
+    MissingBlsPublicKey,
+    #[error("Signer bitvec does not match the validator set")]
+    /// An aggregate signature references a validator index out of range.
+    InvalidBitVec,
+    #[error("Aggregate signature is missing")]
+    /// An aggregate signature with signers does not carry any signature.
+    EmptySignature,
 }
 
 /// Helper struct to manage validator information for validation
@@ -51,6 +65,9 @@ pub enum VerifyError {
 pub struct ValidatorConsensusInfo {
     public_key: Ed25519PublicKey,
     voting_power: u64,
+    /// The BLS12-381 key used for aggregated signatures, if the validator registered one with
+    /// a valid proof of possession.
+    bls_public_key: Option<Bls12381PublicKey>,
 }
 
 impl ValidatorConsensusInfo {
@@ -58,8 +75,14 @@ impl ValidatorConsensusInfo {
         ValidatorConsensusInfo {
             public_key,
             voting_power,
+            bls_public_key: None,
         }
     }
+
+    pub fn with_bls_public_key(mut self, bls_public_key: Bls12381PublicKey) -> Self {
+        self.bls_public_key = Some(bls_public_key);
+        self
+    }
 }
 
 /// Supports validation of signatures for known authors with individual voting powers. This struct
@@ -241,6 +264,115 @@ impl ValidatorVerifier {
         Ok(())
     }
 
+    /// Returns a verifier that also knows the given BLS12-381 public keys. Keys of accounts that
+    /// are not validators are ignored; the keys must have had their proof of possession verified.
+    pub fn with_bls_public_keys(
+        mut self,
+        bls_public_keys: &BTreeMap<AccountAddress, Bls12381PublicKey>,
+    ) -> Self {
+        for (address, validator_info) in self.address_to_validator_info.iter_mut() {
+            validator_info.bls_public_key = bls_public_keys.get(address).cloned();
+        }
+        self
+    }
+
+    /// Verify the correctness of a BLS12-381 signature of a message by a known author.
+    pub fn verify_bls<T: Serialize + CryptoHash>(
+        &self,
+        author: AccountAddress,
+        message: &T,
+        signature: &Bls12381Signature,
+    ) -> std::result::Result<(), VerifyError> {
+        let validator_info = self
+            .address_to_validator_info
+            .get(&author)
+            .ok_or(VerifyError::UnknownAuthor)?;
+        let public_key = validator_info
+            .bls_public_key
+            .as_ref()
+            .ok_or(VerifyError::MissingBlsPublicKey)?;
+        signature
+            .verify(message, public_key)
+            .map_err(|_| VerifyError::InvalidSignature)
+    }
+
+    /// Aggregates individually verified BLS12-381 signatures of the same message into an
+    /// `AggregateSignature`, marking the signers in a bitvec over the ordered validators.
+    pub fn aggregate_signatures(
+        &self,
+        signatures: &BTreeMap<AccountAddress, Bls12381Signature>,
+    ) -> std::result::Result<AggregateSignature, VerifyError> {
+        let mut validator_bitmask = BitVec::default();
+        for (index, address) in self.get_ordered_account_addresses_iter().enumerate() {
+            if signatures.contains_key(&address) {
+                if index > u8::MAX as usize {
+                    return Err(VerifyError::InvalidBitVec);
+                }
+                validator_bitmask.set(index as u8);
+            }
+        }
+        if validator_bitmask.count_ones() as usize != signatures.len() {
+            return Err(VerifyError::UnknownAuthor);
+        }
+        if signatures.is_empty() {
+            return Ok(AggregateSignature::new(validator_bitmask, None));
+        }
+        let aggregated_signature =
+            Bls12381Signature::aggregate(signatures.values().cloned().collect())
+                .map_err(|_| VerifyError::InvalidSignature)?;
+        Ok(AggregateSignature::new(
+            validator_bitmask,
+            Some(aggregated_signature),
+        ))
+    }
+
+    /// Returns the addresses of the validators set in the signers bitvec of an aggregate
+    /// signature, in account address order.
+    pub fn get_signers_from_bitvec(
+        &self,
+        bitvec: &BitVec,
+    ) -> std::result::Result<Vec<AccountAddress>, VerifyError> {
+        if let Some(last_set_bit) = bitvec.last_set_bit() {
+            if last_set_bit as usize >= self.len() {
+                return Err(VerifyError::InvalidBitVec);
+            }
+        }
+        Ok(self
+            .get_ordered_account_addresses_iter()
+            .enumerate()
+            .filter(|(index, _)| *index <= u8::MAX as usize && bitvec.is_set(*index as u8))
+            .map(|(_, address)| address)
+            .collect())
+    }
+
+    /// This function will successfully return when the signers of `multi_signature` have at
+    /// least quorum voting power and their aggregated BLS12-381 public keys verify the aggregate
+    /// signature.
+    pub fn verify_multi_signatures<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        multi_signature: &AggregateSignature,
+    ) -> std::result::Result<(), VerifyError> {
+        let signers = self.get_signers_from_bitvec(multi_signature.get_signers_bitvec())?;
+        self.check_voting_power(signers.iter())?;
+        let public_keys = signers
+            .iter()
+            .map(|address| {
+                self.address_to_validator_info
+                    .get(address)
+                    .and_then(|validator_info| validator_info.bls_public_key.as_ref())
+                    .ok_or(VerifyError::MissingBlsPublicKey)
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let aggregated_public_key =
+            Bls12381PublicKey::aggregate(public_keys).map_err(|_| VerifyError::InvalidSignature)?;
+        multi_signature
+            .sig()
+            .ok_or(VerifyError::EmptySignature)?
+            .verify(message, &aggregated_public_key)
+            .map_err(|_| VerifyError::InvalidSignature)
+    }
+
     /// Returns the public key for this address.
     pub fn get_public_key(&self, author: &AccountAddress) -> Option<Ed25519PublicKey> {
         self.address_to_validator_info
@@ -248,6 +380,13 @@ impl ValidatorVerifier {
             .map(|validator_info| validator_info.public_key.clone())
     }
 
+    /// Returns the BLS12-381 public key for this address, if the validator registered one.
+    pub fn get_bls_public_key(&self, author: &AccountAddress) -> Option<Bls12381PublicKey> {
+        self.address_to_validator_info
+            .get(author)
+            .and_then(|validator_info| validator_info.bls_public_key.clone())
+    }
+
     /// Returns the voting power for this address.
     pub fn get_voting_power(&self, author: &AccountAddress) -> Option<u64> {
         self.address_to_validator_info
@@ -354,10 +493,12 @@ pub fn random_validator_verifier(
         } else {
             crate::validator_signer::ValidatorSigner::random([i as u8; 32])
         };
-        account_address_to_validator_info.insert(
-            random_signer.author(),
-            crate::validator_verifier::ValidatorConsensusInfo::new(random_signer.public_key(), 1),
-        );
+        let mut validator_info =
+            crate::validator_verifier::ValidatorConsensusInfo::new(random_signer.public_key(), 1);
+        if let Some(bls_public_key) = random_signer.bls_public_key() {
+            validator_info = validator_info.with_bls_public_key(bls_public_key);
+        }
+        account_address_to_validator_info.insert(random_signer.author(), validator_info);
         signers.push(random_signer);
     }
     (
@@ -654,4 +795,66 @@ mod tests {
             Err(VerifyError::UnknownAuthor)
         );
     }
+
+    #[test]
+    fn test_aggregate_signatures() {
+        let (validator_signers, validator_verifier) = random_validator_verifier(4, None, false);
+        let dummy_struct = TestDiemCrypto("Hello, World".to_string());
+
+        let mut author_to_signature_map = BTreeMap::new();
+        for validator in validator_signers.iter().take(3) {
+            let signature = validator.sign_bls(&dummy_struct).unwrap();
+            assert_eq!(
+                validator_verifier.verify_bls(validator.author(), &dummy_struct, &signature),
+                Ok(())
+            );
+            author_to_signature_map.insert(validator.author(), signature);
+        }
+        let aggregate_signature = validator_verifier
+            .aggregate_signatures(&author_to_signature_map)
+            .unwrap();
+        assert_eq!(aggregate_signature.num_signers(), 3);
+        assert_eq!(
+            validator_verifier.get_signers_from_bitvec(aggregate_signature.get_signers_bitvec()),
+            Ok(author_to_signature_map.keys().cloned().collect())
+        );
+        assert_eq!(
+            validator_verifier.verify_multi_signatures(&dummy_struct, &aggregate_signature),
+            Ok(())
+        );
+
+        // A signer outside of the validator set cannot be aggregated.
+        let unknown_validator_signer = ValidatorSigner::random([4; 32]);
+        let mut unknown_author_to_signature_map = author_to_signature_map.clone();
+        unknown_author_to_signature_map.insert(
+            unknown_validator_signer.author(),
+            unknown_validator_signer.sign_bls(&dummy_struct).unwrap(),
+        );
+        assert_eq!(
+            validator_verifier.aggregate_signatures(&unknown_author_to_signature_map),
+            Err(VerifyError::UnknownAuthor)
+        );
+
+        // Bits beyond the validator set are rejected.
+        let mut bitvec = aggregate_signature.get_signers_bitvec().clone();
+        bitvec.set(4);
+        assert_eq!(
+            validator_verifier.verify_multi_signatures(
+                &dummy_struct,
+                &AggregateSignature::new(bitvec, aggregate_signature.sig().cloned())
+            ),
+            Err(VerifyError::InvalidBitVec)
+        );
+
+        // An aggregate signature over a different set of signers does not verify.
+        let mut bitvec = aggregate_signature.get_signers_bitvec().clone();
+        bitvec.set(3);
+        assert_eq!(
+            validator_verifier.verify_multi_signatures(
+                &dummy_struct,
+                &AggregateSignature::new(bitvec, aggregate_signature.sig().cloned())
+            ),
+            Err(VerifyError::InvalidSignature)
+        );
+    }
 }
@@ -19,7 +19,7 @@ use crate::logging::AdapterLogSchema;
 use mango_logger::prelude::*;
 use mango_types::{
     access_path::AccessPath,
-    block_metadata::BlockMetadata,
+    block_metadata::{BlockMetadata, BlockMetadataExt},
     transaction::{
         Transaction, TransactionArgument, TransactionOutput, TransactionPayload, TransactionStatus,
         WriteSetPayload,
@@ -259,6 +259,7 @@ pub enum PreprocessedTransaction {
     UserTransaction(Box<SignatureCheckedTransaction>),
     WaypointWriteSet(WriteSetPayload),
     BlockMetadata(BlockMetadata),
+    BlockMetadataExt(BlockMetadataExt),
     WriteSet(Box<SignatureCheckedTransaction>),
     InvalidSignature,
 }
@@ -270,6 +271,7 @@ pub enum PreprocessedTransaction {
 pub(crate) fn preprocess_transaction<A: VMAdapter>(txn: Transaction) -> PreprocessedTransaction {
     match txn {
         Transaction::BlockMetadata(b) => PreprocessedTransaction::BlockMetadata(b),
+        Transaction::BlockMetadataExt(b) => PreprocessedTransaction::BlockMetadataExt(b),
         Transaction::GenesisTransaction(ws) => PreprocessedTransaction::WaypointWriteSet(ws),
         Transaction::UserTransaction(txn) => {
             let checked_txn = match A::check_signature(txn) {
@@ -490,10 +490,13 @@ impl DiemVM {
         ))
     }
 
+    /// Runs `DiemBlock::block_prologue`, or `DiemBlock::block_prologue_ext` when the block
+    /// metadata records failed proposers.
     pub(crate) fn process_block_prologue<S: MoveResolver>(
         &self,
         storage: &S,
         block_metadata: BlockMetadata,
+        failed_proposers: Option<Vec<AccountAddress>>,
         log_context: &AdapterLogSchema,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
         fail_point!("move_adapter::process_block_prologue", |_| {
@@ -510,24 +513,36 @@ impl DiemVM {
         let mut session = self.0.new_session(storage);
 
         let (round, timestamp, previous_vote, proposer) = block_metadata.into_inner();
-        let args = serialize_values(&vec![
+        let mut args = vec![
             MoveValue::Signer(txn_data.sender),
             MoveValue::U64(round),
             MoveValue::U64(timestamp),
             MoveValue::Vector(previous_vote.into_iter().map(MoveValue::Address).collect()),
             MoveValue::Address(proposer),
-        ]);
+        ];
+        let function_name = match failed_proposers {
+            Some(failed_proposers) => {
+                args.push(MoveValue::Vector(
+                    failed_proposers
+                        .into_iter()
+                        .map(MoveValue::Address)
+                        .collect(),
+                ));
+                BLOCK_PROLOGUE_EXT
+            }
+            None => BLOCK_PROLOGUE,
+        };
         session
             .execute_function(
                 &DIEM_BLOCK_MODULE,
-                BLOCK_PROLOGUE,
+                function_name,
                 vec![],
-                args,
+                serialize_values(&args),
                 &mut gas_status,
             )
             .map(|_return_vals| ())
             .or_else(|e| {
-                expect_only_successful_execution(e, BLOCK_PROLOGUE.as_str(), log_context)
+                expect_only_successful_execution(e, function_name.as_str(), log_context)
             })?;
         SYSTEM_TRANSACTIONS_EXECUTED.inc();
 
@@ -838,10 +853,24 @@ impl VMAdapter for DiemVM {
     ) -> Result<(VMStatus, TransactionOutput, Option<String>), VMStatus> {
         Ok(match txn {
             PreprocessedTransaction::BlockMetadata(block_metadata) => {
-                let (vm_status, output) =
-                    self.process_block_prologue(data_cache, block_metadata.clone(), log_context)?;
+                let (vm_status, output) = self.process_block_prologue(
+                    data_cache,
+                    block_metadata.clone(),
+                    None,
+                    log_context,
+                )?;
                 (vm_status, output, Some("block_prologue".to_string()))
             }
+            PreprocessedTransaction::BlockMetadataExt(block_metadata) => {
+                let (block_metadata, failed_proposers) = block_metadata.clone().into_inner();
+                let (vm_status, output) = self.process_block_prologue(
+                    data_cache,
+                    block_metadata,
+                    Some(failed_proposers),
+                    log_context,
+                )?;
+                (vm_status, output, Some("block_prologue_ext".to_string()))
+            }
             PreprocessedTransaction::WaypointWriteSet(write_set_payload) => {
                 let (vm_status, output) =
                     self.process_waypoint_change_set(data_cache, write_set_payload.clone())?;